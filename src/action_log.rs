@@ -0,0 +1,211 @@
+//! Undo/redo history of the open review's mutations.
+//!
+//! Thread resolves/reopens and viewed marks can be taken back: undo issues
+//! the inverse (a reopen through crit, an unviewed mark) and redo issues the
+//! original again. A posted comment can't be: crit has no delete, so undoing
+//! one only flashes that and drops it from the history. Thread changes are
+//! logged once crit accepts them, so a failed write leaves the history as
+//! it was.
+
+use crate::i18n::{tr_fmt, Text};
+
+/// Most actions kept; the oldest drop off beyond this.
+const ACTION_LOG_LIMIT: usize = 100;
+
+/// One mutation of the open review.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewAction {
+    /// A comment or reply was posted at `location` (`path:line`); irreversible
+    Comment { location: String },
+    /// A thread went from `from` to `to` (`open`/`resolved`)
+    ThreadStatus {
+        thread_id: String,
+        from: String,
+        to: String,
+    },
+    /// A file was marked viewed, or not
+    Viewed { path: String, viewed: bool },
+}
+
+impl ReviewAction {
+    /// The action that takes this one back, or `None` if it can't be.
+    #[must_use]
+    pub fn inverse(&self) -> Option<Self> {
+        match self {
+            Self::Comment { .. } => None,
+            Self::ThreadStatus {
+                thread_id,
+                from,
+                to,
+            } => Some(Self::ThreadStatus {
+                thread_id: thread_id.clone(),
+                from: to.clone(),
+                to: from.clone(),
+            }),
+            Self::Viewed { path, viewed } => Some(Self::Viewed {
+                path: path.clone(),
+                viewed: !viewed,
+            }),
+        }
+    }
+
+    /// Short description for the undo/redo flash.
+    #[must_use]
+    pub fn label(&self) -> String {
+        match self {
+            Self::Comment { location } => tr_fmt(Text::ActionComment, &[location]),
+            Self::ThreadStatus { thread_id, to, .. } if to == "open" => {
+                tr_fmt(Text::ActionReopen, &[thread_id])
+            }
+            Self::ThreadStatus { thread_id, .. } => tr_fmt(Text::ActionResolve, &[thread_id]),
+            Self::Viewed { path, viewed: true } => tr_fmt(Text::ActionViewed, &[path]),
+            Self::Viewed {
+                path,
+                viewed: false,
+            } => tr_fmt(Text::ActionUnviewed, &[path]),
+        }
+    }
+}
+
+/// Which history move a queued write finishes once crit accepts it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogStep {
+    /// A new action by the user
+    #[default]
+    Record,
+    /// The inverse of the last done action
+    Undo,
+    /// The last undone action again
+    Redo,
+}
+
+/// Done and undone actions, newest last.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActionLog {
+    done: Vec<ReviewAction>,
+    undone: Vec<ReviewAction>,
+}
+
+impl ActionLog {
+    /// Log a new action. Anything undone can no longer be redone.
+    pub fn record(&mut self, action: ReviewAction) {
+        self.undone.clear();
+        self.done.push(action);
+        if self.done.len() > ACTION_LOG_LIMIT {
+            self.done.remove(0);
+        }
+    }
+
+    /// The action undo would take back.
+    #[must_use]
+    pub fn last_done(&self) -> Option<&ReviewAction> {
+        self.done.last()
+    }
+
+    /// The action redo would apply again.
+    #[must_use]
+    pub fn last_undone(&self) -> Option<&ReviewAction> {
+        self.undone.last()
+    }
+
+    /// Move the last done action to the redo side; an irreversible one is
+    /// dropped instead.
+    pub fn undo(&mut self) -> Option<ReviewAction> {
+        let action = self.done.pop()?;
+        if action.inverse().is_some() {
+            self.undone.push(action.clone());
+        }
+        Some(action)
+    }
+
+    /// Move the last undone action back to the done side.
+    pub fn redo(&mut self) -> Option<ReviewAction> {
+        let action = self.undone.pop()?;
+        self.done.push(action.clone());
+        Some(action)
+    }
+
+    /// Finish a write queued for `step`.
+    pub fn apply(&mut self, step: LogStep, action: ReviewAction) {
+        match step {
+            LogStep::Record => self.record(action),
+            LogStep::Undo => {
+                self.undo();
+            }
+            LogStep::Redo => {
+                self.redo();
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(id: &str) -> ReviewAction {
+        ReviewAction::ThreadStatus {
+            thread_id: id.to_string(),
+            from: "open".to_string(),
+            to: "resolved".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_undo_redo_moves_between_sides() {
+        let mut log = ActionLog::default();
+        log.record(resolve("th-1"));
+        log.record(ReviewAction::Viewed {
+            path: "a.rs".to_string(),
+            viewed: true,
+        });
+
+        let undone = log.undo().unwrap();
+        assert_eq!(
+            undone.inverse(),
+            Some(ReviewAction::Viewed {
+                path: "a.rs".to_string(),
+                viewed: false
+            })
+        );
+        assert_eq!(log.last_done(), Some(&resolve("th-1")));
+        assert_eq!(log.redo(), Some(undone.clone()));
+        assert_eq!(log.last_done(), Some(&undone));
+        assert!(log.last_undone().is_none());
+
+        // A new action drops the redo side
+        log.undo();
+        log.record(resolve("th-2"));
+        assert!(log.redo().is_none());
+    }
+
+    #[test]
+    fn test_comments_are_irreversible() {
+        let mut log = ActionLog::default();
+        log.record(resolve("th-1"));
+        log.record(ReviewAction::Comment {
+            location: "a.rs:12".to_string(),
+        });
+
+        let action = log.undo().unwrap();
+        assert!(action.inverse().is_none());
+        assert!(log.last_undone().is_none());
+        assert_eq!(log.last_done(), Some(&resolve("th-1")));
+    }
+
+    #[test]
+    fn test_apply_steps() {
+        let mut log = ActionLog::default();
+        log.apply(LogStep::Record, resolve("th-1"));
+        log.apply(LogStep::Undo, resolve("th-1"));
+        assert_eq!(log.last_undone(), Some(&resolve("th-1")));
+        log.apply(LogStep::Redo, resolve("th-1"));
+        assert_eq!(log.last_done(), Some(&resolve("th-1")));
+        assert!(log.last_undone().is_none());
+    }
+}
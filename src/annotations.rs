@@ -0,0 +1,155 @@
+//! External lint/CI annotations (`--annotations <file>`), rolled up into
+//! per-file and per-review severity counts.
+//!
+//! The file is a JSON array of `{"path", "line", "severity", "message",
+//! "source"}` objects; only `path` and `severity` are required. Paths are
+//! repo-relative, like the paths crit reports.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[serde(alias = "info", alias = "note")]
+    Notice,
+    #[serde(alias = "warn")]
+    Warning,
+    #[serde(alias = "failure")]
+    Error,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Annotation {
+    pub path: String,
+    #[serde(default)]
+    pub line: Option<i64>,
+    pub severity: Severity,
+    #[serde(default)]
+    pub message: String,
+    /// Tool that reported it, e.g. `clippy`
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// Annotation counts by severity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SeverityCounts {
+    pub errors: usize,
+    pub warnings: usize,
+    pub notices: usize,
+}
+
+impl SeverityCounts {
+    pub const fn add(&mut self, severity: Severity) {
+        match severity {
+            Severity::Error => self.errors += 1,
+            Severity::Warning => self.warnings += 1,
+            Severity::Notice => self.notices += 1,
+        }
+    }
+
+    pub const fn merge(&mut self, other: Self) {
+        self.errors += other.errors;
+        self.warnings += other.warnings;
+        self.notices += other.notices;
+    }
+
+    /// The most severe level present and how many annotations have it, for
+    /// a one-number badge.
+    #[must_use]
+    pub const fn worst(&self) -> Option<(Severity, usize)> {
+        if self.errors > 0 {
+            Some((Severity::Error, self.errors))
+        } else if self.warnings > 0 {
+            Some((Severity::Warning, self.warnings))
+        } else if self.notices > 0 {
+            Some((Severity::Notice, self.notices))
+        } else {
+            None
+        }
+    }
+}
+
+/// Loaded annotations with counts per path.
+#[derive(Debug, Clone, Default)]
+pub struct Annotations {
+    pub items: Vec<Annotation>,
+    by_path: HashMap<String, SeverityCounts>,
+}
+
+impl Annotations {
+    #[must_use]
+    pub fn new(items: Vec<Annotation>) -> Self {
+        let mut by_path: HashMap<String, SeverityCounts> = HashMap::new();
+        for item in &items {
+            let path = item.path.trim_start_matches("./").to_string();
+            by_path.entry(path).or_default().add(item.severity);
+        }
+        Self { items, by_path }
+    }
+
+    /// Read an annotations file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or isn't an annotation
+    /// array.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read annotations: {}", path.display()))?;
+        let items = serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse annotations: {}", path.display()))?;
+        Ok(Self::new(items))
+    }
+
+    /// Counts for one repo-relative path.
+    #[must_use]
+    pub fn counts_for(&self, path: &str) -> SeverityCounts {
+        self.by_path.get(path).copied().unwrap_or_default()
+    }
+
+    /// Counts summed over `paths`.
+    #[must_use]
+    pub fn counts_for_all<'a>(&self, paths: impl IntoIterator<Item = &'a str>) -> SeverityCounts {
+        let mut total = SeverityCounts::default();
+        for path in paths {
+            total.merge(self.counts_for(path));
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_roll_up_by_path_and_severity() {
+        let items = serde_json::from_str(
+            r#"[
+                {"path": "src/lib.rs", "line": 3, "severity": "error", "message": "E0308"},
+                {"path": "./src/lib.rs", "severity": "warning"},
+                {"path": "src/lib.rs", "severity": "failure"},
+                {"path": "src/db.rs", "severity": "note", "source": "clippy"}
+            ]"#,
+        )
+        .unwrap();
+        let annotations = Annotations::new(items);
+
+        let lib = annotations.counts_for("src/lib.rs");
+        assert_eq!((lib.errors, lib.warnings, lib.notices), (2, 1, 0));
+        assert_eq!(lib.worst(), Some((Severity::Error, 2)));
+        assert_eq!(
+            annotations.counts_for("src/db.rs").worst(),
+            Some((Severity::Notice, 1))
+        );
+        assert_eq!(annotations.counts_for("README.md").worst(), None);
+
+        let review = annotations.counts_for_all(["src/lib.rs", "src/db.rs", "README.md"]);
+        assert_eq!((review.errors, review.warnings, review.notices), (2, 1, 1));
+    }
+}
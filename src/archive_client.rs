@@ -0,0 +1,193 @@
+//! Read-only `CritClient` over an exported review bundle, for
+//! `crit-ui open-archive <bundle>`.
+//!
+//! A bundle is one review in the layout of `crit review <id> --include-diffs
+//! --format json` (`review`, `threads` with inline `comments`, `files` with
+//! diffs and file snapshots), plus an optional `thread_events` map from
+//! thread id to status history. Neither the repository nor crit's database
+//! is needed.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::cli_client::{parse_review, CliClient};
+use crate::db::{
+    CreatedComment, CritClient, ExpectedThread, FileData, ReviewData, ReviewSummary, ThreadEvent,
+    ThreadSide, Verdict,
+};
+use crate::preview::diff_stats;
+
+pub struct ArchiveClient {
+    data: ReviewData,
+    events: HashMap<String, Vec<ThreadEvent>>,
+}
+
+#[derive(Deserialize)]
+struct BundleEvents {
+    #[serde(default)]
+    thread_events: HashMap<String, Vec<ThreadEvent>>,
+}
+
+impl ArchiveClient {
+    /// Load a bundle from disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or isn't a review bundle.
+    pub fn open(path: &Path) -> Result<Self> {
+        let json = std::fs::read(path)
+            .with_context(|| format!("Failed to read archive: {}", path.display()))?;
+        Self::from_json(&json)
+            .with_context(|| format!("Failed to parse archive: {}", path.display()))
+    }
+
+    /// Parse bundle JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `json` isn't a review bundle.
+    pub fn from_json(json: &[u8]) -> Result<Self> {
+        let data = parse_review(json)?;
+        let events: BundleEvents = serde_json::from_slice(json)?;
+        Ok(Self {
+            data,
+            events: events.thread_events,
+        })
+    }
+
+    /// Id of the bundled review.
+    #[must_use]
+    pub fn review_id(&self) -> &str {
+        &self.data.detail.review_id
+    }
+}
+
+impl CritClient for ArchiveClient {
+    fn list_reviews(&self, status: Option<&str>) -> Result<Vec<ReviewSummary>> {
+        let detail = &self.data.detail;
+        if status.is_some_and(|status| status != detail.status) {
+            return Ok(Vec::new());
+        }
+        Ok(vec![ReviewSummary {
+            review_id: detail.review_id.clone(),
+            title: detail.title.clone(),
+            author: detail.author.clone(),
+            status: detail.status.clone(),
+            thread_count: detail.thread_count,
+            open_thread_count: detail.open_thread_count,
+            reviewers: Vec::new(),
+            created_at: Some(detail.created_at.clone()),
+            updated_at: Some(self.data.updated_at().to_string()),
+            diff_stats: Some(diff_stats(&self.data.files)),
+        }])
+    }
+
+    fn load_review_data(&self, review_id: &str) -> Result<Option<ReviewData>> {
+        Ok((review_id == self.review_id()).then(|| self.data.clone()))
+    }
+
+    fn get_stored_diff(&self, review_id: &str) -> Result<Option<Vec<FileData>>> {
+        Ok((review_id == self.review_id()).then(|| self.data.files.clone()))
+    }
+
+    fn current_user(&self) -> String {
+        CliClient::comment_agent()
+    }
+
+    fn thread_events(&self, thread_id: &str) -> Result<Vec<ThreadEvent>> {
+        Ok(self.events.get(thread_id).cloned().unwrap_or_default())
+    }
+
+    fn comment(
+        &self,
+        _review_id: &str,
+        _file_path: &str,
+        _start_line: i64,
+        _end_line: Option<i64>,
+        _side: ThreadSide,
+        _body: &str,
+    ) -> Result<Option<CreatedComment>> {
+        bail!("Archives are read-only")
+    }
+
+    fn reply(
+        &self,
+        _thread_id: &str,
+        _body: &str,
+        _expected: Option<ExpectedThread<'_>>,
+    ) -> Result<Option<CreatedComment>> {
+        bail!("Archives are read-only")
+    }
+
+    fn resolve_thread(
+        &self,
+        _thread_id: &str,
+        _expected: Option<ExpectedThread<'_>>,
+    ) -> Result<()> {
+        bail!("Archives are read-only")
+    }
+
+    fn reopen_thread(&self, _thread_id: &str, _expected: Option<ExpectedThread<'_>>) -> Result<()> {
+        bail!("Archives are read-only")
+    }
+
+    fn submit_verdict(
+        &self,
+        _review_id: &str,
+        _verdict: Verdict,
+        _summary: Option<&str>,
+    ) -> Result<()> {
+        bail!("Archives are read-only")
+    }
+
+    fn abandon_review(&self, _review_id: &str, _reason: Option<&str>) -> Result<()> {
+        bail!("Archives are read-only")
+    }
+
+    fn restore_review(&self, _review_id: &str) -> Result<()> {
+        bail!("Archives are read-only")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo_client::{DemoClient, DemoSize, DEFAULT_DEMO_SEED};
+    use crate::export::review_json;
+
+    #[test]
+    fn test_bundle_round_trips_an_exported_review() {
+        let demo = DemoClient::new(DemoSize::Small, DEFAULT_DEMO_SEED);
+        let reviews = demo.list_reviews(None).unwrap();
+        let review_id = reviews
+            .iter()
+            .find(|r| r.thread_count > 0)
+            .unwrap()
+            .review_id
+            .clone();
+        let data = demo.load_review_data(&review_id).unwrap().unwrap();
+        let mut bundle = review_json(&data);
+        bundle["files"] = data
+            .files
+            .iter()
+            .map(|f| serde_json::json!({ "path": f.path, "diff": f.diff }))
+            .collect();
+        let thread_id = &data.threads[0].thread_id;
+        bundle["thread_events"] = serde_json::json!({
+            thread_id.as_str(): [{ "kind": "opened", "actor": "bob", "created_at": "1" }]
+        });
+
+        let client = ArchiveClient::from_json(bundle.to_string().as_bytes()).unwrap();
+        assert_eq!(client.list_reviews(None).unwrap()[0].review_id, review_id);
+        let loaded = client.load_review_data(&review_id).unwrap().unwrap();
+        assert_eq!(loaded.threads.len(), data.threads.len());
+        assert_eq!(loaded.comments.len(), data.comments.len());
+        assert_eq!(loaded.files.len(), data.files.len());
+        assert_eq!(client.thread_events(thread_id).unwrap().len(), 1);
+        assert!(client.load_review_data("cr-missing").unwrap().is_none());
+        assert!(client.reply(thread_id, "hi", None).is_err());
+    }
+}
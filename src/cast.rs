@@ -0,0 +1,133 @@
+//! Session recording to an asciicast v2 file (`--cast <path>`).
+//!
+//! Frames come from the frame buffer, not the terminal: a second presenter
+//! encodes the same diff the terminal got into memory, and each frame is
+//! appended as an output event at its time on the UI's clock (so a
+//! `--replay` produces a cast with the script's timing). Resizes are `r`
+//! events followed by a full frame. Plays with `asciinema play`.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use ftui_render::buffer::Buffer;
+use ftui_render::diff::BufferDiff;
+use ftui_render::presenter::{Presenter, TerminalCapabilities};
+use serde_json::json;
+
+use crate::clock;
+
+/// Hide the cursor and start from a blank screen.
+const PREAMBLE: &str = "\x1b[?25l\x1b[2J\x1b[H";
+
+pub struct CastRecorder {
+    out: BufWriter<File>,
+    /// Encodes frames the way the terminal's presenter does, into memory
+    presenter: Presenter<Vec<u8>>,
+    started: Instant,
+}
+
+impl CastRecorder {
+    /// Create `path` and write the header for a `width` × `height` screen.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created or written.
+    pub fn create(path: &Path, width: u16, height: u16) -> Result<Self> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut header = json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "title": "crit-ui",
+            "env": { "TERM": "xterm-256color" },
+        });
+        // A replay's cast shouldn't differ by when it ran
+        if !clock::is_virtual() {
+            if let Ok(now) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+                header["timestamp"] = now.as_secs().into();
+            }
+        }
+        let mut recorder = Self {
+            out: BufWriter::new(file),
+            presenter: Presenter::new(
+                Vec::new(),
+                // True color, and sync brackets rather than a cursor shown
+                // after each frame
+                TerminalCapabilities::modern(),
+            ),
+            started: clock::now(),
+        };
+        writeln!(recorder.out, "{header}")?;
+        recorder.event("o", PREAMBLE)?;
+        Ok(recorder)
+    }
+
+    /// Append the frame `buffer` as `diff` changed it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frame can't be written.
+    pub fn frame(&mut self, buffer: &Buffer, diff: &BufferDiff) -> Result<()> {
+        self.presenter.present(buffer, diff)?;
+        let bytes = std::mem::take(self.presenter.writer_mut());
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        self.event("o", &String::from_utf8_lossy(&bytes))
+    }
+
+    /// Record a resize to `width` × `height`; the next frame is a full one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event can't be written.
+    pub fn resize(&mut self, width: u16, height: u16) -> Result<()> {
+        self.event("r", &format!("{width}x{height}"))?;
+        self.presenter.reset();
+        self.event("o", PREAMBLE)
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> Result<()> {
+        let at = clock::elapsed(self.started).as_secs_f64();
+        writeln!(
+            self.out,
+            "{}",
+            json!([(at * 1e6).round() / 1e6, code, data])
+        )?;
+        self.out.flush().context("Failed to write cast")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ftui_render::cell::Cell;
+
+    #[test]
+    fn test_cast_has_header_and_frame_events() {
+        let path = std::env::temp_dir().join(format!("crit-ui-cast-{}.cast", std::process::id()));
+        let mut recorder = CastRecorder::create(&path, 4, 2).unwrap();
+        let mut buffer = Buffer::new(4, 2);
+        buffer.set(1, 0, Cell::from_char('x'));
+        recorder.frame(&buffer, &BufferDiff::full(4, 2)).unwrap();
+        recorder.resize(5, 3).unwrap();
+        drop(recorder);
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 4);
+        assert_eq!(lines[1][1], "o");
+        assert!(lines[2][2].as_str().unwrap().contains('x'));
+        assert_eq!(lines[3][1], "r");
+        assert_eq!(lines[3][2], "5x3");
+    }
+}
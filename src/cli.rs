@@ -0,0 +1,684 @@
+//! Command-line parsing.
+//!
+//! Commands and their flags are declared once in `COMMANDS`; parsing,
+//! per-command `--help` and the shell completion scripts are all built from
+//! that table. `crit-ui` without a command runs `view` (the TUI).
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+
+use crate::completions::Shell;
+
+/// Binary name used in help text and completion scripts.
+pub const BIN: &str = "crit-ui";
+
+/// What a flag's value is, for help placeholders and completion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagValue {
+    /// Directory on disk
+    Directory,
+    /// File to write
+    OutputFile,
+    /// File on disk to read
+    InputFile,
+    /// Built-in theme name or theme JSON path
+    Theme,
+    Review,
+    File,
+    Thread,
+    DemoSize,
+    Number,
+}
+
+impl FlagValue {
+    /// Placeholder shown in `--help`.
+    #[must_use]
+    pub const fn placeholder(self) -> &'static str {
+        match self {
+            Self::Directory | Self::OutputFile | Self::InputFile | Self::File => "<path>",
+            Self::Theme => "<name|path>",
+            Self::Review | Self::Thread => "<id>",
+            Self::DemoSize => "<s>",
+            Self::Number => "<n>",
+        }
+    }
+}
+
+/// A command-line option.
+#[derive(Debug, Clone, Copy)]
+pub struct Flag {
+    pub long: &'static str,
+    pub short: Option<char>,
+    pub value: Option<FlagValue>,
+    pub help: &'static str,
+}
+
+impl Flag {
+    const fn new(long: &'static str, value: Option<FlagValue>, help: &'static str) -> Self {
+        Self {
+            long,
+            short: None,
+            value,
+            help,
+        }
+    }
+
+    /// Same flag with command-specific help text.
+    const fn with_help(self, help: &'static str) -> Self {
+        Self { help, ..self }
+    }
+
+    /// `--long <value>` as shown in `--help`.
+    #[must_use]
+    pub fn usage(&self) -> String {
+        let mut usage = format!("--{}", self.long);
+        if let Some(short) = self.short {
+            usage = format!("-{short}, {usage}");
+        }
+        if let Some(value) = self.value {
+            let _ = write!(usage, " {}", value.placeholder());
+        }
+        usage
+    }
+}
+
+const HELP: Flag = Flag {
+    long: "help",
+    short: Some('h'),
+    value: None,
+    help: "Show this help",
+};
+const THEME: Flag = Flag::new(
+    "theme",
+    Some(FlagValue::Theme),
+    "Load theme by name or JSON path",
+);
+const PATH: Flag = Flag::new(
+    "path",
+    Some(FlagValue::Directory),
+    "Path to repo root (uses crit CLI)",
+);
+const REVIEW: Flag = Flag::new(
+    "review",
+    Some(FlagValue::Review),
+    "Open directly to a review (skip review list)",
+);
+const FILE: Flag = Flag::new(
+    "file",
+    Some(FlagValue::File),
+    "Navigate to a specific file (requires --review)",
+);
+const LINE: Flag = Flag::new(
+    "line",
+    Some(FlagValue::Number),
+    "Put the cursor on line n of --file (new side)",
+);
+const THREAD: Flag = Flag::new(
+    "thread",
+    Some(FlagValue::Thread),
+    "Expand a specific thread (requires --review)",
+);
+const READ_ONLY: Flag = Flag::new(
+    "read-only",
+    None,
+    "Browse only; disable commenting and status changes",
+);
+const NO_SYNTAX: Flag = Flag::new(
+    "no-syntax",
+    None,
+    "Skip syntax highlighting; diffs use plain colors",
+);
+const ANNOTATIONS: Flag = Flag::new(
+    "annotations",
+    Some(FlagValue::InputFile),
+    "Load lint/CI annotations from a JSON file",
+);
+const DEMO: Flag = Flag::new(
+    "demo",
+    None,
+    "Use generated demo data even if .crit/ exists",
+);
+const DEMO_SIZE: Flag = Flag::new(
+    "demo-size",
+    Some(FlagValue::DemoSize),
+    "Demo data size: small (default), medium, large",
+);
+const DEMO_SEED: Flag = Flag::new(
+    "demo-seed",
+    Some(FlagValue::Number),
+    "Seed for demo data generation",
+);
+const PROFILE_INPUT: Flag = Flag::new(
+    "profile-input",
+    None,
+    "Print input latency per message type on exit",
+);
+const CONTROL_SOCKET: Flag = Flag::new(
+    "control-socket",
+    Some(FlagValue::OutputFile),
+    "Accept JSON messages on a Unix socket at <path>",
+);
+const RECORD: Flag = Flag::new(
+    "record",
+    Some(FlagValue::OutputFile),
+    "Write the input events to a replay script on exit",
+);
+const REPLAY: Flag = Flag::new(
+    "replay",
+    Some(FlagValue::InputFile),
+    "Play a recorded script on a deterministic clock",
+);
+const CAST: Flag = Flag::new(
+    "cast",
+    Some(FlagValue::OutputFile),
+    "Record the session's frames to an asciicast file",
+);
+const OUTPUT: Flag = Flag::new(
+    "output",
+    Some(FlagValue::OutputFile),
+    "Write to a file instead of stdout",
+);
+const MARKDOWN: Flag = Flag::new(
+    "markdown",
+    None,
+    "Write Markdown with the diffs instead of JSON",
+);
+
+/// Which command a `CommandSpec` runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandKind {
+    View,
+    OpenArchive,
+    Export,
+    Diff,
+    ExportPrefs,
+    ImportPrefs,
+    Completions,
+}
+
+/// A subcommand: its name, help text and accepted flags.
+#[derive(Debug)]
+pub struct CommandSpec {
+    pub kind: CommandKind,
+    pub name: &'static str,
+    /// Arguments shown after the name in the usage line
+    pub usage: &'static str,
+    pub about: &'static str,
+    /// Name of the single positional argument, if any
+    pub positional: Option<&'static str>,
+    pub flags: &'static [Flag],
+}
+
+impl CommandSpec {
+    #[must_use]
+    pub fn flag(&self, long: &str) -> Option<&'static Flag> {
+        self.flags.iter().find(|flag| flag.long == long)
+    }
+
+    /// `--help` text for this command.
+    #[must_use]
+    pub fn help(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "Usage: {BIN} {} {}", self.name, self.usage);
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{}", self.about);
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Options:");
+        write_flags(&mut out, self.flags);
+        out
+    }
+}
+
+pub const VIEW: CommandSpec = CommandSpec {
+    kind: CommandKind::View,
+    name: "view",
+    usage: "[options]",
+    about: "Browse reviews in the TUI (the default command)",
+    positional: None,
+    flags: &[
+        HELP,
+        THEME,
+        PATH,
+        REVIEW,
+        FILE,
+        LINE,
+        THREAD,
+        READ_ONLY,
+        NO_SYNTAX,
+        ANNOTATIONS,
+        DEMO,
+        DEMO_SIZE,
+        DEMO_SEED,
+        PROFILE_INPUT,
+        CONTROL_SOCKET,
+        RECORD,
+        REPLAY,
+        CAST,
+    ],
+};
+
+pub const OPEN_ARCHIVE: CommandSpec = CommandSpec {
+    kind: CommandKind::OpenArchive,
+    name: "open-archive",
+    usage: "<bundle> [options]",
+    about: "Read an exported review bundle (no repo or crit needed)",
+    positional: Some("bundle"),
+    flags: &[HELP, THEME, FILE, LINE, THREAD, NO_SYNTAX],
+};
+
+pub const EXPORT: CommandSpec = CommandSpec {
+    kind: CommandKind::Export,
+    name: "export",
+    usage: "--review <id> [options]",
+    about: "Print a review's threads and comments as JSON or Markdown",
+    positional: None,
+    flags: &[
+        HELP,
+        PATH,
+        REVIEW.with_help("Review to export (required)"),
+        OUTPUT,
+        MARKDOWN,
+        DEMO,
+        DEMO_SIZE,
+        DEMO_SEED,
+    ],
+};
+
+pub const DIFF: CommandSpec = CommandSpec {
+    kind: CommandKind::Diff,
+    name: "diff",
+    usage: "--review <id> [options]",
+    about: "Print a review's diff to stdout",
+    positional: None,
+    flags: &[
+        HELP,
+        PATH,
+        REVIEW.with_help("Review to print (required)"),
+        FILE.with_help("Only print this file's diff"),
+        DEMO,
+        DEMO_SIZE,
+        DEMO_SEED,
+    ],
+};
+
+pub const EXPORT_PREFS: CommandSpec = CommandSpec {
+    kind: CommandKind::ExportPrefs,
+    name: "export-prefs",
+    usage: "[options]",
+    about: "Print your ui.json preferences as JSON, for import-prefs",
+    positional: None,
+    flags: &[HELP, OUTPUT],
+};
+
+pub const IMPORT_PREFS: CommandSpec = CommandSpec {
+    kind: CommandKind::ImportPrefs,
+    name: "import-prefs",
+    usage: "<file>",
+    about: "Merge preferences from export-prefs into your ui.json",
+    positional: Some("file"),
+    flags: &[HELP],
+};
+
+pub const COMPLETIONS: CommandSpec = CommandSpec {
+    kind: CommandKind::Completions,
+    name: "completions",
+    usage: "<shell>",
+    about: "Print a shell completion script (bash, zsh, fish)",
+    positional: Some("shell"),
+    flags: &[HELP],
+};
+
+/// Every subcommand, in help order.
+pub const COMMANDS: &[CommandSpec] = &[
+    VIEW,
+    OPEN_ARCHIVE,
+    EXPORT,
+    DIFF,
+    EXPORT_PREFS,
+    IMPORT_PREFS,
+    COMPLETIONS,
+];
+
+/// Top-level `crit-ui --help`: the command list plus `view`'s options.
+#[must_use]
+pub fn help() -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Usage: {BIN} [command] [options]");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Commands:");
+    let width = help_column(VIEW.flags);
+    for command in COMMANDS {
+        let _ = writeln!(out, "  {:<width$}{}", command.name, command.about);
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Options (view):");
+    write_flags(&mut out, VIEW.flags);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Environment:");
+    let _ = writeln!(out, "  BOTCRIT_UI_THEME  Theme name or JSON path");
+    let _ = writeln!(
+        out,
+        "  BOTCRIT_UI_LANG   UI language (en, es); falls back to LANG"
+    );
+    let _ = writeln!(
+        out,
+        "  BOTCRIT_UI_<KEY>  Any ui.json setting, e.g. BOTCRIT_UI_AUTO_REFRESH_SECS=30"
+    );
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "Settings are read from .botcrit/ui.json under $XDG_CONFIG_DIRS, then"
+    );
+    let _ = writeln!(
+        out,
+        "$XDG_CONFIG_HOME (~/.config), then the repo, then the environment;"
+    );
+    let _ = writeln!(
+        out,
+        "later ones win. Changes made in the UI go to the user file."
+    );
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "If no path is provided, uses $CRIT_DIR, else the nearest .crit/ in the"
+    );
+    let _ = writeln!(
+        out,
+        "current directory or above it (a worktree's main workspace counts)."
+    );
+    let _ = writeln!(
+        out,
+        "If that doesn't exist, runs in demo mode with sample data."
+    );
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Run `{BIN} <command> --help` for a command's options.");
+    out
+}
+
+/// Column option help text starts at: past the longest usage, and no
+/// narrower than the command list's.
+fn help_column(flags: &[Flag]) -> usize {
+    let longest = flags.iter().map(|flag| flag.usage().len()).max();
+    longest.map_or(0, |len| len + 2).max(22)
+}
+
+fn write_flags(out: &mut String, flags: &[Flag]) {
+    let width = help_column(flags);
+    for flag in flags {
+        let _ = writeln!(out, "  {:<width$}{}", flag.usage(), flag.help);
+    }
+}
+
+/// Result of parsing the command line.
+#[derive(Debug)]
+pub struct Matches {
+    pub command: &'static CommandSpec,
+    /// `--help` was given; nothing else is validated
+    pub help: bool,
+    pub positional: Option<String>,
+    values: HashMap<&'static str, String>,
+    switches: HashSet<&'static str>,
+}
+
+impl Matches {
+    /// Value of `--long`, if given.
+    #[must_use]
+    pub fn value(&self, long: &str) -> Option<&str> {
+        self.values.get(long).map(String::as_str)
+    }
+
+    /// Whether the switch `--long` was given.
+    #[must_use]
+    pub fn is_set(&self, long: &str) -> bool {
+        self.switches.contains(long)
+    }
+
+    /// Value of `--long`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the flag if it wasn't given.
+    pub fn required(&self, long: &str) -> Result<&str> {
+        self.value(long)
+            .with_context(|| format!("{BIN} {} requires --{long}", self.command.name))
+    }
+
+    /// Value of `--long` parsed as `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value doesn't parse.
+    pub fn parsed<T: FromStr>(&self, long: &str) -> Result<Option<T>> {
+        self.value(long)
+            .map(|value| {
+                value
+                    .parse()
+                    .ok()
+                    .with_context(|| format!("Invalid value for --{long}: {value}"))
+            })
+            .transpose()
+    }
+}
+
+/// Parse `args` (without the program name).
+///
+/// # Errors
+///
+/// Returns an error for an unknown command or option (suggesting the closest
+/// match), a missing flag value, or an unexpected positional argument.
+pub fn parse(args: &[String]) -> Result<Matches> {
+    let (command, rest) = match args.first() {
+        Some(first) if !first.starts_with('-') => {
+            let Some(command) = COMMANDS.iter().find(|c| c.name == first) else {
+                bail!(
+                    "Unknown command: {first}{}",
+                    did_you_mean(first, COMMANDS.iter().map(|c| c.name), "")
+                );
+            };
+            (command, &args[1..])
+        }
+        _ => (&VIEW, args),
+    };
+
+    let mut matches = Matches {
+        command,
+        help: false,
+        positional: None,
+        values: HashMap::new(),
+        switches: HashSet::new(),
+    };
+    let mut iter = rest.iter();
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = if let Some(long) = arg.strip_prefix("--") {
+            let (name, inline) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (long, None),
+            };
+            let Some(flag) = command.flag(name) else {
+                bail!(
+                    "Unknown option: --{name}{}",
+                    unknown_option_hint(command, name)
+                );
+            };
+            (flag, inline)
+        } else if arg.len() > 1 && arg.starts_with('-') {
+            let mut chars = arg[1..].chars();
+            let short = chars.next().filter(|_| chars.as_str().is_empty());
+            let flag = command
+                .flags
+                .iter()
+                .find(|f| f.short.is_some() && f.short == short);
+            let Some(flag) = flag else {
+                bail!("Unknown option: {arg}");
+            };
+            (flag, None)
+        } else {
+            if command.positional.is_none() || matches.positional.is_some() {
+                bail!("Unexpected argument: {arg}");
+            }
+            matches.positional = Some(arg.clone());
+            continue;
+        };
+
+        if flag.long == HELP.long {
+            matches.help = true;
+            return Ok(matches);
+        }
+        if let Some(value) = flag.value {
+            let Some(given) = inline.map(str::to_string).or_else(|| iter.next().cloned()) else {
+                bail!("--{} requires {}", flag.long, value.placeholder());
+            };
+            matches.values.insert(flag.long, given);
+        } else {
+            if inline.is_some() {
+                bail!("--{} doesn't take a value", flag.long);
+            }
+            matches.switches.insert(flag.long);
+        }
+    }
+    Ok(matches)
+}
+
+/// Typo suggestion for an unknown `--name`, or which command accepts it.
+fn unknown_option_hint(command: &CommandSpec, name: &str) -> String {
+    let hint = did_you_mean(name, command.flags.iter().map(|f| f.long), "--");
+    if !hint.is_empty() {
+        return hint;
+    }
+    COMMANDS
+        .iter()
+        .find(|other| other.flag(name).is_some())
+        .map(|other| format!(" (only valid for `{BIN} {}`)", other.name))
+        .unwrap_or_default()
+}
+
+/// ` (did you mean <prefix><candidate>?)` for the closest candidate, if any
+/// is close enough to be a likely typo.
+#[must_use]
+pub fn did_you_mean<'a>(
+    input: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+    prefix: &str,
+) -> String {
+    suggest(input, candidates)
+        .map(|candidate| format!(" (did you mean {prefix}{candidate}?)"))
+        .unwrap_or_default()
+}
+
+/// Closest candidate within a typo-sized edit distance of `input`.
+#[must_use]
+pub fn suggest<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = (input.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(input, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance over chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != cb);
+            current.push(substitute.min(prev[j + 1] + 1).min(current[j] + 1));
+        }
+        prev = current;
+    }
+    prev[b.len()]
+}
+
+/// Shell named by `completions <shell>`.
+///
+/// # Errors
+///
+/// Returns an error if the shell is missing or unsupported.
+pub fn completion_shell(matches: &Matches) -> Result<Shell> {
+    let names = Shell::NAMES.join(", ");
+    let shell = matches
+        .positional
+        .as_deref()
+        .with_context(|| format!("completions requires a shell: {names}"))?;
+    Shell::parse(shell).with_context(|| {
+        format!(
+            "Unknown shell: {shell}{} (expected {names})",
+            did_you_mean(shell, Shell::NAMES, "")
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_commands_and_flags() {
+        let m = parse(&args("--review cr-1 --read-only")).unwrap();
+        assert_eq!(m.command.kind, CommandKind::View);
+        assert_eq!(m.value("review"), Some("cr-1"));
+        assert!(m.is_set("read-only"));
+
+        let m = parse(&args("diff --review=cr-2 --file src/a.rs")).unwrap();
+        assert_eq!(m.command.kind, CommandKind::Diff);
+        assert_eq!(m.required("review").unwrap(), "cr-2");
+        assert_eq!(m.value("file"), Some("src/a.rs"));
+
+        let m = parse(&args("open-archive r.critbundle --file src/a.rs")).unwrap();
+        assert_eq!(m.command.kind, CommandKind::OpenArchive);
+        assert_eq!(m.positional.as_deref(), Some("r.critbundle"));
+
+        let m = parse(&args("completions fish")).unwrap();
+        assert_eq!(completion_shell(&m).unwrap(), Shell::Fish);
+
+        let m = parse(&args("export -h --bogus")).unwrap();
+        assert!(m.help);
+        assert_eq!(m.command.kind, CommandKind::Export);
+
+        let m = parse(&args("--demo-seed 7")).unwrap();
+        assert_eq!(m.parsed::<u64>("demo-seed").unwrap(), Some(7));
+        assert!(parse(&args("--demo-seed x"))
+            .unwrap()
+            .parsed::<u64>("demo-seed")
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_errors_suggest_typos() {
+        let err = parse(&args("--revew cr-1")).unwrap_err().to_string();
+        assert_eq!(err, "Unknown option: --revew (did you mean --review?)");
+
+        let err = parse(&args("exprot")).unwrap_err().to_string();
+        assert_eq!(err, "Unknown command: exprot (did you mean export?)");
+
+        let err = parse(&args("diff --line 3")).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Unknown option: --line (only valid for `crit-ui view`)"
+        );
+        assert!(parse(&args("--theme")).is_err());
+        assert!(parse(&args("--demo=yes")).is_err());
+        assert!(parse(&args("view extra")).is_err());
+        assert_eq!(suggest("zzz", ["view", "diff"]), None);
+    }
+
+    #[test]
+    fn test_help_keeps_a_gap_before_long_options_text() {
+        let help = help();
+        let line = help
+            .lines()
+            .find(|line| line.contains("--control-socket"))
+            .unwrap();
+        assert!(line.starts_with("  --control-socket <path>  "));
+    }
+}
@@ -2,18 +2,21 @@
 
 use std::collections::HashMap;
 use std::ffi::OsStr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::{bail, Context, Result};
 use serde::Deserialize;
 
 use crate::db::{
-    Comment, CritClient, FileContentData, FileData, ReviewData, ReviewDetail, ReviewSummary,
-    ThreadSummary,
+    thread_etag, Comment, CreatedComment, CritClient, ExpectedThread, FileContentData, FileData,
+    ReviewData, ReviewDetail, ReviewSummary, ReviewVote, ThreadConflict, ThreadEvent, ThreadSide,
+    ThreadSummary, Verdict, REVIEW_THREAD_PATH,
 };
+use crate::vcs::{self, detect_vcs, VcsType};
 
 /// Client that invokes the `crit` binary as a subprocess.
+#[derive(Clone)]
 pub struct CliClient {
     repo_path: PathBuf,
 }
@@ -51,7 +54,29 @@ impl CliClient {
         Ok(output.stdout)
     }
 
-    fn comment_agent() -> String {
+    /// Fail with a [`ThreadConflict`] if the thread no longer matches
+    /// `expected.etag`.
+    fn check_thread_unchanged(&self, thread_id: &str, expected: ExpectedThread<'_>) -> Result<()> {
+        let Some(mut data) = self.load_review_data(expected.review_id)? else {
+            bail!("Review not found: {}", expected.review_id);
+        };
+        let Some(thread) = data.threads.iter().find(|t| t.thread_id == thread_id) else {
+            bail!("Thread not found: {thread_id}");
+        };
+        let comments = data.comments.remove(thread_id).unwrap_or_default();
+        if thread_etag(&thread.status, &comments) == expected.etag {
+            return Ok(());
+        }
+        Err(ThreadConflict {
+            thread_id: thread_id.to_string(),
+            status: thread.status.clone(),
+            comments,
+        }
+        .into())
+    }
+
+    /// `$USER`, the name comments are posted under.
+    pub(crate) fn comment_agent() -> String {
         std::env::var("USER")
             .ok()
             .filter(|value| !value.trim().is_empty())
@@ -90,8 +115,15 @@ struct CombinedFileContent {
     lines: Vec<String>,
 }
 
+/// Response of `crit review <id> --stored-diffs`: the diffs saved with the
+/// review, same shape as `--include-diffs` files.
+#[derive(Deserialize)]
+struct StoredDiffResponse {
+    files: Vec<CombinedFile>,
+}
+
 /// Review detail from the combined endpoint.
-/// Has extra fields (`reviewers`, `votes`) that we ignore.
+/// Has an extra field (`reviewers`) that we ignore.
 #[derive(Deserialize)]
 struct CombinedReview {
     review_id: String,
@@ -108,6 +140,8 @@ struct CombinedReview {
     abandon_reason: Option<String>,
     thread_count: i64,
     open_thread_count: i64,
+    #[serde(default)]
+    votes: Vec<ReviewVote>,
 }
 
 /// Thread from the combined endpoint — carries inline `comments` vec.
@@ -117,6 +151,8 @@ struct CombinedThread {
     file_path: String,
     selection_start: i64,
     selection_end: Option<i64>,
+    #[serde(default)]
+    side: Option<ThreadSide>,
     status: String,
     comments: Vec<CombinedComment>,
 }
@@ -129,6 +165,19 @@ struct CombinedComment {
     created_at: String,
 }
 
+/// Response of `crit threads events <id>`.
+#[derive(Deserialize)]
+struct ThreadEventsResponse {
+    events: Vec<ThreadEvent>,
+}
+
+/// Ids echoed back by `crit comment` / `crit reply`.
+#[derive(Deserialize)]
+struct CreatedResponse {
+    thread_id: Option<String>,
+    comment_id: Option<String>,
+}
+
 // -- Conversions --
 
 impl From<CombinedReview> for ReviewDetail {
@@ -148,30 +197,27 @@ impl From<CombinedReview> for ReviewDetail {
             abandon_reason: r.abandon_reason,
             thread_count: r.thread_count,
             open_thread_count: r.open_thread_count,
+            votes: r.votes,
+            rewritten_to: None,
         }
     }
 }
 
-impl CritClient for CliClient {
-    fn list_reviews(&self, status: Option<&str>) -> Result<Vec<ReviewSummary>> {
-        let stdout = self.run_crit(["reviews", "list"])?;
-        let resp: ReviewsListResponse =
-            serde_json::from_slice(&stdout).context("Failed to parse `crit reviews list` JSON")?;
-        let reviews = resp.reviews;
-
-        match status {
-            Some(s) => Ok(reviews.into_iter().filter(|r| r.status == s).collect()),
-            None => Ok(reviews),
+impl From<CombinedFile> for FileData {
+    fn from(f: CombinedFile) -> Self {
+        Self {
+            path: f.path,
+            diff: f.diff,
+            content: f.content.map(|c| FileContentData {
+                start_line: c.start_line,
+                lines: c.lines,
+            }),
         }
     }
+}
 
-    fn load_review_data(&self, review_id: &str) -> Result<Option<ReviewData>> {
-        let stdout = self.run_crit(["review", review_id, "--include-diffs"])?;
-        let resp: CombinedResponse =
-            serde_json::from_slice(&stdout).context("Failed to parse `crit review` JSON")?;
-
-        let detail: ReviewDetail = resp.review.into();
-
+impl From<CombinedResponse> for ReviewData {
+    fn from(resp: CombinedResponse) -> Self {
         let mut threads = Vec::with_capacity(resp.threads.len());
         let mut comments: HashMap<String, Vec<Comment>> = HashMap::new();
 
@@ -197,30 +243,73 @@ impl CritClient for CliClient {
                 file_path: t.file_path,
                 selection_start: t.selection_start,
                 selection_end: t.selection_end,
+                side: t.side,
                 status: t.status,
                 comment_count,
             });
         }
 
-        let files = resp
-            .files
-            .into_iter()
-            .map(|f| FileData {
-                path: f.path,
-                diff: f.diff,
-                content: f.content.map(|c| FileContentData {
-                    start_line: c.start_line,
-                    lines: c.lines,
-                }),
-            })
-            .collect();
-
-        Ok(Some(ReviewData {
-            detail,
+        Self {
+            detail: resp.review.into(),
             threads,
             comments,
-            files,
-        }))
+            files: resp.files.into_iter().map(FileData::from).collect(),
+        }
+    }
+}
+
+/// Parse the combined `crit review <id>` JSON (also the layout of archive
+/// bundles).
+pub(crate) fn parse_review(json: &[u8]) -> serde_json::Result<ReviewData> {
+    serde_json::from_slice::<CombinedResponse>(json).map(ReviewData::from)
+}
+
+impl CritClient for CliClient {
+    fn list_reviews(&self, status: Option<&str>) -> Result<Vec<ReviewSummary>> {
+        let stdout = self.run_crit(["reviews", "list"])?;
+        let resp: ReviewsListResponse =
+            serde_json::from_slice(&stdout).context("Failed to parse `crit reviews list` JSON")?;
+        let reviews = resp.reviews;
+
+        match status {
+            Some(s) => Ok(reviews.into_iter().filter(|r| r.status == s).collect()),
+            None => Ok(reviews),
+        }
+    }
+
+    fn load_review_data(&self, review_id: &str) -> Result<Option<ReviewData>> {
+        let vcs = detect_vcs(&self.repo_path);
+        // Without a checkout crit can't compute diffs; use the stored ones
+        if vcs.is_none() {
+            let stdout = self.run_crit(["review", review_id])?;
+            let mut data = parse_review(&stdout).context("Failed to parse `crit review` JSON")?;
+            data.files = self.get_stored_diff(review_id)?.unwrap_or_default();
+            return Ok(Some(data));
+        }
+        let stdout = self.run_crit(["review", review_id, "--include-diffs"])?;
+        let mut data = parse_review(&stdout).context("Failed to parse `crit review` JSON")?;
+        if vcs == Some(VcsType::Jj) {
+            follow_jj_change(&self.repo_path, &mut data);
+        }
+        Ok(Some(data))
+    }
+
+    fn get_stored_diff(&self, review_id: &str) -> Result<Option<Vec<FileData>>> {
+        let stdout = self.run_crit(["review", review_id, "--stored-diffs"])?;
+        let resp: StoredDiffResponse = serde_json::from_slice(&stdout)
+            .context("Failed to parse `crit review --stored-diffs` JSON")?;
+        Ok(Some(resp.files.into_iter().map(FileData::from).collect()))
+    }
+
+    fn current_user(&self) -> String {
+        Self::comment_agent()
+    }
+
+    fn thread_events(&self, thread_id: &str) -> Result<Vec<ThreadEvent>> {
+        let stdout = self.run_crit(["threads", "events", thread_id])?;
+        let resp: ThreadEventsResponse = serde_json::from_slice(&stdout)
+            .context("Failed to parse `crit threads events` JSON")?;
+        Ok(resp.events)
     }
 
     fn comment(
@@ -229,23 +318,177 @@ impl CritClient for CliClient {
         file_path: &str,
         start_line: i64,
         end_line: Option<i64>,
+        side: ThreadSide,
         body: &str,
-    ) -> Result<()> {
+    ) -> Result<Option<CreatedComment>> {
         let lines_arg = match end_line {
             Some(end) if end != start_line => format!("{start_line}-{end}"),
             _ => start_line.to_string(),
         };
         let agent = Self::comment_agent();
-        self.run_crit([
-            "comment", review_id, body, "--file", file_path, "--line", &lines_arg, "--agent",
-            &agent,
-        ])?;
+        let mut args = vec!["comment", review_id, body, "--agent", &agent];
+        // A review-level thread has no file or line to anchor to
+        if file_path != REVIEW_THREAD_PATH {
+            args.extend(["--file", file_path, "--line", &lines_arg]);
+        }
+        // New side is crit's default; only pass --side when it differs so
+        // older crit versions keep working for ordinary comments
+        if side == ThreadSide::Old {
+            args.extend(["--side", "old"]);
+        }
+        let stdout = self.run_crit(args)?;
+        Ok(parse_created(&stdout, None, agent))
+    }
+
+    fn reply(
+        &self,
+        thread_id: &str,
+        body: &str,
+        expected: Option<ExpectedThread<'_>>,
+    ) -> Result<Option<CreatedComment>> {
+        if let Some(expected) = expected {
+            // crit has no conditional reply, so check right before posting.
+            // This narrows the race with other clients rather than closing it.
+            self.check_thread_unchanged(thread_id, expected)?;
+        }
+        let agent = Self::comment_agent();
+        let stdout = self.run_crit(["reply", thread_id, body, "--agent", &agent])?;
+        Ok(parse_created(&stdout, Some(thread_id), agent))
+    }
+
+    fn resolve_thread(&self, thread_id: &str, expected: Option<ExpectedThread<'_>>) -> Result<()> {
+        // Same race-narrowing check as `reply`
+        if let Some(expected) = expected {
+            self.check_thread_unchanged(thread_id, expected)?;
+        }
+        self.run_crit(["threads", "resolve", thread_id])?;
         Ok(())
     }
 
-    fn reply(&self, thread_id: &str, body: &str) -> Result<()> {
+    fn reopen_thread(&self, thread_id: &str, expected: Option<ExpectedThread<'_>>) -> Result<()> {
+        if let Some(expected) = expected {
+            self.check_thread_unchanged(thread_id, expected)?;
+        }
+        self.run_crit(["threads", "reopen", thread_id])?;
+        Ok(())
+    }
+
+    fn submit_verdict(
+        &self,
+        review_id: &str,
+        verdict: Verdict,
+        summary: Option<&str>,
+    ) -> Result<()> {
         let agent = Self::comment_agent();
-        self.run_crit(["reply", thread_id, body, "--agent", &agent])?;
+        let mut args = match verdict {
+            Verdict::Approve => vec!["lgtm", review_id],
+            Verdict::RequestChanges => vec!["block", review_id],
+        };
+        if let Some(summary) = summary {
+            args.push(match verdict {
+                Verdict::Approve => "--message",
+                Verdict::RequestChanges => "--reason",
+            });
+            args.push(summary);
+        }
+        args.extend(["--agent", &agent]);
+        self.run_crit(args)?;
+        Ok(())
+    }
+
+    fn abandon_review(&self, review_id: &str, reason: Option<&str>) -> Result<()> {
+        let agent = Self::comment_agent();
+        let mut args = vec!["reviews", "abandon", review_id];
+        if let Some(reason) = reason {
+            args.extend(["--reason", reason]);
+        }
+        args.extend(["--agent", &agent]);
+        self.run_crit(args)?;
         Ok(())
     }
+
+    fn restore_review(&self, review_id: &str) -> Result<()> {
+        let agent = Self::comment_agent();
+        self.run_crit(["reviews", "reopen", review_id, "--agent", &agent])?;
+        Ok(())
+    }
+
+    fn worker(&self) -> Option<Box<dyn CritClient + Send>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+/// Pull the created ids out of a comment/reply response. Older crit versions
+/// don't echo them; callers fall back to a reload in that case.
+fn parse_created(stdout: &[u8], thread_id: Option<&str>, author: String) -> Option<CreatedComment> {
+    let resp: CreatedResponse = serde_json::from_slice(stdout).ok()?;
+    Some(CreatedComment {
+        thread_id: resp.thread_id.or_else(|| thread_id.map(str::to_string))?,
+        comment_id: resp.comment_id?,
+        author,
+    })
+}
+
+/// Follow a review's jj change to the commit it points at now. If it was
+/// rewritten since crit recorded the review, show the change's diff there
+/// and move thread anchors along; a thread on a line the rewrite changed
+/// keeps its line and shows as orphaned.
+fn follow_jj_change(repo_path: &Path, data: &mut ReviewData) {
+    let change_id = &data.detail.jj_change_id;
+    if change_id.is_empty() {
+        return;
+    }
+    let Some(current) = vcs::jj_change_commit(repo_path, change_id) else {
+        return;
+    };
+    let recorded = data.detail.commit().to_string();
+    if vcs::same_commit(&recorded, &current) {
+        return;
+    }
+    let Some(diffs) = vcs::jj_change_diffs(repo_path, change_id) else {
+        return;
+    };
+    // crit's context windows were read at the old commit; main reads them
+    // again at the new one
+    data.files = diffs
+        .into_iter()
+        .map(|(path, diff)| FileData {
+            path,
+            diff: Some(diff),
+            content: None,
+        })
+        .collect();
+    reanchor_threads(repo_path, &recorded, &current, &mut data.threads);
+    data.detail.rewritten_to = Some(current);
+}
+
+/// Move new-side thread anchors from commit `from` to `to` through each
+/// file's diff between the two.
+fn reanchor_threads(repo_path: &Path, from: &str, to: &str, threads: &mut [ThreadSummary]) {
+    let mut interdiffs = HashMap::new();
+    for thread in threads {
+        if thread.is_file_thread() || thread.side == Some(ThreadSide::Old) {
+            continue;
+        }
+        let interdiff = interdiffs
+            .entry(thread.file_path.clone())
+            .or_insert_with(|| vcs::get_file_diff(repo_path, &thread.file_path, from, Some(to)));
+        // Unchanged between the two
+        let Some(interdiff) = interdiff else {
+            continue;
+        };
+        let map = |line: i64| {
+            u32::try_from(line)
+                .ok()
+                .and_then(|line| interdiff.map_old_line(line))
+                .map(i64::from)
+        };
+        if let Some(start) = map(thread.selection_start) {
+            thread.selection_end = thread
+                .selection_end
+                .and_then(map)
+                .filter(|&end| end >= start);
+            thread.selection_start = start;
+        }
+    }
 }
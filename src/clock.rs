@@ -0,0 +1,74 @@
+//! The UI's time source.
+//!
+//! Normally the system's monotonic clock. `--replay` switches to a virtual
+//! clock that only moves to each step's timestamp, so debounce, highlight
+//! windows, spinners and auto-refresh timers play out the same on every
+//! run. Once the script ends the clock runs again from where it stopped.
+
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+enum Mode {
+    Real,
+    /// Stopped at this far past the epoch
+    Virtual(Duration),
+    /// Running again since `since`, which read `at` past the epoch
+    Resumed {
+        at: Duration,
+        since: Instant,
+    },
+}
+
+static MODE: Mutex<Mode> = Mutex::new(Mode::Real);
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+fn epoch() -> Instant {
+    *EPOCH.get_or_init(Instant::now)
+}
+
+fn mode() -> Mode {
+    *MODE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn set_mode(mode: Mode) {
+    *MODE.lock().unwrap_or_else(PoisonError::into_inner) = mode;
+}
+
+/// Current time.
+#[must_use]
+pub fn now() -> Instant {
+    match mode() {
+        Mode::Real => Instant::now(),
+        Mode::Virtual(at) => epoch() + at,
+        Mode::Resumed { at, since } => epoch() + at + since.elapsed(),
+    }
+}
+
+/// Time since `earlier`, zero if it's in the future.
+#[must_use]
+pub fn elapsed(earlier: Instant) -> Duration {
+    now().saturating_duration_since(earlier)
+}
+
+/// Whether time only moves when told to.
+#[must_use]
+pub fn is_virtual() -> bool {
+    matches!(mode(), Mode::Virtual(_))
+}
+
+/// Stop the clock at `at` past the virtual epoch (the first call's time).
+pub fn set_virtual(at: Duration) {
+    epoch();
+    set_mode(Mode::Virtual(at));
+}
+
+/// Start a stopped clock running from where it is.
+pub fn resume() {
+    if let Mode::Virtual(at) = mode() {
+        set_mode(Mode::Resumed {
+            at,
+            since: Instant::now(),
+        });
+    }
+}
@@ -1,15 +1,89 @@
 //! Command definitions for the command palette.
 
+use serde::Deserialize;
+
+use crate::i18n::{tr, Text};
 use crate::message::Message;
+use crate::model::ReviewSort;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 pub enum CommandId {
     Quit,
     SelectTheme,
     ToggleDiffView,
+    ToggleFileDiffView,
     ToggleDiffWrap,
+    ToggleWordDiff,
+    ToggleFileHighlighting,
+    FoldHunk,
+    FoldFile,
+    ExpandUp,
+    ExpandDown,
+    ToggleFileViewed,
+    ToggleCrMarkers,
+    ToggleBlame,
+    CycleDensity,
     ToggleSidebar,
     OpenFileInEditor,
+    CopyCommentId,
+    CopyThreadLink,
+    CopyCheckoutCommand,
+    CopyFilePath,
+    CopyLineReference,
+    ExportReview,
+    RunReviewCommand,
+    ShowInputLog,
+    JumpToComment,
+    ToggleMuteThread,
+    ShowThreadsPanel,
+    ApplySuggestion,
+    UndoSuggestion,
+    CommentOnReview,
+    FinishReview,
+    AbandonReview,
+    RestoreReview,
+    Undo,
+    Redo,
+    OpenInbox,
+    GoToLine,
+    SetContextRadius,
+    SortReviews,
+    ToggleReviewGrouping,
+}
+
+impl CommandId {
+    /// Whether running the command first asks for a value.
+    #[must_use]
+    pub const fn takes_argument(self) -> bool {
+        matches!(
+            self,
+            Self::GoToLine | Self::SetContextRadius | Self::SortReviews
+        )
+    }
+}
+
+/// Largest context radius the palette accepts.
+pub const MAX_CONTEXT_LINES: u16 = 50;
+
+/// Message for an argument command given the prompt's input, or `None` if
+/// the input isn't a valid value.
+#[must_use]
+pub fn argument_message(id: CommandId, input: &str) -> Option<Message> {
+    let input = input.trim();
+    match id {
+        CommandId::GoToLine => input
+            .parse()
+            .ok()
+            .filter(|&line| line > 0)
+            .map(Message::GoToLine),
+        CommandId::SetContextRadius => input
+            .parse()
+            .ok()
+            .filter(|&lines| lines <= MAX_CONTEXT_LINES)
+            .map(Message::SetContextLines),
+        CommandId::SortReviews => ReviewSort::from_name(input).map(Message::SetReviewSort),
+        _ => None,
+    }
 }
 
 #[derive(Clone)]
@@ -23,60 +97,316 @@ pub struct CommandSpec {
     pub active: bool,
 }
 
+/// Palette entries: command, name, description, category and shortcut.
+const COMMANDS: &[(CommandId, Text, Text, Text, Option<&str>)] = &[
+    // --- View ---
+    (
+        CommandId::ToggleDiffView,
+        Text::CmdToggleDiffView,
+        Text::CmdToggleDiffViewDesc,
+        Text::CategoryView,
+        Some("v"),
+    ),
+    (
+        CommandId::ToggleFileDiffView,
+        Text::CmdToggleFileDiffView,
+        Text::CmdToggleFileDiffViewDesc,
+        Text::CategoryView,
+        Some("ctrl+v"),
+    ),
+    (
+        CommandId::ToggleDiffWrap,
+        Text::CmdToggleWrap,
+        Text::CmdToggleWrapDesc,
+        Text::CategoryView,
+        Some("w"),
+    ),
+    (
+        CommandId::ToggleWordDiff,
+        Text::CmdToggleWordDiff,
+        Text::CmdToggleWordDiffDesc,
+        Text::CategoryView,
+        Some("W"),
+    ),
+    (
+        CommandId::ToggleFileHighlighting,
+        Text::CmdToggleHighlighting,
+        Text::CmdToggleHighlightingDesc,
+        Text::CategoryView,
+        None,
+    ),
+    (
+        CommandId::FoldHunk,
+        Text::CmdFoldHunk,
+        Text::CmdFoldHunkDesc,
+        Text::CategoryView,
+        Some("za"),
+    ),
+    (
+        CommandId::FoldFile,
+        Text::CmdFoldFile,
+        Text::CmdFoldFileDesc,
+        Text::CategoryView,
+        Some("zA"),
+    ),
+    (
+        CommandId::ExpandUp,
+        Text::CmdExpandUp,
+        Text::CmdExpandUpDesc,
+        Text::CategoryView,
+        Some("E"),
+    ),
+    (
+        CommandId::ExpandDown,
+        Text::CmdExpandDown,
+        Text::CmdExpandDownDesc,
+        Text::CategoryView,
+        Some("e"),
+    ),
+    (
+        CommandId::ToggleFileViewed,
+        Text::CmdToggleViewed,
+        Text::CmdToggleViewedDesc,
+        Text::CategoryView,
+        Some("x"),
+    ),
+    (
+        CommandId::ToggleCrMarkers,
+        Text::CmdToggleCrMarkers,
+        Text::CmdToggleCrMarkersDesc,
+        Text::CategoryView,
+        None,
+    ),
+    (
+        CommandId::ToggleBlame,
+        Text::CmdToggleBlame,
+        Text::CmdToggleBlameDesc,
+        Text::CategoryView,
+        Some("B"),
+    ),
+    (
+        CommandId::CycleDensity,
+        Text::CmdCycleDensity,
+        Text::CmdCycleDensityDesc,
+        Text::CategoryView,
+        None,
+    ),
+    (
+        CommandId::ToggleSidebar,
+        Text::CmdToggleSidebar,
+        Text::CmdToggleSidebarDesc,
+        Text::CategoryView,
+        Some("s"),
+    ),
+    (
+        CommandId::GoToLine,
+        Text::CmdGoToLine,
+        Text::CmdGoToLineDesc,
+        Text::CategoryView,
+        None,
+    ),
+    (
+        CommandId::SetContextRadius,
+        Text::CmdSetContextRadius,
+        Text::CmdSetContextRadiusDesc,
+        Text::CategoryView,
+        None,
+    ),
+    (
+        CommandId::SortReviews,
+        Text::CmdSortReviews,
+        Text::CmdSortReviewsDesc,
+        Text::CategoryView,
+        Some("o"),
+    ),
+    (
+        CommandId::ToggleReviewGrouping,
+        Text::CmdGroupReviews,
+        Text::CmdGroupReviewsDesc,
+        Text::CategoryView,
+        Some("O"),
+    ),
+    (
+        CommandId::SelectTheme,
+        Text::CmdSelectTheme,
+        Text::CmdSelectThemeDesc,
+        Text::CategoryView,
+        None,
+    ),
+    // --- Comments ---
+    (
+        CommandId::CopyCommentId,
+        Text::CmdCopyCommentId,
+        Text::CmdCopyCommentIdDesc,
+        Text::CategoryComments,
+        Some("Y"),
+    ),
+    (
+        CommandId::CopyThreadLink,
+        Text::CmdCopyThreadLink,
+        Text::CmdCopyThreadLinkDesc,
+        Text::CategoryComments,
+        Some("y"),
+    ),
+    (
+        CommandId::JumpToComment,
+        Text::CmdJumpToComment,
+        Text::CmdJumpToCommentDesc,
+        Text::CategoryComments,
+        None,
+    ),
+    (
+        CommandId::ToggleMuteThread,
+        Text::CmdToggleMuteThread,
+        Text::CmdToggleMuteThreadDesc,
+        Text::CategoryComments,
+        Some("m"),
+    ),
+    (
+        CommandId::ShowThreadsPanel,
+        Text::CmdThreadsPanel,
+        Text::CmdThreadsPanelDesc,
+        Text::CategoryComments,
+        Some("t"),
+    ),
+    (
+        CommandId::ApplySuggestion,
+        Text::CmdApplySuggestion,
+        Text::CmdApplySuggestionDesc,
+        Text::CategoryComments,
+        Some("S"),
+    ),
+    (
+        CommandId::UndoSuggestion,
+        Text::CmdUndoSuggestion,
+        Text::CmdUndoSuggestionDesc,
+        Text::CategoryComments,
+        Some("U"),
+    ),
+    (
+        CommandId::CommentOnReview,
+        Text::CmdCommentOnReview,
+        Text::CmdCommentOnReviewDesc,
+        Text::CategoryComments,
+        None,
+    ),
+    (
+        CommandId::FinishReview,
+        Text::CmdFinishReview,
+        Text::CmdFinishReviewDesc,
+        Text::CategoryComments,
+        None,
+    ),
+    (
+        CommandId::AbandonReview,
+        Text::CmdAbandonReview,
+        Text::CmdAbandonReviewDesc,
+        Text::CategoryComments,
+        None,
+    ),
+    (
+        CommandId::RestoreReview,
+        Text::CmdRestoreReview,
+        Text::CmdRestoreReviewDesc,
+        Text::CategoryComments,
+        None,
+    ),
+    (
+        CommandId::Undo,
+        Text::CmdUndo,
+        Text::CmdUndoDesc,
+        Text::CategoryComments,
+        Some("u"),
+    ),
+    (
+        CommandId::Redo,
+        Text::CmdRedo,
+        Text::CmdRedoDesc,
+        Text::CategoryComments,
+        Some("ctrl+r"),
+    ),
+    // --- Session ---
+    (
+        CommandId::OpenInbox,
+        Text::CmdOpenInbox,
+        Text::CmdOpenInboxDesc,
+        Text::CategorySession,
+        Some("i"),
+    ),
+    (
+        CommandId::CopyCheckoutCommand,
+        Text::CmdCopyCheckoutCommand,
+        Text::CmdCopyCheckoutCommandDesc,
+        Text::CategorySession,
+        Some("C"),
+    ),
+    (
+        CommandId::CopyFilePath,
+        Text::CmdCopyFilePath,
+        Text::CmdCopyFilePathDesc,
+        Text::CategorySession,
+        None,
+    ),
+    (
+        CommandId::CopyLineReference,
+        Text::CmdCopyLineReference,
+        Text::CmdCopyLineReferenceDesc,
+        Text::CategorySession,
+        None,
+    ),
+    (
+        CommandId::ExportReview,
+        Text::CmdExportReview,
+        Text::CmdExportReviewDesc,
+        Text::CategorySession,
+        None,
+    ),
+    (
+        CommandId::RunReviewCommand,
+        Text::CmdRunReviewCommand,
+        Text::CmdRunReviewCommandDesc,
+        Text::CategorySession,
+        Some("!"),
+    ),
+    (
+        CommandId::ShowInputLog,
+        Text::CmdInputLog,
+        Text::CmdInputLogDesc,
+        Text::CategorySession,
+        None,
+    ),
+    (
+        CommandId::OpenFileInEditor,
+        Text::CmdOpenInEditor,
+        Text::CmdOpenInEditorDesc,
+        Text::CategorySession,
+        Some("o"),
+    ),
+    (
+        CommandId::Quit,
+        Text::CmdQuit,
+        Text::CmdQuitDesc,
+        Text::CategorySession,
+        Some("q"),
+    ),
+];
+
+/// Commands for the palette. In read-only mode, commands that would modify
+/// the review are left out.
 #[must_use]
-pub fn get_commands() -> Vec<CommandSpec> {
-    vec![
-        // --- View ---
-        CommandSpec {
-            name: "Toggle diff view",
-            description: "Toggle between unified and side-by-side diff",
-            id: CommandId::ToggleDiffView,
-            category: "View",
-            shortcut: Some("v"),
-            active: false,
-        },
-        CommandSpec {
-            name: "Toggle line wrap",
-            description: "Toggle line wrapping in diffs",
-            id: CommandId::ToggleDiffWrap,
-            category: "View",
-            shortcut: Some("w"),
-            active: false,
-        },
-        CommandSpec {
-            name: "Toggle sidebar",
-            description: "Show or hide the file sidebar",
-            id: CommandId::ToggleSidebar,
-            category: "View",
-            shortcut: Some("s"),
-            active: false,
-        },
-        CommandSpec {
-            name: "Select theme",
-            description: "Choose a theme from the list",
-            id: CommandId::SelectTheme,
-            category: "View",
-            shortcut: None,
-            active: false,
-        },
-        // --- Session ---
-        CommandSpec {
-            name: "Open in editor",
-            description: "Open the current file in an external editor",
-            id: CommandId::OpenFileInEditor,
-            category: "Session",
-            shortcut: Some("o"),
+pub fn get_commands(read_only: bool) -> Vec<CommandSpec> {
+    COMMANDS
+        .iter()
+        .filter(|(id, ..)| !read_only || !command_id_to_message(*id).is_mutating())
+        .map(|&(id, name, description, category, shortcut)| CommandSpec {
+            name: tr(name),
+            description: tr(description),
+            id,
+            category: tr(category),
+            shortcut,
             active: false,
-        },
-        CommandSpec {
-            name: "Quit",
-            description: "Quit the application",
-            id: CommandId::Quit,
-            category: "Session",
-            shortcut: Some("q"),
-            active: false,
-        },
-    ]
+        })
+        .collect()
 }
 
 #[must_use]
@@ -85,8 +415,68 @@ pub const fn command_id_to_message(id: CommandId) -> Message {
         CommandId::Quit => Message::Quit,
         CommandId::SelectTheme => Message::ShowThemePicker,
         CommandId::ToggleDiffView => Message::ToggleDiffView,
+        CommandId::ToggleFileDiffView => Message::ToggleFileDiffView,
         CommandId::ToggleDiffWrap => Message::ToggleDiffWrap,
+        CommandId::ToggleWordDiff => Message::ToggleWordDiff,
+        CommandId::ToggleFileHighlighting => Message::ToggleFileHighlighting,
+        CommandId::FoldHunk => Message::ToggleHunkFold,
+        CommandId::FoldFile => Message::ToggleFileFold,
+        CommandId::ExpandUp => Message::ExpandContextUp,
+        CommandId::ExpandDown => Message::ExpandContextDown,
+        CommandId::ToggleFileViewed => Message::ToggleFileViewed,
+        CommandId::ToggleCrMarkers => Message::ToggleCrMarkers,
+        CommandId::ToggleBlame => Message::ToggleBlame,
+        CommandId::CycleDensity => Message::CycleDensity,
         CommandId::ToggleSidebar => Message::ToggleSidebar,
         CommandId::OpenFileInEditor => Message::OpenFileInEditor,
+        CommandId::CopyCommentId => Message::CopyCommentId,
+        CommandId::CopyThreadLink => Message::CopyThreadLink,
+        CommandId::CopyCheckoutCommand => Message::CopyCheckoutCommand,
+        CommandId::CopyFilePath => Message::CopyFilePath,
+        CommandId::CopyLineReference => Message::CopyLineReference,
+        CommandId::ExportReview => Message::ExportReview,
+        CommandId::RunReviewCommand => Message::RunReviewCommand,
+        CommandId::ShowInputLog => Message::ShowInputLog,
+        CommandId::JumpToComment => Message::ShowCommentPicker,
+        CommandId::ToggleMuteThread => Message::ToggleMuteThread,
+        CommandId::ShowThreadsPanel => Message::ToggleThreadsPanel,
+        CommandId::ApplySuggestion => Message::ApplySuggestion,
+        CommandId::UndoSuggestion => Message::UndoSuggestion,
+        CommandId::CommentOnReview => Message::StartReviewComment,
+        CommandId::FinishReview => Message::FinishReview,
+        CommandId::AbandonReview => Message::StartAbandonReview,
+        CommandId::RestoreReview => Message::RestoreReview,
+        CommandId::Undo => Message::Undo,
+        CommandId::Redo => Message::Redo,
+        CommandId::OpenInbox => Message::ShowInbox,
+        CommandId::ToggleReviewGrouping => Message::ToggleReviewGrouping,
+        CommandId::GoToLine | CommandId::SetContextRadius | CommandId::SortReviews => {
+            Message::PromptCommandArgument(id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_argument_message_validates_input() {
+        assert!(matches!(
+            argument_message(CommandId::GoToLine, " 42 "),
+            Some(Message::GoToLine(42))
+        ));
+        assert!(argument_message(CommandId::GoToLine, "0").is_none());
+        assert!(argument_message(CommandId::GoToLine, "x").is_none());
+        assert!(matches!(
+            argument_message(CommandId::SetContextRadius, "0"),
+            Some(Message::SetContextLines(0))
+        ));
+        assert!(argument_message(CommandId::SetContextRadius, "51").is_none());
+        assert!(matches!(
+            argument_message(CommandId::SortReviews, "Author"),
+            Some(Message::SetReviewSort(ReviewSort::Author))
+        ));
+        assert!(argument_message(CommandId::SortReviews, "size").is_none());
     }
 }
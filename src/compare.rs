@@ -0,0 +1,335 @@
+//! Comparing two reviews: competing implementations of one change, or a fix
+//! and its backport.
+//!
+//! Reviews are marked with Space in the review list and compared with `c`.
+//! The screen lists every file either review touches, which side has it and
+//! whether the changes match. Enter on a file both touch shows a diff of the
+//! two diffs: hunk lines compared with their `+`/`-`/` ` prefix but without
+//! hunk headers, so the same change at other line numbers still matches.
+
+use crate::db::{FileData, ReviewData};
+use crate::diff::{DiffLineKind, ParsedDiff};
+
+/// Patches longer than this (in lines, per side) aren't compared line by
+/// line; every line is shown as differing instead.
+const MAX_DELTA_LINES: usize = 2000;
+
+/// Which of the two reviews touch a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Presence {
+    Both,
+    LeftOnly,
+    RightOnly,
+}
+
+/// One file in a comparison.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComparedFile {
+    pub path: String,
+    pub presence: Presence,
+    /// Lines added and removed on each side
+    pub left: (usize, usize),
+    pub right: (usize, usize),
+    /// Both reviews make the same change (ignoring line numbers)
+    pub same: bool,
+}
+
+/// Where a line of a diff-of-diffs comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaKind {
+    Both,
+    Left,
+    Right,
+}
+
+/// A hunk line, with its prefix, in a diff-of-diffs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeltaLine {
+    pub kind: DeltaKind,
+    pub text: String,
+}
+
+/// Comparison screen state. The two reviews load one per main-loop
+/// iteration.
+#[derive(Debug, Clone, Default)]
+pub struct Comparison {
+    pub left_id: String,
+    pub right_id: String,
+    pub left: Option<ReviewData>,
+    pub right: Option<ReviewData>,
+    /// Why a review couldn't be loaded
+    pub error: Option<String>,
+    pub files: Vec<ComparedFile>,
+    pub selection: usize,
+    pub scroll: usize,
+    /// Diff-of-diffs of the file at `selection`, while it's open
+    pub delta: Option<Vec<DeltaLine>>,
+    pub delta_scroll: usize,
+}
+
+impl Comparison {
+    #[must_use]
+    pub fn new(left_id: &str, right_id: &str) -> Self {
+        Self {
+            left_id: left_id.to_string(),
+            right_id: right_id.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// The review to load next, if any.
+    #[must_use]
+    pub fn next_to_load(&self) -> Option<&str> {
+        if self.error.is_some() {
+            None
+        } else if self.left.is_none() {
+            Some(&self.left_id)
+        } else if self.right.is_none() {
+            Some(&self.right_id)
+        } else {
+            None
+        }
+    }
+
+    /// Store a loaded review; once both are in, compare their files.
+    pub fn loaded(&mut self, data: ReviewData) {
+        if self.left.is_none() {
+            self.left = Some(data);
+        } else {
+            self.right = Some(data);
+        }
+        if let (Some(left), Some(right)) = (&self.left, &self.right) {
+            self.files = compare_files(&left.files, &right.files);
+        }
+    }
+
+    #[must_use]
+    pub fn selected(&self) -> Option<&ComparedFile> {
+        self.files.get(self.selection)
+    }
+
+    /// Open the diff-of-diffs of the selected file. Only files both reviews
+    /// touch have one.
+    pub fn open_selected(&mut self) {
+        let Some(file) = self.selected().filter(|f| f.presence == Presence::Both) else {
+            return;
+        };
+        let diff_of = |data: &Option<ReviewData>| {
+            data.as_ref()
+                .and_then(|d| d.files.iter().find(|f| f.path == file.path))
+                .and_then(|f| f.diff.clone())
+                .unwrap_or_default()
+        };
+        self.delta = Some(diff_of_diffs(&diff_of(&self.left), &diff_of(&self.right)));
+        self.delta_scroll = 0;
+    }
+
+    /// First file shown in a list `visible` rows tall, keeping the selection
+    /// in view.
+    #[must_use]
+    pub const fn first_visible(&self, visible: usize) -> usize {
+        if self.selection < self.scroll {
+            self.selection
+        } else if self.selection >= self.scroll + visible {
+            self.selection + 1 - visible
+        } else {
+            self.scroll
+        }
+    }
+}
+
+/// Every file either side touches, sorted by path.
+#[must_use]
+pub fn compare_files(left: &[FileData], right: &[FileData]) -> Vec<ComparedFile> {
+    let mut paths: Vec<&str> = left.iter().chain(right).map(|f| f.path.as_str()).collect();
+    paths.sort_unstable();
+    paths.dedup();
+
+    let find = |files: &[FileData], path: &str| {
+        files
+            .iter()
+            .find(|f| f.path == path)
+            .map(|f| hunk_lines(f.diff.as_deref().unwrap_or_default()))
+    };
+    paths
+        .into_iter()
+        .map(|path| {
+            let (l, r) = (find(left, path), find(right, path));
+            let presence = match (&l, &r) {
+                (Some(_), None) => Presence::LeftOnly,
+                (None, Some(_)) => Presence::RightOnly,
+                _ => Presence::Both,
+            };
+            let counts =
+                |lines: &Option<Vec<String>>| lines.as_deref().map_or((0, 0), change_counts);
+            ComparedFile {
+                path: path.to_string(),
+                presence,
+                left: counts(&l),
+                right: counts(&r),
+                same: presence == Presence::Both && l == r,
+            }
+        })
+        .collect()
+}
+
+/// Line diff of two patches' hunk lines.
+#[must_use]
+pub fn diff_of_diffs(left: &str, right: &str) -> Vec<DeltaLine> {
+    let left = hunk_lines(left);
+    let right = hunk_lines(right);
+    let line = |kind, text: &String| DeltaLine {
+        kind,
+        text: text.clone(),
+    };
+
+    // Common ends first, so the table only covers the middle
+    let prefix = left.iter().zip(&right).take_while(|(a, b)| a == b).count();
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &left[prefix..left.len() - suffix];
+    let b = &right[prefix..right.len() - suffix];
+
+    let mut out: Vec<DeltaLine> = left[..prefix]
+        .iter()
+        .map(|text| line(DeltaKind::Both, text))
+        .collect();
+    if a.len() > MAX_DELTA_LINES || b.len() > MAX_DELTA_LINES {
+        out.extend(a.iter().map(|text| line(DeltaKind::Left, text)));
+        out.extend(b.iter().map(|text| line(DeltaKind::Right, text)));
+    } else {
+        out.extend(lcs_diff(a, b));
+    }
+    out.extend(
+        left[left.len() - suffix..]
+            .iter()
+            .map(|text| line(DeltaKind::Both, text)),
+    );
+    out
+}
+
+/// Longest-common-subsequence walk; removals come before additions within a
+/// changed run, like a unified diff.
+fn lcs_diff(a: &[String], b: &[String]) -> Vec<DeltaLine> {
+    let width = b.len() + 1;
+    // lengths[i * width + j]: LCS of a[i..] and b[j..]
+    let mut lengths = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i * width + j] = if a[i] == b[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        let (kind, text) = if i < a.len() && j < b.len() && a[i] == b[j] {
+            i += 1;
+            j += 1;
+            (DeltaKind::Both, &a[i - 1])
+        } else if j == b.len()
+            || (i < a.len() && lengths[(i + 1) * width + j] >= lengths[i * width + j + 1])
+        {
+            i += 1;
+            (DeltaKind::Left, &a[i - 1])
+        } else {
+            j += 1;
+            (DeltaKind::Right, &b[j - 1])
+        };
+        out.push(DeltaLine {
+            kind,
+            text: text.clone(),
+        });
+    }
+    out
+}
+
+/// A patch's hunk lines with their prefix, without headers.
+fn hunk_lines(diff: &str) -> Vec<String> {
+    ParsedDiff::parse(diff)
+        .hunks
+        .iter()
+        .flat_map(|hunk| &hunk.lines)
+        .map(|line| {
+            let prefix = match line.kind {
+                DiffLineKind::Added => '+',
+                DiffLineKind::Removed => '-',
+                DiffLineKind::Context => ' ',
+            };
+            format!("{prefix}{}", line.content)
+        })
+        .collect()
+}
+
+fn change_counts(lines: &[String]) -> (usize, usize) {
+    let count = |prefix| lines.iter().filter(|l| l.starts_with(prefix)).count();
+    (count('+'), count('-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, diff: &str) -> FileData {
+        FileData {
+            path: path.to_string(),
+            diff: Some(diff.to_string()),
+            content: None,
+        }
+    }
+
+    const FIX: &str = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -10,3 +10,3 @@\n fn main() {\n-    old();\n+    new();\n }\n";
+    const BACKPORT: &str = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -40,3 +40,3 @@\n fn main() {\n-    old();\n+    new();\n }\n";
+    const OTHER: &str = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -10,3 +10,3 @@\n fn main() {\n-    old();\n+    other();\n }\n";
+
+    #[test]
+    fn test_compare_files_marks_presence_and_sameness() {
+        let left = [file("src/lib.rs", FIX), file("src/a.rs", FIX)];
+        let right = [file("src/lib.rs", BACKPORT), file("src/b.rs", OTHER)];
+        let files = compare_files(&left, &right);
+        let summary: Vec<_> = files
+            .iter()
+            .map(|f| (f.path.as_str(), f.presence, f.same))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("src/a.rs", Presence::LeftOnly, false),
+                ("src/b.rs", Presence::RightOnly, false),
+                ("src/lib.rs", Presence::Both, true),
+            ]
+        );
+        assert_eq!(files[0].left, (1, 1));
+        assert_eq!(files[0].right, (0, 0));
+    }
+
+    #[test]
+    fn test_diff_of_diffs_ignores_line_numbers() {
+        assert!(diff_of_diffs(FIX, BACKPORT)
+            .iter()
+            .all(|l| l.kind == DeltaKind::Both));
+
+        let delta = diff_of_diffs(FIX, OTHER);
+        let changed: Vec<_> = delta
+            .iter()
+            .filter(|l| l.kind != DeltaKind::Both)
+            .map(|l| (l.kind, l.text.as_str()))
+            .collect();
+        assert_eq!(
+            changed,
+            [
+                (DeltaKind::Left, "+    new();"),
+                (DeltaKind::Right, "+    other();")
+            ]
+        );
+        assert_eq!(delta.len(), 5);
+    }
+}
@@ -0,0 +1,437 @@
+//! Shell completion scripts.
+//!
+//! The bash/zsh/fish scripts are generated from `cli::COMMANDS`, so every
+//! command and flag the parser accepts is completed. Review, file and thread IDs
+//! are completed dynamically by calling back into `crit-ui __complete <kind>`
+//! with the words typed so far, so they reflect the same repo (`--path`,
+//! `.crit/` auto-detection or demo data) the TUI would open.
+
+use std::fmt::Write as _;
+
+use crate::cli::{CommandKind, CommandSpec, Flag, FlagValue, BIN, COMMANDS};
+use crate::demo_client::DemoSize;
+use crate::theme::built_in_theme_names;
+
+/// Hidden subcommand the scripts call for dynamic values.
+pub const COMPLETE_HELPER: &str = "__complete";
+
+/// Values listed by `crit-ui __complete <kind>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynamicKind {
+    Reviews,
+    Files,
+    Threads,
+}
+
+impl DynamicKind {
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "reviews" => Some(Self::Reviews),
+            "files" => Some(Self::Files),
+            "threads" => Some(Self::Threads),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Reviews => "reviews",
+            Self::Files => "files",
+            Self::Threads => "threads",
+        }
+    }
+}
+
+/// Shells we can generate completions for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub const NAMES: [&'static str; 3] = ["bash", "zsh", "fish"];
+
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            _ => None,
+        }
+    }
+}
+
+/// Completion script for `shell`.
+#[must_use]
+pub fn script(shell: Shell) -> String {
+    match shell {
+        Shell::Bash => bash_script(),
+        Shell::Zsh => zsh_script(),
+        Shell::Fish => fish_script(),
+    }
+}
+
+/// `__complete` kind for values looked up from crit.
+const fn dynamic_kind(value: FlagValue) -> Option<DynamicKind> {
+    match value {
+        FlagValue::Review => Some(DynamicKind::Reviews),
+        FlagValue::File => Some(DynamicKind::Files),
+        FlagValue::Thread => Some(DynamicKind::Threads),
+        _ => None,
+    }
+}
+
+fn static_values(value: FlagValue) -> Vec<&'static str> {
+    match value {
+        FlagValue::Theme => built_in_theme_names(),
+        FlagValue::DemoSize => DemoSize::NAMES.to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether a command's positional argument is a file on disk.
+const fn positional_is_file(command: &CommandSpec) -> bool {
+    matches!(
+        command.kind,
+        CommandKind::OpenArchive | CommandKind::ImportPrefs
+    )
+}
+
+/// Values for a command's positional argument.
+fn positional_values(command: &CommandSpec) -> Vec<&'static str> {
+    match command.kind {
+        CommandKind::Completions => Shell::NAMES.to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+/// Flags taking a value, once each across all commands.
+fn value_flags() -> Vec<(&'static Flag, FlagValue)> {
+    let mut flags: Vec<(&'static Flag, FlagValue)> = Vec::new();
+    for flag in COMMANDS.iter().flat_map(|command| command.flags) {
+        if let Some(value) = flag.value {
+            if !flags.iter().any(|(seen, _)| seen.long == flag.long) {
+                flags.push((flag, value));
+            }
+        }
+    }
+    flags
+}
+
+fn command_names() -> Vec<&'static str> {
+    COMMANDS.iter().map(|command| command.name).collect()
+}
+
+fn bash_script() -> String {
+    let func = "_crit_ui";
+    let mut out = String::new();
+    let _ = writeln!(out, "# bash completion for {BIN}");
+    let _ = writeln!(out, "{func}() {{");
+    let _ = writeln!(out, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"");
+    let _ = writeln!(out, "    local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"");
+    let _ = writeln!(out, "    local cmd=view");
+    let _ = writeln!(out, "    case \"${{COMP_WORDS[1]}}\" in");
+    let _ = writeln!(
+        out,
+        "        {}) (( COMP_CWORD > 1 )) && cmd=\"${{COMP_WORDS[1]}}\" ;;",
+        command_names().join("|")
+    );
+    let _ = writeln!(out, "    esac");
+    for command in COMMANDS.iter().filter(|c| c.positional.is_some()) {
+        let _ = writeln!(
+            out,
+            "    if [[ $cmd == {} && $cur != -* ]]; then",
+            command.name
+        );
+        if positional_is_file(command) {
+            let _ = writeln!(out, "        COMPREPLY=($(compgen -f -- \"$cur\"))");
+        } else {
+            let _ = writeln!(
+                out,
+                "        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                positional_values(command).join(" ")
+            );
+        }
+        let _ = writeln!(out, "        return");
+        let _ = writeln!(out, "    fi");
+    }
+    let _ = writeln!(out, "    case \"$prev\" in");
+    for (flag, value) in value_flags() {
+        let _ = write!(out, "        --{})\n            ", flag.long);
+        if let Some(kind) = dynamic_kind(value) {
+            let _ = writeln!(out, "local IFS=$'\\n'");
+            let _ = writeln!(
+                out,
+                "            COMPREPLY=($(compgen -W \"$({BIN} {COMPLETE_HELPER} {} \
+                 \"${{COMP_WORDS[@]:1:COMP_CWORD-2}}\" 2>/dev/null | cut -f1)\" -- \"$cur\"))",
+                kind.name()
+            );
+        } else {
+            match value {
+                FlagValue::Directory => {
+                    let _ = writeln!(out, "COMPREPLY=($(compgen -d -- \"$cur\"))");
+                }
+                FlagValue::OutputFile | FlagValue::InputFile => {
+                    let _ = writeln!(out, "COMPREPLY=($(compgen -f -- \"$cur\"))");
+                }
+                _ => {
+                    let _ = writeln!(
+                        out,
+                        "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                        static_values(value).join(" ")
+                    );
+                }
+            }
+        }
+        let _ = writeln!(out, "            return ;;");
+    }
+    let _ = writeln!(out, "    esac");
+    let _ = writeln!(out, "    local words");
+    let _ = writeln!(out, "    case \"$cmd\" in");
+    for command in COMMANDS {
+        let flags: Vec<String> = command
+            .flags
+            .iter()
+            .map(|flag| format!("--{}", flag.long))
+            .collect();
+        let _ = writeln!(
+            out,
+            "        {}) words=\"{}\" ;;",
+            command.name,
+            flags.join(" ")
+        );
+    }
+    let _ = writeln!(out, "    esac");
+    let _ = writeln!(
+        out,
+        "    (( COMP_CWORD == 1 )) && words=\"$words {}\"",
+        command_names().join(" ")
+    );
+    let _ = writeln!(out, "    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))");
+    let _ = writeln!(out, "}}");
+    let _ = writeln!(out, "complete -F {func} {BIN}");
+    out
+}
+
+/// Escape text for use inside a zsh `_arguments` spec in single quotes.
+fn zsh_escape(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+fn zsh_action(value: FlagValue) -> String {
+    if let Some(kind) = dynamic_kind(value) {
+        return format!("_crit_ui_dynamic {}", kind.name());
+    }
+    match value {
+        FlagValue::Directory => "_directories".to_string(),
+        FlagValue::OutputFile | FlagValue::InputFile => "_files".to_string(),
+        FlagValue::Number => " ".to_string(),
+        _ => format!("({})", static_values(value).join(" ")),
+    }
+}
+
+/// One `_arguments` spec per flag.
+fn zsh_flag_spec(flag: &Flag) -> String {
+    let help = zsh_escape(flag.help);
+    let names = flag.short.map_or_else(
+        || format!("--{}", flag.long),
+        |short| {
+            format!(
+                "(-{short} --{long})'{{-{short},--{long}}}'",
+                long = flag.long
+            )
+        },
+    );
+    let action = flag.value.map_or_else(String::new, |value| {
+        format!(
+            ":{}:{}",
+            value.placeholder().trim_matches(['<', '>']),
+            zsh_action(value)
+        )
+    });
+    format!("'{names}[{help}]{action}'")
+}
+
+fn zsh_script() -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "#compdef {BIN}");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "_crit_ui_dynamic() {{");
+    let _ = writeln!(out, "    local line");
+    let _ = writeln!(out, "    local -a items");
+    let _ = writeln!(
+        out,
+        "    for line in ${{(f)\"$({BIN} {COMPLETE_HELPER} $1 $crit_words 2>/dev/null)\"}}; do"
+    );
+    let _ = writeln!(
+        out,
+        "        items+=(\"${{${{line%%$'\\t'*}}//:/\\\\:}}:${{line#*$'\\t'}}\")"
+    );
+    let _ = writeln!(out, "    done");
+    let _ = writeln!(out, "    _describe -t $1 $1 items");
+    let _ = writeln!(out, "}}");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "_crit_ui_commands() {{");
+    let _ = writeln!(out, "    local -a commands=(");
+    for command in COMMANDS {
+        let _ = writeln!(
+            out,
+            "        '{}:{}'",
+            command.name,
+            zsh_escape(command.about)
+        );
+    }
+    let _ = writeln!(out, "    )");
+    let _ = writeln!(out, "    _describe -t commands command commands");
+    let _ = writeln!(out, "}}");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "_crit_ui() {{");
+    let _ = writeln!(
+        out,
+        "    # Words before the one being completed, for __complete"
+    );
+    let _ = writeln!(out, "    local -a crit_words=(${{words[2,CURRENT-2]}})");
+    let _ = writeln!(out, "    local cmd=view");
+    let _ = writeln!(out, "    local -a first=('1: :_crit_ui_commands')");
+    let _ = writeln!(out, "    if (( CURRENT > 2 )); then");
+    let _ = writeln!(out, "        case ${{words[2]}} in");
+    let _ = writeln!(
+        out,
+        "            {}) cmd=${{words[2]}}; first=(); shift words; (( CURRENT-- )) ;;",
+        command_names().join("|")
+    );
+    let _ = writeln!(out, "        esac");
+    let _ = writeln!(out, "    fi");
+    let _ = writeln!(out, "    case $cmd in");
+    for command in COMMANDS {
+        let _ = writeln!(out, "        {})", command.name);
+        let first = if command.kind == CommandKind::View {
+            " $first"
+        } else {
+            ""
+        };
+        let _ = write!(out, "            _arguments{first}");
+        for flag in command.flags {
+            let _ = write!(out, " \\\n                {}", zsh_flag_spec(flag));
+        }
+        if let Some(name) = command.positional {
+            let action = if positional_is_file(command) {
+                "_files".to_string()
+            } else {
+                format!("({})", positional_values(command).join(" "))
+            };
+            let _ = write!(out, " \\\n                '1:{name}:{action}'");
+        }
+        let _ = writeln!(out, " ;;");
+    }
+    let _ = writeln!(out, "    esac");
+    let _ = writeln!(out, "}}");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "_crit_ui \"$@\"");
+    out
+}
+
+/// Escape text for use inside single quotes in fish.
+fn fish_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn fish_script() -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# fish completion for {BIN}");
+    let _ = writeln!(out, "function __crit_ui_dynamic");
+    let _ = writeln!(out, "    set -l words (commandline -opc)");
+    let _ = writeln!(
+        out,
+        "    {BIN} {COMPLETE_HELPER} $argv[1] $words[2..-2] 2>/dev/null"
+    );
+    let _ = writeln!(out, "end");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "complete -c {BIN} -f");
+    for command in COMMANDS {
+        let _ = writeln!(
+            out,
+            "complete -c {BIN} -n __fish_use_subcommand -a {} -d '{}'",
+            command.name,
+            fish_escape(command.about)
+        );
+    }
+    let others: Vec<&str> = COMMANDS
+        .iter()
+        .filter(|c| c.kind != CommandKind::View)
+        .map(|c| c.name)
+        .collect();
+    for command in COMMANDS {
+        // Bare `crit-ui` is `view`
+        let condition = if command.kind == CommandKind::View {
+            format!("not __fish_seen_subcommand_from {}", others.join(" "))
+        } else {
+            format!("__fish_seen_subcommand_from {}", command.name)
+        };
+        if positional_is_file(command) {
+            let _ = writeln!(out, "complete -c {BIN} -n '{condition}' -F");
+        } else if command.positional.is_some() {
+            let _ = writeln!(
+                out,
+                "complete -c {BIN} -n '{condition}' -a '{}'",
+                positional_values(command).join(" ")
+            );
+        }
+        for flag in command.flags {
+            let mut line = format!("complete -c {BIN} -n '{condition}' -l {}", flag.long);
+            if let Some(short) = flag.short {
+                let _ = write!(line, " -s {short}");
+            }
+            if let Some(value) = flag.value {
+                if let Some(kind) = dynamic_kind(value) {
+                    let _ = write!(line, " -x -a '(__crit_ui_dynamic {})'", kind.name());
+                } else {
+                    match value {
+                        FlagValue::Directory => {
+                            line.push_str(" -x -a '(__fish_complete_directories)'");
+                        }
+                        FlagValue::OutputFile | FlagValue::InputFile => line.push_str(" -r -F"),
+                        FlagValue::Number => line.push_str(" -x"),
+                        _ => {
+                            let _ = write!(line, " -x -a '{}'", static_values(value).join(" "));
+                        }
+                    }
+                }
+            }
+            let _ = writeln!(out, "{line} -d '{}'", fish_escape(flag.help));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_cover_every_flag() {
+        for shell in Shell::NAMES.map(|name| Shell::parse(name).unwrap()) {
+            let script = script(shell);
+            for command in COMMANDS {
+                assert!(script.contains(command.name), "{shell:?}");
+                for flag in command.flags {
+                    assert!(
+                        script.contains(&format!("--{}", flag.long))
+                            || script.contains(&format!("-l {}", flag.long)),
+                        "{shell:?} missing --{}",
+                        flag.long
+                    );
+                }
+            }
+            assert!(script.contains(COMPLETE_HELPER), "{shell:?}");
+            assert!(script.contains("reviews"), "{shell:?}");
+        }
+    }
+}
@@ -1,54 +1,412 @@
 //! User configuration handling
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 
+use crate::keymap::KeyChords;
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UiConfig {
     pub theme: Option<String>,
     pub default_diff_view: Option<String>,
+    /// UI language tag, e.g. `en` or `es`
+    pub locale: Option<String>,
+    /// Disable commenting and status changes
+    pub read_only: Option<bool>,
+    /// Re-fetch the review list / open review every this many seconds;
+    /// unset or 0 disables auto-refresh
+    pub auto_refresh_secs: Option<u64>,
+    /// Seconds a saved comment waits before it's sent, while `u` can still
+    /// take it back; 3 by default, 0 sends at once
+    pub comment_undo_secs: Option<u64>,
+    /// Marker glyphs: `unicode` (default), `ascii` or `nerd-font`
+    pub glyphs: Option<String>,
+    /// Spacing around blocks: `compact`, `normal` (default) or `relaxed`
+    pub density: Option<String>,
+    /// Widest comment and description text, in columns; full pane width by
+    /// default. The diff keeps the full width.
+    pub comment_max_width: Option<u16>,
+    /// Where blocks narrowed by `comment_max_width` sit: `left` (default)
+    /// or `center`
+    pub comment_align: Option<String>,
+    /// Syntax highlighting; on by default, `--no-syntax` turns it off for a
+    /// session
+    pub syntax: Option<bool>,
+    /// Reload when crit's database or the working copy changes on disk; on
+    /// by default (needs `--path` or a detected `.crit/`)
+    pub watch: Option<bool>,
+    /// Dim the UI while the terminal window is unfocused
+    pub dim_unfocused: Option<bool>,
+    /// Tint the title bar of the focused pane (`chromeFocused` in the theme);
+    /// on by default
+    pub focus_tint: Option<bool>,
+    /// Sidebar width in columns, set by dragging its divider; by default it
+    /// follows the terminal width
+    pub sidebar_width: Option<u16>,
+    /// Group the file sidebar under collapsible directories
+    pub sidebar_tree: Option<bool>,
+    /// Review list order: `status` (default), `updated`, `created`,
+    /// `author`, `threads`, `files` or `lines`
+    pub review_sort: Option<String>,
+    /// Run the review list in the opposite order, set by clicking the
+    /// sorted column's title again
+    pub review_sort_reversed: Option<bool>,
+    /// Keep the review list's statuses together whatever it's sorted by
+    pub group_reviews_by_status: Option<bool>,
+    /// Lines of file content shown around threads outside the diff, set
+    /// from the palette; `layout::CONTEXT_LINES` by default
+    pub context_lines: Option<u16>,
+    /// Mouse wheel over the diff: `cursor` (default; moves the cursor) or
+    /// `viewport` (scrolls the view and leaves the cursor where it is)
+    pub wheel_scroll: Option<String>,
+    /// Diff cursor drawing: `auto` (default; lean while frames are slow to
+    /// write), `full` or `lean` (a gutter marker instead of a row highlight)
+    pub redraw: Option<String>,
+    /// Threads the user muted: rendered collapsed, skipped by `n`/`p` and
+    /// left out of open-thread counts
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub muted_threads: BTreeSet<String>,
+    /// CODEOWNERS-format file mapping paths to owners; relative paths are
+    /// resolved against the repo. Defaults to the repo's own CODEOWNERS.
+    pub codeowners: Option<String>,
+    /// Owner names (users or `@org/team`s) that count as the user, besides
+    /// their crit name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owner_names: Vec<String>,
+    /// Screen shown at startup: `list` (default), `inbox`, `last-review` or
+    /// `filter:<name>` for an entry of `saved_filters`
+    pub startup: Option<String>,
+    /// Review opened most recently, for `startup: last-review`
+    pub last_review: Option<String>,
+    /// Named review list filters, usable as a startup screen
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub saved_filters: BTreeMap<String, SavedFilter>,
+    /// Shell command the palette's "Run command at review commit" runs in a
+    /// scratch checkout of the review, e.g. `cargo check`
+    pub review_command: Option<String>,
+    /// What `y` on a thread copies, with `{review}` and `{thread}` filled
+    /// in, e.g. `https://crit.example.com/{review}#{thread}`; the
+    /// `crit-ui --review … --thread …` command by default
+    pub thread_link: Option<String>,
+    /// Display of review statuses by name, e.g. a team's `in-review` or
+    /// `landed` (see `status::StatusWorkflow`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub statuses: BTreeMap<String, StatusStyle>,
+    /// Key bindings by action name, `context.action` for one context only
+    /// (see `keymap`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, KeyChords>,
+    /// Which layer set what, for saving back to the user's file
+    #[serde(skip)]
+    pub layers: ConfigLayers,
+}
+
+/// How a review status is shown; unset fields keep the built-in value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusStyle {
+    /// Text shown instead of the status name
+    pub label: Option<String>,
+    /// Theme token (`success`, `warning`, `error`, `muted`, `primary`,
+    /// `foreground`) or `#rrggbb`
+    pub color: Option<String>,
+    /// Review list position, lower first (default 0)
+    pub order: Option<i64>,
+    /// Counts as open for the Open filter and the inbox (default: only
+    /// `open` does)
+    pub open: Option<bool>,
 }
 
-/// Load UI configuration from the user's config directory.
+/// Review list status filter and search query saved under a name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedFilter {
+    /// `all`, `open`, `closed` or `owned`
+    pub status: Option<String>,
+    pub search: Option<String>,
+    /// Filter bar terms, e.g. `author:bob has:open-threads`
+    pub filter: Option<String>,
+}
+
+/// Where the TUI starts when no `--review` is given.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum StartupScreen {
+    #[default]
+    ReviewList,
+    Inbox,
+    /// The review opened most recently (`last_review`)
+    LastReview,
+    /// The review list with a saved filter applied
+    Filter(String),
+}
+
+impl StartupScreen {
+    /// Parse a `startup` value; unknown values fall back to the list.
+    #[must_use]
+    pub fn parse(value: &str) -> Self {
+        match value.trim() {
+            "inbox" => Self::Inbox,
+            "last-review" | "last" => Self::LastReview,
+            other => other
+                .strip_prefix("filter:")
+                .map_or(Self::ReviewList, |name| {
+                    Self::Filter(name.trim().to_string())
+                }),
+        }
+    }
+}
+
+impl UiConfig {
+    #[must_use]
+    pub fn startup_screen(&self) -> StartupScreen {
+        self.startup
+            .as_deref()
+            .map(StartupScreen::parse)
+            .unwrap_or_default()
+    }
+}
+
+/// A source of settings. Later layers take precedence, key by key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigLayer {
+    /// `.botcrit/ui.json` under each of `$XDG_CONFIG_DIRS` (`/etc/xdg`)
+    System,
+    /// `.botcrit/ui.json` under `$XDG_CONFIG_HOME` (`~/.config`); the UI
+    /// saves changes here
+    User,
+    /// `.botcrit/ui.json` in the repo
+    Repo,
+    /// `BOTCRIT_UI_<KEY>` variables, e.g. `BOTCRIT_UI_AUTO_REFRESH_SECS=30`
+    Env,
+}
+
+/// Prefix of the env layer's variables.
+const ENV_PREFIX: &str = "BOTCRIT_UI_";
+
+/// Keys a repo's config can't set: it would run a command the repo chose.
+const REPO_DENIED_KEYS: &[&str] = &["review_command"];
+
+/// Keys that are state on one machine rather than preferences; preference
+/// export and import leave them out.
+const LOCAL_KEYS: &[&str] = &["last_review", "muted_threads"];
+
+type Settings = serde_json::Map<String, serde_json::Value>;
+
+/// Top-level keys each layer set when the config was loaded. A layer's key
+/// replaces the whole value from the layers below it.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayers {
+    layers: Vec<(ConfigLayer, Settings)>,
+}
+
+impl ConfigLayers {
+    /// Value of `key` from the highest layer below `limit` that sets it.
+    fn value_below(&self, key: &str, limit: ConfigLayer) -> Option<&serde_json::Value> {
+        self.layers
+            .iter()
+            .filter(|(layer, _)| *layer < limit)
+            .rev()
+            .find_map(|(_, settings)| settings.get(key))
+    }
+
+    /// Value of `key` after every layer.
+    fn value(&self, key: &str) -> Option<&serde_json::Value> {
+        self.layers
+            .iter()
+            .rev()
+            .find_map(|(_, settings)| settings.get(key))
+    }
+
+    /// Whether a layer above the user's sets `key`.
+    fn shadows_user(&self, key: &str) -> bool {
+        self.layers
+            .iter()
+            .any(|(layer, settings)| *layer > ConfigLayer::User && settings.contains_key(key))
+    }
+}
+
+/// Load UI configuration from every layer: system, user, `repo` (when
+/// given) and environment, in rising precedence. Missing files are skipped.
 ///
 /// # Errors
 ///
-/// Returns an error if the config file exists but cannot be read or parsed.
-pub fn load_ui_config() -> anyhow::Result<Option<UiConfig>> {
-    let Some(path) = config_path() else {
-        return Ok(None);
-    };
-    if !path.exists() {
-        return Ok(None);
+/// Returns an error if a config file exists but cannot be read or parsed,
+/// or a `BOTCRIT_UI_*` variable doesn't fit its setting.
+pub fn load_ui_config(repo: Option<&Path>) -> anyhow::Result<UiConfig> {
+    let mut layers = ConfigLayers::default();
+    for path in system_config_paths() {
+        if let Some(settings) = read_settings(&path)? {
+            layers.layers.push((ConfigLayer::System, settings));
+        }
+    }
+    if let Some(settings) = user_config_path()
+        .map(|p| read_settings(&p))
+        .transpose()?
+        .flatten()
+    {
+        layers.layers.push((ConfigLayer::User, settings));
+    }
+    if let Some(repo) = repo {
+        if let Some(mut settings) = read_settings(&repo.join(".botcrit").join("ui.json"))? {
+            settings.retain(|key, _| !REPO_DENIED_KEYS.contains(&key.as_str()));
+            layers.layers.push((ConfigLayer::Repo, settings));
+        }
+    }
+    let env = env_settings(std::env::vars())?;
+    if !env.is_empty() {
+        layers.layers.push((ConfigLayer::Env, env));
     }
 
-    let contents = std::fs::read_to_string(&path)
-        .with_context(|| format!("Failed to read config: {}", path.display()))?;
-    let config = serde_json::from_str(&contents)
-        .with_context(|| format!("Failed to parse config: {}", path.display()))?;
-    Ok(Some(config))
+    let mut merged = Settings::new();
+    for (_, settings) in &layers.layers {
+        merged.extend(settings.clone());
+    }
+    let mut config: UiConfig =
+        serde_json::from_value(merged.into()).context("Failed to combine config layers")?;
+    config.layers = layers;
+    Ok(config)
 }
 
-/// Save UI configuration to the user's config directory.
+/// Save UI configuration to the user's config file. Settings another layer
+/// supplied are only written once changed here; a setting that ends up
+/// equal to the system layer's is dropped from the user file.
 ///
 /// # Errors
 ///
 /// Returns an error if the config directory cannot be created or the file cannot be written.
 pub fn save_ui_config(config: &UiConfig) -> anyhow::Result<()> {
-    let Some(path) = config_path() else {
+    let Some(path) = user_config_path() else {
         return Ok(());
     };
+    let user = read_settings(&path)?.unwrap_or_default();
+    let user = user_settings_after(&config.layers, user, &settings_of(config)?);
+    write_settings(&path, &user)
+}
+
+/// The user's file `user` updated to `current` settings, given the layers
+/// they were loaded from.
+fn user_settings_after(layers: &ConfigLayers, mut user: Settings, current: &Settings) -> Settings {
+    let keys: BTreeSet<&String> = current
+        .keys()
+        .chain(
+            layers
+                .layers
+                .iter()
+                .flat_map(|(_, settings)| settings.keys()),
+        )
+        .collect();
+    for key in keys {
+        let now = current.get(key);
+        let shadowed = layers.shadows_user(key);
+        let changed = if shadowed {
+            now != layers.value(key)
+        } else {
+            now != layers.value_below(key, ConfigLayer::User)
+        };
+        match now.filter(|_| changed) {
+            Some(value) => {
+                user.insert(key.clone(), value.clone());
+            }
+            None if !shadowed => {
+                user.remove(key);
+            }
+            None => {}
+        }
+    }
+    user
+}
+
+/// The user's settings without machine-local state (`last_review`,
+/// `muted_threads`), for `crit-ui export-prefs`.
+///
+/// # Errors
+///
+/// Returns an error if the user config exists but cannot be read or parsed.
+pub fn export_preferences() -> anyhow::Result<serde_json::Value> {
+    let mut settings = user_config_path()
+        .map(|path| read_settings(&path))
+        .transpose()?
+        .flatten()
+        .unwrap_or_default();
+    settings.retain(|key, _| !LOCAL_KEYS.contains(&key.as_str()));
+    Ok(settings.into())
+}
+
+/// Merge exported preferences (a JSON object) into the user's config file,
+/// each key replacing the user's own. Returns the file and how many
+/// settings were imported.
+///
+/// # Errors
+///
+/// Returns an error if `json` isn't a valid set of preferences or the user
+/// config cannot be read or written.
+pub fn import_preferences(json: &str) -> anyhow::Result<(PathBuf, usize)> {
+    let value: serde_json::Value = serde_json::from_str(json).context("Invalid JSON")?;
+    let mut imported = validated(value).context("Invalid preferences")?;
+    imported.retain(|key, _| !LOCAL_KEYS.contains(&key.as_str()));
+    let path = user_config_path().context("No config directory: set HOME or XDG_CONFIG_HOME")?;
+    let mut user = read_settings(&path)?.unwrap_or_default();
+    let count = imported.len();
+    user.extend(imported);
+    write_settings(&path, &user)?;
+    Ok((path, count))
+}
+
+/// Settings from `BOTCRIT_UI_<KEY>` variables. Values are read as JSON, or
+/// as a plain string when they aren't JSON; unknown keys are ignored.
+fn env_settings(vars: impl Iterator<Item = (String, String)>) -> anyhow::Result<Settings> {
+    let mut settings = Settings::new();
+    for (name, value) in vars {
+        let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let parsed = serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+        let setting = serde_json::json!({ key.to_ascii_lowercase(): parsed });
+        settings.extend(validated(setting).with_context(|| format!("Invalid {name}"))?);
+    }
+    Ok(settings)
+}
+
+/// `value` checked against `UiConfig`: keys it doesn't know and unset
+/// values are dropped.
+fn validated(value: serde_json::Value) -> anyhow::Result<Settings> {
+    let config: UiConfig = serde_json::from_value(value)?;
+    settings_of(&config)
+}
+
+/// The set keys of `config`.
+fn settings_of(config: &UiConfig) -> anyhow::Result<Settings> {
+    let serde_json::Value::Object(mut settings) = serde_json::to_value(config)? else {
+        anyhow::bail!("Config didn't serialize to an object");
+    };
+    settings.retain(|_, value| !value.is_null());
+    Ok(settings)
+}
+
+fn read_settings(path: &Path) -> anyhow::Result<Option<Settings>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config: {}", path.display()))?;
+    let value = serde_json::from_str(&contents)
+        .map_err(anyhow::Error::from)
+        .and_then(validated)
+        .with_context(|| format!("Failed to parse config: {}", path.display()))?;
+    Ok(Some(value))
+}
+
+fn write_settings(path: &Path, settings: &Settings) -> anyhow::Result<()> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)?;
     }
-    let contents = serde_json::to_string_pretty(config)?;
-    std::fs::write(&path, contents)?;
+    let contents = serde_json::to_string_pretty(settings)?;
+    std::fs::write(path, contents)?;
     Ok(())
 }
 
-fn config_path() -> Option<PathBuf> {
+fn user_config_path() -> Option<PathBuf> {
     let base = if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
         PathBuf::from(xdg)
     } else if let Ok(home) = std::env::var("HOME") {
@@ -59,3 +417,97 @@ fn config_path() -> Option<PathBuf> {
 
     Some(base.join(".botcrit").join("ui.json"))
 }
+
+/// System config files, lowest precedence first: `$XDG_CONFIG_DIRS` lists
+/// the most important directory first.
+fn system_config_paths() -> Vec<PathBuf> {
+    let dirs = std::env::var("XDG_CONFIG_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/etc/xdg".to_string());
+    dirs.split(':')
+        .rev()
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| Path::new(dir).join(".botcrit").join("ui.json"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_startup_screen_parse() {
+        assert_eq!(StartupScreen::parse("inbox"), StartupScreen::Inbox);
+        assert_eq!(
+            StartupScreen::parse("last-review"),
+            StartupScreen::LastReview
+        );
+        assert_eq!(
+            StartupScreen::parse("filter: mine"),
+            StartupScreen::Filter("mine".to_string())
+        );
+        assert_eq!(StartupScreen::parse("bogus"), StartupScreen::ReviewList);
+        assert_eq!(
+            UiConfig::default().startup_screen(),
+            StartupScreen::ReviewList
+        );
+    }
+
+    fn settings(value: serde_json::Value) -> Settings {
+        let serde_json::Value::Object(settings) = value else {
+            panic!("not an object");
+        };
+        settings
+    }
+
+    #[test]
+    fn test_env_settings_parse_json_or_strings() {
+        let vars = [
+            ("BOTCRIT_UI_THEME", "nord"),
+            ("BOTCRIT_UI_AUTO_REFRESH_SECS", "30"),
+            ("BOTCRIT_UI_LANG", "es"),
+            ("HOME", "/home/me"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+        assert_eq!(
+            env_settings(vars.into_iter()).unwrap(),
+            settings(serde_json::json!({"theme": "nord", "auto_refresh_secs": 30}))
+        );
+        let bad = [("BOTCRIT_UI_READ_ONLY".to_string(), "maybe".to_string())];
+        assert!(env_settings(bad.into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_saving_writes_only_the_users_changes() {
+        let layers = ConfigLayers {
+            layers: vec![
+                (
+                    ConfigLayer::System,
+                    settings(serde_json::json!({"theme": "nord", "glyphs": "ascii"})),
+                ),
+                (
+                    ConfigLayer::User,
+                    settings(serde_json::json!({"theme": "dracula"})),
+                ),
+                (
+                    ConfigLayer::Env,
+                    settings(serde_json::json!({"auto_refresh_secs": 30})),
+                ),
+            ],
+        };
+        // The user switched theme and went read-only; the env and system
+        // values they kept stay out of their file
+        let current = settings(serde_json::json!({
+            "theme": "solarized",
+            "glyphs": "ascii",
+            "auto_refresh_secs": 30,
+            "read_only": true,
+        }));
+        let user = layers.layers[1].1.clone();
+        assert_eq!(
+            user_settings_after(&layers, user, &current),
+            settings(serde_json::json!({"theme": "solarized", "read_only": true}))
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Remote control over a Unix socket (`--control-socket <path>`).
+//!
+//! Clients write one JSON [`Message`] per line — `"ScrollDown"`,
+//! `{"SelectFile": 2}`, `{"SearchInput": "parser"}` — and read one JSON line
+//! back for each: `{"ok": true, "state": {...}}` once the message has been
+//! applied, or `{"ok": false, "error": "..."}`. `"Noop"` just reads the
+//! state. Work `main` does between frames (loading a review, saving a
+//! comment) lands later, so poll with `"Noop"` to see it.
+
+use std::fs::{self, DirBuilder, Permissions};
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::message::Message;
+use crate::model::{Focus, Model, Screen};
+
+/// Longest request line accepted; a client sending more is dropped.
+const MAX_LINE: usize = 64 * 1024;
+
+/// What a client sees after each message.
+#[derive(Debug, Clone, Serialize)]
+pub struct ControlState {
+    pub screen: Screen,
+    pub focus: Focus,
+    pub width: u16,
+    pub height: u16,
+    pub read_only: bool,
+    /// Selected row of the review list
+    pub list_index: usize,
+    pub review_id: Option<String>,
+    pub review_title: Option<String>,
+    /// Path of the selected file in the open review
+    pub file: Option<String>,
+    pub file_index: usize,
+    pub diff_cursor: usize,
+    pub diff_scroll: usize,
+    pub expanded_thread: Option<String>,
+    pub threads: usize,
+    pub open_threads: usize,
+    pub flash: Option<String>,
+    /// The UI is about to exit
+    pub quitting: bool,
+}
+
+impl ControlState {
+    #[must_use]
+    pub fn of(model: &Model) -> Self {
+        let review = model.current_review.as_ref();
+        Self {
+            screen: model.screen,
+            focus: model.focus,
+            width: model.width,
+            height: model.height,
+            read_only: model.read_only,
+            list_index: model.list_index,
+            review_id: review.map(|r| r.review_id.clone()),
+            review_title: review.map(|r| r.title.clone()),
+            file: model
+                .files_with_threads()
+                .get(model.file_index)
+                .map(|f| f.path.clone()),
+            file_index: model.file_index,
+            diff_cursor: model.diff_cursor,
+            diff_scroll: model.diff_scroll,
+            expanded_thread: model.expanded_thread.clone(),
+            threads: model.threads.len(),
+            open_threads: model.threads.iter().filter(|t| t.status == "open").count(),
+            flash: model.flash_message.clone(),
+            quitting: model.should_quit,
+        }
+    }
+}
+
+/// A connected client and its unfinished line.
+struct Client {
+    stream: UnixStream,
+    pending: Vec<u8>,
+}
+
+/// Non-blocking listener, polled once per frame.
+pub struct ControlServer {
+    path: PathBuf,
+    listener: UnixListener,
+    clients: Vec<Client>,
+}
+
+impl ControlServer {
+    /// Listen at `path`, replacing a socket a previous run left behind.
+    /// Only the owner can connect: the socket takes messages that change
+    /// state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` exists and isn't a socket, or binding
+    /// fails.
+    pub fn bind(path: &Path) -> Result<Self> {
+        if let Ok(meta) = std::fs::symlink_metadata(path) {
+            if !meta.file_type().is_socket() {
+                bail!("{} exists and is not a socket", path.display());
+            }
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove stale {}", path.display()))?;
+        }
+        // Bound in a private directory and moved into place, so it's never
+        // reachable before it's owner-only
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let staging = path.with_file_name(format!(".{name}.{}", std::process::id()));
+        DirBuilder::new()
+            .mode(0o700)
+            .create(&staging)
+            .with_context(|| format!("Failed to create {}", staging.display()))?;
+        let staged = staging.join("socket");
+        let bound = UnixListener::bind(&staged).and_then(|listener| {
+            fs::set_permissions(&staged, Permissions::from_mode(0o600))?;
+            fs::rename(&staged, path)?;
+            Ok(listener)
+        });
+        let _ = fs::remove_dir_all(&staging);
+        let listener = bound.with_context(|| format!("Failed to listen on {}", path.display()))?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            listener,
+            clients: Vec::new(),
+        })
+    }
+
+    /// Whether anyone is connected, so the UI keeps polling promptly.
+    #[must_use]
+    pub fn has_clients(&self) -> bool {
+        !self.clients.is_empty()
+    }
+
+    /// Accept new clients and run each complete request line through
+    /// `apply`, answering with the state it returns.
+    pub fn serve(&mut self, mut apply: impl FnMut(Message) -> ControlState) {
+        while let Ok((stream, _)) = self.listener.accept() {
+            if stream.set_nonblocking(true).is_ok() {
+                self.clients.push(Client {
+                    stream,
+                    pending: Vec::new(),
+                });
+            }
+        }
+        self.clients.retain_mut(|client| client.serve(&mut apply));
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl Client {
+    /// Handle whatever has arrived; `false` once the client is gone.
+    fn serve(&mut self, apply: &mut impl FnMut(Message) -> ControlState) -> bool {
+        let mut chunk = [0u8; 4096];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return false,
+                Ok(read) => self.pending.extend_from_slice(&chunk[..read]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(_) => return false,
+            }
+        }
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            if line.trim().is_empty() {
+                continue;
+            }
+            let reply = match parse_request(&line) {
+                Ok(msg) => json!({ "ok": true, "state": apply(msg) }),
+                Err(error) => json!({ "ok": false, "error": error }),
+            };
+            // A client that stops reading fills its socket buffer; rather
+            // than wait on it (and freeze the UI), drop it
+            if writeln!(self.stream, "{reply}").is_err() {
+                return false;
+            }
+        }
+        self.pending.len() <= MAX_LINE
+    }
+}
+
+/// One request line as a message.
+///
+/// # Errors
+///
+/// Returns serde's description of what didn't match.
+pub fn parse_request(line: &str) -> Result<Message, String> {
+    serde_json::from_str(line.trim()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::UiConfig;
+
+    #[test]
+    fn test_client_that_never_reads_is_dropped() {
+        let dir = std::env::temp_dir().join(format!("crit-ui-control-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("control.sock");
+        let mut server = ControlServer::bind(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let mut peer = UnixStream::connect(&path).unwrap();
+        let state = ControlState::of(&Model::new(80, 24, UiConfig::default()));
+        server.serve(|_| state.clone());
+        assert!(server.has_clients());
+        // Far more replies than a socket buffer holds, and none read
+        let requests = "\"Noop\"\n".repeat(20_000);
+        peer.set_nonblocking(true).unwrap();
+        let mut sent = 0;
+        for _ in 0..1000 {
+            if sent < requests.len() {
+                sent += peer.write(&requests.as_bytes()[sent..]).unwrap_or(0);
+            }
+            server.serve(|_| state.clone());
+            if !server.has_clients() {
+                break;
+            }
+        }
+        assert!(!server.has_clients());
+        drop(server);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_request_accepts_unit_and_data_messages() {
+        assert!(matches!(parse_request("\"Quit\"\n"), Ok(Message::Quit)));
+        assert!(matches!(
+            parse_request(r#"{"SelectFile": 3}"#),
+            Ok(Message::SelectFile(3))
+        ));
+        assert!(matches!(
+            parse_request(r#"{"SearchInput": "parser"}"#),
+            Ok(Message::SearchInput(query)) if query == "parser"
+        ));
+        assert!(parse_request("\"Bogus\"").is_err());
+        // Only the UI produces these
+        assert!(parse_request(r#"{"ReviewLoadFailed": "x"}"#).is_err());
+        assert!(parse_request(r#"{"Resize": {"width": 1, "height": 1}}"#).is_err());
+    }
+}
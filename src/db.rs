@@ -16,6 +16,22 @@ pub struct ReviewSummary {
     pub open_thread_count: i64,
     #[serde(default)]
     pub reviewers: Vec<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// Last status change, comment or verdict
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    /// Changed files and lines; older crit leaves these to the file scan
+    #[serde(default)]
+    pub diff_stats: Option<DiffStats>,
+}
+
+/// Size of a review's change, for the list's columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub files: usize,
+    pub added: usize,
+    pub removed: usize,
 }
 
 /// Full details of a review.
@@ -35,6 +51,59 @@ pub struct ReviewDetail {
     pub abandon_reason: Option<String>,
     pub thread_count: i64,
     pub open_thread_count: i64,
+    /// Reviewers' verdicts, drawn as summary blocks at the top of the review
+    #[serde(default)]
+    pub votes: Vec<ReviewVote>,
+    /// Commit the jj change points at now, when it was rewritten after
+    /// crit recorded the review; its diff is shown instead
+    #[serde(default)]
+    pub rewritten_to: Option<String>,
+}
+
+impl ReviewDetail {
+    /// Commit the review's diff is shown at: where a rewritten jj change
+    /// points now, else the final commit, else the initial one.
+    #[must_use]
+    pub fn commit(&self) -> &str {
+        self.rewritten_to
+            .as_deref()
+            .or(self.final_commit.as_deref())
+            .unwrap_or(&self.initial_commit)
+    }
+}
+
+/// A reviewer's verdict on a review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    /// `crit lgtm`
+    #[serde(rename = "lgtm")]
+    Approve,
+    /// `crit block`
+    #[serde(rename = "block")]
+    RequestChanges,
+}
+
+/// A verdict as recorded on the review, with its summary comment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewVote {
+    pub reviewer: String,
+    pub vote: Verdict,
+    /// Summary given with the verdict, if any
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub voted_at: String,
+}
+
+/// Which side of a diff a thread's line numbers refer to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThreadSide {
+    /// Line numbers in the base revision (removed/context lines)
+    Old,
+    /// Line numbers in the reviewed revision (added/context lines, default)
+    #[default]
+    New,
 }
 
 /// Summary of a thread for list views.
@@ -44,10 +113,45 @@ pub struct ThreadSummary {
     pub file_path: String,
     pub selection_start: i64,
     pub selection_end: Option<i64>,
+    /// Side the selection refers to; `None` for threads created before crit
+    /// recorded it, which are anchored heuristically.
+    #[serde(default)]
+    pub side: Option<ThreadSide>,
     pub status: String,
     pub comment_count: i64,
 }
 
+/// `selection_start` of a file-level thread: one about the file as a whole
+/// rather than any line in it.
+pub const FILE_THREAD_LINE: i64 = 0;
+
+/// `file_path` of a review-level thread: general discussion that isn't about
+/// any file. Its `selection_start` is [`FILE_THREAD_LINE`].
+pub const REVIEW_THREAD_PATH: &str = "";
+
+impl ThreadSummary {
+    #[must_use]
+    pub const fn is_file_thread(&self) -> bool {
+        self.selection_start == FILE_THREAD_LINE
+    }
+
+    #[must_use]
+    pub fn is_review_thread(&self) -> bool {
+        self.file_path == REVIEW_THREAD_PATH
+    }
+}
+
+/// Where a thread or comment points, for display: `path:start-end`, or just
+/// `path` for a file-level thread.
+#[must_use]
+pub fn thread_location(file_path: &str, start: i64, end: Option<i64>) -> String {
+    match end {
+        _ if start == FILE_THREAD_LINE => file_path.to_string(),
+        Some(end) if end != start => format!("{file_path}:{start}-{end}"),
+        _ => format!("{file_path}:{start}"),
+    }
+}
+
 /// Full details of a thread.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThreadDetail {
@@ -57,6 +161,8 @@ pub struct ThreadDetail {
     pub selection_type: String,
     pub selection_start: i64,
     pub selection_end: Option<i64>,
+    #[serde(default)]
+    pub side: Option<ThreadSide>,
     pub commit_hash: String,
     pub author: String,
     pub created_at: String,
@@ -76,7 +182,91 @@ pub struct Comment {
     pub created_at: String,
 }
 
+/// Kind of thread status transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThreadEventKind {
+    Opened,
+    Resolved,
+    Reopened,
+}
+
+/// One entry in a thread's status history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadEvent {
+    pub kind: ThreadEventKind,
+    /// Who made the change
+    pub actor: String,
+    pub created_at: String,
+    /// Resolve/reopen reason, if one was given
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Ids the backend assigned to a newly created comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedComment {
+    /// Thread the comment landed in (new for `comment`, existing for `reply`)
+    pub thread_id: String,
+    pub comment_id: String,
+    /// Author the comment was recorded under
+    pub author: String,
+}
+
+/// Thread state a reply, resolve or reopen was made against, checked before
+/// writing so it never lands on a thread that changed underneath its author.
+#[derive(Debug, Clone, Copy)]
+pub struct ExpectedThread<'a> {
+    pub review_id: &'a str,
+    /// [`thread_etag`] of the state the author saw
+    pub etag: &'a str,
+}
+
+/// Version tag for a thread: its status, comment count and newest comment.
+/// Any resolve, reopen or comment by another client changes it.
+#[must_use]
+pub fn thread_etag(status: &str, comments: &[Comment]) -> String {
+    let newest = comments.last().map_or("", |c| c.comment_id.as_str());
+    format!("{status}:{}:{newest}", comments.len())
+}
+
+/// Version tag for a review's diffs and file contents, so a reload can tell
+/// whether the working copy moved under a review.
+#[must_use]
+pub fn files_etag(files: &[FileData]) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for file in files {
+        file.path.hash(&mut hasher);
+        file.diff.hash(&mut hasher);
+        if let Some(content) = &file.content {
+            content.start_line.hash(&mut hasher);
+            content.lines.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// A reply was refused because the thread changed since it was loaded.
+/// Carries the thread's current state so the caller can show it.
+#[derive(Debug, Clone)]
+pub struct ThreadConflict {
+    pub thread_id: String,
+    pub status: String,
+    /// All comments currently on the thread, oldest first
+    pub comments: Vec<Comment>,
+}
+
+impl std::fmt::Display for ThreadConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "thread {} changed since it was loaded", self.thread_id)
+    }
+}
+
+impl std::error::Error for ThreadConflict {}
+
 /// Per-file diff and content data from crit.
+#[derive(Debug, Clone)]
 pub struct FileData {
     pub path: String,
     /// Unified diff text for this file (if available).
@@ -86,6 +276,7 @@ pub struct FileData {
 }
 
 /// Windowed file content returned by crit for orphaned threads.
+#[derive(Debug, Clone)]
 pub struct FileContentData {
     /// 1-based line number of the first line in `lines`.
     pub start_line: i64,
@@ -93,6 +284,7 @@ pub struct FileContentData {
 }
 
 /// Bundle of review data loaded in one call.
+#[derive(Debug, Clone)]
 pub struct ReviewData {
     pub detail: ReviewDetail,
     pub threads: Vec<ThreadSummary>,
@@ -101,6 +293,26 @@ pub struct ReviewData {
     pub files: Vec<FileData>,
 }
 
+impl ReviewData {
+    /// Newest of the review's creation, status change, verdicts and
+    /// comments. RFC 3339 timestamps compare as strings.
+    #[must_use]
+    pub fn updated_at(&self) -> &str {
+        let detail = &self.detail;
+        std::iter::once(detail.created_at.as_str())
+            .chain(detail.status_changed_at.as_deref())
+            .chain(detail.votes.iter().map(|vote| vote.voted_at.as_str()))
+            .chain(
+                self.comments
+                    .values()
+                    .flatten()
+                    .map(|c| c.created_at.as_str()),
+            )
+            .max()
+            .unwrap_or_default()
+    }
+}
+
 /// Trait for loading review data from any backend.
 pub trait CritClient {
     /// List reviews, optionally filtered by status.
@@ -117,7 +329,31 @@ pub trait CritClient {
     /// Returns an error if the backend query fails.
     fn load_review_data(&self, review_id: &str) -> Result<Option<ReviewData>>;
 
+    /// Per-file diffs stored with the review in crit's database, for
+    /// browsing without the repository checked out. `None` if the review
+    /// doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend query fails (e.g. nothing stored).
+    fn get_stored_diff(&self, review_id: &str) -> Result<Option<Vec<FileData>>>;
+
+    /// Name this client's comments are recorded under. The inbox treats it
+    /// as "me".
+    fn current_user(&self) -> String;
+
+    /// Status history of a thread (opened, resolved, reopened), oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend query fails.
+    fn thread_events(&self, thread_id: &str) -> Result<Vec<ThreadEvent>>;
+
     /// Add a comment to a review on specific lines (auto-creates thread).
+    /// `side` says which revision the line numbers refer to.
+    ///
+    /// Returns the new thread and comment ids, or `None` if the backend
+    /// succeeded without reporting them.
     ///
     /// # Errors
     ///
@@ -128,13 +364,80 @@ pub trait CritClient {
         file_path: &str,
         start_line: i64,
         end_line: Option<i64>,
+        side: ThreadSide,
         body: &str,
-    ) -> Result<()>;
+    ) -> Result<Option<CreatedComment>>;
 
     /// Reply to an existing thread.
     ///
+    /// With `expected`, nothing is posted if the thread's current
+    /// [`thread_etag`] differs; the error then downcasts to
+    /// [`ThreadConflict`].
+    ///
+    /// Returns the new comment's ids, or `None` if the backend succeeded
+    /// without reporting them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CLI call fails or the thread changed.
+    fn reply(
+        &self,
+        thread_id: &str,
+        body: &str,
+        expected: Option<ExpectedThread<'_>>,
+    ) -> Result<Option<CreatedComment>>;
+
+    /// Mark a thread resolved.
+    ///
+    /// With `expected`, nothing changes if the thread's current
+    /// [`thread_etag`] differs; the error then downcasts to
+    /// [`ThreadConflict`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CLI call fails or the thread changed.
+    fn resolve_thread(&self, thread_id: &str, expected: Option<ExpectedThread<'_>>) -> Result<()>;
+
+    /// Reopen a resolved thread. `expected` is checked as for
+    /// [`resolve_thread`](Self::resolve_thread).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CLI call fails or the thread changed.
+    fn reopen_thread(&self, thread_id: &str, expected: Option<ExpectedThread<'_>>) -> Result<()>;
+
+    /// Approve a review or request changes on it, with an optional summary
+    /// comment. Both are recorded by one backend call, so a failure leaves
+    /// neither.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CLI call fails.
+    fn submit_verdict(
+        &self,
+        review_id: &str,
+        verdict: Verdict,
+        summary: Option<&str>,
+    ) -> Result<()>;
+
+    /// Abandon a review, with an optional reason shown to everyone who
+    /// opens it.
+    ///
     /// # Errors
     ///
     /// Returns an error if the CLI call fails.
-    fn reply(&self, thread_id: &str, body: &str) -> Result<()>;
+    fn abandon_review(&self, review_id: &str, reason: Option<&str>) -> Result<()>;
+
+    /// Reopen an abandoned review.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CLI call fails.
+    fn restore_review(&self, review_id: &str) -> Result<()>;
+
+    /// A copy of the client for a worker thread, so a slow load doesn't
+    /// block the UI. `None` (the default) means loads run inline.
+    fn worker(&self) -> Option<Box<dyn CritClient + Send>> {
+        None
+    }
 }
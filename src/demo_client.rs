@@ -0,0 +1,1445 @@
+//! `CritClient` implementation backed by generated demo data.
+//!
+//! Reviews, diffs, threads and comments are derived from a seed, so the same
+//! `--demo-size` / `--demo-seed` always produce the same data. Review lists are
+//! planned up front (cheap); file contents and diffs are generated when a
+//! review is first opened, which keeps `large` usable for performance testing.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use crate::db::{
+    thread_etag, Comment, CreatedComment, CritClient, ExpectedThread, FileContentData, FileData,
+    ReviewData, ReviewDetail, ReviewSummary, ReviewVote, ThreadConflict, ThreadEvent,
+    ThreadEventKind, ThreadSide, ThreadSummary, Verdict,
+};
+
+/// Seed used when `--demo-seed` isn't given.
+pub const DEFAULT_DEMO_SEED: u64 = 0x00C0_FFEE;
+
+/// Context lines around each generated hunk (matches `git diff` defaults).
+const DIFF_CONTEXT: usize = 3;
+
+/// How much demo data to generate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DemoSize {
+    #[default]
+    Small,
+    Medium,
+    Large,
+}
+
+impl DemoSize {
+    /// Names accepted by [`Self::parse`], smallest first.
+    pub const NAMES: [&'static str; 3] = ["small", "medium", "large"];
+
+    /// Parse `small`, `medium` or `large`.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "small" | "s" => Some(Self::Small),
+            "medium" | "m" => Some(Self::Medium),
+            "large" | "l" => Some(Self::Large),
+            _ => None,
+        }
+    }
+
+    const fn profile(self) -> Profile {
+        match self {
+            Self::Small => Profile {
+                reviews: 6,
+                files: (1, 4),
+                file_lines: (40, 160),
+                hunks: (1, 3),
+                threads_per_file: (0, 3),
+                comments_per_thread: (1, 4),
+            },
+            Self::Medium => Profile {
+                reviews: 40,
+                files: (3, 15),
+                file_lines: (80, 600),
+                hunks: (1, 6),
+                threads_per_file: (0, 5),
+                comments_per_thread: (1, 10),
+            },
+            Self::Large => Profile {
+                reviews: 200,
+                files: (10, 60),
+                file_lines: (150, 2500),
+                hunks: (2, 12),
+                threads_per_file: (0, 8),
+                comments_per_thread: (1, 40),
+            },
+        }
+    }
+}
+
+/// Inclusive `(min, max)` ranges for each generated quantity
+struct Profile {
+    reviews: usize,
+    files: (usize, usize),
+    file_lines: (usize, usize),
+    hunks: (usize, usize),
+    threads_per_file: (usize, usize),
+    comments_per_thread: (usize, usize),
+}
+
+/// Client that serves seeded, in-memory demo reviews.
+///
+/// Comments and replies are kept in memory for the session.
+pub struct DemoClient {
+    plans: Vec<ReviewPlan>,
+    summaries: RefCell<Vec<ReviewSummary>>,
+    loaded: RefCell<HashMap<String, DemoReview>>,
+}
+
+impl DemoClient {
+    #[must_use]
+    pub fn new(size: DemoSize, seed: u64) -> Self {
+        let profile = size.profile();
+        let mut rng = Rng::new(seed);
+        let mut review_ids = HashSet::new();
+        let plans: Vec<ReviewPlan> = (0..profile.reviews)
+            .map(|index| plan_review(&mut rng, &profile, index, &mut review_ids))
+            .collect();
+        let summaries = plans.iter().map(|plan| plan.summary.clone()).collect();
+        Self {
+            plans,
+            summaries: RefCell::new(summaries),
+            loaded: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Generate (once) and cache the full data for a review.
+    fn ensure_loaded(&self, review_id: &str) -> bool {
+        if self.loaded.borrow().contains_key(review_id) {
+            return true;
+        }
+        let Some(plan) = self.plans.iter().find(|p| p.summary.review_id == review_id) else {
+            return false;
+        };
+        let review = generate_review(plan);
+        self.loaded
+            .borrow_mut()
+            .insert(review_id.to_string(), review);
+        true
+    }
+
+    /// Recompute thread counts after a comment changes a review.
+    fn refresh_counts(&self, review: &mut DemoReview) {
+        let total = to_i64(review.threads.len());
+        let open = to_i64(review.threads.iter().filter(|t| t.status == "open").count());
+        review.detail.thread_count = total;
+        review.detail.open_thread_count = open;
+        if let Some(summary) = self
+            .summaries
+            .borrow_mut()
+            .iter_mut()
+            .find(|s| s.review_id == review.detail.review_id)
+        {
+            summary.thread_count = total;
+            summary.open_thread_count = open;
+        }
+    }
+
+    fn set_status(
+        &self,
+        thread_id: &str,
+        status: &str,
+        expected: Option<ExpectedThread<'_>>,
+    ) -> Result<()> {
+        let mut loaded = self.loaded.borrow_mut();
+        let Some(review) = loaded
+            .values_mut()
+            .find(|r| r.threads.iter().any(|t| t.thread_id == thread_id))
+        else {
+            bail!("Thread not found: {thread_id}");
+        };
+        let Some(thread) = review.threads.iter_mut().find(|t| t.thread_id == thread_id) else {
+            bail!("Thread not found: {thread_id}");
+        };
+        let comments = review
+            .comments
+            .get(thread_id)
+            .map_or(&[][..], Vec::as_slice);
+        if expected.is_some_and(|e| thread_etag(&thread.status, comments) != e.etag) {
+            return Err(ThreadConflict {
+                thread_id: thread_id.to_string(),
+                status: thread.status.clone(),
+                comments: comments.to_vec(),
+            }
+            .into());
+        }
+        thread.status = status.to_string();
+        self.refresh_counts(review);
+        Ok(())
+    }
+
+    fn set_review_status(&self, review_id: &str, status: &str, reason: Option<&str>) -> Result<()> {
+        if !self.ensure_loaded(review_id) {
+            bail!("Review not found: {review_id}");
+        }
+        let mut loaded = self.loaded.borrow_mut();
+        let Some(review) = loaded.get_mut(review_id) else {
+            bail!("Review not found: {review_id}");
+        };
+        let detail = &mut review.detail;
+        detail.status = status.to_string();
+        detail.status_changed_at = Some("2025-06-30T12:00:00Z".to_string());
+        detail.status_changed_by = Some(DEMO_USER.to_string());
+        detail.abandon_reason = reason.map(str::to_string);
+        if let Some(summary) = self
+            .summaries
+            .borrow_mut()
+            .iter_mut()
+            .find(|s| s.review_id == review_id)
+        {
+            summary.status = status.to_string();
+        }
+        Ok(())
+    }
+}
+
+impl CritClient for DemoClient {
+    fn list_reviews(&self, status: Option<&str>) -> Result<Vec<ReviewSummary>> {
+        let summaries = self.summaries.borrow();
+        Ok(summaries
+            .iter()
+            .filter(|r| status.is_none_or(|s| r.status == s))
+            .cloned()
+            .collect())
+    }
+
+    fn load_review_data(&self, review_id: &str) -> Result<Option<ReviewData>> {
+        if !self.ensure_loaded(review_id) {
+            return Ok(None);
+        }
+        let loaded = self.loaded.borrow();
+        let Some(review) = loaded.get(review_id) else {
+            return Ok(None);
+        };
+        Ok(Some(ReviewData {
+            detail: review.detail.clone(),
+            threads: review.threads.clone(),
+            comments: review.comments.clone(),
+            files: review.file_data(),
+        }))
+    }
+
+    fn get_stored_diff(&self, review_id: &str) -> Result<Option<Vec<FileData>>> {
+        if !self.ensure_loaded(review_id) {
+            return Ok(None);
+        }
+        Ok(self
+            .loaded
+            .borrow()
+            .get(review_id)
+            .map(DemoReview::file_data))
+    }
+
+    fn current_user(&self) -> String {
+        DEMO_USER.to_string()
+    }
+
+    fn thread_events(&self, thread_id: &str) -> Result<Vec<ThreadEvent>> {
+        let loaded = self.loaded.borrow();
+        let Some((thread, comments)) = loaded.values().find_map(|review| {
+            let thread = review.threads.iter().find(|t| t.thread_id == thread_id)?;
+            Some((thread, review.comments.get(thread_id)?))
+        }) else {
+            bail!("Thread not found: {thread_id}");
+        };
+        Ok(thread_history(thread, comments))
+    }
+
+    fn comment(
+        &self,
+        review_id: &str,
+        file_path: &str,
+        start_line: i64,
+        end_line: Option<i64>,
+        side: ThreadSide,
+        body: &str,
+    ) -> Result<Option<CreatedComment>> {
+        if !self.ensure_loaded(review_id) {
+            bail!("Review not found: {review_id}");
+        }
+        if !self.ensure_loaded(review_id) {
+            bail!("Review not found: {review_id}");
+        }
+        let mut loaded = self.loaded.borrow_mut();
+        let Some(review) = loaded.get_mut(review_id) else {
+            bail!("Review not found: {review_id}");
+        };
+        let thread_id = format!("th-demo{}", review.threads.len() + 1);
+        review.threads.push(ThreadSummary {
+            thread_id: thread_id.clone(),
+            file_path: file_path.to_string(),
+            selection_start: start_line,
+            selection_end: end_line.filter(|&end| end != start_line),
+            side: Some(side),
+            status: "open".to_string(),
+            comment_count: 1,
+        });
+        let comment_id = format!("{thread_id}-1");
+        let comment = local_comment(&comment_id, body);
+        let author = comment.author.clone();
+        review.comments.insert(thread_id.clone(), vec![comment]);
+        self.refresh_counts(review);
+        Ok(Some(CreatedComment {
+            thread_id,
+            comment_id,
+            author,
+        }))
+    }
+
+    fn reply(
+        &self,
+        thread_id: &str,
+        body: &str,
+        expected: Option<ExpectedThread<'_>>,
+    ) -> Result<Option<CreatedComment>> {
+        let mut loaded = self.loaded.borrow_mut();
+        let Some(review) = loaded
+            .values_mut()
+            .find(|r| r.threads.iter().any(|t| t.thread_id == thread_id))
+        else {
+            bail!("Thread not found: {thread_id}");
+        };
+        let status = review
+            .threads
+            .iter()
+            .find(|t| t.thread_id == thread_id)
+            .map(|t| t.status.clone())
+            .unwrap_or_default();
+        let comments = review.comments.entry(thread_id.to_string()).or_default();
+        if expected.is_some_and(|e| thread_etag(&status, comments) != e.etag) {
+            return Err(ThreadConflict {
+                thread_id: thread_id.to_string(),
+                status,
+                comments: comments.clone(),
+            }
+            .into());
+        }
+        let comment_id = format!("{thread_id}-{}", comments.len() + 1);
+        let comment = local_comment(&comment_id, body);
+        let author = comment.author.clone();
+        comments.push(comment);
+        let count = to_i64(comments.len());
+        if let Some(thread) = review.threads.iter_mut().find(|t| t.thread_id == thread_id) {
+            thread.comment_count = count;
+        }
+        Ok(Some(CreatedComment {
+            thread_id: thread_id.to_string(),
+            comment_id,
+            author,
+        }))
+    }
+
+    fn resolve_thread(&self, thread_id: &str, expected: Option<ExpectedThread<'_>>) -> Result<()> {
+        self.set_status(thread_id, "resolved", expected)
+    }
+
+    fn reopen_thread(&self, thread_id: &str, expected: Option<ExpectedThread<'_>>) -> Result<()> {
+        self.set_status(thread_id, "open", expected)
+    }
+
+    fn submit_verdict(
+        &self,
+        review_id: &str,
+        verdict: Verdict,
+        summary: Option<&str>,
+    ) -> Result<()> {
+        if !self.ensure_loaded(review_id) {
+            bail!("Review not found: {review_id}");
+        }
+        let mut loaded = self.loaded.borrow_mut();
+        let Some(review) = loaded.get_mut(review_id) else {
+            bail!("Review not found: {review_id}");
+        };
+        // A new verdict replaces the reviewer's last one
+        let votes = &mut review.detail.votes;
+        votes.retain(|vote| vote.reviewer != DEMO_USER);
+        votes.push(ReviewVote {
+            reviewer: DEMO_USER.to_string(),
+            vote: verdict,
+            reason: summary.map(str::to_string),
+            voted_at: "2025-06-30T12:00:00Z".to_string(),
+        });
+        Ok(())
+    }
+
+    fn abandon_review(&self, review_id: &str, reason: Option<&str>) -> Result<()> {
+        self.set_review_status(review_id, "abandoned", reason)
+    }
+
+    fn restore_review(&self, review_id: &str) -> Result<()> {
+        self.set_review_status(review_id, "open", None)
+    }
+}
+
+/// Status history implied by a thread's comments: opened with the first
+/// comment and, if resolved, resolved with the last. Long resolved threads
+/// were resolved once early on and reopened by the next reply.
+fn thread_history(thread: &ThreadSummary, comments: &[Comment]) -> Vec<ThreadEvent> {
+    let event = |kind, comment: &Comment, reason: Option<&str>| ThreadEvent {
+        kind,
+        actor: comment.author.clone(),
+        created_at: comment.created_at.clone(),
+        reason: reason.map(str::to_string),
+    };
+    let (Some(first), Some(last)) = (comments.first(), comments.last()) else {
+        return Vec::new();
+    };
+    let mut events = vec![event(ThreadEventKind::Opened, first, None)];
+    if thread.status == "resolved" {
+        if comments.len() >= 4 {
+            events.push(event(ThreadEventKind::Resolved, &comments[1], None));
+            events.push(event(
+                ThreadEventKind::Reopened,
+                &comments[2],
+                Some("Still reproduces on main"),
+            ));
+        }
+        events.push(event(ThreadEventKind::Resolved, last, None));
+    }
+    events
+}
+
+fn local_comment(comment_id: &str, body: &str) -> Comment {
+    Comment {
+        comment_id: comment_id.to_string(),
+        author: DEMO_USER.to_string(),
+        body: body.to_string(),
+        created_at: "2025-06-30T12:00:00Z".to_string(),
+    }
+}
+
+// -- Planning (cheap, done for every review up front) --
+
+struct ReviewPlan {
+    summary: ReviewSummary,
+    seed: u64,
+    created_minute: u64,
+    files: Vec<FilePlan>,
+}
+
+struct FilePlan {
+    path: String,
+    added: bool,
+    lines: usize,
+    hunks: usize,
+    threads: Vec<ThreadPlan>,
+}
+
+struct ThreadPlan {
+    resolved: bool,
+    comments: usize,
+    orphaned: bool,
+}
+
+fn plan_review(
+    rng: &mut Rng,
+    profile: &Profile,
+    index: usize,
+    review_ids: &mut HashSet<String>,
+) -> ReviewPlan {
+    let review_id = unique_id(rng, "cr-", review_ids);
+    let status = match rng.range(0, 9) {
+        0..=5 => "open",
+        6 => "approved",
+        7 | 8 => "merged",
+        _ => "abandoned",
+    };
+    let title = format!("{} {}", rng.word(TITLE_VERBS), rng.word(TITLE_OBJECTS));
+    let title = if rng.chance(10) {
+        format!("WIP: {title}")
+    } else {
+        title
+    };
+    let author = rng.word(AUTHORS).to_string();
+    let mut reviewers: Vec<String> = Vec::new();
+    for _ in 0..rng.range(0, 3) {
+        let reviewer = rng.word(AUTHORS);
+        if reviewer != author && !reviewers.iter().any(|r| r == reviewer) {
+            reviewers.push(reviewer.to_string());
+        }
+    }
+
+    let mut paths = HashSet::new();
+    let files: Vec<FilePlan> = (0..rng.range(profile.files.0, profile.files.1))
+        .map(|_| {
+            let path = unique_path(rng, &mut paths);
+            let added = rng.chance(15);
+            let threads = (0..rng.range(profile.threads_per_file.0, profile.threads_per_file.1))
+                .map(|_| ThreadPlan {
+                    resolved: status != "open" || rng.chance(35),
+                    comments: if rng.chance(10) {
+                        profile.comments_per_thread.1
+                    } else {
+                        rng.range(profile.comments_per_thread.0, profile.comments_per_thread.1)
+                    },
+                    orphaned: !added && rng.chance(20),
+                })
+                .collect();
+            FilePlan {
+                path,
+                added,
+                lines: rng.range(profile.file_lines.0, profile.file_lines.1),
+                hunks: rng.range(profile.hunks.0, profile.hunks.1),
+                threads,
+            }
+        })
+        .collect();
+
+    let threads = files.iter().flat_map(|f| &f.threads);
+    let thread_count = to_i64(threads.clone().count());
+    let open_thread_count = to_i64(threads.filter(|t| !t.resolved).count());
+
+    let seed = rng.next_u64();
+    // Newer reviews first in the list: later indices are older.
+    let created_minute = 250_000 - (index as u64) * 97 - rng.range(0, 90) as u64;
+    ReviewPlan {
+        summary: ReviewSummary {
+            review_id,
+            title,
+            author,
+            status: status.to_string(),
+            thread_count,
+            open_thread_count,
+            reviewers,
+            created_at: Some(timestamp(created_minute)),
+            updated_at: None,
+            diff_stats: None,
+        },
+        seed,
+        created_minute,
+        files,
+    }
+}
+
+// -- Generation (done lazily per review) --
+
+struct DemoReview {
+    detail: ReviewDetail,
+    threads: Vec<ThreadSummary>,
+    comments: HashMap<String, Vec<Comment>>,
+    files: Vec<DemoFile>,
+}
+
+impl DemoReview {
+    /// Files as a backend returns them. Demo diffs are always "stored".
+    fn file_data(&self) -> Vec<FileData> {
+        self.files
+            .iter()
+            .map(|f| FileData {
+                path: f.path.clone(),
+                diff: Some(f.diff.clone()),
+                content: f.content.as_ref().map(|lines| FileContentData {
+                    start_line: 1,
+                    lines: lines.clone(),
+                }),
+            })
+            .collect()
+    }
+}
+
+struct DemoFile {
+    path: String,
+    diff: String,
+    /// Full new-side file text, included when the file has orphaned threads
+    content: Option<Vec<String>>,
+}
+
+fn generate_review(plan: &ReviewPlan) -> DemoReview {
+    let mut rng = Rng::new(plan.seed);
+    let summary = &plan.summary;
+    let status = summary.status.as_str();
+    let closed = status != "open";
+
+    let detail = ReviewDetail {
+        review_id: summary.review_id.clone(),
+        jj_change_id: rng.string(JJ_ALPHABET, 12),
+        initial_commit: rng.string(HEX_ALPHABET, 12),
+        final_commit: (status == "merged").then(|| rng.string(HEX_ALPHABET, 12)),
+        title: summary.title.clone(),
+        description: rng.chance(80).then(|| review_description(&mut rng)),
+        author: summary.author.clone(),
+        created_at: timestamp(plan.created_minute),
+        status: status.to_string(),
+        status_changed_at: closed.then(|| timestamp(plan.created_minute + 600)),
+        status_changed_by: closed.then(|| summary.author.clone()),
+        abandon_reason: (status == "abandoned")
+            .then(|| "Superseded by a smaller change".to_string()),
+        thread_count: summary.thread_count,
+        open_thread_count: summary.open_thread_count,
+        votes: Vec::new(),
+        rewritten_to: None,
+    };
+
+    let mut threads = Vec::new();
+    let mut comments = HashMap::new();
+    let mut thread_ids = HashSet::new();
+    let mut files = Vec::with_capacity(plan.files.len());
+
+    for file_plan in &plan.files {
+        let generated = generate_file(&mut rng, file_plan);
+        let mut needs_content = false;
+
+        for thread_plan in &file_plan.threads {
+            let orphan_line = if thread_plan.orphaned {
+                rng.pick_opt(&generated.outside_hunks)
+            } else {
+                None
+            };
+            // Some in-diff threads sit on removed code, anchored to the old side
+            let removed_line = (orphan_line.is_none() && rng.chance(15))
+                .then(|| rng.pick_opt(&generated.removed))
+                .flatten();
+            let (side, start) = if let Some(line) = removed_line {
+                (ThreadSide::Old, line)
+            } else if let Some(line) = orphan_line.or_else(|| rng.pick_opt(&generated.inside_hunks))
+            {
+                (ThreadSide::New, line)
+            } else {
+                continue;
+            };
+            needs_content |= orphan_line.is_some();
+            let end = match side {
+                // Keep old-side ranges within a single run of removed lines
+                ThreadSide::Old => (rng.chance(30) && generated.removed.contains(&(start + 1)))
+                    .then_some(start + 1),
+                ThreadSide::New => rng
+                    .chance(30)
+                    .then(|| (start + rng.range(1, 4)).min(generated.new_lines.len())),
+            };
+
+            let thread_id = unique_id(&mut rng, "th-", &mut thread_ids);
+            let thread_comments =
+                thread_comments(&mut rng, &thread_id, thread_plan, plan.created_minute);
+            threads.push(ThreadSummary {
+                thread_id: thread_id.clone(),
+                file_path: file_plan.path.clone(),
+                selection_start: to_i64(start),
+                selection_end: end.filter(|&e| e > start).map(to_i64),
+                side: Some(side),
+                status: if thread_plan.resolved {
+                    "resolved"
+                } else {
+                    "open"
+                }
+                .to_string(),
+                comment_count: to_i64(thread_comments.len()),
+            });
+            comments.insert(thread_id, thread_comments);
+        }
+
+        files.push(DemoFile {
+            path: file_plan.path.clone(),
+            diff: generated.diff,
+            content: needs_content.then_some(generated.new_lines),
+        });
+    }
+
+    DemoReview {
+        detail,
+        threads,
+        comments,
+        files,
+    }
+}
+
+fn review_description(rng: &mut Rng) -> String {
+    let mut text = rng.word(DESCRIPTION_OPENERS).to_string();
+    if rng.chance(60) {
+        text.push_str("\n\nChanges:\n");
+        for _ in 0..rng.range(2, 5) {
+            text.push_str("- ");
+            text.push_str(rng.word(DESCRIPTION_BULLETS));
+            text.push('\n');
+        }
+    }
+    if rng.chance(25) {
+        text.push_str("\nTested with: ");
+        text.push_str(rng.word(UNICODE_SNIPPETS));
+    }
+    text.trim_end().to_string()
+}
+
+fn thread_comments(
+    rng: &mut Rng,
+    thread_id: &str,
+    plan: &ThreadPlan,
+    review_minute: u64,
+) -> Vec<Comment> {
+    let opener = rng.word(AUTHORS);
+    let responder = rng.word(AUTHORS);
+    let mut minute = review_minute + rng.range(5, 600) as u64;
+    (0..plan.comments)
+        .map(|i| {
+            minute += rng.range(1, 180) as u64;
+            let last = i + 1 == plan.comments;
+            let body = if i == 0 {
+                comment_body(rng, COMMENT_OPENERS)
+            } else if last && plan.resolved {
+                rng.word(COMMENT_CLOSERS).to_string()
+            } else {
+                comment_body(rng, COMMENT_REPLIES)
+            };
+            Comment {
+                comment_id: format!("{}-{}", thread_id.replacen("th-", "cm-", 1), i + 1),
+                author: if i % 2 == 0 { opener } else { responder }.to_string(),
+                body,
+                created_at: timestamp(minute),
+            }
+        })
+        .collect()
+}
+
+fn comment_body(rng: &mut Rng, pool: &[&str]) -> String {
+    let mut body = (*rng.pick(pool)).to_string();
+    for _ in 0..rng.range(0, 2) {
+        body.push(' ');
+        body.push_str(rng.word(COMMENT_REPLIES));
+    }
+    if rng.chance(15) {
+        body.push_str("\n\n");
+        body.push_str(rng.word(UNICODE_SNIPPETS));
+    }
+    body
+}
+
+/// A generated file: its diff plus where threads can land.
+struct GeneratedFile {
+    diff: String,
+    new_lines: Vec<String>,
+    /// New-side line numbers covered by hunks
+    inside_hunks: Vec<usize>,
+    /// New-side line numbers outside every hunk (orphaned thread anchors)
+    outside_hunks: Vec<usize>,
+    /// Old-side line numbers of removed lines
+    removed: Vec<usize>,
+}
+
+enum Op {
+    Keep(String),
+    Del(String),
+    Add(String),
+}
+
+fn generate_file(rng: &mut Rng, plan: &FilePlan) -> GeneratedFile {
+    let kind = FileKind::of(&plan.path);
+    let ops: Vec<Op> = if plan.added {
+        (0..plan.lines).map(|_| Op::Add(kind.line(rng))).collect()
+    } else {
+        let old: Vec<String> = (0..plan.lines).map(|_| kind.line(rng)).collect();
+        edit_ops(rng, old, plan.hunks, kind)
+    };
+
+    let mut new_lines = Vec::new();
+    for op in &ops {
+        if let Op::Keep(text) | Op::Add(text) = op {
+            new_lines.push(text.clone());
+        }
+    }
+
+    let ranges = hunk_ranges(&ops);
+    let mut inside_hunks = Vec::new();
+    let mut outside_hunks = Vec::new();
+    let mut removed = Vec::new();
+    let mut new_line = 0;
+    let mut old_line = 0;
+    for (i, op) in ops.iter().enumerate() {
+        if !matches!(op, Op::Add(_)) {
+            old_line += 1;
+        }
+        if matches!(op, Op::Del(_)) {
+            removed.push(old_line);
+            continue;
+        }
+        new_line += 1;
+        if ranges.iter().any(|&(s, e)| (s..=e).contains(&i)) {
+            inside_hunks.push(new_line);
+        } else {
+            outside_hunks.push(new_line);
+        }
+    }
+
+    GeneratedFile {
+        diff: unified_diff(rng, &plan.path, plan.added, &ops, &ranges),
+        new_lines,
+        inside_hunks,
+        outside_hunks,
+        removed,
+    }
+}
+
+/// Turn an old file into keep/delete/add operations with `hunks` edit sites.
+fn edit_ops(rng: &mut Rng, old: Vec<String>, hunks: usize, kind: FileKind) -> Vec<Op> {
+    let mut sites: Vec<usize> = (0..hunks)
+        .map(|_| rng.range(0, old.len().saturating_sub(1)))
+        .collect();
+    sites.sort_unstable();
+    sites.dedup();
+
+    let mut ops = Vec::with_capacity(old.len() + hunks * 4);
+    let mut old = old.into_iter().peekable();
+    let mut index = 0;
+    for site in sites {
+        while index < site && old.peek().is_some() {
+            ops.extend(old.next().map(Op::Keep));
+            index += 1;
+        }
+        let deleted = rng.range(0, 4);
+        for _ in 0..deleted {
+            if let Some(line) = old.next() {
+                ops.push(Op::Del(line));
+                index += 1;
+            }
+        }
+        let added = rng.range(usize::from(deleted == 0), 6);
+        for _ in 0..added {
+            ops.push(Op::Add(kind.line(rng)));
+        }
+    }
+    ops.extend(old.map(Op::Keep));
+    ops
+}
+
+/// Inclusive op-index ranges for each hunk, with context, merged when close.
+fn hunk_ranges(ops: &[Op]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    let last = ops.len().saturating_sub(1);
+    for (i, op) in ops.iter().enumerate() {
+        if matches!(op, Op::Keep(_)) {
+            continue;
+        }
+        let start = i.saturating_sub(DIFF_CONTEXT);
+        let end = (i + DIFF_CONTEXT).min(last);
+        match ranges.last_mut() {
+            Some(prev) if start <= prev.1 + 1 => prev.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+    ranges
+}
+
+fn unified_diff(
+    rng: &mut Rng,
+    path: &str,
+    added: bool,
+    ops: &[Op],
+    ranges: &[(usize, usize)],
+) -> String {
+    let mut out = format!("diff --git a/{path} b/{path}\n");
+    if added {
+        out.push_str("new file mode 100644\n");
+        let _ = writeln!(out, "index 0000000..{}", rng.string(HEX_ALPHABET, 7));
+        let _ = writeln!(out, "--- /dev/null\n+++ b/{path}");
+    } else {
+        let _ = writeln!(
+            out,
+            "index {}..{} 100644",
+            rng.string(HEX_ALPHABET, 7),
+            rng.string(HEX_ALPHABET, 7)
+        );
+        let _ = writeln!(out, "--- a/{path}\n+++ b/{path}");
+    }
+
+    // 1-based old/new line number of each op's position
+    let mut old_line = 1;
+    let mut new_line = 1;
+    let mut positions = Vec::with_capacity(ops.len());
+    for op in ops {
+        positions.push((old_line, new_line));
+        match op {
+            Op::Keep(_) => {
+                old_line += 1;
+                new_line += 1;
+            }
+            Op::Del(_) => old_line += 1,
+            Op::Add(_) => new_line += 1,
+        }
+    }
+
+    for &(start, end) in ranges {
+        let hunk = &ops[start..=end];
+        let old_count = hunk.iter().filter(|op| !matches!(op, Op::Add(_))).count();
+        let new_count = hunk.iter().filter(|op| !matches!(op, Op::Del(_))).count();
+        let (mut old_start, mut new_start) = positions[start];
+        // An empty side is numbered from the line before it, as git does.
+        if old_count == 0 {
+            old_start -= 1;
+        }
+        if new_count == 0 {
+            new_start -= 1;
+        }
+        let _ = writeln!(
+            out,
+            "@@ -{old_start},{old_count} +{new_start},{new_count} @@"
+        );
+        for op in hunk {
+            let (prefix, text) = match op {
+                Op::Keep(text) => (' ', text),
+                Op::Del(text) => ('-', text),
+                Op::Add(text) => ('+', text),
+            };
+            out.push(prefix);
+            out.push_str(text);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[derive(Clone, Copy)]
+enum FileKind {
+    Rust,
+    Markdown,
+    Toml,
+}
+
+impl FileKind {
+    fn of(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("md") => Self::Markdown,
+            Some("toml") => Self::Toml,
+            _ => Self::Rust,
+        }
+    }
+
+    fn line(self, rng: &mut Rng) -> String {
+        match self {
+            Self::Rust => rust_line(rng),
+            Self::Markdown => markdown_line(rng),
+            Self::Toml => format!("{} = \"{}\"", rng.word(IDENTS), rng.word(UNICODE_SNIPPETS)),
+        }
+    }
+}
+
+fn rust_line(rng: &mut Rng) -> String {
+    let a = rng.word(IDENTS);
+    let b = rng.word(IDENTS);
+    let ty = rng.word(TYPES);
+    let method = rng.word(METHODS);
+    match rng.range(0, 11) {
+        0 => format!("fn {a}_{b}(&self, {b}: &{ty}) -> Result<{ty}> {{"),
+        1 => "}".to_string(),
+        2 => format!("    // {}", rng.word(CODE_COMMENTS)),
+        3 => format!("    if {a}.is_empty() {{"),
+        4 => format!("        return Err({ty}Error::Invalid);"),
+        5 => format!(
+            "    const LABEL: &str = \"{}\";",
+            rng.word(UNICODE_SNIPPETS)
+        ),
+        6 => {
+            // Long line to exercise wrapping and horizontal truncation
+            let chain: Vec<String> = (0..rng.range(4, 9))
+                .map(|_| format!(".{}()", rng.word(METHODS)))
+                .collect();
+            format!("    let {a} = {b}{}.unwrap_or_default();", chain.concat())
+        }
+        7 => String::new(),
+        8 => format!("    self.{a}.insert({b}.{method}());"),
+        9 => format!("    tracing::debug!(?{a}, \"{} {b}\");", rng.word(METHODS)),
+        _ => format!("    let {a} = {b}.{method}();"),
+    }
+}
+
+fn markdown_line(rng: &mut Rng) -> String {
+    match rng.range(0, 5) {
+        0 => format!("## {} {}", rng.word(TITLE_VERBS), rng.word(TITLE_OBJECTS)),
+        1 => String::new(),
+        2 => format!("- {}", rng.word(DESCRIPTION_BULLETS)),
+        3 => rng.word(UNICODE_SNIPPETS).to_string(),
+        _ => rng.word(DESCRIPTION_OPENERS).to_string(),
+    }
+}
+
+fn unique_path(rng: &mut Rng, taken: &mut HashSet<String>) -> String {
+    loop {
+        let path = match rng.range(0, 19) {
+            0 => rng.word(UNICODE_PATHS).to_string(),
+            1 => "Cargo.toml".to_string(),
+            _ => {
+                let dir = rng.word(DIRS);
+                let ext = if dir.starts_with("docs") { "md" } else { "rs" };
+                format!("{dir}/{}.{ext}", rng.word(FILE_STEMS))
+            }
+        };
+        if taken.insert(path.clone()) {
+            return path;
+        }
+    }
+}
+
+fn unique_id(rng: &mut Rng, prefix: &str, taken: &mut HashSet<String>) -> String {
+    loop {
+        let id = format!("{prefix}{}", rng.string(ID_ALPHABET, 4));
+        if taken.insert(id.clone()) {
+            return id;
+        }
+    }
+}
+
+/// Minutes since 2025-01-01T00:00Z rendered as an RFC 3339 timestamp.
+fn timestamp(minutes: u64) -> String {
+    const MONTH_DAYS: [u64; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    let mut days = minutes / (24 * 60);
+    let minute_of_day = minutes % (24 * 60);
+    let mut year = 2025;
+    loop {
+        let year_days = if year % 4 == 0 { 366 } else { 365 };
+        if days < year_days {
+            break;
+        }
+        days -= year_days;
+        year += 1;
+    }
+    let mut month = 0;
+    while month < 11 {
+        let len = MONTH_DAYS[month] + u64::from(month == 1 && year % 4 == 0);
+        if days < len {
+            break;
+        }
+        days -= len;
+        month += 1;
+    }
+    format!(
+        "{year}-{:02}-{:02}T{:02}:{:02}:00Z",
+        month + 1,
+        days + 1,
+        minute_of_day / 60,
+        minute_of_day % 60
+    )
+}
+
+fn to_i64(n: usize) -> i64 {
+    i64::try_from(n).unwrap_or(i64::MAX)
+}
+
+/// Small deterministic PRNG (`SplitMix64`); quality is plenty for demo data.
+struct Rng(u64);
+
+impl Rng {
+    const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    const fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `lo..=hi`.
+    const fn range(&mut self, lo: usize, hi: usize) -> usize {
+        if hi <= lo {
+            return lo;
+        }
+        lo + (self.next_u64() % (hi - lo + 1) as u64) as usize
+    }
+
+    const fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0, items.len() - 1)]
+    }
+
+    fn word(&mut self, items: &[&'static str]) -> &'static str {
+        items[self.range(0, items.len() - 1)]
+    }
+
+    fn pick_opt<T: Copy>(&mut self, items: &[T]) -> Option<T> {
+        (!items.is_empty()).then(|| *self.pick(items))
+    }
+
+    fn string(&mut self, alphabet: &[u8], len: usize) -> String {
+        (0..len).map(|_| char::from(*self.pick(alphabet))).collect()
+    }
+}
+
+// -- Word pools --
+
+const ID_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+const JJ_ALPHABET: &[u8] = b"klmnopqrstuvwxyz";
+const HEX_ALPHABET: &[u8] = b"0123456789abcdef";
+
+/// Who the demo session comments as: one of the generated authors, so the
+/// inbox has threads to show.
+const DEMO_USER: &str = "bob";
+
+const AUTHORS: &[&str] = &[
+    "alice",
+    "bob",
+    "carol",
+    "dave",
+    "zoë",
+    "jürgen",
+    "søren",
+    "李雷",
+    "tanaka",
+    "security-reviewer",
+    "api-reviewer",
+];
+
+const TITLE_VERBS: &[&str] = &[
+    "Add", "Fix", "Refactor", "Remove", "Speed up", "Document", "Harden", "Migrate", "Simplify",
+];
+
+const TITLE_OBJECTS: &[&str] = &[
+    "user authentication",
+    "database connection pooling",
+    "diff parser recovery",
+    "theme loading",
+    "sidebar scrolling",
+    "config hot reload",
+    "retry backoff",
+    "unicode handling in paths",
+    "CRLF detection",
+    "cache invalidation",
+    "session expiry",
+    "metrics export",
+];
+
+const DESCRIPTION_OPENERS: &[&str] = &[
+    "This change reworks the module so callers no longer need to handle the legacy path.",
+    "Follow-up to the last release: a few edge cases were reported by users on Windows.",
+    "Small cleanup before the bigger refactor lands. No behaviour change intended.",
+    "Performance work driven by profiling the large-review case; see numbers below.",
+];
+
+const DESCRIPTION_BULLETS: &[&str] = &[
+    "Replace the ad-hoc parser with the shared tokenizer",
+    "Return errors instead of panicking on malformed input",
+    "Add tests for empty and single-line inputs",
+    "Cache the computed layout between frames",
+    "Drop the unused compatibility shim",
+    "Handle wide (CJK/emoji) characters when truncating",
+];
+
+const CODE_COMMENTS: &[&str] = &[
+    "TODO: remove once every caller has migrated",
+    "Keep in sync with the layout computation",
+    "SAFETY: index was bounds-checked above",
+    "Fast path: nothing changed since the last frame",
+    "Ñandú-safe: handles non-ASCII input",
+];
+
+const COMMENT_OPENERS: &[&str] = &[
+    "Should this come from config instead of being hardcoded?",
+    "This allocates on every call — can we reuse the buffer?",
+    "What happens here if the input is empty?",
+    "Nit: naming. `data` doesn't say much.",
+    "I think this breaks when the path contains non-ASCII characters.",
+    "Can we add a test for the error branch?",
+    "这里需要处理空字符串的情况。",
+    "Is the unwrap safe here? 🤔",
+];
+
+const COMMENT_REPLIES: &[&str] = &[
+    "Good catch, updated.",
+    "I'd rather keep it as is for now and follow up separately.",
+    "Done — also added a regression test.",
+    "Not sure that's right; the caller already validates this.",
+    "Agreed, but let's wait for the config rework to land first.",
+    "Benchmarked it: about 12% faster on the large fixture.",
+    "Ça marche, merci !",
+    "Could you expand on that? I don't follow the failure case.",
+];
+
+const COMMENT_CLOSERS: &[&str] = &[
+    "Looks good, thanks!",
+    "LGTM 🚀",
+    "Resolved in the latest revision.",
+    "👍",
+];
+
+const UNICODE_SNIPPETS: &[&str] = &[
+    "héllo wörld",
+    "こんにちは世界",
+    "Привет, мир",
+    "🚀 launch ✨",
+    "naïve café",
+    "e\u{301}toile (combining accent)",
+    "中文测试字符串",
+    "✓ done — 100%",
+    "한국어 텍스트",
+];
+
+const IDENTS: &[&str] = &[
+    "config",
+    "request",
+    "response",
+    "session",
+    "token",
+    "buffer",
+    "cursor",
+    "layout",
+    "thread",
+    "review",
+    "handler",
+    "cache",
+    "parser",
+    "client",
+    "offset",
+    "payload",
+    "retry_count",
+    "deadline",
+];
+
+const TYPES: &[&str] = &[
+    "Config", "Request", "Session", "Token", "Buffer", "Layout", "Review", "Handler", "Cache",
+    "Parser", "Client", "Payload",
+];
+
+const METHODS: &[&str] = &[
+    "clone",
+    "len",
+    "is_empty",
+    "to_string",
+    "iter",
+    "as_ref",
+    "flush",
+    "validate",
+    "resolve",
+    "normalize",
+];
+
+const DIRS: &[&str] = &[
+    "src",
+    "src/api",
+    "src/db",
+    "src/view",
+    "src/net",
+    "crates/core/src",
+    "tests",
+    "docs",
+    "docs/guides",
+];
+
+const FILE_STEMS: &[&str] = &[
+    "auth", "config", "handlers", "pool", "session", "parser", "render", "layout", "cache",
+    "retry", "utils", "metrics", "server", "client", "mod",
+];
+
+const UNICODE_PATHS: &[&str] = &[
+    "docs/résumé.md",
+    "src/i18n/日本語.rs",
+    "src/naïve_impl.rs",
+    "docs/guides/über-setup.md",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::ParsedDiff;
+    use crate::view::map_threads_to_diff;
+
+    #[test]
+    fn test_demo_data_is_deterministic() {
+        let a = DemoClient::new(DemoSize::Small, 7);
+        let b = DemoClient::new(DemoSize::Small, 7);
+        let ids = |c: &DemoClient| -> Vec<String> {
+            c.list_reviews(None)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.review_id)
+                .collect()
+        };
+        assert_eq!(ids(&a), ids(&b));
+
+        let id = &ids(&a)[0];
+        let da = a.load_review_data(id).unwrap().unwrap();
+        let db = b.load_review_data(id).unwrap().unwrap();
+        assert_eq!(da.files[0].diff, db.files[0].diff);
+        assert_ne!(ids(&a), ids(&DemoClient::new(DemoSize::Small, 8)));
+    }
+
+    #[test]
+    fn test_generated_diffs_parse_and_threads_anchor() {
+        let client = DemoClient::new(DemoSize::Medium, DEFAULT_DEMO_SEED);
+        let mut orphaned = 0;
+        let mut old_side = 0;
+        for summary in client.list_reviews(None).unwrap() {
+            let data = client
+                .load_review_data(&summary.review_id)
+                .unwrap()
+                .unwrap();
+            assert_eq!(to_i64(data.threads.len()), summary.thread_count);
+            for file in &data.files {
+                let diff = ParsedDiff::parse(file.diff.as_deref().unwrap());
+                assert!(
+                    diff.warnings.is_empty(),
+                    "{}: {:?}",
+                    file.path,
+                    diff.warnings
+                );
+                let threads: Vec<&ThreadSummary> = data
+                    .threads
+                    .iter()
+                    .filter(|t| t.file_path == file.path)
+                    .collect();
+                let anchors = map_threads_to_diff(&diff, &threads);
+                for thread in threads {
+                    let anchor = anchors.iter().find(|a| a.thread_id == thread.thread_id);
+                    if thread.side == Some(ThreadSide::Old) {
+                        old_side += 1;
+                        assert_eq!(anchor.map(|a| a.side), Some(ThreadSide::Old));
+                    } else if anchor.is_none() {
+                        orphaned += 1;
+                        assert!(file.content.is_some(), "orphan without content");
+                    }
+                }
+            }
+        }
+        assert!(orphaned > 0);
+        assert!(old_side > 0);
+    }
+
+    #[test]
+    fn test_demo_comments_persist() {
+        let client = DemoClient::new(DemoSize::Small, 1);
+        let review = client.list_reviews(None).unwrap().remove(0);
+        client
+            .comment(
+                &review.review_id,
+                "src/new.rs",
+                3,
+                None,
+                ThreadSide::New,
+                "hi",
+            )
+            .unwrap();
+        let data = client.load_review_data(&review.review_id).unwrap().unwrap();
+        let thread = data.threads.last().unwrap();
+        client.reply(&thread.thread_id, "reply", None).unwrap();
+
+        let data = client.load_review_data(&review.review_id).unwrap().unwrap();
+        assert_eq!(data.comments[&thread.thread_id].len(), 2);
+        let summary = client.list_reviews(None).unwrap().remove(0);
+        assert_eq!(summary.thread_count, review.thread_count + 1);
+    }
+
+    #[test]
+    fn test_reply_with_stale_etag_conflicts() {
+        let client = DemoClient::new(DemoSize::Small, 1);
+        let review = client.list_reviews(None).unwrap().remove(0);
+        let data = client.load_review_data(&review.review_id).unwrap().unwrap();
+        let thread = &data.threads[0];
+        let seen = &data.comments[&thread.thread_id];
+        let etag = thread_etag(&thread.status, seen);
+        let expected = ExpectedThread {
+            review_id: &review.review_id,
+            etag: &etag,
+        };
+
+        client
+            .reply(&thread.thread_id, "first", Some(expected))
+            .unwrap();
+        let err = client
+            .reply(&thread.thread_id, "second", Some(expected))
+            .unwrap_err();
+        let conflict = err.downcast_ref::<ThreadConflict>().unwrap();
+        assert_eq!(conflict.comments.len(), seen.len() + 1);
+        assert_eq!(conflict.comments.last().unwrap().body, "first");
+    }
+
+    #[test]
+    fn test_status_change_with_stale_etag_conflicts() {
+        let client = DemoClient::new(DemoSize::Small, 1);
+        let review = client.list_reviews(None).unwrap().remove(0);
+        let data = client.load_review_data(&review.review_id).unwrap().unwrap();
+        let thread = &data.threads[0];
+        let etag = thread_etag(&thread.status, &data.comments[&thread.thread_id]);
+        let expected = ExpectedThread {
+            review_id: &review.review_id,
+            etag: &etag,
+        };
+        let (write, flip): (fn(&DemoClient, &str, _) -> Result<()>, _) = if thread.status == "open"
+        {
+            (DemoClient::resolve_thread, "resolved")
+        } else {
+            (DemoClient::reopen_thread, "open")
+        };
+
+        write(&client, &thread.thread_id, Some(expected)).unwrap();
+        // The same write from the old state finds the thread changed
+        let err = write(&client, &thread.thread_id, Some(expected)).unwrap_err();
+        let conflict = err.downcast_ref::<ThreadConflict>().unwrap();
+        assert_eq!(conflict.status, flip);
+    }
+
+    #[test]
+    fn test_new_verdict_replaces_the_last() {
+        let client = DemoClient::new(DemoSize::Small, 1);
+        let review = client.list_reviews(None).unwrap().remove(0);
+        let id = &review.review_id;
+        client
+            .submit_verdict(id, Verdict::RequestChanges, Some("Needs tests"))
+            .unwrap();
+        client.submit_verdict(id, Verdict::Approve, None).unwrap();
+
+        let votes = client.load_review_data(id).unwrap().unwrap().detail.votes;
+        assert_eq!(votes.len(), 1);
+        assert_eq!(votes[0].vote, Verdict::Approve);
+        assert_eq!(votes[0].reason, None);
+        assert!(client
+            .submit_verdict("cr-none", Verdict::Approve, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_abandon_and_restore_review() {
+        let client = DemoClient::new(DemoSize::Small, 1);
+        let review = client.list_reviews(Some("open")).unwrap().remove(0);
+        let id = &review.review_id;
+        client.abandon_review(id, Some("Split up instead")).unwrap();
+        let detail = client.load_review_data(id).unwrap().unwrap().detail;
+        assert_eq!(detail.status, "abandoned");
+        assert_eq!(detail.abandon_reason.as_deref(), Some("Split up instead"));
+        assert!(client
+            .list_reviews(Some("abandoned"))
+            .unwrap()
+            .iter()
+            .any(|r| &r.review_id == id));
+
+        client.restore_review(id).unwrap();
+        let detail = client.load_review_data(id).unwrap().unwrap().detail;
+        assert_eq!(detail.status, "open");
+        assert_eq!(detail.abandon_reason, None);
+    }
+
+    #[test]
+    fn test_thread_events_follow_status() {
+        let client = DemoClient::new(DemoSize::Medium, DEFAULT_DEMO_SEED);
+        let review = client.list_reviews(None).unwrap().remove(0);
+        let data = client.load_review_data(&review.review_id).unwrap().unwrap();
+        for thread in &data.threads {
+            let events = client.thread_events(&thread.thread_id).unwrap();
+            assert_eq!(events[0].kind, ThreadEventKind::Opened);
+            let resolved = events.last().unwrap().kind == ThreadEventKind::Resolved;
+            assert_eq!(
+                resolved,
+                thread.status == "resolved",
+                "{}",
+                thread.thread_id
+            );
+        }
+        assert!(client.thread_events("th-missing").is_err());
+    }
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(0), "2025-01-01T00:00:00Z");
+        assert_eq!(timestamp(59 * 24 * 60 + 61), "2025-03-01T01:01:00Z");
+    }
+}
@@ -0,0 +1,119 @@
+//! Line-ending and encoding detection for file text.
+//!
+//! Diff and file text arrive already decoded (lossily) as UTF-8. These helpers
+//! record what was lost or normalized along the way and make the text safe to
+//! put into terminal cells.
+
+use std::borrow::Cow;
+
+use crate::i18n::Text;
+
+/// Line-ending and encoding facts gathered while reading file text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextEncoding {
+    /// Lines that ended in `\r\n`
+    pub crlf_lines: usize,
+    /// Lines that ended in a bare `\n`
+    pub lf_lines: usize,
+    /// Text contained U+FFFD or NUL, i.e. it was not valid UTF-8 upstream
+    pub lossy: bool,
+}
+
+impl TextEncoding {
+    /// Record one line of text (with any trailing CR already removed).
+    pub fn observe(&mut self, content: &str, crlf: bool) {
+        if crlf {
+            self.crlf_lines += 1;
+        } else {
+            self.lf_lines += 1;
+        }
+        if content.contains(['\u{FFFD}', '\0']) {
+            self.lossy = true;
+        }
+    }
+
+    /// Combine facts from another source for the same file.
+    pub const fn merge(&mut self, other: Self) {
+        self.crlf_lines += other.crlf_lines;
+        self.lf_lines += other.lf_lines;
+        self.lossy |= other.lossy;
+    }
+
+    /// Header badge for line endings, if they aren't plain LF.
+    #[must_use]
+    pub const fn eol_label(&self) -> Option<Text> {
+        match (self.crlf_lines, self.lf_lines) {
+            (0, _) => None,
+            (_, 0) => Some(Text::BadgeCrlf),
+            _ => Some(Text::BadgeMixedEol),
+        }
+    }
+
+    /// Header badge for the text encoding, if it wasn't clean UTF-8.
+    #[must_use]
+    pub const fn encoding_label(&self) -> Option<Text> {
+        if self.lossy {
+            Some(Text::BadgeNonUtf8)
+        } else {
+            None
+        }
+    }
+}
+
+/// Strip a trailing `\r`, returning the line and whether one was present.
+#[must_use]
+pub fn split_cr(line: &str) -> (&str, bool) {
+    line.strip_suffix('\r')
+        .map_or((line, false), |stripped| (stripped, true))
+}
+
+/// Replace control characters (other than tab) with their Unicode control
+/// pictures so stray `\r`, ESC, etc. can't move the terminal cursor.
+#[must_use]
+pub fn sanitize_control_chars(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(is_unsafe_control) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.chars()
+            .map(|c| {
+                if is_unsafe_control(c) {
+                    control_picture(c)
+                } else {
+                    c
+                }
+            })
+            .collect(),
+    )
+}
+
+fn is_unsafe_control(c: char) -> bool {
+    c.is_control() && c != '\t'
+}
+
+fn control_picture(c: char) -> char {
+    match c {
+        '\u{7f}' => '\u{2421}',
+        c if (c as u32) < 0x20 => char::from_u32(0x2400 + c as u32).unwrap_or('\u{FFFD}'),
+        _ => '\u{FFFD}',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eol_labels_and_sanitize() {
+        let mut enc = TextEncoding::default();
+        enc.observe("a", true);
+        assert_eq!(enc.eol_label(), Some(Text::BadgeCrlf));
+        enc.observe("b\u{FFFD}", false);
+        assert_eq!(enc.eol_label(), Some(Text::BadgeMixedEol));
+        assert_eq!(enc.encoding_label(), Some(Text::BadgeNonUtf8));
+
+        assert_eq!(split_cr("x\r"), ("x", true));
+        assert_eq!(sanitize_control_chars("a\tb"), "a\tb");
+        assert_eq!(sanitize_control_chars("a\rb\u{1b}"), "a\u{240d}b\u{241b}");
+    }
+}
@@ -0,0 +1,195 @@
+//! Expanding a hunk's context: unchanged file lines spliced onto either end
+//! of a hunk, up to the neighbouring hunk, which it then merges with.
+//!
+//! Only new-side line numbers are read from the file. Old-side numbers
+//! follow from the offset between the sides next to the hunk, which
+//! unchanged lines share.
+
+use std::ops::RangeInclusive;
+
+use super::encoding::{sanitize_control_chars, split_cr};
+use super::parse::{DiffHunk, DiffLine, DiffLineKind, ParsedDiff};
+
+/// First line a hunk covers on one side. An empty side's start names the
+/// line before the hunk.
+const fn first_line(start: u32, count: u32) -> u32 {
+    if count == 0 {
+        start + 1
+    } else {
+        start
+    }
+}
+
+/// New-side lines hidden between hunk `index` and the one above it (or the
+/// start of the file).
+#[must_use]
+pub fn hidden_lines_above(hunks: &[DiffHunk], index: usize) -> u32 {
+    let Some(hunk) = hunks.get(index).filter(|hunk| hunk.parents == 1) else {
+        return 0;
+    };
+    let above = index
+        .checked_sub(1)
+        .and_then(|prev| hunks.get(prev))
+        .map_or(1, |prev| {
+            first_line(prev.new_start, prev.new_count) + prev.new_count
+        });
+    first_line(hunk.new_start, hunk.new_count).saturating_sub(above)
+}
+
+impl ParsedDiff {
+    /// The new-side lines expanding hunk `index` by up to `count` lines
+    /// would add: above it with `up`, else below it. Stops at the
+    /// neighbouring hunk; `None` when nothing is hidden there. Below the
+    /// last hunk the window may run past the end of the file.
+    #[must_use]
+    pub fn context_gap(&self, index: usize, up: bool, count: u32) -> Option<RangeInclusive<u32>> {
+        let hunk = self.hunks.get(index)?;
+        // Combined diffs and deleted files have no new side to read
+        if hunk.parents != 1 || count == 0 || (hunk.new_start == 0 && hunk.new_count == 0) {
+            return None;
+        }
+        let first = first_line(hunk.new_start, hunk.new_count);
+        let (start, end) = if up {
+            let hidden = hidden_lines_above(&self.hunks, index);
+            (first - hidden.min(count), first.checked_sub(1)?)
+        } else {
+            let start = first + hunk.new_count;
+            let next = self
+                .hunks
+                .get(index + 1)
+                .map_or(u32::MAX, |next| first_line(next.new_start, next.new_count));
+            (start, next.saturating_sub(1).min(start + (count - 1)))
+        };
+        (start <= end).then_some(start..=end)
+    }
+
+    /// Splice `lines`, unchanged lines starting at new-side line `first`,
+    /// onto hunk `index`: above it with `up`, else below it. A hunk whose
+    /// gap to its neighbour closes merges with it. Returns whether it did.
+    pub fn expand_hunk(&mut self, index: usize, up: bool, first: u32, lines: &[String]) -> bool {
+        let Some(hunk) = self.hunks.get_mut(index) else {
+            return false;
+        };
+        let added = u32::try_from(lines.len()).unwrap_or(u32::MAX);
+        if added == 0 {
+            return false;
+        }
+        let old_first = first_line(hunk.old_start, hunk.old_count);
+        let new_first = first_line(hunk.new_start, hunk.new_count);
+        // Old line of `first`: the sides are offset the same next to the hunk
+        let old_line = if up {
+            (old_first + first).saturating_sub(new_first)
+        } else {
+            (old_first + hunk.old_count + first).saturating_sub(new_first + hunk.new_count)
+        };
+        let context = lines.iter().zip(0..).map(|(line, i)| {
+            let (line, crlf) = split_cr(line);
+            DiffLine {
+                kind: DiffLineKind::Context,
+                old_line: Some(old_line + i),
+                new_line: Some(first + i),
+                content: sanitize_control_chars(line).into_owned(),
+                origins: None,
+                no_newline: false,
+                crlf,
+            }
+        });
+        if up {
+            hunk.lines.splice(0..0, context);
+            hunk.old_start = old_line;
+            hunk.new_start = first;
+        } else {
+            hunk.lines.extend(context);
+            hunk.old_start = old_first;
+            hunk.new_start = new_first;
+        }
+        hunk.old_count += added;
+        hunk.new_count += added;
+        hunk.header = range_header(hunk);
+
+        let upper = if up {
+            index.checked_sub(1)
+        } else {
+            Some(index)
+        };
+        let merged = upper.is_some_and(|upper| {
+            upper + 1 < self.hunks.len() && hidden_lines_above(&self.hunks, upper + 1) == 0
+        });
+        if let Some(upper) = upper.filter(|_| merged) {
+            let lower = self.hunks.remove(upper + 1);
+            let hunk = &mut self.hunks[upper];
+            hunk.old_count += lower.old_count;
+            hunk.new_count += lower.new_count;
+            hunk.lines.extend(lower.lines);
+            hunk.folded = false;
+            hunk.header = range_header(hunk);
+        }
+        merged
+    }
+}
+
+/// An `@@` line for the hunk's ranges.
+fn range_header(hunk: &DiffHunk) -> String {
+    format!(
+        "@@ -{},{} +{},{} @@",
+        hunk.old_start, hunk.old_count, hunk.new_start, hunk.new_count
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "--- a/f\n+++ b/f\n\
+                        @@ -5,3 +5,3 @@\n five\n-six\n+SIX\n seven\n\
+                        @@ -20,2 +20,3 @@\n twenty\n+new\n twenty-one\n";
+
+    fn numbered(lines: RangeInclusive<u32>) -> Vec<String> {
+        lines.map(|n| format!("line {n}")).collect()
+    }
+
+    #[test]
+    fn test_context_gap_stops_at_neighbours() {
+        let diff = ParsedDiff::parse(DIFF);
+        assert_eq!(hidden_lines_above(&diff.hunks, 0), 4);
+        assert_eq!(hidden_lines_above(&diff.hunks, 1), 12);
+        assert_eq!(diff.context_gap(0, true, 20), Some(1..=4));
+        assert_eq!(diff.context_gap(0, false, 5), Some(8..=12));
+        assert_eq!(diff.context_gap(1, true, 5), Some(15..=19));
+        assert_eq!(diff.context_gap(1, false, 5), Some(23..=27));
+        // Nothing above a hunk at the top of the file
+        let top = ParsedDiff::parse("--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n-a\n+A\n b\n");
+        assert_eq!(top.context_gap(0, true, 20), None);
+    }
+
+    #[test]
+    fn test_expand_hunk_numbers_lines_and_merges() {
+        let mut diff = ParsedDiff::parse(DIFF);
+        assert!(!diff.expand_hunk(1, true, 15, &numbered(15..=19)));
+        let hunk = &diff.hunks[1];
+        assert_eq!((hunk.old_start, hunk.old_count), (15, 7));
+        assert_eq!((hunk.new_start, hunk.new_count), (15, 8));
+        assert_eq!(hunk.header, "@@ -15,7 +15,8 @@");
+        assert_eq!(hunk.lines[0].old_line, Some(15));
+        assert_eq!(hunk.lines[0].content, "line 15");
+
+        // Below the first hunk, old and new lines line up again
+        assert!(!diff.expand_hunk(0, false, 8, &numbered(8..=10)));
+        assert_eq!(diff.hunks[0].lines.last().unwrap().old_line, Some(10));
+        assert_eq!(diff.context_gap(0, false, 20), Some(11..=14));
+
+        // Closing the gap leaves one hunk
+        assert!(diff.expand_hunk(0, false, 11, &numbered(11..=14)));
+        assert_eq!(diff.hunks.len(), 1);
+        let hunk = &diff.hunks[0];
+        assert_eq!(hunk.header, "@@ -5,17 +5,18 @@");
+        assert_eq!(hunk.lines.len(), 19);
+        let twenty = hunk.lines.iter().find(|l| l.content == "twenty").unwrap();
+        assert_eq!((twenty.old_line, twenty.new_line), (Some(20), Some(20)));
+
+        // Below the last hunk, after the added line
+        assert!(!diff.expand_hunk(0, false, 23, &numbered(23..=24)));
+        let last = diff.hunks[0].lines.last().unwrap();
+        assert_eq!((last.old_line, last.new_line), (Some(23), Some(24)));
+    }
+}
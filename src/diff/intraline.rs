@@ -0,0 +1,213 @@
+//! Word-level differences between a removed line and the added line that
+//! replaces it.
+//!
+//! Lines are split into words (runs of letters, digits and `_`), runs of
+//! whitespace and single punctuation chars, and the token sequences are
+//! compared. Changed tokens become char ranges to emphasize. Lines that
+//! share too little aren't emphasized at all: a line that was rewritten
+//! reads better without half of it lit up.
+
+use std::ops::Range;
+
+/// Token count (per line) above which lines aren't compared; this runs
+/// on every frame that draws the file.
+const MAX_TOKENS: usize = 120;
+/// Share of chars, out of both lines, that must be unchanged.
+const MIN_COMMON: f32 = 0.4;
+
+/// Changed char ranges in the old line and in the new one.
+pub type LineChanges = (Vec<Range<usize>>, Vec<Range<usize>>);
+
+/// Char ranges that differ in `old` and in `new`, or `None` when the lines
+/// are too different (or too long) to be worth emphasizing.
+#[must_use]
+pub fn intraline_changes(old: &str, new: &str) -> Option<LineChanges> {
+    let (changes, common_share) = compare(old, new)?;
+    (common_share >= MIN_COMMON).then_some(changes)
+}
+
+/// How alike `old` and `new` are, from 0 to 1: the share of their chars
+/// left unchanged. Lines too different to emphasize score 0.
+#[must_use]
+pub(super) fn line_similarity(old: &str, new: &str) -> f32 {
+    compare(old, new)
+        .map(|(_, share)| share)
+        .filter(|&share| share >= MIN_COMMON)
+        .unwrap_or(0.0)
+}
+
+/// Changed char ranges and the share of chars left unchanged, or `None`
+/// for lines over [`MAX_TOKENS`].
+fn compare(old: &str, new: &str) -> Option<(LineChanges, f32)> {
+    let old = tokens(old);
+    let new = tokens(new);
+    if old.len() > MAX_TOKENS || new.len() > MAX_TOKENS {
+        return None;
+    }
+    let old_text: Vec<&str> = old.iter().map(|t| t.text).collect();
+    let new_text: Vec<&str> = new.iter().map(|t| t.text).collect();
+
+    let (mut old_changed, mut new_changed) = (Vec::new(), Vec::new());
+    let mut common = 0;
+    let (mut i, mut j) = (0, 0);
+    for edit in diff_tokens(&old_text, &new_text) {
+        match edit {
+            Edit::Same => {
+                common += old[i].chars.len() * 2;
+                i += 1;
+                j += 1;
+            }
+            Edit::Removed => {
+                push_range(&mut old_changed, old[i].chars.clone());
+                i += 1;
+            }
+            Edit::Added => {
+                push_range(&mut new_changed, new[j].chars.clone());
+                j += 1;
+            }
+        }
+    }
+
+    if old_changed.is_empty() && new_changed.is_empty() {
+        return Some(((old_changed, new_changed), 1.0));
+    }
+    let total: usize = old.iter().chain(&new).map(|t| t.chars.len()).sum();
+    #[allow(clippy::cast_precision_loss)]
+    let common_share = common as f32 / total.max(1) as f32;
+    Some(((old_changed, new_changed), common_share))
+}
+
+/// One step of turning a token sequence into another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Edit {
+    /// The next token of both is kept
+    Same,
+    /// The next old token is dropped
+    Removed,
+    /// The next new token is inserted
+    Added,
+}
+
+/// Edits turning `old` into `new`, keeping their longest common
+/// subsequence. Removals come before additions at each change.
+pub(super) fn diff_tokens(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    // lengths[i * width + j]: LCS of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut lengths = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = if old[i] == new[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push(Edit::Same);
+            i += 1;
+            j += 1;
+        } else if j == new.len()
+            || (i < old.len() && lengths[(i + 1) * width + j] >= lengths[i * width + j + 1])
+        {
+            edits.push(Edit::Removed);
+            i += 1;
+        } else {
+            edits.push(Edit::Added);
+            j += 1;
+        }
+    }
+    edits
+}
+
+/// A token and the chars it covers in its line.
+pub(super) struct Token<'a> {
+    pub(super) text: &'a str,
+    chars: Range<usize>,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Class {
+    Word,
+    Space,
+    Other,
+}
+
+fn class(c: char) -> Class {
+    if c.is_alphanumeric() || c == '_' {
+        Class::Word
+    } else if c.is_whitespace() {
+        Class::Space
+    } else {
+        Class::Other
+    }
+}
+
+/// Split `line` into words, whitespace runs and single punctuation chars.
+pub(super) fn tokens(line: &str) -> Vec<Token<'_>> {
+    // (byte start, char range, class) per token
+    let mut spans: Vec<(usize, Range<usize>, Class)> = Vec::new();
+    for (char_index, (byte, c)) in line.char_indices().enumerate() {
+        let class = class(c);
+        match spans.last_mut() {
+            Some((_, chars, last)) if *last == class && class != Class::Other => {
+                chars.end = char_index + 1;
+            }
+            _ => spans.push((byte, char_index..char_index + 1, class)),
+        }
+    }
+    let ends = spans
+        .iter()
+        .skip(1)
+        .map(|(byte, _, _)| *byte)
+        .chain([line.len()]);
+    spans
+        .iter()
+        .zip(ends)
+        .map(|((start, chars, _), end)| Token {
+            text: &line[*start..end],
+            chars: chars.clone(),
+        })
+        .collect()
+}
+
+/// Append `range`, joining it to the last range when they touch.
+fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_words_are_char_ranges() {
+        let (old, new) =
+            intraline_changes("let total = count * 2;", "let total = count * size;").unwrap();
+        assert_eq!(old, [20..21]);
+        assert_eq!(new, [20..24]);
+
+        // Offsets are chars, not bytes
+        let (old, new) = intraline_changes("naïve = old_value", "naïve = new_value").unwrap();
+        assert_eq!(old, [8..17]);
+        assert_eq!(new, [8..17]);
+
+        let (old, new) = intraline_changes("same", "same").unwrap();
+        assert!(old.is_empty() && new.is_empty());
+    }
+
+    #[test]
+    fn test_rewritten_lines_are_not_emphasized() {
+        assert_eq!(
+            intraline_changes("fn parse(input: &str)", "return Err(error);"),
+            None
+        );
+    }
+}
@@ -1,5 +1,20 @@
 //! Unified diff parser and rendering
 
+mod encoding;
+mod expand;
+mod intraline;
+mod normalize;
+mod pairing;
 mod parse;
+mod word_diff;
 
-pub use parse::{hunk_exclusion_ranges, DiffHunk, DiffLine, DiffLineKind, ParsedDiff};
+pub use encoding::{sanitize_control_chars, split_cr, TextEncoding};
+pub use expand::hidden_lines_above;
+pub use intraline::intraline_changes;
+pub use normalize::normalize_json_diff;
+pub use pairing::{pair_lines, PairedRow};
+pub use parse::{
+    hunk_exclusion_ranges, BinaryChange, DiffHunk, DiffLine, DiffLineKind, ParseWarning,
+    ParseWarningKind, ParsedDiff,
+};
+pub use word_diff::{is_prose_path, word_diff, wrap_spans, WordKind, WordLine, WordSpan};
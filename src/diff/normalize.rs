@@ -0,0 +1,200 @@
+//! Normalized diffs for JSON that doesn't diff well by line: notebooks and
+//! JSON files with very long lines (minified or single-line documents).
+//!
+//! When a diff holds both whole documents, each side is parsed and
+//! pretty-printed (keys sorted, two-space indent) and the results are
+//! diffed again by line. Line numbers then refer to the pretty-printed
+//! text, not the file.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use super::intraline::{diff_tokens, Edit};
+use super::parse::{DiffLineKind, ParsedDiff};
+
+/// A `.json` line longer than this makes the file a candidate.
+const LONG_LINE: usize = 500;
+
+/// Unchanged lines shown around each change.
+const CONTEXT: usize = 3;
+
+/// Line pairs above which the middle isn't compared; it's shown as all
+/// removed, then all added.
+const MAX_CELLS: usize = 4_000_000;
+
+/// `diff` with both sides pretty-printed, for notebooks and long-lined JSON.
+/// `None` if the file isn't one, the diff doesn't cover whole documents on
+/// both sides, or normalizing leaves nothing changed.
+#[must_use]
+pub fn normalize_json_diff(path: &str, diff: &ParsedDiff) -> Option<ParsedDiff> {
+    let ext = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+    let long_lines = || {
+        diff.hunks
+            .iter()
+            .flat_map(|hunk| &hunk.lines)
+            .any(|line| line.content.len() > LONG_LINE)
+    };
+    if !(ext == "ipynb" || ext == "json" && long_lines()) || diff.is_combined() {
+        return None;
+    }
+    // Only a single hunk from the top can hold the whole file; a side that
+    // doesn't parse was cut off
+    let [hunk] = diff.hunks.as_slice() else {
+        return None;
+    };
+    if hunk.old_start > 1 || hunk.new_start > 1 {
+        return None;
+    }
+    let side = |kind: DiffLineKind| {
+        let lines: Vec<&str> = hunk
+            .lines
+            .iter()
+            .filter(|line| line.kind == DiffLineKind::Context || line.kind == kind)
+            .map(|line| line.content.as_str())
+            .collect();
+        pretty(&lines.join("\n"))
+    };
+    let old = side(DiffLineKind::Removed)?;
+    let new = side(DiffLineKind::Added)?;
+    if old == new {
+        return None;
+    }
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    Some(ParsedDiff::parse(&unified_diff(path, &old, &new)))
+}
+
+/// `text` pretty-printed, or empty for a side that doesn't exist.
+fn pretty(text: &str) -> Option<String> {
+    if text.trim().is_empty() {
+        return Some(String::new());
+    }
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    serde_json::to_string_pretty(&value).ok()
+}
+
+/// Line edits from `old` to `new`, comparing only what's between the
+/// common prefix and suffix.
+fn line_edits(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_mid, new_mid) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+    let middle = if old_mid.len().saturating_mul(new_mid.len()) > MAX_CELLS {
+        [
+            vec![Edit::Removed; old_mid.len()],
+            vec![Edit::Added; new_mid.len()],
+        ]
+        .concat()
+    } else {
+        diff_tokens(old_mid, new_mid)
+    };
+    [vec![Edit::Same; prefix], middle, vec![Edit::Same; suffix]].concat()
+}
+
+/// Unified diff text from `old` to `new`, with [`CONTEXT`] lines around
+/// each change.
+fn unified_diff(path: &str, old: &[&str], new: &[&str]) -> String {
+    let edits = line_edits(old, new);
+    // Index into each side before each edit
+    let mut positions = Vec::with_capacity(edits.len() + 1);
+    let (mut i, mut j) = (0, 0);
+    for edit in &edits {
+        positions.push((i, j));
+        match edit {
+            Edit::Same => (i, j) = (i + 1, j + 1),
+            Edit::Removed => i += 1,
+            Edit::Added => j += 1,
+        }
+    }
+    positions.push((i, j));
+
+    let mut hunks: Vec<std::ops::Range<usize>> = Vec::new();
+    for (k, _) in edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| **edit != Edit::Same)
+    {
+        let range = k.saturating_sub(CONTEXT)..(k + 1 + CONTEXT).min(edits.len());
+        match hunks.last_mut() {
+            Some(last) if range.start <= last.end => last.end = range.end,
+            _ => hunks.push(range),
+        }
+    }
+
+    let mut out = format!("--- a/{path}\n+++ b/{path}\n");
+    for range in hunks {
+        let (old_at, new_at) = positions[range.start];
+        let (old_end, new_end) = positions[range.end];
+        let (old_count, new_count) = (old_end - old_at, new_end - new_at);
+        // An empty side is numbered by the line before it
+        let start = |at: usize, count: usize| if count == 0 { at } else { at + 1 };
+        let _ = writeln!(
+            out,
+            "@@ -{},{old_count} +{},{new_count} @@",
+            start(old_at, old_count),
+            start(new_at, new_count)
+        );
+        for k in range {
+            let (i, j) = positions[k];
+            let (sign, line) = match edits[k] {
+                Edit::Same => (' ', new[j]),
+                Edit::Removed => ('-', old[i]),
+                Edit::Added => ('+', new[j]),
+            };
+            out.push(sign);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_line_json_is_pretty_printed_and_rediffed() {
+        let long = "x".repeat(LONG_LINE);
+        let diff = ParsedDiff::parse(&format!(
+            "--- a/data.json\n+++ b/data.json\n@@ -1 +1 @@\n-{{\"b\":1,\"a\":\"{long}\"}}\n+{{\"b\":2,\"a\":\"{long}\"}}\n"
+        ));
+        let normalized = normalize_json_diff("data.json", &diff).unwrap();
+        let lines: Vec<(DiffLineKind, &str)> = normalized.hunks[0]
+            .lines
+            .iter()
+            .map(|line| (line.kind, line.content.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (DiffLineKind::Context, "{"),
+                (DiffLineKind::Context, &*format!("  \"a\": \"{long}\",")),
+                (DiffLineKind::Removed, "  \"b\": 1"),
+                (DiffLineKind::Added, "  \"b\": 2"),
+                (DiffLineKind::Context, "}"),
+            ]
+        );
+        let hunk = &normalized.hunks[0];
+        assert_eq!((hunk.new_start, hunk.new_count), (1, 4));
+    }
+
+    #[test]
+    fn test_partial_or_short_json_is_left_alone() {
+        let short =
+            ParsedDiff::parse("--- a/a.json\n+++ b/a.json\n@@ -1 +1 @@\n-{\"a\":1}\n+{\"a\":2}\n");
+        assert!(normalize_json_diff("a.json", &short).is_none());
+        let cut = ParsedDiff::parse(
+            "--- a/n.ipynb\n+++ b/n.ipynb\n@@ -10,3 +10,3 @@\n {\n-  \"a\": 1\n+  \"a\": 2\n",
+        );
+        assert!(normalize_json_diff("n.ipynb", &cut).is_none());
+    }
+}
@@ -0,0 +1,129 @@
+//! Side-by-side rows for a run of removed lines followed by added ones.
+//!
+//! Lines are paired by similarity rather than only by position, so a block
+//! replaced by a similar one (a line inserted or dropped partway through, a
+//! rename across several lines) puts each line across from its counterpart
+//! and intra-line emphasis compares the right pair. Pairs keep the order of
+//! both sides; lines between two pairs are set side by side by position.
+
+use std::ops::Range;
+
+use super::intraline::line_similarity;
+
+/// Line pairs above which a run is laid out by position only; the matcher
+/// compares every pair, on every frame that lays out the file.
+const MAX_PAIRS: usize = 400;
+
+/// A row: the index of its removed line, of its added line, or both.
+pub type PairedRow = (Option<usize>, Option<usize>);
+
+/// Rows for removed lines `old` and added lines `new`.
+#[must_use]
+pub fn pair_lines(old: &[&str], new: &[&str]) -> Vec<PairedRow> {
+    if old.is_empty() || new.is_empty() || old.len() * new.len() > MAX_PAIRS {
+        return positional(0..old.len(), 0..new.len());
+    }
+    let mut rows = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    for (pi, pj) in similar_pairs(old, new) {
+        rows.extend(positional(i..pi, j..pj));
+        rows.push((Some(pi), Some(pj)));
+        (i, j) = (pi + 1, pj + 1);
+    }
+    rows.extend(positional(i..old.len(), j..new.len()));
+    rows
+}
+
+/// Rows pairing `old` and `new` indexes by position.
+fn positional(old: Range<usize>, new: Range<usize>) -> Vec<PairedRow> {
+    let len = old.len().max(new.len());
+    (0..len)
+        .map(|k| {
+            (
+                (k < old.len()).then(|| old.start + k),
+                (k < new.len()).then(|| new.start + k),
+            )
+        })
+        .collect()
+}
+
+/// Index pairs, in order on both sides, with the greatest total similarity.
+fn similar_pairs(old: &[&str], new: &[&str]) -> Vec<(usize, usize)> {
+    let width = new.len() + 1;
+    let similarity: Vec<f32> = old
+        .iter()
+        .flat_map(|a| new.iter().map(move |b| line_similarity(a, b)))
+        .collect();
+    // best[i * width + j]: greatest total for old[i..] and new[j..], and
+    // the step that reaches it
+    let mut best = vec![0.0f32; (old.len() + 1) * width];
+    let mut step = vec![Step::SkipOld; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            let pair = similarity[i * new.len() + j];
+            let paired = best[(i + 1) * width + j + 1] + pair;
+            let skip_old = best[(i + 1) * width + j];
+            let skip_new = best[i * width + j + 1];
+            let cell = i * width + j;
+            (best[cell], step[cell]) = if pair > 0.0 && paired >= skip_old.max(skip_new) {
+                (paired, Step::Pair)
+            } else if skip_old >= skip_new {
+                (skip_old, Step::SkipOld)
+            } else {
+                (skip_new, Step::SkipNew)
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        match step[i * width + j] {
+            Step::Pair => {
+                pairs.push((i, j));
+                i += 1;
+                j += 1;
+            }
+            Step::SkipOld => i += 1,
+            Step::SkipNew => j += 1,
+        }
+    }
+    pairs
+}
+
+/// Best move from a cell of the matcher's table.
+#[derive(Clone, Copy)]
+enum Step {
+    Pair,
+    SkipOld,
+    SkipNew,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_similar_lines_pair_across_an_inserted_line() {
+        let old = ["let a = parse(input);", "let b = check(a);"];
+        let new = [
+            "// Parse first",
+            "let a = parse(input)?;",
+            "let b = check(&a);",
+        ];
+        assert_eq!(
+            pair_lines(&old, &new),
+            [(None, Some(0)), (Some(0), Some(1)), (Some(1), Some(2))]
+        );
+    }
+
+    #[test]
+    fn test_unrelated_lines_pair_by_position() {
+        let old = ["fn parse(input: &str)", "x"];
+        let new = ["return Err(error);"];
+        assert_eq!(
+            pair_lines(&old, &new),
+            [(Some(0), Some(0)), (Some(1), None)]
+        );
+    }
+}
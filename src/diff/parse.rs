@@ -2,12 +2,235 @@
 //!
 //! Parses standard unified diff format into structured data.
 
+use std::collections::BTreeSet;
+
+use super::encoding::{sanitize_control_chars, split_cr, TextEncoding};
+use crate::i18n::{tr, tr_fmt, Text};
+use crate::text::format_size;
+
 /// A parsed unified diff
 #[derive(Debug, Clone, Default)]
 pub struct ParsedDiff {
     pub file_a: Option<String>,
     pub file_b: Option<String>,
     pub hunks: Vec<DiffHunk>,
+    /// Problems recovered from while parsing, in input order
+    pub warnings: Vec<ParseWarning>,
+    /// Line endings and encoding seen in hunk lines
+    pub encoding: TextEncoding,
+    /// Set for a binary file, which has no hunks
+    pub binary: Option<BinaryChange>,
+}
+
+/// A binary file's change: whether it was added or deleted and its sizes
+/// in bytes, where the diff (or `vcs::get_file_diff`) knew them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BinaryChange {
+    pub added: bool,
+    pub deleted: bool,
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+}
+
+impl BinaryChange {
+    /// "Binary file changed (12.3 KB → 14.1 KB)", sizes left out when
+    /// unknown.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let (label, sizes) = if self.added {
+            (Text::BinaryAdded, self.new_size.map(format_size))
+        } else if self.deleted {
+            (Text::BinaryDeleted, self.old_size.map(format_size))
+        } else {
+            let sizes = self
+                .old_size
+                .zip(self.new_size)
+                .map(|(old, new)| format!("{} → {}", format_size(old), format_size(new)));
+            (Text::BinaryChanged, sizes)
+        };
+        sizes.map_or_else(
+            || tr(label).to_string(),
+            |sizes| format!("{} ({sizes})", tr(label)),
+        )
+    }
+}
+
+/// A recoverable problem found while parsing a diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// 1-based line number in the raw diff text
+    pub line: usize,
+    pub kind: ParseWarningKind,
+}
+
+/// What went wrong at a [`ParseWarning`] location
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarningKind {
+    /// `@@` header whose ranges could not be read; line numbers were inferred
+    MalformedHunkHeader(String),
+    /// Hunk lines appeared without a preceding `@@` header
+    MissingHunkHeader,
+    /// Input ended before the line counts declared in the hunk header
+    TruncatedHunk {
+        expected_old: u32,
+        expected_new: u32,
+        actual_old: u32,
+        actual_new: u32,
+    },
+    /// Hunk line without a `+`, `-` or space prefix; shown as context
+    UnrecognizedLine,
+}
+
+impl std::fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&tr_fmt(Text::WarnAtLine, &[&self.line]))?;
+        let detail = match &self.kind {
+            ParseWarningKind::MalformedHunkHeader(header) => {
+                tr_fmt(Text::WarnMalformedHunkHeader, &[header])
+            }
+            ParseWarningKind::MissingHunkHeader => tr(Text::WarnMissingHunkHeader).to_string(),
+            ParseWarningKind::TruncatedHunk {
+                expected_old,
+                expected_new,
+                actual_old,
+                actual_new,
+            } => tr_fmt(
+                Text::WarnTruncatedHunk,
+                &[expected_old, expected_new, actual_old, actual_new],
+            ),
+            ParseWarningKind::UnrecognizedLine => tr(Text::WarnUnrecognizedLine).to_string(),
+        };
+        f.write_str(&detail)
+    }
+}
+
+/// Raw diff lines (trailing `\r` kept) paired with their 0-based index
+type DiffLines<'a> = std::iter::Peekable<std::iter::Enumerate<std::str::SplitTerminator<'a, char>>>;
+
+/// Ranges read from (or inferred for) a hunk's `@@` line.
+/// Counts are `None` when the header didn't provide them.
+struct HunkHeader {
+    /// 0-based index of the header line (or first body line if synthetic)
+    line: usize,
+    text: String,
+    parents: usize,
+    old_start: u32,
+    old_count: Option<u32>,
+    new_start: u32,
+    new_count: Option<u32>,
+}
+
+impl HunkHeader {
+    /// Parse `@@ -start,count +start,count @@ optional context`, or the
+    /// combined form `@@@ -a,b -c,d +e,f @@@` with one `-` range per parent.
+    fn parse(line: usize, header: &str) -> Option<Self> {
+        let parents = header_parents(header);
+        let parts: Vec<&str> = header.split_whitespace().collect();
+        if parts.len() < parents + 2 || parts[0].len() != parents + 1 {
+            return None;
+        }
+        for range in &parts[2..=parents] {
+            ParsedDiff::parse_range(range.strip_prefix('-')?)?;
+        }
+        let (old_start, old_count) = ParsedDiff::parse_range(parts[1].strip_prefix('-')?)?;
+        let (new_start, new_count) =
+            ParsedDiff::parse_range(parts[parents + 1].strip_prefix('+')?)?;
+        Some(Self {
+            line,
+            text: header.to_string(),
+            parents,
+            old_start,
+            old_count: Some(old_count),
+            new_start,
+            new_count: Some(new_count),
+        })
+    }
+
+    /// Continue numbering after `prev` (or from line 1) when the header is
+    /// missing or unreadable.
+    fn inferred(line: usize, text: &str, prev: Option<&DiffHunk>) -> Self {
+        let (old_start, new_start) = prev.map_or((1, 1), |h| {
+            (h.old_start + h.old_count, h.new_start + h.new_count)
+        });
+        Self {
+            line,
+            text: text.to_string(),
+            parents: header_parents(text),
+            old_start,
+            old_count: None,
+            new_start,
+            new_count: None,
+        }
+    }
+}
+
+fn mark_no_newline(hunk: &mut DiffHunk) {
+    if let Some(last) = hunk.lines.last_mut() {
+        last.no_newline = true;
+    }
+}
+
+/// Parent count implied by the run of `@` opening a hunk header
+fn header_parents(header: &str) -> usize {
+    header
+        .bytes()
+        .take_while(|b| *b == b'@')
+        .count()
+        .saturating_sub(1)
+        .max(1)
+}
+
+/// Split a combined-diff body line into kind, content and origin markers.
+/// A line is in the merge result unless some parent column is `-`.
+fn split_combined_line(line: &str, parents: usize) -> Option<(DiffLineKind, &str, Option<&str>)> {
+    let markers = line.get(..parents)?;
+    if !markers.bytes().all(|b| matches!(b, b' ' | b'+' | b'-')) {
+        return None;
+    }
+    let kind = if markers.contains('-') {
+        DiffLineKind::Removed
+    } else if markers.contains('+') {
+        DiffLineKind::Added
+    } else {
+        DiffLineKind::Context
+    };
+    Some((kind, &line[parents..], Some(markers)))
+}
+
+fn is_binary_notice(line: &str) -> bool {
+    line == "GIT binary patch" || (line.starts_with("Binary files ") && line.ends_with(" differ"))
+}
+
+/// Note a binary notice or a `literal <size>`/`delta <size>` line of a
+/// binary patch: the first block is the forward (new) side, the second the
+/// reverse (old). A delta's size isn't the file's.
+fn read_binary_line(binary: &mut Option<BinaryChange>, blocks: &mut usize, line: &str) {
+    if is_binary_notice(line) {
+        let change = binary.get_or_insert_with(BinaryChange::default);
+        change.added |= line.starts_with("Binary files /dev/null ");
+        change.deleted |= line.ends_with(" and /dev/null differ");
+        return;
+    }
+    let Some(change) = binary.as_mut() else {
+        return;
+    };
+    let size = if let Some(size) = line.strip_prefix("literal ") {
+        size.parse().ok()
+    } else if line.starts_with("delta ") {
+        None
+    } else {
+        return;
+    };
+    match *blocks {
+        0 => change.new_size = size,
+        1 => change.old_size = size,
+        _ => {}
+    }
+    *blocks += 1;
+}
+
+fn is_hunk_body_line(line: &str) -> bool {
+    line.starts_with(['+', '-', ' '])
 }
 
 /// Line ranges covered by diff hunks (union of old-side and new-side),
@@ -60,8 +283,14 @@ pub struct DiffHunk {
     pub new_start: u32,
     /// Number of lines in new file
     pub new_count: u32,
+    /// Number of parents: 1 for a regular diff, 2+ for a combined (`@@@`)
+    /// merge diff. `old_*` fields track the first parent.
+    pub parents: usize,
     /// Lines in this hunk
     pub lines: Vec<DiffLine>,
+    /// Drawn as a one-line summary; set on a copy for display, see
+    /// [`ParsedDiff::with_folded_hunks`]
+    pub folded: bool,
 }
 
 /// A single line in a diff hunk
@@ -74,6 +303,12 @@ pub struct DiffLine {
     pub new_line: Option<u32>,
     /// The line content (without the +/- prefix)
     pub content: String,
+    /// Per-parent origin markers (e.g. `" +"`) for combined diffs
+    pub origins: Option<String>,
+    /// Followed by a `\ No newline at end of file` marker
+    pub no_newline: bool,
+    /// Ended in `\r\n` in the source (the `\r` is stripped from `content`)
+    pub crlf: bool,
 }
 
 /// Type of diff line
@@ -86,13 +321,20 @@ pub enum DiffLineKind {
 
 impl ParsedDiff {
     /// Parse a unified diff string
+    ///
+    /// Parsing never fails: malformed or truncated input produces best-effort
+    /// hunks and records what went wrong in [`ParsedDiff::warnings`]. CRLF line
+    /// endings are stripped (and noted per line), and control characters in
+    /// line content are replaced with visible control pictures.
     #[must_use]
     pub fn parse(diff: &str) -> Self {
         let mut result = Self::default();
-        let mut lines = diff.lines().peekable();
+        let mut lines: DiffLines<'_> = diff.split_terminator('\n').enumerate().peekable();
+        let (mut new_file, mut deleted_file, mut binary_blocks) = (false, false, 0);
 
         // Parse header (--- and +++ lines)
-        while let Some(line) = lines.peek() {
+        while let Some(&(idx, line)) = lines.peek() {
+            let line = line.trim_end_matches('\r');
             if line.starts_with("---") {
                 result.file_a = line.strip_prefix("--- ").map(|s| {
                     // Remove a/ prefix if present
@@ -107,113 +349,150 @@ impl ParsedDiff {
                 lines.next();
             } else if line.starts_with("@@") {
                 break;
+            } else if result.file_b.is_some() && is_hunk_body_line(line) {
+                // Body lines with no @@ header: keep them in a synthetic hunk
+                // rather than dropping them.
+                result.warn(idx, ParseWarningKind::MissingHunkHeader);
+                let header = HunkHeader::inferred(idx, "@@ @@", None);
+                let hunk = result.parse_hunk_body(header, &mut lines);
+                result.hunks.push(hunk);
             } else {
-                lines.next(); // Skip other header lines (diff --git, index, etc.)
+                // Other header lines (diff --git, index, etc.) and binary
+                // patch data
+                new_file |= line.starts_with("new file mode");
+                deleted_file |= line.starts_with("deleted file mode");
+                read_binary_line(&mut result.binary, &mut binary_blocks, line);
+                lines.next();
             }
         }
 
         // Parse hunks
-        while let Some(line) = lines.next() {
+        while let Some((idx, line)) = lines.next() {
+            let line = line.trim_end_matches('\r');
             if line.starts_with("@@") {
-                if let Some(hunk) = Self::parse_hunk(line, &mut lines) {
-                    result.hunks.push(hunk);
-                }
+                let header = HunkHeader::parse(idx, line).unwrap_or_else(|| {
+                    result.warn(idx, ParseWarningKind::MalformedHunkHeader(line.to_string()));
+                    HunkHeader::inferred(idx, line, result.hunks.last())
+                });
+                let hunk = result.parse_hunk_body(header, &mut lines);
+                result.hunks.push(hunk);
             }
         }
 
+        if let Some(binary) = &mut result.binary {
+            binary.added |= new_file;
+            binary.deleted |= deleted_file;
+        }
         result
     }
 
-    fn parse_hunk(
-        header: &str,
-        lines: &mut std::iter::Peekable<std::str::Lines<'_>>,
-    ) -> Option<DiffHunk> {
-        // Parse @@ -start,count +start,count @@ optional context
-        // Example: @@ -1,5 +1,7 @@ fn main() {
-        let header_str = header.to_string();
-
-        let parts: Vec<&str> = header.split_whitespace().collect();
-        if parts.len() < 3 {
-            return None;
-        }
-
-        let (old_start, old_count) = Self::parse_range(parts[1].trim_start_matches('-'))?;
-        let (new_start, new_count) = Self::parse_range(parts[2].trim_start_matches('+'))?;
+    fn warn(&mut self, idx: usize, kind: ParseWarningKind) {
+        self.warnings.push(ParseWarning {
+            line: idx + 1,
+            kind,
+        });
+    }
 
+    fn parse_hunk_body(&mut self, header: HunkHeader, lines: &mut DiffLines<'_>) -> DiffHunk {
         let mut hunk = DiffHunk {
-            header: header_str,
-            old_start,
-            old_count,
-            new_start,
-            new_count,
+            header: header.text,
+            parents: header.parents,
+            old_start: header.old_start,
+            old_count: header.old_count.unwrap_or(0),
+            new_start: header.new_start,
+            new_count: header.new_count.unwrap_or(0),
             lines: Vec::new(),
+            folded: false,
         };
 
-        let mut old_line = old_start;
-        let mut new_line = new_start;
+        let mut old_line = header.old_start;
+        let mut new_line = header.new_start;
 
-        while let Some(line) = lines.peek() {
+        while let Some(&(idx, line)) = lines.peek() {
             if line.starts_with("@@") || line.starts_with("diff ") {
                 break;
             }
-
-            let line = lines.next().unwrap_or_default();
-
-            let (kind, content) = if let Some(content) = line.strip_prefix('+') {
-                (DiffLineKind::Added, content)
+            lines.next();
+            let (line, crlf) = split_cr(line);
+
+            let (kind, content, origins) = if header.parents > 1 && !line.is_empty() {
+                if let Some(split) = split_combined_line(line, header.parents) {
+                    split
+                } else if line.starts_with('\\') {
+                    mark_no_newline(&mut hunk);
+                    continue;
+                } else {
+                    self.warn(idx, ParseWarningKind::UnrecognizedLine);
+                    (DiffLineKind::Context, line, None)
+                }
+            } else if let Some(content) = line.strip_prefix('+') {
+                (DiffLineKind::Added, content, None)
             } else if let Some(content) = line.strip_prefix('-') {
-                (DiffLineKind::Removed, content)
+                (DiffLineKind::Removed, content, None)
             } else if let Some(content) = line.strip_prefix(' ') {
-                (DiffLineKind::Context, content)
+                (DiffLineKind::Context, content, None)
             } else if line.is_empty() {
                 // Empty context line
-                (DiffLineKind::Context, "")
+                (DiffLineKind::Context, "", None)
             } else if line.starts_with('\\') {
-                // "\ No newline at end of file"
+                // "\ No newline at end of file" applies to the preceding line
+                mark_no_newline(&mut hunk);
                 continue;
             } else {
                 // Unknown line format, treat as context
-                (DiffLineKind::Context, line)
+                self.warn(idx, ParseWarningKind::UnrecognizedLine);
+                (DiffLineKind::Context, line, None)
             };
 
-            let diff_line = match kind {
-                DiffLineKind::Added => {
-                    let dl = DiffLine {
-                        kind,
-                        old_line: None,
-                        new_line: Some(new_line),
-                        content: content.to_string(),
-                    };
-                    new_line += 1;
-                    dl
-                }
-                DiffLineKind::Removed => {
-                    let dl = DiffLine {
-                        kind,
-                        old_line: Some(old_line),
-                        new_line: None,
-                        content: content.to_string(),
-                    };
-                    old_line += 1;
-                    dl
-                }
-                DiffLineKind::Context => {
-                    let dl = DiffLine {
-                        kind,
-                        old_line: Some(old_line),
-                        new_line: Some(new_line),
-                        content: content.to_string(),
-                    };
-                    old_line += 1;
-                    new_line += 1;
-                    dl
+            // Old-side numbering follows the first parent for combined diffs
+            let (in_old, in_new) = match (kind, origins) {
+                (_, Some(markers)) => {
+                    let in_new = !markers.contains('-');
+                    let first = markers.as_bytes()[0];
+                    (first == b'-' || (in_new && first == b' '), in_new)
                 }
+                (DiffLineKind::Added, None) => (false, true),
+                (DiffLineKind::Removed, None) => (true, false),
+                (DiffLineKind::Context, None) => (true, true),
             };
+            self.encoding.observe(content, crlf);
+            let diff_line = DiffLine {
+                kind,
+                old_line: in_old.then_some(old_line),
+                new_line: in_new.then_some(new_line),
+                content: sanitize_control_chars(content).into_owned(),
+                origins: origins.map(str::to_string),
+                no_newline: false,
+                crlf,
+            };
+            old_line += u32::from(in_old);
+            new_line += u32::from(in_new);
 
             hunk.lines.push(diff_line);
         }
 
-        Some(hunk)
+        let actual_old = old_line - header.old_start;
+        let actual_new = new_line - header.new_start;
+        if let (Some(expected_old), Some(expected_new)) = (header.old_count, header.new_count) {
+            if actual_old < expected_old || actual_new < expected_new {
+                self.warn(
+                    header.line,
+                    ParseWarningKind::TruncatedHunk {
+                        expected_old,
+                        expected_new,
+                        actual_old,
+                        actual_new,
+                    },
+                );
+            }
+        } else {
+            // Counts were unknown: use what was actually there so exclusion
+            // ranges still cover the rendered lines.
+            hunk.old_count = actual_old;
+            hunk.new_count = actual_new;
+        }
+
+        hunk
     }
 
     fn parse_range(s: &str) -> Option<(u32, u32)> {
@@ -226,11 +505,62 @@ impl ParsedDiff {
         }
     }
 
+    /// Whether this is a combined (merge) diff with more than one parent
+    #[must_use]
+    pub fn is_combined(&self) -> bool {
+        self.hunks.iter().any(|h| h.parents > 1)
+    }
+
+    /// Whether the new side of the file ends without a trailing newline
+    #[must_use]
+    pub fn missing_final_newline(&self) -> bool {
+        self.hunks
+            .iter()
+            .flat_map(|h| &h.lines)
+            .any(|l| l.no_newline && l.new_line.is_some())
+    }
+
     /// Get total number of lines across all hunks
     #[must_use]
     pub fn total_lines(&self) -> usize {
         self.hunks.iter().map(|h| h.lines.len()).sum()
     }
+
+    /// Where old-side `line` ended up on the new side, or `None` if this
+    /// diff removed or replaced it.
+    #[must_use]
+    pub fn map_old_line(&self, line: u32) -> Option<u32> {
+        let mut shift = 0i64;
+        for hunk in &self.hunks {
+            // An empty side's start is the line before the change
+            let old_begin = hunk.old_start + u32::from(hunk.old_count == 0);
+            if line < old_begin {
+                break;
+            }
+            let old_end = old_begin + hunk.old_count;
+            if line < old_end {
+                return hunk
+                    .lines
+                    .iter()
+                    .find(|l| l.kind == DiffLineKind::Context && l.old_line == Some(line))
+                    .and_then(|l| l.new_line);
+            }
+            let new_end = hunk.new_start + u32::from(hunk.new_count == 0) + hunk.new_count;
+            shift = i64::from(new_end) - i64::from(old_end);
+        }
+        u32::try_from(i64::from(line) + shift).ok()
+    }
+
+    /// A copy with the hunks at `folded` (indices) marked folded. Their
+    /// lines stay, so display indices don't move.
+    #[must_use]
+    pub fn with_folded_hunks(&self, folded: &BTreeSet<usize>) -> Self {
+        let mut diff = self.clone();
+        for (index, hunk) in diff.hunks.iter_mut().enumerate() {
+            hunk.folded = folded.contains(&index);
+        }
+        diff
+    }
 }
 
 #[cfg(test)]
@@ -302,4 +632,143 @@ index abc123..def456 100644
         assert_eq!(lines[3].old_line, None);
         assert_eq!(lines[3].new_line, Some(12));
     }
+
+    #[test]
+    fn test_malformed_header_recovers_lines() {
+        let diff = "--- a/f.txt\r\n+++ b/f.txt\r\n@@ -1,2 +1,2 @@\r\n a\r\n-b\r\n+c\r\n@@ bogus @@\r\n d\r\n+e\r\n";
+
+        let parsed = ParsedDiff::parse(diff);
+
+        assert_eq!(parsed.hunks.len(), 2);
+        assert_eq!(parsed.hunks[0].lines[0].content, "a");
+        assert!(parsed.hunks[0].lines[0].crlf);
+        assert_eq!(parsed.encoding.eol_label(), Some(Text::BadgeCrlf));
+        let second = &parsed.hunks[1];
+        assert_eq!(second.new_start, 3);
+        assert_eq!(second.new_count, 2);
+        assert_eq!(second.lines[1].new_line, Some(4));
+        assert_eq!(
+            parsed.warnings,
+            vec![ParseWarning {
+                line: 7,
+                kind: ParseWarningKind::MalformedHunkHeader("@@ bogus @@".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_binary_missing_header_and_truncation_warnings() {
+        let binary = ParsedDiff::parse(
+            "diff --git a/logo.png b/logo.png\nBinary files a/logo.png and b/logo.png differ\n",
+        );
+        assert!(binary.hunks.is_empty());
+        assert!(binary.warnings.is_empty());
+        assert_eq!(binary.binary, Some(BinaryChange::default()));
+
+        let headerless =
+            ParsedDiff::parse("--- a/x\n+++ b/x\n one\n+two\n\\ No newline at end of file\n");
+        assert_eq!(headerless.hunks.len(), 1);
+        assert_eq!(headerless.hunks[0].lines.len(), 2);
+        assert_eq!(
+            headerless.warnings[0].kind,
+            ParseWarningKind::MissingHunkHeader
+        );
+
+        let truncated = ParsedDiff::parse("--- a/x\n+++ b/x\n@@ -1,3 +1,3 @@\n one\n");
+        assert_eq!(
+            truncated.warnings[0].kind,
+            ParseWarningKind::TruncatedHunk {
+                expected_old: 3,
+                expected_new: 3,
+                actual_old: 1,
+                actual_new: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_binary_patch_sizes_and_summary() {
+        let patch = ParsedDiff::parse(
+            "diff --git a/logo.png b/logo.png\nindex 1a2b..3c4d 100644\nGIT binary patch\n\
+             literal 14438\nzcmV<abc\n\nliteral 12595\nzcmV<def\n\n",
+        );
+        let change = patch.binary.unwrap();
+        assert_eq!(
+            (change.old_size, change.new_size),
+            (Some(12595), Some(14438))
+        );
+        assert_eq!(change.summary(), "Binary file changed (12.3 KB → 14.1 KB)");
+
+        let delta = ParsedDiff::parse("GIT binary patch\ndelta 210\nzcmV<abc\n\nliteral 9\n");
+        assert_eq!(delta.binary.unwrap().new_size, None);
+
+        let added = ParsedDiff::parse(
+            "diff --git a/a.bin b/a.bin\nnew file mode 100644\n\
+             Binary files /dev/null and b/a.bin differ\n",
+        );
+        assert!(added.binary.unwrap().added);
+        assert_eq!(added.binary.unwrap().summary(), "Binary file added");
+    }
+
+    #[test]
+    fn test_combined_diff() {
+        let diff = "diff --cc src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@@ -1,2 -1,2 +1,2 @@@
+  shared
+- ours
+ -theirs
+++merged
+";
+
+        let parsed = ParsedDiff::parse(diff);
+
+        assert!(parsed.is_combined());
+        assert!(parsed.warnings.is_empty());
+        let lines = &parsed.hunks[0].lines;
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0].kind, DiffLineKind::Context);
+        assert_eq!((lines[0].old_line, lines[0].new_line), (Some(1), Some(1)));
+        assert_eq!(lines[1].kind, DiffLineKind::Removed);
+        assert_eq!(lines[1].old_line, Some(2));
+        assert_eq!(lines[2].kind, DiffLineKind::Removed);
+        assert_eq!(lines[2].old_line, None);
+        assert_eq!(lines[3].kind, DiffLineKind::Added);
+        assert_eq!(lines[3].origins.as_deref(), Some("++"));
+        assert_eq!(lines[3].new_line, Some(2));
+    }
+
+    #[test]
+    fn test_map_old_line() {
+        let diff = "--- a/x\n+++ b/x\n\
+                    @@ -2,3 +2,3 @@\n two\n-three\n+THREE\n four\n\
+                    @@ -10,0 +11,2 @@\n+new\n+new\n\
+                    @@ -20,2 +21,0 @@\n-gone\n-gone\n";
+        let parsed = ParsedDiff::parse(diff);
+
+        assert_eq!(parsed.map_old_line(1), Some(1));
+        assert_eq!(parsed.map_old_line(2), Some(2));
+        assert_eq!(parsed.map_old_line(3), None);
+        assert_eq!(parsed.map_old_line(4), Some(4));
+        // Lines after an insertion move down, then back up past a deletion
+        assert_eq!(parsed.map_old_line(10), Some(10));
+        assert_eq!(parsed.map_old_line(11), Some(13));
+        assert_eq!(parsed.map_old_line(21), None);
+        assert_eq!(parsed.map_old_line(22), Some(22));
+    }
+
+    #[test]
+    fn test_no_newline_marker() {
+        let diff =
+            "--- a/x\n+++ b/x\n@@ -1,2 +1,2 @@\n one\n-two\n\\ No newline at end of file\n+two\n";
+
+        let parsed = ParsedDiff::parse(diff);
+        let lines = &parsed.hunks[0].lines;
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].no_newline);
+        assert!(!lines[2].no_newline);
+        assert!(!parsed.missing_final_newline());
+    }
 }
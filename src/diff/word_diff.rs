@@ -0,0 +1,312 @@
+//! Word diff for prose: each run of removed lines and the added lines that
+//! replace it are compared word by word, and the result reads as the new
+//! text with removed words left in place. Both are marked.
+//!
+//! Display lines follow the new side. Removed line breaks inside a run are
+//! dropped, so a re-wrapped paragraph shows only the words that changed.
+
+use std::path::Path;
+
+use super::intraline::{diff_tokens, tokens, Edit};
+use super::parse::{DiffHunk, DiffLine, DiffLineKind};
+
+/// Extensions of files that read as prose rather than code.
+const PROSE_EXTENSIONS: &[&str] = &["md", "markdown", "mdx", "txt", "rst", "adoc", "org"];
+
+/// Token pairs above which a run isn't compared; it's shown as all removed,
+/// then all added.
+const MAX_CELLS: usize = 250_000;
+
+/// Whether `path` is a prose file, which can be shown as a word diff.
+#[must_use]
+pub fn is_prose_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            PROSE_EXTENSIONS
+                .iter()
+                .any(|prose| prose.eq_ignore_ascii_case(ext))
+        })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordKind {
+    Same,
+    Removed,
+    Added,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordSpan {
+    pub kind: WordKind,
+    pub text: String,
+}
+
+/// A display line of the word diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordLine {
+    /// Old-side line, for context lines and runs with nothing added
+    pub old_line: Option<u32>,
+    /// New-side line the text belongs to
+    pub new_line: Option<u32>,
+    pub spans: Vec<WordSpan>,
+}
+
+impl WordLine {
+    /// Whether anything on the line was removed or added.
+    #[must_use]
+    pub fn is_change(&self) -> bool {
+        self.spans.iter().any(|span| span.kind != WordKind::Same)
+    }
+}
+
+/// Word diff of each hunk, in order.
+#[must_use]
+pub fn word_diff(hunks: &[DiffHunk]) -> Vec<Vec<WordLine>> {
+    hunks
+        .iter()
+        .map(|hunk| hunk_word_lines(&hunk.lines))
+        .collect()
+}
+
+fn hunk_word_lines(lines: &[DiffLine]) -> Vec<WordLine> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].kind == DiffLineKind::Context {
+            out.push(WordLine {
+                old_line: lines[i].old_line,
+                new_line: lines[i].new_line,
+                spans: vec![WordSpan {
+                    kind: WordKind::Same,
+                    text: lines[i].content.clone(),
+                }],
+            });
+            i += 1;
+            continue;
+        }
+        let removed_end = i + lines[i..]
+            .iter()
+            .take_while(|l| l.kind == DiffLineKind::Removed)
+            .count();
+        let added_end = removed_end
+            + lines[removed_end..]
+                .iter()
+                .take_while(|l| l.kind == DiffLineKind::Added)
+                .count();
+        change_run(
+            &lines[i..removed_end],
+            &lines[removed_end..added_end],
+            &mut out,
+        );
+        i = added_end;
+    }
+    out
+}
+
+/// Word lines for removed lines followed by the added lines replacing them.
+fn change_run(removed: &[DiffLine], added: &[DiffLine], out: &mut Vec<WordLine>) {
+    if added.is_empty() {
+        out.extend(removed.iter().map(|line| WordLine {
+            old_line: line.old_line,
+            new_line: None,
+            spans: vec![WordSpan {
+                kind: WordKind::Removed,
+                text: line.content.clone(),
+            }],
+        }));
+        return;
+    }
+
+    let old = run_tokens(removed);
+    let new = run_tokens(added);
+    let edits = if old.len().saturating_mul(new.len()) > MAX_CELLS {
+        [vec![Edit::Removed; old.len()], vec![Edit::Added; new.len()]].concat()
+    } else {
+        diff_tokens(&old, &new)
+    };
+
+    let mut added_lines = added.iter().map(|line| line.new_line);
+    let mut line = WordLine {
+        old_line: None,
+        new_line: added_lines.next().flatten(),
+        spans: Vec::new(),
+    };
+    let (mut i, mut j) = (0, 0);
+    for edit in edits {
+        let (kind, text) = match edit {
+            Edit::Same => {
+                i += 1;
+                j += 1;
+                (WordKind::Same, new[j - 1])
+            }
+            Edit::Removed => {
+                i += 1;
+                // A dropped line break only separates the words around it
+                (
+                    WordKind::Removed,
+                    if old[i - 1] == "\n" { " " } else { old[i - 1] },
+                )
+            }
+            Edit::Added => {
+                j += 1;
+                (WordKind::Added, new[j - 1])
+            }
+        };
+        if text == "\n" {
+            let next = WordLine {
+                old_line: None,
+                new_line: added_lines.next().flatten(),
+                spans: Vec::new(),
+            };
+            out.push(std::mem::replace(&mut line, next));
+        } else {
+            push_span(&mut line.spans, kind, text);
+        }
+    }
+    out.push(line);
+}
+
+/// Tokens of `lines`, with `"\n"` between lines.
+fn run_tokens(lines: &[DiffLine]) -> Vec<&str> {
+    let mut out = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 {
+            out.push("\n");
+        }
+        out.extend(tokens(&line.content).iter().map(|token| token.text));
+    }
+    out
+}
+
+/// Append `text`, joining it to the last span when the kinds match.
+fn push_span(spans: &mut Vec<WordSpan>, kind: WordKind, text: &str) {
+    match spans.last_mut() {
+        Some(last) if last.kind == kind => last.text.push_str(text),
+        _ => spans.push(WordSpan {
+            kind,
+            text: text.to_string(),
+        }),
+    }
+}
+
+/// Break `spans` into rows of at most `width` chars, at spaces where
+/// possible. Spaces a row would start with are dropped.
+#[must_use]
+pub fn wrap_spans(spans: &[WordSpan], width: usize) -> Vec<Vec<WordSpan>> {
+    let width = width.max(1);
+    let mut rows = vec![Vec::new()];
+    let mut row_len = 0;
+    let pieces = spans.iter().flat_map(|span| {
+        split_words(&span.text)
+            .into_iter()
+            .map(move |piece| (span.kind, piece))
+    });
+    for (kind, piece) in pieces {
+        let blank = piece.trim().is_empty();
+        let mut piece_len = piece.chars().count();
+        if row_len + piece_len > width && row_len > 0 {
+            rows.push(Vec::new());
+            row_len = 0;
+        }
+        if row_len == 0 && blank && rows.len() > 1 {
+            continue;
+        }
+        let mut rest = piece;
+        // Words longer than a row are split
+        while piece_len > width - row_len {
+            let split = rest
+                .char_indices()
+                .nth(width - row_len)
+                .map_or(rest.len(), |(byte, _)| byte);
+            if let Some(row) = rows.last_mut() {
+                push_span(row, kind, &rest[..split]);
+            }
+            rest = &rest[split..];
+            piece_len -= width - row_len;
+            rows.push(Vec::new());
+            row_len = 0;
+        }
+        if let Some(row) = rows.last_mut() {
+            push_span(row, kind, rest);
+        }
+        row_len += piece_len;
+    }
+    rows
+}
+
+/// `text` as alternating runs of non-space and space chars.
+fn split_words(text: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut last_space = None;
+    for (byte, c) in text.char_indices() {
+        let space = c.is_whitespace();
+        if last_space.is_some_and(|last| last != space) {
+            pieces.push(&text[start..byte]);
+            start = byte;
+        }
+        last_space = Some(space);
+    }
+    if start < text.len() {
+        pieces.push(&text[start..]);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::ParsedDiff;
+
+    fn spans(line: &WordLine) -> Vec<(WordKind, &str)> {
+        line.spans
+            .iter()
+            .map(|s| (s.kind, s.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rewrapped_paragraph_shows_changed_words() {
+        let diff = ParsedDiff::parse(
+            "--- a/README.md\n+++ b/README.md\n@@ -1,3 +1,2 @@\n # Title\n-The quick brown\n-fox jumps.\n+The quick red fox jumps.\n",
+        );
+        let hunks = word_diff(&diff.hunks);
+        assert_eq!(hunks.len(), 1);
+        let lines = &hunks[0];
+        assert_eq!(lines.len(), 2);
+        assert!(!lines[0].is_change());
+        assert_eq!(lines[1].new_line, Some(2));
+        assert_eq!(
+            spans(&lines[1]),
+            [
+                (WordKind::Same, "The quick "),
+                (WordKind::Removed, "brown "),
+                (WordKind::Added, "red "),
+                (WordKind::Same, "fox jumps."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrap_breaks_at_spaces_and_splits_long_words() {
+        let spans = [
+            WordSpan {
+                kind: WordKind::Same,
+                text: "one two ".to_string(),
+            },
+            WordSpan {
+                kind: WordKind::Added,
+                text: "abcdefghij".to_string(),
+            },
+        ];
+        let rows: Vec<String> = wrap_spans(&spans, 6)
+            .iter()
+            .map(|row| row.iter().map(|s| s.text.as_str()).collect())
+            .collect();
+        assert_eq!(rows, ["one ", "two ", "abcdef", "ghij"]);
+        assert!(is_prose_path("docs/Guide.MD"));
+        assert!(!is_prose_path("src/lib.rs"));
+    }
+}
@@ -0,0 +1,186 @@
+//! Review export for `crit-ui export` and the palette's "Export review".
+
+use std::fmt::Write as _;
+
+use serde_json::{json, Value};
+
+use crate::db::{thread_location, ReviewData, ThreadSummary};
+
+/// Review detail plus every thread with its comments, as JSON.
+#[must_use]
+pub fn review_json(data: &ReviewData) -> Value {
+    let threads: Vec<Value> = data
+        .threads
+        .iter()
+        .map(|thread| {
+            let mut value = json!(thread);
+            value["comments"] = json!(data
+                .comments
+                .get(&thread.thread_id)
+                .map_or(&[][..], Vec::as_slice));
+            value
+        })
+        .collect();
+    json!({ "review": data.detail, "threads": threads })
+}
+
+/// Review as Markdown: title, description, review-level discussion, then
+/// each file's diff followed by the threads on it.
+#[must_use]
+pub fn review_markdown(data: &ReviewData) -> String {
+    let detail = &data.detail;
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}", detail.title);
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "`{}` by {} · {} · {} open of {} threads",
+        detail.review_id,
+        detail.author,
+        detail.status,
+        data.threads.iter().filter(|t| t.status == "open").count(),
+        data.threads.len()
+    );
+    if let Some(description) = detail.description.as_deref().map(str::trim) {
+        if !description.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "{description}");
+        }
+    }
+
+    let review_threads: Vec<_> = data
+        .threads
+        .iter()
+        .filter(|t| t.is_review_thread())
+        .collect();
+    if !review_threads.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "## Discussion");
+        for thread in review_threads {
+            write_thread(&mut out, data, thread);
+        }
+    }
+
+    // Files in diff order, then any that only have threads
+    let mut paths: Vec<&str> = data
+        .files
+        .iter()
+        .map(|f| f.path.as_str())
+        .filter(|path| !path.starts_with(".crit/"))
+        .collect();
+    for thread in data.threads.iter().filter(|t| !t.is_review_thread()) {
+        if !paths.contains(&thread.file_path.as_str()) {
+            paths.push(&thread.file_path);
+        }
+    }
+    for path in paths {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "## `{path}`");
+        let diff = data
+            .files
+            .iter()
+            .find(|f| f.path == path)
+            .and_then(|f| f.diff.as_deref());
+        if let Some(diff) = diff {
+            let fence = code_fence(diff);
+            let _ = writeln!(out);
+            let _ = writeln!(out, "{fence}diff");
+            let _ = writeln!(out, "{}", diff.trim_end_matches('\n'));
+            let _ = writeln!(out, "{fence}");
+        }
+        for thread in data.threads.iter().filter(|t| t.file_path == path) {
+            write_thread(&mut out, data, thread);
+        }
+    }
+    out
+}
+
+/// A thread's heading and its comments, each quoted under its author.
+fn write_thread(out: &mut String, data: &ReviewData, thread: &ThreadSummary) {
+    let _ = writeln!(out);
+    let location = if thread.is_review_thread() {
+        String::new()
+    } else {
+        format!(
+            " · {}",
+            thread_location(
+                &thread.file_path,
+                thread.selection_start,
+                thread.selection_end
+            )
+        )
+    };
+    let _ = writeln!(
+        out,
+        "### Thread `{}`{location} · {}",
+        thread.thread_id, thread.status
+    );
+    for comment in data.comments.get(&thread.thread_id).into_iter().flatten() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "**{}** · {}", comment.author, comment.created_at);
+        let _ = writeln!(out);
+        for line in comment.body.trim_end().lines() {
+            if line.is_empty() {
+                let _ = writeln!(out, ">");
+            } else {
+                let _ = writeln!(out, "> {line}");
+            }
+        }
+    }
+}
+
+/// A backtick fence longer than any run of backticks in `text`.
+fn code_fence(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::CritClient;
+    use crate::demo_client::{DemoClient, DemoSize, DEFAULT_DEMO_SEED};
+
+    #[test]
+    fn test_review_json_nests_comments_under_threads() {
+        let client = DemoClient::new(DemoSize::Small, DEFAULT_DEMO_SEED);
+        let review = &client.list_reviews(None).unwrap()[0];
+        let data = client.load_review_data(&review.review_id).unwrap().unwrap();
+
+        let value = review_json(&data);
+        assert_eq!(value["review"]["review_id"], review.review_id.as_str());
+        let threads = value["threads"].as_array().unwrap();
+        assert_eq!(threads.len(), data.threads.len());
+        for (exported, thread) in threads.iter().zip(&data.threads) {
+            assert_eq!(exported["thread_id"], thread.thread_id.as_str());
+            let comments = data.comments.get(&thread.thread_id).map_or(0, Vec::len);
+            assert_eq!(exported["comments"].as_array().unwrap().len(), comments);
+        }
+    }
+
+    #[test]
+    fn test_review_markdown_includes_diffs_and_comments() {
+        let client = DemoClient::new(DemoSize::Small, DEFAULT_DEMO_SEED);
+        let review = &client.list_reviews(None).unwrap()[0];
+        let data = client.load_review_data(&review.review_id).unwrap().unwrap();
+
+        let markdown = review_markdown(&data);
+        assert!(markdown.starts_with(&format!("# {}\n", data.detail.title)));
+        for file in data.files.iter().filter(|f| f.diff.is_some()) {
+            assert!(markdown.contains(&format!("## `{}`", file.path)));
+        }
+        for thread in &data.threads {
+            assert!(markdown.contains(&format!("### Thread `{}`", thread.thread_id)));
+        }
+        for comment in data.comments.values().flatten() {
+            let first = comment.body.lines().next().unwrap_or_default();
+            assert!(markdown.contains(&format!("> {first}")));
+        }
+    }
+
+    #[test]
+    fn test_code_fence_outruns_backticks_in_text() {
+        assert_eq!(code_fence("plain"), "```");
+        assert_eq!(code_fence("+```rust"), "````");
+    }
+}
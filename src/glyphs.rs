@@ -0,0 +1,219 @@
+//! Marker glyphs for the TUI, swappable for fonts that lack the defaults.
+//!
+//! Like the locale, the active set is process-wide: views call [`glyphs`]
+//! instead of threading it through every render helper.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Which characters to draw markers and icons with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum GlyphSet {
+    #[default]
+    Unicode = 0,
+    /// Plain ASCII for terminals and fonts with poor symbol coverage
+    Ascii = 1,
+    /// Icons from a patched Nerd Font
+    NerdFont = 2,
+}
+
+/// The characters one glyph set draws with. Each is one cell wide.
+#[derive(Debug)]
+pub struct Glyphs {
+    /// Sidebar file group, threads hidden
+    pub collapsed: &'static str,
+    /// Sidebar file group, threads shown
+    pub expanded: &'static str,
+    /// File whose threads are all resolved; resolved thread event
+    pub resolved: &'static str,
+    pub opened: &'static str,
+    pub reopened: &'static str,
+    /// Prefix for the review's branch or tag
+    pub branch: &'static str,
+    pub warning: &'static str,
+    /// Gutter marker for `\ No newline at end of file`
+    pub no_newline: &'static str,
+    /// Active item in palette lists
+    pub bullet: &'static str,
+    /// Muted thread
+    pub muted: &'static str,
+    /// File-level thread in the sidebar
+    pub file_thread: &'static str,
+    /// Diff cursor in the gutter, when the row isn't highlighted
+    pub cursor: &'static str,
+    /// File marked viewed in the sidebar
+    pub viewed: &'static str,
+    /// Binary file notice in the diff stream
+    pub binary: &'static str,
+    /// Sidebar divider handle, while hovered or dragged
+    pub grip: &'static str,
+    /// Between breadcrumb segments
+    pub crumb: &'static str,
+    /// Hunk separator with lines hidden above the hunk
+    pub expand: &'static str,
+    pub spinner: &'static [&'static str],
+}
+
+const UNICODE: Glyphs = Glyphs {
+    collapsed: "▸",
+    expanded: "▾",
+    resolved: "✓",
+    opened: "○",
+    reopened: "↺",
+    branch: "⎇",
+    warning: "⚠",
+    no_newline: "∅",
+    bullet: "●",
+    muted: "⊘",
+    file_thread: "≡",
+    cursor: "▶",
+    viewed: "✔",
+    binary: "▣",
+    grip: "┃",
+    crumb: "›",
+    expand: "↕",
+    spinner: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+};
+
+const ASCII: Glyphs = Glyphs {
+    collapsed: ">",
+    expanded: "v",
+    resolved: "x",
+    opened: "o",
+    reopened: "~",
+    branch: "@",
+    warning: "!",
+    no_newline: "$",
+    bullet: "*",
+    muted: "-",
+    file_thread: "=",
+    cursor: ">",
+    viewed: "+",
+    binary: "#",
+    grip: "|",
+    crumb: ">",
+    expand: "^",
+    spinner: &["|", "/", "-", "\\"],
+};
+
+const NERD_FONT: Glyphs = Glyphs {
+    collapsed: "\u{f054}",
+    expanded: "\u{f078}",
+    resolved: "\u{f00c}",
+    opened: "\u{f10c}",
+    reopened: "\u{f0e2}",
+    branch: "\u{e725}",
+    warning: "\u{f071}",
+    no_newline: "\u{f05e}",
+    bullet: "\u{f111}",
+    muted: "\u{f1f6}",
+    file_thread: "\u{f15c}",
+    cursor: "\u{f0da}",
+    viewed: "\u{f046}",
+    binary: "\u{f471}",
+    grip: UNICODE.grip,
+    crumb: "\u{f105}",
+    expand: "\u{f07d}",
+    spinner: UNICODE.spinner,
+};
+
+impl GlyphSet {
+    pub const ALL: [Self; 3] = [Self::Unicode, Self::Ascii, Self::NerdFont];
+
+    /// Parse a config name: `unicode`, `ascii` or `nerd-font`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "unicode" => Some(Self::Unicode),
+            "ascii" => Some(Self::Ascii),
+            "nerd-font" | "nerdfont" | "nerd" => Some(Self::NerdFont),
+            _ => None,
+        }
+    }
+
+    /// Name used in config files and `BOTCRIT_UI_GLYPHS`.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Unicode => "unicode",
+            Self::Ascii => "ascii",
+            Self::NerdFont => "nerd-font",
+        }
+    }
+
+    /// Pick a set from `BOTCRIT_UI_GLYPHS`, then the config value.
+    #[must_use]
+    pub fn detect(config: Option<&str>) -> Self {
+        let env = std::env::var("BOTCRIT_UI_GLYPHS").ok();
+        [env.as_deref(), config]
+            .into_iter()
+            .flatten()
+            .find_map(Self::from_name)
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub const fn glyphs(self) -> &'static Glyphs {
+        match self {
+            Self::Unicode => &UNICODE,
+            Self::Ascii => &ASCII,
+            Self::NerdFont => &NERD_FONT,
+        }
+    }
+
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Ascii,
+            2 => Self::NerdFont,
+            _ => Self::Unicode,
+        }
+    }
+}
+
+static CURRENT_SET: AtomicU8 = AtomicU8::new(GlyphSet::Unicode as u8);
+
+/// Set the glyph set returned by [`glyphs`].
+pub fn set_glyph_set(set: GlyphSet) {
+    CURRENT_SET.store(set as u8, Ordering::Relaxed);
+}
+
+/// The glyphs of the active set.
+#[must_use]
+pub fn glyphs() -> &'static Glyphs {
+    GlyphSet::from_u8(CURRENT_SET.load(Ordering::Relaxed)).glyphs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::display_width;
+
+    #[test]
+    fn test_glyph_sets_parse_and_stay_one_cell_wide() {
+        assert_eq!(GlyphSet::from_name("Nerd_Font"), Some(GlyphSet::NerdFont));
+        assert_eq!(GlyphSet::from_name("wingdings"), None);
+        for set in GlyphSet::ALL {
+            assert_eq!(GlyphSet::from_name(set.name()), Some(set));
+            let g = set.glyphs();
+            let markers = [
+                g.collapsed,
+                g.expanded,
+                g.resolved,
+                g.opened,
+                g.reopened,
+                g.branch,
+                g.warning,
+                g.no_newline,
+                g.bullet,
+                g.muted,
+                g.file_thread,
+                g.cursor,
+                g.viewed,
+                g.grip,
+            ];
+            for glyph in markers.iter().chain(g.spinner) {
+                assert_eq!(display_width(glyph), 1, "{glyph:?} in {set:?}");
+            }
+        }
+    }
+}
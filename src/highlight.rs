@@ -0,0 +1,272 @@
+//! Syntax highlighting off the main thread.
+//!
+//! Highlighting a big file takes long enough to stall input, so loaded files
+//! go into the cache unhighlighted and draw as plain text right away. A
+//! [`HighlightWorker`] thread highlights them in the order they were queued;
+//! `main` polls it each iteration and hands each file's [`Highlights`] to
+//! `update` as `Message::HighlightsReady`, which upgrades the entry in place
+//! unless it changed since (a different `FileCacheEntry::revision`). Without
+//! a worker (tests), `Model::highlight_file` highlights inline.
+
+use std::collections::VecDeque;
+use std::iter;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+
+use crate::diff::ParsedDiff;
+use crate::model::FileCacheEntry;
+use crate::syntax::{HighlightSpan, Highlighter};
+
+/// One file's text to highlight, as of `revision`.
+pub struct HighlightJob {
+    pub path: String,
+    pub revision: u64,
+    pub highlighter: Arc<Highlighter>,
+    pub diff: Option<ParsedDiff>,
+    pub content: Option<Vec<String>>,
+}
+
+/// What a job produced.
+#[derive(Debug, Clone)]
+pub struct Highlights {
+    pub path: String,
+    pub revision: u64,
+    /// By diff display line: one per hunk header, then its lines
+    pub diff: Option<Vec<Vec<HighlightSpan>>>,
+    /// By line of the file content
+    pub content: Option<Vec<Vec<HighlightSpan>>>,
+}
+
+impl HighlightJob {
+    #[must_use]
+    pub fn run(self) -> Highlights {
+        Highlights {
+            diff: self
+                .diff
+                .as_ref()
+                .map(|diff| diff_highlights(diff, &self.path, &self.highlighter)),
+            content: self
+                .content
+                .as_ref()
+                .map(|lines| file_highlights(lines, &self.path, &self.highlighter)),
+            path: self.path,
+            revision: self.revision,
+        }
+    }
+}
+
+impl Highlights {
+    /// Put these into `entry`: a diff's go in `highlighted_lines` and the
+    /// content's in `file_highlighted_lines`, or the content's in
+    /// `highlighted_lines` when there's no diff.
+    pub fn apply(self, entry: &mut FileCacheEntry) {
+        let content = self.content.unwrap_or_default();
+        if entry.diff.is_some() {
+            entry.highlighted_lines = self.diff.unwrap_or_default();
+            entry.file_highlighted_lines = content;
+        } else {
+            entry.highlighted_lines = content;
+        }
+        entry.revision = FileCacheEntry::next_revision();
+    }
+}
+
+/// Take `old`'s highlights into `new` if both show the same text. Returns
+/// `false` if `new` still needs highlighting.
+pub fn carry_over(old: &FileCacheEntry, new: &mut FileCacheEntry) -> bool {
+    let highlighted = !old.highlighted_lines.is_empty() || !old.file_highlighted_lines.is_empty();
+    if !highlighted || diff_text(old) != diff_text(new) || content_text(old) != content_text(new) {
+        return false;
+    }
+    new.highlighted_lines.clone_from(&old.highlighted_lines);
+    new.file_highlighted_lines
+        .clone_from(&old.file_highlighted_lines);
+    true
+}
+
+/// The diff's text by display line, `None` for hunk headers.
+fn diff_text(entry: &FileCacheEntry) -> Option<Vec<Option<&str>>> {
+    entry.diff.as_ref().map(|diff| {
+        diff.hunks
+            .iter()
+            .flat_map(|hunk| {
+                iter::once(None).chain(hunk.lines.iter().map(|line| Some(line.content.as_str())))
+            })
+            .collect()
+    })
+}
+
+fn content_text(entry: &FileCacheEntry) -> Option<&[String]> {
+    entry.file_content.as_ref().map(|c| c.lines.as_slice())
+}
+
+/// Spans for each diff display line, hunk headers unhighlighted.
+#[must_use]
+pub fn diff_highlights(
+    diff: &ParsedDiff,
+    file_path: &str,
+    highlighter: &Highlighter,
+) -> Vec<Vec<HighlightSpan>> {
+    let mut result = Vec::new();
+
+    // Get a file highlighter to maintain state across lines
+    let Some(mut file_hl) = highlighter.for_file(file_path) else {
+        return result;
+    };
+
+    for hunk in &diff.hunks {
+        // Hunk header - no highlighting needed
+        result.push(Vec::new());
+
+        for line in &hunk.lines {
+            let spans = file_hl.highlight_line(&line.content);
+            result.push(spans);
+        }
+    }
+
+    result
+}
+
+/// Spans for each line of file content.
+#[must_use]
+pub fn file_highlights(
+    lines: &[String],
+    file_path: &str,
+    highlighter: &Highlighter,
+) -> Vec<Vec<HighlightSpan>> {
+    let Some(mut file_hl) = highlighter.for_file(file_path) else {
+        return Vec::new();
+    };
+
+    lines
+        .iter()
+        .map(|line| file_hl.highlight_line(line))
+        .collect()
+}
+
+struct Shared {
+    queue: Mutex<VecDeque<HighlightJob>>,
+    ready: Condvar,
+    /// Jobs queued, running, or done but not yet polled
+    outstanding: AtomicUsize,
+    stopped: AtomicBool,
+}
+
+/// A thread highlighting queued files one at a time.
+pub struct HighlightWorker {
+    shared: Arc<Shared>,
+    results: Receiver<Highlights>,
+}
+
+impl HighlightWorker {
+    #[must_use]
+    pub fn start() -> Self {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(VecDeque::new()),
+            ready: Condvar::new(),
+            outstanding: AtomicUsize::new(0),
+            stopped: AtomicBool::new(false),
+        });
+        let (sender, results) = mpsc::channel();
+        let worker = Arc::clone(&shared);
+        std::thread::spawn(move || loop {
+            let job = {
+                let mut queue = worker.queue.lock().unwrap_or_else(PoisonError::into_inner);
+                loop {
+                    if worker.stopped.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    if let Some(job) = queue.pop_front() {
+                        break job;
+                    }
+                    queue = worker
+                        .ready
+                        .wait(queue)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            };
+            if sender.send(job.run()).is_err() {
+                return;
+            }
+        });
+        Self { shared, results }
+    }
+
+    pub fn queue(&self, job: HighlightJob) {
+        self.shared.outstanding.fetch_add(1, Ordering::Relaxed);
+        self.lock().push_back(job);
+        self.shared.ready.notify_one();
+    }
+
+    /// Drop queued jobs for which `keep(path, revision)` is false.
+    pub fn retain(&self, keep: impl Fn(&str, u64) -> bool) {
+        let mut queue = self.lock();
+        let before = queue.len();
+        queue.retain(|job| keep(&job.path, job.revision));
+        self.shared
+            .outstanding
+            .fetch_sub(before - queue.len(), Ordering::Relaxed);
+    }
+
+    /// Results finished since the last poll.
+    #[must_use]
+    pub fn poll(&self) -> Vec<Highlights> {
+        let ready: Vec<Highlights> = self.results.try_iter().collect();
+        self.shared
+            .outstanding
+            .fetch_sub(ready.len(), Ordering::Relaxed);
+        ready
+    }
+
+    /// Whether every queued job has been polled (or dropped).
+    #[must_use]
+    pub fn is_idle(&self) -> bool {
+        self.shared.outstanding.load(Ordering::Relaxed) == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<HighlightJob>> {
+        self.shared
+            .queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for HighlightWorker {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::Relaxed);
+        self.shared.ready.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_highlights_queued_files_in_order() {
+        let highlighter = Arc::new(Highlighter::new());
+        let worker = HighlightWorker::start();
+        for (revision, path) in [(1, "a.rs"), (2, "b.rs"), (3, "c.rs")] {
+            worker.queue(HighlightJob {
+                path: path.to_string(),
+                revision,
+                highlighter: Arc::clone(&highlighter),
+                diff: None,
+                content: Some(vec!["fn main() {}".to_string()]),
+            });
+        }
+        worker.retain(|path, _| path != "b.rs");
+        let mut done = Vec::new();
+        while !worker.is_idle() {
+            done.extend(worker.poll());
+            std::thread::yield_now();
+        }
+        let paths: Vec<&str> = done.iter().map(|h| h.path.as_str()).collect();
+        // b.rs may already have been running when it was dropped
+        assert!(paths == ["a.rs", "c.rs"] || paths == ["a.rs", "b.rs", "c.rs"]);
+        let spans = &done[0].content.as_ref().unwrap()[0];
+        assert!(spans.len() > 1, "{spans:?}");
+    }
+}
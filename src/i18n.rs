@@ -0,0 +1,1591 @@
+//! Message catalog for user-facing UI strings.
+//!
+//! Every string the TUI shows is a [`Text`] key looked up in the active
+//! [`Locale`]'s catalog. Placeholders are positional (`{0}`, `{1}`, ...) so a
+//! translation can reorder them; fill them with [`tr_fmt`].
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Supported UI languages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Locale {
+    #[default]
+    En = 0,
+    Es = 1,
+}
+
+impl Locale {
+    pub const ALL: [Self; 2] = [Self::En, Self::Es];
+
+    /// Parse a locale tag such as `es`, `es-MX` or `es_ES.UTF-8`.
+    /// Returns `None` for languages without a catalog.
+    #[must_use]
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let lang = tag
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match lang.as_str() {
+            "en" | "c" | "posix" => Some(Self::En),
+            "es" => Some(Self::Es),
+            _ => None,
+        }
+    }
+
+    /// Short tag used in config files and `BOTCRIT_UI_LANG`.
+    #[must_use]
+    pub const fn tag(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Es => "es",
+        }
+    }
+
+    /// Pick a locale from `BOTCRIT_UI_LANG`, then the config value, then the
+    /// standard `LC_ALL` / `LC_MESSAGES` / `LANG` variables.
+    #[must_use]
+    pub fn detect(config: Option<&str>) -> Self {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let candidates = [
+            env("BOTCRIT_UI_LANG"),
+            config.map(str::to_string),
+            env("LC_ALL"),
+            env("LC_MESSAGES"),
+            env("LANG"),
+        ];
+        candidates
+            .into_iter()
+            .flatten()
+            .find_map(|tag| Self::from_tag(&tag))
+            .unwrap_or_default()
+    }
+
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Es,
+            _ => Self::En,
+        }
+    }
+}
+
+static CURRENT_LOCALE: AtomicU8 = AtomicU8::new(Locale::En as u8);
+
+/// Set the locale used by [`tr`] and [`tr_fmt`].
+pub fn set_locale(locale: Locale) {
+    CURRENT_LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+/// The locale currently used for lookups.
+#[must_use]
+pub fn locale() -> Locale {
+    Locale::from_u8(CURRENT_LOCALE.load(Ordering::Relaxed))
+}
+
+/// Look up a string in the active locale.
+#[must_use]
+pub fn tr(text: Text) -> &'static str {
+    text.in_locale(locale())
+}
+
+/// Look up a string in the active locale and fill its `{N}` placeholders.
+#[must_use]
+pub fn tr_fmt(text: Text, args: &[&dyn Display]) -> String {
+    fill_placeholders(tr(text), args)
+}
+
+fn fill_placeholders(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let arg = after
+            .find('}')
+            .and_then(|close| Some((close, after[..close].parse::<usize>().ok()?)))
+            .and_then(|(close, index)| Some((close, args.get(index)?)));
+        if let Some((close, arg)) = arg {
+            out.push_str(&arg.to_string());
+            rest = &after[close + 1..];
+        } else {
+            out.push('{');
+            rest = after;
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Keys for every user-facing UI string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    // --- Command palette ---
+    CategoryView,
+    CategorySession,
+    CategoryRecent,
+    CategoryComments,
+    CmdToggleDiffView,
+    CmdToggleDiffViewDesc,
+    CmdToggleFileDiffView,
+    CmdToggleFileDiffViewDesc,
+    CmdToggleWrap,
+    CmdToggleWrapDesc,
+    CmdToggleWordDiff,
+    CmdToggleHighlighting,
+    CmdToggleHighlightingDesc,
+    CmdFoldHunk,
+    CmdFoldHunkDesc,
+    CmdFoldFile,
+    CmdFoldFileDesc,
+    CmdExpandUp,
+    CmdExpandUpDesc,
+    CmdExpandDown,
+    CmdExpandDownDesc,
+    CmdToggleWordDiffDesc,
+    CmdToggleViewed,
+    CmdToggleViewedDesc,
+    CmdToggleCrMarkers,
+    CmdToggleCrMarkersDesc,
+    CmdToggleBlame,
+    CmdToggleBlameDesc,
+    CmdCycleDensity,
+    CmdCycleDensityDesc,
+    CmdToggleSidebar,
+    CmdToggleSidebarDesc,
+    CmdSelectTheme,
+    CmdSelectThemeDesc,
+    CmdGoToLine,
+    CmdGoToLineDesc,
+    CmdSetContextRadius,
+    CmdSetContextRadiusDesc,
+    CmdSortReviews,
+    CmdSortReviewsDesc,
+    CmdGroupReviews,
+    CmdGroupReviewsDesc,
+    CmdOpenInEditor,
+    CmdOpenInEditorDesc,
+    CmdCopyCommentId,
+    CmdCopyCommentIdDesc,
+    CmdCopyThreadLink,
+    CmdCopyThreadLinkDesc,
+    CmdCopyFilePath,
+    CmdCopyFilePathDesc,
+    CmdCopyLineReference,
+    CmdCopyLineReferenceDesc,
+    CmdApplySuggestion,
+    CmdApplySuggestionDesc,
+    CmdUndoSuggestion,
+    CmdUndoSuggestionDesc,
+    CmdJumpToComment,
+    CmdJumpToCommentDesc,
+    CmdToggleMuteThread,
+    CmdToggleMuteThreadDesc,
+    CmdThreadsPanel,
+    CmdThreadsPanelDesc,
+    CmdCommentOnReview,
+    CmdCommentOnReviewDesc,
+    CmdFinishReview,
+    CmdFinishReviewDesc,
+    CmdAbandonReview,
+    CmdAbandonReviewDesc,
+    CmdRestoreReview,
+    CmdRestoreReviewDesc,
+    CmdUndo,
+    CmdUndoDesc,
+    CmdRedo,
+    CmdRedoDesc,
+    CmdOpenInbox,
+    CmdOpenInboxDesc,
+    CmdCopyCheckoutCommand,
+    CmdCopyCheckoutCommandDesc,
+    CmdRunReviewCommand,
+    CmdRunReviewCommandDesc,
+    CmdInputLog,
+    CmdInputLogDesc,
+    InputLogTitle,
+    CmdExportReview,
+    CmdExportReviewDesc,
+    CmdQuit,
+    CmdQuitDesc,
+    PaletteCommands,
+    PaletteThemes,
+    ThreadsPanelTitle,
+    ThreadsPanelCount,
+    ThreadsPanelEmpty,
+    ThreadsPanelEmptyHint,
+    ThreadsPanelReview,
+    PaletteComments,
+    PaletteSearch,
+    PromptGoToLine,
+    PromptContextRadius,
+    PromptReviewSort,
+
+    // --- Headers and placeholders ---
+    Reviews,
+    /// `{0}`: repository path
+    ReviewsFor,
+    NoReviews,
+    /// Empty review list: why it's empty, then what to do about it
+    EmptyNoReviewsHint,
+    EmptyNoOpenReviews,
+    EmptyNoClosedReviews,
+    EmptyNoOwnedReviews,
+    EmptyStatusFilterHint,
+    /// `{0}`: the search text
+    EmptySearchNoMatch,
+    EmptySearchActiveHint,
+    EmptySearchHint,
+    EmptyFilterNoMatch,
+    EmptyFilterHint,
+    /// Review list badge; `{0}`: changed files the user owns
+    OwnedFiles,
+    /// Review list badge: lint/CI annotations on the review's files
+    AnnotationErrors,
+    AnnotationWarnings,
+    AnnotationNotices,
+    /// Pinned file header: position in the review and open threads
+    PinnedFilePosition,
+    PinnedOpenThreads,
+    PinnedViewedFiles,
+    PressSlashToSearch,
+    FilterBarHelp,
+    FilterBarUnknown,
+    FiltersActive,
+    StatusAll,
+    StatusOpen,
+    StatusClosed,
+    StatusOwned,
+    SortedBy,
+    SortStatus,
+    SortUpdated,
+    SortCreated,
+    SortAuthor,
+    SortOpenThreads,
+    SortFiles,
+    SortLines,
+    GroupedByStatus,
+    ColumnFiles,
+    ColumnLines,
+    ColumnCreated,
+    ColumnUpdated,
+    ColumnThreads,
+    LoadingReview,
+    NoFiles,
+    NoFileSelected,
+    NoContent,
+    ReviewEmpty,
+    ReviewEmptyHint,
+    /// `{0}`: number of hidden lines
+    HiddenLines,
+    /// `{0}`: file and line range
+    CommentOn,
+    /// `{0}`: file and line range
+    ReplyOn,
+    VerdictSummaryOn,
+    AbandonReasonTitle,
+    VerdictApproved,
+    VerdictRequestedChanges,
+    ReviewThreadLocation,
+    DefaultEditorName,
+    /// Author shown on a comment that hasn't been confirmed by crit yet
+    PendingAuthor,
+    PendingCommentNewThread,
+    PendingCommentReply,
+    PendingCommentCountdown,
+    /// Thread history rows; `{0}`: who made the change
+    ThreadEventOpened,
+    ThreadEventResolved,
+    ThreadEventReopened,
+    /// Collapsed body of a muted thread
+    MutedThreadSummary,
+    /// `{0}`: current user
+    InboxTitle,
+    /// `{0}`/`{1}`: reviews scanned / total
+    InboxScanning,
+    InboxSummary,
+    InboxEmpty,
+    InboxEmptyHint,
+    InboxFailed,
+    CompareNeedsTwo,
+    CompareTitle,
+    CompareLoading,
+    CompareSummary,
+    CompareSame,
+    CompareOnly,
+    CompareNoDiff,
+    CompareDeltaSummary,
+    PreviewFiles,
+    PreviewMoreFiles,
+    PreviewOpenThreads,
+    PreviewNoDescription,
+    PreviewNoOpenThreads,
+    PreviewReviewThread,
+
+    // --- Help bar ---
+    HintCommands,
+    HintNavigate,
+    HintOpen,
+    HintSidebar,
+    HintFold,
+    HintBack,
+    HintQuit,
+    HintSelect,
+    HintClear,
+    HintInbox,
+    HintFilterBar,
+    HintMark,
+    HintCompare,
+    HintPreview,
+    HintSort,
+    HintRefresh,
+    HintComment,
+    /// `{0}`: editor name
+    HintCommentWith,
+    HintQuote,
+    HintExit,
+    HintView,
+    HintWrap,
+    HintOpenFile,
+    HintResolve,
+    HintReopen,
+    HintReply,
+    HintCollapse,
+    HintCopy,
+    HintActions,
+    /// Action menu entries
+    MenuComment,
+    MenuCommentHunk,
+    MenuCommentFile,
+    MenuCopyLine,
+    MenuCopyLineReference,
+    MenuCopyThreadLink,
+    MenuCopyCommentId,
+    MenuOpenInEditor,
+    MenuViewBlame,
+    MenuToggleMute,
+    MenuApplySuggestion,
+    MenuApprove,
+    MenuRequestChanges,
+    MenuAbandonReview,
+    MenuRestoreReview,
+    MenuEmpty,
+    /// `{0}`: short commit, `{1}`: author, `{2}`: date, `{3}`: commit summary
+    BlameLine,
+    BlameUnavailable,
+    BlameUnifiedOnly,
+    DensitySet,
+    /// Side-by-side column focus (`←/→`)
+    HintSide,
+    HintHunk,
+    HintFile,
+    HintChange,
+    HintScroll,
+    HintRerun,
+    HintClose,
+    HintSubmit,
+    HintCancel,
+    HintSuggest,
+    HintSearch,
+    HintMatch,
+
+    // --- Flash messages ---
+    /// `{0}`: error message
+    CommentFailed,
+    ThreadStatusFailed,
+    WordDiffProseOnly,
+    /// `{0}`: file path
+    FileHighlightingOn,
+    /// `{0}`: file path
+    FileHighlightingOff,
+    SyntaxDisabled,
+    /// `{0}`: file path, `{1}`: `ViewUnified` or `ViewSideBySide`
+    FileViewModeSet,
+    FoldedHunk,
+    FoldWordDiff,
+    NoHunkAtCursor,
+    /// `{0}`: line count
+    ExpandableLines,
+    NoMoreContext,
+    ExpandReformatted,
+    ViewUnified,
+    ViewSideBySide,
+    SuggestedChange,
+    SuggestionUnavailable,
+    NoSuggestionAtCursor,
+    SuggestionNeedsRepo,
+    SuggestionApplied,
+    SuggestionApplyFailed,
+    SuggestionUndone,
+    SuggestionUndoFailed,
+    NoSuggestionToUndo,
+    NormalizedNoLineComments,
+    VerdictSubmitted,
+    VerdictFailed,
+    ReviewAbandoned,
+    ReviewRestored,
+    ReviewStatusFailed,
+    AlreadyAbandoned,
+    NotAbandoned,
+    NothingToUndo,
+    NothingToRedo,
+    Undone,
+    Redone,
+    CantUndo,
+    ActionComment,
+    ActionResolve,
+    ActionReopen,
+    ActionViewed,
+    ActionUnviewed,
+    AbandonedBy,
+    AbandonedBanner,
+    ReviewLoadFailed,
+    ReviewNotFound,
+    LoadingFile,
+    /// `{0}`: new comment count, `{1}`: thread status
+    ReplyConflict,
+    ThreadStatusConflict,
+    /// `{0}`: file and line range the comment now targets
+    CommentTargetMoved,
+    /// Under a comment target's excerpt; `{0}`: lines not shown
+    ExcerptMoreLines,
+    DraftRestored,
+    DraftVerdictChip,
+    /// `{0}`: file path, `{1}`: line number
+    LineNotShown,
+    /// `{0}`: file and line range that was copied
+    Copied,
+    NothingToCopy,
+    /// `{0}`: comment id
+    CopiedCommentId,
+    CopiedCheckoutCommand,
+    NoReviewCommand,
+    ReviewCommandNeedsRepo,
+    ReviewExported,
+    ReviewExportFailed,
+    SearchNoMatches,
+    SearchBreakdown,
+    RunRunning,
+    RunPassed,
+    RunFailed,
+    RunKilled,
+    RunError,
+    RunNoOutput,
+    /// `{0}`: comment id
+    CommentNotFound,
+    NoCommentAtCursor,
+    NoThreadAtCursor,
+    ThreadMuted,
+    ThreadUnmuted,
+    ReadOnlyBlocked,
+    /// Help bar label shown while in read-only mode
+    ReadOnlyLabel,
+
+    // --- Diff parse warnings ---
+    /// `{0}`: 1-based line number
+    WarnAtLine,
+    /// `{0}`: header text
+    WarnMalformedHunkHeader,
+    WarnMissingHunkHeader,
+    /// `{0}`/`{1}`: expected old/new counts, `{2}`/`{3}`: actual old/new counts
+    WarnTruncatedHunk,
+    WarnUnrecognizedLine,
+
+    // --- Binary files ---
+    BinaryChanged,
+    BinaryAdded,
+    BinaryDeleted,
+
+    // --- File header badges ---
+    /// The diff was pretty-printed (JSON, notebooks)
+    BadgeNormalized,
+    BadgeNonUtf8,
+    BadgeCrlf,
+    BadgeMixedEol,
+    BadgeNoEol,
+}
+
+impl Text {
+    pub const ALL: &'static [Self] = &[
+        Self::CategoryView,
+        Self::CategorySession,
+        Self::CategoryRecent,
+        Self::CategoryComments,
+        Self::CmdToggleDiffView,
+        Self::CmdToggleDiffViewDesc,
+        Self::CmdToggleFileDiffView,
+        Self::CmdToggleFileDiffViewDesc,
+        Self::CmdToggleWrap,
+        Self::CmdToggleWrapDesc,
+        Self::CmdToggleWordDiff,
+        Self::CmdToggleHighlighting,
+        Self::CmdToggleHighlightingDesc,
+        Self::CmdFoldHunk,
+        Self::CmdFoldHunkDesc,
+        Self::CmdFoldFile,
+        Self::CmdFoldFileDesc,
+        Self::CmdExpandUp,
+        Self::CmdExpandUpDesc,
+        Self::CmdExpandDown,
+        Self::CmdExpandDownDesc,
+        Self::CmdToggleWordDiffDesc,
+        Self::CmdToggleViewed,
+        Self::CmdToggleViewedDesc,
+        Self::CmdToggleCrMarkers,
+        Self::CmdToggleCrMarkersDesc,
+        Self::CmdToggleBlame,
+        Self::CmdToggleBlameDesc,
+        Self::CmdCycleDensity,
+        Self::CmdCycleDensityDesc,
+        Self::CmdToggleSidebar,
+        Self::CmdToggleSidebarDesc,
+        Self::CmdSelectTheme,
+        Self::CmdSelectThemeDesc,
+        Self::CmdGoToLine,
+        Self::CmdGoToLineDesc,
+        Self::CmdSetContextRadius,
+        Self::CmdSetContextRadiusDesc,
+        Self::CmdSortReviews,
+        Self::CmdSortReviewsDesc,
+        Self::CmdGroupReviews,
+        Self::CmdGroupReviewsDesc,
+        Self::CmdOpenInEditor,
+        Self::CmdOpenInEditorDesc,
+        Self::CmdCopyCommentId,
+        Self::CmdCopyCommentIdDesc,
+        Self::CmdCopyThreadLink,
+        Self::CmdCopyThreadLinkDesc,
+        Self::CmdCopyFilePath,
+        Self::CmdCopyFilePathDesc,
+        Self::CmdCopyLineReference,
+        Self::CmdCopyLineReferenceDesc,
+        Self::CmdApplySuggestion,
+        Self::CmdApplySuggestionDesc,
+        Self::CmdUndoSuggestion,
+        Self::CmdUndoSuggestionDesc,
+        Self::CmdJumpToComment,
+        Self::CmdJumpToCommentDesc,
+        Self::CmdToggleMuteThread,
+        Self::CmdToggleMuteThreadDesc,
+        Self::CmdThreadsPanel,
+        Self::CmdThreadsPanelDesc,
+        Self::CmdCommentOnReview,
+        Self::CmdCommentOnReviewDesc,
+        Self::CmdFinishReview,
+        Self::CmdFinishReviewDesc,
+        Self::CmdAbandonReview,
+        Self::CmdAbandonReviewDesc,
+        Self::CmdRestoreReview,
+        Self::CmdRestoreReviewDesc,
+        Self::CmdUndo,
+        Self::CmdUndoDesc,
+        Self::CmdRedo,
+        Self::CmdRedoDesc,
+        Self::CmdOpenInbox,
+        Self::CmdOpenInboxDesc,
+        Self::CmdCopyCheckoutCommand,
+        Self::CmdCopyCheckoutCommandDesc,
+        Self::CmdRunReviewCommand,
+        Self::CmdRunReviewCommandDesc,
+        Self::CmdInputLog,
+        Self::CmdInputLogDesc,
+        Self::InputLogTitle,
+        Self::CmdExportReview,
+        Self::CmdExportReviewDesc,
+        Self::CmdQuit,
+        Self::CmdQuitDesc,
+        Self::PaletteCommands,
+        Self::PaletteThemes,
+        Self::ThreadsPanelTitle,
+        Self::ThreadsPanelCount,
+        Self::ThreadsPanelEmpty,
+        Self::ThreadsPanelEmptyHint,
+        Self::ThreadsPanelReview,
+        Self::PaletteComments,
+        Self::PaletteSearch,
+        Self::PromptGoToLine,
+        Self::PromptContextRadius,
+        Self::PromptReviewSort,
+        Self::Reviews,
+        Self::ReviewsFor,
+        Self::NoReviews,
+        Self::EmptyNoReviewsHint,
+        Self::EmptyNoOpenReviews,
+        Self::EmptyNoClosedReviews,
+        Self::EmptyNoOwnedReviews,
+        Self::EmptyStatusFilterHint,
+        Self::EmptySearchNoMatch,
+        Self::EmptySearchActiveHint,
+        Self::EmptySearchHint,
+        Self::EmptyFilterNoMatch,
+        Self::EmptyFilterHint,
+        Self::OwnedFiles,
+        Self::AnnotationErrors,
+        Self::AnnotationWarnings,
+        Self::AnnotationNotices,
+        Self::PinnedFilePosition,
+        Self::PinnedOpenThreads,
+        Self::PinnedViewedFiles,
+        Self::PressSlashToSearch,
+        Self::FilterBarHelp,
+        Self::FilterBarUnknown,
+        Self::FiltersActive,
+        Self::StatusAll,
+        Self::StatusOpen,
+        Self::StatusClosed,
+        Self::StatusOwned,
+        Self::SortedBy,
+        Self::SortStatus,
+        Self::SortUpdated,
+        Self::SortCreated,
+        Self::SortAuthor,
+        Self::SortOpenThreads,
+        Self::SortFiles,
+        Self::SortLines,
+        Self::GroupedByStatus,
+        Self::ColumnFiles,
+        Self::ColumnLines,
+        Self::ColumnCreated,
+        Self::ColumnUpdated,
+        Self::ColumnThreads,
+        Self::LoadingReview,
+        Self::NoFiles,
+        Self::NoFileSelected,
+        Self::NoContent,
+        Self::ReviewEmpty,
+        Self::ReviewEmptyHint,
+        Self::HiddenLines,
+        Self::CommentOn,
+        Self::ReplyOn,
+        Self::VerdictSummaryOn,
+        Self::AbandonReasonTitle,
+        Self::VerdictApproved,
+        Self::VerdictRequestedChanges,
+        Self::ReviewThreadLocation,
+        Self::DefaultEditorName,
+        Self::PendingAuthor,
+        Self::PendingCommentNewThread,
+        Self::PendingCommentReply,
+        Self::PendingCommentCountdown,
+        Self::ThreadEventOpened,
+        Self::ThreadEventResolved,
+        Self::ThreadEventReopened,
+        Self::MutedThreadSummary,
+        Self::InboxTitle,
+        Self::InboxScanning,
+        Self::InboxSummary,
+        Self::InboxEmpty,
+        Self::InboxEmptyHint,
+        Self::InboxFailed,
+        Self::CompareNeedsTwo,
+        Self::CompareTitle,
+        Self::CompareLoading,
+        Self::CompareSummary,
+        Self::CompareSame,
+        Self::CompareOnly,
+        Self::CompareNoDiff,
+        Self::CompareDeltaSummary,
+        Self::PreviewFiles,
+        Self::PreviewMoreFiles,
+        Self::PreviewOpenThreads,
+        Self::PreviewNoDescription,
+        Self::PreviewNoOpenThreads,
+        Self::PreviewReviewThread,
+        Self::HintCommands,
+        Self::HintNavigate,
+        Self::HintOpen,
+        Self::HintSidebar,
+        Self::HintFold,
+        Self::HintBack,
+        Self::HintQuit,
+        Self::HintSelect,
+        Self::HintClear,
+        Self::HintInbox,
+        Self::HintFilterBar,
+        Self::HintMark,
+        Self::HintCompare,
+        Self::HintPreview,
+        Self::HintSort,
+        Self::HintRefresh,
+        Self::HintComment,
+        Self::HintCommentWith,
+        Self::HintQuote,
+        Self::HintExit,
+        Self::HintView,
+        Self::HintWrap,
+        Self::HintOpenFile,
+        Self::HintResolve,
+        Self::HintReopen,
+        Self::HintReply,
+        Self::HintCollapse,
+        Self::HintCopy,
+        Self::HintActions,
+        Self::MenuComment,
+        Self::MenuCommentHunk,
+        Self::MenuCommentFile,
+        Self::MenuCopyLine,
+        Self::MenuCopyLineReference,
+        Self::MenuCopyThreadLink,
+        Self::MenuCopyCommentId,
+        Self::MenuOpenInEditor,
+        Self::MenuViewBlame,
+        Self::MenuToggleMute,
+        Self::MenuApplySuggestion,
+        Self::MenuApprove,
+        Self::MenuRequestChanges,
+        Self::MenuAbandonReview,
+        Self::MenuRestoreReview,
+        Self::MenuEmpty,
+        Self::BlameLine,
+        Self::BlameUnavailable,
+        Self::BlameUnifiedOnly,
+        Self::DensitySet,
+        Self::HintSide,
+        Self::HintHunk,
+        Self::HintFile,
+        Self::HintChange,
+        Self::HintScroll,
+        Self::HintRerun,
+        Self::HintClose,
+        Self::HintSubmit,
+        Self::HintCancel,
+        Self::HintSuggest,
+        Self::HintSearch,
+        Self::HintMatch,
+        Self::CommentFailed,
+        Self::ThreadStatusFailed,
+        Self::WordDiffProseOnly,
+        Self::FileHighlightingOn,
+        Self::FileHighlightingOff,
+        Self::SyntaxDisabled,
+        Self::FileViewModeSet,
+        Self::FoldedHunk,
+        Self::FoldWordDiff,
+        Self::NoHunkAtCursor,
+        Self::ExpandableLines,
+        Self::NoMoreContext,
+        Self::ExpandReformatted,
+        Self::ViewUnified,
+        Self::ViewSideBySide,
+        Self::SuggestedChange,
+        Self::SuggestionUnavailable,
+        Self::NoSuggestionAtCursor,
+        Self::SuggestionNeedsRepo,
+        Self::SuggestionApplied,
+        Self::SuggestionApplyFailed,
+        Self::SuggestionUndone,
+        Self::SuggestionUndoFailed,
+        Self::NoSuggestionToUndo,
+        Self::NormalizedNoLineComments,
+        Self::VerdictSubmitted,
+        Self::VerdictFailed,
+        Self::ReviewAbandoned,
+        Self::ReviewRestored,
+        Self::ReviewStatusFailed,
+        Self::AlreadyAbandoned,
+        Self::NotAbandoned,
+        Self::NothingToUndo,
+        Self::NothingToRedo,
+        Self::Undone,
+        Self::Redone,
+        Self::CantUndo,
+        Self::ActionComment,
+        Self::ActionResolve,
+        Self::ActionReopen,
+        Self::ActionViewed,
+        Self::ActionUnviewed,
+        Self::AbandonedBy,
+        Self::AbandonedBanner,
+        Self::ReviewLoadFailed,
+        Self::ReviewNotFound,
+        Self::LoadingFile,
+        Self::ReplyConflict,
+        Self::ThreadStatusConflict,
+        Self::CommentTargetMoved,
+        Self::ExcerptMoreLines,
+        Self::DraftRestored,
+        Self::DraftVerdictChip,
+        Self::LineNotShown,
+        Self::Copied,
+        Self::NothingToCopy,
+        Self::CopiedCommentId,
+        Self::CopiedCheckoutCommand,
+        Self::NoReviewCommand,
+        Self::ReviewCommandNeedsRepo,
+        Self::ReviewExported,
+        Self::ReviewExportFailed,
+        Self::SearchNoMatches,
+        Self::SearchBreakdown,
+        Self::RunRunning,
+        Self::RunPassed,
+        Self::RunFailed,
+        Self::RunKilled,
+        Self::RunError,
+        Self::RunNoOutput,
+        Self::CommentNotFound,
+        Self::NoCommentAtCursor,
+        Self::NoThreadAtCursor,
+        Self::ThreadMuted,
+        Self::ThreadUnmuted,
+        Self::ReadOnlyBlocked,
+        Self::ReadOnlyLabel,
+        Self::WarnAtLine,
+        Self::WarnMalformedHunkHeader,
+        Self::WarnMissingHunkHeader,
+        Self::WarnTruncatedHunk,
+        Self::WarnUnrecognizedLine,
+        Self::BinaryChanged,
+        Self::BinaryAdded,
+        Self::BinaryDeleted,
+        Self::BadgeNormalized,
+        Self::BadgeNonUtf8,
+        Self::BadgeCrlf,
+        Self::BadgeMixedEol,
+        Self::BadgeNoEol,
+    ];
+
+    /// Look up this string in a specific locale.
+    #[must_use]
+    pub const fn in_locale(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => english(self),
+            Locale::Es => spanish(self),
+        }
+    }
+}
+
+// Keys are separate even when the text matches so translations can diverge.
+#[allow(clippy::match_same_arms, clippy::too_many_lines)]
+const fn english(text: Text) -> &'static str {
+    match text {
+        Text::CategoryView => "View",
+        Text::CategorySession => "Session",
+        Text::CategoryRecent => "Recent",
+        Text::CategoryComments => "Comments",
+        Text::CmdToggleDiffView => "Toggle diff view",
+        Text::CmdToggleDiffViewDesc => "Toggle between unified and side-by-side diff",
+        Text::CmdToggleFileDiffView => "Toggle diff view for this file",
+        Text::CmdToggleFileDiffViewDesc => "Unified or side-by-side for the current file only",
+        Text::CmdToggleWrap => "Toggle line wrap",
+        Text::CmdToggleWrapDesc => "Toggle line wrapping in diffs",
+        Text::CmdToggleWordDiff => "Toggle word diff",
+        Text::CmdToggleHighlighting => "Toggle syntax highlighting for this file",
+        Text::CmdToggleHighlightingDesc => "Plain diff colors for big or minified files",
+        Text::CmdFoldHunk => "Fold hunk",
+        Text::CmdFoldHunkDesc => "Collapse the hunk at the cursor to one line, or expand it",
+        Text::CmdFoldFile => "Fold file",
+        Text::CmdFoldFileDesc => "Collapse the current file to its header, or expand it",
+        Text::CmdExpandUp => "Expand context up",
+        Text::CmdExpandUpDesc => "Show more unchanged lines above the hunk at the cursor",
+        Text::CmdExpandDown => "Expand context down",
+        Text::CmdExpandDownDesc => "Show more unchanged lines below the hunk at the cursor",
+        Text::CmdToggleWordDiffDesc => "Show the current prose file's changes word by word",
+        Text::CmdToggleViewed => "Toggle file viewed",
+        Text::CmdToggleViewedDesc => "Check off the selected file; kept when the review is reopened",
+        Text::CmdToggleCrMarkers => "Toggle ^M markers",
+        Text::CmdToggleCrMarkersDesc => "Show ^M at the end of lines with CRLF endings",
+        Text::CmdToggleBlame => "Toggle blame column",
+        Text::CmdToggleBlameDesc => "Show the commit and author that last changed each line",
+        Text::CmdCycleDensity => "Cycle UI density",
+        Text::CmdCycleDensityDesc => "Compact, normal or relaxed spacing around blocks",
+        Text::CmdToggleSidebar => "Toggle sidebar",
+        Text::CmdToggleSidebarDesc => "Show or hide the file sidebar",
+        Text::CmdSelectTheme => "Select theme",
+        Text::CmdSelectThemeDesc => "Choose a theme from the list",
+        Text::CmdGoToLine => "Go to line…",
+        Text::CmdGoToLineDesc => "Put the cursor on a line of the current file",
+        Text::CmdSetContextRadius => "Set context radius…",
+        Text::CmdSetContextRadiusDesc => "Lines shown around threads outside the diff",
+        Text::CmdSortReviews => "Sort reviews by…",
+        Text::CmdSortReviewsDesc => "Order the review list by status, update, creation, author, threads, files or lines",
+        Text::CmdGroupReviews => "Group reviews by status",
+        Text::CmdGroupReviewsDesc => "Keep each status together in the review list under any sort",
+        Text::CmdOpenInEditor => "Open in editor",
+        Text::CmdOpenInEditorDesc => "Open the current file in an external editor",
+        Text::CmdCopyCommentId => "Copy comment id",
+        Text::CmdCopyCommentIdDesc => "Copy the id of the comment under the cursor",
+        Text::CmdCopyThreadLink => "Copy thread link",
+        Text::CmdCopyThreadLinkDesc => "Copy a command or URL that opens the thread under the cursor",
+        Text::CmdCopyFilePath => "Copy file path",
+        Text::CmdCopyFilePathDesc => "Copy the path of the current file",
+        Text::CmdCopyLineReference => "Copy line reference",
+        Text::CmdCopyLineReferenceDesc => "Copy path:line for the cursor line or selection",
+        Text::CmdApplySuggestion => "Apply suggestion",
+        Text::CmdApplySuggestionDesc => "Apply the suggested change under the cursor to the working copy",
+        Text::CmdUndoSuggestion => "Undo suggestion",
+        Text::CmdUndoSuggestionDesc => "Take the last applied suggestion back out of the working copy",
+        Text::CmdJumpToComment => "Jump to comment",
+        Text::CmdJumpToCommentDesc => "Go to a comment by id (cm-…)",
+        Text::CmdToggleMuteThread => "Mute/unmute thread",
+        Text::CmdToggleMuteThreadDesc => "Collapse a thread and skip it in n/p and counts",
+        Text::CmdThreadsPanel => "Threads panel",
+        Text::CmdThreadsPanelDesc => "Every thread in the review, grouped by file",
+        Text::CmdCommentOnReview => "Comment on review",
+        Text::CmdCommentOnReviewDesc => "Start a general thread not tied to any file",
+        Text::CmdFinishReview => "Finish review",
+        Text::CmdFinishReviewDesc => "Approve or request changes, with a summary comment",
+        Text::CmdAbandonReview => "Abandon review",
+        Text::CmdAbandonReviewDesc => "Close the review without merging, with a reason",
+        Text::CmdRestoreReview => "Restore review",
+        Text::CmdRestoreReviewDesc => "Reopen an abandoned review",
+        Text::CmdUndo => "Undo",
+        Text::CmdUndoDesc => "Take back the last resolve, reopen or viewed mark",
+        Text::CmdRedo => "Redo",
+        Text::CmdRedoDesc => "Apply the last undone action again",
+        Text::CmdOpenInbox => "Inbox",
+        Text::CmdOpenInboxDesc => "Threads waiting on your reply across open reviews",
+        Text::CmdCopyCheckoutCommand => "Copy checkout command",
+        Text::CmdCopyCheckoutCommandDesc => {
+            "Copy the jj/git command that checks out this review's change"
+        }
+        Text::CmdRunReviewCommand => "Run command at review commit",
+        Text::CmdRunReviewCommandDesc => {
+            "Run review_command from ui.json in a scratch checkout of the review"
+        }
+        Text::CmdExportReview => "Export review to Markdown",
+        Text::CmdInputLog => "Input latency log",
+        Text::CmdInputLogDesc => "Time from each key to its frame, by phase",
+        Text::InputLogTitle => "Input latency",
+        Text::CmdExportReviewDesc => "Write the review, its diffs and threads to <review id>.md",
+        Text::CmdQuit => "Quit",
+        Text::CmdQuitDesc => "Quit the application",
+        Text::PaletteCommands => "Commands",
+        Text::PaletteThemes => "Themes",
+        Text::ThreadsPanelTitle => "Threads",
+        Text::ThreadsPanelCount => "{0} open of {1}",
+        Text::ThreadsPanelEmpty => "No threads in this review",
+        Text::ThreadsPanelEmptyHint => "Press a on a line to start one",
+        Text::ThreadsPanelReview => "Review",
+        Text::PaletteComments => "Comments",
+        Text::PaletteSearch => "Search",
+        Text::PromptGoToLine => "Line number in {0}",
+        Text::PromptContextRadius => "Lines around threads outside the diff, 0–{0} (now {1})",
+        Text::PromptReviewSort => "{0} (now {1})",
+        Text::Reviews => "Reviews",
+        Text::ReviewsFor => "Reviews for {0}",
+        Text::NoReviews => "No reviews yet",
+        Text::EmptyNoReviewsHint => "Run `crit reviews create` to start one",
+        Text::EmptyNoOpenReviews => "No open reviews",
+        Text::EmptyNoClosedReviews => "No closed reviews",
+        Text::EmptyNoOwnedReviews => "No reviews touch files you own",
+        Text::EmptyStatusFilterHint => "Press s to show all, or run `crit reviews create`",
+        Text::EmptySearchNoMatch => "No reviews match {0}",
+        Text::EmptySearchActiveHint => "Esc clears the search",
+        Text::EmptySearchHint => "Press / then Esc to clear the search",
+        Text::EmptyFilterNoMatch => "No reviews match the filter",
+        Text::EmptyFilterHint => "Press f to change it",
+        Text::OwnedFiles => "{0} files you own",
+        Text::AnnotationErrors => "{0} errors",
+        Text::AnnotationWarnings => "{0} warnings",
+        Text::AnnotationNotices => "{0} notices",
+        Text::PinnedFilePosition => "file {0}/{1}",
+        Text::PinnedOpenThreads => "{0} open",
+        Text::PinnedViewedFiles => "{0}/{1} viewed",
+        Text::PressSlashToSearch => "Press / to search",
+        Text::FilterBarHelp => {
+            "author:NAME  status:NAME  path:GLOB  has:open-threads  (-term negates)"
+        }
+        Text::FilterBarUnknown => "unknown: {0}",
+        Text::FiltersActive => "filter: {0}",
+        Text::StatusAll => "Status (All)",
+        Text::StatusOpen => "Status (Open)",
+        Text::StatusClosed => "Status (Closed)",
+        Text::StatusOwned => "Status (Owned)",
+        Text::SortedBy => "sorted by {0}",
+        Text::SortStatus => "status",
+        Text::SortUpdated => "last update",
+        Text::SortCreated => "creation",
+        Text::SortAuthor => "author",
+        Text::SortOpenThreads => "open threads",
+        Text::SortFiles => "files",
+        Text::SortLines => "changed lines",
+        Text::GroupedByStatus => "grouped by status",
+        Text::ColumnFiles => "files",
+        Text::ColumnLines => "+/-",
+        Text::ColumnCreated => "created",
+        Text::ColumnUpdated => "updated",
+        Text::ColumnThreads => "threads",
+        Text::LoadingReview => "Loading review...",
+        Text::NoFiles => "No files",
+        Text::NoFileSelected => "No file selected",
+        Text::NoContent => "No content available",
+        Text::ReviewEmpty => "Nothing to show in this review",
+        Text::ReviewEmptyHint => "Press Esc to go back to the reviews",
+        Text::HiddenLines => "··· {0} lines ···",
+        Text::CommentOn => "Comment on {0}",
+        Text::ReplyOn => "Reply on {0}",
+        Text::VerdictSummaryOn => "{0}: summary (optional)",
+        Text::AbandonReasonTitle => "Abandon review: reason (optional)",
+        Text::VerdictApproved => "{0} approved",
+        Text::VerdictRequestedChanges => "{0} requested changes",
+        Text::ReviewThreadLocation => "review",
+        Text::DefaultEditorName => "Editor",
+        Text::PendingAuthor => "you (sending…)",
+        Text::PendingCommentNewThread => "New thread",
+        Text::PendingCommentReply => "Reply to {0}",
+        Text::PendingCommentCountdown => "sending in {0}s — press u to cancel",
+        Text::ThreadEventOpened => "opened by @{0}",
+        Text::ThreadEventResolved => "resolved by @{0}",
+        Text::ThreadEventReopened => "reopened by @{0}",
+        Text::MutedThreadSummary => "muted · {0} comments",
+        Text::InboxTitle => "Inbox · {0}",
+        Text::InboxScanning => "Scanning reviews {0}/{1}…",
+        Text::InboxSummary => "{0} threads waiting on you",
+        Text::InboxEmpty => "Nothing waiting on you",
+        Text::InboxEmptyHint => "Press r to check again, or Esc to go back",
+        Text::InboxFailed => "{0} reviews failed to load",
+        Text::CompareNeedsTwo => "Mark two reviews with Space to compare them",
+        Text::CompareTitle => "Compare {0} ↔ {1}",
+        Text::CompareLoading => "Loading {0}…",
+        Text::CompareSummary => "{0} files · {1} in both · {2} identical",
+        Text::CompareSame => "same change",
+        Text::CompareOnly => "only {0}",
+        Text::CompareNoDiff => "Both reviews make the same change",
+        Text::CompareDeltaSummary => "{0}  < {1}  > {2}",
+        Text::PreviewFiles => "Files ({0})",
+        Text::PreviewMoreFiles => "… {0} more",
+        Text::PreviewOpenThreads => "Open threads ({0})",
+        Text::PreviewNoDescription => "No description",
+        Text::PreviewNoOpenThreads => "No open threads",
+        Text::PreviewReviewThread => "review",
+        Text::HintCommands => "Commands",
+        Text::HintNavigate => "Navigate",
+        Text::HintOpen => "Open",
+        Text::HintSidebar => "Sidebar",
+        Text::HintFold => "Fold",
+        Text::HintBack => "Back",
+        Text::HintQuit => "Quit",
+        Text::HintSelect => "Select",
+        Text::HintClear => "Clear",
+        Text::HintInbox => "Inbox",
+        Text::HintFilterBar => "Filter",
+        Text::HintMark => "Mark",
+        Text::HintCompare => "Compare",
+        Text::HintPreview => "Preview",
+        Text::HintSort => "Sort",
+        Text::HintRefresh => "Refresh",
+        Text::HintComment => "Comment",
+        Text::HintCommentWith => "Comment with {0}",
+        Text::HintQuote => "Quote",
+        Text::HintExit => "Exit",
+        Text::HintView => "View",
+        Text::HintWrap => "Wrap",
+        Text::HintOpenFile => "Open File",
+        Text::HintResolve => "Resolve",
+        Text::HintReopen => "Reopen",
+        Text::HintReply => "Reply",
+        Text::HintCollapse => "Collapse",
+        Text::HintCopy => "Copy",
+        Text::HintActions => "Actions",
+        Text::MenuComment => "Comment",
+        Text::MenuCommentHunk => "Comment on hunk",
+        Text::MenuCommentFile => "Comment on file",
+        Text::MenuCopyLine => "Copy line",
+        Text::MenuCopyLineReference => "Copy path:line",
+        Text::MenuCopyThreadLink => "Copy thread link",
+        Text::MenuCopyCommentId => "Copy comment id",
+        Text::MenuOpenInEditor => "Open in editor",
+        Text::MenuViewBlame => "View blame",
+        Text::MenuToggleMute => "Mute/unmute thread",
+        Text::MenuApplySuggestion => "Apply suggestion",
+        Text::MenuApprove => "Approve",
+        Text::MenuRequestChanges => "Request changes",
+        Text::MenuAbandonReview => "Abandon review",
+        Text::MenuRestoreReview => "Restore review",
+        Text::MenuEmpty => "No actions here",
+        Text::BlameLine => "{0} · {1} · {2} · {3}",
+        Text::BlameUnavailable => "No blame for line {0}",
+        Text::BlameUnifiedOnly => "The blame column shows in the unified view (v)",
+        Text::DensitySet => "Density: {0}",
+        Text::HintSide => "Side",
+        Text::HintHunk => "Hunk",
+        Text::HintFile => "File",
+        Text::HintChange => "Change",
+        Text::HintScroll => "Scroll",
+        Text::HintRerun => "Rerun",
+        Text::HintClose => "Close",
+        Text::HintSubmit => "Submit",
+        Text::HintCancel => "Cancel",
+        Text::HintSuggest => "Suggest",
+        Text::HintSearch => "Search",
+        Text::HintMatch => "Match",
+        Text::CommentFailed => "Comment failed: {0}",
+        Text::ThreadStatusFailed => "Thread update failed: {0}",
+        Text::WordDiffProseOnly => "Word diff is only for prose files (Markdown, text)",
+        Text::FileHighlightingOn => "{0}: syntax highlighting on",
+        Text::FileHighlightingOff => "{0}: syntax highlighting off",
+        Text::SyntaxDisabled => "Syntax highlighting is off (--no-syntax or `syntax` in ui.json)",
+        Text::FileViewModeSet => "{0}: {1}",
+        Text::FoldedHunk => "{0} lines folded · +{1} -{2}",
+        Text::FoldWordDiff => "Hunks don't fold in a word diff (W)",
+        Text::NoHunkAtCursor => "No hunk at the cursor",
+        Text::ExpandableLines => "{0} lines hidden",
+        Text::NoMoreContext => "No more lines to show there",
+        Text::ExpandReformatted => "Context doesn't expand in a reformatted file",
+        Text::ViewUnified => "unified",
+        Text::ViewSideBySide => "side-by-side",
+        Text::SuggestedChange => "Suggested change",
+        Text::SuggestionUnavailable => "Suggestions replace new-side lines of a line comment",
+        Text::NoSuggestionAtCursor => "No suggestion at the cursor",
+        Text::SuggestionNeedsRepo => "Applying a suggestion needs the repository",
+        Text::SuggestionApplied => "Applied suggestion to {0} (U to undo)",
+        Text::SuggestionApplyFailed => "Couldn't apply suggestion: {0}",
+        Text::SuggestionUndone => "Undid suggestion in {0}",
+        Text::SuggestionUndoFailed => "Couldn't undo suggestion: {0}",
+        Text::NoSuggestionToUndo => "No applied suggestion to undo",
+        Text::NormalizedNoLineComments => "This diff is pretty-printed, so its line numbers aren't the file's; comment on the file instead",
+        Text::VerdictSubmitted => "Verdict submitted",
+        Text::VerdictFailed => "Verdict failed: {0}",
+        Text::ReviewAbandoned => "Review abandoned",
+        Text::ReviewRestored => "Review restored",
+        Text::ReviewStatusFailed => "Review status change failed: {0}",
+        Text::AlreadyAbandoned => "Review is already abandoned",
+        Text::NotAbandoned => "Review isn't abandoned",
+        Text::NothingToUndo => "Nothing to undo",
+        Text::NothingToRedo => "Nothing to redo",
+        Text::Undone => "Undone: {0}",
+        Text::Redone => "Redone: {0}",
+        Text::CantUndo => "Can't undo {0}: crit can't delete comments",
+        Text::ActionComment => "comment on {0}",
+        Text::ActionResolve => "resolve {0}",
+        Text::ActionReopen => "reopen {0}",
+        Text::ActionViewed => "mark {0} viewed",
+        Text::ActionUnviewed => "mark {0} unviewed",
+        Text::AbandonedBy => "Abandoned by {0}",
+        Text::AbandonedBanner => "Abandoned",
+        Text::ReviewLoadFailed => "Loading the review failed: {0}",
+        Text::ReviewNotFound => "Review {0} not found",
+        Text::LoadingFile => "Loading…",
+        Text::ReplyConflict => {
+            "Thread changed since you opened it: {0} new comment(s), now {1}. Save again to post."
+        }
+        Text::CommentTargetMoved => "Display was out of date; commenting on {0}",
+        Text::ThreadStatusConflict => "Thread changed since you opened it: {0} new comment(s), now {1}. Nothing was changed.",
+        Text::DraftRestored => "Restored your unsent draft",
+        Text::DraftVerdictChip => "draft verdict: {0}",
+        Text::ExcerptMoreLines => "… {0} more lines",
+        Text::LineNotShown => "{0}:{1} isn't shown in this review",
+        Text::Copied => "Copied {0}",
+        Text::NothingToCopy => "Nothing to copy here",
+        Text::CopiedCommentId => "Copied {0}",
+        Text::CopiedCheckoutCommand => "Copied {0}",
+        Text::NoReviewCommand => "Set review_command in ui.json to run a command",
+        Text::ReviewCommandNeedsRepo => "Running a command needs a repository (--path)",
+        Text::ReviewExported => "Exported review to {0}",
+        Text::ReviewExportFailed => "Export failed: {0}",
+        Text::SearchNoMatches => "No matches for {0}",
+        Text::SearchBreakdown => "title {0} · paths {1} · comments {2} · lines {3}",
+        Text::RunRunning => "running",
+        Text::RunPassed => "passed",
+        Text::RunFailed => "exit {0}",
+        Text::RunKilled => "killed",
+        Text::RunError => "error",
+        Text::RunNoOutput => "No output",
+        Text::CommentNotFound => "No comment {0} in this review",
+        Text::NoCommentAtCursor => "Move the cursor onto a comment to copy its id",
+        Text::NoThreadAtCursor => "Move the cursor onto a thread to mute it",
+        Text::ThreadMuted => "Muted {0}",
+        Text::ThreadUnmuted => "Unmuted {0}",
+        Text::ReadOnlyBlocked => "Read-only mode: reviews can't be modified",
+        Text::ReadOnlyLabel => "read-only",
+        Text::WarnAtLine => "line {0}: ",
+        Text::WarnMalformedHunkHeader => "malformed hunk header `{0}`, line numbers inferred",
+        Text::WarnMissingHunkHeader => "hunk lines without @@ header",
+        Text::WarnTruncatedHunk => "truncated hunk: expected -{0}/+{1} lines, got -{2}/+{3}",
+        Text::WarnUnrecognizedLine => "unrecognized line shown as context",
+        Text::BinaryChanged => "Binary file changed",
+        Text::BinaryAdded => "Binary file added",
+        Text::BinaryDeleted => "Binary file deleted",
+        Text::BadgeNormalized => "normalized",
+        Text::BadgeNonUtf8 => "non-UTF-8",
+        Text::BadgeCrlf => "CRLF",
+        Text::BadgeMixedEol => "mixed EOL",
+        Text::BadgeNoEol => "no EOL",
+    }
+}
+
+#[allow(clippy::match_same_arms, clippy::too_many_lines)]
+const fn spanish(text: Text) -> &'static str {
+    match text {
+        Text::CategoryView => "Vista",
+        Text::CategorySession => "Sesión",
+        Text::CategoryRecent => "Recientes",
+        Text::CategoryComments => "Comentarios",
+        Text::CmdToggleDiffView => "Cambiar vista del diff",
+        Text::CmdToggleDiffViewDesc => "Alternar entre diff unificado y lado a lado",
+        Text::CmdToggleFileDiffView => "Alternar vista del diff de este archivo",
+        Text::CmdToggleFileDiffViewDesc => "Unificado o lado a lado solo para el archivo actual",
+        Text::CmdToggleWrap => "Ajuste de línea",
+        Text::CmdToggleWrapDesc => "Activar o desactivar el ajuste de línea en los diffs",
+        Text::CmdToggleWordDiff => "Alternar diff por palabras",
+        Text::CmdToggleHighlighting => "Alternar resaltado de sintaxis de este archivo",
+        Text::CmdToggleHighlightingDesc => "Colores de diff simples para archivos grandes o minificados",
+        Text::CmdFoldHunk => "Plegar bloque",
+        Text::CmdFoldHunkDesc => "Reducir el bloque del cursor a una línea, o desplegarlo",
+        Text::CmdFoldFile => "Plegar archivo",
+        Text::CmdFoldFileDesc => "Reducir el archivo actual a su cabecera, o desplegarlo",
+        Text::CmdExpandUp => "Ampliar contexto arriba",
+        Text::CmdExpandUpDesc => "Mostrar más líneas sin cambios encima del bloque del cursor",
+        Text::CmdExpandDown => "Ampliar contexto abajo",
+        Text::CmdExpandDownDesc => "Mostrar más líneas sin cambios debajo del bloque del cursor",
+        Text::CmdToggleWordDiffDesc => "Mostrar los cambios del archivo de texto actual palabra por palabra",
+        Text::CmdToggleViewed => "Marcar archivo como visto",
+        Text::CmdToggleViewedDesc => "Marcar el archivo seleccionado; se conserva al volver a abrir la revisión",
+        Text::CmdToggleCrMarkers => "Marcadores ^M",
+        Text::CmdToggleCrMarkersDesc => "Mostrar ^M al final de las líneas con fin CRLF",
+        Text::CmdToggleBlame => "Columna de autoría",
+        Text::CmdToggleBlameDesc => "Mostrar el commit y el autor que cambiaron cada línea por última vez",
+        Text::CmdCycleDensity => "Cambiar densidad",
+        Text::CmdCycleDensityDesc => "Espaciado compacto, normal o amplio alrededor de los bloques",
+        Text::CmdToggleSidebar => "Barra lateral",
+        Text::CmdToggleSidebarDesc => "Mostrar u ocultar la barra lateral de archivos",
+        Text::CmdSelectTheme => "Elegir tema",
+        Text::CmdSelectThemeDesc => "Elegir un tema de la lista",
+        Text::CmdGoToLine => "Ir a la línea…",
+        Text::CmdGoToLineDesc => "Poner el cursor en una línea del archivo actual",
+        Text::CmdSetContextRadius => "Fijar radio de contexto…",
+        Text::CmdSetContextRadiusDesc => "Líneas mostradas alrededor de los hilos fuera del diff",
+        Text::CmdSortReviews => "Ordenar revisiones por…",
+        Text::CmdSortReviewsDesc => "Ordena la lista por estado, actualización, creación, autor, hilos, archivos o líneas",
+        Text::CmdGroupReviews => "Agrupar revisiones por estado",
+        Text::CmdGroupReviewsDesc => "Mantiene juntos los estados en la lista con cualquier orden",
+        Text::CmdOpenInEditor => "Abrir en editor",
+        Text::CmdOpenInEditorDesc => "Abrir el archivo actual en un editor externo",
+        Text::CmdCopyCommentId => "Copiar id del comentario",
+        Text::CmdCopyCommentIdDesc => "Copiar el id del comentario bajo el cursor",
+        Text::CmdCopyThreadLink => "Copiar enlace del hilo",
+        Text::CmdCopyThreadLinkDesc => "Copiar un comando o URL que abre el hilo bajo el cursor",
+        Text::CmdCopyFilePath => "Copiar ruta del archivo",
+        Text::CmdCopyFilePathDesc => "Copiar la ruta del archivo actual",
+        Text::CmdCopyLineReference => "Copiar referencia de línea",
+        Text::CmdCopyLineReferenceDesc => "Copiar ruta:línea de la línea del cursor o la selección",
+        Text::CmdApplySuggestion => "Aplicar sugerencia",
+        Text::CmdApplySuggestionDesc => "Aplicar el cambio sugerido bajo el cursor a la copia de trabajo",
+        Text::CmdUndoSuggestion => "Deshacer sugerencia",
+        Text::CmdUndoSuggestionDesc => "Quitar de la copia de trabajo la última sugerencia aplicada",
+        Text::CmdJumpToComment => "Ir al comentario",
+        Text::CmdJumpToCommentDesc => "Ir a un comentario por su id (cm-…)",
+        Text::CmdToggleMuteThread => "Silenciar/activar hilo",
+        Text::CmdToggleMuteThreadDesc => "Contraer un hilo y omitirlo en n/p y en los contadores",
+        Text::CmdThreadsPanel => "Panel de hilos",
+        Text::CmdThreadsPanelDesc => "Todos los hilos de la revisión, agrupados por archivo",
+        Text::CmdCommentOnReview => "Comentar la revisión",
+        Text::CmdCommentOnReviewDesc => "Abrir un hilo general que no es de ningún archivo",
+        Text::CmdFinishReview => "Terminar la revisión",
+        Text::CmdFinishReviewDesc => "Aprobar o pedir cambios, con un comentario de resumen",
+        Text::CmdAbandonReview => "Abandonar la revisión",
+        Text::CmdAbandonReviewDesc => "Cerrar la revisión sin fusionarla, con un motivo",
+        Text::CmdRestoreReview => "Restaurar la revisión",
+        Text::CmdRestoreReviewDesc => "Reabrir una revisión abandonada",
+        Text::CmdUndo => "Deshacer",
+        Text::CmdUndoDesc => "Revertir la última resolución, reapertura o marca de vista",
+        Text::CmdRedo => "Rehacer",
+        Text::CmdRedoDesc => "Aplicar de nuevo la última acción deshecha",
+        Text::CmdOpenInbox => "Bandeja de entrada",
+        Text::CmdOpenInboxDesc => "Hilos que esperan tu respuesta en las revisiones abiertas",
+        Text::CmdCopyCheckoutCommand => "Copiar comando de checkout",
+        Text::CmdCopyCheckoutCommandDesc => "Copiar el comando jj/git que hace checkout del cambio de esta revisión",
+        Text::CmdRunReviewCommand => "Ejecutar comando en el commit de la revisión",
+        Text::CmdRunReviewCommandDesc => "Ejecutar review_command de ui.json en una copia temporal de la revisión",
+        Text::CmdInputLog => "Registro de latencia de entrada",
+        Text::CmdInputLogDesc => "Tiempo de cada tecla hasta su fotograma, por fase",
+        Text::InputLogTitle => "Latencia de entrada",
+        Text::CmdExportReview => "Exportar revisión a Markdown",
+        Text::CmdExportReviewDesc => "Escribir la revisión, sus diffs e hilos en <id de revisión>.md",
+        Text::CmdQuit => "Salir",
+        Text::CmdQuitDesc => "Salir de la aplicación",
+        Text::PaletteCommands => "Comandos",
+        Text::PaletteThemes => "Temas",
+        Text::ThreadsPanelTitle => "Hilos",
+        Text::ThreadsPanelCount => "{0} abiertos de {1}",
+        Text::ThreadsPanelEmpty => "No hay hilos en esta revisión",
+        Text::ThreadsPanelEmptyHint => "Pulsa a sobre una línea para empezar uno",
+        Text::ThreadsPanelReview => "Revisión",
+        Text::PaletteComments => "Comentarios",
+        Text::PaletteSearch => "Buscar",
+        Text::PromptGoToLine => "Número de línea en {0}",
+        Text::PromptContextRadius => "Líneas alrededor de los hilos fuera del diff, 0–{0} (ahora {1})",
+        Text::PromptReviewSort => "{0} (ahora {1})",
+        Text::Reviews => "Revisiones",
+        Text::ReviewsFor => "Revisiones de {0}",
+        Text::NoReviews => "Todavía no hay revisiones",
+        Text::EmptyNoReviewsHint => "Ejecuta `crit reviews create` para empezar una",
+        Text::EmptyNoOpenReviews => "No hay revisiones abiertas",
+        Text::EmptyNoClosedReviews => "No hay revisiones cerradas",
+        Text::EmptyNoOwnedReviews => "Ninguna revisión toca archivos tuyos",
+        Text::EmptyStatusFilterHint => "Pulsa s para ver todas, o ejecuta `crit reviews create`",
+        Text::EmptySearchNoMatch => "Ninguna revisión coincide con {0}",
+        Text::EmptySearchActiveHint => "Esc borra la búsqueda",
+        Text::EmptySearchHint => "Pulsa / y luego Esc para borrar la búsqueda",
+        Text::EmptyFilterNoMatch => "Ninguna revisión coincide con el filtro",
+        Text::EmptyFilterHint => "Pulsa f para cambiarlo",
+        Text::OwnedFiles => "{0} archivos tuyos",
+        Text::AnnotationErrors => "{0} errores",
+        Text::AnnotationWarnings => "{0} avisos",
+        Text::AnnotationNotices => "{0} notas",
+        Text::PinnedFilePosition => "archivo {0}/{1}",
+        Text::PinnedOpenThreads => "{0} abiertos",
+        Text::PinnedViewedFiles => "{0}/{1} vistos",
+        Text::PressSlashToSearch => "Pulsa / para buscar",
+        Text::FilterBarHelp => "author:NOMBRE  status:NOMBRE  path:GLOB  has:open-threads  (-término niega)",
+        Text::FilterBarUnknown => "desconocido: {0}",
+        Text::FiltersActive => "filtro: {0}",
+        Text::StatusAll => "Estado (Todas)",
+        Text::StatusOpen => "Estado (Abiertas)",
+        Text::StatusClosed => "Estado (Cerradas)",
+        Text::StatusOwned => "Estado (Propias)",
+        Text::SortedBy => "ordenado por {0}",
+        Text::SortStatus => "estado",
+        Text::SortUpdated => "última actualización",
+        Text::SortCreated => "creación",
+        Text::SortAuthor => "autor",
+        Text::SortOpenThreads => "hilos abiertos",
+        Text::SortFiles => "archivos",
+        Text::SortLines => "líneas cambiadas",
+        Text::GroupedByStatus => "agrupado por estado",
+        Text::ColumnFiles => "archivos",
+        Text::ColumnLines => "+/-",
+        Text::ColumnCreated => "creada",
+        Text::ColumnUpdated => "actualizada",
+        Text::ColumnThreads => "hilos",
+        Text::LoadingReview => "Cargando revisión...",
+        Text::NoFiles => "Sin archivos",
+        Text::NoFileSelected => "Ningún archivo seleccionado",
+        Text::NoContent => "Sin contenido disponible",
+        Text::ReviewEmpty => "No hay nada que mostrar en esta revisión",
+        Text::ReviewEmptyHint => "Pulsa Esc para volver a las revisiones",
+        Text::HiddenLines => "··· {0} líneas ···",
+        Text::CommentOn => "Comentario en {0}",
+        Text::ReplyOn => "Respuesta en {0}",
+        Text::VerdictSummaryOn => "{0}: resumen (opcional)",
+        Text::AbandonReasonTitle => "Abandonar la revisión: motivo (opcional)",
+        Text::VerdictApproved => "{0} aprobó",
+        Text::VerdictRequestedChanges => "{0} pidió cambios",
+        Text::ReviewThreadLocation => "revisión",
+        Text::DefaultEditorName => "editor",
+        Text::PendingAuthor => "tú (enviando…)",
+        Text::PendingCommentNewThread => "Hilo nuevo",
+        Text::PendingCommentReply => "Respuesta a {0}",
+        Text::PendingCommentCountdown => "enviando en {0}s — pulsa u para cancelar",
+        Text::ThreadEventOpened => "abierto por @{0}",
+        Text::ThreadEventResolved => "resuelto por @{0}",
+        Text::ThreadEventReopened => "reabierto por @{0}",
+        Text::MutedThreadSummary => "silenciado · {0} comentarios",
+        Text::InboxTitle => "Bandeja de entrada · {0}",
+        Text::InboxScanning => "Revisando revisiones {0}/{1}…",
+        Text::InboxSummary => "{0} hilos esperan tu respuesta",
+        Text::InboxEmpty => "Nada pendiente",
+        Text::InboxEmptyHint => "Pulsa r para volver a comprobar, o Esc para salir",
+        Text::InboxFailed => "No se pudieron cargar {0} revisiones",
+        Text::CompareNeedsTwo => "Marca dos revisiones con Espacio para compararlas",
+        Text::CompareTitle => "Comparar {0} ↔ {1}",
+        Text::CompareLoading => "Cargando {0}…",
+        Text::CompareSummary => "{0} archivos · {1} en ambas · {2} idénticos",
+        Text::CompareSame => "mismo cambio",
+        Text::CompareOnly => "solo {0}",
+        Text::CompareNoDiff => "Ambas revisiones hacen el mismo cambio",
+        Text::CompareDeltaSummary => "{0}  < {1}  > {2}",
+        Text::PreviewFiles => "Archivos ({0})",
+        Text::PreviewMoreFiles => "… {0} más",
+        Text::PreviewOpenThreads => "Hilos abiertos ({0})",
+        Text::PreviewNoDescription => "Sin descripción",
+        Text::PreviewNoOpenThreads => "Ningún hilo abierto",
+        Text::PreviewReviewThread => "revisión",
+        Text::HintCommands => "Comandos",
+        Text::HintNavigate => "Navegar",
+        Text::HintOpen => "Abrir",
+        Text::HintSidebar => "Barra",
+        Text::HintFold => "Plegar",
+        Text::HintBack => "Atrás",
+        Text::HintQuit => "Salir",
+        Text::HintSelect => "Seleccionar",
+        Text::HintClear => "Limpiar",
+        Text::HintInbox => "Bandeja",
+        Text::HintFilterBar => "Filtrar",
+        Text::HintMark => "Marcar",
+        Text::HintCompare => "Comparar",
+        Text::HintPreview => "Vista previa",
+        Text::HintSort => "Ordenar",
+        Text::HintRefresh => "Actualizar",
+        Text::HintComment => "Comentar",
+        Text::HintCommentWith => "Comentar con {0}",
+        Text::HintQuote => "Citar",
+        Text::HintExit => "Salir",
+        Text::HintView => "Vista",
+        Text::HintWrap => "Ajuste",
+        Text::HintOpenFile => "Abrir archivo",
+        Text::HintResolve => "Resolver",
+        Text::HintReopen => "Reabrir",
+        Text::HintReply => "Responder",
+        Text::HintCollapse => "Contraer",
+        Text::HintCopy => "Copiar",
+        Text::HintActions => "Acciones",
+        Text::MenuComment => "Comentar",
+        Text::MenuCommentHunk => "Comentar el bloque",
+        Text::MenuCommentFile => "Comentar el archivo",
+        Text::MenuCopyLine => "Copiar línea",
+        Text::MenuCopyLineReference => "Copiar ruta:línea",
+        Text::MenuCopyThreadLink => "Copiar enlace del hilo",
+        Text::MenuCopyCommentId => "Copiar id del comentario",
+        Text::MenuOpenInEditor => "Abrir en el editor",
+        Text::MenuViewBlame => "Ver autoría",
+        Text::MenuToggleMute => "Silenciar/activar hilo",
+        Text::MenuApplySuggestion => "Aplicar sugerencia",
+        Text::MenuApprove => "Aprobar",
+        Text::MenuRequestChanges => "Pedir cambios",
+        Text::MenuAbandonReview => "Abandonar la revisión",
+        Text::MenuRestoreReview => "Restaurar la revisión",
+        Text::MenuEmpty => "No hay acciones aquí",
+        Text::BlameLine => "{0} · {1} · {2} · {3}",
+        Text::BlameUnavailable => "Sin autoría para la línea {0}",
+        Text::BlameUnifiedOnly => "La columna de autoría se muestra en la vista unificada (v)",
+        Text::DensitySet => "Densidad: {0}",
+        Text::HintSide => "Lado",
+        Text::HintHunk => "Bloque",
+        Text::HintFile => "Archivo",
+        Text::HintChange => "Cambio",
+        Text::HintScroll => "Desplazar",
+        Text::HintRerun => "Repetir",
+        Text::HintClose => "Cerrar",
+        Text::HintSubmit => "Enviar",
+        Text::HintCancel => "Cancelar",
+        Text::HintSuggest => "Sugerir",
+        Text::HintSearch => "Buscar",
+        Text::HintMatch => "Coincidencia",
+        Text::CommentFailed => "Error al comentar: {0}",
+        Text::ThreadStatusFailed => "Error al actualizar el hilo: {0}",
+        Text::WordDiffProseOnly => "El diff por palabras es solo para archivos de texto (Markdown, texto)",
+        Text::FileHighlightingOn => "{0}: resaltado de sintaxis activado",
+        Text::FileHighlightingOff => "{0}: resaltado de sintaxis desactivado",
+        Text::SyntaxDisabled => "El resaltado de sintaxis está desactivado (--no-syntax o `syntax` en ui.json)",
+        Text::FileViewModeSet => "{0}: {1}",
+        Text::FoldedHunk => "{0} líneas plegadas · +{1} -{2}",
+        Text::FoldWordDiff => "Los bloques no se pliegan en un diff de palabras (W)",
+        Text::NoHunkAtCursor => "No hay ningún bloque en el cursor",
+        Text::ExpandableLines => "{0} líneas ocultas",
+        Text::NoMoreContext => "No hay más líneas que mostrar ahí",
+        Text::ExpandReformatted => "El contexto no se amplía en un archivo reformateado",
+        Text::ViewUnified => "unificado",
+        Text::ViewSideBySide => "lado a lado",
+        Text::SuggestedChange => "Cambio sugerido",
+        Text::SuggestionUnavailable => "Las sugerencias reemplazan líneas nuevas de un comentario de línea",
+        Text::NoSuggestionAtCursor => "No hay ninguna sugerencia en el cursor",
+        Text::SuggestionNeedsRepo => "Aplicar una sugerencia requiere el repositorio",
+        Text::SuggestionApplied => "Sugerencia aplicada a {0} (U para deshacer)",
+        Text::SuggestionApplyFailed => "No se pudo aplicar la sugerencia: {0}",
+        Text::SuggestionUndone => "Sugerencia deshecha en {0}",
+        Text::SuggestionUndoFailed => "No se pudo deshacer la sugerencia: {0}",
+        Text::NoSuggestionToUndo => "No hay ninguna sugerencia aplicada que deshacer",
+        Text::NormalizedNoLineComments => "Este diff está reformateado y sus números de línea no son los del archivo; comenta el archivo en su lugar",
+        Text::VerdictSubmitted => "Veredicto enviado",
+        Text::VerdictFailed => "Error al enviar el veredicto: {0}",
+        Text::ReviewAbandoned => "Revisión abandonada",
+        Text::ReviewRestored => "Revisión restaurada",
+        Text::ReviewStatusFailed => "Error al cambiar el estado de la revisión: {0}",
+        Text::AlreadyAbandoned => "La revisión ya está abandonada",
+        Text::NotAbandoned => "La revisión no está abandonada",
+        Text::NothingToUndo => "Nada que deshacer",
+        Text::NothingToRedo => "Nada que rehacer",
+        Text::Undone => "Deshecho: {0}",
+        Text::Redone => "Rehecho: {0}",
+        Text::CantUndo => "No se puede deshacer {0}: crit no puede borrar comentarios",
+        Text::ActionComment => "comentario en {0}",
+        Text::ActionResolve => "resolver {0}",
+        Text::ActionReopen => "reabrir {0}",
+        Text::ActionViewed => "marcar {0} como visto",
+        Text::ActionUnviewed => "marcar {0} como no visto",
+        Text::AbandonedBy => "Abandonada por {0}",
+        Text::AbandonedBanner => "Abandonada",
+        Text::ReviewLoadFailed => "Error al cargar la revisión: {0}",
+        Text::ReviewNotFound => "No se encontró la revisión {0}",
+        Text::LoadingFile => "Cargando…",
+        Text::ReplyConflict => "El hilo cambió desde que lo abriste: {0} comentario(s) nuevo(s), ahora {1}. Guarda de nuevo para publicar.",
+        Text::ThreadStatusConflict => "El hilo cambió desde que lo abriste: {0} comentario(s) nuevo(s), ahora {1}. No se cambió nada.",
+        Text::CommentTargetMoved => "La vista estaba desactualizada; comentando en {0}",
+        Text::DraftRestored => "Borrador sin enviar recuperado",
+        Text::DraftVerdictChip => "veredicto en borrador: {0}",
+        Text::ExcerptMoreLines => "… {0} líneas más",
+        Text::LineNotShown => "{0}:{1} no aparece en esta revisión",
+        Text::Copied => "Copiado {0}",
+        Text::NothingToCopy => "Nada que copiar aquí",
+        Text::CopiedCommentId => "Copiado {0}",
+        Text::CopiedCheckoutCommand => "Copiado {0}",
+        Text::NoReviewCommand => "Define review_command en ui.json para ejecutar un comando",
+        Text::ReviewCommandNeedsRepo => "Ejecutar un comando requiere un repositorio (--path)",
+        Text::ReviewExported => "Revisión exportada a {0}",
+        Text::ReviewExportFailed => "Falló la exportación: {0}",
+        Text::SearchNoMatches => "Sin coincidencias para {0}",
+        Text::SearchBreakdown => "título {0} · rutas {1} · comentarios {2} · líneas {3}",
+        Text::RunRunning => "en curso",
+        Text::RunPassed => "correcto",
+        Text::RunFailed => "salida {0}",
+        Text::RunKilled => "terminado",
+        Text::RunError => "error",
+        Text::RunNoOutput => "Sin salida",
+        Text::CommentNotFound => "No hay ningún comentario {0} en esta revisión",
+        Text::NoCommentAtCursor => "Mueve el cursor a un comentario para copiar su id",
+        Text::NoThreadAtCursor => "Mueve el cursor a un hilo para silenciarlo",
+        Text::ThreadMuted => "{0} silenciado",
+        Text::ThreadUnmuted => "{0} ya no está silenciado",
+        Text::ReadOnlyBlocked => "Modo solo lectura: no se pueden modificar revisiones",
+        Text::ReadOnlyLabel => "solo lectura",
+        Text::WarnAtLine => "línea {0}: ",
+        Text::WarnMalformedHunkHeader => {
+            "cabecera de hunk mal formada `{0}`, números de línea inferidos"
+        }
+        Text::WarnMissingHunkHeader => "líneas de hunk sin cabecera @@",
+        Text::WarnTruncatedHunk => {
+            "hunk truncado: se esperaban -{0}/+{1} líneas, se obtuvieron -{2}/+{3}"
+        }
+        Text::WarnUnrecognizedLine => "línea no reconocida mostrada como contexto",
+        Text::BinaryChanged => "Archivo binario modificado",
+        Text::BinaryAdded => "Archivo binario añadido",
+        Text::BinaryDeleted => "Archivo binario eliminado",
+        Text::BadgeNormalized => "normalizado",
+        Text::BadgeNonUtf8 => "no UTF-8",
+        Text::BadgeCrlf => "CRLF",
+        Text::BadgeMixedEol => "EOL mixto",
+        Text::BadgeNoEol => "sin EOL",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(s: &str) -> Vec<usize> {
+        let mut found: Vec<usize> = (0..10)
+            .filter(|i| s.contains(&format!("{{{i}}}")))
+            .collect();
+        found.sort_unstable();
+        found
+    }
+
+    #[test]
+    fn test_catalogs_are_complete_and_consistent() {
+        for &text in Text::ALL {
+            let en = text.in_locale(Locale::En);
+            for locale in Locale::ALL {
+                let translated = text.in_locale(locale);
+                assert!(!translated.is_empty(), "{text:?} empty in {locale:?}");
+                assert_eq!(
+                    placeholders(en),
+                    placeholders(translated),
+                    "{text:?} placeholders differ in {locale:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_locale_tags_and_placeholders() {
+        assert_eq!(Locale::from_tag("es_ES.UTF-8"), Some(Locale::Es));
+        assert_eq!(Locale::from_tag("es-MX"), Some(Locale::Es));
+        assert_eq!(Locale::from_tag("C"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("fr_FR"), None);
+
+        assert_eq!(fill_placeholders("-{0}/+{1} {2}", &[&3, &"x"]), "-3/+x {2}");
+        assert_eq!(fill_placeholders("{} {0", &[&1]), "{} {0");
+    }
+}
@@ -0,0 +1,287 @@
+//! "Action needed" inbox: open threads across all open reviews where
+//! someone else spoke last and the user either wrote the review or took
+//! part in the thread.
+
+use crate::db::{ReviewData, ReviewSummary};
+use crate::status::StatusWorkflow;
+
+/// A thread waiting on the user.
+#[derive(Debug, Clone)]
+pub struct InboxItem {
+    pub review_id: String,
+    pub review_title: String,
+    pub thread_id: String,
+    pub file_path: String,
+    pub line: i64,
+    /// Author of the newest comment
+    pub last_author: String,
+    /// First line of the newest comment
+    pub last_body: String,
+    /// When the newest comment was written (sort key)
+    pub last_at: String,
+}
+
+/// Inbox screen state. Reviews are scanned one per main-loop iteration;
+/// items already found stay listed while the rest load.
+#[derive(Debug, Clone, Default)]
+pub struct Inbox {
+    /// Newest first
+    pub items: Vec<InboxItem>,
+    /// Reviews still to scan, next one last
+    pub pending: Vec<String>,
+    /// Reviews queued since the last full scan started (for the progress
+    /// label)
+    pub total: usize,
+    /// Reviews that couldn't be loaded
+    pub failed: usize,
+    pub selection: usize,
+    pub scroll: usize,
+}
+
+impl Inbox {
+    /// An inbox that will scan the open reviews in `reviews`, in list order.
+    #[must_use]
+    pub fn new(reviews: &[ReviewSummary], statuses: &StatusWorkflow) -> Self {
+        let mut inbox = Self::default();
+        inbox.queue_open(reviews, statuses);
+        inbox
+    }
+
+    /// Queue every open review in `reviews` for a (re)scan and drop items of
+    /// reviews that are no longer open. Progress starts over from none of
+    /// them scanned.
+    pub fn queue_open(&mut self, reviews: &[ReviewSummary], statuses: &StatusWorkflow) {
+        self.failed = 0;
+        self.total = 0;
+        self.pending.clear();
+        let open: Vec<&str> = reviews
+            .iter()
+            .filter(|r| statuses.is_open(&r.status))
+            .map(|r| r.review_id.as_str())
+            .collect();
+        self.items
+            .retain(|item| open.contains(&item.review_id.as_str()));
+        for review_id in open.into_iter().rev() {
+            self.queue(review_id);
+        }
+        self.clamp_selection();
+    }
+
+    /// Queue one review for a rescan, e.g. after the user replied in it.
+    pub fn queue(&mut self, review_id: &str) {
+        if !self.pending.iter().any(|id| id == review_id) {
+            self.pending.push(review_id.to_string());
+            self.total = self.total.max(self.pending.len());
+        }
+    }
+
+    #[must_use]
+    pub fn is_loading(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Reviews scanned so far, out of [`Self::total`].
+    #[must_use]
+    pub fn scanned(&self) -> usize {
+        self.total.saturating_sub(self.pending.len())
+    }
+
+    #[must_use]
+    pub fn selected(&self) -> Option<&InboxItem> {
+        self.items.get(self.selection)
+    }
+
+    /// First item shown in a list `visible` items tall: `scroll`, moved just
+    /// enough to keep the selection in view.
+    #[must_use]
+    pub const fn first_visible(&self, visible: usize) -> usize {
+        if self.selection < self.scroll {
+            self.selection
+        } else if self.selection >= self.scroll + visible {
+            self.selection + 1 - visible
+        } else {
+            self.scroll
+        }
+    }
+
+    /// Swap in the latest scan of one review, keeping the selection on the
+    /// same thread when it's still listed.
+    pub fn replace(&mut self, review_id: &str, items: Vec<InboxItem>) {
+        let selected = self.selected().map(|item| item.thread_id.clone());
+        self.items.retain(|item| item.review_id != review_id);
+        self.items.extend(items);
+        self.items.sort_by(|a, b| b.last_at.cmp(&a.last_at));
+        if let Some(position) =
+            selected.and_then(|id| self.items.iter().position(|item| item.thread_id == id))
+        {
+            self.selection = position;
+        }
+        self.clamp_selection();
+    }
+
+    fn clamp_selection(&mut self) {
+        if self.selection >= self.items.len() {
+            self.selection = self.items.len().saturating_sub(1);
+        }
+    }
+}
+
+/// Threads in `data` waiting on `me`: open, with a newest comment by someone
+/// else, on a review `me` wrote or in a thread `me` commented in.
+#[must_use]
+pub fn action_needed(me: &str, data: &ReviewData) -> Vec<InboxItem> {
+    let my_review = data.detail.author == me;
+    data.threads
+        .iter()
+        .filter(|thread| thread.status == "open")
+        .filter_map(|thread| {
+            let comments = data.comments.get(&thread.thread_id)?;
+            let last = comments.last()?;
+            let involved = my_review || comments.iter().any(|c| c.author == me);
+            (involved && last.author != me).then(|| InboxItem {
+                review_id: data.detail.review_id.clone(),
+                review_title: data.detail.title.clone(),
+                thread_id: thread.thread_id.clone(),
+                file_path: thread.file_path.clone(),
+                line: thread.selection_start,
+                last_author: last.author.clone(),
+                last_body: last.body.lines().next().unwrap_or_default().to_string(),
+                last_at: last.created_at.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::db::{Comment, ReviewDetail, ThreadSummary};
+
+    fn review(author: &str, threads: &[(&str, &str, &[(&str, &str)])]) -> ReviewData {
+        let mut comments = HashMap::new();
+        let threads = threads
+            .iter()
+            .map(|&(thread_id, status, thread_comments)| {
+                comments.insert(
+                    thread_id.to_string(),
+                    thread_comments
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &(author, at))| Comment {
+                            comment_id: format!("cm-{thread_id}-{i}"),
+                            author: author.to_string(),
+                            body: format!("reply from {author}\nmore"),
+                            created_at: at.to_string(),
+                        })
+                        .collect(),
+                );
+                ThreadSummary {
+                    thread_id: thread_id.to_string(),
+                    file_path: "src/lib.rs".to_string(),
+                    selection_start: 3,
+                    selection_end: None,
+                    side: None,
+                    status: status.to_string(),
+                    comment_count: thread_comments.len() as i64,
+                }
+            })
+            .collect();
+        ReviewData {
+            detail: ReviewDetail {
+                review_id: "cr-1".to_string(),
+                jj_change_id: String::new(),
+                initial_commit: String::new(),
+                final_commit: None,
+                title: "Pool connections".to_string(),
+                description: None,
+                author: author.to_string(),
+                created_at: String::new(),
+                status: "open".to_string(),
+                status_changed_at: None,
+                status_changed_by: None,
+                abandon_reason: None,
+                thread_count: 0,
+                open_thread_count: 0,
+                votes: Vec::new(),
+                rewritten_to: None,
+            },
+            threads,
+            comments,
+            files: Vec::new(),
+        }
+    }
+
+    fn thread_ids(items: &[InboxItem]) -> Vec<&str> {
+        items.iter().map(|item| item.thread_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_action_needed_picks_threads_awaiting_me() {
+        let data = review(
+            "bob",
+            &[
+                ("th-a", "open", &[("me", "1"), ("bob", "2")]),
+                ("th-b", "open", &[("bob", "1"), ("me", "2")]),
+                ("th-c", "open", &[("bob", "1"), ("carol", "2")]),
+                ("th-d", "resolved", &[("me", "1"), ("bob", "2")]),
+            ],
+        );
+        let items = action_needed("me", &data);
+        assert_eq!(thread_ids(&items), ["th-a"]);
+        assert_eq!(items[0].last_author, "bob");
+        assert_eq!(items[0].last_body, "reply from bob");
+
+        // On my own review, every thread someone else spoke last in counts
+        let items = action_needed("bob", &data);
+        assert_eq!(thread_ids(&items), ["th-b", "th-c"]);
+    }
+
+    #[test]
+    fn test_replace_sorts_by_recency_and_keeps_selection() {
+        let mut inbox = Inbox::default();
+        let data = review("me", &[("th-old", "open", &[("bob", "2024-01-01")])]);
+        inbox.replace("cr-1", action_needed("me", &data));
+        assert_eq!(inbox.selected().unwrap().thread_id, "th-old");
+
+        let mut data = review("me", &[("th-new", "open", &[("bob", "2025-01-01")])]);
+        data.detail.review_id = "cr-2".to_string();
+        inbox.replace("cr-2", action_needed("me", &data));
+        assert_eq!(thread_ids(&inbox.items), ["th-new", "th-old"]);
+        assert_eq!(inbox.selection, 1);
+
+        inbox.replace("cr-1", Vec::new());
+        assert_eq!(thread_ids(&inbox.items), ["th-new"]);
+        assert_eq!(inbox.selection, 0);
+    }
+
+    #[test]
+    fn test_rescan_counts_progress_from_zero() {
+        let summary = |id: &str| ReviewSummary {
+            review_id: id.to_string(),
+            title: id.to_string(),
+            author: "me".to_string(),
+            status: "open".to_string(),
+            thread_count: 0,
+            open_thread_count: 0,
+            reviewers: Vec::new(),
+            created_at: None,
+            updated_at: None,
+            diff_stats: None,
+        };
+        let statuses = StatusWorkflow::default();
+        let mut inbox = Inbox::new(
+            &[summary("cr-1"), summary("cr-2"), summary("cr-3")],
+            &statuses,
+        );
+        assert_eq!(inbox.pending.pop().as_deref(), Some("cr-1"));
+        assert_eq!((inbox.scanned(), inbox.total), (1, 3));
+
+        inbox.queue_open(&[summary("cr-2")], &statuses);
+        assert_eq!(inbox.pending, ["cr-2"]);
+        assert_eq!((inbox.scanned(), inbox.total), (0, 1));
+        inbox.pending.pop();
+        assert_eq!((inbox.scanned(), inbox.total), (1, 1));
+    }
+}
@@ -6,11 +6,14 @@
 use std::time::{Duration, Instant};
 
 use crate::render_backend::{
-    Event, KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
 };
 
+use crate::db::ThreadSide;
+use crate::keymap::{Action, KeyContext};
+use crate::layout::{block_height, visible_stream_rows};
 use crate::message::Message;
-use crate::model::{Focus, LayoutMode, Model, Screen};
+use crate::model::{DiffViewMode, Focus, LayoutMode, Model, Screen, WheelScroll};
 
 pub fn map_event_to_message(model: &mut Model, event: &Event) -> Message {
     match event {
@@ -31,6 +34,8 @@ pub fn map_event_to_message(model: &mut Model, event: &Event) -> Message {
             match model.screen {
                 Screen::ReviewList => map_review_list_key(key.code, key.modifiers, model),
                 Screen::ReviewDetail => map_review_detail_key(model, key.code, key.modifiers),
+                Screen::Inbox => map_inbox_key(key.code, model),
+                Screen::Compare => map_compare_key(key.code),
             }
         }
         Event::Resize(resize) => Message::Resize {
@@ -40,12 +45,19 @@ pub fn map_event_to_message(model: &mut Model, event: &Event) -> Message {
         Event::Mouse(mouse) => match model.screen {
             Screen::ReviewList => map_review_list_mouse(model, *mouse),
             Screen::ReviewDetail => map_review_detail_mouse(model, *mouse),
+            Screen::Inbox => map_inbox_mouse(model, *mouse),
+            Screen::Compare => map_compare_mouse(*mouse),
         },
-        Event::Paste(_) | Event::FocusGained | Event::FocusLost => Message::Noop,
+        Event::FocusGained => Message::TerminalFocusGained,
+        Event::FocusLost => Message::TerminalFocusLost,
+        Event::Paste(_) => Message::Noop,
     }
 }
 
 fn map_review_list_key(key: KeyCode, modifiers: KeyModifiers, model: &Model) -> Message {
+    if model.filter_bar_active {
+        return map_filter_bar_key(key, modifiers);
+    }
     // When search is active, route chars to search input
     if model.search_active {
         if modifiers.contains(KeyModifiers::CTRL) {
@@ -72,28 +84,139 @@ fn map_review_list_key(key: KeyCode, modifiers: KeyModifiers, model: &Model) ->
         };
     }
 
+    model
+        .keymap
+        .action(KeyContext::List, key, modifiers)
+        .map_or(Message::Noop, |action| list_action_message(model, action))
+}
+
+/// Message for a review list action.
+fn list_action_message(model: &Model, action: Action) -> Message {
+    let selected = || {
+        model
+            .filtered_reviews()
+            .get(model.list_index)
+            .map(|review| review.review_id.clone())
+    };
+    match action {
+        Action::Quit => Message::Quit,
+        Action::Down => Message::ListDown,
+        Action::Up => Message::ListUp,
+        Action::Top => Message::ListTop,
+        Action::Bottom => Message::ListBottom,
+        Action::PageUp => Message::ListPageUp,
+        Action::PageDown => Message::ListPageDown,
+        Action::Open => selected().map_or(Message::Noop, Message::SelectReview),
+        Action::CycleStatusFilter => Message::CycleStatusFilter,
+        Action::CycleSort => Message::SetReviewSort(model.review_sort.next()),
+        Action::GroupByStatus => Message::ToggleReviewGrouping,
+        Action::Search => Message::SearchActivate,
+        Action::FilterBar => Message::FilterBarActivate,
+        Action::Inbox => Message::ShowInbox,
+        Action::CompareMark => selected().map_or(Message::Noop, Message::ToggleCompareMark),
+        Action::Compare => Message::ShowComparison,
+        Action::Preview => Message::TogglePreview,
+        _ => Message::Noop,
+    }
+}
+
+fn map_filter_bar_key(key: KeyCode, modifiers: KeyModifiers) -> Message {
+    if modifiers.contains(KeyModifiers::CTRL) {
+        return match key {
+            KeyCode::Char('w') => Message::FilterBarDeleteWord,
+            KeyCode::Char('u') => Message::FilterBarClear,
+            _ => Message::Noop,
+        };
+    }
+    match key {
+        KeyCode::Esc => Message::FilterBarClear,
+        KeyCode::Enter => Message::FilterBarSubmit,
+        KeyCode::Backspace => Message::FilterBarBackspace,
+        KeyCode::Char(c) => Message::FilterBarInput(c.to_string()),
+        _ => Message::Noop,
+    }
+}
+
+const fn map_compare_key(key: KeyCode) -> Message {
     match key {
         KeyCode::Char('q') => Message::Quit,
+        KeyCode::Esc | KeyCode::Char('h') => Message::Back,
         KeyCode::Char('j') | KeyCode::Down => Message::ListDown,
         KeyCode::Char('k') | KeyCode::Up => Message::ListUp,
         KeyCode::Char('g') | KeyCode::Home => Message::ListTop,
         KeyCode::Char('G') | KeyCode::End => Message::ListBottom,
         KeyCode::PageUp => Message::ListPageUp,
         KeyCode::PageDown => Message::ListPageDown,
-        KeyCode::Enter | KeyCode::Char('l') => {
-            let reviews = model.filtered_reviews();
-            reviews
-                .get(model.list_index)
-                .map_or(Message::Noop, |review| {
-                    Message::SelectReview(review.review_id.clone())
-                })
-        }
-        KeyCode::Char('s') => Message::CycleStatusFilter,
-        KeyCode::Char('/') => Message::SearchActivate,
+        KeyCode::Enter | KeyCode::Char('l') => Message::OpenComparedFile,
         _ => Message::Noop,
     }
 }
 
+/// Scrolling only; files open with Enter.
+const fn map_compare_mouse(mouse: MouseEvent) -> Message {
+    match mouse.kind {
+        MouseEventKind::ScrollUp => Message::ListUp,
+        MouseEventKind::ScrollDown => Message::ListDown,
+        _ => Message::Noop,
+    }
+}
+
+fn map_inbox_key(key: KeyCode, model: &Model) -> Message {
+    match key {
+        KeyCode::Char('q') => Message::Quit,
+        KeyCode::Esc | KeyCode::Char('h') => Message::Back,
+        KeyCode::Char('j') | KeyCode::Down => Message::ListDown,
+        KeyCode::Char('k') | KeyCode::Up => Message::ListUp,
+        KeyCode::Char('g') | KeyCode::Home => Message::ListTop,
+        KeyCode::Char('G') | KeyCode::End => Message::ListBottom,
+        KeyCode::PageUp => Message::ListPageUp,
+        KeyCode::PageDown => Message::ListPageDown,
+        KeyCode::Enter | KeyCode::Char('l') => model
+            .inbox
+            .as_ref()
+            .filter(|inbox| !inbox.items.is_empty())
+            .map_or(Message::Noop, |inbox| {
+                Message::OpenInboxItem(inbox.selection)
+            }),
+        KeyCode::Char('r') => Message::RefreshInbox,
+        _ => Message::Noop,
+    }
+}
+
+/// Scroll moves the selection; a click opens the item. Rows match the review
+/// list: header (5) + status line (2), two rows per item.
+fn map_inbox_mouse(model: &Model, mouse: MouseEvent) -> Message {
+    if model.focus == Focus::CommandPalette {
+        return Message::Noop;
+    }
+    if mouse.is_scroll() {
+        return match mouse.kind {
+            MouseEventKind::ScrollUp => Message::ListUp,
+            MouseEventKind::ScrollDown => Message::ListDown,
+            _ => Message::Noop,
+        };
+    }
+    if mouse.button != MouseButton::Left || mouse.kind != MouseEventKind::Press {
+        return Message::Noop;
+    }
+    let visible = model.list_visible_height().max(1);
+    let Some(inbox) = model.inbox.as_ref() else {
+        return Message::Noop;
+    };
+    // The header block + status line (2), as in view/inbox.rs
+    let Some(row) = mouse
+        .y
+        .checked_sub(crate::layout::screen_header_height() + 2)
+    else {
+        return Message::Noop;
+    };
+    let index = inbox.first_visible(visible) + row as usize / 2;
+    if row as usize / 2 >= visible || index >= inbox.items.len() {
+        return Message::Noop;
+    }
+    Message::OpenInboxItem(index)
+}
+
 fn map_review_list_mouse(model: &mut Model, mouse: MouseEvent) -> Message {
     if model.focus == Focus::CommandPalette {
         return Message::Noop;
@@ -122,9 +245,25 @@ fn map_review_list_mouse(model: &mut Model, mouse: MouseEvent) -> Message {
     if !matches!(mouse.kind, MouseEventKind::Press | MouseEventKind::Release) {
         return Message::Noop;
     }
+    // Column titles and the status filter act once, on press
+    let target = model
+        .list_click_targets
+        .borrow()
+        .iter()
+        .find(|target| target.y == mouse.y && target.x.contains(&mouse.x))
+        .map(|target| target.message.clone());
+    if let Some(message) = target {
+        return match mouse.kind {
+            MouseEventKind::Press => message,
+            _ => Message::Noop,
+        };
+    }
+    if model.preview_x().is_some_and(|x| mouse.x >= u32::from(x)) {
+        return Message::Noop;
+    }
 
-    // Must match review_list.rs: HEADER_HEIGHT(5) + SEARCH_HEIGHT(2)
-    let header_height = 7u32;
+    // Must match review_list.rs: the header block + SEARCH_HEIGHT(2)
+    let header_height = crate::layout::screen_header_height() + 2;
     let footer_height = 2u32;
     let height = u32::from(model.height);
     if height <= header_height + footer_height {
@@ -149,7 +288,15 @@ fn map_review_list_mouse(model: &mut Model, mouse: MouseEvent) -> Message {
 }
 
 fn map_review_detail_mouse(model: &mut Model, mouse: MouseEvent) -> Message {
-    if model.focus == Focus::CommandPalette || model.focus == Focus::Commenting {
+    if matches!(
+        model.focus,
+        Focus::CommandPalette
+            | Focus::Commenting
+            | Focus::ActionMenu
+            | Focus::CommandOutput
+            | Focus::InputLog
+            | Focus::ThreadsPanel
+    ) {
         return Message::Noop;
     }
 
@@ -159,7 +306,7 @@ fn map_review_detail_mouse(model: &mut Model, mouse: MouseEvent) -> Message {
                 Some((
                     0u32,
                     0u32,
-                    u32::from(model.layout_mode.sidebar_width()),
+                    u32::from(model.sidebar_width()),
                     u32::from(model.height),
                 ))
             } else {
@@ -191,44 +338,48 @@ fn map_review_detail_mouse(model: &mut Model, mouse: MouseEvent) -> Message {
                     return Message::Noop;
                 }
                 return match mouse.kind {
-                    MouseEventKind::ScrollUp => Message::PrevFile,
-                    MouseEventKind::ScrollDown => Message::NextFile,
+                    MouseEventKind::ScrollUp => Message::SidebarScrollUp,
+                    MouseEventKind::ScrollDown => Message::SidebarScrollDown,
                     _ => Message::Noop,
                 };
             }
         }
 
-        return match mouse.kind {
-            MouseEventKind::ScrollUp => Message::ScrollUp,
-            MouseEventKind::ScrollDown => Message::ScrollDown,
+        return match (mouse.kind, model.wheel_scroll()) {
+            (MouseEventKind::ScrollUp, WheelScroll::Cursor) => Message::ScrollUp,
+            (MouseEventKind::ScrollDown, WheelScroll::Cursor) => Message::ScrollDown,
+            (MouseEventKind::ScrollUp, WheelScroll::Viewport) => Message::ViewportScrollUp,
+            (MouseEventKind::ScrollDown, WheelScroll::Viewport) => Message::ViewportScrollDown,
             _ => Message::Noop,
         };
     }
 
+    if let Some(message) = map_divider_mouse(model, mouse) {
+        return message;
+    }
     if mouse.button != MouseButton::Left {
         return Message::Noop;
     }
 
+    let in_sidebar = sidebar_rect.is_some_and(|(x, y, width, height)| {
+        mouse.x >= x
+            && mouse.x < x.saturating_add(width)
+            && mouse.y >= y
+            && mouse.y < y.saturating_add(height)
+    });
+    if !in_sidebar || model.diff_drag_anchor.is_some() {
+        return map_diff_pane_mouse(model, mouse, sidebar_rect.map_or(0, |(_, _, w, _)| w));
+    }
+
     if !matches!(mouse.kind, MouseEventKind::Press | MouseEventKind::Release) {
         return Message::Noop;
     }
 
-    let Some((sidebar_x, sidebar_y, sidebar_width, sidebar_height)) = sidebar_rect else {
+    let Some((_, sidebar_y, _, sidebar_height)) = sidebar_rect else {
         return Message::Noop;
     };
 
-    if mouse.x < sidebar_x
-        || mouse.x >= sidebar_x.saturating_add(sidebar_width)
-        || mouse.y < sidebar_y
-        || mouse.y >= sidebar_y.saturating_add(sidebar_height)
-    {
-        return Message::Noop;
-    }
-
-    let mut list_start = sidebar_y + 1;
-    if model.current_review.is_some() {
-        list_start = list_start.saturating_add(5);
-    }
+    let list_start = model.sidebar_list_top.get().max(sidebar_y + 1);
     let bottom = sidebar_y + sidebar_height.saturating_sub(1);
     if list_start >= bottom || mouse.y < list_start || mouse.y >= bottom {
         return Message::Noop;
@@ -244,9 +395,77 @@ fn map_review_detail_mouse(model: &mut Model, mouse: MouseEvent) -> Message {
     Message::Noop
 }
 
+/// The sidebar divider, its last column and the one after it: a press
+/// grabs it and dragging resizes the sidebar; plain motion over it shows
+/// the grip. `None` leaves the event to the panes.
+fn map_divider_mouse(model: &Model, mouse: MouseEvent) -> Option<Message> {
+    if model.sidebar_resizing {
+        return Some(match mouse.kind {
+            MouseEventKind::Move => {
+                Message::ResizeSidebar(u16::try_from(mouse.x + 1).unwrap_or(u16::MAX))
+            }
+            MouseEventKind::Release => Message::EndSidebarResize,
+            _ => Message::Noop,
+        });
+    }
+    if model.layout_mode == LayoutMode::Single
+        || !model.sidebar_visible
+        || model.diff_drag_anchor.is_some()
+    {
+        return None;
+    }
+    let width = u32::from(model.sidebar_width());
+    let on_divider = mouse.x + 1 == width || mouse.x == width;
+    match mouse.kind {
+        MouseEventKind::Press if on_divider && mouse.button == MouseButton::Left => {
+            Some(Message::StartSidebarResize)
+        }
+        MouseEventKind::Move
+            if mouse.button == MouseButton::None && on_divider != model.divider_hover =>
+        {
+            Some(Message::HoverDivider(on_divider))
+        }
+        _ => None,
+    }
+}
+
+/// Left-button press, drag and release in the diff pane, which starts
+/// `pane_x` columns in: a press puts the cursor on the line under it, and
+/// dragging selects lines from there as in visual mode.
+fn map_diff_pane_mouse(model: &Model, mouse: MouseEvent, pane_x: u32) -> Message {
+    let top = block_height(1) as u32;
+    let visible = visible_stream_rows(model.height);
+    if visible == 0 {
+        return Message::Noop;
+    }
+    // Past the top or bottom edge while dragging, one row beyond it, so the
+    // view follows the selection
+    let row = if mouse.y < top {
+        model.diff_scroll.saturating_sub(1)
+    } else {
+        model.diff_scroll + ((mouse.y - top) as usize).min(visible)
+    };
+    match mouse.kind {
+        MouseEventKind::Press if mouse.y >= top && (mouse.y as usize) < top as usize + visible => {
+            let pane_width = u32::from(model.width).saturating_sub(pane_x);
+            let side = (model.view_mode_at_row(row) == DiffViewMode::SideBySide).then(|| {
+                if mouse.x >= pane_x + pane_width / 2 {
+                    ThreadSide::New
+                } else {
+                    ThreadSide::Old
+                }
+            });
+            Message::ClickDiffRow { row, side }
+        }
+        MouseEventKind::Move if model.diff_drag_anchor.is_some() => Message::DragDiffRow(row),
+        MouseEventKind::Release if model.diff_drag_anchor.is_some() => Message::EndDiffDrag,
+        _ => Message::Noop,
+    }
+}
+
 fn should_handle_scroll(last: &mut Option<(Instant, i8)>, direction: i8) -> bool {
     const DEBOUNCE: Duration = Duration::from_millis(5);
-    let now = Instant::now();
+    let now = crate::clock::now();
     if let Some((prev_at, prev_dir)) = last {
         if *prev_dir == direction && now.duration_since(*prev_at) < DEBOUNCE {
             return false;
@@ -256,7 +475,63 @@ fn should_handle_scroll(last: &mut Option<(Instant, i8)>, direction: i8) -> bool
     true
 }
 
-fn map_review_detail_key(model: &Model, key: KeyCode, modifiers: KeyModifiers) -> Message {
+/// Keys while typing a `/` search on a review.
+fn map_diff_search_key(key: KeyCode, modifiers: KeyModifiers) -> Message {
+    if modifiers.contains(KeyModifiers::CTRL) {
+        return match key {
+            KeyCode::Char('w') => Message::SearchDeleteWord,
+            KeyCode::Char('u') => Message::SearchClearLine,
+            _ => Message::Noop,
+        };
+    }
+    match key {
+        KeyCode::Esc => Message::SearchClear,
+        KeyCode::Enter => Message::SearchSubmit,
+        KeyCode::Backspace => Message::SearchBackspace,
+        KeyCode::Char(c) => Message::SearchInput(c.to_string()),
+        _ => Message::Noop,
+    }
+}
+
+fn map_review_detail_key(model: &mut Model, key: KeyCode, modifiers: KeyModifiers) -> Message {
+    // Whatever comes next ends a sequence started by the last key
+    let pending = model.pending_key.take();
+    if model.focus == Focus::DiffPane && model.diff_search.as_ref().is_some_and(|s| s.editing) {
+        return map_diff_search_key(key, modifiers);
+    }
+    // While a saved comment waits to be sent, `u` takes it back
+    if model.pending_comment_submission.is_some()
+        && model.focus != Focus::Commenting
+        && key == KeyCode::Char('u')
+        && modifiers == KeyModifiers::empty()
+    {
+        return Message::CancelCommentSubmission;
+    }
+    // Alt+1… jump among the files visited last, outside the keymap
+    if let KeyCode::Char(digit @ '1'..='9') = key {
+        if modifiers == KeyModifiers::ALT && model.focus != Focus::Commenting {
+            return Message::JumpToRecentFile(digit as usize - '0' as usize);
+        }
+    }
+    let context = match model.focus {
+        Focus::FileSidebar => Some(KeyContext::Sidebar),
+        Focus::DiffPane if model.visual_mode => Some(KeyContext::Visual),
+        Focus::DiffPane => Some(KeyContext::Diff),
+        _ => None,
+    };
+    if let Some(context) = context {
+        let action = match pending {
+            Some(first) => model.keymap.sequence_action(context, first, key, modifiers),
+            None if model.keymap.starts_sequence(context, key, modifiers) => {
+                model.pending_key = Some(KeyEvent::new(key, modifiers));
+                return Message::Noop;
+            }
+            None => model.keymap.action(context, key, modifiers),
+        };
+        return action.map_or(Message::Noop, |action| {
+            review_action_message(model, context, action)
+        });
+    }
     if modifiers.contains(KeyModifiers::CTRL) {
         match key {
             KeyCode::Char('j') => return Message::ScrollTenDown,
@@ -266,110 +541,236 @@ fn map_review_detail_key(model: &Model, key: KeyCode, modifiers: KeyModifiers) -
     }
 
     match model.focus {
-        Focus::FileSidebar => match key {
-            KeyCode::Char('q') => Message::Quit,
-            KeyCode::Esc | KeyCode::Char('h') => Message::Back,
-            KeyCode::Tab | KeyCode::Char('l') => Message::ToggleFocus,
-            KeyCode::Char('j') | KeyCode::Down => Message::NextFile,
-            KeyCode::Char('k') | KeyCode::Up => Message::PrevFile,
-            KeyCode::Char('g') | KeyCode::Home => Message::SidebarTop,
-            KeyCode::Char('G') | KeyCode::End => Message::SidebarBottom,
-            KeyCode::Enter => Message::SidebarSelect,
-            KeyCode::Char('s') => Message::ToggleSidebar,
-            _ => Message::Noop,
-        },
-        Focus::DiffPane if model.visual_mode => match key {
-            KeyCode::Char('j') | KeyCode::Down => Message::CursorDown,
-            KeyCode::Char('k') | KeyCode::Up => Message::CursorUp,
-            KeyCode::Char('g') | KeyCode::Home => Message::CursorTop,
-            KeyCode::Char('G') | KeyCode::End => Message::CursorBottom,
-            KeyCode::Char('a') => Message::StartComment,
-            KeyCode::Char('A') => Message::StartCommentExternal,
-            KeyCode::Char('V') | KeyCode::Esc => Message::VisualToggle,
-            _ => Message::Noop,
-        },
-        Focus::DiffPane => match key {
-            KeyCode::Char('q') => Message::Quit,
-            KeyCode::Esc => Message::Back,
-            KeyCode::Tab | KeyCode::Char('h') => Message::ToggleFocus,
-            KeyCode::Char('j') | KeyCode::Down => Message::CursorDown,
-            KeyCode::Char('k') | KeyCode::Up => Message::CursorUp,
-            KeyCode::Char('g') | KeyCode::Home => Message::CursorTop,
-            KeyCode::Char('G') | KeyCode::End => Message::CursorBottom,
-            KeyCode::Char('n') => Message::NextThread,
-            KeyCode::Char('p' | 'N') => Message::PrevThread,
-            KeyCode::Char('v') => Message::ToggleDiffView,
-            KeyCode::Char('w') => Message::ToggleDiffWrap,
-            KeyCode::Char('o') => Message::OpenFileInEditor,
-            KeyCode::Char('u') => Message::ScrollHalfPageUp,
-            KeyCode::Char('d') => Message::ScrollHalfPageDown,
-            KeyCode::Char('b') | KeyCode::PageUp => Message::PageUp,
-            KeyCode::Char('f') | KeyCode::PageDown => Message::PageDown,
-            KeyCode::Char('s') => Message::ToggleSidebar,
-            KeyCode::Enter => {
-                // Expand the current thread (if one is selected via n/p)
-                model
-                    .expanded_thread
-                    .as_ref()
-                    .map_or(Message::NextThread, |id| Message::ExpandThread(id.clone()))
-            }
-            KeyCode::Char('a') => Message::StartComment,
-            KeyCode::Char('A') => Message::StartCommentExternal,
-            KeyCode::Char('V') => Message::VisualToggle,
-            KeyCode::Char('[') => Message::PrevFile,
-            KeyCode::Char(']') => Message::NextFile,
+        Focus::ActionMenu => map_action_menu_key(model, key),
+        Focus::ThreadsPanel => map_threads_panel_key(key),
+        Focus::CommandOutput => map_command_output_key(key),
+        Focus::InputLog => map_input_log_key(key),
+        Focus::ThreadExpanded => map_thread_expanded_key(model, key),
+        Focus::Commenting => map_comment_editor_key(key, modifiers),
+        _ => Message::Noop,
+    }
+}
+
+/// Keys in the `.` action menu: a row's letter runs it.
+fn map_action_menu_key(model: &Model, key: KeyCode) -> Message {
+    match key {
+        KeyCode::Esc | KeyCode::Char('.' | 'q') => Message::HideActionMenu,
+        KeyCode::Char('j') | KeyCode::Down => Message::ActionMenuNext,
+        KeyCode::Char('k') | KeyCode::Up => Message::ActionMenuPrev,
+        KeyCode::Enter => Message::ActionMenuExecute,
+        KeyCode::Char(c) => model
+            .action_menu
+            .iter()
+            .position(|action| action.key() == c)
+            .map_or(Message::Noop, Message::ActionMenuPick),
+        _ => Message::Noop,
+    }
+}
+
+/// Keys in the threads panel.
+const fn map_threads_panel_key(key: KeyCode) -> Message {
+    match key {
+        KeyCode::Esc | KeyCode::Char('q' | 't') => Message::ToggleThreadsPanel,
+        KeyCode::Char('j') | KeyCode::Down => Message::ThreadsPanelDown,
+        KeyCode::Char('k') | KeyCode::Up => Message::ThreadsPanelUp,
+        KeyCode::Char('g') | KeyCode::Home => Message::ThreadsPanelTop,
+        KeyCode::Char('G') | KeyCode::End => Message::ThreadsPanelBottom,
+        KeyCode::Enter | KeyCode::Char('l') => Message::ThreadsPanelSelect,
+        KeyCode::Char('y') => Message::CopyThreadLink,
+        _ => Message::Noop,
+    }
+}
+
+/// Keys in the `review_command` output overlay.
+const fn map_command_output_key(key: KeyCode) -> Message {
+    match key {
+        KeyCode::Esc | KeyCode::Char('q' | '!') => Message::HideCommandOutput,
+        KeyCode::Char('j') | KeyCode::Down => Message::CommandOutputDown,
+        KeyCode::Char('k') | KeyCode::Up => Message::CommandOutputUp,
+        KeyCode::Char('g') | KeyCode::Home => Message::CommandOutputTop,
+        KeyCode::Char('G') | KeyCode::End => Message::CommandOutputBottom,
+        KeyCode::Char('r') => Message::RunReviewCommand,
+        _ => Message::Noop,
+    }
+}
+
+/// Keys in the input latency log.
+const fn map_input_log_key(key: KeyCode) -> Message {
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') => Message::HideInputLog,
+        KeyCode::Char('j') | KeyCode::Down => Message::InputLogDown,
+        KeyCode::Char('k') | KeyCode::Up => Message::InputLogUp,
+        KeyCode::Char('g') | KeyCode::Home => Message::InputLogTop,
+        KeyCode::Char('G') | KeyCode::End => Message::InputLogBottom,
+        _ => Message::Noop,
+    }
+}
+
+/// Keys while a thread is expanded: scrolling, reply, and `R` to resolve
+/// or reopen it.
+fn map_thread_expanded_key(model: &Model, key: KeyCode) -> Message {
+    match key {
+        KeyCode::Esc => Message::CollapseThread,
+        KeyCode::Char('j') | KeyCode::Down => Message::ScrollDown,
+        KeyCode::Char('k') | KeyCode::Up => Message::ScrollUp,
+        KeyCode::Char('g') | KeyCode::Home => Message::ScrollTop,
+        KeyCode::Char('G') | KeyCode::End => Message::ScrollBottom,
+        KeyCode::Char('r') => Message::StartReply,
+        KeyCode::Char('R') => model
+            .expanded_thread
+            .as_ref()
+            .and_then(|id| model.threads.iter().find(|t| t.thread_id == *id))
+            .map_or(Message::Noop, |thread| {
+                if thread.status == "open" {
+                    Message::ResolveThread(thread.thread_id.clone())
+                } else {
+                    Message::ReopenThread(thread.thread_id.clone())
+                }
+            }),
+        _ => Message::Noop,
+    }
+}
+
+/// Keys in the inline comment editor.
+fn map_comment_editor_key(key: KeyCode, modifiers: KeyModifiers) -> Message {
+    if modifiers.contains(KeyModifiers::CTRL) {
+        return match key {
+            KeyCode::Char('s') => Message::SaveComment,
+            KeyCode::Char('w') => Message::CommentDeleteWord,
+            KeyCode::Char('u') => Message::CommentClearLine,
+            KeyCode::Char('g') => Message::CommentInsertSuggestion,
+            KeyCode::Char('a') => Message::CommentHome,
+            KeyCode::Char('e') => Message::CommentEnd,
+            KeyCode::Char('b') => Message::CommentCursorLeft,
+            KeyCode::Char('f') => Message::CommentCursorRight,
             _ => Message::Noop,
-        },
-        Focus::ThreadExpanded => match key {
-            KeyCode::Esc => Message::CollapseThread,
-            KeyCode::Char('j') | KeyCode::Down => Message::ScrollDown,
-            KeyCode::Char('k') | KeyCode::Up => Message::ScrollUp,
-            KeyCode::Char('g') | KeyCode::Home => Message::ScrollTop,
-            KeyCode::Char('G') | KeyCode::End => Message::ScrollBottom,
-            KeyCode::Char('r' | 'R') => model
-                .expanded_thread
-                .as_ref()
-                .map_or(Message::Noop, |id| Message::ResolveThread(id.clone())),
+        };
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        return match key {
+            KeyCode::Char('b') => Message::CommentWordLeft,
+            KeyCode::Char('f') => Message::CommentWordRight,
             _ => Message::Noop,
-        },
-        Focus::Commenting => {
-            if modifiers.contains(KeyModifiers::CTRL) {
-                return match key {
-                    KeyCode::Char('s') => Message::SaveComment,
-                    KeyCode::Char('w') => Message::CommentDeleteWord,
-                    KeyCode::Char('u') => Message::CommentClearLine,
-                    KeyCode::Char('a') => Message::CommentHome,
-                    KeyCode::Char('e') => Message::CommentEnd,
-                    KeyCode::Char('b') => Message::CommentCursorLeft,
-                    KeyCode::Char('f') => Message::CommentCursorRight,
-                    _ => Message::Noop,
-                };
-            }
-            if modifiers.contains(KeyModifiers::ALT) {
-                return match key {
-                    KeyCode::Char('b') => Message::CommentWordLeft,
-                    KeyCode::Char('f') => Message::CommentWordRight,
-                    _ => Message::Noop,
-                };
-            }
-            match key {
-                KeyCode::Esc => Message::CancelComment,
-                KeyCode::Enter => Message::CommentNewline,
-                KeyCode::Up => Message::CommentCursorUp,
-                KeyCode::Down => Message::CommentCursorDown,
-                KeyCode::Left => Message::CommentCursorLeft,
-                KeyCode::Right => Message::CommentCursorRight,
-                KeyCode::Home => Message::CommentHome,
-                KeyCode::End => Message::CommentEnd,
-                KeyCode::Backspace => Message::CommentInputBackspace,
-                KeyCode::Char(c) => Message::CommentInput(c.to_string()),
-                _ => Message::Noop,
-            }
-        }
+        };
+    }
+    match key {
+        KeyCode::Esc => Message::CancelComment,
+        KeyCode::Enter => Message::CommentNewline,
+        KeyCode::Up => Message::CommentCursorUp,
+        KeyCode::Down => Message::CommentCursorDown,
+        KeyCode::Left => Message::CommentCursorLeft,
+        KeyCode::Right => Message::CommentCursorRight,
+        KeyCode::Home => Message::CommentHome,
+        KeyCode::End => Message::CommentEnd,
+        KeyCode::Backspace => Message::CommentInputBackspace,
+        KeyCode::Char(c) => Message::CommentInput(c.to_string()),
         _ => Message::Noop,
     }
 }
 
+/// Message for a sidebar, diff pane or visual mode action.
+fn review_action_message(model: &Model, context: KeyContext, action: Action) -> Message {
+    let sidebar = context == KeyContext::Sidebar;
+    let searching = model.diff_search_query().is_some();
+    match action {
+        Action::Quit => Message::Quit,
+        Action::Back if !sidebar && model.diff_search.is_some() => Message::SearchClear,
+        Action::Collapse if model.sidebar_tree() => Message::SidebarCollapse,
+        Action::Expand if model.sidebar_tree() => Message::SidebarExpand,
+        // Flat, `h`/`l` leave the sidebar as before
+        Action::Back | Action::Collapse => Message::Back,
+        Action::ToggleFocus | Action::Expand => Message::ToggleFocus,
+        Action::Down if sidebar => Message::NextFile,
+        Action::Up if sidebar => Message::PrevFile,
+        Action::Top if sidebar => Message::SidebarTop,
+        Action::Bottom if sidebar => Message::SidebarBottom,
+        Action::Open if sidebar => Message::SidebarSelect,
+        Action::PageUp if sidebar => Message::SidebarPageUp,
+        Action::PageDown if sidebar => Message::SidebarPageDown,
+        Action::Down => Message::CursorDown,
+        Action::Up => Message::CursorUp,
+        Action::Top => Message::CursorTop,
+        Action::Bottom => Message::CursorBottom,
+        Action::ScrollTenDown => Message::ScrollTenDown,
+        Action::ScrollTenUp => Message::ScrollTenUp,
+        Action::HalfPageUp => Message::ScrollHalfPageUp,
+        Action::HalfPageDown => Message::ScrollHalfPageDown,
+        Action::PageUp => Message::PageUp,
+        Action::PageDown => Message::PageDown,
+        Action::NextHunk => Message::CursorNextHunk,
+        Action::PrevHunk => Message::CursorPrevHunk,
+        Action::NextFile => Message::CursorNextFile,
+        Action::PrevFile => Message::CursorPrevFile,
+        Action::NextChange => Message::CursorNextChange,
+        Action::PrevChange => Message::CursorPrevChange,
+        Action::SidebarNextFile => Message::NextFile,
+        Action::SidebarPrevFile => Message::PrevFile,
+        Action::Search => Message::SearchActivate,
+        Action::Next if searching => Message::SearchNext,
+        Action::Next => Message::NextThread,
+        Action::Prev if searching => Message::SearchPrev,
+        Action::Prev | Action::PrevThread => Message::PrevThread,
+        // Expand the current thread (if one is selected via n/p)
+        Action::ExpandThread => model
+            .expanded_thread
+            .as_ref()
+            .map_or(Message::NextThread, |id| Message::ExpandThread(id.clone())),
+        Action::ToggleView => Message::ToggleDiffView,
+        Action::ToggleFileView => Message::ToggleFileDiffView,
+        Action::ToggleWrap => Message::ToggleDiffWrap,
+        Action::ToggleWordDiff => Message::ToggleWordDiff,
+        Action::ToggleBlame => Message::ToggleBlame,
+        Action::ToggleViewed => Message::ToggleFileViewed,
+        Action::Undo => Message::Undo,
+        Action::Redo => Message::Redo,
+        Action::ToggleSidebar => Message::ToggleSidebar,
+        Action::OpenInEditor => Message::OpenFileInEditor,
+        Action::Comment => Message::StartComment,
+        Action::CommentExternal => Message::StartCommentExternal,
+        Action::QuoteComment => Message::StartQuotedComment,
+        Action::HunkComment => Message::StartHunkComment,
+        Action::FileComment => Message::StartFileComment,
+        Action::Visual => Message::VisualToggle,
+        // On a thread's comments, `y` copies its link
+        Action::CopyLines if !model.visual_mode && model.thread_at_cursor().is_some() => {
+            Message::CopyThreadLink
+        }
+        Action::CopyLines => Message::CopyLines,
+        Action::CopyPath => Message::CopyFilePath,
+        Action::CopyCommentId => Message::CopyCommentId,
+        Action::CopyCheckout => Message::CopyCheckoutCommand,
+        Action::CommandOutput => Message::ShowCommandOutput,
+        Action::ThreadsPanel => Message::ToggleThreadsPanel,
+        Action::ActionMenu => Message::ShowActionMenu,
+        Action::Mute => Message::ToggleMuteThread,
+        Action::ApplySuggestion => Message::ApplySuggestion,
+        Action::UndoSuggestion => Message::UndoSuggestion,
+        Action::SideOld => sbs_side_message(model, ThreadSide::Old),
+        Action::SideNew => sbs_side_message(model, ThreadSide::New),
+        Action::FoldHunk => Message::ToggleHunkFold,
+        Action::FoldFile => Message::ToggleFileFold,
+        Action::ExpandUp => Message::ExpandContextUp,
+        Action::ExpandDown => Message::ExpandContextDown,
+        Action::Open
+        | Action::FilterBar
+        | Action::CycleStatusFilter
+        | Action::CycleSort
+        | Action::GroupByStatus
+        | Action::Inbox
+        | Action::CompareMark
+        | Action::Compare
+        | Action::Preview => Message::Noop,
+    }
+}
+
+/// `←`/`→` pick the side-by-side column; they do nothing in unified mode.
+fn sbs_side_message(model: &Model, side: ThreadSide) -> Message {
+    if model.view_mode_at_row(model.diff_cursor) != DiffViewMode::SideBySide {
+        return Message::Noop;
+    }
+    Message::FocusSbsSide(side)
+}
+
 fn map_command_palette_key(key: KeyCode, modifiers: KeyModifiers) -> Message {
     if modifiers.contains(KeyModifiers::CTRL) {
         return match key {
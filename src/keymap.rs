@@ -0,0 +1,737 @@
+//! Key bindings for the review list, sidebar and diff pane: which chord
+//! runs which named action.
+//!
+//! The defaults are a table of (context, action, chords). The `keys`
+//! section of `ui.json` replaces an action's chords, everywhere it's
+//! available (`"down": ["j", "down"]`) or in one context
+//! (`"diff.toggle-focus": ["tab"]`); an empty list unbinds it. A binding
+//! can be two keys apart by a space (`"z a"`). Turning an
+//! action into a message is up to `input.rs`, which has the model at hand.
+//! Text entry (search, filter bar, comment editor) isn't rebindable.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context as _};
+use serde::{Deserialize, Serialize};
+
+use crate::render_backend::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Where a binding applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    /// Review list
+    List,
+    /// File sidebar of a review
+    Sidebar,
+    /// Diff pane of a review
+    Diff,
+    /// Diff pane in visual mode
+    Visual,
+}
+
+impl KeyContext {
+    pub const ALL: [Self; 4] = [Self::List, Self::Sidebar, Self::Diff, Self::Visual];
+
+    /// Name used as a prefix in `keys`, e.g. `diff.down`.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::List => "list",
+            Self::Sidebar => "sidebar",
+            Self::Diff => "diff",
+            Self::Visual => "visual",
+        }
+    }
+}
+
+/// Something a key can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Back,
+    ToggleFocus,
+    Collapse,
+    Expand,
+    Down,
+    Up,
+    Top,
+    Bottom,
+    HalfPageUp,
+    HalfPageDown,
+    PageUp,
+    PageDown,
+    ScrollTenUp,
+    ScrollTenDown,
+    Open,
+    Search,
+    FilterBar,
+    CycleStatusFilter,
+    CycleSort,
+    GroupByStatus,
+    Inbox,
+    CompareMark,
+    Compare,
+    Preview,
+    NextHunk,
+    PrevHunk,
+    NextFile,
+    PrevFile,
+    NextChange,
+    PrevChange,
+    SidebarNextFile,
+    SidebarPrevFile,
+    Next,
+    Prev,
+    PrevThread,
+    ExpandThread,
+    ToggleView,
+    ToggleFileView,
+    ToggleWrap,
+    ToggleWordDiff,
+    ToggleBlame,
+    ToggleViewed,
+    Undo,
+    Redo,
+    ToggleSidebar,
+    OpenInEditor,
+    Comment,
+    CommentExternal,
+    QuoteComment,
+    HunkComment,
+    FileComment,
+    Visual,
+    CopyLines,
+    CopyPath,
+    CopyCommentId,
+    CopyCheckout,
+    CommandOutput,
+    ThreadsPanel,
+    ActionMenu,
+    Mute,
+    ApplySuggestion,
+    UndoSuggestion,
+    SideOld,
+    SideNew,
+    FoldHunk,
+    FoldFile,
+    ExpandUp,
+    ExpandDown,
+}
+
+impl Action {
+    pub const ALL: &'static [Self] = &[
+        Self::Quit,
+        Self::Back,
+        Self::ToggleFocus,
+        Self::Collapse,
+        Self::Expand,
+        Self::Down,
+        Self::Up,
+        Self::Top,
+        Self::Bottom,
+        Self::HalfPageUp,
+        Self::HalfPageDown,
+        Self::PageUp,
+        Self::PageDown,
+        Self::ScrollTenUp,
+        Self::ScrollTenDown,
+        Self::Open,
+        Self::Search,
+        Self::FilterBar,
+        Self::CycleStatusFilter,
+        Self::CycleSort,
+        Self::GroupByStatus,
+        Self::Inbox,
+        Self::CompareMark,
+        Self::Compare,
+        Self::Preview,
+        Self::NextHunk,
+        Self::PrevHunk,
+        Self::NextFile,
+        Self::PrevFile,
+        Self::NextChange,
+        Self::PrevChange,
+        Self::SidebarNextFile,
+        Self::SidebarPrevFile,
+        Self::Next,
+        Self::Prev,
+        Self::PrevThread,
+        Self::ExpandThread,
+        Self::ToggleView,
+        Self::ToggleFileView,
+        Self::ToggleWrap,
+        Self::ToggleWordDiff,
+        Self::ToggleBlame,
+        Self::ToggleViewed,
+        Self::Undo,
+        Self::Redo,
+        Self::ToggleSidebar,
+        Self::OpenInEditor,
+        Self::Comment,
+        Self::CommentExternal,
+        Self::QuoteComment,
+        Self::HunkComment,
+        Self::FileComment,
+        Self::Visual,
+        Self::CopyLines,
+        Self::CopyPath,
+        Self::CopyCommentId,
+        Self::CopyCheckout,
+        Self::CommandOutput,
+        Self::ThreadsPanel,
+        Self::ActionMenu,
+        Self::Mute,
+        Self::ApplySuggestion,
+        Self::UndoSuggestion,
+        Self::SideOld,
+        Self::SideNew,
+        Self::FoldHunk,
+        Self::FoldFile,
+        Self::ExpandUp,
+        Self::ExpandDown,
+    ];
+
+    /// Name used in `keys`.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::Back => "back",
+            Self::ToggleFocus => "toggle-focus",
+            Self::Collapse => "collapse",
+            Self::Expand => "expand",
+            Self::Down => "down",
+            Self::Up => "up",
+            Self::Top => "top",
+            Self::Bottom => "bottom",
+            Self::HalfPageUp => "half-page-up",
+            Self::HalfPageDown => "half-page-down",
+            Self::PageUp => "page-up",
+            Self::PageDown => "page-down",
+            Self::ScrollTenUp => "scroll-ten-up",
+            Self::ScrollTenDown => "scroll-ten-down",
+            Self::Open => "open",
+            Self::Search => "search",
+            Self::FilterBar => "filter-bar",
+            Self::CycleStatusFilter => "cycle-status-filter",
+            Self::CycleSort => "cycle-sort",
+            Self::GroupByStatus => "group-by-status",
+            Self::Inbox => "inbox",
+            Self::CompareMark => "compare-mark",
+            Self::Compare => "compare",
+            Self::Preview => "preview",
+            Self::NextHunk => "next-hunk",
+            Self::PrevHunk => "prev-hunk",
+            Self::NextFile => "next-file",
+            Self::PrevFile => "prev-file",
+            Self::NextChange => "next-change",
+            Self::PrevChange => "prev-change",
+            Self::SidebarNextFile => "sidebar-next-file",
+            Self::SidebarPrevFile => "sidebar-prev-file",
+            Self::Next => "next",
+            Self::Prev => "prev",
+            Self::PrevThread => "prev-thread",
+            Self::ExpandThread => "expand-thread",
+            Self::ToggleView => "toggle-view",
+            Self::ToggleFileView => "toggle-file-view",
+            Self::ToggleWrap => "toggle-wrap",
+            Self::ToggleWordDiff => "toggle-word-diff",
+            Self::ToggleBlame => "toggle-blame",
+            Self::ToggleViewed => "toggle-viewed",
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+            Self::ToggleSidebar => "toggle-sidebar",
+            Self::OpenInEditor => "open-in-editor",
+            Self::Comment => "comment",
+            Self::CommentExternal => "comment-external",
+            Self::QuoteComment => "quote-comment",
+            Self::HunkComment => "hunk-comment",
+            Self::FileComment => "file-comment",
+            Self::Visual => "visual",
+            Self::CopyLines => "copy-lines",
+            Self::CopyPath => "copy-path",
+            Self::CopyCommentId => "copy-comment-id",
+            Self::CopyCheckout => "copy-checkout",
+            Self::CommandOutput => "command-output",
+            Self::ThreadsPanel => "threads-panel",
+            Self::ActionMenu => "action-menu",
+            Self::Mute => "mute",
+            Self::ApplySuggestion => "apply-suggestion",
+            Self::UndoSuggestion => "undo-suggestion",
+            Self::SideOld => "side-old",
+            Self::SideNew => "side-new",
+            Self::FoldHunk => "fold-hunk",
+            Self::FoldFile => "fold-file",
+            Self::ExpandUp => "expand-up",
+            Self::ExpandDown => "expand-down",
+        }
+    }
+}
+
+use Action as A;
+use KeyContext::{Diff, List, Sidebar, Visual};
+
+/// Built-in bindings.
+const DEFAULTS: &[(KeyContext, Action, &[&str])] = &[
+    (List, A::Quit, &["q"]),
+    (List, A::Down, &["j", "down"]),
+    (List, A::Up, &["k", "up"]),
+    (List, A::Top, &["g", "home"]),
+    (List, A::Bottom, &["G", "end"]),
+    (List, A::PageUp, &["pageup"]),
+    (List, A::PageDown, &["pagedown"]),
+    (List, A::Open, &["enter", "l"]),
+    (List, A::CycleStatusFilter, &["s"]),
+    (List, A::CycleSort, &["o"]),
+    (List, A::GroupByStatus, &["O"]),
+    (List, A::Search, &["/"]),
+    (List, A::FilterBar, &["f"]),
+    (List, A::Inbox, &["i"]),
+    (List, A::CompareMark, &["space"]),
+    (List, A::Compare, &["c"]),
+    (List, A::Preview, &["p"]),
+    (Sidebar, A::Quit, &["q"]),
+    (Sidebar, A::Back, &["esc"]),
+    (Sidebar, A::ToggleFocus, &["tab"]),
+    (Sidebar, A::Collapse, &["h"]),
+    (Sidebar, A::Expand, &["l"]),
+    (Sidebar, A::Down, &["j", "down"]),
+    (Sidebar, A::Up, &["k", "up"]),
+    (Sidebar, A::Top, &["g", "home"]),
+    (Sidebar, A::Bottom, &["G", "end"]),
+    (Sidebar, A::PageUp, &["pageup"]),
+    (Sidebar, A::PageDown, &["pagedown"]),
+    (Sidebar, A::ScrollTenDown, &["ctrl+j"]),
+    (Sidebar, A::ScrollTenUp, &["ctrl+k"]),
+    (Sidebar, A::Open, &["enter"]),
+    (Sidebar, A::ToggleSidebar, &["s"]),
+    (Sidebar, A::Mute, &["m"]),
+    (Sidebar, A::FileComment, &["a"]),
+    (Sidebar, A::ToggleViewed, &["x", "V"]),
+    (Sidebar, A::Undo, &["u"]),
+    (Sidebar, A::Redo, &["ctrl+r"]),
+    (Sidebar, A::CopyPath, &["y"]),
+    (Sidebar, A::CopyCheckout, &["C"]),
+    (Sidebar, A::CommandOutput, &["!"]),
+    (Sidebar, A::ThreadsPanel, &["t"]),
+    (Diff, A::Quit, &["q"]),
+    (Diff, A::Back, &["esc"]),
+    (Diff, A::ToggleFocus, &["tab", "h"]),
+    (Diff, A::Down, &["j", "down"]),
+    (Diff, A::Up, &["k", "up"]),
+    (Diff, A::Top, &["g", "home"]),
+    (Diff, A::Bottom, &["G", "end"]),
+    (Diff, A::ScrollTenDown, &["ctrl+j"]),
+    (Diff, A::ScrollTenUp, &["ctrl+k"]),
+    (Diff, A::HalfPageUp, &["ctrl+u"]),
+    (Diff, A::HalfPageDown, &["d", "ctrl+d"]),
+    (Diff, A::PageUp, &["b", "pageup"]),
+    (Diff, A::PageDown, &["f", "pagedown"]),
+    (Diff, A::NextHunk, &["}"]),
+    (Diff, A::PrevHunk, &["{"]),
+    (Diff, A::NextFile, &[")"]),
+    (Diff, A::PrevFile, &["("]),
+    (Diff, A::NextChange, &["+"]),
+    (Diff, A::PrevChange, &["-"]),
+    (Diff, A::SidebarNextFile, &["]"]),
+    (Diff, A::SidebarPrevFile, &["["]),
+    (Diff, A::Search, &["/"]),
+    (Diff, A::Next, &["n"]),
+    (Diff, A::Prev, &["N"]),
+    (Diff, A::PrevThread, &["p"]),
+    (Diff, A::ExpandThread, &["enter"]),
+    (Diff, A::ToggleView, &["v"]),
+    (Diff, A::ToggleFileView, &["ctrl+v"]),
+    (Diff, A::ToggleWrap, &["w"]),
+    (Diff, A::ToggleWordDiff, &["W"]),
+    (Diff, A::ToggleBlame, &["B"]),
+    (Diff, A::ToggleViewed, &["x"]),
+    (Diff, A::Undo, &["u"]),
+    (Diff, A::Redo, &["ctrl+r"]),
+    (Diff, A::ToggleSidebar, &["s"]),
+    (Diff, A::OpenInEditor, &["o"]),
+    (Diff, A::Comment, &["a"]),
+    (Diff, A::CommentExternal, &["A"]),
+    (Diff, A::HunkComment, &["c"]),
+    (Diff, A::Visual, &["V"]),
+    (Diff, A::CopyLines, &["y"]),
+    (Diff, A::CopyCommentId, &["Y"]),
+    (Diff, A::CopyCheckout, &["C"]),
+    (Diff, A::CommandOutput, &["!"]),
+    (Diff, A::ThreadsPanel, &["t"]),
+    (Diff, A::ActionMenu, &["."]),
+    (Diff, A::Mute, &["m"]),
+    (Diff, A::ApplySuggestion, &["S"]),
+    (Diff, A::UndoSuggestion, &["U"]),
+    (Diff, A::SideOld, &["left"]),
+    (Diff, A::SideNew, &["right"]),
+    (Diff, A::FoldHunk, &["z a"]),
+    (Diff, A::FoldFile, &["z A"]),
+    (Diff, A::ExpandUp, &["E"]),
+    (Diff, A::ExpandDown, &["e"]),
+    (Visual, A::Down, &["j", "down"]),
+    (Visual, A::Up, &["k", "up"]),
+    (Visual, A::Top, &["g", "home"]),
+    (Visual, A::Bottom, &["G", "end"]),
+    (Visual, A::ScrollTenDown, &["ctrl+j"]),
+    (Visual, A::ScrollTenUp, &["ctrl+k"]),
+    (Visual, A::NextHunk, &["}"]),
+    (Visual, A::PrevHunk, &["{"]),
+    (Visual, A::NextFile, &[")"]),
+    (Visual, A::PrevFile, &["("]),
+    (Visual, A::NextChange, &["+"]),
+    (Visual, A::PrevChange, &["-"]),
+    (Visual, A::Comment, &["a"]),
+    (Visual, A::CommentExternal, &["A"]),
+    (Visual, A::QuoteComment, &[">"]),
+    (Visual, A::CopyLines, &["y"]),
+    (Visual, A::Visual, &["V", "esc"]),
+    (Visual, A::SideOld, &["left"]),
+    (Visual, A::SideNew, &["right"]),
+];
+
+/// Chords for an action in `keys`: one, or a list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyChords {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyChords {
+    fn as_slice(&self) -> &[String] {
+        match self {
+            Self::One(chord) => std::slice::from_ref(chord),
+            Self::Many(chords) => chords,
+        }
+    }
+}
+
+/// A key with Ctrl and Alt as pressed. Shift is part of the character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Chord {
+    code: KeyCode,
+    ctrl: bool,
+    alt: bool,
+}
+
+impl Chord {
+    /// Parse `j`, `G`, `ctrl+j`, `alt+f`, `enter`, `space`, `pagedown`, ….
+    fn parse(text: &str) -> Option<Self> {
+        let mut chord = Self {
+            code: KeyCode::Null,
+            ctrl: false,
+            alt: false,
+        };
+        let mut key = text;
+        // A lone `+` is the key itself
+        while let Some((modifier, rest)) = key.split_once('+').filter(|(_, rest)| !rest.is_empty())
+        {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "c" => chord.ctrl = true,
+                "alt" | "a" | "m" => chord.alt = true,
+                _ => return None,
+            }
+            key = rest;
+        }
+        let mut chars = key.chars();
+        chord.code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+            },
+        };
+        Some(chord)
+    }
+
+    /// Parse a chord, or a sequence of them apart by spaces (`z a`).
+    fn parse_keys(text: &str) -> Option<Vec<Self>> {
+        let keys: Option<Vec<Self>> = text.split_whitespace().map(Self::parse).collect();
+        keys.filter(|keys| !keys.is_empty())
+    }
+
+    fn matches(self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        self.code == code
+            && self.ctrl == modifiers.contains(KeyModifiers::CTRL)
+            && self.alt == modifiers.contains(KeyModifiers::ALT)
+    }
+}
+
+/// Key event for a chord in the `keys` syntax, as replay scripts write them.
+#[must_use]
+pub fn parse_key(text: &str) -> Option<KeyEvent> {
+    let chord = Chord::parse(text)?;
+    let mut modifiers = KeyModifiers::empty();
+    if chord.ctrl {
+        modifiers |= KeyModifiers::CTRL;
+    }
+    if chord.alt {
+        modifiers |= KeyModifiers::ALT;
+    }
+    Some(KeyEvent::new(chord.code, modifiers))
+}
+
+/// `key` in the `keys` syntax, or `None` for a key it has no name for.
+#[must_use]
+pub fn key_name(key: &KeyEvent) -> Option<String> {
+    let name = match key.code {
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::BackTab => "backtab".to_string(),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::Delete => "delete".to_string(),
+        KeyCode::Up => "up".to_string(),
+        KeyCode::Down => "down".to_string(),
+        KeyCode::Left => "left".to_string(),
+        KeyCode::Right => "right".to_string(),
+        KeyCode::Home => "home".to_string(),
+        KeyCode::End => "end".to_string(),
+        KeyCode::PageUp => "pageup".to_string(),
+        KeyCode::PageDown => "pagedown".to_string(),
+        KeyCode::F(n) => format!("f{n}"),
+        _ => return None,
+    };
+    let ctrl = if key.modifiers.contains(KeyModifiers::CTRL) {
+        "ctrl+"
+    } else {
+        ""
+    };
+    let alt = if key.modifiers.contains(KeyModifiers::ALT) {
+        "alt+"
+    } else {
+        ""
+    };
+    Some(format!("{ctrl}{alt}{name}"))
+}
+
+/// Bindings in effect.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// User bindings first, so they win over a default on the same chord.
+    /// Each is one chord or a sequence.
+    bindings: Vec<(KeyContext, Vec<Chord>, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).expect("built-in key bindings are valid")
+    }
+}
+
+impl Keymap {
+    /// The defaults with `keys` (from `ui.json`) applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the entry for an unknown action or context,
+    /// an action the context doesn't have, or a chord that doesn't parse.
+    pub fn new(keys: &BTreeMap<String, KeyChords>) -> anyhow::Result<Self> {
+        let mut bindings = Vec::new();
+        // (context, action) pairs the user rebound
+        let mut replaced = Vec::new();
+        for (name, chords) in keys {
+            let (context, action_name) = match name.split_once('.') {
+                Some((context, action)) => {
+                    let context = KeyContext::ALL
+                        .into_iter()
+                        .find(|c| c.name() == context)
+                        .with_context(|| format!("Unknown key context in {name:?}"))?;
+                    (Some(context), action)
+                }
+                None => (None, name.as_str()),
+            };
+            let action = Action::ALL
+                .iter()
+                .copied()
+                .find(|a| a.name() == action_name)
+                .with_context(|| format!("Unknown key action {name:?}"))?;
+            let contexts: Vec<KeyContext> = KeyContext::ALL
+                .into_iter()
+                .filter(|&c| context.is_none_or(|context| context == c))
+                .filter(|&c| DEFAULTS.iter().any(|&(dc, da, _)| dc == c && da == action))
+                .collect();
+            if contexts.is_empty() {
+                bail!("Key action {name:?} isn't available there");
+            }
+            for chord in chords.as_slice() {
+                let parsed = Chord::parse_keys(chord)
+                    .with_context(|| format!("Invalid key chord {chord:?} for {name:?}"))?;
+                bindings.extend(contexts.iter().map(|&c| (c, parsed.clone(), action)));
+            }
+            replaced.extend(contexts.into_iter().map(|c| (c, action)));
+        }
+        for &(context, action, chords) in DEFAULTS {
+            if replaced.contains(&(context, action)) {
+                continue;
+            }
+            for chord in chords {
+                let parsed = Chord::parse_keys(chord)
+                    .with_context(|| format!("Invalid default {chord:?}"))?;
+                bindings.push((context, parsed, action));
+            }
+        }
+        Ok(Self { bindings })
+    }
+
+    /// Action bound to `code` with `modifiers` in `context`. A chord with
+    /// Ctrl or Alt that isn't bound falls back to the bare key.
+    #[must_use]
+    pub fn action(
+        &self,
+        context: KeyContext,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Option<Action> {
+        let ctrl = modifiers.contains(KeyModifiers::CTRL);
+        let alt = modifiers.contains(KeyModifiers::ALT);
+        let find = |ctrl: bool, alt: bool| {
+            self.bindings
+                .iter()
+                .find(|(c, keys, _)| {
+                    *c == context
+                        && matches!(keys.as_slice(), [chord] if chord.code == code
+                            && chord.ctrl == ctrl
+                            && chord.alt == alt)
+                })
+                .map(|&(_, _, action)| action)
+        };
+        find(ctrl, alt).or_else(|| {
+            if ctrl || alt {
+                find(false, false)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Whether `code` with `modifiers` is the first key of a sequence in
+    /// `context`, so input waits for the next one.
+    #[must_use]
+    pub fn starts_sequence(
+        &self,
+        context: KeyContext,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> bool {
+        self.bindings
+            .iter()
+            .any(|(c, keys, _)| *c == context && keys.len() > 1 && keys[0].matches(code, modifiers))
+    }
+
+    /// Action bound to `first` followed by `code` with `modifiers` in
+    /// `context`.
+    #[must_use]
+    pub fn sequence_action(
+        &self,
+        context: KeyContext,
+        first: KeyEvent,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(c, keys, _)| {
+                *c == context
+                    && matches!(keys.as_slice(), [a, b] if a.matches(first.code, first.modifiers)
+                        && b.matches(code, modifiers))
+            })
+            .map(|&(_, _, action)| action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(entries: &[(&str, &[&str])]) -> BTreeMap<String, KeyChords> {
+        entries
+            .iter()
+            .map(|(name, chords)| {
+                let chords = chords.iter().map(ToString::to_string).collect();
+                ((*name).to_string(), KeyChords::Many(chords))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_user_bindings_replace_defaults_per_context() {
+        let keymap = Keymap::new(&keys(&[("down", &["n"]), ("diff.up", &["ctrl+p"])])).unwrap();
+        let none = KeyModifiers::empty();
+        let at = |context, code, modifiers| keymap.action(context, code, modifiers);
+        assert_eq!(at(Diff, KeyCode::Char('n'), none), Some(A::Down));
+        assert_eq!(at(List, KeyCode::Char('n'), none), Some(A::Down));
+        assert_eq!(at(Diff, KeyCode::Char('j'), none), None);
+        assert_eq!(
+            at(Diff, KeyCode::Char('p'), KeyModifiers::CTRL),
+            Some(A::Up)
+        );
+        assert_eq!(at(List, KeyCode::Char('k'), none), Some(A::Up));
+        assert_eq!(
+            at(Diff, KeyCode::Char('q'), KeyModifiers::CTRL),
+            Some(A::Quit),
+            "unbound modifiers fall back to the bare key"
+        );
+    }
+
+    #[test]
+    fn test_bad_entries_are_named() {
+        for (name, chord) in [
+            ("jump", "j"),
+            ("list.mute", "m"),
+            ("down", "hyper+j"),
+            ("tree.down", "j"),
+            ("fold-hunk", "z hyper+a"),
+        ] {
+            let error = Keymap::new(&keys(&[(name, &[chord])])).unwrap_err();
+            assert!(error.to_string().contains(name), "{error}");
+        }
+        assert_eq!(
+            Chord::parse("ctrl++"),
+            Some(Chord {
+                code: KeyCode::Char('+'),
+                ctrl: true,
+                alt: false
+            })
+        );
+    }
+
+    #[test]
+    fn test_sequences_wait_for_their_second_key() {
+        let keymap = Keymap::new(&keys(&[("fold-file", &["z f"])])).unwrap();
+        let none = KeyModifiers::empty();
+        let z = KeyEvent::new(KeyCode::Char('z'), none);
+        assert!(keymap.starts_sequence(Diff, KeyCode::Char('z'), none));
+        assert!(!keymap.starts_sequence(List, KeyCode::Char('z'), none));
+        assert_eq!(keymap.action(Diff, KeyCode::Char('z'), none), None);
+        assert_eq!(
+            keymap.sequence_action(Diff, z, KeyCode::Char('a'), none),
+            Some(A::FoldHunk)
+        );
+        assert_eq!(
+            keymap.sequence_action(Diff, z, KeyCode::Char('f'), none),
+            Some(A::FoldFile)
+        );
+        assert_eq!(
+            keymap.sequence_action(Diff, z, KeyCode::Char('A'), none),
+            None
+        );
+    }
+}
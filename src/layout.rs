@@ -3,11 +3,129 @@
 //! All magic numbers for block, diff, comment, and side-by-side layout live
 //! here so they stay in sync between the rendering layer (`view/diff`) and
 //! the stream-layout calculator (`stream.rs`).
+//!
+//! Vertical block spacing and comment block insets follow the [`Density`]
+//! setting instead, read through [`block_margin`] and friends.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
+
+use crate::diff::ParsedDiff;
+
+// --- Density ---
+
+/// How much room blocks get (`density` in `ui.json`). Like the glyph set,
+/// it's process-wide so the stream layout and the renderer agree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Density {
+    /// No blank rows around blocks, narrow comment insets
+    Compact = 0,
+    #[default]
+    Normal = 1,
+    /// Extra padding rows and wider comment insets
+    Relaxed = 2,
+}
+
+/// Spacing one density lays blocks out with.
+struct Spacing {
+    block_margin: usize,
+    block_padding: usize,
+    comment_h_margin: u32,
+    comment_h_pad: u32,
+}
+
+impl Density {
+    pub const ALL: [Self; 3] = [Self::Compact, Self::Normal, Self::Relaxed];
+
+    /// Parse a config name: `compact`, `normal` or `relaxed`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "compact" => Some(Self::Compact),
+            "normal" => Some(Self::Normal),
+            "relaxed" => Some(Self::Relaxed),
+            _ => None,
+        }
+    }
+
+    /// Name used in config files.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Compact => "compact",
+            Self::Normal => "normal",
+            Self::Relaxed => "relaxed",
+        }
+    }
+
+    #[must_use]
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Compact => Self::Normal,
+            Self::Normal => Self::Relaxed,
+            Self::Relaxed => Self::Compact,
+        }
+    }
+
+    const fn spacing(self) -> Spacing {
+        match self {
+            Self::Compact => Spacing {
+                block_margin: 0,
+                block_padding: 0,
+                comment_h_margin: 1,
+                comment_h_pad: 1,
+            },
+            Self::Normal => Spacing {
+                block_margin: 1,
+                block_padding: 1,
+                comment_h_margin: 4,
+                comment_h_pad: 1,
+            },
+            Self::Relaxed => Spacing {
+                block_margin: 1,
+                block_padding: 2,
+                comment_h_margin: 6,
+                comment_h_pad: 2,
+            },
+        }
+    }
+
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Compact,
+            2 => Self::Relaxed,
+            _ => Self::Normal,
+        }
+    }
+}
+
+static CURRENT_DENSITY: AtomicU8 = AtomicU8::new(Density::Normal as u8);
 
-// --- Block constants (file headers, pinned headers, comment blocks) ---
+/// Set the density the spacing functions below follow.
+pub fn set_density(density: Density) {
+    CURRENT_DENSITY.store(density as u8, Ordering::Relaxed);
+}
+
+/// The active density.
+#[must_use]
+pub fn density() -> Density {
+    Density::from_u8(CURRENT_DENSITY.load(Ordering::Relaxed))
+}
+
+// --- Block spacing (file headers, pinned headers, comment blocks) ---
+
+/// Blank rows above and below a block.
+#[must_use]
+pub fn block_margin() -> usize {
+    density().spacing().block_margin
+}
+
+/// Rows between a block's edge and its content.
+#[must_use]
+pub fn block_padding() -> usize {
+    density().spacing().block_padding
+}
 
-pub const BLOCK_MARGIN: usize = 1;
-pub const BLOCK_PADDING: usize = 1;
 pub const BLOCK_SIDE_MARGIN: u32 = 2;
 pub const BLOCK_LEFT_PAD: u32 = 2;
 pub const BLOCK_RIGHT_PAD: u32 = 2;
@@ -15,6 +133,9 @@ pub const BLOCK_RIGHT_PAD: u32 = 2;
 /// Minimum terminal width before we switch from SBS to unified.
 pub const SIDE_BY_SIDE_MIN_WIDTH: u32 = 100;
 
+/// Minimum terminal width for the review list's preview pane.
+pub const PREVIEW_MIN_WIDTH: u32 = 100;
+
 // --- Diff constants ---
 
 pub const DIFF_H_PAD: u32 = 2;
@@ -25,23 +146,111 @@ pub const ORPHANED_CONTEXT_LEFT_PAD: u32 = 2;
 
 pub const THREAD_COL_WIDTH: u32 = 0;
 pub const UNIFIED_LINE_NUM_WIDTH: u32 = 12;
+/// Blame column (hash and author) left of the unified line numbers
+pub const BLAME_GUTTER_WIDTH: u32 = 22;
 pub const SBS_LINE_NUM_WIDTH: u32 = 6;
 pub const CONTEXT_LINE_NUM_WIDTH: u32 = 6;
 
 // --- Comment layout ---
 
-pub const COMMENT_H_MARGIN: u32 = 4;
-pub const COMMENT_H_PAD: u32 = 1;
+/// Columns between the diff pane's edges and a comment block.
+#[must_use]
+pub fn comment_h_margin() -> u32 {
+    density().spacing().comment_h_margin
+}
+
+/// Columns between a comment block's bars and its text.
+#[must_use]
+pub fn comment_h_pad() -> u32 {
+    density().spacing().comment_h_pad
+}
+
+// --- Comment block width ---
+
+static COMMENT_MAX_WIDTH: AtomicU32 = AtomicU32::new(0);
+static COMMENT_CENTERED: AtomicBool = AtomicBool::new(false);
+
+/// Cap the text of comment and description blocks at `width` columns.
+///
+/// That's `comment_max_width` in `ui.json`, 0 for no cap; `centered`
+/// (`comment_align: "center"`) centers the narrowed blocks in the pane.
+pub fn set_comment_max_width(width: u32, centered: bool) {
+    COMMENT_MAX_WIDTH.store(width, Ordering::Relaxed);
+    COMMENT_CENTERED.store(centered, Ordering::Relaxed);
+}
+
+/// Columns the text of comment and description blocks is capped at.
+#[must_use]
+pub fn comment_max_width() -> Option<u32> {
+    Some(COMMENT_MAX_WIDTH.load(Ordering::Relaxed)).filter(|&width| width > 0)
+}
+
+/// Left inset and width of a comment or description block.
+///
+/// That's the density's margins in a pane `pane_width` wide, narrowed to
+/// fit [`comment_max_width`] of text.
+#[must_use]
+pub fn comment_block_span(pane_width: u32) -> (u32, u32) {
+    let margin = comment_h_margin();
+    let width = pane_width.saturating_sub(margin * 2);
+    let Some(max_width) = comment_max_width() else {
+        return (margin, width);
+    };
+    // The double bar and padding on each side, as in `comment_content_area`
+    let capped = width.min(max_width + 4 + comment_h_pad() * 2);
+    let offset = if COMMENT_CENTERED.load(Ordering::Relaxed) {
+        (width - capped) / 2
+    } else {
+        0
+    };
+    (margin + offset, capped)
+}
 
 // --- Context lines around threads ---
 
 pub const CONTEXT_LINES: i64 = 5;
 
+/// Unchanged lines one context expansion (`e`, `E`) adds to a hunk
+pub const EXPAND_CONTEXT_LINES: u32 = 20;
+
+// --- Mouse wheel ---
+
+/// Rows one wheel step scrolls the diff with `wheel_scroll: viewport`
+pub const WHEEL_SCROLL_ROWS: usize = 3;
+
+// --- Flash messages ---
+
+/// Columns of copied text echoed in the "Copied …" flash.
+pub const FLASH_PREVIEW_WIDTH: usize = 60;
+
+// --- Diff banner ---
+
+/// Warnings listed individually before the banner collapses the rest.
+pub const MAX_PARSE_WARNING_ROWS: usize = 3;
+
+/// Rows taken by the banner shown above a file's hunks: a binary file's
+/// notice, then parse warnings.
+#[must_use]
+pub fn diff_banner_rows(diff: &ParsedDiff) -> usize {
+    let warnings = if diff.warnings.len() > MAX_PARSE_WARNING_ROWS {
+        MAX_PARSE_WARNING_ROWS + 1
+    } else {
+        diff.warnings.len()
+    };
+    usize::from(diff.binary.is_some()) + warnings
+}
+
 // --- Block height ---
 
 #[must_use]
-pub const fn block_height(content_lines: usize) -> usize {
-    content_lines + (BLOCK_MARGIN * 2) + (BLOCK_PADDING * 2)
+pub fn block_height(content_lines: usize) -> usize {
+    content_lines + (block_margin() * 2) + (block_padding() * 2)
+}
+
+/// Header block of the list screens (review list, inbox, comparison).
+#[must_use]
+pub fn screen_header_height() -> u32 {
+    block_height(1) as u32
 }
 
 /// Number of stream rows visible in the diff pane.
@@ -49,13 +258,20 @@ pub const fn block_height(content_lines: usize) -> usize {
 /// Accounts for the help bar footer (2 lines + 1 margin = 3) and the pinned
 /// header block at the top of the stream area.
 #[must_use]
-pub const fn visible_stream_rows(terminal_height: u16) -> usize {
-    let total = terminal_height as u32;
+pub fn visible_stream_rows(terminal_height: u16) -> usize {
+    let total = u32::from(terminal_height);
     let footer: u32 = 3; // help bar (2 lines) + margin (1 line)
     let pinned: u32 = block_height(1) as u32;
     total.saturating_sub(footer + pinned) as usize
 }
 
+/// Output rows in the `review_command` overlay: the screen less a 2-row
+/// margin top and bottom, and the overlay's title and blank rows.
+#[must_use]
+pub const fn command_output_rows(terminal_height: u16) -> usize {
+    (terminal_height as usize).saturating_sub(4 + 4)
+}
+
 // --- Stream-layout inner-width helpers ---
 
 /// Inner width for diff content (no block bar/margins, just horizontal padding).
@@ -70,3 +286,22 @@ pub const fn diff_inner_width(pane_width: u32) -> u32 {
 pub const fn block_inner_width(pane_width: u32) -> u32 {
     pane_width.saturating_sub(BLOCK_SIDE_MARGIN * 2 + 1 + BLOCK_LEFT_PAD + BLOCK_RIGHT_PAD)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_density_names_and_spacing_order() {
+        for density in Density::ALL {
+            assert_eq!(Density::from_name(density.name()), Some(density));
+            assert_eq!(Density::from_u8(density as u8), density);
+        }
+        assert_eq!(Density::from_name(" Relaxed "), Some(Density::Relaxed));
+        assert_eq!(Density::from_name("cozy"), None);
+        let rows = |d: Density| d.spacing().block_margin + d.spacing().block_padding;
+        assert!(rows(Density::Compact) < rows(Density::Normal));
+        assert!(rows(Density::Normal) < rows(Density::Relaxed));
+        assert_eq!(Density::Relaxed.next(), Density::Compact);
+    }
+}
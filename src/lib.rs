@@ -5,26 +5,60 @@
 #![allow(clippy::cast_possible_truncation)]
 #![allow(clippy::cast_sign_loss)]
 
+pub mod action_log;
+pub mod annotations;
+pub mod archive_client;
+pub mod cast;
+pub mod cli;
 pub mod cli_client;
+pub mod clipboard;
+pub mod clock;
 pub mod command;
+pub mod compare;
+pub mod completions;
 pub mod config;
+pub mod control;
 pub mod db;
+pub mod demo_client;
 pub mod diff;
+pub mod export;
+pub mod glyphs;
+pub mod highlight;
+pub mod i18n;
+pub mod inbox;
 pub mod input;
+pub mod keymap;
 pub mod layout;
+pub mod loader;
 pub mod message;
 pub mod model;
+pub mod owners;
+pub mod preview;
+pub mod profile;
+pub mod redraw;
+pub mod refresh;
 pub mod render_backend;
+pub mod replay;
+pub mod repo;
+pub mod review_command;
+pub mod review_query;
+pub mod search;
+pub mod state;
+pub mod status;
 pub mod stream;
+pub mod suggestion;
 pub mod syntax;
 pub mod text;
 pub mod theme;
 pub mod update;
 pub mod vcs;
 pub mod view;
+pub mod watch;
 
+pub use archive_client::ArchiveClient;
 pub use cli_client::CliClient;
 pub use db::CritClient;
+pub use demo_client::{DemoClient, DemoSize};
 pub use message::Message;
 pub use model::{Focus, LayoutMode, Model, Screen};
 pub use syntax::{HighlightSpan, Highlighter};
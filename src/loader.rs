@@ -0,0 +1,74 @@
+//! Loading a review off the main thread.
+//!
+//! `crit review --include-diffs` can take seconds on a big review. With a
+//! client that offers a [`CritClient::worker`], a [`ReviewLoad`] runs it on a
+//! background thread; the main loop checks [`ReviewLoad::poll`] each
+//! iteration and hands the result to `update` as `Message::ReviewLoaded`.
+//! The files are then parsed a few per iteration (see
+//! `Model::pending_files`), drawing a "Loading…" placeholder until they are,
+//! and highlighted on a `HighlightWorker`.
+
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+use anyhow::{anyhow, Result};
+
+use crate::db::{CritClient, ReviewData};
+
+pub struct ReviewLoad {
+    pub review_id: String,
+    result: Receiver<Result<Option<ReviewData>>>,
+}
+
+impl ReviewLoad {
+    /// Start loading `review_id` through `client` on a worker thread.
+    #[must_use]
+    pub fn start(client: Box<dyn CritClient + Send>, review_id: &str) -> Self {
+        let (sender, result) = mpsc::channel();
+        let id = review_id.to_string();
+        // Not joined: if the load is dropped the thread finishes its crit
+        // call and the send fails
+        std::thread::spawn(move || {
+            let _ = sender.send(client.load_review_data(&id));
+        });
+        Self {
+            review_id: review_id.to_string(),
+            result,
+        }
+    }
+
+    /// The load's result, once it has finished.
+    #[must_use]
+    pub fn poll(&self) -> Option<Result<Option<ReviewData>>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow!("Review load stopped"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive_client::ArchiveClient;
+    use crate::demo_client::{DemoClient, DemoSize, DEFAULT_DEMO_SEED};
+    use crate::export::review_json;
+
+    #[test]
+    fn test_load_finishes_on_a_worker_thread() {
+        let demo = DemoClient::new(DemoSize::Small, DEFAULT_DEMO_SEED);
+        let review_id = demo.list_reviews(None).unwrap()[0].review_id.clone();
+        let data = demo.load_review_data(&review_id).unwrap().unwrap();
+        let archive = ArchiveClient::from_json(review_json(&data).to_string().as_bytes()).unwrap();
+
+        let load = ReviewLoad::start(Box::new(archive), &review_id);
+        let loaded = loop {
+            if let Some(result) = load.poll() {
+                break result.unwrap().unwrap();
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        };
+        assert_eq!(loaded.detail.review_id, review_id);
+        assert_eq!(loaded.threads.len(), data.threads.len());
+    }
+}
@@ -1,8 +1,8 @@
 //! botcrit-ui - GitHub-style code review TUI
 //!
-//! Usage: crit-ui [path-to-crit-db]
+//! Usage: crit-ui [command] [options]; see `crit-ui --help` and `cli.rs`.
 //!
-//! If no path is provided, looks for .crit/index.db in current directory.
+//! If no path is provided, looks for .crit/ in the current directory.
 
 #![allow(clippy::too_many_lines)]
 #![allow(clippy::unnecessary_wraps)]
@@ -10,53 +10,131 @@
 #![allow(clippy::needless_pass_by_value)]
 #![allow(clippy::literal_string_with_formatting_args)]
 
+use std::collections::HashMap;
 use std::io::Write;
+use std::num::NonZeroU32;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 
-use botcrit_ui::config::{load_ui_config, save_ui_config};
+use botcrit_ui::annotations::Annotations;
+use botcrit_ui::cast::CastRecorder;
+use botcrit_ui::cli::{self, CommandKind};
+use botcrit_ui::clock;
+use botcrit_ui::completions::{self, DynamicKind};
+use botcrit_ui::config::{load_ui_config, save_ui_config, StartupScreen};
+use botcrit_ui::control::{ControlServer, ControlState};
+use botcrit_ui::db::{
+    files_etag, thread_etag, Comment, CreatedComment, ExpectedThread, ReviewData, ThreadConflict,
+    ThreadSide, ThreadSummary,
+};
+use botcrit_ui::demo_client::DEFAULT_DEMO_SEED;
+use botcrit_ui::export;
+use botcrit_ui::glyphs::{self, GlyphSet};
+use botcrit_ui::highlight::{self, HighlightWorker};
+use botcrit_ui::i18n::{self, tr, tr_fmt, Locale, Text};
 use botcrit_ui::input::map_event_to_message;
-use botcrit_ui::model::{CommentRequest, DiffViewMode, EditorRequest};
+use botcrit_ui::keymap::Keymap;
+use botcrit_ui::layout::{self, visible_stream_rows, Density};
+use botcrit_ui::loader::ReviewLoad;
+use botcrit_ui::model::{
+    CommentRequest, DiffViewMode, EditorRequest, FileScan, ReviewFilter, ReviewStatusChange,
+};
+use botcrit_ui::owners::Codeowners;
+use botcrit_ui::preview::ReviewPreview;
+use botcrit_ui::profile::Phase;
+use botcrit_ui::redraw::{LinkMonitor, RedrawMode};
 use botcrit_ui::render_backend::{
-    enable_raw_mode, Event, RawModeGuard, Renderer, RendererOptions,
+    enable_raw_mode, Event, RawModeGuard, Renderer, RendererOptions, ResizeEvent,
 };
-use botcrit_ui::render_backend::{event_from_ftui, rgba_to_packed, OptimizedBuffer};
+use botcrit_ui::render_backend::{event_from_ftui, rgba_to_packed, Damage, OptimizedBuffer};
 use botcrit_ui::render_backend::{
     Cell as OtCell, CellContent as OtCellContent, TextAttributes as OtTextAttributes,
 };
-use botcrit_ui::stream::{
-    compute_stream_layout, file_scroll_offset, StreamLayoutParams, SIDE_BY_SIDE_MIN_WIDTH,
-};
+use botcrit_ui::replay::{self, Player, Recorder};
+use botcrit_ui::repo;
+use botcrit_ui::review_command::CommandRun;
+use botcrit_ui::review_query::ReviewQuery;
+use botcrit_ui::state::UiState;
+use botcrit_ui::stream::{file_scroll_offset, SIDE_BY_SIDE_MIN_WIDTH};
 use botcrit_ui::theme::{load_built_in_theme, load_theme_from_path};
+use botcrit_ui::watch::{FileWatcher, EXTERNAL_STATE, WATCH_INTERVAL};
 use botcrit_ui::{
-    update, view, CliClient, CritClient, Focus, Highlighter, LayoutMode, Message, Model, Screen,
-    Theme,
+    update, view, ArchiveClient, CliClient, CritClient, DemoClient, DemoSize, Focus, Highlighter,
+    LayoutMode, Message, Model, Screen, Theme,
 };
+use ftui_core::terminal_session::{SessionOptions as FtuiSessionOptions, TerminalSession};
 use ftui_render::buffer::Buffer as FtuiBuffer;
 use ftui_render::cell::{
     Cell as FtuiCell, CellAttrs as FtuiCellAttrs, CellContent as FtuiCellContent,
     StyleFlags as FtuiStyleFlags,
 };
-use ftui_core::terminal_session::{SessionOptions as FtuiSessionOptions, TerminalSession};
 use ftui_render::diff::BufferDiff as FtuiBufferDiff;
 use ftui_render::presenter::{Presenter as FtuiPresenter, TerminalCapabilities};
 
-fn main() -> Result<()> {
-    let args = parse_args()?;
-
-    // Build client: --path or auto-detect .crit/ → CliClient, else demo
-    let client: Option<Box<dyn CritClient>> = args
-        .repo_path
-        .as_ref()
-        .map(|repo| -> Box<dyn CritClient> { Box::new(CliClient::new(repo)) });
+/// Queued input events handled before the next frame is drawn.
+const EVENTS_PER_FRAME: usize = 64;
 
-    let repo_path = args.repo_path.clone();
+fn main() -> Result<()> {
+    let argv: Vec<String> = std::env::args().collect();
+    if argv.get(1).map(String::as_str) == Some(completions::COMPLETE_HELPER) {
+        print_dynamic_completions(&argv[2..]);
+        return Ok(());
+    }
+    let matches = cli::parse(&argv[1..])?;
+    if matches.help {
+        if matches.command.kind == CommandKind::View {
+            print!("{}", cli::help());
+        } else {
+            print!("{}", matches.command.help());
+        }
+        return Ok(());
+    }
+    match matches.command.kind {
+        CommandKind::View | CommandKind::OpenArchive => {}
+        CommandKind::Export => return run_export(&matches),
+        CommandKind::ExportPrefs => return run_export_prefs(&matches),
+        CommandKind::ImportPrefs => return run_import_prefs(&matches),
+        CommandKind::Diff => return run_diff(&matches),
+        CommandKind::Completions => {
+            print!("{}", completions::script(cli::completion_shell(&matches)?));
+            return Ok(());
+        }
+    }
+    let mut args = view_args(&matches)?;
+    // Stops the clock, so it comes before anything reads the time
+    let mut replay = args.replay.as_deref().map(Player::load).transpose()?;
+    let (repo_path, client) = build_client(&args.source)?;
+    // An archive holds one review; open it directly
+    if args.source.archive.is_some() && args.review.is_none() {
+        args.review = client
+            .list_reviews(None)?
+            .first()
+            .map(|r| r.review_id.clone());
+    }
 
     // Load theme (optional)
-    let mut config = load_ui_config()?.unwrap_or_default();
+    let mut config = load_ui_config(repo_path.as_deref())?;
+    let keymap = Keymap::new(&config.keys).context("Invalid keys in config")?;
+    i18n::set_locale(Locale::detect(config.locale.as_deref()));
+    glyphs::set_glyph_set(GlyphSet::detect(config.glyphs.as_deref()));
+    layout::set_density(
+        config
+            .density
+            .as_deref()
+            .and_then(Density::from_name)
+            .unwrap_or_default(),
+    );
+    layout::set_comment_max_width(
+        config.comment_max_width.map_or(0, u32::from),
+        config
+            .comment_align
+            .as_deref()
+            .is_some_and(|align| align.trim().eq_ignore_ascii_case("center")),
+    );
     let theme_override = args
         .theme
         .clone()
@@ -103,29 +181,42 @@ fn main() -> Result<()> {
     // Create model
     let mut model = Model::new(width, height, config);
     model.theme = theme;
+    model.keymap = keymap;
     if let Some(theme_name) = syntax_theme {
-        model.highlighter = Highlighter::with_theme(&theme_name);
+        model.highlighter = Arc::new(Highlighter::with_theme(&theme_name));
     } else if model.theme.name.to_lowercase().contains("light") {
-        model.highlighter = Highlighter::with_theme("base16-ocean.light");
+        model.highlighter = Arc::new(Highlighter::with_theme("base16-ocean.light"));
     }
+    model.highlight_worker = Some(HighlightWorker::start());
 
     apply_default_diff_view(&mut model);
 
     // Store repo path for display in header
     model.repo_path = repo_path.as_ref().map(|p| p.display().to_string());
-
-    // Store pending CLI navigation targets
-    model.pending_review = args.review;
+    model.ui_state = UiState::load(repo_path.as_deref());
+
+    // Store pending CLI navigation targets; without --review, the startup
+    // screen may pick one
+    let startup = model.config.startup_screen();
+    model.pending_review = args.review.clone().or_else(|| {
+        (startup == StartupScreen::LastReview).then(|| model.config.last_review.clone())?
+    });
     model.pending_file = args.file;
     model.pending_thread = args.thread;
+    model.pending_line = args.line;
+    model.read_only |= args.read_only;
+    model.syntax &= !args.no_syntax;
 
     // Load initial data
-    if let Some(c) = &client {
-        model.reviews = c.list_reviews(None).unwrap_or_default();
-    } else {
-        // Demo data for testing without a database
-        load_demo_data(&mut model);
-    }
+    model.reviews = client.list_reviews(None).unwrap_or_default();
+    model.current_user = client.current_user();
+    model.codeowners = Codeowners::load(repo_path.as_deref(), model.config.codeowners.as_deref())?;
+    model.annotations = args
+        .annotations
+        .as_deref()
+        .map(Annotations::load)
+        .transpose()?;
+    model.queue_file_scan();
 
     // Apply --review: jump directly to a review if specified
     if let Some(review_id) = model.pending_review.take() {
@@ -143,17 +234,25 @@ fn main() -> Result<()> {
             model.current_file_content = None;
             model.highlighted_lines.clear();
             model.file_cache.clear();
+            model.blame.clear();
             model.threads.clear();
             model.all_comments.clear();
+            model.thread_events.clear();
+            model.pending_thread_events.clear();
         } else {
             // Review not found — clear pending file/thread and stay on review list
             model.pending_file = None;
             model.pending_thread = None;
+            model.pending_line = None;
         }
     } else {
-        // No --review, ignore --file and --thread
+        // No --review, ignore --file, --line and --thread
         model.pending_file = None;
         model.pending_thread = None;
+        model.pending_line = None;
+    }
+    if args.review.is_none() {
+        apply_startup_screen(&mut model, &startup);
     }
 
     // Raw mode guard is managed by backend/session integrations.
@@ -175,14 +274,16 @@ fn main() -> Result<()> {
     let mut ftui_presenter = FtuiPresenter::new(std::io::stdout(), TerminalCapabilities::detect());
     let mut ftui_prev = FtuiBuffer::new(width, height);
     let mut ftui_next = FtuiBuffer::new(width, height);
-    let mut terminal_session = Some(TerminalSession::new(FtuiSessionOptions {
-        alternate_screen: true,
-        mouse_capture: true,
-        bracketed_paste: true,
-        focus_events: true,
-        ..Default::default()
-    })
-    .context("Failed to initialize ftui terminal session")?);
+    let mut terminal_session = Some(
+        TerminalSession::new(FtuiSessionOptions {
+            alternate_screen: true,
+            mouse_capture: true,
+            bracketed_paste: true,
+            focus_events: true,
+            ..Default::default()
+        })
+        .context("Failed to initialize ftui terminal session")?,
+    );
     terminal_session
         .as_ref()
         .expect("ftui session initialized")
@@ -203,48 +304,188 @@ fn main() -> Result<()> {
         }
     }
 
+    // Only a real working directory has files worth watching
+    let mut watcher = repo_path
+        .as_deref()
+        .map(|root| FileWatcher::new(root, WATCH_INTERVAL));
+    // crit's database and the VCS state, for changes made by other clients
+    let mut state_watcher = repo_path
+        .as_deref()
+        .filter(|_| model.config.watch != Some(false))
+        .map(|root| FileWatcher::new(root, WATCH_INTERVAL));
+
+    let redraw_mode = model
+        .config
+        .redraw
+        .as_deref()
+        .and_then(RedrawMode::from_name)
+        .unwrap_or_default();
+    let mut link_monitor = LinkMonitor::new(redraw_mode);
+    model.lean_cursor = link_monitor.lean();
+    let mut recorder = args
+        .record
+        .as_deref()
+        .map(|path| Recorder::new(path, model.width, model.height));
+    let mut cast = args
+        .cast
+        .as_deref()
+        .map(|path| CastRecorder::create(path, model.width, model.height))
+        .transpose()?;
+    let mut control = args
+        .control_socket
+        .as_deref()
+        .map(ControlServer::bind)
+        .transpose()?;
+
     // Main loop
     loop {
-        // Force a full redraw to avoid render artifacts
-        renderer.invalidate();
-        model.needs_redraw = false;
-
-        // Render
-        renderer.clear();
-        view(&model, renderer.buffer());
-        bridge_buffer_to_ftui(renderer.buffer(), &mut ftui_next);
-        let diff = FtuiBufferDiff::compute(&ftui_prev, &ftui_next);
-        ftui_presenter
-            .present(&ftui_next, &diff)
-            .context("Failed to present ftui frame")?;
-        ftui_presenter
-            .hide_cursor()
-            .context("Failed to keep cursor hidden")?;
-        std::mem::swap(&mut ftui_prev, &mut ftui_next);
+        // In the background, only draw frames something asked for
+        if model.terminal_focused || model.needs_redraw {
+            model.needs_redraw = false;
+
+            // Render; only the cells that changed go out, and an unchanged
+            // frame writes nothing
+            let render_started = Instant::now();
+            renderer.clear();
+            view(&model, renderer.buffer());
+            let damage = renderer.present();
+            if !damage.is_empty() {
+                ftui_next.clear_dirty();
+                bridge_damage_to_ftui(renderer.buffer(), &damage, &mut ftui_next);
+                let diff = if damage.full {
+                    FtuiBufferDiff::full(ftui_next.width(), ftui_next.height())
+                } else {
+                    FtuiBufferDiff::compute_dirty(&ftui_prev, &ftui_next)
+                };
+                let stats = ftui_presenter
+                    .present(&ftui_next, &diff)
+                    .context("Failed to present ftui frame")?;
+                ftui_presenter
+                    .hide_cursor()
+                    .context("Failed to keep cursor hidden")?;
+                if let Some(cast) = cast.as_mut() {
+                    cast.frame(&ftui_next, &diff)?;
+                }
+                // Both buffers hold the presented frame again
+                bridge_damage_to_ftui(renderer.buffer(), &damage, &mut ftui_prev);
+                if link_monitor.record(stats.duration) {
+                    model.lean_cursor = link_monitor.lean();
+                    model.needs_redraw = true;
+                }
+            }
+            model
+                .input_profile
+                .phase(Phase::Render, render_started.elapsed());
+        }
+
+        if let Some(text) = model.pending_clipboard.take() {
+            let mut out = std::io::stdout();
+            let _ = out.write_all(botcrit_ui::clipboard::osc52(&text).as_bytes());
+            let _ = out.flush();
+        }
 
         if model.should_quit {
+            // A comment still in its undo window goes out now
+            submit_pending_comment(&mut model, client.as_ref(), repo_path.as_deref());
             break;
         }
 
-        if let Some(c) = &client {
-            handle_data_loading(&mut model, c.as_ref(), repo_path.as_deref());
-        } else {
-            handle_demo_data_loading(&mut model);
+        let effects_started = Instant::now();
+        submit_pending_comment(&mut model, client.as_ref(), repo_path.as_deref());
+        write_status_change(&mut model, client.as_ref());
+        submit_verdict(&mut model, client.as_ref(), repo_path.as_deref());
+        write_review_status(&mut model, client.as_ref(), repo_path.as_deref());
+        show_blame(&mut model, repo_path.as_deref());
+        load_blame(&mut model, repo_path.as_deref());
+        apply_patch(&mut model, repo_path.as_deref());
+        run_review_command(&mut model, repo_path.as_deref());
+        expand_context(&mut model, repo_path.as_deref());
+        export_review(&mut model, client.as_ref(), repo_path.as_deref());
+        handle_data_loading(&mut model, client.as_ref(), repo_path.as_deref());
+        receive_highlights(&mut model);
+        let polled = model.pending_refresh;
+        run_auto_refresh(&mut model, client.as_ref());
+        if let Some(watcher) = watcher.as_mut().filter(|_| model.terminal_focused) {
+            refresh_changed_files(&mut model, watcher);
         }
-
-        // Poll for input (with timeout for potential refresh)
-        if terminal_session
-            .as_ref()
-            .expect("ftui session available")
-            .poll_event(Duration::from_millis(100))
-            .context("Failed polling ftui terminal events")?
-            && let Some(ft_event) = terminal_session
+        if let Some(watcher) = state_watcher.as_mut().filter(|_| model.terminal_focused) {
+            watch_external_state(&mut model, watcher, polled);
+        }
+        let effects_done = Instant::now();
+        model
+            .input_profile
+            .phase(Phase::Effects, effects_done - effects_started);
+        model.input_profile.frame_done(effects_done);
+        // Timers (auto-refresh) run off the tick; a due poll shows its
+        // spinner in the next frame before it blocks on crit
+        update(&mut model, Message::Tick);
+
+        // Poll for input (with timeout for potential refresh); wake less
+        // often while the terminal is in the background
+        let poll_timeout = if replay.is_some() && replay::settled(&model) {
+            // Straight on to the next step
+            Duration::ZERO
+        } else if model.review_load.is_some()
+            || !model.pending_files.is_empty()
+            || model
+                .highlight_worker
                 .as_ref()
-                .expect("ftui session available")
-                .read_event()
-                .context("Failed reading ftui terminal event")?
-            && let Some(event) = event_from_ftui(ft_event)
+                .is_some_and(|worker| !worker.is_idle())
+        {
+            // Keep loading between keys
+            Duration::from_millis(10)
+        } else if model.terminal_focused || control.as_ref().is_some_and(ControlServer::has_clients)
         {
+            Duration::from_millis(100)
+        } else {
+            Duration::from_secs(1)
+        };
+        // Then take everything already queued before drawing, so a held key
+        // sends one frame per batch instead of one per step
+        let mut timeout = poll_timeout;
+        for _ in 0..EVENTS_PER_FRAME {
+            let session = terminal_session.as_ref().expect("ftui session available");
+            let event = if let Some(player) = replay.as_mut() {
+                // The terminal's own input waits for the script to end
+                discard_input(session, timeout)?;
+                timeout = Duration::ZERO;
+                match player.next(replay::settled(&model)) {
+                    Some(event) => event,
+                    None if player.is_done() => {
+                        replay = None;
+                        // Back to the terminal's size for whoever takes over
+                        let Some((width, height)) = session
+                            .size()
+                            .ok()
+                            .filter(|&size| size != (model.width, model.height))
+                        else {
+                            break;
+                        };
+                        Event::Resize(ResizeEvent::new(width, height))
+                    }
+                    None => break,
+                }
+            } else {
+                if !session
+                    .poll_event(timeout)
+                    .context("Failed polling ftui terminal events")?
+                {
+                    break;
+                }
+                timeout = Duration::ZERO;
+                let Some(event) = session
+                    .read_event()
+                    .context("Failed reading ftui terminal event")?
+                    .and_then(event_from_ftui)
+                else {
+                    continue;
+                };
+                event
+            };
+            let received = Instant::now();
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.record(&event);
+            }
             let resized_to = if let Event::Resize(resize) = &event {
                 Some((resize.width, resize.height))
             } else {
@@ -258,31 +499,98 @@ fn main() -> Result<()> {
                     raw_guard: &mut raw_guard,
                     wrap_guard: &mut wrap_guard,
                     cursor_guard: &mut cursor_guard,
-                    client: &client,
                     repo_path: repo_path.as_deref(),
                     options,
                     terminal_session: &mut terminal_session,
+                    received,
                 },
             )?;
             if let Some((width, height)) = resized_to {
                 ftui_prev = FtuiBuffer::new(width, height);
                 ftui_next = FtuiBuffer::new(width, height);
+                if let Some(cast) = cast.as_mut() {
+                    cast.resize(width, height)?;
+                }
+            }
+            model.needs_redraw = true;
+            // A replay draws every step, before the clock moves on
+            if model.should_quit || replay.is_some() {
+                break;
+            }
+        }
+
+        if let Some(server) = control.as_mut() {
+            let mut failed = None;
+            server.serve(|msg| {
+                if failed.is_none() {
+                    let ctx = &mut EventContext {
+                        renderer: &mut renderer,
+                        raw_guard: &mut raw_guard,
+                        wrap_guard: &mut wrap_guard,
+                        cursor_guard: &mut cursor_guard,
+                        repo_path: repo_path.as_deref(),
+                        options,
+                        terminal_session: &mut terminal_session,
+                        received: Instant::now(),
+                    };
+                    failed = process_message(msg, &mut model, ctx).err();
+                    model.needs_redraw = true;
+                }
+                ControlState::of(&model)
+            });
+            if let Some(err) = failed {
+                return Err(err);
             }
         }
     }
 
+    if let Some(recorder) = &recorder {
+        recorder.save()?;
+    }
+    let profile =
+        Some(&model.input_profile).filter(|profile| args.profile_input && !profile.is_empty());
+    if profile.is_some() || args.replay.is_some() {
+        // Leave the alternate screen first so what's printed stays visible
+        drop(terminal_session);
+        drop(cursor_guard);
+        drop(wrap_guard);
+        drop(raw_guard);
+    }
+    if let Some(profile) = profile {
+        eprint!("{}", profile.summary());
+    }
+    if args.replay.is_some() {
+        // Where the replay left the UI, for regression tests to compare;
+        // stdout is the terminal's
+        eprintln!("{}", serde_json::to_string(&ControlState::of(&model))?);
+    }
+
     Ok(())
 }
 
-fn bridge_buffer_to_ftui(src: &OptimizedBuffer, dst: &mut FtuiBuffer) {
-    let width = src.width().min(u32::from(dst.width())) as u16;
-    let height = src.height().min(u32::from(dst.height())) as u16;
-    dst.clear();
+/// Read and drop terminal input, waiting up to `timeout` for the first.
+fn discard_input(session: &TerminalSession, timeout: Duration) -> Result<()> {
+    let mut timeout = timeout;
+    while session
+        .poll_event(timeout)
+        .context("Failed polling ftui terminal events")?
+    {
+        session
+            .read_event()
+            .context("Failed reading ftui terminal event")?;
+        timeout = Duration::ZERO;
+    }
+    Ok(())
+}
 
-    for y in 0..height {
-        for x in 0..width {
-            if let Some(cell) = src.get(u32::from(x), u32::from(y)) {
-                dst.set_raw(x, y, convert_backend_cell(cell));
+/// Copy the damaged cells of `src` into `dst`.
+fn bridge_damage_to_ftui(src: &OptimizedBuffer, damage: &Damage, dst: &mut FtuiBuffer) {
+    let width = src.width().min(u32::from(dst.width()));
+    let height = src.height().min(u32::from(dst.height()));
+    for &(y, start, end) in damage.spans.iter().filter(|span| span.0 < height) {
+        for x in start..end.min(width) {
+            if let Some(cell) = src.get(x, y) {
+                dst.set_raw(x as u16, y as u16, convert_backend_cell(cell));
             }
         }
     }
@@ -341,15 +649,28 @@ struct EventContext<'a> {
     raw_guard: &'a mut Option<RawModeGuard>,
     wrap_guard: &'a mut Option<AutoWrapGuard>,
     cursor_guard: &'a mut Option<CursorGuard>,
-    client: &'a Option<Box<dyn CritClient>>,
     repo_path: Option<&'a Path>,
     options: RendererOptions,
     terminal_session: &'a mut Option<TerminalSession>,
+    /// When the event being processed was read
+    received: Instant,
 }
 
 fn process_event(event: &Event, model: &mut Model, ctx: &mut EventContext<'_>) -> Result<()> {
-    let _ = ctx.client; // reserved for future use
     let msg = map_event_to_message(model, event);
+    let timed = model.input_profile.start(&msg, ctx.received);
+    let started = Instant::now();
+    let result = process_message(msg, model, ctx);
+    if timed {
+        model.input_profile.phase(Phase::Update, started.elapsed());
+    }
+    result
+}
+
+/// Apply a message from the terminal or the control socket, then run what
+/// it asked for that needs the terminal (resizing, suspending for
+/// `$EDITOR`).
+fn process_message(msg: Message, model: &mut Model, ctx: &mut EventContext<'_>) -> Result<()> {
     let resize = if let Message::Resize { width, height } = &msg {
         Some((*width, *height))
     } else {
@@ -421,16 +742,10 @@ fn process_event(event: &Event, model: &mut Model, ctx: &mut EventContext<'_>) -
 
         let comment_result = run_comment_editor(ctx.repo_path, &request);
 
-        // Persist the comment if editor returned content
-        if let Ok(Some(body)) = &comment_result {
-            if let Some(client) = ctx.client.as_ref() {
-                let persist_result =
-                    persist_comment(client.as_ref(), ctx.repo_path, &request, body);
-                if persist_result.is_ok() {
-                    // Refresh review data to show the new comment
-                    reload_review_data(model, client.as_ref(), ctx.repo_path);
-                }
-            }
+        // Queue the comment if editor returned content; it's persisted after
+        // the next frame shows the optimistic copy
+        if let Ok(Some(body)) = comment_result {
+            model.queue_comment_submission(request, body);
         }
 
         *ctx.raw_guard = Some(enable_raw_mode().context("Failed to enable raw mode")?);
@@ -460,25 +775,6 @@ fn process_event(event: &Event, model: &mut Model, ctx: &mut EventContext<'_>) -
         ctx.renderer.invalidate();
     }
 
-    // Handle inline editor submission (no TUI teardown needed)
-    if let Some(submission) = model.pending_comment_submission.take() {
-        if let Some(client) = ctx.client.as_ref() {
-            let persist_result = persist_comment(
-                client.as_ref(),
-                ctx.repo_path,
-                &submission.request,
-                &submission.body,
-            );
-            match persist_result {
-                Ok(()) => reload_review_data(model, client.as_ref(), ctx.repo_path),
-                Err(e) => {
-                    model.flash_message = Some(format!("Comment failed: {e}"));
-                }
-            }
-        }
-        model.needs_redraw = true;
-    }
-
     Ok(())
 }
 
@@ -520,104 +816,276 @@ impl Drop for CursorGuard {
     }
 }
 
+/// Flags that pick the data source, shared by every command that loads reviews.
+struct SourceArgs {
+    repo_path: Option<PathBuf>,
+    /// `open-archive <bundle>`
+    archive: Option<PathBuf>,
+    demo: bool,
+    demo_size: DemoSize,
+    demo_seed: u64,
+}
+
 struct CliArgs {
+    source: SourceArgs,
     theme: Option<String>,
-    repo_path: Option<PathBuf>,
     review: Option<String>,
     file: Option<String>,
+    line: Option<u32>,
     thread: Option<String>,
+    read_only: bool,
+    /// `--no-syntax`: skip syntax highlighting
+    no_syntax: bool,
+    annotations: Option<PathBuf>,
+    profile_input: bool,
+    control_socket: Option<PathBuf>,
+    /// `--record`: script to write on exit
+    record: Option<PathBuf>,
+    /// `--replay`: script to play on the virtual clock
+    replay: Option<PathBuf>,
+    /// `--cast`: asciicast file to record frames to
+    cast: Option<PathBuf>,
 }
 
-fn parse_args() -> Result<CliArgs> {
-    let args: Vec<String> = std::env::args().collect();
-    let mut theme: Option<String> = None;
-    let mut repo_path: Option<PathBuf> = None;
-    let mut review: Option<String> = None;
-    let mut file: Option<String> = None;
-    let mut thread: Option<String> = None;
-
-    let mut i = 1;
-    while i < args.len() {
-        match args[i].as_str() {
-            "--help" | "-h" => {
-                println!("Usage: crit-ui [options]");
-                println!();
-                println!("Options:");
-                println!("  --theme <name|path>   Load theme by name or JSON path");
-                println!("  --path <path>    Path to repo root (uses crit CLI)");
-                println!("  --review <id>    Open directly to a review (skip review list)");
-                println!("  --file <path>    Navigate to a specific file (requires --review)");
-                println!("  --thread <id>    Expand a specific thread (requires --review)");
-                println!();
-                println!("Environment:");
-                println!("  BOTCRIT_UI_THEME  Theme name or JSON path");
-                println!();
-                println!("If no path is provided, auto-detects .crit/ in the current directory.");
-                println!("If that doesn't exist, runs in demo mode with sample data.");
-                std::process::exit(0);
-            }
-            "--theme" => {
-                i += 1;
-                if i >= args.len() {
-                    anyhow::bail!("--theme requires a path");
-                }
-                theme = Some(args[i].clone());
-            }
-            "--path" => {
-                i += 1;
-                if i >= args.len() {
-                    anyhow::bail!("--path requires a path");
-                }
-                repo_path = Some(PathBuf::from(&args[i]));
-            }
-            "--review" => {
-                i += 1;
-                if i >= args.len() {
-                    anyhow::bail!("--review requires a review ID");
-                }
-                review = Some(args[i].clone());
-            }
-            "--file" => {
-                i += 1;
-                if i >= args.len() {
-                    anyhow::bail!("--file requires a file path");
-                }
-                file = Some(args[i].clone());
-            }
-            "--thread" => {
-                i += 1;
-                if i >= args.len() {
-                    anyhow::bail!("--thread requires a thread ID");
-                }
-                thread = Some(args[i].clone());
-            }
-            arg if arg.starts_with('-') => {
-                anyhow::bail!("Unknown option: {arg}");
-            }
-            arg => {
-                anyhow::bail!("Unexpected argument: {arg}");
-            }
+/// An archive bundle → `ArchiveClient`, `--path` or auto-detected `.crit/`
+/// → `CliClient`, else demo data.
+fn build_client(args: &SourceArgs) -> Result<(Option<PathBuf>, Box<dyn CritClient>)> {
+    if let Some(bundle) = &args.archive {
+        return Ok((None, Box::new(ArchiveClient::open(bundle)?)));
+    }
+    let repo_path = if args.demo {
+        None
+    } else {
+        args.repo_path.clone()
+    };
+    let client: Box<dyn CritClient> = match &repo_path {
+        Some(repo) => Box::new(CliClient::new(repo)),
+        None => Box::new(DemoClient::new(args.demo_size, args.demo_seed)),
+    };
+    Ok((repo_path, client))
+}
+
+/// `crit-ui __complete <kind> [words...]`: list IDs for shell completion,
+/// one per line as `value<TAB>description`. `words` are the command and flags
+/// typed so far, so `--path`/`--demo` pick the same backend and `--review`
+/// scopes file and thread lookups. Errors print nothing.
+fn print_dynamic_completions(argv: &[String]) {
+    let Some(kind) = argv.first().and_then(|k| DynamicKind::parse(k)) else {
+        return;
+    };
+    let words: Vec<String> = argv[1..]
+        .iter()
+        .filter(|w| !matches!(w.as_str(), "-h" | "--help"))
+        .cloned()
+        .collect();
+    let Ok(matches) = cli::parse(&words) else {
+        return;
+    };
+    let Ok(source) = source_args(&matches) else {
+        return;
+    };
+    let Ok((_, client)) = build_client(&source) else {
+        return;
+    };
+    // The shell may stop reading early; ignore write errors
+    let mut out = std::io::stdout().lock();
+
+    if kind == DynamicKind::Reviews {
+        for review in client.list_reviews(None).unwrap_or_default() {
+            let _ = writeln!(out, "{}\t{}", review.review_id, review.title);
+        }
+        return;
+    }
+    let Some(review_id) = matches.value("review") else {
+        return;
+    };
+    let Ok(Some(data)) = client.load_review_data(review_id) else {
+        return;
+    };
+    if kind == DynamicKind::Files {
+        for file in data.files.iter().filter(|f| !f.path.starts_with(".crit/")) {
+            let _ = writeln!(out, "{}\t", file.path);
+        }
+    } else {
+        for thread in &data.threads {
+            let _ = writeln!(
+                out,
+                "{}\t{}:{}",
+                thread.thread_id, thread.file_path, thread.selection_start
+            );
         }
-        i += 1;
     }
+}
+
+/// Load `--review` for `export`/`diff`, or fail with a message naming it.
+fn load_required_review(matches: &cli::Matches) -> Result<botcrit_ui::db::ReviewData> {
+    let review_id = matches.required("review")?;
+    let (_, client) = build_client(&source_args(matches)?)?;
+    client
+        .load_review_data(review_id)?
+        .with_context(|| format!("Review not found: {review_id}"))
+}
+
+/// `crit-ui export`: the review's threads and comments as JSON, or the
+/// whole review as Markdown with `--markdown`.
+fn run_export(matches: &cli::Matches) -> Result<()> {
+    let data = load_required_review(matches)?;
+    let text = if matches.is_set("markdown") {
+        export::review_markdown(&data)
+    } else {
+        serde_json::to_string_pretty(&export::review_json(&data))? + "\n"
+    };
+    match matches.value("output") {
+        Some(path) => std::fs::write(path, text).with_context(|| format!("Failed to write {path}")),
+        None => write_stdout(&text),
+    }
+}
+
+/// `crit-ui export-prefs`: the user's preferences as one JSON object.
+fn run_export_prefs(matches: &cli::Matches) -> Result<()> {
+    let prefs = botcrit_ui::config::export_preferences()?;
+    let json = serde_json::to_string_pretty(&prefs)? + "\n";
+    match matches.value("output") {
+        Some(path) => std::fs::write(path, json).with_context(|| format!("Failed to write {path}")),
+        None => write_stdout(&json),
+    }
+}
+
+/// `crit-ui import-prefs <file>`: merge exported preferences into the
+/// user's config.
+fn run_import_prefs(matches: &cli::Matches) -> Result<()> {
+    let file = matches
+        .positional
+        .as_deref()
+        .context("import-prefs requires a file (from export-prefs)")?;
+    let json = std::fs::read_to_string(file).with_context(|| format!("Failed to read {file}"))?;
+    let (path, count) = botcrit_ui::config::import_preferences(&json)
+        .with_context(|| format!("Failed to import {file}"))?;
+    println!("Imported {count} settings into {}", path.display());
+    Ok(())
+}
 
-    // Auto-detect: .crit/ exists in cwd → use CliClient with cwd
-    if repo_path.is_none() {
-        let crit_dir = PathBuf::from(".crit");
-        if crit_dir.is_dir() {
-            repo_path = Some(PathBuf::from("."));
+/// `crit-ui diff`: the review's unified diff, optionally for one file.
+fn run_diff(matches: &cli::Matches) -> Result<()> {
+    let data = load_required_review(matches)?;
+    let files: Vec<_> = data
+        .files
+        .iter()
+        .filter(|f| !f.path.starts_with(".crit/"))
+        .collect();
+    let selected: Vec<_> = match matches.value("file") {
+        Some(path) => {
+            let Some(file) = files.iter().find(|f| f.path == path) else {
+                anyhow::bail!(
+                    "File not in review: {path}{}",
+                    cli::did_you_mean(path, files.iter().map(|f| f.path.as_str()), "")
+                );
+            };
+            vec![*file]
+        }
+        None => files,
+    };
+    let mut out = String::new();
+    for diff in selected.iter().filter_map(|f| f.diff.as_deref()) {
+        out.push_str(diff);
+        if !diff.ends_with('\n') {
+            out.push('\n');
         }
     }
+    write_stdout(&out)
+}
 
-    Ok(CliArgs {
-        theme,
+/// Print command output; a closed pipe (e.g. `| head`) isn't an error.
+fn write_stdout(text: &str) -> Result<()> {
+    match std::io::stdout().lock().write_all(text.as_bytes()) {
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+fn source_args(matches: &cli::Matches) -> Result<SourceArgs> {
+    let repo_path = match matches.value("path") {
+        Some(path) => Some(PathBuf::from(path)),
+        // Auto-detect: CRIT_DIR, then .crit/ in the cwd or above it
+        None => repo::discover(
+            &std::env::current_dir()?,
+            std::env::var_os(repo::CRIT_DIR_ENV).as_deref(),
+        )?,
+    };
+    let demo_size = match matches.value("demo-size") {
+        Some(size) => {
+            DemoSize::parse(size).with_context(|| format!("Unknown demo size: {size}"))?
+        }
+        None => DemoSize::default(),
+    };
+    let archive = if matches.command.kind == CommandKind::OpenArchive {
+        let bundle = matches
+            .positional
+            .as_deref()
+            .context("open-archive requires a bundle path")?;
+        Some(PathBuf::from(bundle))
+    } else {
+        None
+    };
+    Ok(SourceArgs {
         repo_path,
-        review,
+        archive,
+        demo: matches.is_set("demo"),
+        demo_size,
+        demo_seed: matches.parsed("demo-seed")?.unwrap_or(DEFAULT_DEMO_SEED),
+    })
+}
+
+fn view_args(matches: &cli::Matches) -> Result<CliArgs> {
+    let line = matches.parsed::<NonZeroU32>("line")?.map(NonZeroU32::get);
+    let file = matches.value("file").map(str::to_string);
+    if line.is_some() && file.is_none() {
+        anyhow::bail!("--line requires --file");
+    }
+    if matches.value("record").is_some() && matches.value("replay").is_some() {
+        anyhow::bail!("--record and --replay can't be used together");
+    }
+    Ok(CliArgs {
+        source: source_args(matches)?,
+        theme: matches.value("theme").map(str::to_string),
+        review: matches.value("review").map(str::to_string),
         file,
-        thread,
+        line,
+        thread: matches.value("thread").map(str::to_string),
+        read_only: matches.is_set("read-only") || matches.command.kind == CommandKind::OpenArchive,
+        no_syntax: matches.is_set("no-syntax"),
+        annotations: matches.value("annotations").map(PathBuf::from),
+        profile_input: matches.is_set("profile-input"),
+        control_socket: matches.value("control-socket").map(PathBuf::from),
+        record: matches.value("record").map(PathBuf::from),
+        replay: matches.value("replay").map(PathBuf::from),
+        cast: matches.value("cast").map(PathBuf::from),
     })
 }
 
+/// Open the configured startup screen (`startup` in `ui.json`). A last
+/// review that no longer exists or an unknown filter leaves the list as is.
+fn apply_startup_screen(model: &mut Model, startup: &StartupScreen) {
+    match startup {
+        StartupScreen::Inbox => update(model, Message::ShowInbox),
+        StartupScreen::Filter(name) => {
+            let Some(saved) = model.config.saved_filters.get(name).cloned() else {
+                return;
+            };
+            if let Some(filter) = saved.status.as_deref().and_then(ReviewFilter::from_name) {
+                model.filter = filter;
+            }
+            model.search_input = saved.search.unwrap_or_default();
+            if let Some(filter) = saved.filter {
+                model.review_query = ReviewQuery::parse(&filter);
+                model.filter_bar_input = filter;
+                model.queue_file_scan();
+            }
+        }
+        StartupScreen::ReviewList | StartupScreen::LastReview => {}
+    }
+}
+
 fn apply_default_diff_view(model: &mut Model) {
     if let Some(value) = model.config.default_diff_view.as_deref() {
         if let Some(mode) = parse_diff_view_mode(value) {
@@ -644,9 +1112,7 @@ fn should_default_side_by_side(model: &Model) -> bool {
     let diff_pane_width = match model.layout_mode {
         LayoutMode::Full | LayoutMode::Compact => {
             if model.sidebar_visible {
-                model
-                    .width
-                    .saturating_sub(model.layout_mode.sidebar_width())
+                model.width.saturating_sub(model.sidebar_width())
             } else {
                 model.width
             }
@@ -705,6 +1171,23 @@ fn run_comment_editor(
         if let Some(thread_id) = &request.thread_id {
             writeln!(f, "# Thread: {thread_id}")?;
         }
+        if let Some(notice) = &request.notice {
+            writeln!(f, "# Note: {notice}")?;
+        }
+        if !request.excerpt.is_empty() {
+            writeln!(f, "#")?;
+            for (line_num, line) in (request.start_line..).zip(&request.excerpt) {
+                writeln!(f, "# {line_num:>5} | {line}")?;
+            }
+            let hidden = request.excerpt_hidden_lines();
+            if hidden > 0 {
+                writeln!(
+                    f,
+                    "#         {}",
+                    tr_fmt(Text::ExcerptMoreLines, &[&hidden])
+                )?;
+            }
+        }
         if !request.existing_comments.is_empty() {
             writeln!(f, "#")?;
             writeln!(f, "# Existing comments:")?;
@@ -770,65 +1253,449 @@ fn persist_comment(
     _repo_path: Option<&Path>,
     request: &CommentRequest,
     body: &str,
-) -> Result<()> {
+) -> Result<Option<CreatedComment>> {
     if let Some(thread_id) = &request.thread_id {
-        client.reply(thread_id, body)?;
+        let expected = request.thread_etag.as_deref().map(|etag| ExpectedThread {
+            review_id: &request.review_id,
+            etag,
+        });
+        client.reply(thread_id, body, expected)
     } else {
         client.comment(
             &request.review_id,
             &request.file_path,
             request.start_line,
             request.end_line,
+            request.side,
             body,
-        )?;
+        )
     }
-    Ok(())
 }
 
-/// Build file cache entries from data returned by crit (no VCS calls needed).
-fn populate_file_cache(model: &mut Model, files: Vec<botcrit_ui::db::FileData>) {
-    use botcrit_ui::diff::ParsedDiff;
+/// Persist a queued comment once its undo window is over, or right away
+/// when quitting or leaving its review. Its optimistic copy is already on
+/// screen, so swap in the real ids on success, or roll it back and flash
+/// the error.
+fn submit_pending_comment(model: &mut Model, client: &dyn CritClient, repo_path: Option<&Path>) {
+    let due = model.pending_comment_submission.as_ref().is_some_and(|s| {
+        model.should_quit
+            || clock::now() >= s.send_at
+            || model
+                .current_review
+                .as_ref()
+                .is_none_or(|review| review.review_id != s.request.review_id)
+    });
+    let Some(submission) = model.pending_comment_submission.take_if(|_| due) else {
+        return;
+    };
+    match persist_comment(client, repo_path, &submission.request, &submission.body) {
+        Ok(Some(created)) => {
+            model.discard_draft(&submission.request);
+            model.log_posted_comment(&submission.request);
+            model.reconcile_comment(created);
+        }
+        // Backend didn't report ids; fetch the authoritative state instead
+        Ok(None) => {
+            model.discard_draft(&submission.request);
+            model.log_posted_comment(&submission.request);
+            reload_review_data(model, client, repo_path);
+        }
+        Err(e) => {
+            model.rollback_comment(&submission);
+            match e.downcast::<ThreadConflict>() {
+                Ok(conflict) => model.reopen_conflicted_reply(conflict, submission),
+                Err(e) => model.flash_message = Some(tr_fmt(Text::CommentFailed, &[&e])),
+            }
+        }
+    }
+    model.needs_redraw = true;
+}
+
+/// Persist a queued resolve or reopen. The new status is already on screen;
+/// roll it back and flash the error if crit refuses, showing the thread as
+/// it is now if another client changed it.
+fn write_status_change(model: &mut Model, client: &dyn CritClient) {
+    let Some(change) = model.pending_status_change.take() else {
+        return;
+    };
+    let expected = change
+        .expected
+        .as_ref()
+        .map(|(review_id, etag)| ExpectedThread { review_id, etag });
+    let result = if change.status == "open" {
+        client.reopen_thread(&change.thread_id, expected)
+    } else {
+        client.resolve_thread(&change.thread_id, expected)
+    };
+    match result {
+        // The history gained an entry; fetch it again
+        Ok(()) => {
+            model.log_status_change(&change);
+            model.thread_events.remove(&change.thread_id);
+            model.pending_thread_events.push(change.thread_id);
+        }
+        Err(e) => match e.downcast::<ThreadConflict>() {
+            Ok(conflict) => model.refuse_status_change(&change, conflict),
+            Err(e) => {
+                model.rollback_status_change(&change);
+                model.flash_message = Some(tr_fmt(Text::ThreadStatusFailed, &[&e]));
+            }
+        },
+    }
+    model.needs_redraw = true;
+}
+
+/// Persist a queued verdict and its summary, then reload so the summary
+/// block shows at the top of the review.
+fn submit_verdict(model: &mut Model, client: &dyn CritClient, repo_path: Option<&Path>) {
+    let Some(submission) = model.pending_verdict.take() else {
+        return;
+    };
+    let result = client.submit_verdict(
+        &submission.review_id,
+        submission.verdict,
+        submission.summary.as_deref(),
+    );
+    match result {
+        Ok(()) => {
+            model.discard_verdict_draft(&submission.review_id);
+            reload_review_data(model, client, repo_path);
+            model.flash_message = Some(tr(Text::VerdictSubmitted).to_string());
+        }
+        Err(e) => model.flash_message = Some(tr_fmt(Text::VerdictFailed, &[&e])),
+    }
+    model.needs_redraw = true;
+}
+
+/// Persist a queued abandon or restore of the review, then reload so the
+/// banner at the top of the review follows.
+fn write_review_status(model: &mut Model, client: &dyn CritClient, repo_path: Option<&Path>) {
+    let Some(change) = model.pending_review_status.take() else {
+        return;
+    };
+    let (review_id, result, status, done) = match &change {
+        ReviewStatusChange::Abandon { review_id, reason } => (
+            review_id,
+            client.abandon_review(review_id, reason.as_deref()),
+            "abandoned",
+            Text::ReviewAbandoned,
+        ),
+        ReviewStatusChange::Restore { review_id } => (
+            review_id,
+            client.restore_review(review_id),
+            "open",
+            Text::ReviewRestored,
+        ),
+    };
+    match result {
+        Ok(()) => {
+            // The list shows the new status without waiting for a refresh
+            if let Some(summary) = model.reviews.iter_mut().find(|r| &r.review_id == review_id) {
+                summary.status = status.to_string();
+            }
+            reload_review_data(model, client, repo_path);
+            model.flash_message = Some(tr(done).to_string());
+        }
+        Err(e) => model.flash_message = Some(tr_fmt(Text::ReviewStatusFailed, &[&e])),
+    }
+    model.needs_redraw = true;
+}
+
+/// Apply a queued suggestion to the working copy, or take the last one back
+/// out.
+fn apply_patch(model: &mut Model, repo_path: Option<&Path>) {
+    let Some(request) = model.pending_patch.take() else {
+        return;
+    };
+    let Some(root) = repo_path else {
+        return;
+    };
+    let path = &request.patch.file_path;
+    let result = botcrit_ui::vcs::apply_patch(root, &request.patch.patch, request.undo);
+    model.flash_message = Some(match (result, request.undo) {
+        (Ok(()), false) => {
+            model.applied_suggestion = Some(request.patch.clone());
+            tr_fmt(Text::SuggestionApplied, &[path])
+        }
+        (Ok(()), true) => {
+            model.applied_suggestion = None;
+            tr_fmt(Text::SuggestionUndone, &[path])
+        }
+        (Err(e), false) => tr_fmt(Text::SuggestionApplyFailed, &[&e]),
+        (Err(e), true) => tr_fmt(Text::SuggestionUndoFailed, &[&e]),
+    });
+    model.needs_redraw = true;
+}
+
+/// Answer a queued blame lookup with a flash line.
+fn show_blame(model: &mut Model, repo_path: Option<&Path>) {
+    let Some(request) = model.pending_blame_request.take() else {
+        return;
+    };
+    let blame = repo_path.and_then(|root| {
+        botcrit_ui::vcs::blame_line(root, &request.file_path, request.line, &request.commit)
+    });
+    model.flash_message = Some(match blame {
+        Some(b) => tr_fmt(
+            Text::BlameLine,
+            &[&b.commit, &b.author, &b.date, &b.summary],
+        ),
+        None => tr_fmt(Text::BlameUnavailable, &[&request.line]),
+    });
+    model.needs_redraw = true;
+}
+
+/// Blame the current file for the blame column, once per file.
+fn load_blame(model: &mut Model, repo_path: Option<&Path>) {
+    if !model.blame_gutter || model.active_view_mode() != DiffViewMode::Unified {
+        return;
+    }
+    let Some(review) = model.current_review.as_ref() else {
+        return;
+    };
+    let files = model.files_with_threads();
+    let Some(path) = files.get(model.file_index).map(|f| f.path.clone()) else {
+        return;
+    };
+    if model.blame.contains_key(&path) {
+        return;
+    }
+    let commit = review.commit();
+    let blame = repo_path
+        .and_then(|root| botcrit_ui::vcs::blame_file(root, &path, commit))
+        .unwrap_or_default();
+    model.blame.insert(path, blame);
+    model.needs_redraw = true;
+}
 
-    model.file_cache.clear();
+/// Read the lines a context expansion asked for, from the VCS at the
+/// review's commit or, without a repository, the file content crit sent.
+fn expand_context(model: &mut Model, repo_path: Option<&Path>) {
+    let Some(expansion) = model.pending_expansion.take() else {
+        return;
+    };
+    let Some(review) = model.current_review.as_ref() else {
+        return;
+    };
+    let commit = review.commit();
+    let (first, last) = (*expansion.lines.start(), *expansion.lines.end());
+    let lines = match repo_path {
+        Some(root) => {
+            let window = first as usize..=last as usize;
+            botcrit_ui::vcs::get_file_content(root, &expansion.path, commit, Some(window))
+                .unwrap_or_default()
+        }
+        None => model
+            .file_cache
+            .get(&expansion.path)
+            .and_then(|entry| entry.file_content.as_ref())
+            .map(|content| {
+                (i64::from(first)..=i64::from(last))
+                    .map_while(|line| {
+                        let index = usize::try_from(line - content.start_line).ok()?;
+                        content.lines.get(index).cloned()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    model.apply_context_expansion(&expansion, &lines);
+}
 
-    for file_data in files.into_iter().filter(|f| !f.path.starts_with(".crit/")) {
-        let diff = file_data.diff.as_deref().map(ParsedDiff::parse);
+/// Start a requested `review_command` run and take in output from the
+/// current one.
+fn run_review_command(model: &mut Model, repo_path: Option<&Path>) {
+    if std::mem::take(&mut model.pending_command_run) {
+        let review = model.current_review.as_ref();
+        let command = model.config.review_command.as_deref();
+        if let (Some(root), Some(review), Some(command)) = (repo_path, review, command) {
+            let commit = review.commit();
+            // Replacing a finished run drops it; a running one was kept
+            model.command_run = Some(CommandRun::start(root, &review.review_id, commit, command));
+        }
+    }
+    if model.command_run.as_mut().is_some_and(CommandRun::poll) {
+        model.needs_redraw = true;
+    }
+}
 
-        let file_content = file_data.content.map(|c| botcrit_ui::model::FileContent {
-            lines: c.lines,
-            start_line: c.start_line,
+/// Write a requested Markdown export next to the repo (or in the current
+/// directory) and flash where it went.
+fn export_review(model: &mut Model, client: &dyn CritClient, repo_path: Option<&Path>) {
+    if !std::mem::take(&mut model.pending_export) {
+        return;
+    }
+    let Some(review_id) = model.current_review.as_ref().map(|r| r.review_id.clone()) else {
+        return;
+    };
+    let path = repo_path
+        .unwrap_or_else(|| Path::new("."))
+        .join(format!("{review_id}.md"));
+    let written = client
+        .load_review_data(&review_id)
+        .and_then(|data| data.with_context(|| format!("Review not found: {review_id}")))
+        .and_then(|data| {
+            std::fs::write(&path, export::review_markdown(&data))
+                .with_context(|| format!("Failed to write {}", path.display()))
         });
+    model.flash_message = Some(match written {
+        Ok(()) => tr_fmt(Text::ReviewExported, &[&path.display()]),
+        Err(e) => tr_fmt(Text::ReviewExportFailed, &[&e]),
+    });
+    model.needs_redraw = true;
+}
 
-        let highlighted_lines = if let Some(parsed) = &diff {
-            compute_diff_highlights(parsed, &file_data.path, &model.highlighter)
-        } else if let Some(content) = &file_content {
-            compute_file_highlights(&content.lines, &file_data.path, &model.highlighter)
-        } else {
-            Vec::new()
-        };
+/// Build a file cache entry from data returned by crit (no VCS calls
+/// needed), not yet highlighted.
+fn file_cache_entry(file_data: botcrit_ui::db::FileData) -> botcrit_ui::model::FileCacheEntry {
+    use botcrit_ui::diff::{normalize_json_diff, ParsedDiff};
 
-        let file_highlighted_lines = if diff.is_some() {
-            if let Some(content) = &file_content {
-                compute_file_highlights(&content.lines, &file_data.path, &model.highlighter)
-            } else {
-                Vec::new()
-            }
-        } else {
-            Vec::new()
-        };
+    let parsed = file_data.diff.as_deref().map(ParsedDiff::parse);
+    // Notebooks and long-lined JSON are shown pretty-printed
+    let normalized = parsed
+        .as_ref()
+        .and_then(|diff| normalize_json_diff(&file_data.path, diff));
+    let is_normalized = normalized.is_some();
+    let diff = normalized.or(parsed);
+
+    let file_content = file_data
+        .content
+        .map(|c| botcrit_ui::model::FileContent::new(c.lines, c.start_line));
 
-        model.file_cache.insert(
-            file_data.path,
-            botcrit_ui::model::FileCacheEntry {
-                diff,
-                file_content,
-                highlighted_lines,
-                file_highlighted_lines,
-            },
-        );
+    let word_lines = diff
+        .as_ref()
+        .filter(|parsed| !parsed.is_combined() && botcrit_ui::diff::is_prose_path(&file_data.path))
+        .map(|parsed| botcrit_ui::diff::word_diff(&parsed.hunks));
+
+    botcrit_ui::model::FileCacheEntry {
+        diff,
+        file_content,
+        highlighted_lines: Vec::new(),
+        file_highlighted_lines: Vec::new(),
+        word_lines,
+        normalized: is_normalized,
+        revision: botcrit_ui::model::FileCacheEntry::next_revision(),
     }
+}
 
+/// Parse and highlight queued files into the file cache, stopping after a
+/// short time budget so a large review doesn't stall input. Files still
+/// queued draw a loading placeholder.
+fn load_pending_files(model: &mut Model, repo_path: Option<&Path>) {
+    const BUDGET: Duration = Duration::from_millis(30);
+    if model.pending_files.is_empty() {
+        return;
+    }
+    let started = clock::now();
+    while let Some(file_data) = model.pending_files.pop() {
+        let path = file_data.path.clone();
+        let mut entry = file_cache_entry(file_data);
+        if let Some(root) = repo_path {
+            load_orphaned_context(model, root, &path, &mut entry);
+        }
+        // A reload that didn't change the file keeps its colors
+        let highlighted = model
+            .file_cache
+            .get(&path)
+            .is_some_and(|old| highlight::carry_over(old, &mut entry));
+        model.file_cache.insert(path.clone(), entry);
+        if !highlighted {
+            model.highlight_file(&path);
+        }
+        if clock::elapsed(started) >= BUDGET {
+            break;
+        }
+    }
     model.sync_active_file_cache();
+    model.needs_redraw = true;
+}
+
+/// Give a file crit sent without content just the lines its orphaned
+/// threads show as context, read from the VCS at the review's commit.
+fn load_orphaned_context(
+    model: &Model,
+    repo_path: &Path,
+    path: &str,
+    entry: &mut botcrit_ui::model::FileCacheEntry,
+) {
+    let Some(review) = model.current_review.as_ref() else {
+        return;
+    };
+    let Some(lines) = model.orphaned_context_window(path, entry) else {
+        return;
+    };
+    let commit = review.commit();
+    let start_line = i64::try_from(*lines.start()).unwrap_or(1);
+    if let Some(content) = botcrit_ui::vcs::get_file_content(repo_path, path, commit, Some(lines))
+        .filter(|content| !content.is_empty())
+    {
+        entry.file_content = Some(botcrit_ui::model::FileContent::new(content, start_line));
+    }
+}
+
+/// Hand finished highlights to `update`, first dropping queued work for
+/// entries that have changed since.
+fn receive_highlights(model: &mut Model) {
+    let Some(worker) = &model.highlight_worker else {
+        return;
+    };
+    if worker.is_idle() {
+        return;
+    }
+    worker.retain(|path, revision| {
+        model
+            .file_cache
+            .get(path)
+            .is_some_and(|entry| entry.revision == revision)
+    });
+    for highlights in worker.poll() {
+        update(model, Message::HighlightsReady(Box::new(highlights)));
+    }
+}
+
+/// Reload file content that changed on disk, so edits made in another
+/// terminal show up in full-file views and orphaned-thread context.
+fn refresh_changed_files(model: &mut Model, watcher: &mut FileWatcher) {
+    if model.screen != Screen::ReviewDetail {
+        return;
+    }
+    let watched: Vec<String> = model
+        .file_cache
+        .iter()
+        .filter(|(_, entry)| entry.file_content.is_some())
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    let mut refreshed = false;
+    for path in watcher.poll(watched.iter().map(String::as_str)) {
+        let Ok(lines) = watcher.read_lines(&path) else {
+            continue;
+        };
+        let Some(entry) = model.file_cache.get_mut(&path) else {
+            continue;
+        };
+        let Some(old) = &entry.file_content else {
+            continue;
+        };
+        let start_line = old.start_line.max(1);
+        let skip = usize::try_from(start_line - 1).unwrap_or(0);
+        // A full-file view takes the whole file; context windows keep their size
+        let take = if entry.diff.is_some() {
+            old.lines.len()
+        } else {
+            usize::MAX
+        };
+        let window = lines.into_iter().skip(skip).take(take).collect();
+        entry.file_content = Some(botcrit_ui::model::FileContent::new(window, start_line));
+        entry.revision = botcrit_ui::model::FileCacheEntry::next_revision();
+        model.highlight_file(&path);
+        refreshed = true;
+    }
+
+    if refreshed {
+        model.sync_active_file_cache();
+        model.needs_redraw = true;
+    }
 }
 
 /// Reload review data after a comment is persisted.
@@ -838,42 +1705,253 @@ fn reload_review_data(model: &mut Model, client: &dyn CritClient, _repo_path: Op
     };
     let review_id = review.review_id.clone();
     if let Ok(Some(data)) = client.load_review_data(&review_id) {
-        model.current_review = Some(data.detail);
-        model.threads = data.threads;
-        model.all_comments = data.comments;
-        populate_file_cache(model, data.files);
+        model.install_review_data(data);
+    }
+}
+
+/// Poll crit's database and the VCS state; a change queues a reload. What
+/// our own poll just did to them (`polled`) is recorded, not reported.
+fn watch_external_state(model: &mut Model, watcher: &mut FileWatcher, polled: bool) {
+    let paths = EXTERNAL_STATE.iter().copied();
+    if polled {
+        watcher.settle(paths);
+    } else if !watcher.poll(paths).is_empty() {
+        update(model, Message::ExternalRefresh);
+    }
+}
+
+/// Re-fetch the data on screen for a due auto-refresh poll. Failures back
+/// the schedule off instead of flashing, since nobody asked for this call.
+fn run_auto_refresh(model: &mut Model, client: &dyn CritClient) {
+    if !std::mem::take(&mut model.pending_refresh) {
+        return;
+    }
+    let result = match model.screen {
+        Screen::ReviewList => client.list_reviews(None).map(|reviews| {
+            model.reviews = reviews;
+            model.previews.clear();
+            model.queue_file_scan();
+            let count = model.filtered_reviews().len();
+            model.list_index = model.list_index.min(count.saturating_sub(1));
+        }),
+        Screen::ReviewDetail => refresh_current_review(model, client),
+        Screen::Inbox => client.list_reviews(None).map(|reviews| {
+            model.reviews = reviews;
+            if let Some(inbox) = model.inbox.as_mut() {
+                inbox.queue_open(&model.reviews, &model.statuses);
+            }
+        }),
+        // A comparison is a snapshot of the two reviews
+        Screen::Compare => Ok(()),
+    };
+    let now = clock::now();
+    if let Some(schedule) = model.auto_refresh.as_mut() {
+        if result.is_ok() {
+            schedule.record_success(now);
+        } else {
+            schedule.record_failure(now);
+        }
+    }
+    model.needs_redraw = true;
+}
+
+/// Reload the open review, keeping the current data (and its highlighting)
+/// when nothing changed.
+fn refresh_current_review(model: &mut Model, client: &dyn CritClient) -> Result<()> {
+    let Some(review) = &model.current_review else {
+        return Ok(());
+    };
+    let review_id = review.review_id.clone();
+    if let Some(data) = client.load_review_data(&review_id)? {
+        if review_data_changed(model, &data) {
+            model.install_review_data(data);
+        }
+    }
+    Ok(())
+}
+
+fn review_data_changed(model: &Model, data: &ReviewData) -> bool {
+    let etags = |threads: &[ThreadSummary], comments: &HashMap<String, Vec<Comment>>| {
+        threads
+            .iter()
+            .map(|t| {
+                let thread_comments = comments.get(&t.thread_id).map_or(&[][..], Vec::as_slice);
+                (t.thread_id.clone(), thread_etag(&t.status, thread_comments))
+            })
+            .collect::<Vec<_>>()
+    };
+    model.current_review.as_ref().is_none_or(|current| {
+        current.status != data.detail.status || current.commit() != data.detail.commit()
+    }) || etags(&model.threads, &model.all_comments) != etags(&data.threads, &data.comments)
+        || model.files_etag != files_etag(&data.files)
+}
+
+/// Load queued thread status histories, stopping after a short time budget
+/// so a large review doesn't stall input. Gives up on the rest of the queue
+/// if the backend can't answer (e.g. a crit without `threads events`).
+fn load_thread_events(model: &mut Model, client: &dyn CritClient) {
+    const BUDGET: Duration = Duration::from_millis(30);
+    let started = clock::now();
+    while let Some(thread_id) = model.pending_thread_events.pop() {
+        match client.thread_events(&thread_id) {
+            Ok(events) => {
+                if !events.is_empty() {
+                    model.thread_events.insert(thread_id, events);
+                    model.needs_redraw = true;
+                }
+            }
+            Err(_) => {
+                model.pending_thread_events.clear();
+                return;
+            }
+        }
+        if clock::elapsed(started) >= BUDGET {
+            return;
+        }
     }
 }
 
 fn handle_data_loading(
     model: &mut Model,
     client: &dyn CritClient,
-    _repo_path: Option<&std::path::Path>,
+    repo_path: Option<&std::path::Path>,
 ) {
     // Load review details when entering detail screen
     if model.screen == Screen::ReviewDetail && model.current_review.is_none() {
-        let reviews = model.filtered_reviews();
-        if let Some(review) = reviews.get(model.list_index) {
-            let review_id = review.review_id.clone();
-            if let Ok(Some(data)) = client.load_review_data(&review_id) {
-                model.current_review = Some(data.detail);
-                model.threads = data.threads;
-                model.all_comments = data.comments;
-                populate_file_cache(model, data.files);
-            }
-        }
+        load_review(model, client);
     }
 
-    // If we're on the detail screen and file cache is empty but we have review data,
-    // the cache was already populated by load_review_data above (or a previous call).
     if model.screen == Screen::ReviewDetail && model.current_review.is_some() {
+        load_pending_files(model, repo_path);
         model.sync_active_file_cache();
-        apply_pending_navigation(model);
+        // --file/--line/--thread targets need every file's rows in place
+        if model.pending_files.is_empty() {
+            apply_pending_navigation(model);
+        }
+        load_thread_events(model, client);
+    }
+
+    match model.screen {
+        Screen::ReviewList => {
+            load_review_preview(model, client);
+            scan_review_files(model, client);
+        }
+        Screen::Inbox => scan_inbox_review(model, client),
+        Screen::Compare => load_compared_review(model, client),
+        Screen::ReviewDetail => {}
     }
 
     ensure_default_expanded_thread(model);
 }
 
+/// Load the review opened from the list: on a worker thread when the client
+/// offers one (picking up the result on a later call), else inline.
+fn load_review(model: &mut Model, client: &dyn CritClient) {
+    if let Some(load) = &model.review_load {
+        if let Some(result) = load.poll() {
+            let review_id = load.review_id.clone();
+            model.review_load = None;
+            update(model, review_loaded_message(&review_id, result));
+        }
+        return;
+    }
+    let Some(review_id) = model
+        .filtered_reviews()
+        .get(model.list_index)
+        .map(|review| review.review_id.clone())
+    else {
+        return;
+    };
+    if let Some(worker) = client.worker() {
+        model.review_load = Some(ReviewLoad::start(worker, &review_id));
+    } else {
+        let result = client.load_review_data(&review_id);
+        update(model, review_loaded_message(&review_id, result));
+    }
+}
+
+fn review_loaded_message(review_id: &str, result: Result<Option<ReviewData>>) -> Message {
+    match result {
+        Ok(Some(data)) => Message::ReviewLoaded(Box::new(data)),
+        Ok(None) => Message::ReviewLoadFailed(tr_fmt(Text::ReviewNotFound, &[&review_id])),
+        Err(e) => Message::ReviewLoadFailed(format!("{e:#}")),
+    }
+}
+
+/// Load the highlighted review for the preview pane, if it shows one that
+/// isn't loaded yet. The data also counts as its file scan.
+fn load_review_preview(model: &mut Model, client: &dyn CritClient) {
+    let Some(review_id) = model.preview_to_load() else {
+        return;
+    };
+    let preview = match client.load_review_data(&review_id) {
+        Ok(Some(data)) => {
+            model.record_file_scan(&data);
+            model.pending_file_scan.retain(|id| id != &review_id);
+            ReviewPreview::from_data(&data)
+        }
+        Ok(None) => ReviewPreview::failed(tr_fmt(Text::ReviewNotFound, &[&review_id])),
+        Err(e) => ReviewPreview::failed(format!("{e:#}")),
+    };
+    model.previews.insert(review_id, preview);
+    model.needs_redraw = true;
+}
+
+/// Check the next queued review's changed files for ones the user owns and
+/// for annotations, one per iteration like the inbox scan. A review that
+/// fails to load is skipped.
+fn scan_review_files(model: &mut Model, client: &dyn CritClient) {
+    let Some(review_id) = model.pending_file_scan.pop() else {
+        return;
+    };
+    match client.load_review_data(&review_id) {
+        Ok(Some(data)) => {
+            model.record_file_scan(&data);
+            model.needs_redraw = true;
+        }
+        Ok(None) | Err(_) => {
+            model.file_scans.insert(review_id, FileScan::default());
+        }
+    }
+}
+
+/// Scan the next queued review for threads awaiting the user. One review
+/// per iteration (a crit call each), so keys stay responsive meanwhile.
+fn scan_inbox_review(model: &mut Model, client: &dyn CritClient) {
+    let Some(inbox) = model.inbox.as_mut() else {
+        return;
+    };
+    let Some(review_id) = inbox.pending.pop() else {
+        return;
+    };
+    match client.load_review_data(&review_id) {
+        Ok(data) => {
+            let items = data.map_or_else(Vec::new, |data| {
+                botcrit_ui::inbox::action_needed(&model.current_user, &data)
+            });
+            inbox.replace(&review_id, items);
+        }
+        Err(_) => inbox.failed += 1,
+    }
+    model.needs_redraw = true;
+}
+
+/// Load the next of the two compared reviews, one per iteration.
+fn load_compared_review(model: &mut Model, client: &dyn CritClient) {
+    let Some(comparison) = model.comparison.as_mut() else {
+        return;
+    };
+    let Some(review_id) = comparison.next_to_load().map(str::to_string) else {
+        return;
+    };
+    match client.load_review_data(&review_id) {
+        Ok(Some(data)) => comparison.loaded(data),
+        Ok(None) => comparison.error = Some(tr_fmt(Text::ReviewNotFound, &[&review_id])),
+        Err(e) => comparison.error = Some(format!("{e:#}")),
+    }
+    model.needs_redraw = true;
+}
+
 fn apply_pending_navigation(model: &mut Model) {
     if model.pending_thread.is_none() && model.pending_file.is_none() {
         return;
@@ -886,12 +1964,13 @@ fn apply_pending_navigation(model: &mut Model) {
             let files = model.files_with_threads();
             if let Some(idx) = files.iter().position(|f| f.path == thread_file) {
                 model.file_index = idx;
-                model.diff_scroll = file_scroll_offset(&nav_stream_layout(model), idx);
+                model.diff_scroll = file_scroll_offset(&model.stream_layout(), idx);
                 model.sync_active_file_cache();
             }
             model.expanded_thread = Some(thread_id);
-            // Clear pending_file since --thread takes precedence
+            // Clear pending_file/line since --thread takes precedence
             model.pending_file = None;
+            model.pending_line = None;
             model.needs_redraw = true;
             return;
         }
@@ -902,248 +1981,32 @@ fn apply_pending_navigation(model: &mut Model) {
         let files = model.files_with_threads();
         if let Some(idx) = files.iter().position(|f| f.path == file_path) {
             model.file_index = idx;
-            model.diff_scroll = file_scroll_offset(&nav_stream_layout(model), idx);
+            model.diff_scroll = file_scroll_offset(&model.stream_layout(), idx);
             model.sync_active_file_cache();
+            if let Some(line) = model.pending_line.take() {
+                jump_to_line(model, idx, &file_path, line);
+            }
             model.needs_redraw = true;
         }
     }
+    model.pending_line = None;
 }
 
-/// Compute stream layout for navigation purposes (mirrors `update.rs::stream_layout`).
-fn nav_stream_layout(model: &Model) -> botcrit_ui::stream::StreamLayout {
-    const DIFF_MARGIN: u32 = 2;
-    let total_width = u32::from(model.width);
-    let pane_width = match model.layout_mode {
-        LayoutMode::Full | LayoutMode::Compact | LayoutMode::Overlay => {
-            if model.sidebar_visible {
-                total_width.saturating_sub(u32::from(model.layout_mode.sidebar_width()))
-            } else {
-                total_width
-            }
-        }
-        LayoutMode::Single => total_width,
-    };
-    let width = pane_width.saturating_sub(DIFF_MARGIN * 2);
-    let files = model.files_with_threads();
-    let description = model
-        .current_review
-        .as_ref()
-        .and_then(|r| r.description.as_deref());
-    compute_stream_layout(&StreamLayoutParams {
-        files: &files,
-        file_cache: &model.file_cache,
-        threads: &model.threads,
-        all_comments: &model.all_comments,
-        view_mode: model.diff_view_mode,
-        wrap: model.diff_wrap,
-        content_width: width,
-        description,
-    })
-}
-
-fn handle_demo_data_loading(model: &mut Model) {
-    use botcrit_ui::db::ReviewDetail;
-
-    // In demo mode, repopulate threads/comments after SelectReview clears them
-    if model.screen == Screen::ReviewDetail && model.threads.is_empty() {
-        populate_demo_threads(model);
-    }
-
-    // In demo mode, populate current_review when entering detail view
-    if model.screen == Screen::ReviewDetail && model.current_review.is_none() {
-        let reviews = model.filtered_reviews();
-        if let Some(review) = reviews.get(model.list_index) {
-            model.current_review = Some(ReviewDetail {
-                review_id: review.review_id.clone(),
-                jj_change_id: "demo-change-id".to_string(),
-                initial_commit: "abc123".to_string(),
-                final_commit: None,
-                title: review.title.clone(),
-                description: Some("Demo review description".to_string()),
-                author: review.author.clone(),
-                created_at: "2025-01-27T12:00:00Z".to_string(),
-                status: review.status.clone(),
-                status_changed_at: None,
-                status_changed_by: None,
-                abandon_reason: None,
-                thread_count: review.thread_count,
-                open_thread_count: review.open_thread_count,
-            });
-        }
-    }
-
-    // Load diffs for all files in demo mode
-    if model.screen == Screen::ReviewDetail {
-        let files = model.files_with_threads();
-        for file in &files {
-            if model.file_cache.contains_key(&file.path) {
-                continue;
-            }
-            let diff = get_demo_diff(&file.path);
-            let highlighted_lines = if let Some(parsed) = &diff {
-                compute_diff_highlights(parsed, &file.path, &model.highlighter)
-            } else {
-                Vec::new()
-            };
-
-            model.file_cache.insert(
-                file.path.clone(),
-                botcrit_ui::model::FileCacheEntry {
-                    diff,
-                    file_content: None,
-                    highlighted_lines,
-                    file_highlighted_lines: Vec::new(),
-                },
-            );
-        }
-
-        model.sync_active_file_cache();
+/// Put the cursor on new-side `line` of the file at `file_index` (`--line`),
+/// centered in the view. Stays at the top of the file with a flash message if
+/// the line isn't rendered (outside every hunk and the content window).
+fn jump_to_line(model: &mut Model, file_index: usize, file_path: &str, line: u32) {
+    let regions = botcrit_ui::view::compute_stream_regions(model);
+    let row = regions.line_row(file_index, ThreadSide::New, i64::from(line));
+    *model.line_map.borrow_mut() = regions.rendered_rows().line_map.clone();
+
+    if let Some(row) = row {
+        model.diff_cursor = row;
+        model.diff_scroll = row.saturating_sub(visible_stream_rows(model.height) / 2);
+    } else {
+        model.diff_cursor = file_scroll_offset(&regions, file_index);
+        model.flash_message = Some(tr_fmt(Text::LineNotShown, &[&file_path, &line]));
     }
-
-    ensure_default_expanded_thread(model);
-}
-
-fn load_demo_data(model: &mut Model) {
-    use botcrit_ui::db::ReviewSummary;
-
-    model.reviews = vec![
-        ReviewSummary {
-            review_id: "cr-1d3".to_string(),
-            title: "Add user authentication".to_string(),
-            author: "alice".to_string(),
-            status: "open".to_string(),
-            thread_count: 3,
-            open_thread_count: 2,
-            reviewers: vec!["security-reviewer".to_string()],
-        },
-        ReviewSummary {
-            review_id: "cr-2f8".to_string(),
-            title: "Fix database connection pooling".to_string(),
-            author: "bob".to_string(),
-            status: "open".to_string(),
-            thread_count: 1,
-            open_thread_count: 1,
-            reviewers: Vec::new(),
-        },
-        ReviewSummary {
-            review_id: "cr-4a1".to_string(),
-            title: "Update dependencies to latest versions".to_string(),
-            author: "carol".to_string(),
-            status: "open".to_string(),
-            thread_count: 0,
-            open_thread_count: 0,
-            reviewers: Vec::new(),
-        },
-        ReviewSummary {
-            review_id: "cr-0b2".to_string(),
-            title: "Initial project setup".to_string(),
-            author: "alice".to_string(),
-            status: "merged".to_string(),
-            thread_count: 2,
-            open_thread_count: 0,
-            reviewers: vec!["api-reviewer".to_string(), "security-reviewer".to_string()],
-        },
-        ReviewSummary {
-            review_id: "cr-1c9".to_string(),
-            title: "WIP: Experimental feature".to_string(),
-            author: "bob".to_string(),
-            status: "abandoned".to_string(),
-            thread_count: 0,
-            open_thread_count: 0,
-            reviewers: Vec::new(),
-        },
-    ];
-
-    populate_demo_threads(model);
-}
-
-fn populate_demo_threads(model: &mut Model) {
-    use botcrit_ui::db::{Comment, ThreadSummary};
-
-    model.threads = vec![
-        ThreadSummary {
-            thread_id: "th-001".to_string(),
-            file_path: "src/auth.rs".to_string(),
-            selection_start: 42,
-            selection_end: Some(45),
-            status: "open".to_string(),
-            comment_count: 3,
-        },
-        ThreadSummary {
-            thread_id: "th-002".to_string(),
-            file_path: "src/auth.rs".to_string(),
-            selection_start: 78,
-            selection_end: None,
-            status: "resolved".to_string(),
-            comment_count: 2,
-        },
-        ThreadSummary {
-            thread_id: "th-003".to_string(),
-            file_path: "src/main.rs".to_string(),
-            selection_start: 15,
-            selection_end: None,
-            status: "open".to_string(),
-            comment_count: 1,
-        },
-    ];
-
-    model.all_comments.insert(
-        "th-001".to_string(),
-        vec![
-            Comment {
-                comment_id: "cm-001a".to_string(),
-                author: "bob".to_string(),
-                body: "The hardcoded 24h expiry should come from config. \
-                       What if we need shorter tokens for API clients?"
-                    .to_string(),
-                created_at: "2025-01-15T10:30:00Z".to_string(),
-            },
-            Comment {
-                comment_id: "cm-001b".to_string(),
-                author: "alice".to_string(),
-                body: "Good catch — updated to read from config.token_expiry_hours. \
-                       Defaults to 24h if unset."
-                    .to_string(),
-                created_at: "2025-01-15T11:05:00Z".to_string(),
-            },
-            Comment {
-                comment_id: "cm-001c".to_string(),
-                author: "bob".to_string(),
-                body: "Looks good, thanks!".to_string(),
-                created_at: "2025-01-15T11:20:00Z".to_string(),
-            },
-        ],
-    );
-
-    model.all_comments.insert(
-        "th-002".to_string(),
-        vec![
-            Comment {
-                comment_id: "cm-002a".to_string(),
-                author: "carol".to_string(),
-                body: "verify_password now returns Result instead of bool — \
-                       nice, this removes the silent failure path."
-                    .to_string(),
-                created_at: "2025-01-15T14:00:00Z".to_string(),
-            },
-            Comment {
-                comment_id: "cm-002b".to_string(),
-                author: "alice".to_string(),
-                body: "Exactly. The old unwrap_or(false) was masking bcrypt errors.".to_string(),
-                created_at: "2025-01-15T14:30:00Z".to_string(),
-            },
-        ],
-    );
-
-    model.all_comments.insert(
-        "th-003".to_string(),
-        vec![Comment {
-            comment_id: "cm-003a".to_string(),
-            author: "bob".to_string(),
-            body: "Should we also add a shutdown hook for graceful cleanup?".to_string(),
-            created_at: "2025-01-16T09:00:00Z".to_string(),
-        }],
-    );
 }
 
 fn ensure_default_expanded_thread(model: &mut Model) {
@@ -1151,145 +2014,12 @@ fn ensure_default_expanded_thread(model: &mut Model) {
         return;
     }
 
-    if let Some(thread) = model.threads_for_current_file().first() {
+    if let Some(thread) = model.visible_threads_for_current_file().first() {
         model.expanded_thread = Some(thread.thread_id.clone());
         return;
     }
 
-    if let Some(thread) = model.threads.first() {
+    if let Some(thread) = model.threads.iter().find(|t| !model.is_muted(&t.thread_id)) {
         model.expanded_thread = Some(thread.thread_id.clone());
     }
 }
-
-/// Get demo diff content for a file path
-fn get_demo_diff(file_path: &str) -> Option<botcrit_ui::diff::ParsedDiff> {
-    use botcrit_ui::diff::ParsedDiff;
-
-    let diff_content = match file_path {
-        "src/auth.rs" => {
-            r#"diff --git a/src/auth.rs b/src/auth.rs
-index abc123..def456 100644
---- a/src/auth.rs
-+++ b/src/auth.rs
-@@ -38,15 +38,18 @@ impl AuthService {
-     pub fn new(config: &Config) -> Self {
-         Self {
-             secret: config.jwt_secret.clone(),
--            expiry: Duration::hours(24),
-+            expiry: Duration::hours(config.token_expiry_hours),
-         }
-     }
- 
--    pub fn authenticate(&self, username: &str, password: &str) -> Result<Token> {
-+    /// Authenticate a user and return a JWT token
-+    pub fn authenticate(&self, username: &str, password: &str) -> Result<Token, AuthError> {
-+        // Validate input
-+        if username.is_empty() || password.is_empty() {
-+            return Err(AuthError::InvalidCredentials);
-+        }
-+
-         let user = self.find_user(username)?;
--        if !verify_password(password, &user.password_hash) {
--            return Err(anyhow!("Invalid credentials"));
--        }
-+        self.verify_password(password, &user.password_hash)?;
-         
-         self.generate_token(&user)
-     }
-@@ -72,12 +75,14 @@ impl AuthService {
-         Ok(User { id, username, role })
-     }
- 
--    fn verify_password(&self, password: &str, hash: &str) -> bool {
--        bcrypt::verify(password, hash).unwrap_or(false)
-+    fn verify_password(&self, password: &str, hash: &str) -> Result<(), AuthError> {
-+        if bcrypt::verify(password, hash).unwrap_or(false) {
-+            Ok(())
-+        } else {
-+            Err(AuthError::InvalidCredentials)
-+        }
-     }
- 
-     fn generate_token(&self, user: &User) -> Result<Token> {
-         let claims = Claims {
-             sub: user.id.to_string(),
-"#
-        }
-        "src/main.rs" => {
-            r#"diff --git a/src/main.rs b/src/main.rs
-index 111222..333444 100644
---- a/src/main.rs
-+++ b/src/main.rs
-@@ -10,14 +10,18 @@ use config::Config;
- 
- mod auth;
- mod config;
-+mod error;
- mod handlers;
- 
- fn main() -> Result<()> {
--    let config = Config::from_env()?;
-+    // Load configuration from environment or file
-+    let config = Config::load()?;
-+    
-+    // Initialize logging
-     env_logger::init();
-+    info!("Starting server with config: {:?}", config);
-     
--    let auth = AuthService::new(&config);
--    let server = Server::new(config.port);
-+    let app = App::new(config)?;
-     
--    server.run(auth)?;
-+    app.run()?;
-     Ok(())
- }
-"#
-        }
-        _ => return None,
-    };
-
-    Some(ParsedDiff::parse(diff_content))
-}
-
-/// Compute syntax highlighting for diff lines
-fn compute_diff_highlights(
-    diff: &botcrit_ui::diff::ParsedDiff,
-    file_path: &str,
-    highlighter: &botcrit_ui::Highlighter,
-) -> Vec<Vec<botcrit_ui::HighlightSpan>> {
-    let mut result = Vec::new();
-
-    // Get a file highlighter to maintain state across lines
-    let Some(mut file_hl) = highlighter.for_file(file_path) else {
-        return result;
-    };
-
-    for hunk in &diff.hunks {
-        // Hunk header - no highlighting needed
-        result.push(Vec::new());
-
-        for line in &hunk.lines {
-            let spans = file_hl.highlight_line(&line.content);
-            result.push(spans);
-        }
-    }
-
-    result
-}
-
-/// Compute syntax highlighting for file content lines
-fn compute_file_highlights(
-    lines: &[String],
-    file_path: &str,
-    highlighter: &botcrit_ui::Highlighter,
-) -> Vec<Vec<botcrit_ui::HighlightSpan>> {
-    let Some(mut file_hl) = highlighter.for_file(file_path) else {
-        return Vec::new();
-    };
-
-    lines
-        .iter()
-        .map(|line| file_hl.highlight_line(line))
-        .collect()
-}
@@ -1,13 +1,48 @@
 //! Message types for the Elm Architecture
 
-/// All possible user actions and system events
-#[derive(Debug, Clone)]
+use serde::Deserialize;
+
+use crate::command::CommandId;
+use crate::db::{ReviewData, ThreadSide, Verdict};
+use crate::highlight::Highlights;
+use crate::model::ReviewSort;
+
+/// All possible user actions and system events. Deserializes from the
+/// control socket's JSON (`"Quit"`, `{"SelectFile": 2}`); events only the
+/// UI itself produces are skipped.
+#[derive(Debug, Clone, Deserialize)]
 pub enum Message {
     // === Navigation ===
     /// Select a review from the list
     SelectReview(String),
     /// Go back to previous screen
     Back,
+    /// Show threads awaiting a reply across all open reviews
+    ShowInbox,
+    /// Rescan all open reviews for the inbox
+    RefreshInbox,
+    /// Open the inbox thread at this index in its review
+    OpenInboxItem(usize),
+    /// Mark or unmark a review in the list for comparison
+    ToggleCompareMark(String),
+    /// Compare the two marked reviews
+    ShowComparison,
+    /// Show the diff-of-diffs of the selected compared file
+    OpenComparedFile,
+    /// Show or hide the review list's preview pane
+    TogglePreview,
+    /// The review opened by `SelectReview` finished loading
+    #[serde(skip_deserializing)]
+    ReviewLoaded(Box<ReviewData>),
+    /// Loading the opened review failed, with the reason
+    #[serde(skip_deserializing)]
+    ReviewLoadFailed(String),
+    /// The highlight worker finished a cached file
+    #[serde(skip_deserializing)]
+    HighlightsReady(Box<Highlights>),
+    /// crit's database or the working copy changed on disk; reload what's
+    /// on screen
+    ExternalRefresh,
 
     // === List Navigation ===
     /// Move selection up in list
@@ -32,14 +67,46 @@ pub enum Message {
     SidebarTop,
     /// Jump to last sidebar item
     SidebarBottom,
+    /// Move up a page in the sidebar tree
+    SidebarPageUp,
+    /// Move down a page in the sidebar tree
+    SidebarPageDown,
+    /// Scroll the sidebar up a row, keeping the selection (mouse wheel)
+    SidebarScrollUp,
+    /// Scroll the sidebar down a row, keeping the selection (mouse wheel)
+    SidebarScrollDown,
     /// Select file by index
     SelectFile(usize),
+    /// Jump to the nth (from 1) of `Model::recent_other_files` (Alt+n)
+    JumpToRecentFile(usize),
     /// Select sidebar item by row index (mouse click)
     ClickSidebarItem(usize),
     /// Activate current sidebar item (Enter)
     SidebarSelect,
+    /// Collapse the selected tree sidebar node, else go to its parent (`h`)
+    SidebarCollapse,
+    /// Expand the selected tree sidebar node, else enter it (`l`)
+    SidebarExpand,
+    /// Left press on the sidebar divider
+    StartSidebarResize,
+    /// Divider dragged; the sidebar's new width
+    ResizeSidebar(u16),
+    /// Button released after `StartSidebarResize`; saves the width
+    EndSidebarResize,
+    /// Mouse moved onto (`true`) or off the sidebar divider
+    HoverDivider(bool),
 
     // === Diff/Content Pane ===
+    /// Left click on a stream row in the diff pane; `side` is the
+    /// side-by-side column clicked
+    ClickDiffRow {
+        row: usize,
+        side: Option<ThreadSide>,
+    },
+    /// Drag from the last `ClickDiffRow` to a stream row
+    DragDiffRow(usize),
+    /// Mouse button released after `ClickDiffRow`
+    EndDiffDrag,
     /// Move cursor up one row
     CursorUp,
     /// Move cursor down one row
@@ -48,12 +115,79 @@ pub enum Message {
     CursorTop,
     /// Move cursor to last row
     CursorBottom,
+    /// Move cursor to the first line of the next hunk
+    CursorNextHunk,
+    /// Move cursor to the first line of this hunk, or the previous one
+    CursorPrevHunk,
+    /// Move cursor to the first line of the next file
+    CursorNextFile,
+    /// Move cursor to the first line of this file, or the previous one
+    CursorPrevFile,
+    /// Move cursor to the start of the next run of added/removed lines
+    CursorNextChange,
+    /// Move cursor to the start of this run of changes, or the previous one
+    CursorPrevChange,
     /// Toggle visual line selection mode (Shift+V)
     VisualToggle,
+    /// Focus the old (left) or new (right) column in side-by-side mode
+    FocusSbsSide(ThreadSide),
+    /// Copy the cursor line or visual selection from the focused side
+    CopyLines,
+    /// Copy the id of the comment under the cursor
+    CopyCommentId,
+    /// Copy a link to the thread under the cursor (or the expanded one)
+    CopyThreadLink,
+    /// Copy the path of the selected or current file
+    CopyFilePath,
+    /// Copy `path:line` (or `path:start-end`) for the cursor line or
+    /// visual selection
+    CopyLineReference,
+    /// Put text on the clipboard
+    CopyToClipboard(String),
+    /// Copy the command that checks out the review's change locally
+    CopyCheckoutCommand,
+    /// Write the open review to `<review id>.md` as Markdown
+    ExportReview,
+    /// Run `review_command` at the review's commit (unless it's already
+    /// running) and show its output
+    RunReviewCommand,
+    /// Show the last `review_command` output, or start a run
+    ShowCommandOutput,
+    /// Close the command output overlay
+    HideCommandOutput,
+    /// Scroll the command output overlay
+    CommandOutputUp,
+    CommandOutputDown,
+    CommandOutputTop,
+    CommandOutputBottom,
+    /// Show the input latency log
+    ShowInputLog,
+    /// Close the input latency log
+    HideInputLog,
+    /// Scroll the input latency log
+    InputLogUp,
+    InputLogDown,
+    InputLogTop,
+    InputLogBottom,
+    /// Open the threads panel, or close it if open
+    ToggleThreadsPanel,
+    /// Move the threads panel selection
+    ThreadsPanelUp,
+    ThreadsPanelDown,
+    ThreadsPanelTop,
+    ThreadsPanelBottom,
+    /// Close the threads panel and go to the selected thread
+    ThreadsPanelSelect,
+    /// Scroll to a comment by id and briefly highlight it
+    JumpToComment(String),
     /// Scroll content up
     ScrollUp,
     /// Scroll content down
     ScrollDown,
+    /// Scroll the diff without moving the cursor (the wheel with
+    /// `wheel_scroll: viewport`)
+    ViewportScrollUp,
+    ViewportScrollDown,
     /// Scroll to top
     ScrollTop,
     /// Scroll to bottom
@@ -78,6 +212,8 @@ pub enum Message {
     ExpandThread(String),
     /// Collapse expanded thread
     CollapseThread,
+    /// Mute or unmute the thread under the cursor (or selected in the sidebar)
+    ToggleMuteThread,
 
     // === Focus ===
     /// Toggle focus between panes
@@ -88,11 +224,23 @@ pub enum Message {
     ResolveThread(String),
     /// Reopen a resolved thread
     ReopenThread(String),
+    /// Take back the last resolve, reopen or viewed mark (u)
+    Undo,
+    /// Apply the last undone action again (Ctrl+r)
+    Redo,
 
     // === Filter/View ===
     /// Cycle review list status filter (All → Open → Closed → All)
     CycleStatusFilter,
-    /// Activate search input on review list
+    /// Order the review list, saved to `ui.json`
+    SetReviewSort(ReviewSort),
+    /// Run the review list in the opposite order (clicking the sorted
+    /// column's title)
+    ReverseReviewSort,
+    /// Toggle keeping the review list's statuses together under any sort
+    ToggleReviewGrouping,
+    /// Activate search input: the review list filter, or `/` search on a
+    /// review
     SearchActivate,
     /// Append character to search input
     SearchInput(String),
@@ -104,12 +252,52 @@ pub enum Message {
     SearchClearLine,
     /// Clear and deactivate search
     SearchClear,
+    /// Finish typing a review search, keeping its matches
+    SearchSubmit,
+    /// Move to the next review search match
+    SearchNext,
+    /// Move to the previous review search match
+    SearchPrev,
+    /// Open the review list's filter bar
+    FilterBarActivate,
+    /// Append to the filter bar
+    FilterBarInput(String),
+    /// Delete the last character from the filter bar
+    FilterBarBackspace,
+    /// Delete the last word (term) from the filter bar
+    FilterBarDeleteWord,
+    /// Close the filter bar, keeping its filters
+    FilterBarSubmit,
+    /// Close the filter bar and drop its filters
+    FilterBarClear,
     /// Toggle between unified and side-by-side diff view
     ToggleDiffView,
+    /// Toggle the diff view of the current file only
+    ToggleFileDiffView,
     /// Toggle file sidebar visibility
     ToggleSidebar,
     /// Toggle diff line wrapping
     ToggleDiffWrap,
+    /// Toggle the word diff of the current (prose) file
+    ToggleWordDiff,
+    /// Toggle syntax highlighting of the current file
+    ToggleFileHighlighting,
+    /// Fold or unfold the hunk at the cursor
+    ToggleHunkFold,
+    /// Fold or unfold the current file to its header
+    ToggleFileFold,
+    /// Show more unchanged lines above the hunk at the cursor
+    ExpandContextUp,
+    /// Show more unchanged lines below the hunk at the cursor
+    ExpandContextDown,
+    /// Mark the selected or current file viewed, or not
+    ToggleFileViewed,
+    /// Toggle `^M` markers on CRLF lines
+    ToggleCrMarkers,
+    /// Toggle the blame column beside unified diff lines
+    ToggleBlame,
+    /// Step the UI density (compact → normal → relaxed) and save it
+    CycleDensity,
     /// Open current file in editor
     OpenFileInEditor,
 
@@ -122,12 +310,55 @@ pub enum Message {
     CommandPaletteInputBackspace,
     CommandPaletteDeleteWord,
     CommandPaletteExecute,
+    /// Reopen the palette as a comment id prompt
+    ShowCommentPicker,
+    /// Reopen the palette as a value prompt for a command
+    PromptCommandArgument(CommandId),
+    /// Put the cursor on a new-side line of the file under the cursor
+    GoToLine(u32),
+    /// Set the lines of context around threads outside the diff
+    SetContextLines(u16),
+
+    // === Action Menu ===
+    /// Open the `.` menu of actions available at the diff cursor
+    ShowActionMenu,
+    HideActionMenu,
+    ActionMenuNext,
+    ActionMenuPrev,
+    ActionMenuExecute,
+    /// Run the menu entry at this index (its key was pressed)
+    ActionMenuPick(usize),
+    /// Show who last changed the cursor line
+    ViewBlame,
+    /// Apply the suggestion under the cursor to the working copy (S)
+    ApplySuggestion,
+    /// Take the last applied suggestion back out (U)
+    UndoSuggestion,
 
     // === Commenting ===
     /// Open inline multi-line comment editor (a)
     StartComment,
     /// Open $EDITOR for comment (Shift+A)
     StartCommentExternal,
+    /// Inline comment on the visual selection, pre-filled with the selected
+    /// code as a quoted snippet (>)
+    StartQuotedComment,
+    /// New thread on the whole hunk under the cursor (c)
+    StartHunkComment,
+    /// New file-level thread on the selected file (a in the sidebar)
+    StartFileComment,
+    /// New review-level thread, about no file (palette)
+    StartReviewComment,
+    /// Reply to the expanded thread (r)
+    StartReply,
+    /// Offer the verdict choices in the action menu (palette)
+    FinishReview,
+    /// Open the inline editor on the summary for a verdict
+    StartVerdict(Verdict),
+    /// Open the inline editor on the reason for abandoning the review
+    StartAbandonReview,
+    /// Reopen the abandoned review
+    RestoreReview,
     EnterCommentMode,
     CommentInput(String),
     CommentInputBackspace,
@@ -142,8 +373,12 @@ pub enum Message {
     CommentWordRight,
     CommentDeleteWord,
     CommentClearLine,
+    /// Insert a suggestion block holding the target lines (Ctrl+G)
+    CommentInsertSuggestion,
     SaveComment,
     CancelComment,
+    /// Take back a saved comment before it's sent (`u`)
+    CancelCommentSubmission,
 
     // === Theme Selection ===
     ShowThemePicker,
@@ -151,14 +386,47 @@ pub enum Message {
 
     // === System ===
     /// Terminal resize event
+    #[serde(skip_deserializing)]
     Resize {
         width: u16,
         height: u16,
     },
     /// Periodic tick for animations/refresh
     Tick,
+    /// The terminal window gained focus
+    TerminalFocusGained,
+    /// The terminal window lost focus
+    TerminalFocusLost,
     /// Request to quit
     Quit,
     /// No-op (ignore event)
     Noop,
 }
+
+impl Message {
+    /// Whether this message would change review data (comments, thread or
+    /// review status) or the working copy. Blocked in read-only mode.
+    #[must_use]
+    pub const fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            Self::StartComment
+                | Self::StartCommentExternal
+                | Self::StartQuotedComment
+                | Self::StartHunkComment
+                | Self::StartFileComment
+                | Self::StartReviewComment
+                | Self::StartReply
+                | Self::FinishReview
+                | Self::StartVerdict(_)
+                | Self::StartAbandonReview
+                | Self::RestoreReview
+                | Self::EnterCommentMode
+                | Self::SaveComment
+                | Self::ResolveThread(_)
+                | Self::ReopenThread(_)
+                | Self::ApplySuggestion
+                | Self::UndoSuggestion
+        )
+    }
+}
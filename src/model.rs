@@ -2,14 +2,50 @@
 
 use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
-use std::time::Instant;
-
-use crate::command::CommandSpec;
+use std::ops::{Range, RangeInclusive};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::action_log::{ActionLog, LogStep, ReviewAction};
+use crate::annotations::{Annotations, SeverityCounts};
+use crate::command::{CommandId, CommandSpec};
+use crate::compare::Comparison;
 use crate::config::UiConfig;
-use crate::db::{Comment, ReviewDetail, ReviewSummary, ThreadDetail, ThreadSummary};
-use crate::diff::ParsedDiff;
+use crate::db::{
+    files_etag, thread_etag, thread_location, Comment, CreatedComment, DiffStats, FileData,
+    ReviewData, ReviewDetail, ReviewSummary, ReviewVote, ThreadConflict, ThreadDetail, ThreadEvent,
+    ThreadSide, ThreadSummary, Verdict,
+};
+use crate::diff::{
+    sanitize_control_chars, split_cr, word_diff, ParsedDiff, TextEncoding, WordLine,
+};
+use crate::highlight::{HighlightJob, HighlightWorker};
+use crate::i18n::{tr, tr_fmt, Text};
+use crate::inbox::Inbox;
+use crate::keymap::Keymap;
+use crate::layout::PREVIEW_MIN_WIDTH;
+use crate::loader::ReviewLoad;
+use crate::message::Message;
+use crate::owners::Codeowners;
+use crate::preview::{diff_stats, ReviewPreview};
+use crate::profile::InputProfile;
+use crate::refresh::{spinner_frame, RefreshSchedule};
+use crate::render_backend::KeyEvent;
+use crate::review_command::CommandRun;
+use crate::review_query::ReviewQuery;
+use crate::search::{DiffSearch, SearchHit};
+use crate::state::{UiState, VerdictDraft};
+use crate::status::StatusWorkflow;
+use crate::stream::{split_file_threads, Folds, StreamCache, StreamLayout, StreamLayoutParams};
+use crate::suggestion::{last_suggestion, replaced_lines, SuggestionPatch};
 use crate::syntax::{HighlightSpan, Highlighter};
 use crate::theme::Theme;
+use crate::vcs::{self, VcsType};
+use crate::view::map_threads_to_diff;
 
 /// File content for displaying context when no diff is available.
 ///
@@ -21,6 +57,30 @@ pub struct FileContent {
     pub lines: Vec<String>,
     /// 1-based line number of `lines[0]`. Defaults to 1 for full files.
     pub start_line: i64,
+    /// Line endings and encoding seen in `lines`
+    pub encoding: TextEncoding,
+}
+
+impl FileContent {
+    /// Build from raw lines, stripping trailing `\r` and replacing control
+    /// characters so the text is safe to render.
+    #[must_use]
+    pub fn new(raw_lines: Vec<String>, start_line: i64) -> Self {
+        let mut encoding = TextEncoding::default();
+        let lines = raw_lines
+            .into_iter()
+            .map(|raw| {
+                let (line, crlf) = split_cr(&raw);
+                encoding.observe(line, crlf);
+                sanitize_control_chars(line).into_owned()
+            })
+            .collect();
+        Self {
+            lines,
+            start_line,
+            encoding,
+        }
+    }
 }
 
 /// Cached data for a file in the review stream
@@ -31,18 +91,78 @@ pub struct FileCacheEntry {
     /// Syntax highlights indexed by file line number (for orphaned thread context).
     /// Only populated when both `diff` and `file_content` are present.
     pub file_highlighted_lines: Vec<Vec<HighlightSpan>>,
+    /// Word diff of each hunk, for prose files (`W` shows it)
+    pub word_lines: Option<Vec<Vec<WordLine>>>,
+    /// `diff` was pretty-printed from JSON, so its line numbers aren't the
+    /// file's
+    pub normalized: bool,
+    /// Changes whenever the entry does, for `StreamCache` keys
+    pub revision: u64,
+}
+
+impl FileCacheEntry {
+    /// A revision no entry has had yet.
+    #[must_use]
+    pub fn next_revision() -> u64 {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Text of a new-side line, from the diff if it covers the line, otherwise
+    /// from the file content window.
+    #[must_use]
+    pub fn new_side_line(&self, line: i64) -> Option<&str> {
+        let from_diff = self.diff.as_ref().and_then(|diff| {
+            diff.hunks
+                .iter()
+                .flat_map(|hunk| &hunk.lines)
+                .find(|l| l.new_line.is_some_and(|n| i64::from(n) == line))
+                .map(|l| l.content.as_str())
+        });
+        from_diff.or_else(|| {
+            let content = self.file_content.as_ref()?;
+            let idx = usize::try_from(line - content.start_line).ok()?;
+            content.lines.get(idx).map(String::as_str)
+        })
+    }
+
+    /// Text of an old-side line. Only lines inside the diff's hunks are
+    /// known; the content window is new-side.
+    #[must_use]
+    pub fn old_side_line(&self, line: i64) -> Option<&str> {
+        self.diff
+            .as_ref()?
+            .hunks
+            .iter()
+            .flat_map(|hunk| &hunk.lines)
+            .find(|l| l.old_line.is_some_and(|n| i64::from(n) == line))
+            .map(|l| l.content.as_str())
+    }
+
+    /// Text of `line` on `side`.
+    #[must_use]
+    pub fn side_line(&self, side: ThreadSide, line: i64) -> Option<&str> {
+        match side {
+            ThreadSide::Old => self.old_side_line(line),
+            ThreadSide::New => self.new_side_line(line),
+        }
+    }
 }
 
 /// Current screen/view
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
 pub enum Screen {
     #[default]
     ReviewList,
     ReviewDetail,
+    /// Threads awaiting the user's reply across all open reviews
+    Inbox,
+    /// Two reviews' files side by side
+    Compare,
 }
 
 /// Which pane has focus
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
 pub enum Focus {
     #[default]
     ReviewList,
@@ -51,6 +171,117 @@ pub enum Focus {
     ThreadExpanded,
     CommandPalette,
     Commenting,
+    ActionMenu,
+    /// Output overlay of `review_command`
+    CommandOutput,
+    /// Review-wide thread list (`t`)
+    ThreadsPanel,
+    /// Input latency log overlay
+    InputLog,
+}
+
+/// Entry in the `.` action menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    Comment,
+    CommentHunk,
+    CommentFile,
+    CopyLine,
+    CopyLineReference,
+    CopyCommentId,
+    CopyThreadLink,
+    OpenInEditor,
+    ViewBlame,
+    ToggleMute,
+    ApplySuggestion,
+    /// Verdict choices, offered by "Finish review"
+    Approve,
+    RequestChanges,
+    /// Offered by "Finish review" in place of a verdict
+    AbandonReview,
+    RestoreReview,
+}
+
+impl MenuAction {
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        tr(match self {
+            Self::Comment => Text::MenuComment,
+            Self::CommentHunk => Text::MenuCommentHunk,
+            Self::CommentFile => Text::MenuCommentFile,
+            Self::CopyLine => Text::MenuCopyLine,
+            Self::CopyLineReference => Text::MenuCopyLineReference,
+            Self::CopyCommentId => Text::MenuCopyCommentId,
+            Self::CopyThreadLink => Text::MenuCopyThreadLink,
+            Self::OpenInEditor => Text::MenuOpenInEditor,
+            Self::ViewBlame => Text::MenuViewBlame,
+            Self::ToggleMute => Text::MenuToggleMute,
+            Self::ApplySuggestion => Text::MenuApplySuggestion,
+            Self::Approve => Text::MenuApprove,
+            Self::RequestChanges => Text::MenuRequestChanges,
+            Self::AbandonReview => Text::MenuAbandonReview,
+            Self::RestoreReview => Text::MenuRestoreReview,
+        })
+    }
+
+    /// Key that picks the action while the menu is open.
+    #[must_use]
+    pub const fn key(self) -> char {
+        match self {
+            Self::Comment | Self::Approve => 'a',
+            Self::CommentHunk => 'c',
+            Self::CommentFile => 'f',
+            Self::CopyLine => 'y',
+            Self::AbandonReview => 'x',
+            Self::CopyLineReference => 'p',
+            Self::CopyCommentId => 'Y',
+            Self::CopyThreadLink => 'l',
+            Self::OpenInEditor | Self::RestoreReview => 'o',
+            Self::ViewBlame => 'b',
+            Self::ToggleMute => 'm',
+            Self::ApplySuggestion => 's',
+            Self::RequestChanges => 'r',
+        }
+    }
+
+    #[must_use]
+    pub const fn message(self) -> Message {
+        match self {
+            Self::Comment => Message::StartComment,
+            Self::CommentHunk => Message::StartHunkComment,
+            Self::CommentFile => Message::StartFileComment,
+            Self::CopyLine => Message::CopyLines,
+            Self::CopyLineReference => Message::CopyLineReference,
+            Self::CopyCommentId => Message::CopyCommentId,
+            Self::CopyThreadLink => Message::CopyThreadLink,
+            Self::OpenInEditor => Message::OpenFileInEditor,
+            Self::ViewBlame => Message::ViewBlame,
+            Self::ToggleMute => Message::ToggleMuteThread,
+            Self::ApplySuggestion => Message::ApplySuggestion,
+            Self::Approve => Message::StartVerdict(Verdict::Approve),
+            Self::RequestChanges => Message::StartVerdict(Verdict::RequestChanges),
+            Self::AbandonReview => Message::StartAbandonReview,
+            Self::RestoreReview => Message::RestoreReview,
+        }
+    }
+}
+
+/// Request to look up who last changed a line (`git blame`).
+#[derive(Debug, Clone)]
+pub struct BlameRequest {
+    pub file_path: String,
+    /// 1-based new-side line number
+    pub line: i64,
+    /// Revision the line number refers to
+    pub commit: String,
+}
+
+/// Suggestion patch to apply to the working copy, or with `undo` to take
+/// back out.
+#[derive(Debug, Clone)]
+pub struct PatchRequest {
+    pub patch: SuggestionPatch,
+    pub undo: bool,
 }
 
 /// What the command palette is showing
@@ -59,12 +290,17 @@ pub enum PaletteMode {
     #[default]
     Commands,
     Themes,
+    /// Comment id prompt for jumping to a comment
+    Comments,
+    /// Value prompt for a command that takes one
+    Argument(CommandId),
 }
 
 /// Responsive layout mode based on terminal width
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum LayoutMode {
     /// >= 120 cols: full sidebar + diff
+    #[default]
     Full,
     /// 90-119 cols: compact sidebar + diff
     Compact,
@@ -75,7 +311,7 @@ pub enum LayoutMode {
 }
 
 /// Diff view mode
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum DiffViewMode {
     /// Traditional unified diff (default)
     #[default]
@@ -84,6 +320,32 @@ pub enum DiffViewMode {
     SideBySide,
 }
 
+impl DiffViewMode {
+    /// The other mode.
+    #[must_use]
+    pub const fn toggled(self) -> Self {
+        match self {
+            Self::Unified => Self::SideBySide,
+            Self::SideBySide => Self::Unified,
+        }
+    }
+}
+
+/// More unchanged lines asked for around a hunk, read by the main loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextExpansion {
+    pub path: String,
+    /// Index of the file in `files_with_threads`
+    pub file: usize,
+    pub hunk: usize,
+    /// Above the hunk, else below it
+    pub up: bool,
+    /// New-side lines to read
+    pub lines: RangeInclusive<u32>,
+    /// Stream row of the hunk's header
+    pub header_row: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct EditorRequest {
     pub file_path: String,
@@ -97,21 +359,185 @@ pub struct CommentRequest {
     pub review_id: String,
     /// File the comment targets
     pub file_path: String,
-    /// Start line (new-side, 1-based)
+    /// Start line (1-based, numbered on `side`)
     pub start_line: i64,
-    /// End line (new-side, 1-based); None means single line
+    /// End line (1-based, numbered on `side`); None means single line
     pub end_line: Option<i64>,
+    /// Which revision the line numbers refer to
+    pub side: ThreadSide,
     /// If Some, add comment to existing thread; if None, create new thread
     pub thread_id: Option<String>,
     /// Existing comments for context in the editor temp file
     pub existing_comments: Vec<Comment>,
+    /// For replies, the thread's `thread_etag` when the request was built;
+    /// the reply is refused if the thread has changed since
+    pub thread_etag: Option<String>,
+    /// First few source lines of the target, shown so the author can confirm
+    /// what they're commenting on
+    pub excerpt: Vec<String>,
+    /// Warning shown above the excerpt, e.g. when the target had to be
+    /// corrected because the display was out of date
+    pub notice: Option<String>,
 }
 
-/// A comment ready to be persisted (from the inline editor).
+impl CommentRequest {
+    /// Where a draft for this target is kept within its review: the thread
+    /// for a reply, else `path:line` (old-side lines marked).
+    #[must_use]
+    pub fn draft_key(&self) -> String {
+        if let Some(thread_id) = &self.thread_id {
+            return thread_id.clone();
+        }
+        let location = thread_location(&self.file_path, self.start_line, self.end_line);
+        match self.side {
+            ThreadSide::Old => format!("{location} (old)"),
+            ThreadSide::New => location,
+        }
+    }
+
+    /// Lines of the target range past the end of `excerpt`, for a "more
+    /// lines" row under it. 0 without an excerpt.
+    #[must_use]
+    pub fn excerpt_hidden_lines(&self) -> usize {
+        if self.excerpt.is_empty() {
+            return 0;
+        }
+        let end = self
+            .end_line
+            .unwrap_or(self.start_line)
+            .max(self.start_line);
+        usize::try_from(end - self.start_line + 1)
+            .unwrap_or(0)
+            .saturating_sub(self.excerpt.len())
+    }
+}
+
+/// `template` with `{review}` and `{thread}` filled in, or without one the
+/// command that opens crit-ui on the thread.
+#[must_use]
+pub fn thread_link(template: Option<&str>, review_id: &str, thread_id: &str) -> String {
+    template.map_or_else(
+        || format!("crit-ui --review {review_id} --thread {thread_id}"),
+        |template| {
+            template
+                .replace("{review}", review_id)
+                .replace("{thread}", thread_id)
+        },
+    )
+}
+
+/// `lines` (the request's target) as a Markdown block quote.
+///
+/// A `path:start-end` attribution comes first, then a code fence tagged
+/// with the file's extension. Old-side targets are marked, since their
+/// numbers refer to the base revision.
+#[must_use]
+pub fn quoted_snippet(request: &CommentRequest, lines: &[&str]) -> String {
+    let mut target = match request.end_line {
+        Some(end) if end != request.start_line => {
+            format!("{}:{}-{end}", request.file_path, request.start_line)
+        }
+        _ => format!("{}:{}", request.file_path, request.start_line),
+    };
+    if request.side == ThreadSide::Old {
+        target.push_str(" (old)");
+    }
+    // A fence longer than any backtick run in the code
+    let longest_run = lines
+        .iter()
+        .flat_map(|line| line.split(|c| c != '`'))
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    let language = std::path::Path::new(&request.file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+
+    let mut quoted = vec![format!("> `{target}`"), format!("> {fence}{language}")];
+    quoted.extend(
+        lines
+            .iter()
+            .map(|line| format!("> {line}").trim_end().to_string()),
+    );
+    quoted.push(format!("> {fence}"));
+    quoted.join("\n")
+}
+
+/// Max source lines kept in `CommentRequest::excerpt`; enough to show most
+/// visual selections whole.
+pub const COMMENT_EXCERPT_LINES: usize = 8;
+
+/// Narrowest the sidebar can be dragged to.
+pub const MIN_SIDEBAR_WIDTH: u16 = 16;
+
+/// How long a comment stays highlighted after jumping to it.
+pub const COMMENT_FLASH: Duration = Duration::from_millis(1200);
+
+/// How often the open comment editor's text is kept as a draft.
+pub const DRAFT_AUTOSAVE: Duration = Duration::from_secs(2);
+
+/// Recent files offered on Alt+1… in the breadcrumb, besides the current one.
+pub const RECENT_FILES: usize = 5;
+
+/// How long a saved comment waits before it's sent, so `u` can take it back
+/// (`comment_undo_secs` in `ui.json`).
+pub const COMMENT_UNDO_WINDOW: Duration = Duration::from_secs(3);
+
+/// Placeholder id for an optimistic thread until crit assigns the real one.
+const PENDING_THREAD_ID: &str = "pending-thread";
+/// Placeholder id for an optimistic comment until crit assigns the real one.
+const PENDING_COMMENT_ID: &str = "pending-comment";
+
+/// A comment ready to be persisted (from the inline or external editor).
+///
+/// While queued, an optimistic copy is already in `threads`/`all_comments`.
 #[derive(Debug, Clone)]
 pub struct PendingCommentSubmission {
     pub request: CommentRequest,
     pub body: String,
+    /// When main sends it; until then `u` cancels it
+    pub send_at: Instant,
+}
+
+/// A thread resolve or reopen waiting to be written through the client.
+///
+/// While queued, the thread already shows `status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadStatusChange {
+    pub thread_id: String,
+    pub status: String,
+    /// Status to restore if the write fails
+    pub previous: String,
+    /// The open review's id and the thread's `thread_etag` before the
+    /// change; crit refuses the write if another client changed the thread
+    /// since
+    pub expected: Option<(String, String)>,
+    /// History move to make once it's written
+    pub step: LogStep,
+}
+
+/// A verdict and its summary comment, waiting to be written through the
+/// client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerdictSubmission {
+    pub review_id: String,
+    pub verdict: Verdict,
+    pub summary: Option<String>,
+}
+
+/// A change to the review's own status, waiting to be written through the
+/// client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewStatusChange {
+    Abandon {
+        review_id: String,
+        reason: Option<String>,
+    },
+    Restore {
+        review_id: String,
+    },
 }
 
 /// In-TUI multi-line comment editor state.
@@ -127,6 +553,12 @@ pub struct InlineEditor {
     pub scroll: usize,
     /// The comment request this editor is for
     pub request: CommentRequest,
+    /// Set when writing a verdict's summary rather than a comment; the
+    /// body may then be empty
+    pub verdict: Option<Verdict>,
+    /// Set when writing the reason for abandoning the review, which may
+    /// also be empty
+    pub abandon: bool,
 }
 
 impl InlineEditor {
@@ -138,9 +570,35 @@ impl InlineEditor {
             cursor_col: 0,
             scroll: 0,
             request,
+            verdict: None,
+            abandon: false,
+        }
+    }
+
+    /// Editor pre-filled with `body`, cursor at the end.
+    #[must_use]
+    pub fn with_body(request: CommentRequest, body: &str) -> Self {
+        let lines: Vec<String> = body.split('\n').map(str::to_string).collect();
+        let cursor_row = lines.len() - 1;
+        let cursor_col = lines[cursor_row].chars().count();
+        Self {
+            lines,
+            cursor_row,
+            cursor_col,
+            scroll: 0,
+            request,
+            verdict: None,
+            abandon: false,
         }
     }
 
+    /// Whether the editor holds a comment or reply, rather than a verdict
+    /// summary or an abandon reason.
+    #[must_use]
+    pub const fn is_comment(&self) -> bool {
+        self.verdict.is_none() && !self.abandon
+    }
+
     /// Insert a character at the cursor position.
     pub fn insert_char(&mut self, c: char) {
         let line = &mut self.lines[self.cursor_row];
@@ -149,6 +607,17 @@ impl InlineEditor {
         self.cursor_col += 1;
     }
 
+    /// Insert `text` at the cursor; `\n` starts a new line.
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.chars() {
+            if c == '\n' {
+                self.newline();
+            } else {
+                self.insert_char(c);
+            }
+        }
+    }
+
     /// Insert a newline, splitting the current line.
     pub fn newline(&mut self) {
         let line = &self.lines[self.cursor_row];
@@ -344,9 +813,122 @@ pub enum ReviewFilter {
     All,
     Open,
     Closed,
+    /// Reviews changing files the user owns (needs a CODEOWNERS mapping)
+    Owned,
+}
+
+impl ReviewFilter {
+    /// Parse a saved filter's `status`: `all`, `open`, `closed` or `owned`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "all" => Some(Self::All),
+            "open" => Some(Self::Open),
+            "closed" => Some(Self::Closed),
+            "owned" => Some(Self::Owned),
+            _ => None,
+        }
+    }
+}
+
+/// Order of the review list (`o` cycles it, `review_sort` in `ui.json`
+/// keeps it)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum ReviewSort {
+    /// Workflow order of the statuses
+    #[default]
+    Status,
+    /// Most recently updated first
+    Updated,
+    /// Newest first
+    Created,
+    /// Author name, A to Z
+    Author,
+    /// Most open threads first
+    OpenThreads,
+    /// Most files first
+    Files,
+    /// Most changed lines first
+    Lines,
+}
+
+impl ReviewSort {
+    pub const ALL: [Self; 7] = [
+        Self::Status,
+        Self::Updated,
+        Self::Created,
+        Self::Author,
+        Self::OpenThreads,
+        Self::Files,
+        Self::Lines,
+    ];
+
+    #[must_use]
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Status => Self::Updated,
+            Self::Updated => Self::Created,
+            Self::Created => Self::Author,
+            Self::Author => Self::OpenThreads,
+            Self::OpenThreads => Self::Files,
+            Self::Files => Self::Lines,
+            Self::Lines => Self::Status,
+        }
+    }
+
+    /// Name used in `ui.json` and the palette's "Sort reviews by" prompt.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Updated => "updated",
+            Self::Created => "created",
+            Self::Author => "author",
+            Self::OpenThreads => "threads",
+            Self::Files => "files",
+            Self::Lines => "lines",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|sort| sort.name() == name)
+    }
+}
+
+/// A span of one screen row that sends `message` when clicked.
+#[derive(Debug, Clone)]
+pub struct ClickTarget {
+    pub y: u32,
+    pub x: Range<u32>,
+    pub message: Message,
+}
+
+/// What the mouse wheel moves over the diff (`wheel_scroll` in `ui.json`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WheelScroll {
+    /// The cursor, with the view following it
+    #[default]
+    Cursor,
+    /// Only the view; the next key press brings the cursor back into it
+    Viewport,
+}
+
+impl WheelScroll {
+    /// Parse a config name: `cursor` or `viewport`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "cursor" => Some(Self::Cursor),
+            "viewport" => Some(Self::Viewport),
+            _ => None,
+        }
+    }
 }
 
 /// Application state
+#[derive(Default)]
 #[allow(clippy::struct_excessive_bools)] // TUI state inherently needs many boolean flags
 pub struct Model {
     // === Screen state ===
@@ -360,14 +942,28 @@ pub struct Model {
     pub threads: Vec<ThreadSummary>,
     pub current_thread: Option<ThreadDetail>,
     pub all_comments: HashMap<String, Vec<Comment>>,
+    /// `db::files_etag` of the open review's files as installed
+    pub files_etag: u64,
+    /// Status history per thread id, filled in after the review loads
+    pub thread_events: HashMap<String, Vec<ThreadEvent>>,
+    /// Thread ids whose status history hasn't been loaded yet
+    pub pending_thread_events: Vec<String>,
     /// Parsed diff for the currently selected file
     pub current_diff: Option<ParsedDiff>,
     /// File content for context when no diff available
     pub current_file_content: Option<FileContent>,
     /// Cache for all files in the review stream
     pub file_cache: HashMap<String, FileCacheEntry>,
-    /// Syntax highlighter
-    pub highlighter: Highlighter,
+    /// Review being loaded on a worker thread
+    pub review_load: Option<ReviewLoad>,
+    /// Loaded files not yet parsed into `file_cache`; popped from the back,
+    /// a few per main-loop iteration
+    pub pending_files: Vec<FileData>,
+    /// Syntax highlighter, shared with `highlight_worker`
+    pub highlighter: Arc<Highlighter>,
+    /// Highlights cached files off the main thread; `None` highlights them
+    /// inline
+    pub highlight_worker: Option<HighlightWorker>,
     /// Cached highlighted lines for current diff (indexed by display line)
     pub highlighted_lines: Vec<Vec<HighlightSpan>>,
 
@@ -382,8 +978,25 @@ pub struct Model {
     pub sidebar_index: usize,
     /// Scroll offset for sidebar tree
     pub sidebar_scroll: usize,
-    /// Files whose thread children are collapsed
+    /// Files whose thread children are collapsed, and in the tree sidebar
+    /// directories (keyed by [`dir_key`]) whose contents are
     pub collapsed_files: HashSet<String>,
+    /// Prose files shown as a word diff (`W`), for the open review
+    pub word_diff_files: HashSet<String>,
+    /// Files of the open review drawn without syntax highlighting
+    pub plain_files: HashSet<String>,
+    /// Files of the open review marked viewed (`x`)
+    pub viewed_files: HashSet<String>,
+    /// Hunks and files of the open review folded (`za`, `zA`)
+    pub folds: Folds,
+    /// First key of a sequence like `za`, waiting for the second
+    pub pending_key: Option<KeyEvent>,
+    /// Context lines to read and splice into a hunk (`e`, `E`)
+    pub pending_expansion: Option<ContextExpansion>,
+    /// Files of the open review jumped to or from, most recent first
+    pub recent_files: Vec<String>,
+    /// Reading progress per review, restored when one is reopened
+    pub ui_state: UiState,
     /// Scroll offset in diff pane
     pub diff_scroll: usize,
     /// Line cursor position in diff pane (stream row index)
@@ -392,32 +1005,112 @@ pub struct Model {
     pub expanded_thread: Option<String>,
     /// Review list filter
     pub filter: ReviewFilter,
+    /// Review list order
+    pub review_sort: ReviewSort,
     /// Show sidebar in overlay mode
     pub sidebar_visible: bool,
     /// Diff view mode (unified or side-by-side)
     pub diff_view_mode: DiffViewMode,
+    /// Files of the open review drawn in the other mode (`ctrl+v`); `v`
+    /// leaves them be
+    pub diff_view_overrides: HashMap<String, DiffViewMode>,
     /// Wrap diff lines when enabled
     pub diff_wrap: bool,
+    /// Show `^M` after diff lines that ended in CRLF
+    pub show_cr: bool,
+    /// Browse-only session: commenting and status changes are disabled
+    pub read_only: bool,
+    /// Syntax highlighting; off with `--no-syntax` or `syntax: false`
+    pub syntax: bool,
     /// Pending editor launch request
     pub pending_editor_request: Option<EditorRequest>,
+    /// Pending blame lookup; main shows the result as a flash
+    pub pending_blame_request: Option<BlameRequest>,
+    /// Show who last changed each line beside unified diff lines (`B`)
+    pub blame_gutter: bool,
+    /// Blame of each file shown with the gutter on, by path; main loads the
+    /// current file's, empty if git can't blame it
+    pub blame: HashMap<String, Vec<vcs::BlameLine>>,
+    /// Pending suggestion apply or undo; main runs it through `vcs`
+    pub pending_patch: Option<PatchRequest>,
+    /// Last suggestion applied to the working copy, for `U`
+    pub applied_suggestion: Option<SuggestionPatch>,
+    /// Latest `review_command` run, kept after it finishes for its output
+    /// and header badge
+    pub command_run: Option<CommandRun>,
+    /// Start `review_command` for the open review (main does it)
+    pub pending_command_run: bool,
+    /// Input latency spans; main times each event
+    pub input_profile: InputProfile,
+    /// First line shown in the input latency log
+    pub input_log_scroll: usize,
+    /// Markdown export of the open review requested; main writes it
+    pub pending_export: bool,
+    /// Entries of the open `.` action menu
+    pub action_menu: Vec<MenuAction>,
+    pub action_menu_selection: usize,
+    /// Selected row of the threads panel, an index into `panel_threads`
+    pub threads_panel_selection: usize,
+    /// Text to put on the system clipboard after the next frame
+    pub pending_clipboard: Option<String>,
     /// Pending comment-via-$EDITOR request (Shift+A)
     pub pending_comment_request: Option<CommentRequest>,
     /// Inline comment editor state (a)
     pub inline_editor: Option<InlineEditor>,
+    /// When the open editor's text was last kept as a draft
+    pub draft_stashed_at: Option<Instant>,
     /// Comment ready for persistence (from inline editor submit)
     pub pending_comment_submission: Option<PendingCommentSubmission>,
+    /// Resolve or reopen ready for persistence (R on an expanded thread)
+    pub pending_status_change: Option<ThreadStatusChange>,
+    /// Undo/redo history of the open review (u / Ctrl+r)
+    pub action_log: ActionLog,
+    /// Verdict ready for persistence ("Finish review")
+    pub pending_verdict: Option<VerdictSubmission>,
+    /// Abandon or restore of the open review, ready for persistence
+    pub pending_review_status: Option<ReviewStatusChange>,
+    /// Auto-refresh timing; `None` when disabled
+    pub auto_refresh: Option<RefreshSchedule>,
+    /// A background poll is due; main re-fetches after the next frame
+    pub pending_refresh: bool,
+    /// The watcher saw crit's database or the working copy change; polls
+    /// once nothing holds it back, auto-refresh or not
+    pub external_change: bool,
+    /// When the latest poll started (drives the header spinner)
+    pub refresh_started: Option<Instant>,
+    /// Whether the terminal window has focus; polling pauses while it doesn't
+    pub terminal_focused: bool,
+    /// Dim the whole UI while the terminal is unfocused
+    pub dim_unfocused: bool,
+    /// Tint the focused pane's title bar
+    pub focus_tint: bool,
+    /// Mark the diff cursor in the gutter instead of highlighting its row
+    /// (see `redraw::LinkMonitor`)
+    pub lean_cursor: bool,
 
     // === Command Palette ===
     pub command_palette_input: String,
     pub command_palette_selection: usize,
     pub command_palette_commands: Vec<CommandSpec>,
     pub command_palette_mode: PaletteMode,
+    /// Commands run from the palette, most recent first
+    pub palette_recent: Vec<CommandId>,
 
     // === Visual Selection ===
     /// Whether visual line selection mode is active (Shift+V)
     pub visual_mode: bool,
     /// Anchor stream row where visual mode was entered
     pub visual_anchor: usize,
+    /// Cursor stop a left-button press in the diff pane landed on, while
+    /// the button is held; dragging selects from it
+    pub diff_drag_anchor: Option<usize>,
+    /// The sidebar divider is being dragged
+    pub sidebar_resizing: bool,
+    /// The mouse is over the sidebar divider
+    pub divider_hover: bool,
+    /// Column the cursor is in while in side-by-side mode (←/→). `y` copies
+    /// from it and new comments are numbered on its side.
+    pub sbs_side: ThreadSide,
 
     // === Commenting State ===
     pub comment_input: String,
@@ -427,11 +1120,15 @@ pub struct Model {
     pub width: u16,
     pub height: u16,
     pub layout_mode: LayoutMode,
+    /// Key bindings, the defaults with the config's `keys` applied
+    pub keymap: Keymap,
 
     // === Theme ===
     pub theme: Theme,
     /// Theme name before opening the picker (for revert on Esc)
     pub pre_palette_theme: Option<String>,
+    /// Labels, colors, order and openness of review statuses
+    pub statuses: StatusWorkflow,
     pub config: UiConfig,
 
     // === Render-computed data ===
@@ -439,17 +1136,80 @@ pub struct Model {
     pub thread_positions: RefCell<HashMap<String, usize>>,
     /// Total stream rows from the last render pass (for cursor clamping)
     pub max_stream_row: Cell<usize>,
+    /// Screen row of the first sidebar item, below the review header, from
+    /// the last render pass
+    pub sidebar_list_top: Cell<u32>,
+    /// Clickable column titles and status filter of the review list, from
+    /// the last render pass
+    pub list_click_targets: RefCell<Vec<ClickTarget>>,
     /// Diff line mapping captured during rendering: `stream_row` → new-side line number.
     /// Populated for every diff line (including all wrapped rows).
     pub line_map: RefCell<HashMap<usize, i64>>,
+    /// Like `line_map` but for old-side line numbers; only the side-by-side
+    /// renderer records it.
+    pub old_line_map: RefCell<HashMap<usize, i64>>,
     /// Sorted list of stream rows that are valid cursor stops (one per logical item).
     /// Populated during rendering; used by cursor navigation to skip wrapped/padding rows.
     pub cursor_stops: RefCell<Vec<usize>>,
+    /// Stream rows of hunk headers, in order. Populated during rendering.
+    pub hunk_rows: RefCell<Vec<usize>>,
+    /// Stream rows where a run of added/removed lines starts, in order.
+    /// Populated during rendering.
+    pub change_rows: RefCell<Vec<usize>>,
+    /// Stream rows of each comment (author line through its last body line),
+    /// captured during rendering.
+    pub comment_positions: RefCell<HashMap<String, Range<usize>>>,
+    /// Stream rows of each thread's comment block, captured during rendering.
+    pub thread_rows: RefCell<HashMap<String, Range<usize>>>,
+    /// Matches of the `/` search, in stream order, captured during rendering.
+    pub search_hits: RefCell<Vec<SearchHit>>,
+    /// Per-file layout rows and render records reused between frames
+    pub stream_cache: RefCell<StreamCache>,
+    /// Comment highlighted after a jump, and when the highlight started
+    pub comment_flash: Option<(String, Instant)>,
+
+    // === Inbox ===
+    /// Set while the inbox is open, or a review was opened from it (`Back`
+    /// returns there)
+    pub inbox: Option<Inbox>,
+    /// Who "me" is for the inbox (`CritClient::current_user`)
+    pub current_user: String,
+
+    // === Comparison ===
+    /// Reviews marked with Space in the list, oldest first (at most two)
+    pub compare_marks: Vec<String>,
+    /// Set while the comparison screen is open
+    pub comparison: Option<Comparison>,
+
+    // === Review preview ===
+    /// Preview pane on the review list (`p`)
+    pub preview_open: bool,
+    /// Loaded previews by review id
+    pub previews: HashMap<String, ReviewPreview>,
+
+    // === Code ownership and annotations ===
+    /// CODEOWNERS mapping, when the repo has one or `codeowners` is configured
+    pub codeowners: Option<Codeowners>,
+    /// Lint/CI annotations from `--annotations`
+    pub annotations: Option<Annotations>,
+    /// What the scan found in each scanned review's changed files
+    pub file_scans: HashMap<String, FileScan>,
+    /// Reviews whose changed files haven't been scanned yet, next last
+    pub pending_file_scan: Vec<String>,
 
     // === Review list search ===
     pub search_input: String,
     pub search_active: bool,
 
+    // === Review list filter bar (`f`) ===
+    pub filter_bar_input: String,
+    pub filter_bar_active: bool,
+    /// `filter_bar_input` parsed; applied by `filtered_reviews`
+    pub review_query: ReviewQuery,
+
+    // === Diff search (`/` on the review) ===
+    pub diff_search: Option<DiffSearch>,
+
     // === Repo path for display ===
     pub repo_path: Option<String>,
 
@@ -473,88 +1233,124 @@ pub struct Model {
     pub pending_review: Option<String>,
     pub pending_file: Option<String>,
     pub pending_thread: Option<String>,
+    /// New-side line to put the cursor on in `pending_file` (`--line`)
+    pub pending_line: Option<u32>,
 }
 
 impl Model {
-    /// Create a new model
+    /// Create a new model. Fields not set here start at their `Default`.
     #[must_use]
     pub fn new(width: u16, height: u16, config: UiConfig) -> Self {
         Self {
-            screen: Screen::default(),
-            focus: Focus::default(),
-            previous_focus: None,
-            reviews: Vec::new(),
-            current_review: None,
-            threads: Vec::new(),
-            current_thread: None,
-            all_comments: HashMap::new(),
-            current_diff: None,
-            current_file_content: None,
-            file_cache: HashMap::new(),
-            highlighter: Highlighter::new(),
-            highlighted_lines: Vec::new(),
-            list_index: 0,
-            list_scroll: 0,
-            file_index: 0,
-            sidebar_index: 0,
-            sidebar_scroll: 0,
-            collapsed_files: HashSet::new(),
-            diff_scroll: 0,
-            diff_cursor: 0,
-            expanded_thread: None,
-            filter: ReviewFilter::default(),
+            review_sort: config
+                .review_sort
+                .as_deref()
+                .and_then(ReviewSort::from_name)
+                .unwrap_or_default(),
             sidebar_visible: true,
-            diff_view_mode: DiffViewMode::default(),
             diff_wrap: true,
-            pending_editor_request: None,
-            pending_comment_request: None,
-            inline_editor: None,
-            pending_comment_submission: None,
-            command_palette_input: String::new(),
-            command_palette_selection: 0,
-            command_palette_commands: Vec::new(),
-            command_palette_mode: PaletteMode::default(),
-            visual_mode: false,
-            visual_anchor: 0,
-            comment_input: String::new(),
-            comment_target_line: None,
+            read_only: config.read_only.unwrap_or(false),
+            syntax: config.syntax.unwrap_or(true),
+            auto_refresh: config
+                .auto_refresh_secs
+                .filter(|&secs| secs > 0)
+                .map(|secs| {
+                    RefreshSchedule::new(
+                        Duration::from_secs(secs),
+                        crate::clock::now(),
+                        // A replay jitters the same way every run
+                        if crate::clock::is_virtual() {
+                            1
+                        } else {
+                            u64::from(std::process::id())
+                        },
+                    )
+                }),
+            terminal_focused: true,
+            dim_unfocused: config.dim_unfocused.unwrap_or(false),
+            focus_tint: config.focus_tint.unwrap_or(true),
             width,
             height,
             layout_mode: LayoutMode::from_width(width),
-            theme: Theme::default(),
-            pre_palette_theme: None,
+            statuses: StatusWorkflow::from_config(&config.statuses),
             config,
-            thread_positions: RefCell::new(HashMap::new()),
-            max_stream_row: Cell::new(0),
-            line_map: RefCell::new(HashMap::new()),
-            cursor_stops: RefCell::new(Vec::new()),
-            search_input: String::new(),
-            search_active: false,
-            repo_path: None,
             editor_name: std::env::var("EDITOR")
                 .or_else(|_| std::env::var("VISUAL"))
                 .ok()
                 .and_then(|e| e.rsplit('/').next().map(String::from))
-                .unwrap_or_else(|| "Editor".to_string()),
-            flash_message: None,
-            should_quit: false,
+                .unwrap_or_else(|| tr(Text::DefaultEditorName).to_string()),
             needs_redraw: true,
-            last_list_scroll: None,
-            last_sidebar_scroll: None,
-            pending_review: None,
-            pending_file: None,
-            pending_thread: None,
+            ..Self::default()
         }
     }
 
     /// Get filtered reviews based on current filter and search query
     #[must_use]
     pub fn filtered_reviews(&self) -> Vec<&ReviewSummary> {
-        let status_filtered: Vec<&ReviewSummary> = match self.filter {
+        let open = |r: &&ReviewSummary| self.statuses.is_open(&r.status);
+        let mut status_filtered: Vec<&ReviewSummary> = match self.filter {
             ReviewFilter::All => self.reviews.iter().collect(),
-            ReviewFilter::Open => self.reviews.iter().filter(|r| r.status == "open").collect(),
-            ReviewFilter::Closed => self.reviews.iter().filter(|r| r.status != "open").collect(),
+            ReviewFilter::Open => self.reviews.iter().filter(open).collect(),
+            ReviewFilter::Closed => self.reviews.iter().filter(|r| !open(r)).collect(),
+            ReviewFilter::Owned => self
+                .reviews
+                .iter()
+                .filter(|r| self.owned_file_count(&r.review_id) > 0)
+                .collect(),
         };
+        status_filtered.retain(|r| {
+            let paths = self
+                .file_scans
+                .get(&r.review_id)
+                .map(|s| s.paths.as_slice());
+            self.review_query.matches(r, &self.statuses, paths)
+        });
+        status_filtered.sort_by_key(|r| self.statuses.order(&r.status));
+        match self.review_sort {
+            ReviewSort::Status => {}
+            ReviewSort::Updated => status_filtered
+                .sort_by(|a, b| self.review_updated_at(b).cmp(&self.review_updated_at(a))),
+            ReviewSort::Created => status_filtered.sort_by(|a, b| {
+                // Reviews without a creation time go last
+                b.created_at
+                    .is_some()
+                    .cmp(&a.created_at.is_some())
+                    .then_with(|| b.created_at.cmp(&a.created_at))
+            }),
+            ReviewSort::Author => status_filtered.sort_by_cached_key(|r| r.author.to_lowercase()),
+            ReviewSort::OpenThreads => status_filtered.sort_by_key(|r| {
+                (
+                    std::cmp::Reverse(r.open_thread_count),
+                    std::cmp::Reverse(r.thread_count),
+                )
+            }),
+            // Reviews not scanned yet go last
+            ReviewSort::Files => status_filtered
+                .sort_by_key(|r| std::cmp::Reverse(self.review_stats(r).map(|stats| stats.files))),
+            ReviewSort::Lines => status_filtered.sort_by_key(|r| {
+                std::cmp::Reverse(
+                    self.review_stats(r)
+                        .map(|stats| stats.added + stats.removed),
+                )
+            }),
+        }
+        if self.review_sort_reversed() {
+            status_filtered.reverse();
+        }
+        if self.group_reviews_by_status() {
+            // Statuses sharing an `order` (all of them by default) keep
+            // crit's order among themselves
+            let mut first_seen: HashMap<&str, usize> = HashMap::new();
+            for (index, review) in self.reviews.iter().enumerate() {
+                first_seen.entry(review.status.as_str()).or_insert(index);
+            }
+            status_filtered.sort_by_key(|r| {
+                (
+                    self.statuses.order(&r.status),
+                    first_seen[r.status.as_str()],
+                )
+            });
+        }
         if self.search_input.is_empty() {
             return status_filtered;
         }
@@ -569,6 +1365,128 @@ impl Model {
             .collect()
     }
 
+    /// Changed files in `review_id` the user owns; 0 until it's been scanned.
+    #[must_use]
+    pub fn owned_file_count(&self, review_id: &str) -> usize {
+        self.file_scans.get(review_id).map_or(0, |scan| scan.owned)
+    }
+
+    /// Changed files and lines in `review`, from crit or else the scan.
+    #[must_use]
+    pub fn review_stats(&self, review: &ReviewSummary) -> Option<DiffStats> {
+        review.diff_stats.or_else(|| {
+            self.file_scans
+                .get(&review.review_id)
+                .and_then(|scan| scan.stats)
+        })
+    }
+
+    /// When `review` last changed, from crit or else the scan.
+    #[must_use]
+    pub fn review_updated_at<'a>(&'a self, review: &'a ReviewSummary) -> Option<&'a str> {
+        review.updated_at.as_deref().or_else(|| {
+            self.file_scans
+                .get(&review.review_id)
+                .and_then(|scan| scan.updated_at.as_deref())
+        })
+    }
+
+    /// Annotations on `review_id`'s changed files; none until it's been
+    /// scanned.
+    #[must_use]
+    pub fn review_annotations(&self, review_id: &str) -> SeverityCounts {
+        self.file_scans
+            .get(review_id)
+            .map(|scan| scan.annotations)
+            .unwrap_or_default()
+    }
+
+    /// Annotations on one file of the open review.
+    #[must_use]
+    pub fn file_annotations(&self, path: &str) -> SeverityCounts {
+        self.annotations
+            .as_ref()
+            .map(|annotations| annotations.counts_for(path))
+            .unwrap_or_default()
+    }
+
+    /// Queue reviews that haven't been scanned yet: open ones for a
+    /// CODEOWNERS mapping or annotations, every review while a `path:`
+    /// filter needs their paths, and any crit listed without an update time
+    /// or diff stats.
+    pub fn queue_file_scan(&mut self) {
+        let all = self.review_query.needs_paths();
+        let open = self.codeowners.is_some() || self.annotations.is_some();
+        let queued: Vec<String> = self
+            .reviews
+            .iter()
+            .rev()
+            .filter(|r| {
+                all || (open && self.statuses.is_open(&r.status))
+                    || r.updated_at.is_none()
+                    || r.diff_stats.is_none()
+            })
+            .filter(|r| !self.file_scans.contains_key(&r.review_id))
+            .filter(|r| !self.pending_file_scan.contains(&r.review_id))
+            .map(|r| r.review_id.clone())
+            .collect();
+        self.pending_file_scan.extend(queued);
+    }
+
+    /// Install freshly loaded review data and queue its threads' status
+    /// history and its files. Cached files that are still in the review stay
+    /// on screen until their new entries replace them.
+    pub fn install_review_data(&mut self, data: ReviewData) {
+        self.record_file_scan(&data);
+        self.files_etag = files_etag(&data.files);
+        self.current_review = Some(data.detail);
+        // Popped from the back, so reverse to load in stream order
+        self.pending_thread_events = data
+            .threads
+            .iter()
+            .rev()
+            .map(|t| t.thread_id.clone())
+            .collect();
+        self.thread_events.clear();
+        self.threads = data.threads;
+        self.all_comments = data.comments;
+
+        let files: Vec<FileData> = data
+            .files
+            .into_iter()
+            .filter(|f| !f.path.starts_with(".crit/"))
+            .collect();
+        self.file_cache
+            .retain(|path, _| files.iter().any(|f| &f.path == path));
+        self.pending_files = files.into_iter().rev().collect();
+        self.sync_active_file_cache();
+        self.needs_redraw = true;
+    }
+
+    /// Count the files `data` changes that the user owns, the annotations on
+    /// them and the lines they change.
+    pub fn record_file_scan(&mut self, data: &ReviewData) {
+        let changed = data.files.iter().filter(|file| file.diff.is_some());
+        let mut scan = FileScan {
+            paths: changed.clone().map(|file| file.path.clone()).collect(),
+            stats: Some(diff_stats(&data.files)),
+            updated_at: Some(data.updated_at().to_string()),
+            ..FileScan::default()
+        };
+        if let Some(codeowners) = &self.codeowners {
+            let mut names = self.config.owner_names.clone();
+            names.push(self.current_user.clone());
+            scan.owned = changed
+                .clone()
+                .filter(|file| codeowners.is_owned_by(&file.path, &names))
+                .count();
+        }
+        if let Some(annotations) = &self.annotations {
+            scan.annotations = annotations.counts_for_all(changed.map(|file| file.path.as_str()));
+        }
+        self.file_scans.insert(data.detail.review_id.clone(), scan);
+    }
+
     /// Get unique files from threads and the diff file cache for the sidebar.
     #[must_use]
     pub fn files_with_threads(&self) -> Vec<FileEntry> {
@@ -576,8 +1494,11 @@ impl Model {
 
         let mut files: HashMap<String, (usize, usize)> = HashMap::new();
 
-        for thread in &self.threads {
+        for thread in self.threads.iter().filter(|t| !t.is_review_thread()) {
             let entry = files.entry(thread.file_path.clone()).or_insert((0, 0));
+            if self.is_muted(&thread.thread_id) {
+                continue;
+            }
             if thread.status == "open" {
                 entry.0 += 1;
             } else {
@@ -585,8 +1506,10 @@ impl Model {
             }
         }
 
-        // Include cached files that have diffs but no threads.
-        for path in self.file_cache.keys() {
+        // Include cached files that have diffs but no threads, and ones
+        // still loading.
+        let loading = self.pending_files.iter().map(|f| &f.path);
+        for path in self.file_cache.keys().chain(loading) {
             files.entry(path.clone()).or_insert((0, 0));
         }
 
@@ -617,24 +1540,88 @@ impl Model {
             .collect()
     }
 
-    /// Get threads that are visible in the current diff (all threads for the file)
+    /// Threads `n`/`p` step through: the current file's, minus muted ones
     #[must_use]
     pub fn visible_threads_for_current_file(&self) -> Vec<&ThreadSummary> {
-        self.threads_for_current_file()
+        let mut threads = self.threads_for_current_file();
+        threads.retain(|t| !self.is_muted(&t.thread_id));
+        threads
     }
 
-    /// Build a flat list of sidebar items: files with their threads as children
+    /// Every thread in the review for the threads panel: review-level ones
+    /// first, then by file path and line
+    #[must_use]
+    pub fn panel_threads(&self) -> Vec<&ThreadSummary> {
+        let mut threads: Vec<&ThreadSummary> = self.threads.iter().collect();
+        threads.sort_by(|a, b| {
+            (&a.file_path, a.selection_start, &a.thread_id).cmp(&(
+                &b.file_path,
+                b.selection_start,
+                &b.thread_id,
+            ))
+        });
+        threads
+    }
+
+    #[must_use]
+    pub fn is_muted(&self, thread_id: &str) -> bool {
+        self.config.muted_threads.contains(thread_id)
+    }
+
+    /// Build a flat list of sidebar items: files with their threads as
+    /// children, under their directories in tree mode
     #[must_use]
     pub fn sidebar_items(&self) -> Vec<SidebarItem> {
         let files = self.files_with_threads();
+        let tree = self.sidebar_tree();
         let mut items = Vec::new();
+        // Tree mode: directories the current file is in, and whether each is
+        // collapsed. Files are sorted by path, so a directory's are adjacent.
+        let mut open_dirs: Vec<(&str, bool)> = Vec::new();
 
         for (file_idx, file) in files.iter().enumerate() {
+            let dirs = if tree {
+                parent_dirs(&file.path)
+            } else {
+                Vec::new()
+            };
+            let shared = open_dirs
+                .iter()
+                .zip(&dirs)
+                .take_while(|((open, _), dir)| open == *dir)
+                .count();
+            open_dirs.truncate(shared);
+            for &dir in &dirs[shared..] {
+                let collapsed = self.collapsed_files.contains(&dir_key(dir));
+                if !open_dirs.iter().any(|&(_, hidden)| hidden) {
+                    let prefix = dir_key(dir);
+                    let inside: Vec<&FileEntry> = files[file_idx..]
+                        .iter()
+                        .take_while(|f| f.path.starts_with(&prefix))
+                        .collect();
+                    items.push(SidebarItem::Directory {
+                        path: dir.to_string(),
+                        depth: open_dirs.len(),
+                        open_threads: inside.iter().map(|f| f.open_threads).sum(),
+                        resolved_threads: inside.iter().map(|f| f.resolved_threads).sum(),
+                        collapsed,
+                        files: file_idx..file_idx + inside.len(),
+                    });
+                }
+                open_dirs.push((dir, collapsed));
+            }
+            if open_dirs.iter().any(|&(_, collapsed)| collapsed) {
+                continue;
+            }
+
+            let depth = dirs.len();
             let collapsed = self.collapsed_files.contains(&file.path);
             items.push(SidebarItem::File {
                 entry: file.clone(),
                 file_idx,
                 collapsed,
+                viewed: self.viewed_files.contains(&file.path),
+                depth,
             });
             if !collapsed {
                 // Add threads belonging to this file, sorted by their
@@ -655,7 +1642,10 @@ impl Model {
                         thread_id: thread.thread_id.clone(),
                         status: thread.status.clone(),
                         comment_count: thread.comment_count,
+                        muted: self.is_muted(&thread.thread_id),
+                        file_level: thread.is_file_thread(),
                         file_idx,
+                        depth: depth + 1,
                     });
                 }
             }
@@ -664,6 +1654,119 @@ impl Model {
         items
     }
 
+    /// Sidebar position of file `file_idx`, or of the collapsed directory
+    /// hiding it.
+    #[must_use]
+    pub fn sidebar_position_of_file(&self, file_idx: usize) -> Option<usize> {
+        self.sidebar_items().iter().position(|item| match item {
+            SidebarItem::File { file_idx: idx, .. } => *idx == file_idx,
+            SidebarItem::Directory {
+                files, collapsed, ..
+            } => *collapsed && files.contains(&file_idx),
+            SidebarItem::Thread { .. } => false,
+        })
+    }
+
+    /// What the mouse wheel moves over the diff (`wheel_scroll`).
+    #[must_use]
+    pub fn wheel_scroll(&self) -> WheelScroll {
+        self.config
+            .wheel_scroll
+            .as_deref()
+            .and_then(WheelScroll::from_name)
+            .unwrap_or_default()
+    }
+
+    /// Whether the review list runs in the opposite order to
+    /// `review_sort` (`review_sort_reversed`).
+    #[must_use]
+    pub fn review_sort_reversed(&self) -> bool {
+        self.config.review_sort_reversed.unwrap_or(false)
+    }
+
+    /// Whether the review list keeps statuses together under any sort
+    /// (`group_reviews_by_status`).
+    #[must_use]
+    pub fn group_reviews_by_status(&self) -> bool {
+        self.config.group_reviews_by_status.unwrap_or(false)
+    }
+
+    /// Whether the sidebar groups files under directories (`sidebar_tree`).
+    #[must_use]
+    pub fn sidebar_tree(&self) -> bool {
+        self.config.sidebar_tree.unwrap_or(false)
+    }
+
+    /// Sidebar width: the configured one, kept between
+    /// [`MIN_SIDEBAR_WIDTH`] and half the terminal, else the layout's.
+    #[must_use]
+    pub fn sidebar_width(&self) -> u16 {
+        match (self.layout_mode, self.config.sidebar_width) {
+            (LayoutMode::Single, _) | (_, None) => self.layout_mode.sidebar_width(),
+            (_, Some(width)) => {
+                width.clamp(MIN_SIDEBAR_WIDTH, (self.width / 2).max(MIN_SIDEBAR_WIDTH))
+            }
+        }
+    }
+
+    /// Lines of context around threads outside the diff
+    #[must_use]
+    pub fn context_lines(&self) -> i64 {
+        self.config
+            .context_lines
+            .map_or(crate::layout::CONTEXT_LINES, i64::from)
+    }
+
+    /// Width of the diff pane's content: the pane less `DIFF_MARGIN` on
+    /// each side.
+    #[must_use]
+    pub fn diff_content_width(&self) -> u32 {
+        /// Must match `DIFF_MARGIN` in diff.rs.
+        const DIFF_MARGIN: u32 = 2;
+        let total_width = u32::from(self.width);
+        let pane_width = match self.layout_mode {
+            LayoutMode::Full | LayoutMode::Compact | LayoutMode::Overlay
+                if self.sidebar_visible =>
+            {
+                total_width.saturating_sub(u32::from(self.sidebar_width()))
+            }
+            _ => total_width,
+        };
+        pane_width.saturating_sub(DIFF_MARGIN * 2)
+    }
+
+    /// Stream layout of the open review as it's drawn now: where each file
+    /// starts. `view::compute_stream_regions` adds the rows of lines and
+    /// threads.
+    #[must_use]
+    pub fn stream_layout(&self) -> StreamLayout {
+        let files = self.files_with_threads();
+        let description = self
+            .current_review
+            .as_ref()
+            .and_then(|r| r.description.as_deref());
+        self.stream_cache.borrow_mut().layout(&StreamLayoutParams {
+            files: &files,
+            file_cache: &self.file_cache,
+            threads: &self.threads,
+            all_comments: &self.all_comments,
+            thread_events: &self.thread_events,
+            view_mode: self.diff_view_mode,
+            view_mode_overrides: &self.diff_view_overrides,
+            wrap: self.diff_wrap,
+            content_width: self.diff_content_width(),
+            description,
+            votes: self.review_votes(),
+            abandoned: self.abandoned_review(),
+            muted_threads: &self.config.muted_threads,
+            word_diff_files: &self.word_diff_files,
+            viewed_files: &self.viewed_files,
+            folds: &self.folds,
+            context_lines: self.context_lines(),
+            blame_gutter: self.blame_gutter,
+        })
+    }
+
     /// Handle terminal resize
     pub const fn resize(&mut self, width: u16, height: u16) {
         self.width = width;
@@ -671,25 +1774,847 @@ impl Model {
         self.layout_mode = LayoutMode::from_width(width);
     }
 
+    /// Column the preview pane starts at, while it's open and the terminal
+    /// is wide enough for it.
+    #[must_use]
+    pub const fn preview_x(&self) -> Option<u16> {
+        if self.preview_open && self.width as u32 >= PREVIEW_MIN_WIDTH {
+            Some(self.width / 2)
+        } else {
+            None
+        }
+    }
+
+    /// The highlighted review, when the preview pane shows it and it hasn't
+    /// been loaded.
+    #[must_use]
+    pub fn preview_to_load(&self) -> Option<String> {
+        if self.screen != Screen::ReviewList || self.preview_x().is_none() {
+            return None;
+        }
+        self.filtered_reviews()
+            .get(self.list_index)
+            .filter(|review| !self.previews.contains_key(&review.review_id))
+            .map(|review| review.review_id.clone())
+    }
+
     /// Get the visible height for the review list (accounting for chrome)
     #[must_use]
-    pub const fn list_visible_height(&self) -> usize {
-        // Account for header block (5) + search bar (2) + help bar (2)
+    pub fn list_visible_height(&self) -> usize {
+        // Account for header block + search bar (2) + help bar (2)
         // Each item is 2 lines tall
-        let available = self.height.saturating_sub(9) as usize;
+        let chrome = crate::layout::screen_header_height() + 4;
+        let available = u32::from(self.height).saturating_sub(chrome) as usize;
         available / 2
     }
 
-    /// Sync current file fields from the file cache
-    pub fn sync_active_file_cache(&mut self) {
-        let files = self.files_with_threads();
-        let Some(file) = files.get(self.file_index) else {
-            self.current_diff = None;
-            self.current_file_content = None;
-            self.highlighted_lines.clear();
-            return;
+    /// Queue a comment for submission and show it right away.
+    ///
+    /// New threads and comments get placeholder ids until
+    /// [`Self::reconcile_comment`] or [`Self::rollback_comment`] runs.
+    pub fn queue_comment_submission(&mut self, request: CommentRequest, body: String) {
+        let thread_id = if let Some(thread_id) = &request.thread_id {
+            thread_id.clone()
+        } else {
+            self.threads.push(ThreadSummary {
+                thread_id: PENDING_THREAD_ID.to_string(),
+                file_path: request.file_path.clone(),
+                selection_start: request.start_line,
+                selection_end: request.end_line.filter(|&end| end != request.start_line),
+                side: Some(request.side),
+                status: "open".to_string(),
+                comment_count: 0,
+            });
+            self.adjust_review_thread_counts(1);
+            PENDING_THREAD_ID.to_string()
         };
-
+        if let Some(thread) = self.threads.iter_mut().find(|t| t.thread_id == thread_id) {
+            thread.comment_count += 1;
+        }
+        self.all_comments
+            .entry(thread_id)
+            .or_default()
+            .push(Comment {
+                comment_id: PENDING_COMMENT_ID.to_string(),
+                author: tr(Text::PendingAuthor).to_string(),
+                body: body.clone(),
+                created_at: String::new(),
+            });
+        let undo_window = self
+            .config
+            .comment_undo_secs
+            .map_or(COMMENT_UNDO_WINDOW, Duration::from_secs);
+        self.pending_comment_submission = Some(PendingCommentSubmission {
+            request,
+            body,
+            send_at: crate::clock::now() + undo_window,
+        });
+        self.needs_redraw = true;
+    }
+
+    /// Open the inline editor on `request`, with the draft left for its
+    /// target unless that's the comment waiting to be sent.
+    pub fn open_inline_editor(&mut self, request: CommentRequest) {
+        let key = request.draft_key();
+        let sending = self.pending_comment_submission.as_ref().is_some_and(|s| {
+            s.request.review_id == request.review_id && s.request.draft_key() == key
+        });
+        let draft = self
+            .ui_state
+            .draft(&request.review_id, &key)
+            .filter(|_| !sending)
+            .map(str::to_string);
+        self.inline_editor = Some(match draft {
+            Some(body) => {
+                let mut request = request;
+                request
+                    .notice
+                    .get_or_insert_with(|| tr(Text::DraftRestored).to_string());
+                InlineEditor::with_body(request, &body)
+            }
+            None => InlineEditor::new(request),
+        });
+        self.focus = Focus::Commenting;
+        self.needs_redraw = true;
+    }
+
+    /// Keep what the inline editor holds as its target's draft, saving the
+    /// state if it changed. A verdict is kept as the review's draft verdict,
+    /// even without a summary.
+    pub fn stash_draft(&mut self) {
+        self.draft_stashed_at = Some(crate::clock::now());
+        let Some(editor) = self.inline_editor.as_ref() else {
+            return;
+        };
+        // An abandon reason is short and rarely worth keeping
+        if editor.abandon {
+            return;
+        }
+        let request = &editor.request;
+        let changed = match editor.verdict {
+            Some(verdict) => self.ui_state.set_verdict_draft(
+                &request.review_id,
+                Some(VerdictDraft {
+                    verdict,
+                    summary: editor.body(),
+                }),
+            ),
+            None => {
+                self.ui_state
+                    .set_draft(&request.review_id, &request.draft_key(), &editor.body())
+            }
+        };
+        if changed {
+            let _ = self.ui_state.save();
+        }
+    }
+
+    /// Open the inline editor on the summary for `verdict`, with the
+    /// review's draft summary if one was left.
+    pub fn open_verdict_editor(&mut self, mut request: CommentRequest, verdict: Verdict) {
+        let summary = self
+            .ui_state
+            .verdict_drafts
+            .get(&request.review_id)
+            .map(|draft| draft.summary.clone())
+            .filter(|summary| !summary.is_empty());
+        let mut editor = match summary {
+            Some(summary) => {
+                request.notice = Some(tr(Text::DraftRestored).to_string());
+                InlineEditor::with_body(request, &summary)
+            }
+            None => InlineEditor::new(request),
+        };
+        editor.verdict = Some(verdict);
+        self.inline_editor = Some(editor);
+        self.focus = Focus::Commenting;
+        self.needs_redraw = true;
+    }
+
+    /// Open the inline editor on the reason for abandoning the review.
+    pub fn open_abandon_editor(&mut self, request: CommentRequest) {
+        let mut editor = InlineEditor::new(request);
+        editor.abandon = true;
+        self.inline_editor = Some(editor);
+        self.focus = Focus::Commenting;
+        self.needs_redraw = true;
+    }
+
+    /// The open review, if it's been abandoned.
+    #[must_use]
+    pub fn abandoned_review(&self) -> Option<&ReviewDetail> {
+        self.current_review
+            .as_ref()
+            .filter(|review| review.status == "abandoned")
+    }
+
+    /// The open review's unsent verdict, if one was left.
+    #[must_use]
+    pub fn draft_verdict(&self) -> Option<&VerdictDraft> {
+        let review = self.current_review.as_ref()?;
+        self.ui_state.verdict_drafts.get(&review.review_id)
+    }
+
+    /// Forget the draft verdict for a review once it's been sent.
+    pub fn discard_verdict_draft(&mut self, review_id: &str) {
+        if self.ui_state.set_verdict_draft(review_id, None) {
+            let _ = self.ui_state.save();
+        }
+    }
+
+    /// Forget the draft for a comment that's been sent.
+    pub fn discard_draft(&mut self, request: &CommentRequest) {
+        if self
+            .ui_state
+            .set_draft(&request.review_id, &request.draft_key(), "")
+        {
+            let _ = self.ui_state.save();
+        }
+    }
+
+    /// Take back a comment still waiting to be sent and reopen the editor
+    /// with it.
+    pub fn cancel_comment_submission(&mut self) {
+        let Some(submission) = self.pending_comment_submission.take() else {
+            return;
+        };
+        self.rollback_comment(&submission);
+        self.inline_editor = Some(InlineEditor::with_body(
+            submission.request,
+            &submission.body,
+        ));
+        self.focus = Focus::Commenting;
+        self.needs_redraw = true;
+    }
+
+    /// Swap the optimistic placeholders for the ids crit assigned.
+    pub fn reconcile_comment(&mut self, created: CreatedComment) {
+        if let Some(thread) = self
+            .threads
+            .iter_mut()
+            .find(|t| t.thread_id == PENDING_THREAD_ID)
+        {
+            thread.thread_id.clone_from(&created.thread_id);
+            if let Some(comments) = self.all_comments.remove(PENDING_THREAD_ID) {
+                self.all_comments
+                    .insert(created.thread_id.clone(), comments);
+            }
+            let mut positions = self.thread_positions.borrow_mut();
+            if let Some(pos) = positions.remove(PENDING_THREAD_ID) {
+                positions.insert(created.thread_id.clone(), pos);
+            }
+            if self.expanded_thread.as_deref() == Some(PENDING_THREAD_ID) {
+                self.expanded_thread = Some(created.thread_id.clone());
+            }
+        }
+        if let Some(comment) = self
+            .all_comments
+            .get_mut(&created.thread_id)
+            .and_then(|comments| {
+                comments
+                    .iter_mut()
+                    .find(|c| c.comment_id == PENDING_COMMENT_ID)
+            })
+        {
+            comment.comment_id = created.comment_id;
+            comment.author = created.author;
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Remove the optimistic copy of a submission that crit rejected.
+    pub fn rollback_comment(&mut self, submission: &PendingCommentSubmission) {
+        if let Some(thread_id) = &submission.request.thread_id {
+            if let Some(comments) = self.all_comments.get_mut(thread_id) {
+                comments.retain(|c| c.comment_id != PENDING_COMMENT_ID);
+            }
+            if let Some(thread) = self.threads.iter_mut().find(|t| t.thread_id == *thread_id) {
+                thread.comment_count = (thread.comment_count - 1).max(0);
+            }
+        } else if self
+            .threads
+            .iter()
+            .any(|t| t.thread_id == PENDING_THREAD_ID)
+        {
+            self.threads.retain(|t| t.thread_id != PENDING_THREAD_ID);
+            self.all_comments.remove(PENDING_THREAD_ID);
+            self.thread_positions.borrow_mut().remove(PENDING_THREAD_ID);
+            if self.expanded_thread.as_deref() == Some(PENDING_THREAD_ID) {
+                self.expanded_thread = None;
+            }
+            self.adjust_review_thread_counts(-1);
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Show a refused reply's thread as it is now and reopen the editor with
+    /// the draft, so the author reads the newer comments before retrying.
+    pub fn reopen_conflicted_reply(
+        &mut self,
+        conflict: ThreadConflict,
+        submission: PendingCommentSubmission,
+    ) {
+        let seen: HashSet<&str> = submission
+            .request
+            .existing_comments
+            .iter()
+            .map(|c| c.comment_id.as_str())
+            .collect();
+        let new_count = conflict
+            .comments
+            .iter()
+            .filter(|c| !seen.contains(c.comment_id.as_str()))
+            .count();
+
+        self.show_conflicted_thread(&conflict);
+        self.all_comments
+            .insert(conflict.thread_id.clone(), conflict.comments.clone());
+
+        let mut request = submission.request;
+        request.thread_etag = Some(thread_etag(&conflict.status, &conflict.comments));
+        request.notice = Some(tr_fmt(Text::ReplyConflict, &[&new_count, &conflict.status]));
+        request.existing_comments = conflict.comments;
+        self.inline_editor = Some(InlineEditor::with_body(request, &submission.body));
+        self.focus = Focus::Commenting;
+        self.needs_redraw = true;
+    }
+
+    /// Take a refused write's thread status and comment count, and refetch
+    /// its status history. The caller stores the comments.
+    fn show_conflicted_thread(&mut self, conflict: &ThreadConflict) {
+        if let Some(thread) = self
+            .threads
+            .iter_mut()
+            .find(|t| t.thread_id == conflict.thread_id)
+        {
+            let open_delta =
+                i64::from(conflict.status == "open") - i64::from(thread.status == "open");
+            thread.status.clone_from(&conflict.status);
+            thread.comment_count = i64::try_from(conflict.comments.len()).unwrap_or(i64::MAX);
+            if let Some(review) = &mut self.current_review {
+                review.open_thread_count += open_delta;
+            }
+        }
+        // Status history may have moved too
+        self.thread_events.remove(&conflict.thread_id);
+        self.pending_thread_events.push(conflict.thread_id.clone());
+        self.needs_redraw = true;
+    }
+
+    /// Queue a resolve or reopen and show the new status right away, along
+    /// with the sidebar and header open-thread counts. Does nothing if the
+    /// thread already has `status`.
+    pub fn queue_status_change(&mut self, thread_id: &str, status: &str) {
+        let Some(previous) = self.set_thread_status(thread_id, status) else {
+            return;
+        };
+        let comments = self
+            .all_comments
+            .get(thread_id)
+            .map_or(&[][..], Vec::as_slice);
+        let etag = thread_etag(&previous, comments);
+        let expected = self
+            .current_review
+            .as_ref()
+            .map(|review| (review.review_id.clone(), etag));
+        self.pending_status_change = Some(ThreadStatusChange {
+            thread_id: thread_id.to_string(),
+            status: status.to_string(),
+            previous,
+            expected,
+            step: LogStep::Record,
+        });
+    }
+
+    /// Put back the status a rejected resolve or reopen replaced.
+    pub fn rollback_status_change(&mut self, change: &ThreadStatusChange) {
+        self.set_thread_status(&change.thread_id, &change.previous);
+    }
+
+    /// Show the thread a resolve or reopen was refused for as it is now,
+    /// so the author sees what changed before trying again.
+    pub fn refuse_status_change(&mut self, change: &ThreadStatusChange, conflict: ThreadConflict) {
+        self.rollback_status_change(change);
+        let new_count = conflict.comments.len().saturating_sub(
+            self.all_comments
+                .get(&conflict.thread_id)
+                .map_or(0, Vec::len),
+        );
+        self.show_conflicted_thread(&conflict);
+        self.flash_message = Some(tr_fmt(
+            Text::ThreadStatusConflict,
+            &[&new_count, &conflict.status],
+        ));
+        self.all_comments
+            .insert(conflict.thread_id, conflict.comments);
+    }
+
+    /// Log a written resolve or reopen, or finish the undo/redo that
+    /// queued it.
+    pub fn log_status_change(&mut self, change: &ThreadStatusChange) {
+        let action = ReviewAction::ThreadStatus {
+            thread_id: change.thread_id.clone(),
+            from: change.previous.clone(),
+            to: change.status.clone(),
+        };
+        let flash = match change.step {
+            LogStep::Record => None,
+            // The logged action is the one this change took back
+            LogStep::Undo => action
+                .inverse()
+                .map(|original| tr_fmt(Text::Undone, &[&original.label()])),
+            LogStep::Redo => Some(tr_fmt(Text::Redone, &[&action.label()])),
+        };
+        self.action_log.apply(change.step, action);
+        if flash.is_some() {
+            self.flash_message = flash;
+        }
+    }
+
+    /// Log a posted comment; it can't be undone.
+    pub fn log_posted_comment(&mut self, request: &CommentRequest) {
+        self.action_log.record(ReviewAction::Comment {
+            location: format!("{}:{}", request.file_path, request.start_line),
+        });
+    }
+
+    /// Set a thread's status, keeping the review's open count in step.
+    /// Returns the old status if it changed.
+    fn set_thread_status(&mut self, thread_id: &str, status: &str) -> Option<String> {
+        let thread = self
+            .threads
+            .iter_mut()
+            .find(|t| t.thread_id == thread_id)
+            .filter(|t| t.status != status)?;
+        let open_delta = i64::from(status == "open") - i64::from(thread.status == "open");
+        let previous = std::mem::replace(&mut thread.status, status.to_string());
+        if let Some(review) = &mut self.current_review {
+            review.open_thread_count += open_delta;
+        }
+        self.needs_redraw = true;
+        Some(previous)
+    }
+
+    const fn adjust_review_thread_counts(&mut self, delta: i64) {
+        if let Some(review) = &mut self.current_review {
+            review.thread_count += delta;
+            review.open_thread_count += delta;
+        }
+    }
+
+    /// Diff view mode of `path`: its override, else `diff_view_mode`.
+    #[must_use]
+    pub fn file_view_mode(&self, path: &str) -> DiffViewMode {
+        self.diff_view_overrides
+            .get(path)
+            .copied()
+            .unwrap_or(self.diff_view_mode)
+    }
+
+    /// Diff view mode of the current file.
+    #[must_use]
+    pub fn active_view_mode(&self) -> DiffViewMode {
+        self.files_with_threads()
+            .get(self.file_index)
+            .map_or(self.diff_view_mode, |file| self.file_view_mode(&file.path))
+    }
+
+    /// Diff view mode of the file drawn at stream row `row`.
+    #[must_use]
+    pub fn view_mode_at_row(&self, row: usize) -> DiffViewMode {
+        self.stream_layout()
+            .file_at_row(row)
+            .and_then(|index| {
+                self.files_with_threads()
+                    .get(index)
+                    .map(|file| self.file_view_mode(&file.path))
+            })
+            .unwrap_or(self.diff_view_mode)
+    }
+
+    /// Side whose line numbers the cursor addresses: the focused column in
+    /// side-by-side mode, otherwise the new side.
+    #[must_use]
+    pub fn cursor_side(&self) -> ThreadSide {
+        match self.view_mode_at_row(self.diff_cursor) {
+            DiffViewMode::SideBySide => self.sbs_side,
+            DiffViewMode::Unified => ThreadSide::New,
+        }
+    }
+
+    /// Stream row → line number map for `side`, as of the last frame.
+    #[must_use]
+    pub const fn side_line_map(&self, side: ThreadSide) -> &RefCell<HashMap<usize, i64>> {
+        match side {
+            ThreadSide::Old => &self.old_line_map,
+            ThreadSide::New => &self.line_map,
+        }
+    }
+
+    /// Id of the comment whose rows contain the diff cursor.
+    #[must_use]
+    pub fn comment_at_cursor(&self) -> Option<String> {
+        self.comment_positions
+            .borrow()
+            .iter()
+            .find(|(_, rows)| rows.contains(&self.diff_cursor))
+            .map(|(id, _)| id.clone())
+    }
+
+    /// Put `path` first among the recent files.
+    pub fn visit_file(&mut self, path: &str) {
+        self.recent_files.retain(|recent| recent != path);
+        self.recent_files.insert(0, path.to_string());
+        self.recent_files.truncate(RECENT_FILES + 1);
+    }
+
+    /// Recent files other than the current one, most recent first: the
+    /// targets of Alt+1, Alt+2, ….
+    #[must_use]
+    pub fn recent_other_files(&self) -> Vec<&str> {
+        let files = self.files_with_threads();
+        let current = files.get(self.file_index).map(|f| f.path.as_str());
+        self.recent_files
+            .iter()
+            .map(String::as_str)
+            .filter(|&path| Some(path) != current)
+            .take(RECENT_FILES)
+            .collect()
+    }
+
+    /// Id of the thread whose comment block contains the diff cursor.
+    #[must_use]
+    pub fn thread_at_cursor(&self) -> Option<String> {
+        self.thread_rows
+            .borrow()
+            .iter()
+            .find(|(_, rows)| rows.contains(&self.diff_cursor))
+            .map(|(id, _)| id.clone())
+    }
+
+    /// Patch for the suggestion under the cursor: the comment's own, or on a
+    /// thread the latest one made. `None` if the replaced lines aren't
+    /// loaded.
+    #[must_use]
+    pub fn cursor_suggestion(&self) -> Option<SuggestionPatch> {
+        let thread_id = self
+            .thread_at_cursor()
+            .or_else(|| self.expanded_thread.clone())?;
+        let thread = self.threads.iter().find(|t| t.thread_id == thread_id)?;
+        let replaced = replaced_lines(thread)?;
+        let comments = self.all_comments.get(&thread_id)?;
+        let at_cursor = self.comment_at_cursor();
+        let lines = comments
+            .iter()
+            .find(|c| Some(&c.comment_id) == at_cursor.as_ref())
+            .and_then(|c| last_suggestion(&c.body))
+            .or_else(|| comments.iter().rev().find_map(|c| last_suggestion(&c.body)))?;
+        let entry = self.file_cache.get(&thread.file_path)?;
+        let line = |n| entry.new_side_line(n).map(str::to_string);
+        let original = replaced.clone().map(line).collect::<Option<Vec<_>>>()?;
+        Some(SuggestionPatch::new(
+            &thread.file_path,
+            *replaced.start(),
+            &original,
+            &lines,
+            line,
+        ))
+    }
+
+    /// Command that checks out the open review's change, for the repo's VCS.
+    /// Without a repo, jj when the review has a change id, else git.
+    #[must_use]
+    pub fn checkout_command(&self) -> Option<String> {
+        let review = self.current_review.as_ref()?;
+        let vcs = self
+            .repo_path
+            .as_deref()
+            .and_then(|path| vcs::detect_vcs(Path::new(path)))
+            .unwrap_or(if review.jj_change_id.is_empty() {
+                VcsType::Git
+            } else {
+                VcsType::Jj
+            });
+        Some(vcs::checkout_command(
+            vcs,
+            &review.jj_change_id,
+            review.commit(),
+        ))
+    }
+
+    /// Link to a thread of the open review, from the `thread_link` template.
+    #[must_use]
+    pub fn thread_link(&self, thread_id: &str) -> Option<String> {
+        let review = self.current_review.as_ref()?;
+        Some(thread_link(
+            self.config.thread_link.as_deref(),
+            &review.review_id,
+            thread_id,
+        ))
+    }
+
+    /// Whether the file sidebar is the focused pane, including under an
+    /// overlay opened from it.
+    #[must_use]
+    pub fn sidebar_has_focus(&self) -> bool {
+        match self.focus {
+            Focus::FileSidebar => true,
+            Focus::CommandPalette
+            | Focus::Commenting
+            | Focus::ActionMenu
+            | Focus::CommandOutput
+            | Focus::InputLog
+            | Focus::ThreadsPanel => self.previous_focus == Some(Focus::FileSidebar),
+            _ => false,
+        }
+    }
+
+    /// The latest `review_command` run, if it was for the open review.
+    #[must_use]
+    pub fn current_command_run(&self) -> Option<&CommandRun> {
+        let review = self.current_review.as_ref()?;
+        self.command_run
+            .as_ref()
+            .filter(|run| run.review_id == review.review_id)
+    }
+
+    /// Comments whose id, author or body contains every whitespace-separated
+    /// term of `query` (case-insensitive), in thread order.
+    #[must_use]
+    pub fn find_comments(&self, query: &str) -> Vec<&Comment> {
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        self.threads
+            .iter()
+            .filter_map(|t| self.all_comments.get(&t.thread_id))
+            .flatten()
+            .filter(|c| {
+                let text = format!("{} {} {}", c.comment_id, c.author, c.body).to_lowercase();
+                terms.iter().all(|term| text.contains(term.as_str()))
+            })
+            .collect()
+    }
+
+    /// Actions that apply at the diff cursor, in menu order. Mutating ones
+    /// are left out in read-only mode.
+    #[must_use]
+    pub fn cursor_actions(&self) -> Vec<MenuAction> {
+        let side = self.cursor_side();
+        let on_line = self
+            .side_line_map(side)
+            .borrow()
+            .contains_key(&self.diff_cursor);
+        let on_comment = self.comment_at_cursor().is_some();
+        let has_file = self.files_with_threads().get(self.file_index).is_some();
+
+        let mut actions = Vec::new();
+        if (on_line || on_comment) && !self.read_only {
+            actions.push(MenuAction::Comment);
+        }
+        if on_line && !self.read_only {
+            actions.push(MenuAction::CommentHunk);
+        }
+        if has_file && !self.read_only {
+            actions.push(MenuAction::CommentFile);
+        }
+        if on_line {
+            actions.push(MenuAction::CopyLine);
+            actions.push(MenuAction::CopyLineReference);
+        }
+        if on_comment {
+            actions.push(MenuAction::CopyCommentId);
+        }
+        if self.thread_at_cursor().is_some() {
+            actions.push(MenuAction::CopyThreadLink);
+            actions.push(MenuAction::ToggleMute);
+        }
+        if !self.read_only && self.repo_path.is_some() && self.cursor_suggestion().is_some() {
+            actions.push(MenuAction::ApplySuggestion);
+        }
+        if has_file && self.repo_path.is_some() {
+            actions.push(MenuAction::OpenInEditor);
+            // Old-side lines aren't in the reviewed revision
+            if on_line && side == ThreadSide::New {
+                actions.push(MenuAction::ViewBlame);
+            }
+        }
+        actions
+    }
+
+    /// Verdicts on the open review.
+    #[must_use]
+    pub fn review_votes(&self) -> &[ReviewVote] {
+        self.current_review
+            .as_ref()
+            .map_or(&[], |review| review.votes.as_slice())
+    }
+
+    /// Header spinner frame while an auto-refresh poll is (recently) running.
+    #[must_use]
+    pub fn refresh_spinner(&self) -> Option<&'static str> {
+        self.refresh_started.and_then(spinner_frame)
+    }
+
+    /// The `/` search query to highlight in the diff stream, if any.
+    #[must_use]
+    pub fn diff_search_query(&self) -> Option<&str> {
+        self.diff_search.as_ref().and_then(DiffSearch::active_query)
+    }
+
+    /// Comment id still within its post-jump highlight.
+    #[must_use]
+    pub fn flashed_comment(&self) -> Option<&str> {
+        self.comment_flash
+            .as_ref()
+            .filter(|&&(_, at)| crate::clock::elapsed(at) < COMMENT_FLASH)
+            .map(|(id, _)| id.as_str())
+    }
+
+    /// Up to [`COMMENT_EXCERPT_LINES`] source lines on `side` starting at
+    /// `start`, stopping early at `end` or at a line that isn't loaded.
+    #[must_use]
+    pub fn target_excerpt(
+        &self,
+        file_path: &str,
+        side: ThreadSide,
+        start: i64,
+        end: Option<i64>,
+    ) -> Vec<String> {
+        let Some(entry) = self.file_cache.get(file_path) else {
+            return Vec::new();
+        };
+        let end = end.unwrap_or(start).max(start);
+        (start..=end)
+            .take(COMMENT_EXCERPT_LINES)
+            .map_while(|line| entry.side_line(side, line).map(str::to_string))
+            .collect()
+    }
+
+    /// The 1-based lines of `path` that context for its orphaned threads,
+    /// the ones `entry`'s diff can't place, spans. `None` when there are
+    /// none or crit already sent content.
+    #[must_use]
+    pub fn orphaned_context_window(
+        &self,
+        path: &str,
+        entry: &FileCacheEntry,
+    ) -> Option<RangeInclusive<usize>> {
+        if entry.file_content.is_some() {
+            return None;
+        }
+        let (_, threads) = split_file_threads(&self.threads, path);
+        let anchored: HashSet<String> = entry
+            .diff
+            .as_ref()
+            .map(|diff| {
+                map_threads_to_diff(diff, &threads)
+                    .into_iter()
+                    .map(|anchor| anchor.thread_id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let (start, end) = threads
+            .iter()
+            .filter(|t| !anchored.contains(&t.thread_id))
+            .map(|t| {
+                (
+                    t.selection_start,
+                    t.selection_end.unwrap_or(t.selection_start),
+                )
+            })
+            .reduce(|(s1, e1), (s2, e2)| (s1.min(s2), e1.max(e2)))?;
+        let context = self.context_lines();
+        let start = usize::try_from((start - context).max(1)).ok()?;
+        let end = usize::try_from(end + context).ok()?;
+        Some(start..=end.max(start))
+    }
+
+    /// Splice the lines read for `expansion` into its file's diff. Below
+    /// its hunk's header the cursor moves with the lines it was on.
+    pub fn apply_context_expansion(&mut self, expansion: &ContextExpansion, lines: &[String]) {
+        if lines.is_empty() {
+            self.flash_message = Some(tr(Text::NoMoreContext).to_string());
+            return;
+        }
+        let file_rows = |model: &Self| {
+            model
+                .stream_layout()
+                .file_rows(expansion.file)
+                .map_or(0, |rows| rows.len())
+        };
+        let rows_before = file_rows(self);
+        let Some(entry) = self.file_cache.get_mut(&expansion.path) else {
+            return;
+        };
+        let Some(diff) = entry.diff.as_mut() else {
+            return;
+        };
+        let first = *expansion.lines.start();
+        if diff.expand_hunk(expansion.hunk, expansion.up, first, lines) {
+            let lower = expansion.hunk + usize::from(!expansion.up);
+            self.folds.merge_hunk(&expansion.path, lower);
+        }
+        if let Some(word_lines) = &mut entry.word_lines {
+            *word_lines = word_diff(&diff.hunks);
+        }
+        // Old colors no longer line up with the diff's lines
+        entry.highlighted_lines.clear();
+        entry.revision = FileCacheEntry::next_revision();
+        self.highlight_file(&expansion.path);
+        self.sync_active_file_cache();
+        if expansion.up && self.diff_cursor > expansion.header_row {
+            self.diff_cursor = (self.diff_cursor + file_rows(self)).saturating_sub(rows_before);
+            let visible = crate::layout::visible_stream_rows(self.height);
+            if self.diff_cursor >= self.diff_scroll + visible {
+                self.diff_scroll = self.diff_cursor + 1 - visible.max(1);
+            }
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Whether `path` gets syntax highlighting.
+    #[must_use]
+    pub fn highlights_file(&self, path: &str) -> bool {
+        self.syntax && !self.plain_files.contains(path)
+    }
+
+    /// Highlight the cached entry for `path`, its diff and its content: on
+    /// the worker, whose result comes back as `Message::HighlightsReady`, or
+    /// right away without one. A file left plain drops its colors instead,
+    /// along with any result still on its way.
+    pub fn highlight_file(&mut self, path: &str) {
+        let highlights = self.highlights_file(path);
+        let Some(entry) = self.file_cache.get_mut(path) else {
+            return;
+        };
+        if !highlights {
+            entry.highlighted_lines.clear();
+            entry.file_highlighted_lines.clear();
+            entry.revision = FileCacheEntry::next_revision();
+            return;
+        }
+        let job = HighlightJob {
+            path: path.to_string(),
+            revision: entry.revision,
+            highlighter: Arc::clone(&self.highlighter),
+            diff: entry.diff.clone(),
+            content: entry.file_content.as_ref().map(|c| c.lines.clone()),
+        };
+        match &self.highlight_worker {
+            Some(worker) => worker.queue(job),
+            None => job.run().apply(entry),
+        }
+    }
+
+    /// Sync current file fields from the file cache
+    pub fn sync_active_file_cache(&mut self) {
+        let files = self.files_with_threads();
+        let Some(file) = files.get(self.file_index) else {
+            self.current_diff = None;
+            self.current_file_content = None;
+            self.highlighted_lines.clear();
+            return;
+        };
+
         if let Some(entry) = self.file_cache.get(&file.path) {
             self.current_diff = entry.diff.clone();
             self.current_file_content = entry.file_content.clone();
@@ -710,21 +2635,678 @@ pub struct FileEntry {
     pub resolved_threads: usize,
 }
 
-/// An item in the sidebar tree (file or thread)
+/// What the background scan found in one review's changed files.
+#[derive(Debug, Clone, Default)]
+pub struct FileScan {
+    /// Files the user owns (CODEOWNERS)
+    pub owned: usize,
+    pub annotations: SeverityCounts,
+    /// Changed file paths, for the filter bar's `path:`
+    pub paths: Vec<String>,
+    /// Stand-ins for what older crit leaves out of the review list
+    pub stats: Option<DiffStats>,
+    pub updated_at: Option<String>,
+}
+
+/// An item in the sidebar tree (directory, file or thread)
 #[derive(Debug, Clone)]
 pub enum SidebarItem {
+    /// A directory in the tree sidebar (`sidebar_tree`)
+    Directory {
+        path: String,
+        /// Directories above it
+        depth: usize,
+        /// Thread counts of every file under it
+        open_threads: usize,
+        resolved_threads: usize,
+        collapsed: bool,
+        /// Indices into `files_with_threads()` of the files under it
+        files: Range<usize>,
+    },
     File {
         entry: FileEntry,
         /// Index into `files_with_threads()` for selection matching
         file_idx: usize,
         /// Whether this file's threads are collapsed
         collapsed: bool,
+        /// Marked viewed (`x`)
+        viewed: bool,
+        /// Directories above it in the tree sidebar; 0 when flat
+        depth: usize,
     },
     Thread {
         thread_id: String,
         status: String,
         comment_count: i64,
+        muted: bool,
+        /// About the file as a whole, not a line
+        file_level: bool,
         /// Parent file index for selection matching
         file_idx: usize,
+        /// One more than its file's
+        depth: usize,
     },
 }
+
+impl SidebarItem {
+    /// Nesting level: a child is one deeper than its parent.
+    #[must_use]
+    pub const fn depth(&self) -> usize {
+        match self {
+            Self::Directory { depth, .. }
+            | Self::File { depth, .. }
+            | Self::Thread { depth, .. } => *depth,
+        }
+    }
+}
+
+/// `collapsed_files` key of a tree sidebar directory: its path and a `/`,
+/// so it can't clash with a file's.
+#[must_use]
+pub fn dir_key(path: &str) -> String {
+    format!("{path}/")
+}
+
+/// Directories containing `path`, outermost first: `a`, `a/b` for `a/b/c.rs`.
+fn parent_dirs(path: &str) -> Vec<&str> {
+    path.match_indices('/').map(|(i, _)| &path[..i]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(thread_id: Option<&str>) -> CommentRequest {
+        CommentRequest {
+            review_id: "cr-1".to_string(),
+            file_path: "src/lib.rs".to_string(),
+            start_line: 4,
+            end_line: None,
+            side: ThreadSide::New,
+            thread_id: thread_id.map(str::to_string),
+            existing_comments: Vec::new(),
+            thread_etag: None,
+            excerpt: Vec::new(),
+            notice: None,
+        }
+    }
+
+    #[test]
+    fn test_quoted_snippet_fences_and_attributes_lines() {
+        let mut range = request(None);
+        range.end_line = Some(6);
+        let quoted = quoted_snippet(&range, &["fn a() {", "", "    let s = \"```\";"]);
+        assert_eq!(
+            quoted,
+            "> `src/lib.rs:4-6`\n> ````rs\n> fn a() {\n>\n>     let s = \"```\";\n> ````"
+        );
+
+        let mut old = request(None);
+        old.side = ThreadSide::Old;
+        assert_eq!(
+            quoted_snippet(&old, &["x"]),
+            "> `src/lib.rs:4 (old)`\n> ```rs\n> x\n> ```"
+        );
+    }
+
+    #[test]
+    fn test_optimistic_comment_reconcile_and_rollback() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+
+        model.queue_comment_submission(request(None), "new thread".to_string());
+        assert_eq!(model.threads.len(), 1);
+        assert_eq!(model.all_comments[PENDING_THREAD_ID].len(), 1);
+        model.reconcile_comment(CreatedComment {
+            thread_id: "th-9".to_string(),
+            comment_id: "c-9".to_string(),
+            author: "alice".to_string(),
+        });
+        assert_eq!(model.threads[0].thread_id, "th-9");
+        assert_eq!(model.all_comments["th-9"][0].comment_id, "c-9");
+        assert!(!model.all_comments.contains_key(PENDING_THREAD_ID));
+
+        model.queue_comment_submission(request(Some("th-9")), "reply".to_string());
+        assert_eq!(model.threads[0].comment_count, 2);
+        let submission = model.pending_comment_submission.take().unwrap();
+        model.rollback_comment(&submission);
+        assert_eq!(model.threads[0].comment_count, 1);
+        assert_eq!(model.all_comments["th-9"].len(), 1);
+
+        model.queue_comment_submission(request(None), "rejected".to_string());
+        let submission = model.pending_comment_submission.take().unwrap();
+        model.rollback_comment(&submission);
+        assert_eq!(model.threads.len(), 1);
+    }
+
+    #[test]
+    fn test_cancelled_submission_reopens_the_editor() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        model.queue_comment_submission(request(None), "second thoughts".to_string());
+        let send_at = model.pending_comment_submission.as_ref().unwrap().send_at;
+        assert!(send_at >= crate::clock::now() + COMMENT_UNDO_WINDOW / 2);
+
+        model.cancel_comment_submission();
+        assert!(model.pending_comment_submission.is_none());
+        assert!(model.threads.is_empty());
+        assert_eq!(model.focus, Focus::Commenting);
+        assert_eq!(model.inline_editor.unwrap().body(), "second thoughts");
+
+        let mut model = Model::new(80, 24, UiConfig::default());
+        model.config.comment_undo_secs = Some(0);
+        model.queue_comment_submission(request(None), "now".to_string());
+        let send_at = model.pending_comment_submission.as_ref().unwrap().send_at;
+        assert!(send_at <= crate::clock::now());
+    }
+
+    #[test]
+    fn test_drafts_survive_cancel_until_sent() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        model.open_inline_editor(request(None));
+        model
+            .inline_editor
+            .as_mut()
+            .unwrap()
+            .insert_str("half a thought");
+        model.stash_draft();
+        model.inline_editor = None;
+
+        model.open_inline_editor(request(None));
+        let editor = model.inline_editor.as_ref().unwrap();
+        assert_eq!(editor.body(), "half a thought");
+        assert_eq!(
+            editor.request.notice.as_deref(),
+            Some(tr(Text::DraftRestored))
+        );
+        // A reply to a thread keeps its own draft
+        model.open_inline_editor(request(Some("th-1")));
+        assert_eq!(model.inline_editor.as_ref().unwrap().body(), "");
+
+        model.discard_draft(&request(None));
+        model.open_inline_editor(request(None));
+        assert_eq!(model.inline_editor.as_ref().unwrap().body(), "");
+    }
+
+    #[test]
+    fn test_verdict_draft_kept_until_sent() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        model.open_verdict_editor(request(None), Verdict::RequestChanges);
+        model
+            .inline_editor
+            .as_mut()
+            .unwrap()
+            .insert_str("Needs tests");
+        model.stash_draft();
+        model.inline_editor = None;
+        assert_eq!(
+            model.ui_state.verdict_drafts["cr-1"],
+            VerdictDraft {
+                verdict: Verdict::RequestChanges,
+                summary: "Needs tests".to_string(),
+            }
+        );
+        // Comment drafts are kept apart
+        assert!(model.ui_state.drafts.is_empty());
+
+        // The summary comes back whichever verdict is picked
+        model.open_verdict_editor(request(None), Verdict::Approve);
+        let editor = model.inline_editor.as_ref().unwrap();
+        assert_eq!(editor.body(), "Needs tests");
+        assert_eq!(editor.verdict, Some(Verdict::Approve));
+
+        model.discard_verdict_draft("cr-1");
+        assert!(model.ui_state.verdict_drafts.is_empty());
+    }
+
+    #[test]
+    fn test_recent_files_most_recent_first_without_repeats() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        for path in ["a.rs", "b.rs", "a.rs", "c.rs"] {
+            model.visit_file(path);
+        }
+        assert_eq!(model.recent_other_files(), ["c.rs", "a.rs", "b.rs"]);
+        for n in 0..10 {
+            model.visit_file(&format!("{n}.rs"));
+        }
+        // One more than offered, so the current file can be left out
+        assert_eq!(model.recent_files.len(), RECENT_FILES + 1);
+        assert_eq!(model.recent_other_files().len(), RECENT_FILES);
+        assert_eq!(model.recent_other_files()[0], "9.rs");
+    }
+
+    #[test]
+    fn test_thread_link_fills_template() {
+        assert_eq!(
+            thread_link(None, "cr-1", "th-2"),
+            "crit-ui --review cr-1 --thread th-2"
+        );
+        assert_eq!(
+            thread_link(
+                Some("https://crit.example.com/{review}#{thread}"),
+                "cr-1",
+                "th-2"
+            ),
+            "https://crit.example.com/cr-1#th-2"
+        );
+    }
+
+    #[test]
+    fn test_find_comments_and_comment_at_cursor() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        model.queue_comment_submission(request(None), "Needs a bounds check".to_string());
+        model.reconcile_comment(CreatedComment {
+            thread_id: "th-1".to_string(),
+            comment_id: "cm-ab12".to_string(),
+            author: "alice".to_string(),
+        });
+        model.queue_comment_submission(request(Some("th-1")), "Agreed".to_string());
+        model.reconcile_comment(CreatedComment {
+            thread_id: "th-1".to_string(),
+            comment_id: "cm-cd34".to_string(),
+            author: "bob".to_string(),
+        });
+
+        let ids = |query| -> Vec<String> {
+            model
+                .find_comments(query)
+                .iter()
+                .map(|c| c.comment_id.clone())
+                .collect()
+        };
+        assert_eq!(ids(""), ["cm-ab12", "cm-cd34"]);
+        assert_eq!(ids("CM-CD"), ["cm-cd34"]);
+        assert_eq!(ids("bounds alice"), ["cm-ab12"]);
+        assert!(ids("cm-zz").is_empty());
+
+        model
+            .comment_positions
+            .borrow_mut()
+            .insert("cm-cd34".to_string(), 7..9);
+        model.diff_cursor = 8;
+        assert_eq!(model.comment_at_cursor().as_deref(), Some("cm-cd34"));
+        model.diff_cursor = 9;
+        assert_eq!(model.comment_at_cursor(), None);
+    }
+
+    #[test]
+    fn test_muted_threads_skip_counts_and_navigation() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        for (thread, body) in [(None, "first"), (None, "second")] {
+            model.queue_comment_submission(request(thread), body.to_string());
+            model.reconcile_comment(CreatedComment {
+                thread_id: format!("th-{body}"),
+                comment_id: format!("cm-{body}"),
+                author: "alice".to_string(),
+            });
+        }
+        assert_eq!(model.files_with_threads()[0].open_threads, 2);
+
+        model.config.muted_threads.insert("th-first".to_string());
+        assert!(model.is_muted("th-first"));
+        assert_eq!(model.files_with_threads()[0].open_threads, 1);
+        assert_eq!(model.threads_for_current_file().len(), 2);
+        let visible: Vec<_> = model
+            .visible_threads_for_current_file()
+            .iter()
+            .map(|t| t.thread_id.as_str())
+            .collect();
+        assert_eq!(visible, ["th-second"]);
+    }
+
+    #[test]
+    fn test_tree_sidebar_groups_and_collapses_directories() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        for (n, path) in ["src/b.rs", "src/api/x.rs", "src/a.rs"].iter().enumerate() {
+            let mut req = request(None);
+            req.file_path = (*path).to_string();
+            model.queue_comment_submission(req, "note".to_string());
+            model.reconcile_comment(CreatedComment {
+                thread_id: format!("th-{n}"),
+                comment_id: format!("cm-{n}"),
+                author: "alice".to_string(),
+            });
+        }
+        let shape = |model: &Model| -> Vec<(String, usize)> {
+            model
+                .sidebar_items()
+                .iter()
+                .map(|item| {
+                    let label = match item {
+                        SidebarItem::Directory { path, .. } => dir_key(path),
+                        SidebarItem::File { entry, .. } => entry.path.clone(),
+                        SidebarItem::Thread { thread_id, .. } => thread_id.clone(),
+                    };
+                    (label, item.depth())
+                })
+                .collect()
+        };
+        assert!(shape(&model).iter().all(|&(_, depth)| depth <= 1));
+
+        model.config.sidebar_tree = Some(true);
+        let labels: Vec<String> = shape(&model).into_iter().map(|(l, _)| l).collect();
+        assert_eq!(
+            labels,
+            [
+                "src/",
+                "src/a.rs",
+                "th-2",
+                "src/api/",
+                "src/api/x.rs",
+                "th-1",
+                "src/b.rs",
+                "th-0"
+            ]
+        );
+        assert_eq!(shape(&model)[6].1, 1);
+        assert!(matches!(
+            model.sidebar_items()[0],
+            SidebarItem::Directory {
+                open_threads: 3,
+                files: Range { start: 0, end: 3 },
+                ..
+            }
+        ));
+
+        model.collapsed_files.insert(dir_key("src/api"));
+        let labels: Vec<String> = shape(&model).into_iter().map(|(l, _)| l).collect();
+        assert_eq!(
+            labels,
+            ["src/", "src/a.rs", "th-2", "src/api/", "src/b.rs", "th-0"]
+        );
+        assert_eq!(model.sidebar_position_of_file(1), Some(3));
+
+        model.collapsed_files.insert(dir_key("src"));
+        assert_eq!(shape(&model), [("src/".to_string(), 0)]);
+        assert_eq!(model.sidebar_position_of_file(2), Some(0));
+    }
+
+    #[test]
+    fn test_status_change_is_optimistic_and_rolls_back() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        model.queue_comment_submission(request(None), "first".to_string());
+        model.reconcile_comment(CreatedComment {
+            thread_id: "th-1".to_string(),
+            comment_id: "cm-1".to_string(),
+            author: "alice".to_string(),
+        });
+
+        model.queue_status_change("th-1", "resolved");
+        assert_eq!(model.threads[0].status, "resolved");
+        assert_eq!(model.files_with_threads()[0].open_threads, 0);
+        let change = model.pending_status_change.take().unwrap();
+        assert_eq!(change.previous, "open");
+
+        model.rollback_status_change(&change);
+        assert_eq!(model.threads[0].status, "open");
+        assert_eq!(model.files_with_threads()[0].open_threads, 1);
+        assert!(model.action_log.last_done().is_none());
+
+        // Only a written change is logged; its undo finishes the same way
+        model.queue_status_change("th-1", "resolved");
+        let change = model.pending_status_change.take().unwrap();
+        model.log_status_change(&change);
+        let resolved = model.action_log.last_done().cloned().unwrap();
+        model.queue_status_change("th-1", "open");
+        let mut undo = model.pending_status_change.take().unwrap();
+        undo.step = LogStep::Undo;
+        model.log_status_change(&undo);
+        assert!(model.action_log.last_done().is_none());
+        assert_eq!(model.action_log.last_undone(), Some(&resolved));
+
+        model.queue_status_change("th-1", "open");
+        assert!(model.pending_status_change.is_none());
+    }
+
+    #[test]
+    fn test_refused_status_change_shows_the_thread_as_it_is() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        model.queue_comment_submission(request(None), "first".to_string());
+        model.reconcile_comment(CreatedComment {
+            thread_id: "th-1".to_string(),
+            comment_id: "cm-1".to_string(),
+            author: "alice".to_string(),
+        });
+
+        model.queue_status_change("th-1", "resolved");
+        let change = model.pending_status_change.take().unwrap();
+        let mut comments = model.all_comments["th-1"].clone();
+        comments.push(Comment {
+            comment_id: "cm-2".to_string(),
+            author: "bob".to_string(),
+            body: "not yet".to_string(),
+            created_at: String::new(),
+        });
+        model.refuse_status_change(
+            &change,
+            ThreadConflict {
+                thread_id: "th-1".to_string(),
+                status: "open".to_string(),
+                comments,
+            },
+        );
+        assert_eq!(model.threads[0].status, "open");
+        assert_eq!(model.threads[0].comment_count, 2);
+        assert_eq!(model.all_comments["th-1"].len(), 2);
+        assert!(model.action_log.last_done().is_none());
+        assert!(model.flash_message.is_some());
+    }
+
+    #[test]
+    fn test_review_sort_uses_scanned_metadata() {
+        use crate::db::CritClient;
+        use crate::demo_client::{DemoClient, DemoSize, DEFAULT_DEMO_SEED};
+
+        let client = DemoClient::new(DemoSize::Small, DEFAULT_DEMO_SEED);
+        let mut model = Model::new(80, 24, UiConfig::default());
+        model.reviews = client.list_reviews(None).unwrap();
+        model.queue_file_scan();
+        assert_eq!(model.pending_file_scan.len(), model.reviews.len());
+        while let Some(review_id) = model.pending_file_scan.pop() {
+            let data = client.load_review_data(&review_id).unwrap().unwrap();
+            model.record_file_scan(&data);
+        }
+        let first = &model.reviews[0];
+        assert!(model.review_stats(first).is_some_and(|s| s.files > 0));
+        assert!(model.review_updated_at(first) >= first.created_at.as_deref());
+
+        model.review_sort = ReviewSort::Updated;
+        let updated: Vec<_> = model
+            .filtered_reviews()
+            .iter()
+            .map(|r| model.review_updated_at(r))
+            .collect();
+        assert!(updated.windows(2).all(|w| w[0] >= w[1]));
+
+        model.review_sort = ReviewSort::OpenThreads;
+        let open: Vec<i64> = model
+            .filtered_reviews()
+            .iter()
+            .map(|r| r.open_thread_count)
+            .collect();
+        assert!(open.windows(2).all(|w| w[0] >= w[1]));
+
+        model.review_sort = ReviewSort::Files;
+        let files = |model: &Model| -> Vec<usize> {
+            model
+                .filtered_reviews()
+                .iter()
+                .filter_map(|r| model.review_stats(r).map(|stats| stats.files))
+                .collect()
+        };
+        assert!(files(&model).windows(2).all(|w| w[0] >= w[1]));
+        model.config.review_sort_reversed = Some(true);
+        assert!(files(&model).windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_review_sort_by_author_and_creation_with_status_groups() {
+        let review = |id: &str, status: &str, author: &str, created: Option<&str>| ReviewSummary {
+            review_id: id.to_string(),
+            title: id.to_string(),
+            author: author.to_string(),
+            status: status.to_string(),
+            thread_count: 0,
+            open_thread_count: 0,
+            reviewers: Vec::new(),
+            created_at: created.map(str::to_string),
+            updated_at: None,
+            diff_stats: None,
+        };
+        let config = UiConfig {
+            review_sort: Some("Author".to_string()),
+            ..UiConfig::default()
+        };
+        let mut model = Model::new(80, 24, config);
+        model.reviews = vec![
+            review("cr-1", "merged", "alice", None),
+            review("cr-2", "open", "Carol", Some("2026-01-02T00:00:00Z")),
+            review("cr-3", "open", "bob", Some("2026-01-01T00:00:00Z")),
+            review("cr-4", "merged", "dave", Some("2026-01-03T00:00:00Z")),
+        ];
+        let ids = |model: &Model| -> Vec<String> {
+            model
+                .filtered_reviews()
+                .iter()
+                .map(|r| r.review_id.clone())
+                .collect()
+        };
+
+        assert_eq!(model.review_sort, ReviewSort::Author);
+        assert_eq!(ids(&model), ["cr-1", "cr-3", "cr-2", "cr-4"]);
+        model.review_sort = ReviewSort::Created;
+        assert_eq!(ids(&model), ["cr-4", "cr-2", "cr-3", "cr-1"]);
+
+        model.config.group_reviews_by_status = Some(true);
+        assert_eq!(ids(&model), ["cr-4", "cr-1", "cr-2", "cr-3"]);
+        model.review_sort = ReviewSort::Author;
+        assert_eq!(ids(&model), ["cr-1", "cr-4", "cr-3", "cr-2"]);
+    }
+
+    #[test]
+    fn test_plain_files_and_no_syntax_skip_highlighting() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        model.file_cache.insert(
+            "src/lib.rs".to_string(),
+            FileCacheEntry {
+                diff: Some(ParsedDiff::parse(
+                    "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,1 +1,1 @@\n-fn old() {}\n+fn new() {}\n",
+                )),
+                file_content: None,
+                highlighted_lines: Vec::new(),
+                file_highlighted_lines: Vec::new(),
+                word_lines: None,
+                normalized: false,
+                revision: 0,
+            },
+        );
+        let highlighted =
+            |model: &Model| !model.file_cache["src/lib.rs"].highlighted_lines.is_empty();
+
+        model.highlight_file("src/lib.rs");
+        assert!(highlighted(&model));
+
+        model.plain_files.insert("src/lib.rs".to_string());
+        let revision = model.file_cache["src/lib.rs"].revision;
+        model.highlight_file("src/lib.rs");
+        assert!(!highlighted(&model));
+        assert_ne!(model.file_cache["src/lib.rs"].revision, revision);
+
+        model.plain_files.clear();
+        model.syntax = false;
+        model.highlight_file("src/lib.rs");
+        assert!(!highlighted(&model));
+        model.syntax = true;
+        model.highlight_file("src/lib.rs");
+        assert!(highlighted(&model));
+    }
+
+    #[test]
+    fn test_target_excerpt_prefers_diff_then_content() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        model.file_cache.insert(
+            "src/lib.rs".to_string(),
+            FileCacheEntry {
+                diff: Some(ParsedDiff::parse(
+                    "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -10,2 +10,2 @@\n ten\n-old\n+eleven\n",
+                )),
+                file_content: Some(FileContent::new(
+                    (1..=20).map(|n| format!("line {n}")).collect(),
+                    1,
+                )),
+                highlighted_lines: Vec::new(),
+                file_highlighted_lines: Vec::new(),
+                word_lines: None,
+                normalized: false,
+                revision: 0,
+            },
+        );
+
+        assert_eq!(
+            model.target_excerpt("src/lib.rs", ThreadSide::New, 10, Some(14)),
+            ["ten", "eleven", "line 12", "line 13", "line 14"]
+        );
+        // A long selection keeps its first lines and counts the rest
+        let mut range = request(None);
+        range.start_line = 2;
+        range.end_line = Some(19);
+        range.excerpt = model.target_excerpt("src/lib.rs", ThreadSide::New, 2, Some(19));
+        assert_eq!(range.excerpt.len(), COMMENT_EXCERPT_LINES);
+        assert_eq!(range.excerpt_hidden_lines(), 18 - COMMENT_EXCERPT_LINES);
+        assert_eq!(
+            model.target_excerpt("src/lib.rs", ThreadSide::New, 20, None),
+            ["line 20"]
+        );
+        assert!(model
+            .target_excerpt("src/lib.rs", ThreadSide::New, 21, None)
+            .is_empty());
+        // Old-side text only comes from the diff
+        assert_eq!(
+            model.target_excerpt("src/lib.rs", ThreadSide::Old, 10, Some(12)),
+            ["ten", "old"]
+        );
+    }
+
+    #[test]
+    fn test_orphaned_context_window_spans_unplaced_threads() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        model.config.context_lines = Some(2);
+        let thread = |id: &str, start: i64, end: Option<i64>| ThreadSummary {
+            thread_id: id.to_string(),
+            file_path: "src/lib.rs".to_string(),
+            selection_start: start,
+            selection_end: end,
+            side: Some(ThreadSide::New),
+            status: "open".to_string(),
+            comment_count: 1,
+        };
+        // One in the hunk, two far from it and one on the whole file
+        model.threads = vec![
+            thread("th-hunk", 11, None),
+            thread("th-stale", 400, Some(402)),
+            thread("th-early", 2, None),
+            thread("th-file", crate::db::FILE_THREAD_LINE, None),
+        ];
+        let mut entry = FileCacheEntry {
+            diff: Some(ParsedDiff::parse(
+                "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -10,2 +10,2 @@\n ten\n-old\n+eleven\n",
+            )),
+            file_content: None,
+            highlighted_lines: Vec::new(),
+            file_highlighted_lines: Vec::new(),
+            word_lines: None,
+            normalized: false,
+            revision: 0,
+        };
+        assert_eq!(
+            model.orphaned_context_window("src/lib.rs", &entry),
+            Some(1..=404)
+        );
+        model.threads.remove(2);
+        assert_eq!(
+            model.orphaned_context_window("src/lib.rs", &entry),
+            Some(398..=404)
+        );
+        // Content crit sent already covers them
+        entry.file_content = Some(FileContent::new(Vec::new(), 1));
+        assert_eq!(model.orphaned_context_window("src/lib.rs", &entry), None);
+    }
+}
@@ -0,0 +1,199 @@
+//! CODEOWNERS-style file ownership, used to flag reviews that touch files
+//! the user owns.
+//!
+//! Each non-comment line is a pattern followed by owners; the last matching
+//! line wins. Patterns follow CODEOWNERS (gitignore) rules: a leading or
+//! inner `/` anchors to the repo root, a trailing `/` matches directories
+//! only, `*`/`?` stay within a path segment and `**` spans segments. A
+//! pattern that matches a directory owns everything under it.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+/// Where a repository keeps its CODEOWNERS file, in lookup order.
+const DEFAULT_LOCATIONS: [&str; 4] = [
+    "CODEOWNERS",
+    ".github/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+    "docs/CODEOWNERS",
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct Codeowners {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: String,
+    owners: Vec<String>,
+}
+
+impl Codeowners {
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let rules = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let pattern = fields.next()?.to_string();
+                let owners = fields
+                    .take_while(|field| !field.starts_with('#'))
+                    .map(str::to_string)
+                    .collect();
+                Some(Rule { pattern, owners })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Read the mapping from `configured` (relative paths resolve against
+    /// `repo_root`), or from the first standard location in `repo_root`.
+    /// `Ok(None)` when nothing is configured and the repo has no file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but can't be read.
+    pub fn load(
+        repo_root: Option<&Path>,
+        configured: Option<&str>,
+    ) -> anyhow::Result<Option<Self>> {
+        let path = if let Some(configured) = configured {
+            let path = PathBuf::from(configured);
+            match repo_root {
+                Some(root) if path.is_relative() => root.join(path),
+                _ => path,
+            }
+        } else {
+            let Some(found) = repo_root.and_then(|root| {
+                DEFAULT_LOCATIONS
+                    .iter()
+                    .map(|location| root.join(location))
+                    .find(|path| path.is_file())
+            }) else {
+                return Ok(None);
+            };
+            found
+        };
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read owners file: {}", path.display()))?;
+        Ok(Some(Self::parse(&text)))
+    }
+
+    /// Owners of `path` (repo-relative), empty if no rule covers it.
+    #[must_use]
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| pattern_matches(&rule.pattern, path))
+            .map_or(&[], |rule| rule.owners.as_slice())
+    }
+
+    /// Whether any of `names` owns `path`. Names compare without a leading
+    /// `@` and ignoring case, so `bob` matches `@Bob`.
+    #[must_use]
+    pub fn is_owned_by(&self, path: &str, names: &[String]) -> bool {
+        let normalize = |name: &str| name.trim_start_matches('@').to_lowercase();
+        self.owners_of(path).iter().any(|owner| {
+            let owner = normalize(owner);
+            names.iter().any(|name| normalize(name) == owner)
+        })
+    }
+}
+
+/// Whether a CODEOWNERS pattern covers `path` (also used by the review
+/// list's `path:` filter).
+#[must_use]
+pub fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let dir_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_matches('/');
+    if trimmed.is_empty() {
+        return false;
+    }
+    let anchored = pattern.starts_with('/') || trimmed.contains('/');
+    let pattern: Vec<&str> = trimmed.split('/').collect();
+    let path: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    if anchored {
+        segments_match(&pattern, &path, dir_only)
+    } else {
+        (0..path.len()).any(|start| segments_match(&pattern, &path[start..], dir_only))
+    }
+}
+
+/// Match pattern segments against the start of `path`. Running out of
+/// pattern with path left over means a directory matched.
+fn segments_match(pattern: &[&str], path: &[&str], dir_only: bool) -> bool {
+    match pattern.split_first() {
+        None => !path.is_empty() || !dir_only,
+        Some((&"**", rest)) => {
+            (0..=path.len()).any(|skip| segments_match(rest, &path[skip..], dir_only))
+        }
+        Some((segment, rest)) => path.split_first().is_some_and(|(name, path)| {
+            glob_match(segment, name) && segments_match(rest, path, dir_only)
+        }),
+    }
+}
+
+/// `*` and `?` wildcards within one path segment.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            p += 1;
+            backtrack = Some((p, t));
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p;
+            t = star_t + 1;
+            backtrack = Some((star_p, t));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_follow_codeowners_rules() {
+        assert!(pattern_matches("*.rs", "src/db/mod.rs"));
+        assert!(!pattern_matches("*.rs", "src/db/mod.rs.orig"));
+        assert!(pattern_matches("db/", "src/db/mod.rs"));
+        assert!(!pattern_matches("/db/", "src/db/mod.rs"));
+        assert!(pattern_matches("src/db", "src/db/mod.rs"));
+        assert!(!pattern_matches("src/db", "lib/src/db/mod.rs"));
+        assert!(pattern_matches("docs/**/*.md", "docs/guides/setup.md"));
+        assert!(pattern_matches("docs/**/*.md", "docs/setup.md"));
+        assert!(pattern_matches("/src/api?.rs", "src/api2.rs"));
+        assert!(!pattern_matches("README.md/", "README.md"));
+    }
+
+    #[test]
+    fn test_last_matching_rule_wins() {
+        let owners = Codeowners::parse(
+            "# Default owners\n\
+             *            @alice\n\
+             /src/db/     @Bob @db-team  # storage\n\
+             /src/db/legacy.rs\n",
+        );
+        let me = vec!["bob".to_string()];
+        assert_eq!(owners.owners_of("README.md"), ["@alice"]);
+        assert!(owners.is_owned_by("src/db/pool.rs", &me));
+        assert!(!owners.is_owned_by("src/db/legacy.rs", &me));
+        assert!(owners.owners_of("src/db/legacy.rs").is_empty());
+        assert!(!owners.is_owned_by("src/api/server.rs", &me));
+    }
+}
@@ -0,0 +1,158 @@
+//! Review list preview pane (`p`): the highlighted review's description,
+//! changed files and open threads, on the right half of the list.
+//!
+//! The main loop loads the highlighted review's data when it has no preview
+//! yet, one per iteration like the file scan. Previews are kept per review
+//! id until the list refreshes or the review is opened.
+
+use crate::db::{thread_location, DiffStats, FileData, ReviewData};
+use crate::diff::{DiffLineKind, ParsedDiff};
+
+/// A changed file and its lines added and removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewFile {
+    pub path: String,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// An open thread: where it is and how it starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewThread {
+    /// `path:line`, or empty for a review-level thread
+    pub location: String,
+    pub author: String,
+    /// First line of the first comment
+    pub summary: String,
+    pub comment_count: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReviewPreview {
+    pub description: Option<String>,
+    pub files: Vec<PreviewFile>,
+    pub open_threads: Vec<PreviewThread>,
+    /// Why the review couldn't be loaded
+    pub error: Option<String>,
+}
+
+impl ReviewPreview {
+    #[must_use]
+    pub fn from_data(data: &ReviewData) -> Self {
+        let files = data
+            .files
+            .iter()
+            .filter(|file| !file.path.starts_with(".crit/"))
+            .filter_map(|file| {
+                let (added, removed) = line_counts(file.diff.as_deref()?);
+                Some(PreviewFile {
+                    path: file.path.clone(),
+                    added,
+                    removed,
+                })
+            })
+            .collect();
+        let open_threads = data
+            .threads
+            .iter()
+            .filter(|thread| thread.status == "open")
+            .map(|thread| {
+                let first = data
+                    .comments
+                    .get(&thread.thread_id)
+                    .and_then(|comments| comments.first());
+                PreviewThread {
+                    location: if thread.is_review_thread() {
+                        String::new()
+                    } else {
+                        thread_location(
+                            &thread.file_path,
+                            thread.selection_start,
+                            thread.selection_end,
+                        )
+                    },
+                    author: first.map(|c| c.author.clone()).unwrap_or_default(),
+                    summary: first
+                        .and_then(|c| c.body.lines().find(|line| !line.trim().is_empty()))
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                    comment_count: usize::try_from(thread.comment_count).unwrap_or(0),
+                }
+            })
+            .collect();
+        Self {
+            description: data
+                .detail
+                .description
+                .as_ref()
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty()),
+            files,
+            open_threads,
+            error: None,
+        }
+    }
+
+    #[must_use]
+    pub fn failed(error: String) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::default()
+        }
+    }
+}
+
+/// Lines added and removed by one file's diff.
+fn line_counts(diff: &str) -> (usize, usize) {
+    let diff = ParsedDiff::parse(diff);
+    let count = |kind| {
+        diff.hunks
+            .iter()
+            .flat_map(|hunk| &hunk.lines)
+            .filter(|line| line.kind == kind)
+            .count()
+    };
+    (count(DiffLineKind::Added), count(DiffLineKind::Removed))
+}
+
+/// Changed files and lines added and removed across `files`, leaving out
+/// crit's own `.crit/` files like the preview does.
+#[must_use]
+pub fn diff_stats(files: &[FileData]) -> DiffStats {
+    files
+        .iter()
+        .filter(|file| !file.path.starts_with(".crit/"))
+        .filter_map(|file| file.diff.as_deref())
+        .map(line_counts)
+        .fold(DiffStats::default(), |stats, (added, removed)| DiffStats {
+            files: stats.files + 1,
+            added: stats.added + added,
+            removed: stats.removed + removed,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::CritClient;
+    use crate::demo_client::{DemoClient, DemoSize, DEFAULT_DEMO_SEED};
+
+    #[test]
+    fn test_preview_summarizes_files_and_open_threads() {
+        let client = DemoClient::new(DemoSize::Small, DEFAULT_DEMO_SEED);
+        let review = &client.list_reviews(None).unwrap()[0];
+        let data = client.load_review_data(&review.review_id).unwrap().unwrap();
+        let preview = ReviewPreview::from_data(&data);
+
+        let changed = data.files.iter().filter(|f| f.diff.is_some()).count();
+        assert_eq!(preview.files.len(), changed);
+        assert!(preview.files.iter().all(|f| f.added + f.removed > 0));
+        let open = data.threads.iter().filter(|t| t.status == "open").count();
+        assert_eq!(preview.open_threads.len(), open);
+        assert!(preview
+            .open_threads
+            .iter()
+            .all(|t| !t.summary.is_empty() && !t.summary.contains('\n')));
+    }
+}
@@ -0,0 +1,319 @@
+//! Input latency per message type.
+//!
+//! Each input event is a span from when it was read to the end of the next
+//! frame and the effects `main` runs after it (loading a review,
+//! re-highlighting after `ToggleDiffView`). Inside it are phases: the
+//! message's own `update`, then the frame's render and effects. Several
+//! events handled before one frame all end at that frame and share its
+//! render and effects.
+//!
+//! The newest spans and a per-message table show in the input latency log
+//! (command palette). With `--profile-input`, the table is also printed to
+//! stderr on exit, slowest total first.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+
+use crate::message::Message;
+
+/// Samples kept per message type; later ones still count toward the mean
+/// and max.
+const MAX_SAMPLES: usize = 4096;
+
+/// Spans kept for the log; older ones drop off.
+const MAX_SPANS: usize = 500;
+
+/// A part of a span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// The message's own `update`
+    Update,
+    /// Drawing and presenting the frame
+    Render,
+    /// What `main` ran after the frame: writes, loads, highlighting
+    Effects,
+}
+
+impl Phase {
+    const ALL: [Self; 3] = [Self::Update, Self::Render, Self::Effects];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Update => "update",
+            Self::Render => "render",
+            Self::Effects => "effects",
+        }
+    }
+}
+
+/// One timed input event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// Message variant, as [`message_kind`]
+    pub kind: String,
+    /// Read to end of effects
+    pub took: Duration,
+    /// Time in each [`Phase`], in [`Phase::ALL`] order
+    pub phases: [Duration; 3],
+}
+
+impl Span {
+    /// One log line: message, total, then each phase.
+    #[must_use]
+    pub fn line(&self) -> String {
+        let mut out = format!("{:<28}{:>9}", self.kind, format_ms(self.took));
+        for (phase, took) in Phase::ALL.iter().zip(self.phases) {
+            let _ = write!(out, "  {} {}", phase.name(), format_ms(took));
+        }
+        out
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Latencies {
+    count: u32,
+    total: Duration,
+    max: Duration,
+    samples: Vec<Duration>,
+}
+
+impl Latencies {
+    fn record(&mut self, took: Duration) {
+        self.count += 1;
+        self.total += took;
+        self.max = self.max.max(took);
+        if self.samples.len() < MAX_SAMPLES {
+            self.samples.push(took);
+        }
+    }
+
+    fn mean(&self) -> Duration {
+        self.total / self.count.max(1)
+    }
+
+    fn p95(&self) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort_unstable();
+        let index = (sorted.len() * 95).div_ceil(100).saturating_sub(1);
+        sorted.get(index).copied().unwrap_or_default()
+    }
+}
+
+/// A message waiting for its frame.
+#[derive(Debug, Clone)]
+struct Waiting {
+    kind: String,
+    /// When its event was read
+    read: Instant,
+    update: Duration,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InputProfile {
+    by_message: HashMap<String, Latencies>,
+    waiting: Vec<Waiting>,
+    /// Render and effects time of the frame under way
+    frame: [Duration; 2],
+    /// Newest last
+    spans: VecDeque<Span>,
+}
+
+impl InputProfile {
+    /// Start timing `msg`, whose event was read at `at`. Returns whether it
+    /// is timed; `Noop` (an ignored event) isn't, so its update mustn't be
+    /// added as a phase.
+    pub fn start(&mut self, msg: &Message, at: Instant) -> bool {
+        if matches!(msg, Message::Noop) {
+            return false;
+        }
+        self.waiting.push(Waiting {
+            kind: message_kind(msg),
+            read: at,
+            update: Duration::ZERO,
+        });
+        true
+    }
+
+    /// Add `took` to `phase` of the waiting messages: their update if it's
+    /// [`Phase::Update`] (the last one started), else the frame's. A frame
+    /// no timed message waits for isn't counted.
+    pub fn phase(&mut self, phase: Phase, took: Duration) {
+        if self.waiting.is_empty() {
+            return;
+        }
+        match phase {
+            Phase::Update => {
+                if let Some(waiting) = self.waiting.last_mut() {
+                    waiting.update += took;
+                }
+            }
+            Phase::Render => self.frame[0] += took,
+            Phase::Effects => self.frame[1] += took,
+        }
+    }
+
+    /// A frame and its effects finished at `at`: stop timing everything
+    /// waiting for it.
+    pub fn frame_done(&mut self, at: Instant) {
+        let [render, effects] = std::mem::take(&mut self.frame);
+        for waiting in self.waiting.drain(..) {
+            let took = at.saturating_duration_since(waiting.read);
+            self.by_message
+                .entry(waiting.kind.clone())
+                .or_default()
+                .record(took);
+            if self.spans.len() == MAX_SPANS {
+                self.spans.pop_front();
+            }
+            self.spans.push_back(Span {
+                kind: waiting.kind,
+                took,
+                phases: [waiting.update, render, effects],
+            });
+        }
+    }
+
+    /// Timed spans, newest last.
+    #[must_use]
+    pub fn spans(&self) -> impl DoubleEndedIterator<Item = &Span> {
+        self.spans.iter()
+    }
+
+    /// The log viewer's lines: the summary table, then spans newest first.
+    #[must_use]
+    pub fn log_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.summary().lines().map(str::to_string).collect();
+        lines.push(String::new());
+        lines.extend(self.spans().rev().map(Span::line));
+        lines
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.by_message.is_empty()
+    }
+
+    /// A table of count, mean, p95 and max per message type.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut rows: Vec<_> = self.by_message.iter().collect();
+        rows.sort_by(|a, b| b.1.total.cmp(&a.1.total).then_with(|| a.0.cmp(b.0)));
+        let mut out = String::from("Input latency, event to frame and effects:\n");
+        let _ = writeln!(
+            out,
+            "  {:<28}{:>7}{:>11}{:>11}{:>11}",
+            "message", "count", "mean", "p95", "max"
+        );
+        for (kind, latencies) in rows {
+            let _ = writeln!(
+                out,
+                "  {kind:<28}{:>7}{:>11}{:>11}{:>11}",
+                latencies.count,
+                format_ms(latencies.mean()),
+                format_ms(latencies.p95()),
+                format_ms(latencies.max),
+            );
+        }
+        out
+    }
+}
+
+/// Variant name of `msg`, e.g. `ScrollDown` for `ScrollDown` and
+/// `SelectReview` for `SelectReview("cr-1")`.
+#[must_use]
+pub fn message_kind(msg: &Message) -> String {
+    let debug = format!("{msg:?}");
+    let end = debug
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(debug.len());
+    debug[..end].to_string()
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_before_a_frame_end_at_that_frame() {
+        let mut profile = InputProfile::default();
+        let t0 = Instant::now();
+        profile.start(&Message::ScrollDown, t0);
+        profile.start(&Message::ScrollDown, t0 + Duration::from_millis(10));
+        profile.start(&Message::ToggleDiffView, t0 + Duration::from_millis(10));
+        profile.start(&Message::Noop, t0);
+        profile.phase(Phase::Render, Duration::from_millis(4));
+        profile.phase(Phase::Effects, Duration::from_millis(6));
+        profile.frame_done(t0 + Duration::from_millis(30));
+        profile.frame_done(t0 + Duration::from_millis(90));
+
+        let scroll = &profile.by_message["ScrollDown"];
+        assert_eq!(scroll.count, 2);
+        assert_eq!(scroll.mean(), Duration::from_millis(25));
+        assert_eq!(scroll.max, Duration::from_millis(30));
+        assert_eq!(scroll.p95(), Duration::from_millis(30));
+        assert!(!profile.by_message.contains_key("Noop"));
+        // The frame's render and effects land in every span that ended there
+        let spans: Vec<_> = profile.spans().collect();
+        assert_eq!(spans.len(), 3);
+        assert!(spans
+            .iter()
+            .all(|span| span.phases[1..] == [Duration::from_millis(4), Duration::from_millis(6)]));
+
+        let summary = profile.summary();
+        // 50ms of scrolling sorts above one 20ms toggle
+        let toggle = summary.find("ToggleDiffView").unwrap();
+        assert!(summary.find("ScrollDown").unwrap() < toggle);
+        assert_eq!(
+            message_kind(&Message::SelectReview("cr-1".into())),
+            "SelectReview"
+        );
+    }
+
+    #[test]
+    fn test_update_time_goes_to_the_last_message_started() {
+        let mut profile = InputProfile::default();
+        let t0 = Instant::now();
+        profile.start(&Message::ScrollDown, t0);
+        profile.phase(Phase::Update, Duration::from_millis(1));
+        profile.start(&Message::ToggleDiffView, t0);
+        profile.phase(Phase::Update, Duration::from_millis(8));
+        // Not timed, so its update isn't added to the toggle's
+        assert!(!profile.start(&Message::Noop, t0));
+        profile.frame_done(t0 + Duration::from_millis(20));
+
+        let lines = profile.log_lines();
+        // Newest first, after the summary
+        let toggle = lines
+            .iter()
+            .position(|l| l.contains("update 8.0ms"))
+            .unwrap();
+        let scroll = lines
+            .iter()
+            .position(|l| l.contains("update 1.0ms"))
+            .unwrap();
+        assert!(lines[toggle].starts_with("ToggleDiffView"));
+        assert!(toggle < scroll);
+        assert_eq!(lines[toggle - 1], "");
+    }
+
+    #[test]
+    fn test_frames_without_timed_messages_are_not_counted() {
+        let mut profile = InputProfile::default();
+        let t0 = Instant::now();
+        profile.phase(Phase::Render, Duration::from_millis(50));
+        profile.phase(Phase::Effects, Duration::from_millis(50));
+        profile.frame_done(t0);
+
+        assert!(profile.start(&Message::ScrollDown, t0));
+        profile.phase(Phase::Render, Duration::from_millis(2));
+        profile.frame_done(t0 + Duration::from_millis(5));
+        let span = profile.spans().next().unwrap();
+        assert_eq!(span.phases[1..], [Duration::from_millis(2), Duration::ZERO]);
+    }
+}
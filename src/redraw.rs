@@ -0,0 +1,124 @@
+//! Keeping frames small over slow links (`redraw` in `ui.json`).
+//!
+//! Frames already only carry the cells that changed (`Renderer::present`),
+//! but moving the diff cursor still repaints two full-width rows. In lean
+//! mode the cursor row isn't highlighted; a marker in the left gutter moves
+//! instead, a couple of cells per step. `auto` turns lean mode on while
+//! writing frames to the terminal is slow, which over SSH means the link is
+//! backing up.
+
+use std::time::Duration;
+
+/// How the diff cursor is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RedrawMode {
+    /// Lean while frames are slow to write
+    #[default]
+    Auto,
+    /// Always highlight the cursor row
+    Full,
+    /// Always mark the cursor in the gutter
+    Lean,
+}
+
+impl RedrawMode {
+    /// Parse a config name: `auto`, `full` or `lean`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "full" => Some(Self::Full),
+            "lean" => Some(Self::Lean),
+            _ => None,
+        }
+    }
+}
+
+/// A frame that took longer than this to write counts as slow.
+const SLOW_PRESENT: Duration = Duration::from_millis(40);
+/// Slow frames, out of the last 8, that switch `auto` to lean.
+const SLOW_TO_LEAN: u32 = 3;
+/// Fast frames in a row that switch `auto` back.
+const FAST_TO_FULL: u32 = 50;
+
+/// Picks lean mode from how long recent frames took to write.
+#[derive(Debug, Clone, Default)]
+pub struct LinkMonitor {
+    mode: RedrawMode,
+    /// One bit per recent frame, newest lowest; set when slow
+    recent: u8,
+    fast_streak: u32,
+    lean: bool,
+}
+
+impl LinkMonitor {
+    #[must_use]
+    pub fn new(mode: RedrawMode) -> Self {
+        Self {
+            mode,
+            lean: mode == RedrawMode::Lean,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the cursor should be drawn lean.
+    #[must_use]
+    pub const fn lean(&self) -> bool {
+        self.lean
+    }
+
+    /// Note how long a frame took to write. Returns whether [`Self::lean`]
+    /// changed.
+    pub fn record(&mut self, took: Duration) -> bool {
+        if self.mode != RedrawMode::Auto {
+            return false;
+        }
+        let slow = took >= SLOW_PRESENT;
+        self.recent = (self.recent << 1) | u8::from(slow);
+        self.fast_streak = if slow { 0 } else { self.fast_streak + 1 };
+        let was = self.lean;
+        if self.recent.count_ones() >= SLOW_TO_LEAN {
+            self.lean = true;
+        } else if self.fast_streak >= FAST_TO_FULL {
+            self.lean = false;
+        }
+        self.lean != was
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_goes_lean_on_slow_frames_and_back() {
+        let slow = SLOW_PRESENT * 2;
+        let fast = Duration::from_millis(1);
+        let mut monitor = LinkMonitor::new(RedrawMode::Auto);
+        assert!(!monitor.record(slow));
+        assert!(!monitor.record(fast));
+        assert!(!monitor.record(slow));
+        assert!(monitor.record(slow));
+        assert!(monitor.lean());
+
+        for _ in 1..FAST_TO_FULL {
+            assert!(!monitor.record(fast));
+        }
+        assert!(monitor.record(fast));
+        assert!(!monitor.lean());
+    }
+
+    #[test]
+    fn test_fixed_modes_ignore_timing() {
+        let mut full = LinkMonitor::new(RedrawMode::Full);
+        let mut lean = LinkMonitor::new(RedrawMode::Lean);
+        for _ in 0..8 {
+            assert!(!full.record(SLOW_PRESENT));
+            assert!(!lean.record(Duration::ZERO));
+        }
+        assert!(!full.lean());
+        assert!(lean.lean());
+        assert_eq!(RedrawMode::from_name(" Lean"), Some(RedrawMode::Lean));
+        assert_eq!(RedrawMode::from_name("fast"), None);
+    }
+}
@@ -0,0 +1,118 @@
+//! Auto-refresh scheduling: fixed interval, exponential backoff with jitter
+//! after failed polls.
+
+use std::time::{Duration, Instant};
+
+use crate::glyphs::glyphs;
+
+/// Failed polls beyond this many don't lengthen the delay further (2^4 = 16x).
+const MAX_BACKOFF_SHIFT: u32 = 4;
+/// Delays are spread by up to this fraction either way, so several
+/// instances started together don't poll crit in lockstep.
+const JITTER: f64 = 0.1;
+/// How long the header spinner stays up after a poll starts, so a fast poll
+/// is still visible.
+pub const SPINNER_MIN: Duration = Duration::from_millis(600);
+
+const SPINNER_FRAME_MS: u128 = 80;
+
+#[derive(Debug, Clone)]
+pub struct RefreshSchedule {
+    interval: Duration,
+    failures: u32,
+    next_at: Instant,
+    /// xorshift state for jitter
+    seed: u64,
+}
+
+impl RefreshSchedule {
+    #[must_use]
+    pub fn new(interval: Duration, now: Instant, seed: u64) -> Self {
+        let mut schedule = Self {
+            interval,
+            failures: 0,
+            next_at: now,
+            seed: seed | 1,
+        };
+        schedule.next_at = now + schedule.next_delay();
+        schedule
+    }
+
+    #[must_use]
+    pub fn is_due(&self, now: Instant) -> bool {
+        now >= self.next_at
+    }
+
+    #[must_use]
+    pub const fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    pub fn record_success(&mut self, now: Instant) {
+        self.failures = 0;
+        self.next_at = now + self.next_delay();
+    }
+
+    pub fn record_failure(&mut self, now: Instant) {
+        self.failures = self.failures.saturating_add(1);
+        self.next_at = now + self.next_delay();
+    }
+
+    /// Interval scaled by the backoff factor, then jittered.
+    fn next_delay(&mut self) -> Duration {
+        let base = self.interval * (1 << self.failures.min(MAX_BACKOFF_SHIFT));
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        #[allow(clippy::cast_precision_loss)]
+        let unit = (self.seed >> 11) as f64 / (1u64 << 53) as f64;
+        base.mul_f64(JITTER.mul_add(unit.mul_add(2.0, -1.0), 1.0))
+    }
+}
+
+/// Spinner frame for a poll that started at `started`, or `None` once it has
+/// been shown for `SPINNER_MIN`.
+#[must_use]
+pub fn spinner_frame(started: Instant) -> Option<&'static str> {
+    (crate::clock::elapsed(started) < SPINNER_MIN).then(|| spinner_at(started))
+}
+
+/// Spinner frame for something that has been going since `started`.
+#[must_use]
+pub fn spinner_at(started: Instant) -> &'static str {
+    let frames = glyphs().spinner;
+    frames[(crate::clock::elapsed(started).as_millis() / SPINNER_FRAME_MS) as usize % frames.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delay(schedule: &RefreshSchedule, now: Instant) -> Duration {
+        schedule.next_at - now
+    }
+
+    #[test]
+    fn test_backoff_grows_caps_and_resets() {
+        let now = Instant::now();
+        let interval = Duration::from_secs(10);
+        let mut schedule = RefreshSchedule::new(interval, now, 42);
+        let within = |d: Duration, base: Duration| {
+            d >= base.mul_f64(1.0 - JITTER) && d <= base.mul_f64(1.0 + JITTER)
+        };
+        assert!(within(delay(&schedule, now), interval));
+        assert!(!schedule.is_due(now));
+
+        schedule.record_failure(now);
+        assert!(within(delay(&schedule, now), interval * 2));
+        for _ in 0..10 {
+            schedule.record_failure(now);
+        }
+        assert!(within(delay(&schedule, now), interval * 16));
+
+        schedule.record_success(now);
+        assert_eq!(schedule.failures(), 0);
+        assert!(within(delay(&schedule, now), interval));
+        assert!(schedule.is_due(now + interval * 2));
+    }
+}
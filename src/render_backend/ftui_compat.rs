@@ -3,6 +3,7 @@ use std::io;
 use ftui_core::event as ft;
 use ftui_render::cell::PackedRgba;
 use palette::{Mix, Srgba};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rgba {
@@ -140,7 +141,8 @@ impl KeyEvent {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MouseButton {
     Left,
     Middle,
@@ -148,7 +150,8 @@ pub enum MouseButton {
     None,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MouseEventKind {
     Press,
     Release,
@@ -159,14 +162,17 @@ pub enum MouseEventKind {
     ScrollRight,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MouseEvent {
     pub x: u32,
     pub y: u32,
     pub button: MouseButton,
     pub kind: MouseEventKind,
+    #[serde(default)]
     pub shift: bool,
+    #[serde(default)]
     pub ctrl: bool,
+    #[serde(default)]
     pub alt: bool,
 }
 
@@ -216,7 +222,7 @@ impl PasteEvent {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ResizeEvent {
     pub width: u16,
     pub height: u16,
@@ -606,9 +612,38 @@ pub struct RendererOptions {
     pub query_capabilities: bool,
 }
 
+/// Cells of a frame that differ from the last presented one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Damage {
+    /// Nothing on screen can be trusted: first frame, resize or
+    /// [`Renderer::invalidate`]. `spans` then cover every row.
+    pub full: bool,
+    /// `(y, x_start, x_end)`, end exclusive, at most one per row
+    pub spans: Vec<(u32, u32, u32)>,
+}
+
+impl Damage {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        !self.full && self.spans.is_empty()
+    }
+
+    /// Number of cells covered by the spans.
+    #[must_use]
+    pub fn cells(&self) -> usize {
+        self.spans
+            .iter()
+            .map(|&(_, start, end)| (end - start) as usize)
+            .sum()
+    }
+}
+
 #[derive(Debug)]
 pub struct Renderer {
     buffer: OptimizedBuffer,
+    /// The last presented frame, what the terminal shows
+    front: OptimizedBuffer,
+    full_damage: bool,
     background: Rgba,
     _options: RendererOptions,
 }
@@ -617,6 +652,8 @@ impl Renderer {
     pub fn new_with_options(width: u32, height: u32, options: RendererOptions) -> io::Result<Self> {
         let mut renderer = Self {
             buffer: OptimizedBuffer::new(width.max(1), height.max(1)),
+            front: OptimizedBuffer::new(width.max(1), height.max(1)),
+            full_damage: true,
             background: Rgba::BLACK,
             _options: options,
         };
@@ -637,15 +674,48 @@ impl Renderer {
         self.buffer.clear(self.background);
     }
 
-    pub fn invalidate(&mut self) {}
-
-    pub fn present(&mut self) -> io::Result<()> {
-        Ok(())
+    /// Make the next [`Renderer::present`] report the whole screen, e.g.
+    /// after the terminal was handed to another program.
+    pub fn invalidate(&mut self) {
+        self.full_damage = true;
+    }
+
+    /// Finish a frame: the cells that changed since the previous one, for
+    /// the terminal writer to emit. Unchanged frames give empty damage.
+    pub fn present(&mut self) -> Damage {
+        let width = self.buffer.width;
+        let full = std::mem::take(&mut self.full_damage);
+        let mut spans = Vec::new();
+        if width > 0 {
+            let rows = self.buffer.cells.chunks(width as usize);
+            let front_rows = self.front.cells.chunks(width as usize);
+            for (y, (row, front)) in rows.zip(front_rows).enumerate() {
+                if full {
+                    spans.push((y as u32, 0, width));
+                    continue;
+                }
+                let Some(start) = row.iter().zip(front).position(|(a, b)| a != b) else {
+                    continue;
+                };
+                let end = row.len()
+                    - row
+                        .iter()
+                        .zip(front)
+                        .rev()
+                        .position(|(a, b)| a != b)
+                        .unwrap_or(0);
+                spans.push((y as u32, start as u32, end as u32));
+            }
+        }
+        self.front.cells.clone_from(&self.buffer.cells);
+        Damage { full, spans }
     }
 
     pub fn resize(&mut self, width: u32, height: u32) -> io::Result<()> {
         self.buffer = OptimizedBuffer::new(width.max(1), height.max(1));
         self.buffer.clear(self.background);
+        self.front = OptimizedBuffer::new(width.max(1), height.max(1));
+        self.full_damage = true;
         Ok(())
     }
 
@@ -770,6 +840,21 @@ pub fn buffer_dim_cell_rgb(buffer: &mut OptimizedBuffer, x: u32, y: u32, scale:
     }
 }
 
+/// Recolor a drawn cell, keeping its content.
+pub fn buffer_recolor_cell(buffer: &mut OptimizedBuffer, x: u32, y: u32, fg: Rgba, bg: Rgba) {
+    if let Some(cell) = buffer.get_mut(x, y) {
+        cell.fg = fg;
+        cell.bg = bg;
+    }
+}
+
+/// Change a drawn cell's background, keeping its content and foreground.
+pub fn buffer_set_cell_bg(buffer: &mut OptimizedBuffer, x: u32, y: u32, bg: Rgba) {
+    if let Some(cell) = buffer.get_mut(x, y) {
+        cell.bg = bg;
+    }
+}
+
 #[must_use]
 pub fn rgba_to_packed(color: Rgba) -> PackedRgba {
     let (r, g, b, a) = color.to_rgba_u8();
@@ -118,4 +118,33 @@ mod tests {
             _ => panic!("expected resize event"),
         }
     }
+
+    #[test]
+    fn present_reports_only_changed_cells() {
+        use crate::render_backend::{buffer_draw_text, Renderer, RendererOptions, Style};
+
+        let options = RendererOptions {
+            use_alt_screen: false,
+            hide_cursor: false,
+            enable_mouse: false,
+            query_capabilities: false,
+        };
+        let mut renderer = Renderer::new_with_options(10, 3, options).unwrap();
+        let first = renderer.present();
+        assert!(first.full);
+        assert_eq!(first.cells(), 30);
+
+        renderer.clear();
+        assert!(renderer.present().is_empty());
+
+        renderer.clear();
+        buffer_draw_text(renderer.buffer(), 2, 1, "ab", Style::fg(Rgba::WHITE));
+        buffer_draw_text(renderer.buffer(), 6, 1, "c", Style::fg(Rgba::WHITE));
+        let damage = renderer.present();
+        assert!(!damage.full);
+        assert_eq!(damage.spans, [(1, 2, 7)]);
+
+        renderer.invalidate();
+        assert!(renderer.present().full);
+    }
 }
@@ -0,0 +1,269 @@
+//! Input scripts: `--record <path>` writes one, `--replay <path>` plays it.
+//!
+//! A script is a JSON array of steps, one per line, each an input and the
+//! milliseconds since start it arrived at:
+//!
+//! ```json
+//! [
+//! {"at_ms":0,"resize":{"width":120,"height":36}},
+//! {"at_ms":850,"key":"j"},
+//! {"at_ms":1400,"key":"ctrl+d"},
+//! {"at_ms":2100,"paste":"some text"},
+//! {"at_ms":2600,"mouse":{"x":40,"y":10,"button":"left","kind":"press"}},
+//! {"at_ms":3000,"focus":false}
+//! ]
+//! ```
+//!
+//! Keys use the chord syntax of `keys` in `ui.json`. A recording starts with
+//! the terminal's size. A replay runs on the virtual clock: each step waits
+//! for loading to settle, the clock moves to its time for one loop iteration
+//! (so timers due before it fire first), then the step goes in. The result
+//! depends only on the script, the data and the config, not on how fast the
+//! machine is.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::clock;
+use crate::highlight::HighlightWorker;
+use crate::inbox::Inbox;
+use crate::keymap::{key_name, parse_key};
+use crate::model::Model;
+use crate::render_backend::{Event, MouseEvent, PasteEvent, ResizeEvent};
+
+/// An input at a time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Step {
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub input: Input,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Input {
+    /// Chord in the `keys` syntax: `j`, `G`, `ctrl+d`, `enter`
+    Key(String),
+    Paste(String),
+    Mouse(MouseEvent),
+    Resize(ResizeEvent),
+    /// Terminal focus gained (`true`) or lost
+    Focus(bool),
+}
+
+impl Input {
+    /// `event` as a script input; `None` for a key with no chord name.
+    #[must_use]
+    pub fn of(event: &Event) -> Option<Self> {
+        Some(match event {
+            Event::Key(key) => Self::Key(key_name(key)?),
+            Event::Paste(paste) => Self::Paste(paste.text.clone()),
+            Event::Mouse(mouse) => Self::Mouse(*mouse),
+            Event::Resize(resize) => Self::Resize(*resize),
+            Event::FocusGained => Self::Focus(true),
+            Event::FocusLost => Self::Focus(false),
+        })
+    }
+
+    /// The terminal event this stands for.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for a key that isn't a valid chord.
+    pub fn event(&self) -> Result<Event> {
+        Ok(match self {
+            Self::Key(chord) => {
+                Event::Key(parse_key(chord).with_context(|| format!("Invalid key {chord:?}"))?)
+            }
+            Self::Paste(text) => Event::Paste(PasteEvent::new(text.clone())),
+            Self::Mouse(mouse) => Event::Mouse(*mouse),
+            Self::Resize(resize) => Event::Resize(*resize),
+            Self::Focus(true) => Event::FocusGained,
+            Self::Focus(false) => Event::FocusLost,
+        })
+    }
+}
+
+/// Parse a script.
+///
+/// # Errors
+///
+/// Returns an error if it isn't a JSON array of steps.
+pub fn parse(text: &str) -> Result<Vec<Step>> {
+    serde_json::from_str(text).context("Invalid replay script")
+}
+
+/// Write `steps` as a script, one step per line.
+#[must_use]
+pub fn format(steps: &[Step]) -> String {
+    let lines: Vec<String> = steps
+        .iter()
+        .filter_map(|step| serde_json::to_string(step).ok())
+        .collect();
+    format!("[\n{}\n]\n", lines.join(",\n"))
+}
+
+/// Whether nothing is still loading between frames, so the next step sees
+/// the same model however long loading took.
+#[must_use]
+pub fn settled(model: &Model) -> bool {
+    model.review_load.is_none()
+        && model.pending_files.is_empty()
+        && model.pending_thread_events.is_empty()
+        && model.pending_file_scan.is_empty()
+        && !model.pending_refresh
+        && model.preview_to_load().is_none()
+        && !model.inbox.as_ref().is_some_and(Inbox::is_loading)
+        && model
+            .highlight_worker
+            .as_ref()
+            .is_none_or(HighlightWorker::is_idle)
+}
+
+/// Inputs seen since start, for `--record`.
+pub struct Recorder {
+    path: PathBuf,
+    started: Instant,
+    steps: Vec<Step>,
+}
+
+impl Recorder {
+    /// Start recording in a `width` × `height` terminal.
+    #[must_use]
+    pub fn new(path: &Path, width: u16, height: u16) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            started: clock::now(),
+            steps: vec![Step {
+                at_ms: 0,
+                input: Input::Resize(ResizeEvent::new(width, height)),
+            }],
+        }
+    }
+
+    pub fn record(&mut self, event: &Event) {
+        if let Some(input) = Input::of(event) {
+            self.steps.push(Step {
+                at_ms: clock::elapsed(self.started).as_millis() as u64,
+                input,
+            });
+        }
+    }
+
+    /// Write the script.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(&self.path, format(&self.steps))
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+/// Steps still to play, for `--replay`.
+pub struct Player {
+    steps: VecDeque<(Duration, Event)>,
+    /// Where the virtual clock stands
+    now: Duration,
+    done: bool,
+}
+
+impl Player {
+    /// Load the script at `path` and stop the clock at its start.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or a step is invalid.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let steps = parse(&text)
+            .with_context(|| path.display().to_string())?
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                let event = step
+                    .input
+                    .event()
+                    .with_context(|| format!("Step {} of {}", index + 1, path.display()))?;
+                Ok((Duration::from_millis(step.at_ms), event))
+            })
+            .collect::<Result<_>>()?;
+        clock::set_virtual(Duration::ZERO);
+        Ok(Self {
+            steps,
+            now: Duration::ZERO,
+            done: false,
+        })
+    }
+
+    /// The next input, once the model has `settled` and the clock has
+    /// spent an iteration at its time. After the last one the clock runs
+    /// again and the player is done.
+    pub fn next(&mut self, settled: bool) -> Option<Event> {
+        if !settled || self.done {
+            return None;
+        }
+        let Some(&(at, _)) = self.steps.front() else {
+            self.done = true;
+            clock::resume();
+            return None;
+        };
+        if at > self.now {
+            self.now = at;
+            clock::set_virtual(at);
+            return None;
+        }
+        self.steps.pop_front().map(|(_, event)| event)
+    }
+
+    #[must_use]
+    pub const fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render_backend::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEventKind};
+
+    #[test]
+    fn test_script_inputs_round_trip_through_events() {
+        let script = r#"[
+            {"at_ms": 0, "resize": {"width": 120, "height": 36}},
+            {"at_ms": 850, "key": "j"},
+            {"at_ms": 1400, "key": "ctrl+d"},
+            {"at_ms": 1500, "key": "space"},
+            {"at_ms": 2100, "paste": "some text"},
+            {"at_ms": 2600, "mouse": {"x": 40, "y": 10, "button": "left", "kind": "press"}},
+            {"at_ms": 3000, "focus": false}
+        ]"#;
+        let steps = parse(script).unwrap();
+        assert_eq!(steps.len(), 7);
+        assert_eq!(
+            steps[2].input.event().unwrap(),
+            Event::Key(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CTRL))
+        );
+        assert_eq!(
+            steps[5].input.event().unwrap(),
+            Event::Mouse(MouseEvent::new(
+                40,
+                10,
+                MouseButton::Left,
+                MouseEventKind::Press
+            ))
+        );
+        for step in &steps {
+            let event = step.input.event().unwrap();
+            assert_eq!(Input::of(&event).as_ref(), Some(&step.input));
+        }
+        assert_eq!(parse(&format(&steps)).unwrap(), steps);
+        assert!(Input::Key("hyper+j".into()).event().is_err());
+    }
+}
@@ -0,0 +1,127 @@
+//! Finding the crit repo to open when `--path` isn't given.
+//!
+//! `CRIT_DIR` names it outright (the repo or its `.crit/`). Otherwise each
+//! directory from the cwd up is tried: one with a `.crit/` is the repo, and
+//! a git worktree or jj workspace without one uses its main workspace's,
+//! since that's where `crit` keeps the reviews.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+
+/// Environment variable naming the repo (or its `.crit/`) to open.
+pub const CRIT_DIR_ENV: &str = "CRIT_DIR";
+
+/// The repo to open from `start`, given `CRIT_DIR`'s value; `None` if
+/// there isn't one.
+///
+/// # Errors
+///
+/// Returns an error if `crit_dir` is set but has no `.crit/`.
+pub fn discover(start: &Path, crit_dir: Option<&OsStr>) -> Result<Option<PathBuf>> {
+    if let Some(dir) = crit_dir.filter(|dir| !dir.is_empty()).map(Path::new) {
+        if has_crit(dir) {
+            return Ok(Some(dir.to_path_buf()));
+        }
+        if dir.file_name() == Some(OsStr::new(".crit")) && dir.is_dir() {
+            return Ok(dir.parent().map(Path::to_path_buf));
+        }
+        bail!("{CRIT_DIR_ENV}={} has no .crit/ directory", dir.display());
+    }
+    Ok(start.ancestors().find_map(|dir| {
+        if has_crit(dir) {
+            return Some(dir.to_path_buf());
+        }
+        main_workspace(dir).filter(|main| has_crit(main))
+    }))
+}
+
+fn has_crit(dir: &Path) -> bool {
+    dir.join(".crit").is_dir()
+}
+
+/// The main workspace of the git worktree or jj workspace rooted at `dir`.
+fn main_workspace(dir: &Path) -> Option<PathBuf> {
+    git_main_worktree(dir).or_else(|| jj_main_workspace(dir))
+}
+
+/// A linked worktree's `.git` is a file, `gitdir: <main>/.git/worktrees/<name>`,
+/// and that directory's `commondir` leads back to `<main>/.git`.
+fn git_main_worktree(dir: &Path) -> Option<PathBuf> {
+    let link = std::fs::read_to_string(dir.join(".git")).ok()?;
+    let git_dir = dir.join(link.trim().strip_prefix("gitdir:")?.trim());
+    let common = std::fs::read_to_string(git_dir.join("commondir")).ok()?;
+    let common = std::fs::canonicalize(git_dir.join(common.trim())).ok()?;
+    common.parent().map(Path::to_path_buf)
+}
+
+/// A secondary jj workspace's `.jj/repo` is a file holding the path of the
+/// main workspace's `.jj/repo` directory.
+fn jj_main_workspace(dir: &Path) -> Option<PathBuf> {
+    let jj_dir = dir.join(".jj");
+    let link = std::fs::read_to_string(jj_dir.join("repo")).ok()?;
+    let repo = std::fs::canonicalize(jj_dir.join(link.trim())).ok()?;
+    repo.parent()?.parent().map(Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_walks_up_and_follows_worktrees() {
+        let root = std::env::temp_dir().join(format!("crit-ui-repo-{}", std::process::id()));
+        let mkdir = |path: &Path| std::fs::create_dir_all(path).unwrap();
+        let write = |path: &Path, text: &str| std::fs::write(path, text).unwrap();
+
+        // .crit/ found from a subdirectory
+        let main = root.join("main");
+        mkdir(&main.join(".crit"));
+        mkdir(&main.join("src/deep"));
+        assert_eq!(
+            discover(&main.join("src/deep"), None).unwrap(),
+            Some(main.clone())
+        );
+
+        // A git worktree uses its main worktree's .crit/
+        let worktrees = main.join(".git/worktrees/wt");
+        mkdir(&worktrees);
+        write(&worktrees.join("commondir"), "../..\n");
+        let wt = root.join("wt");
+        mkdir(&wt.join("src"));
+        write(
+            &wt.join(".git"),
+            &format!("gitdir: {}\n", worktrees.display()),
+        );
+        let main_canon = std::fs::canonicalize(&main).unwrap();
+        assert_eq!(
+            discover(&wt.join("src"), None).unwrap(),
+            Some(main_canon.clone())
+        );
+
+        // So does a secondary jj workspace
+        mkdir(&main.join(".jj/repo"));
+        let ws = root.join("ws");
+        mkdir(&ws.join(".jj"));
+        write(
+            &ws.join(".jj/repo"),
+            &main.join(".jj/repo").display().to_string(),
+        );
+        assert_eq!(discover(&ws, None).unwrap(), Some(main_canon));
+
+        // CRIT_DIR wins, as the repo or its .crit/
+        let crit_dir = main.join(".crit");
+        assert_eq!(
+            discover(&ws, Some(crit_dir.as_os_str())).unwrap(),
+            Some(main.clone())
+        );
+        assert_eq!(
+            discover(&ws, Some(main.as_os_str())).unwrap(),
+            Some(main.clone())
+        );
+        assert!(discover(&ws, Some(root.as_os_str())).is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,255 @@
+//! `review_command` from `ui.json` (e.g. `cargo check`), run against the
+//! open review's commit.
+//!
+//! The command runs through `sh -c` with stderr merged into stdout, in a
+//! scratch [`TempWorktree`] so the user's checkout is left alone. A
+//! background thread reads the output line by line; the main loop picks it
+//! up with [`CommandRun::poll`].
+
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::JoinHandle;
+use std::time::Instant;
+
+use crate::diff::sanitize_control_chars;
+use crate::vcs::TempWorktree;
+
+/// Where a run is at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunStatus {
+    Running,
+    Succeeded,
+    /// Nonzero exit, or `None` when killed by a signal
+    Failed(Option<i32>),
+    /// The worktree or the shell couldn't be set up
+    Error(String),
+}
+
+enum RunEvent {
+    Line(String),
+    Done(RunStatus),
+}
+
+pub struct CommandRun {
+    pub review_id: String,
+    pub command: String,
+    pub commit: String,
+    pub output: Vec<String>,
+    pub status: RunStatus,
+    pub started: Instant,
+    /// First output line shown in the overlay
+    pub scroll: usize,
+    /// Keep the newest output in view as it arrives
+    pub follow: bool,
+    events: Receiver<RunEvent>,
+    child: Arc<Mutex<Option<Child>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CommandRun {
+    /// Start `command` in a worktree of `repo_path` at `commit`.
+    #[must_use]
+    pub fn start(repo_path: &Path, review_id: &str, commit: &str, command: &str) -> Self {
+        let (sender, events) = mpsc::channel();
+        let child = Arc::new(Mutex::new(None));
+        let job = Job {
+            repo_path: repo_path.to_path_buf(),
+            commit: commit.to_string(),
+            command: command.to_string(),
+            child: Arc::clone(&child),
+        };
+        let thread = std::thread::spawn(move || {
+            let status = job.run(&sender);
+            let _ = sender.send(RunEvent::Done(status));
+        });
+        Self {
+            review_id: review_id.to_string(),
+            command: command.to_string(),
+            commit: commit.to_string(),
+            output: Vec::new(),
+            status: RunStatus::Running,
+            started: crate::clock::now(),
+            scroll: 0,
+            follow: true,
+            events,
+            child,
+            thread: Some(thread),
+        }
+    }
+
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.status == RunStatus::Running
+    }
+
+    /// First output line shown in a view `rows` tall.
+    #[must_use]
+    pub fn first_visible(&self, rows: usize) -> usize {
+        let last_page = self.output.len().saturating_sub(rows);
+        if self.follow {
+            last_page
+        } else {
+            self.scroll.min(last_page)
+        }
+    }
+
+    /// Scroll a view `rows` tall by `delta` lines. Reaching the end follows
+    /// new output again.
+    pub fn scroll_by(&mut self, delta: isize, rows: usize) {
+        let last_page = self.output.len().saturating_sub(rows);
+        self.scroll = self
+            .first_visible(rows)
+            .saturating_add_signed(delta)
+            .min(last_page);
+        self.follow = self.scroll == last_page;
+    }
+
+    /// Take in output and status that arrived since the last call. Returns
+    /// whether anything did.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                RunEvent::Line(line) => self.output.push(line),
+                RunEvent::Done(status) => self.status = status,
+            }
+            changed = true;
+        }
+        changed
+    }
+}
+
+impl Drop for CommandRun {
+    /// Kill a command still running and wait for its worktree to be removed.
+    fn drop(&mut self) {
+        if let Some(child) = self
+            .child
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+        {
+            #[cfg(unix)]
+            let _ = Command::new("kill")
+                .args(["-KILL", "--", &format!("-{}", child.id())])
+                .output();
+            let _ = child.kill();
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// What the background thread needs.
+struct Job {
+    repo_path: PathBuf,
+    commit: String,
+    command: String,
+    child: Arc<Mutex<Option<Child>>>,
+}
+
+impl Job {
+    fn run(&self, sender: &Sender<RunEvent>) -> RunStatus {
+        let worktree = match TempWorktree::create(&self.repo_path, &self.commit) {
+            Ok(worktree) => worktree,
+            Err(e) => return RunStatus::Error(format!("{e:#}")),
+        };
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(format!("exec 2>&1\n{}", self.command))
+            .current_dir(&worktree.path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped());
+        // Own process group, so a kill reaches whatever the shell started
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let spawned = command.spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => return RunStatus::Error(e.to_string()),
+        };
+        let stdout = child.stdout.take();
+        *self.child.lock().unwrap_or_else(PoisonError::into_inner) = Some(child);
+
+        if let Some(stdout) = stdout {
+            for line in BufReader::new(stdout).split(b'\n') {
+                let Ok(line) = line else { break };
+                let line = String::from_utf8_lossy(&line);
+                let line = line.trim_end_matches('\r').replace('\t', "    ");
+                let line = sanitize_control_chars(&line).into_owned();
+                if sender.send(RunEvent::Line(line)).is_err() {
+                    break;
+                }
+            }
+        }
+
+        let child = self
+            .child
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        match child.map(|mut child| child.wait()) {
+            Some(Ok(status)) if status.success() => RunStatus::Succeeded,
+            Some(Ok(status)) => RunStatus::Failed(status.code()),
+            Some(Err(e)) => RunStatus::Error(e.to_string()),
+            None => RunStatus::Failed(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(repo: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .current_dir(repo)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?}");
+    }
+
+    #[test]
+    fn test_runs_in_a_worktree_at_the_commit() {
+        let repo = std::env::temp_dir().join(format!("crit-ui-run-test-{}", std::process::id()));
+        std::fs::create_dir_all(&repo).unwrap();
+        git(&repo, &["init", "-q"]);
+        std::fs::write(repo.join("version.txt"), "1\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-qm", "first"]);
+        // Later edits to the checkout must not leak into the run
+        std::fs::write(repo.join("version.txt"), "2\n").unwrap();
+
+        let mut run = CommandRun::start(
+            &repo,
+            "cr-1",
+            "HEAD",
+            "cat version.txt; echo oops >&2; exit 3",
+        );
+        while run.is_running() {
+            run.poll();
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(run.output, ["1", "oops"]);
+        assert_eq!(run.status, RunStatus::Failed(Some(3)));
+        drop(run);
+
+        let worktrees = Command::new("git")
+            .args(["worktree", "list"])
+            .current_dir(&repo)
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&worktrees.stdout).lines().count(),
+            1
+        );
+        let _ = std::fs::remove_dir_all(&repo);
+    }
+}
@@ -0,0 +1,162 @@
+//! The review list's filter bar (`f`): space-separated predicates that all
+//! have to match.
+//!
+//! - `author:NAME` (or `@NAME`): the review's author
+//! - `status:NAME`: its status, by name or configured label
+//! - `path:GLOB`: it changes a file matching a CODEOWNERS-style pattern
+//! - `has:open-threads` (or `has:open`): it has unresolved threads
+//!
+//! A leading `-` negates a term. Matching ignores case. Paths come from the
+//! background file scan, so a review matches `path:` only once scanned.
+
+use crate::db::ReviewSummary;
+use crate::owners::pattern_matches;
+use crate::status::StatusWorkflow;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Predicate {
+    Author(String),
+    Status(String),
+    Path(String),
+    OpenThreads,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub negated: bool,
+    pub predicate: Predicate,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReviewQuery {
+    pub terms: Vec<Term>,
+    /// Words that aren't a known predicate; they're ignored
+    pub invalid: Vec<String>,
+}
+
+impl ReviewQuery {
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let mut query = Self::default();
+        for word in text.split_whitespace() {
+            let (negated, rest) = word
+                .strip_prefix('-')
+                .map_or((false, word), |rest| (true, rest));
+            match parse_predicate(rest) {
+                Some(predicate) => query.terms.push(Term { negated, predicate }),
+                None => query.invalid.push(word.to_string()),
+            }
+        }
+        query
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Whether matching needs each review's changed paths.
+    #[must_use]
+    pub fn needs_paths(&self) -> bool {
+        self.terms
+            .iter()
+            .any(|term| matches!(term.predicate, Predicate::Path(_)))
+    }
+
+    /// Whether `review` passes every term. `paths` are its changed files,
+    /// `None` until it's been scanned; a `path:` term (negated or not)
+    /// doesn't match an unscanned review.
+    #[must_use]
+    pub fn matches(
+        &self,
+        review: &ReviewSummary,
+        statuses: &StatusWorkflow,
+        paths: Option<&[String]>,
+    ) -> bool {
+        self.terms.iter().all(|term| {
+            let matched = match &term.predicate {
+                Predicate::Author(name) => {
+                    review.author.trim_start_matches('@').to_lowercase() == *name
+                }
+                Predicate::Status(name) => {
+                    review.status.to_lowercase() == *name
+                        || statuses.label(&review.status).to_lowercase() == *name
+                }
+                Predicate::Path(pattern) => match paths {
+                    Some(paths) => paths.iter().any(|path| pattern_matches(pattern, path)),
+                    None => return false,
+                },
+                Predicate::OpenThreads => review.open_thread_count > 0,
+            };
+            matched != term.negated
+        })
+    }
+}
+
+fn parse_predicate(word: &str) -> Option<Predicate> {
+    if let Some(name) = word.strip_prefix('@') {
+        return non_empty(name).map(|name| Predicate::Author(name.to_lowercase()));
+    }
+    let (key, value) = word.split_once(':')?;
+    match key.to_ascii_lowercase().as_str() {
+        "author" => non_empty(value.trim_start_matches('@'))
+            .map(|name| Predicate::Author(name.to_lowercase())),
+        "status" => non_empty(value).map(|name| Predicate::Status(name.to_lowercase())),
+        "path" => non_empty(value).map(|glob| Predicate::Path(glob.to_string())),
+        "has" => matches!(value, "open-threads" | "open").then_some(Predicate::OpenThreads),
+        _ => None,
+    }
+}
+
+fn non_empty(value: &str) -> Option<&str> {
+    (!value.is_empty()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review(author: &str, status: &str, open_threads: i64) -> ReviewSummary {
+        ReviewSummary {
+            review_id: "cr-1".to_string(),
+            title: "Title".to_string(),
+            author: author.to_string(),
+            status: status.to_string(),
+            thread_count: open_threads,
+            open_thread_count: open_threads,
+            reviewers: Vec::new(),
+            created_at: None,
+            updated_at: None,
+            diff_stats: None,
+        }
+    }
+
+    #[test]
+    fn test_terms_compose_and_negate() {
+        let statuses = StatusWorkflow::default();
+        let query = ReviewQuery::parse("@Alice -status:merged has:open-threads bogus");
+        assert_eq!(query.terms.len(), 3);
+        assert_eq!(query.invalid, ["bogus"]);
+
+        assert!(query.matches(&review("alice", "open", 2), &statuses, None));
+        assert!(!query.matches(&review("alice", "merged", 2), &statuses, None));
+        assert!(!query.matches(&review("alice", "open", 0), &statuses, None));
+        assert!(!query.matches(&review("bob", "open", 2), &statuses, None));
+    }
+
+    #[test]
+    fn test_path_terms_need_scanned_paths() {
+        let statuses = StatusWorkflow::default();
+        let query = ReviewQuery::parse("path:*.rs");
+        assert!(query.needs_paths());
+        let paths = ["docs/guide.md".to_string(), "src/lib.rs".to_string()];
+        assert!(query.matches(&review("a", "open", 0), &statuses, Some(&paths)));
+        assert!(!query.matches(&review("a", "open", 0), &statuses, Some(&paths[..1])));
+        assert!(!query.matches(&review("a", "open", 0), &statuses, None));
+        assert!(!ReviewQuery::parse("-path:docs/").matches(
+            &review("a", "open", 0),
+            &statuses,
+            Some(&paths)
+        ));
+    }
+}
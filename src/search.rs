@@ -0,0 +1,242 @@
+//! `/` search over the open review.
+//!
+//! Matching is plain substring and smart-case: case-insensitive unless the
+//! query has an uppercase letter. Ranges are char offsets on a whole logical
+//! line, the unit the diff wrapper splits lines by.
+//!
+//! The diff stream records a [`SearchHit`] per match in file paths, comments
+//! and diff or context lines while it draws; `n`/`N` step through those. The
+//! review title isn't part of the stream, so its matches are only counted.
+//! A side-by-side row matches in the focused column only
+//! ([`column_matches`]): a context line is one stop rather than two, and the
+//! other side is neither highlighted nor stepped to.
+//!
+//! With wrap on, a line spans several stream rows and a match can cross from
+//! one to the next. Its hit sits on the row holding its start
+//! ([`wrapped_row`], with each side-by-side column's own wrap width), so
+//! `n`/`N` scroll to that row rather than the line's first. Each drawn row
+//! restyles only the part of a match inside its chars ([`clip_to_row`]);
+//! highlighting never adds rows, so stream layout heights don't change.
+
+use std::ops::Range;
+
+use crate::db::ThreadSide;
+
+/// What a hit matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchKind {
+    File,
+    Comment,
+    Line,
+}
+
+/// One match in the diff stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchHit {
+    /// Stream row holding the start of the match
+    pub row: usize,
+    pub kind: SearchKind,
+}
+
+/// Search state for the review detail screen.
+#[derive(Debug, Clone, Default)]
+pub struct DiffSearch {
+    pub query: String,
+    /// The query is still being typed
+    pub editing: bool,
+    /// Cursor row when `/` was pressed; typing searches forward from here and
+    /// Esc goes back to it
+    pub origin: usize,
+}
+
+impl DiffSearch {
+    #[must_use]
+    pub const fn new(origin: usize) -> Self {
+        Self {
+            query: String::new(),
+            editing: true,
+            origin,
+        }
+    }
+
+    /// The query to highlight, if there is one.
+    #[must_use]
+    pub fn active_query(&self) -> Option<&str> {
+        (!self.query.is_empty()).then_some(self.query.as_str())
+    }
+}
+
+/// Non-overlapping matches of `query` in `text`, as char ranges.
+#[must_use]
+pub fn find_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let fold = !query.chars().any(char::is_uppercase);
+    let normalize = |c: char| {
+        if fold {
+            c.to_lowercase().next().unwrap_or(c)
+        } else {
+            c
+        }
+    };
+    let needle: Vec<char> = query.chars().map(normalize).collect();
+    let haystack: Vec<char> = text.chars().map(normalize).collect();
+
+    let mut matches = Vec::new();
+    let mut start = 0;
+    while start + needle.len() <= haystack.len() {
+        if haystack[start..start + needle.len()] == needle[..] {
+            matches.push(start..start + needle.len());
+            start += needle.len();
+        } else {
+            start += 1;
+        }
+    }
+    matches
+}
+
+/// Wrapped row of a line holding char `offset`, `width` chars to a row.
+/// A `width` of 0 means the line isn't wrapped.
+#[must_use]
+pub const fn wrapped_row(offset: usize, width: usize) -> usize {
+    match offset.checked_div(width) {
+        Some(row) => row,
+        None => 0,
+    }
+}
+
+/// The parts of `matches` inside the chars `row`, relative to `row.start`.
+pub fn clip_to_row(
+    matches: &[Range<usize>],
+    row: Range<usize>,
+) -> impl Iterator<Item = Range<usize>> + '_ {
+    matches.iter().filter_map(move |m| {
+        let start = m.start.max(row.start);
+        let end = m.end.min(row.end);
+        (start < end).then(|| start - row.start..end - row.start)
+    })
+}
+
+/// Matches in the old and new columns of a side-by-side row, each given as
+/// its text and wrap width, plus their hits. Only the `side` column is
+/// searched; the other gets no matches.
+#[must_use]
+pub fn column_matches(
+    query: &str,
+    old: Option<(&str, usize)>,
+    new: Option<(&str, usize)>,
+    side: ThreadSide,
+    first_row: usize,
+) -> (Vec<Range<usize>>, Vec<Range<usize>>, Vec<SearchHit>) {
+    let focused = match side {
+        ThreadSide::Old => old,
+        ThreadSide::New => new,
+    };
+    let (matches, hits) = focused.map_or_else(Default::default, |(text, width)| {
+        let matches = find_matches(text, query);
+        let hits = matches
+            .iter()
+            .map(|m| SearchHit {
+                row: first_row + wrapped_row(m.start, width),
+                kind: SearchKind::Line,
+            })
+            .collect();
+        (matches, hits)
+    });
+    match side {
+        ThreadSide::Old => (matches, Vec::new(), hits),
+        ThreadSide::New => (Vec::new(), matches, hits),
+    }
+}
+
+/// Row of the first hit after `cursor`, wrapping around to the first.
+#[must_use]
+pub fn next_hit_row(hits: &[SearchHit], cursor: usize) -> Option<usize> {
+    hits.iter()
+        .find(|hit| hit.row > cursor)
+        .or_else(|| hits.first())
+        .map(|hit| hit.row)
+}
+
+/// Row of the last hit before `cursor`, wrapping around to the last.
+#[must_use]
+pub fn prev_hit_row(hits: &[SearchHit], cursor: usize) -> Option<usize> {
+    hits.iter()
+        .rev()
+        .find(|hit| hit.row < cursor)
+        .or_else(|| hits.last())
+        .map(|hit| hit.row)
+}
+
+/// 1-based position of the hit at or before `cursor`, for "3/17".
+#[must_use]
+pub fn hit_position(hits: &[SearchHit], cursor: usize) -> usize {
+    hits.partition_point(|hit| hit.row <= cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_matches_is_smart_case_in_chars() {
+        assert_eq!(find_matches("Föo foo FOO", "foo"), [4..7, 8..11]);
+        assert_eq!(find_matches("föo FÖO", "föo"), [0..3, 4..7]);
+        assert_eq!(find_matches("foo Foo", "Foo"), [4..7]);
+        assert_eq!(find_matches("aaaa", "aa"), [0..2, 2..4]);
+        assert!(find_matches("foo", "").is_empty());
+    }
+
+    #[test]
+    fn test_matches_split_across_wrapped_rows() {
+        // 10 chars to a row: "0123456789|abcdef"
+        let matches = find_matches("0123456789abcdef", "89ab");
+        assert_eq!(wrapped_row(matches[0].start, 10), 0);
+        assert_eq!(clip_to_row(&matches, 0..10).collect::<Vec<_>>(), [8..10]);
+        assert_eq!(clip_to_row(&matches, 10..16).collect::<Vec<_>>(), [0..2]);
+        assert_eq!(wrapped_row(12, 0), 0);
+    }
+
+    #[test]
+    fn test_column_matches_count_the_focused_side() {
+        // A context line has the same text on both sides: one hit, not two
+        let line = Some(("let foo = 1;", 0));
+        let (old, new, hits) = column_matches("foo", line, line, ThreadSide::New, 4);
+        assert_eq!((old.len(), new.len()), (0, 1));
+        assert_eq!(
+            hits,
+            [SearchHit {
+                row: 4,
+                kind: SearchKind::Line
+            }]
+        );
+
+        // A removed line only matches on the old side
+        let removed = Some(("foo()", 0));
+        let added = Some(("bar()", 0));
+        let (old, new, hits) = column_matches("foo", removed, added, ThreadSide::New, 0);
+        assert!(old.is_empty() && new.is_empty() && hits.is_empty());
+        let (old, _, hits) = column_matches("foo", removed, added, ThreadSide::Old, 0);
+        assert_eq!(old, [0..3]);
+        assert_eq!(hits.len(), 1);
+    }
+
+    #[test]
+    fn test_hit_navigation_wraps_around() {
+        let hits: Vec<SearchHit> = [3, 8, 8, 20]
+            .into_iter()
+            .map(|row| SearchHit {
+                row,
+                kind: SearchKind::Line,
+            })
+            .collect();
+        assert_eq!(next_hit_row(&hits, 3), Some(8));
+        assert_eq!(next_hit_row(&hits, 20), Some(3));
+        assert_eq!(prev_hit_row(&hits, 8), Some(3));
+        assert_eq!(prev_hit_row(&hits, 3), Some(20));
+        assert_eq!(hit_position(&hits, 8), 3);
+        assert_eq!(hit_position(&hits, 0), 0);
+        assert_eq!(next_hit_row(&[], 0), None);
+    }
+}
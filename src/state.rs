@@ -0,0 +1,169 @@
+//! Reading progress per review, kept across sessions: where the cursor
+//! was, which files were marked viewed and which sidebar groups were
+//! collapsed.
+//!
+//! Also unsent comment drafts and verdicts, so a cancelled editor or a
+//! crash doesn't lose them.
+//!
+//! Stored as JSON in the repo's `.crit/ui-state.json`, next to the reviews
+//! it describes; demo and archive sessions keep it in memory only.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::db::Verdict;
+
+/// Where one review was left.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewProgress {
+    /// File the cursor was in
+    pub file: Option<String>,
+    /// New-side line under the cursor in `file`
+    pub line: Option<u32>,
+    /// Files marked viewed (`x`)
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub viewed: BTreeSet<String>,
+    /// Files whose sidebar threads were collapsed, and tree sidebar
+    /// directories (`dir/`)
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub collapsed: BTreeSet<String>,
+}
+
+/// A verdict chosen but not sent, with its summary so far.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerdictDraft {
+    pub verdict: Verdict,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub summary: String,
+}
+
+/// Reading progress of every review in a repo.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UiState {
+    #[serde(default)]
+    pub reviews: BTreeMap<String, ReviewProgress>,
+    /// Comment drafts by review id, then by target
+    /// (`CommentRequest::draft_key`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub drafts: BTreeMap<String, BTreeMap<String, String>>,
+    /// Unsent verdicts by review id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub verdict_drafts: BTreeMap<String, VerdictDraft>,
+    /// File it's saved to; `None` keeps it in memory
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl UiState {
+    /// State for the repo at `repo`, or an empty in-memory one without a
+    /// repo. A file that doesn't parse is started over rather than
+    /// blocking startup: it only holds positions.
+    #[must_use]
+    pub fn load(repo: Option<&Path>) -> Self {
+        let Some(path) = repo.map(|repo| repo.join(".crit").join("ui-state.json")) else {
+            return Self::default();
+        };
+        let state: Self = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            ..state
+        }
+    }
+
+    #[must_use]
+    pub fn draft(&self, review_id: &str, key: &str) -> Option<&str> {
+        self.drafts.get(review_id)?.get(key).map(String::as_str)
+    }
+
+    /// Keep `body` as the draft for `key`, or drop the draft if `body` is
+    /// blank. Returns whether anything changed.
+    pub fn set_draft(&mut self, review_id: &str, key: &str, body: &str) -> bool {
+        if body.trim().is_empty() {
+            let Some(drafts) = self.drafts.get_mut(review_id) else {
+                return false;
+            };
+            let removed = drafts.remove(key).is_some();
+            if drafts.is_empty() {
+                self.drafts.remove(review_id);
+            }
+            return removed;
+        }
+        if self.draft(review_id, key) == Some(body) {
+            return false;
+        }
+        self.drafts
+            .entry(review_id.to_string())
+            .or_default()
+            .insert(key.to_string(), body.to_string());
+        true
+    }
+
+    /// Keep `draft` as the review's unsent verdict, or drop it with `None`.
+    /// Returns whether anything changed.
+    pub fn set_verdict_draft(&mut self, review_id: &str, draft: Option<VerdictDraft>) -> bool {
+        match draft {
+            Some(draft) => {
+                let previous = self
+                    .verdict_drafts
+                    .insert(review_id.to_string(), draft.clone());
+                previous != Some(draft)
+            }
+            None => self.verdict_drafts.remove(review_id).is_some(),
+        }
+    }
+
+    /// Write the state to its file, if it has one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_round_trips_through_the_repo() {
+        let repo = std::env::temp_dir().join(format!("crit-ui-state-{}", std::process::id()));
+        std::fs::create_dir_all(repo.join(".crit")).unwrap();
+
+        let mut state = UiState::load(Some(&repo));
+        assert!(state.reviews.is_empty());
+        let progress = ReviewProgress {
+            file: Some("src/lib.rs".to_string()),
+            line: Some(12),
+            viewed: BTreeSet::from(["README.md".to_string()]),
+            collapsed: BTreeSet::new(),
+        };
+        state.reviews.insert("cr-1".to_string(), progress.clone());
+        state.save().unwrap();
+        assert_eq!(UiState::load(Some(&repo)).reviews["cr-1"], progress);
+
+        assert!(state.set_draft("cr-1", "src/lib.rs:4", "half a thought"));
+        assert!(!state.set_draft("cr-1", "src/lib.rs:4", "half a thought"));
+        state.save().unwrap();
+        let loaded = UiState::load(Some(&repo));
+        assert_eq!(loaded.draft("cr-1", "src/lib.rs:4"), Some("half a thought"));
+        assert!(state.set_draft("cr-1", "src/lib.rs:4", "  "));
+        assert!(state.drafts.is_empty());
+
+        std::fs::write(repo.join(".crit").join("ui-state.json"), "{oops").unwrap();
+        assert!(UiState::load(Some(&repo)).reviews.is_empty());
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+}
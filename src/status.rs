@@ -0,0 +1,167 @@
+//! Review status vocabulary (`statuses` in `ui.json`).
+//!
+//! crit's own statuses are `open`, `approved`, `merged` and `abandoned`, but
+//! a team's workflow may use others ("in-review", "landed"). Each status can
+//! get a display label, a color, a place in the review list order and
+//! whether it counts as open (the Open filter, the inbox and ownership
+//! scans). Statuses without an entry show as-is in the foreground color.
+
+use std::collections::BTreeMap;
+
+use crate::config::StatusStyle;
+use crate::render_backend::{color_from_hex, Rgba};
+use crate::theme::Theme;
+
+/// How one status is shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StatusEntry {
+    label: Option<String>,
+    /// Theme token (`success`, `warning`, …) or `#rrggbb`
+    color: Option<String>,
+    order: i64,
+    open: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusWorkflow {
+    entries: BTreeMap<String, StatusEntry>,
+}
+
+impl Default for StatusWorkflow {
+    /// crit's statuses in their usual order, colored as before statuses were
+    /// configurable.
+    fn default() -> Self {
+        let builtin = |color: &str, open| StatusEntry {
+            label: None,
+            color: Some(color.to_string()),
+            order: 0,
+            open,
+        };
+        let entries = [
+            ("open", builtin("success", true)),
+            ("approved", builtin("warning", false)),
+            ("merged", builtin("success", false)),
+            ("abandoned", builtin("muted", false)),
+        ]
+        .into_iter()
+        .map(|(name, entry)| (name.to_string(), entry))
+        .collect();
+        Self { entries }
+    }
+}
+
+impl StatusWorkflow {
+    /// The built-in statuses with `config` laid over them. A configured
+    /// status keeps the built-in values it doesn't set.
+    #[must_use]
+    pub fn from_config(config: &BTreeMap<String, StatusStyle>) -> Self {
+        let mut workflow = Self::default();
+        for (name, style) in config {
+            let entry = workflow.entries.entry(name.clone()).or_insert(StatusEntry {
+                label: None,
+                color: None,
+                order: 0,
+                open: false,
+            });
+            if style.label.is_some() {
+                entry.label.clone_from(&style.label);
+            }
+            if style.color.is_some() {
+                entry.color.clone_from(&style.color);
+            }
+            if let Some(order) = style.order {
+                entry.order = order;
+            }
+            if let Some(open) = style.open {
+                entry.open = open;
+            }
+        }
+        workflow
+    }
+
+    /// Text shown for `status`.
+    #[must_use]
+    pub fn label<'a>(&'a self, status: &'a str) -> &'a str {
+        self.entries
+            .get(status)
+            .and_then(|entry| entry.label.as_deref())
+            .unwrap_or(status)
+    }
+
+    /// Color of `status`; the foreground for unknown statuses and colors.
+    #[must_use]
+    pub fn color(&self, status: &str, theme: &Theme) -> Rgba {
+        self.entries
+            .get(status)
+            .and_then(|entry| entry.color.as_deref())
+            .and_then(|color| theme_color(theme, color))
+            .unwrap_or(theme.foreground)
+    }
+
+    /// Sort key in the review list, lower first. Unset is 0, and the sort
+    /// is stable, so without any `order` the list keeps crit's order.
+    #[must_use]
+    pub fn order(&self, status: &str) -> i64 {
+        self.entries.get(status).map_or(0, |entry| entry.order)
+    }
+
+    /// Whether reviews in `status` are still open.
+    #[must_use]
+    pub fn is_open(&self, status: &str) -> bool {
+        self.entries.get(status).is_some_and(|entry| entry.open)
+    }
+}
+
+/// A theme token name or a hex color.
+fn theme_color(theme: &Theme, name: &str) -> Option<Rgba> {
+    match name {
+        "success" => Some(theme.success),
+        "warning" => Some(theme.warning),
+        "error" => Some(theme.error),
+        "muted" => Some(theme.muted),
+        "primary" => Some(theme.primary),
+        "foreground" => Some(theme.foreground),
+        hex => color_from_hex(hex),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_extends_and_overrides_builtins() {
+        let style = |label: Option<&str>, color: Option<&str>, order, open| StatusStyle {
+            label: label.map(str::to_string),
+            color: color.map(str::to_string),
+            order,
+            open,
+        };
+        let config = BTreeMap::from([
+            (
+                "in-review".to_string(),
+                style(Some("In review"), Some("#ff0000"), Some(0), Some(true)),
+            ),
+            (
+                "merged".to_string(),
+                style(Some("Landed"), None, Some(5), None),
+            ),
+        ]);
+        let workflow = StatusWorkflow::from_config(&config);
+        let theme = Theme::default();
+
+        assert_eq!(workflow.label("in-review"), "In review");
+        assert_eq!(workflow.label("merged"), "Landed");
+        assert_eq!(workflow.label("parked"), "parked");
+        assert_eq!(workflow.color("merged", &theme), theme.success);
+        assert_eq!(
+            workflow.color("in-review", &theme),
+            color_from_hex("#ff0000").unwrap()
+        );
+        assert_eq!(workflow.color("parked", &theme), theme.foreground);
+        assert!(workflow.is_open("in-review"));
+        assert!(workflow.is_open("open"));
+        assert!(!workflow.is_open("parked"));
+        assert!(workflow.order("parked") < workflow.order("merged"));
+    }
+}
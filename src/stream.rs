@@ -1,24 +1,124 @@
 //! Stream layout helpers for the right pane
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
 
-use crate::db::{Comment, ThreadSummary};
-use crate::diff::ParsedDiff;
+use crate::db::{Comment, ReviewDetail, ReviewVote, ThreadEvent, ThreadSide, ThreadSummary};
+use crate::diff::{wrap_spans, ParsedDiff, WordLine};
 use crate::layout;
 use crate::model::{DiffViewMode, FileCacheEntry, FileEntry};
+use crate::search::SearchHit;
+use crate::suggestion::{body_rows, replaced_lines};
 use crate::text::{wrap_text, wrap_text_preserve};
 
 // Re-export for downstream users that were importing from stream::
 pub use crate::layout::{
-    block_height, BLOCK_LEFT_PAD, BLOCK_MARGIN, BLOCK_PADDING, BLOCK_RIGHT_PAD, BLOCK_SIDE_MARGIN,
+    block_height, block_margin, block_padding, BLOCK_LEFT_PAD, BLOCK_RIGHT_PAD, BLOCK_SIDE_MARGIN,
     SIDE_BY_SIDE_MIN_WIDTH,
 };
 
+/// Where things sit in the review's stream of rows.
+///
+/// [`compute_stream_layout`] fills in the file regions. Rows of diff lines
+/// and thread blocks are only known once the stream is drawn, so they're
+/// empty until [`StreamLayout::with_rendered_rows`] adds what a render
+/// recorded (`view::compute_stream_regions` does this off-screen). Ask the
+/// accessors rather than re-deriving rows from the diff.
 pub struct StreamLayout {
-    /// Offset where files start (after description block, if any)
+    /// Rows of the description block, if any
     pub description_lines: usize,
     pub file_offsets: Vec<usize>,
     pub total_lines: usize,
+    rendered: RenderedRows,
+}
+
+/// Rows a render recorded, by stream row.
+#[derive(Debug, Clone, Default)]
+pub struct RenderedRows {
+    /// Stream row → new-side line number, for every row of a wrapped line
+    pub line_map: HashMap<usize, i64>,
+    /// Like `line_map` for old-side lines (side-by-side only)
+    pub old_line_map: HashMap<usize, i64>,
+    /// Thread id → first row of its comment block
+    pub thread_positions: HashMap<String, usize>,
+}
+
+impl StreamLayout {
+    /// Attach what a render recorded, for the line and thread accessors.
+    #[must_use]
+    pub fn with_rendered_rows(mut self, rendered: RenderedRows) -> Self {
+        self.rendered = rendered;
+        self
+    }
+
+    /// What the render recorded, empty unless attached.
+    #[must_use]
+    pub const fn rendered_rows(&self) -> &RenderedRows {
+        &self.rendered
+    }
+
+    #[must_use]
+    pub fn file_count(&self) -> usize {
+        self.file_offsets.len()
+    }
+
+    /// Rows of the file at `index`: its header through its last thread.
+    #[must_use]
+    pub fn file_rows(&self, index: usize) -> Option<Range<usize>> {
+        let start = *self.file_offsets.get(index)?;
+        let end = self
+            .file_offsets
+            .get(index + 1)
+            .copied()
+            .unwrap_or(self.total_lines);
+        Some(start..end)
+    }
+
+    /// Index of the file `row` belongs to; `None` above the first file
+    /// (verdicts, description, review threads) or past the end.
+    #[must_use]
+    pub fn file_at_row(&self, row: usize) -> Option<usize> {
+        if row >= self.total_lines {
+            return None;
+        }
+        self.file_offsets
+            .partition_point(|&offset| offset <= row)
+            .checked_sub(1)
+    }
+
+    /// First row showing `line` on `side` of the file at `index`, if drawn.
+    #[must_use]
+    pub fn line_row(&self, index: usize, side: ThreadSide, line: i64) -> Option<usize> {
+        let rows = self.file_rows(index)?;
+        self.side_map(side)
+            .iter()
+            .filter(|&(row, &mapped)| rows.contains(row) && mapped == line)
+            .map(|(&row, _)| row)
+            .min()
+    }
+
+    /// File index and `side` line number drawn on `row`.
+    #[must_use]
+    pub fn line_at_row(&self, row: usize, side: ThreadSide) -> Option<(usize, i64)> {
+        let line = *self.side_map(side).get(&row)?;
+        Some((self.file_at_row(row)?, line))
+    }
+
+    /// First row of the thread's comment block, if drawn.
+    #[must_use]
+    pub fn thread_row(&self, thread_id: &str) -> Option<usize> {
+        self.rendered.thread_positions.get(thread_id).copied()
+    }
+
+    const fn side_map(&self, side: ThreadSide) -> &HashMap<usize, i64> {
+        match side {
+            ThreadSide::Old => &self.rendered.old_line_map,
+            ThreadSide::New => &self.rendered.line_map,
+        }
+    }
 }
 
 /// Parameters for [`compute_stream_layout`].
@@ -27,20 +127,127 @@ pub struct StreamLayoutParams<'a> {
     pub file_cache: &'a HashMap<String, FileCacheEntry>,
     pub threads: &'a [ThreadSummary],
     pub all_comments: &'a HashMap<String, Vec<Comment>>,
+    pub thread_events: &'a HashMap<String, Vec<ThreadEvent>>,
     pub view_mode: DiffViewMode,
+    /// Files drawn in another mode than `view_mode`
+    pub view_mode_overrides: &'a HashMap<String, DiffViewMode>,
     pub wrap: bool,
     pub content_width: u32,
     pub description: Option<&'a str>,
+    /// Verdicts, drawn above the description
+    pub votes: &'a [ReviewVote],
+    /// The review, when it's abandoned: a banner above the verdicts
+    pub abandoned: Option<&'a ReviewDetail>,
+    /// Threads drawn collapsed
+    pub muted_threads: &'a BTreeSet<String>,
+    /// Prose files drawn as a word diff
+    pub word_diff_files: &'a HashSet<String>,
+    /// Files marked viewed, drawn as their header only
+    pub viewed_files: &'a HashSet<String>,
+    /// Folded hunks and files
+    pub folds: &'a Folds,
+    /// Lines of file content shown around threads outside the diff
+    pub context_lines: i64,
+    /// Unified lines make room for the blame column
+    pub blame_gutter: bool,
+}
+
+impl StreamLayoutParams<'_> {
+    /// View mode `path` is drawn in.
+    #[must_use]
+    pub fn view_mode_for(&self, path: &str) -> DiffViewMode {
+        self.view_mode_overrides
+            .get(path)
+            .copied()
+            .unwrap_or(self.view_mode)
+    }
+}
+
+/// Hunks folded to a summary line (`za`) and files folded to their header
+/// (`zA`), by path.
+#[derive(Debug, Clone, Default)]
+pub struct Folds {
+    hunks: HashMap<String, BTreeSet<usize>>,
+    files: HashSet<String>,
+}
+
+impl Folds {
+    /// Whether `path` is folded to its header.
+    #[must_use]
+    pub fn file(&self, path: &str) -> bool {
+        self.files.contains(path)
+    }
+
+    /// Whether hunk `index` of `path` is folded.
+    #[must_use]
+    pub fn hunk(&self, path: &str, index: usize) -> bool {
+        self.hunks
+            .get(path)
+            .is_some_and(|hunks| hunks.contains(&index))
+    }
+
+    /// Fold or unfold `path`; returns whether it's folded now.
+    pub fn toggle_file(&mut self, path: &str) -> bool {
+        let folded = !self.files.remove(path);
+        if folded {
+            self.files.insert(path.to_string());
+        }
+        folded
+    }
+
+    /// Fold or unfold hunk `index` of `path`; returns whether it's folded
+    /// now.
+    pub fn toggle_hunk(&mut self, path: &str, index: usize) -> bool {
+        let hunks = self.hunks.entry(path.to_string()).or_default();
+        let folded = !hunks.remove(&index);
+        if folded {
+            hunks.insert(index);
+        } else if hunks.is_empty() {
+            self.hunks.remove(path);
+        }
+        folded
+    }
+
+    /// Hunk `index` of `path` merged into the one above it: its fold goes
+    /// and later hunks move up one.
+    pub fn merge_hunk(&mut self, path: &str, index: usize) {
+        if let Some(hunks) = self.hunks.get_mut(path) {
+            *hunks = hunks
+                .iter()
+                .filter(|&&hunk| hunk != index)
+                .map(|&hunk| if hunk > index { hunk - 1 } else { hunk })
+                .collect();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.hunks.clear();
+        self.files.clear();
+    }
+
+    /// `diff` of `path` as drawn: with its folded hunks marked.
+    #[must_use]
+    pub fn apply<'a>(&self, path: &str, diff: &'a ParsedDiff) -> Cow<'a, ParsedDiff> {
+        self.hunks.get(path).map_or(Cow::Borrowed(diff), |hunks| {
+            Cow::Owned(diff.with_folded_hunks(hunks))
+        })
+    }
+
+    fn hash_path(&self, path: &str, hasher: &mut impl Hasher) {
+        self.file(path).hash(hasher);
+        self.hunks.get(path).hash(hasher);
+    }
 }
 
 /// Inner width for description/comment block content.
 /// Uses the same `comment_block_area` → `comment_content_area` chain as
 /// `emit_comment_block` so text wraps at the same column.
-const fn block_wrap_width(pane_width: u32) -> usize {
-    // comment_block_area: inset COMMENT_H_MARGIN on each side
-    let block_w = pane_width.saturating_sub(layout::COMMENT_H_MARGIN * 2);
-    // comment_content_area: inset 2 (double bar) + COMMENT_H_PAD on each side
-    block_w.saturating_sub(4 + layout::COMMENT_H_PAD * 2) as usize
+fn block_wrap_width(pane_width: u32) -> usize {
+    // comment_block_area: inset comment_h_margin() on each side, narrowed
+    // to comment_max_width()
+    let (_, block_w) = layout::comment_block_span(pane_width);
+    // comment_content_area: inset 2 (double bar) + comment_h_pad() on each side
+    block_w.saturating_sub(4 + layout::comment_h_pad() * 2) as usize
 }
 
 /// Compute height of description block (if present).
@@ -57,14 +264,63 @@ pub fn description_block_height(description: Option<&str>, pane_width: u32) -> u
     block_height(wrapped.len())
 }
 
+/// Summary text of a verdict, if it has any.
+#[must_use]
+pub fn verdict_summary(vote: &ReviewVote) -> Option<&str> {
+    vote.reason
+        .as_deref()
+        .filter(|reason| !reason.trim().is_empty())
+}
+
+/// Why an abandoned review was abandoned, if it says.
+#[must_use]
+pub fn abandon_reason(review: &ReviewDetail) -> Option<&str> {
+    review
+        .abandon_reason
+        .as_deref()
+        .filter(|reason| !reason.trim().is_empty())
+}
+
+/// Compute height of the abandoned banner: a heading line, then the reason
+/// after a blank line.
+#[must_use]
+pub fn abandoned_banner_height(abandoned: Option<&ReviewDetail>, pane_width: u32) -> usize {
+    abandoned.map_or(0, |review| {
+        let reason_lines = abandon_reason(review).map_or(0, |reason| {
+            1 + wrap_text(reason, block_wrap_width(pane_width)).len()
+        });
+        block_height(1 + reason_lines)
+    })
+}
+
+/// Compute height of the verdict blocks: a heading line per verdict, then
+/// its summary after a blank line.
+#[must_use]
+pub fn verdict_blocks_height(votes: &[ReviewVote], pane_width: u32) -> usize {
+    let wrap_width = block_wrap_width(pane_width);
+    votes
+        .iter()
+        .map(|vote| {
+            let summary_lines =
+                verdict_summary(vote).map_or(0, |summary| 1 + wrap_text(summary, wrap_width).len());
+            block_height(1 + summary_lines)
+        })
+        .sum()
+}
+
 /// Inner width for diff content (no block bar/margins, just horizontal padding).
 const fn diff_inner_width(pane_width: u32) -> u32 {
     layout::diff_inner_width(pane_width)
 }
 
-const fn unified_wrap_width(pane_width: u32) -> usize {
+const fn unified_wrap_width(pane_width: u32, blame_gutter: bool) -> usize {
+    let gutter = if blame_gutter {
+        layout::BLAME_GUTTER_WIDTH
+    } else {
+        0
+    };
     let content_width = diff_inner_width(pane_width)
-        .saturating_sub(layout::THREAD_COL_WIDTH + layout::UNIFIED_LINE_NUM_WIDTH);
+        .saturating_sub(layout::THREAD_COL_WIDTH + layout::UNIFIED_LINE_NUM_WIDTH + gutter);
     let max_content = content_width.saturating_sub(2);
     max_content as usize
 }
@@ -83,6 +339,22 @@ const fn side_by_side_wrap_widths(pane_width: u32) -> (usize, usize) {
     (left, right)
 }
 
+/// Rows of a word diff: a separator per hunk, then each line wrapped at the
+/// unified view's width.
+#[must_use]
+pub fn word_diff_line_count(hunks: &[Vec<WordLine>], pane_width: u32) -> usize {
+    let width = unified_wrap_width(pane_width, false);
+    hunks
+        .iter()
+        .map(|lines| {
+            1 + lines
+                .iter()
+                .map(|line| wrap_spans(&line.spans, width).len())
+                .sum::<usize>()
+        })
+        .sum()
+}
+
 fn wrap_line_count(text: &str, max_width: usize) -> usize {
     if max_width == 0 {
         return 1;
@@ -92,33 +364,90 @@ fn wrap_line_count(text: &str, max_width: usize) -> usize {
 }
 
 #[must_use]
-#[allow(clippy::implicit_hasher)] // internal fn, always uses default hasher
 pub fn compute_stream_layout(params: &StreamLayoutParams<'_>) -> StreamLayout {
+    stream_layout_with(params, |file| file_rows(params, file))
+}
+
+/// The stream's layout given each file's rows.
+fn stream_layout_with(
+    params: &StreamLayoutParams<'_>,
+    mut rows_of: impl FnMut(&FileEntry) -> usize,
+) -> StreamLayout {
+    let content_width = params.content_width;
+    let description_lines = description_block_height(params.description, content_width);
+    let mut file_offsets = Vec::with_capacity(params.files.len());
+    let mut total = abandoned_banner_height(params.abandoned, content_width)
+        + verdict_blocks_height(params.votes, content_width)
+        + description_lines
+        + review_threads_height(
+            params.threads,
+            params.all_comments,
+            params.thread_events,
+            params.muted_threads,
+            content_width,
+        );
+    for file in params.files {
+        file_offsets.push(total);
+        total += rows_of(file);
+    }
+    StreamLayout {
+        description_lines,
+        file_offsets,
+        total_lines: total,
+        rendered: RenderedRows::default(),
+    }
+}
+
+/// Rows of one file: its header and, unless it's marked viewed or folded,
+/// its file-level threads and body.
+fn file_rows(params: &StreamLayoutParams<'_>, file: &FileEntry) -> usize {
     let StreamLayoutParams {
-        files,
         file_cache,
         threads,
         all_comments,
-        view_mode,
+        thread_events,
         wrap,
         content_width,
-        description,
+        muted_threads,
+        word_diff_files,
+        viewed_files,
+        folds,
+        context_lines,
+        ..
     } = *params;
 
-    let description_lines = description_block_height(description, content_width);
-    let mut file_offsets = Vec::with_capacity(files.len());
-    let mut total = description_lines;
+    let mut total = block_height(1); // file header block
+    if viewed_files.contains(&file.path) || folds.file(&file.path) {
+        return total;
+    }
 
-    for file in files {
-        file_offsets.push(total);
-        total += block_height(1); // file header block
+    let (file_level, file_threads) = split_file_threads(threads, &file.path);
+    total += threads_comment_height(
+        &file_level,
+        all_comments,
+        thread_events,
+        muted_threads,
+        content_width,
+    );
 
-        if let Some(entry) = file_cache.get(&file.path) {
-            let file_threads: Vec<&ThreadSummary> = threads
-                .iter()
-                .filter(|t| t.file_path == file.path)
-                .collect();
-            let diff_lines = entry.diff.as_ref().map_or_else(
+    if let Some(entry) = file_cache.get(&file.path) {
+        let word_lines = entry
+            .word_lines
+            .as_ref()
+            .filter(|_| word_diff_files.contains(&file.path));
+        let diff_lines = if let (Some(diff), Some(word_lines)) = (&entry.diff, word_lines) {
+            // Must match render_file_words: threads follow the hunks
+            layout::diff_banner_rows(diff)
+                + word_diff_line_count(word_lines, content_width)
+                + threads_comment_height(
+                    &file_threads,
+                    all_comments,
+                    thread_events,
+                    muted_threads,
+                    content_width,
+                )
+        } else {
+            entry.diff.as_ref().map_or_else(
                 || {
                     entry.file_content.as_ref().map_or(0, |content| {
                         context_display_count(
@@ -126,6 +455,7 @@ pub fn compute_stream_layout(params: &StreamLayoutParams<'_>) -> StreamLayout {
                             content.start_line,
                             threads,
                             &file.path,
+                            context_lines,
                             wrap,
                             content_width,
                         ) + all_context_extra_lines(
@@ -133,59 +463,262 @@ pub fn compute_stream_layout(params: &StreamLayoutParams<'_>) -> StreamLayout {
                             content.lines.len(),
                             &file_threads,
                             all_comments,
+                            thread_events,
+                            muted_threads,
                             content_width,
                         )
                     })
                 },
-                |diff| {
-                    let anchors = crate::view::map_threads_to_diff(diff, &file_threads);
-                    let anchored_ids: std::collections::HashSet<&str> =
-                        anchors.iter().map(|a| a.thread_id.as_str()).collect();
-                    let anchored_threads: Vec<&ThreadSummary> = file_threads
-                        .iter()
-                        .filter(|t| anchored_ids.contains(t.thread_id.as_str()))
-                        .copied()
-                        .collect();
-                    let orphaned_threads: Vec<&ThreadSummary> = file_threads
-                        .iter()
-                        .filter(|t| !anchored_ids.contains(t.thread_id.as_str()))
-                        .copied()
-                        .collect();
-
-                    let mut count = diff_line_count_for_view(diff, view_mode, wrap, content_width)
-                        + threads_comment_height(&anchored_threads, all_comments, content_width);
-
-                    if !orphaned_threads.is_empty() {
-                        if let Some(content) = &entry.file_content {
-                            let hunk_ranges = crate::diff::hunk_exclusion_ranges(&diff.hunks);
-                            count += orphaned_context_display_count(
-                                content.lines.as_slice(),
-                                content.start_line,
-                                &orphaned_threads,
-                                &hunk_ranges,
-                                wrap,
-                                content_width,
-                            );
-                        }
-                        count +=
-                            threads_comment_height(&orphaned_threads, all_comments, content_width);
-                    }
+                |diff| diff_rows(params, file, entry, diff, &file_threads),
+            )
+        };
 
-                    count
-                },
+        total += diff_lines.max(1);
+    } else {
+        total += 1;
+    }
+    total
+}
+
+/// Rows of a file's diff body: banner, lines and anchored threads, then the
+/// context and threads of those that fall outside every hunk.
+fn diff_rows(
+    params: &StreamLayoutParams<'_>,
+    file: &FileEntry,
+    entry: &FileCacheEntry,
+    diff: &ParsedDiff,
+    file_threads: &[&ThreadSummary],
+) -> usize {
+    let StreamLayoutParams {
+        all_comments,
+        thread_events,
+        wrap,
+        content_width,
+        muted_threads,
+        folds,
+        context_lines,
+        blame_gutter,
+        ..
+    } = *params;
+    let view_mode = params.view_mode_for(&file.path);
+    let diff = &*folds.apply(&file.path, diff);
+    let anchors = crate::view::map_threads_to_diff(diff, file_threads);
+    let anchored_ids: std::collections::HashSet<&str> =
+        anchors.iter().map(|a| a.thread_id.as_str()).collect();
+    let anchored_threads: Vec<&ThreadSummary> = file_threads
+        .iter()
+        .filter(|t| anchored_ids.contains(t.thread_id.as_str()))
+        .copied()
+        .collect();
+    let orphaned_threads: Vec<&ThreadSummary> = file_threads
+        .iter()
+        .filter(|t| !anchored_ids.contains(t.thread_id.as_str()))
+        .copied()
+        .collect();
+
+    let mut count = layout::diff_banner_rows(diff)
+        + diff_line_count_for_view(diff, view_mode, wrap, blame_gutter, content_width)
+        + threads_comment_height(
+            &anchored_threads,
+            all_comments,
+            thread_events,
+            muted_threads,
+            content_width,
+        );
+
+    if !orphaned_threads.is_empty() {
+        if let Some(content) = &entry.file_content {
+            let hunk_ranges = crate::diff::hunk_exclusion_ranges(&diff.hunks);
+            count += orphaned_context_display_count(
+                content.lines.as_slice(),
+                content.start_line,
+                &orphaned_threads,
+                &hunk_ranges,
+                context_lines,
+                wrap,
+                content_width,
             );
+        }
+        count += threads_comment_height(
+            &orphaned_threads,
+            all_comments,
+            thread_events,
+            muted_threads,
+            content_width,
+        );
+    }
 
-            total += diff_lines.max(1);
-        } else {
-            total += 1;
+    count
+}
+
+/// Hash of everything a file's rows depend on.
+///
+/// That's the pane width, wrap, view mode, blame column, density, comment
+/// width cap and context radius, whether it's viewed or a word diff, its folds, its cache
+/// entry's revision, and its threads with their comments and history.
+#[must_use]
+pub fn file_key(params: &StreamLayoutParams<'_>, path: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    params.content_width.hash(&mut hasher);
+    params.wrap.hash(&mut hasher);
+    params.view_mode_for(path).hash(&mut hasher);
+    params.context_lines.hash(&mut hasher);
+    params.blame_gutter.hash(&mut hasher);
+    layout::density().hash(&mut hasher);
+    layout::comment_max_width().hash(&mut hasher);
+    params.viewed_files.contains(path).hash(&mut hasher);
+    params.word_diff_files.contains(path).hash(&mut hasher);
+    params.folds.hash_path(path, &mut hasher);
+    params
+        .file_cache
+        .get(path)
+        .map(|entry| entry.revision)
+        .hash(&mut hasher);
+    for thread in params.threads.iter().filter(|t| t.file_path == path) {
+        thread.thread_id.hash(&mut hasher);
+        thread.status.hash(&mut hasher);
+        thread.selection_start.hash(&mut hasher);
+        thread.selection_end.hash(&mut hasher);
+        thread.side.hash(&mut hasher);
+        thread.comment_count.hash(&mut hasher);
+        params
+            .muted_threads
+            .contains(&thread.thread_id)
+            .hash(&mut hasher);
+        for comment in params
+            .all_comments
+            .get(&thread.thread_id)
+            .into_iter()
+            .flatten()
+        {
+            comment.comment_id.hash(&mut hasher);
+            comment.author.hash(&mut hasher);
+            comment.created_at.hash(&mut hasher);
+            comment.body.hash(&mut hasher);
+        }
+        for event in params
+            .thread_events
+            .get(&thread.thread_id)
+            .into_iter()
+            .flatten()
+        {
+            event.actor.hash(&mut hasher);
+            event.created_at.hash(&mut hasher);
+            event.reason.hash(&mut hasher);
         }
     }
+    hasher.finish()
+}
 
-    StreamLayout {
-        description_lines,
-        file_offsets,
-        total_lines: total,
+/// What rendering one file recorded, in rows from the file's first row, so
+/// a file outside the viewport can be skipped and its rows replayed.
+#[derive(Debug, Clone, Default)]
+pub struct FileRecord {
+    /// Rows the file took
+    pub rows: usize,
+    pub cursor_stops: Vec<usize>,
+    pub hunk_rows: Vec<usize>,
+    pub change_rows: Vec<usize>,
+    pub search_hits: Vec<SearchHit>,
+    pub line_map: Vec<(usize, i64)>,
+    pub old_line_map: Vec<(usize, i64)>,
+    pub thread_positions: Vec<(String, usize)>,
+    pub thread_rows: Vec<(String, Range<usize>)>,
+    pub comment_positions: Vec<(String, Range<usize>)>,
+}
+
+/// Per-file layout rows and render records kept between frames.
+///
+/// Each is stored by path with the [`file_key`] it was computed under; one
+/// whose key no longer matches is recomputed. So only files that changed
+/// are laid out again after a comment or a reload, and everything after a
+/// resize or a wrap or view mode toggle.
+#[derive(Debug, Default)]
+pub struct StreamCache {
+    rows: HashMap<String, (u64, usize)>,
+    records: HashMap<String, (u64, FileRecord)>,
+}
+
+impl StreamCache {
+    /// [`compute_stream_layout`], reusing the rows of unchanged files.
+    pub fn layout(&mut self, params: &StreamLayoutParams<'_>) -> StreamLayout {
+        let layout = stream_layout_with(params, |file| {
+            let key = file_key(params, &file.path);
+            match self.rows.get(&file.path) {
+                Some(&(cached, rows)) if cached == key => rows,
+                _ => {
+                    let rows = file_rows(params, file);
+                    self.rows.insert(file.path.clone(), (key, rows));
+                    rows
+                }
+            }
+        });
+        if self.rows.len() > params.files.len() {
+            self.rows
+                .retain(|path, _| params.files.iter().any(|f| &f.path == path));
+        }
+        layout
+    }
+
+    /// What `path` recorded when last rendered under `key`.
+    #[must_use]
+    pub fn record(&self, path: &str, key: u64) -> Option<&FileRecord> {
+        self.records
+            .get(path)
+            .filter(|(cached, _)| *cached == key)
+            .map(|(_, record)| record)
     }
+
+    pub fn store_record(&mut self, path: &str, key: u64, record: FileRecord) {
+        self.records.insert(path.to_string(), (key, record));
+    }
+
+    /// Drop records of files no longer in the stream.
+    pub fn retain_records(&mut self, files: &[FileEntry]) {
+        if self.records.len() > files.len() {
+            self.records
+                .retain(|path, _| files.iter().any(|f| &f.path == path));
+        }
+    }
+}
+
+/// Review-level threads, drawn after the description and before any file.
+#[must_use]
+pub fn review_threads(threads: &[ThreadSummary]) -> Vec<&ThreadSummary> {
+    threads.iter().filter(|t| t.is_review_thread()).collect()
+}
+
+/// Rows taken by the [`review_threads`].
+#[must_use]
+#[allow(clippy::implicit_hasher)] // internal fn, always uses default hasher
+pub fn review_threads_height(
+    threads: &[ThreadSummary],
+    all_comments: &HashMap<String, Vec<Comment>>,
+    thread_events: &HashMap<String, Vec<ThreadEvent>>,
+    muted_threads: &BTreeSet<String>,
+    content_width: u32,
+) -> usize {
+    threads_comment_height(
+        &review_threads(threads),
+        all_comments,
+        thread_events,
+        muted_threads,
+        content_width,
+    )
+}
+
+/// A file's threads: file-level ones, which go right under the file header,
+/// and the rest.
+#[must_use]
+pub fn split_file_threads<'a>(
+    threads: &'a [ThreadSummary],
+    file_path: &str,
+) -> (Vec<&'a ThreadSummary>, Vec<&'a ThreadSummary>) {
+    threads
+        .iter()
+        .filter(|t| t.file_path == file_path)
+        .partition(|t| t.is_file_thread())
 }
 
 #[must_use]
@@ -210,12 +743,19 @@ fn diff_line_count_for_view(
     diff: &ParsedDiff,
     view_mode: DiffViewMode,
     wrap: bool,
+    blame_gutter: bool,
     content_width: u32,
 ) -> usize {
+    // Must match render_file_with_diff: combined diffs always render unified
+    let view_mode = if diff.is_combined() {
+        DiffViewMode::Unified
+    } else {
+        view_mode
+    };
     match view_mode {
         DiffViewMode::Unified => {
             if wrap {
-                diff_line_count_wrapped(diff, unified_wrap_width(content_width))
+                diff_line_count_wrapped(diff, unified_wrap_width(content_width, blame_gutter))
             } else {
                 diff_line_count(diff)
             }
@@ -232,13 +772,19 @@ fn diff_line_count_for_view(
 }
 
 fn diff_line_count(diff: &ParsedDiff) -> usize {
-    diff.hunks.iter().map(|h| 1 + h.lines.len()).sum()
+    diff.hunks
+        .iter()
+        .map(|h| 1 + if h.folded { 0 } else { h.lines.len() })
+        .sum()
 }
 
 fn diff_line_count_wrapped(diff: &ParsedDiff, max_width: usize) -> usize {
     let mut count = 0usize;
     for hunk in &diff.hunks {
         count += 1;
+        if hunk.folded {
+            continue;
+        }
         for line in &hunk.lines {
             count += wrap_line_count(&line.content, max_width);
         }
@@ -250,22 +796,16 @@ fn side_by_side_line_count(diff: &ParsedDiff) -> usize {
     let mut count = 0usize;
     for hunk in &diff.hunks {
         count += 1; // header
+        if hunk.folded {
+            continue;
+        }
         let mut i = 0;
         let lines = &hunk.lines;
         while i < lines.len() {
             match lines[i].kind {
                 crate::diff::DiffLineKind::Removed => {
-                    let mut removals = 0;
-                    while i < lines.len() && lines[i].kind == crate::diff::DiffLineKind::Removed {
-                        removals += 1;
-                        i += 1;
-                    }
-                    let mut additions = 0;
-                    while i < lines.len() && lines[i].kind == crate::diff::DiffLineKind::Added {
-                        additions += 1;
-                        i += 1;
-                    }
-                    count += removals.max(additions);
+                    let (removals, additions) = change_run(lines, &mut i);
+                    count += crate::diff::pair_lines(&removals, &additions).len();
                 }
                 crate::diff::DiffLineKind::Context | crate::diff::DiffLineKind::Added => {
                     count += 1;
@@ -285,6 +825,9 @@ fn side_by_side_line_count_wrapped(
     let mut count = 0usize;
     for hunk in &diff.hunks {
         count += 1;
+        if hunk.folded {
+            continue;
+        }
         let mut i = 0;
         let lines = &hunk.lines;
         while i < lines.len() {
@@ -294,24 +837,12 @@ fn side_by_side_line_count_wrapped(
                     i += 1;
                 }
                 crate::diff::DiffLineKind::Removed => {
-                    let mut removals = Vec::new();
-                    while i < lines.len() && lines[i].kind == crate::diff::DiffLineKind::Removed {
-                        removals.push(&lines[i]);
-                        i += 1;
-                    }
-                    let mut additions = Vec::new();
-                    while i < lines.len() && lines[i].kind == crate::diff::DiffLineKind::Added {
-                        additions.push(&lines[i]);
-                        i += 1;
-                    }
-                    let max_len = removals.len().max(additions.len());
-                    for idx in 0..max_len {
-                        let left_lines = removals
-                            .get(idx)
-                            .map_or(1, |line| wrap_line_count(&line.content, left_width));
-                        let right_lines = additions
-                            .get(idx)
-                            .map_or(1, |line| wrap_line_count(&line.content, right_width));
+                    let (removals, additions) = change_run(lines, &mut i);
+                    for (old, new) in crate::diff::pair_lines(&removals, &additions) {
+                        let left_lines =
+                            old.map_or(1, |k| wrap_line_count(removals[k], left_width));
+                        let right_lines =
+                            new.map_or(1, |k| wrap_line_count(additions[k], right_width));
                         count += left_lines.max(right_lines);
                     }
                 }
@@ -325,20 +856,51 @@ fn side_by_side_line_count_wrapped(
     count
 }
 
-fn comment_block_height(comments: &[Comment], content_width: u32) -> usize {
+/// Removed lines from `lines[*i]` on and the added lines after them, moving
+/// `i` past both. Side-by-side rows pair them with `diff::pair_lines`.
+fn change_run<'a>(
+    lines: &'a [crate::diff::DiffLine],
+    i: &mut usize,
+) -> (Vec<&'a str>, Vec<&'a str>) {
+    let mut run = |kind| {
+        let mut contents = Vec::new();
+        while *i < lines.len() && lines[*i].kind == kind {
+            contents.push(lines[*i].content.as_str());
+            *i += 1;
+        }
+        contents
+    };
+    let removals = run(crate::diff::DiffLineKind::Removed);
+    let additions = run(crate::diff::DiffLineKind::Added);
+    (removals, additions)
+}
+
+/// Must agree with `build_comment_lines` in the diff view.
+fn comment_block_height(
+    thread: &ThreadSummary,
+    comments: &[Comment],
+    events: &[ThreadEvent],
+    muted: bool,
+    content_width: u32,
+) -> usize {
     if comments.is_empty() {
         return 0;
     }
+    if muted {
+        // thread header line + spacing + summary
+        return block_height(3).saturating_sub(block_margin());
+    }
     let max_width =
         content_width.saturating_sub(BLOCK_SIDE_MARGIN * 2 + 1 + BLOCK_LEFT_PAD + BLOCK_RIGHT_PAD);
-    let max_width = max_width as usize;
-    let mut content_lines = 2; // thread header line + spacing
+    let max_width =
+        layout::comment_max_width().map_or(max_width, |cap| max_width.min(cap)) as usize;
+    // thread header line + spacing, then one row per status event
+    let mut content_lines = 2 + events.len();
     for comment in comments {
         content_lines += 1; // author line
-        let wrapped = wrap_text(&comment.body, max_width);
-        content_lines += wrapped.len();
+        content_lines += body_rows(&comment.body, max_width, replaced_lines(thread)).len();
     }
-    block_height(content_lines).saturating_sub(BLOCK_MARGIN)
+    block_height(content_lines).saturating_sub(block_margin())
 }
 
 fn context_display_count(
@@ -346,16 +908,20 @@ fn context_display_count(
     start_line: i64,
     threads: &[ThreadSummary],
     file_path: &str,
+    context_lines: i64,
     wrap: bool,
     content_width: u32,
 ) -> usize {
     let mut ranges = Vec::new();
     #[allow(clippy::cast_possible_wrap)]
     let end_line = start_line + lines.len() as i64 - 1;
-    for thread in threads.iter().filter(|t| t.file_path == file_path) {
+    for thread in threads
+        .iter()
+        .filter(|t| t.file_path == file_path && !t.is_file_thread())
+    {
         let thread_end = thread.selection_end.unwrap_or(thread.selection_start);
-        let start = (thread.selection_start - layout::CONTEXT_LINES).max(start_line);
-        let end = (thread_end + layout::CONTEXT_LINES).min(end_line);
+        let start = (thread.selection_start - context_lines).max(start_line);
+        let end = (thread_end + context_lines).min(end_line);
         if start <= end {
             ranges.push((start, end));
         }
@@ -412,6 +978,7 @@ fn orphaned_context_display_count(
     start_line: i64,
     orphaned_threads: &[&ThreadSummary],
     exclude_ranges: &[(i64, i64)],
+    context_lines: i64,
     wrap: bool,
     content_width: u32,
 ) -> usize {
@@ -421,8 +988,8 @@ fn orphaned_context_display_count(
         .iter()
         .map(|t| {
             let thread_end = t.selection_end.unwrap_or(t.selection_start);
-            let start = (t.selection_start - layout::CONTEXT_LINES).max(start_line);
-            let end = (thread_end + layout::CONTEXT_LINES).min(end_line);
+            let start = (t.selection_start - context_lines).max(start_line);
+            let end = (thread_end + context_lines).min(end_line);
             (start, end)
         })
         .filter(|(start, end)| start <= end)
@@ -503,12 +1070,18 @@ fn orphaned_context_display_count(
 fn threads_comment_height(
     threads: &[&ThreadSummary],
     all_comments: &HashMap<String, Vec<Comment>>,
+    thread_events: &HashMap<String, Vec<ThreadEvent>>,
+    muted_threads: &BTreeSet<String>,
     content_width: u32,
 ) -> usize {
     let mut total = 0;
     for thread in threads {
         if let Some(comments) = all_comments.get(&thread.thread_id) {
-            total += comment_block_height(comments, content_width);
+            let events = thread_events
+                .get(&thread.thread_id)
+                .map_or(&[][..], Vec::as_slice);
+            let muted = muted_threads.contains(&thread.thread_id);
+            total += comment_block_height(thread, comments, events, muted, content_width);
         }
     }
     total
@@ -519,6 +1092,8 @@ fn all_context_extra_lines(
     total_lines: usize,
     file_threads: &[&ThreadSummary],
     all_comments: &HashMap<String, Vec<Comment>>,
+    thread_events: &HashMap<String, Vec<ThreadEvent>>,
+    muted_threads: &BTreeSet<String>,
     content_width: u32,
 ) -> usize {
     #[allow(clippy::cast_possible_wrap)]
@@ -529,7 +1104,11 @@ fn all_context_extra_lines(
             continue;
         }
         if let Some(comments) = all_comments.get(&thread.thread_id) {
-            total += comment_block_height(comments, content_width);
+            let events = thread_events
+                .get(&thread.thread_id)
+                .map_or(&[][..], Vec::as_slice);
+            let muted = muted_threads.contains(&thread.thread_id);
+            total += comment_block_height(thread, comments, events, muted, content_width);
         }
     }
     total
@@ -538,7 +1117,7 @@ fn all_context_extra_lines(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::ThreadSummary;
+    use crate::db::{ThreadSummary, FILE_THREAD_LINE, REVIEW_THREAD_PATH};
 
     fn thread(file_path: &str, start: i64, end: Option<i64>) -> ThreadSummary {
         ThreadSummary {
@@ -546,32 +1125,342 @@ mod tests {
             file_path: file_path.to_string(),
             selection_start: start,
             selection_end: end,
+            side: None,
             status: "open".to_string(),
             comment_count: 1,
         }
     }
 
+    #[test]
+    fn layout_accessors_map_rows_to_files_lines_and_threads() {
+        let rendered = RenderedRows {
+            line_map: HashMap::from([(12, 7), (13, 7), (22, 7), (23, 8)]),
+            old_line_map: HashMap::from([(12, 5)]),
+            thread_positions: HashMap::from([("th-1".to_string(), 24)]),
+        };
+        let layout = StreamLayout {
+            description_lines: 4,
+            file_offsets: vec![10, 20],
+            total_lines: 30,
+            rendered: RenderedRows::default(),
+        }
+        .with_rendered_rows(rendered);
+
+        assert_eq!(layout.file_count(), 2);
+        assert_eq!(layout.file_rows(0), Some(10..20));
+        assert_eq!(layout.file_rows(1), Some(20..30));
+        assert_eq!(layout.file_rows(2), None);
+        assert_eq!(layout.file_at_row(3), None);
+        assert_eq!(layout.file_at_row(19), Some(0));
+        assert_eq!(layout.file_at_row(20), Some(1));
+        assert_eq!(layout.file_at_row(30), None);
+        // Wrapped rows of a line report its first row, per file
+        assert_eq!(layout.line_row(0, ThreadSide::New, 7), Some(12));
+        assert_eq!(layout.line_row(1, ThreadSide::New, 7), Some(22));
+        assert_eq!(layout.line_row(0, ThreadSide::Old, 5), Some(12));
+        assert_eq!(layout.line_row(1, ThreadSide::New, 9), None);
+        assert_eq!(layout.line_at_row(23, ThreadSide::New), Some((1, 8)));
+        assert_eq!(layout.thread_row("th-1"), Some(24));
+        assert_eq!(layout.thread_row("th-2"), None);
+    }
+
     #[test]
     fn context_display_count_uses_window_start_line() {
         let lines: Vec<String> = (100..=109).map(|n| format!("line {n}")).collect();
         let threads = vec![thread("src/lib.rs", 105, None)];
 
-        let count = context_display_count(&lines, 100, &threads, "src/lib.rs", false, 120);
+        let count = context_display_count(
+            &lines,
+            100,
+            &threads,
+            "src/lib.rs",
+            layout::CONTEXT_LINES,
+            false,
+            120,
+        );
 
         assert_eq!(count, 10);
     }
 
+    #[test]
+    fn file_threads_pull_in_no_context() {
+        let lines: Vec<String> = (1..=10).map(|n| format!("line {n}")).collect();
+        let threads = vec![thread("src/lib.rs", FILE_THREAD_LINE, None)];
+
+        let count = context_display_count(
+            &lines,
+            1,
+            &threads,
+            "src/lib.rs",
+            layout::CONTEXT_LINES,
+            false,
+            120,
+        );
+
+        // Just the "No threads" line
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn review_threads_come_before_the_first_file() {
+        let threads = vec![thread(REVIEW_THREAD_PATH, FILE_THREAD_LINE, None)];
+        let comment = Comment {
+            comment_id: "th-1-1".to_string(),
+            author: "bob".to_string(),
+            body: "General question".to_string(),
+            created_at: String::new(),
+        };
+        let all_comments = HashMap::from([("th-1".to_string(), vec![comment])]);
+        let files = [FileEntry {
+            path: "src/lib.rs".to_string(),
+            open_threads: 0,
+            resolved_threads: 0,
+        }];
+        let mut params = StreamLayoutParams {
+            files: &files,
+            file_cache: &HashMap::new(),
+            threads: &[],
+            all_comments: &all_comments,
+            thread_events: &HashMap::new(),
+            view_mode: DiffViewMode::Unified,
+            view_mode_overrides: &HashMap::new(),
+            wrap: false,
+            content_width: 120,
+            description: None,
+            votes: &[],
+            abandoned: None,
+            muted_threads: &BTreeSet::new(),
+            word_diff_files: &HashSet::new(),
+            viewed_files: &HashSet::new(),
+            folds: &Folds::default(),
+            context_lines: layout::CONTEXT_LINES,
+            blame_gutter: false,
+        };
+        assert_eq!(compute_stream_layout(&params).file_offsets, [0]);
+
+        params.threads = &threads;
+        let block = review_threads_height(
+            &threads,
+            &all_comments,
+            &HashMap::new(),
+            &BTreeSet::new(),
+            120,
+        );
+        assert!(block > 0);
+        assert_eq!(compute_stream_layout(&params).file_offsets, [block]);
+    }
+
+    #[test]
+    fn viewed_files_collapse_to_their_header() {
+        let files: Vec<FileEntry> = ["a.rs", "b.rs"]
+            .iter()
+            .map(|path| FileEntry {
+                path: (*path).to_string(),
+                open_threads: 0,
+                resolved_threads: 0,
+            })
+            .collect();
+        let entry = FileCacheEntry {
+            diff: Some(ParsedDiff::parse(
+                "--- a/a.rs\n+++ b/a.rs\n@@ -1,2 +1,2 @@\n one\n-two\n+TWO\n",
+            )),
+            file_content: None,
+            highlighted_lines: Vec::new(),
+            file_highlighted_lines: Vec::new(),
+            word_lines: None,
+            normalized: false,
+            revision: 0,
+        };
+        let file_cache = HashMap::from([("a.rs".to_string(), entry)]);
+        let viewed = HashSet::from(["a.rs".to_string()]);
+        let mut params = StreamLayoutParams {
+            files: &files,
+            file_cache: &file_cache,
+            threads: &[],
+            all_comments: &HashMap::new(),
+            thread_events: &HashMap::new(),
+            view_mode: DiffViewMode::Unified,
+            view_mode_overrides: &HashMap::new(),
+            wrap: false,
+            content_width: 120,
+            description: None,
+            votes: &[],
+            abandoned: None,
+            muted_threads: &BTreeSet::new(),
+            word_diff_files: &HashSet::new(),
+            viewed_files: &HashSet::new(),
+            folds: &Folds::default(),
+            context_lines: layout::CONTEXT_LINES,
+            blame_gutter: false,
+        };
+        assert!(compute_stream_layout(&params).file_offsets[1] > block_height(1));
+
+        params.viewed_files = &viewed;
+        assert_eq!(
+            compute_stream_layout(&params).file_offsets,
+            [0, block_height(1)]
+        );
+    }
+
+    #[test]
+    fn folded_hunks_shrink_to_their_header_and_keep_their_threads() {
+        let files: Vec<FileEntry> = ["a.rs", "b.rs"]
+            .iter()
+            .map(|path| FileEntry {
+                path: (*path).to_string(),
+                open_threads: 0,
+                resolved_threads: 0,
+            })
+            .collect();
+        let diff = ParsedDiff::parse(
+            "--- a/a.rs\n+++ b/a.rs\n@@ -1,2 +1,2 @@\n one\n-two\n+TWO\n\
+             @@ -10,2 +10,2 @@\n ten\n-eleven\n+ELEVEN\n",
+        );
+        let entry = FileCacheEntry {
+            diff: Some(diff.clone()),
+            file_content: None,
+            highlighted_lines: Vec::new(),
+            file_highlighted_lines: Vec::new(),
+            word_lines: None,
+            normalized: false,
+            revision: 0,
+        };
+        let file_cache = HashMap::from([("a.rs".to_string(), entry)]);
+        let no_folds = Folds::default();
+        let mut hunk_folded = Folds::default();
+        assert!(hunk_folded.toggle_hunk("a.rs", 0));
+        let mut file_folded = hunk_folded.clone();
+        assert!(!file_folded.toggle_hunk("a.rs", 0));
+        assert!(file_folded.toggle_file("a.rs"));
+        let mut params = StreamLayoutParams {
+            files: &files,
+            file_cache: &file_cache,
+            threads: &[],
+            all_comments: &HashMap::new(),
+            thread_events: &HashMap::new(),
+            view_mode: DiffViewMode::Unified,
+            view_mode_overrides: &HashMap::new(),
+            wrap: false,
+            content_width: 120,
+            description: None,
+            votes: &[],
+            abandoned: None,
+            muted_threads: &BTreeSet::new(),
+            word_diff_files: &HashSet::new(),
+            viewed_files: &HashSet::new(),
+            folds: &no_folds,
+            context_lines: layout::CONTEXT_LINES,
+            blame_gutter: false,
+        };
+        let unfolded = compute_stream_layout(&params).file_offsets[1];
+
+        params.folds = &hunk_folded;
+        assert_eq!(compute_stream_layout(&params).file_offsets[1], unfolded - 3);
+
+        // A thread inside the folded hunk hangs off its header
+        let folded = hunk_folded.apply("a.rs", &diff);
+        let inside = thread("a.rs", 2, None);
+        let anchors = crate::view::map_threads_to_diff(&folded, &[&inside]);
+        assert_eq!(anchors[0].hunk, Some(0));
+        assert_eq!(anchors[0].display_line, 0);
+
+        params.folds = &file_folded;
+        assert_eq!(
+            compute_stream_layout(&params).file_offsets,
+            [0, block_height(1)]
+        );
+    }
+
     #[test]
     fn orphaned_context_count_uses_window_start_line_and_exclusions() {
         let lines: Vec<String> = (100..=109).map(|n| format!("line {n}")).collect();
         let thread = thread("src/lib.rs", 105, None);
         let threads = vec![&thread];
 
-        let unclipped = orphaned_context_display_count(&lines, 100, &threads, &[], false, 120);
+        let unclipped = orphaned_context_display_count(
+            &lines,
+            100,
+            &threads,
+            &[],
+            layout::CONTEXT_LINES,
+            false,
+            120,
+        );
         assert_eq!(unclipped, 10);
 
-        let clipped =
-            orphaned_context_display_count(&lines, 100, &threads, &[(103, 106)], false, 120);
+        let clipped = orphaned_context_display_count(
+            &lines,
+            100,
+            &threads,
+            &[(103, 106)],
+            layout::CONTEXT_LINES,
+            false,
+            120,
+        );
         assert_eq!(clipped, 7);
     }
+
+    #[test]
+    fn stream_cache_layout_follows_width_wrap_and_view_mode() {
+        let files = [FileEntry {
+            path: "a.rs".to_string(),
+            open_threads: 0,
+            resolved_threads: 0,
+        }];
+        let long = "x".repeat(300);
+        let entry = FileCacheEntry {
+            diff: Some(ParsedDiff::parse(&format!(
+                "--- a/a.rs\n+++ b/a.rs\n@@ -1,2 +1,2 @@\n one\n-two\n+{long}\n"
+            ))),
+            file_content: None,
+            highlighted_lines: Vec::new(),
+            file_highlighted_lines: Vec::new(),
+            word_lines: None,
+            normalized: false,
+            revision: 0,
+        };
+        let file_cache = HashMap::from([("a.rs".to_string(), entry)]);
+        let mut params = StreamLayoutParams {
+            files: &files,
+            file_cache: &file_cache,
+            threads: &[],
+            all_comments: &HashMap::new(),
+            thread_events: &HashMap::new(),
+            view_mode: DiffViewMode::Unified,
+            view_mode_overrides: &HashMap::new(),
+            wrap: false,
+            content_width: 120,
+            description: None,
+            votes: &[],
+            abandoned: None,
+            muted_threads: &BTreeSet::new(),
+            word_diff_files: &HashSet::new(),
+            viewed_files: &HashSet::new(),
+            folds: &Folds::default(),
+            context_lines: layout::CONTEXT_LINES,
+            blame_gutter: false,
+        };
+        let mut cache = StreamCache::default();
+        let unwrapped = cache.layout(&params).total_lines;
+        assert_eq!(unwrapped, compute_stream_layout(&params).total_lines);
+
+        params.wrap = true;
+        let wrapped = cache.layout(&params).total_lines;
+        assert!(wrapped > unwrapped);
+        assert_eq!(wrapped, compute_stream_layout(&params).total_lines);
+
+        params.content_width = 60;
+        let unified = cache.layout(&params).total_lines;
+        assert_eq!(unified, compute_stream_layout(&params).total_lines);
+
+        // A per-file override lays the file out like the global mode would
+        let overrides = HashMap::from([("a.rs".to_string(), DiffViewMode::SideBySide)]);
+        let no_overrides = HashMap::new();
+        params.view_mode_overrides = &overrides;
+        let overridden = cache.layout(&params).total_lines;
+        assert_ne!(overridden, unified);
+        params.view_mode_overrides = &no_overrides;
+        params.view_mode = DiffViewMode::SideBySide;
+        assert_eq!(cache.layout(&params).total_lines, overridden);
+    }
 }
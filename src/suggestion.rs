@@ -0,0 +1,258 @@
+//! Suggested changes: a ```` ```suggestion ```` block in a comment proposes
+//! replacement text for the thread's lines.
+//!
+//! In the diff stream the block is drawn as a mini-diff (the thread's lines
+//! removed, the suggestion added). `S` applies it to the working copy as a
+//! patch through `vcs::apply_patch`; `U` reverses the last one applied.
+
+use std::ops::RangeInclusive;
+
+use crate::db::{ThreadSide, ThreadSummary, FILE_THREAD_LINE, REVIEW_THREAD_PATH};
+use crate::text::wrap_text;
+
+/// Info string that marks a fenced block as a suggestion.
+const INFO: &str = "suggestion";
+
+/// Unchanged lines kept around the replaced ones in a patch.
+const PATCH_CONTEXT: usize = 3;
+
+/// New-side lines a suggestion on `thread` replaces. File and review
+/// threads, and threads on the old side, take no suggestions.
+#[must_use]
+pub fn replaced_lines(thread: &ThreadSummary) -> Option<RangeInclusive<i64>> {
+    target_lines(
+        &thread.file_path,
+        thread.selection_start,
+        thread.selection_end,
+        thread.side,
+    )
+}
+
+/// [`replaced_lines`] for a comment target.
+#[must_use]
+pub fn target_lines(
+    file_path: &str,
+    start: i64,
+    end: Option<i64>,
+    side: Option<ThreadSide>,
+) -> Option<RangeInclusive<i64>> {
+    if file_path == REVIEW_THREAD_PATH || start == FILE_THREAD_LINE || side == Some(ThreadSide::Old)
+    {
+        return None;
+    }
+    Some(start..=end.unwrap_or(start).max(start))
+}
+
+/// A fenced ```` ```suggestion ```` block around `lines`, for the editor.
+#[must_use]
+pub fn suggestion_block(lines: &[String]) -> String {
+    let mut block = vec![format!("```{INFO}")];
+    block.extend(lines.iter().cloned());
+    block.push("```".to_string());
+    block.join("\n")
+}
+
+/// Part of a comment body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BodyPart {
+    Text(String),
+    /// Replacement lines of a suggestion block
+    Suggestion(Vec<String>),
+}
+
+/// `body` split around its suggestion blocks. An unclosed block runs to the
+/// end of the body.
+#[must_use]
+pub fn body_parts(body: &str) -> Vec<BodyPart> {
+    let mut parts = Vec::new();
+    let mut text: Vec<&str> = Vec::new();
+    let mut lines = body.lines();
+    while let Some(line) = lines.next() {
+        let Some(fence) = suggestion_fence(line) else {
+            text.push(line);
+            continue;
+        };
+        if !text.is_empty() {
+            parts.push(BodyPart::Text(text.join("\n")));
+            text.clear();
+        }
+        let suggested = lines
+            .by_ref()
+            .take_while(|line| !closes(line, fence))
+            .map(str::to_string)
+            .collect();
+        parts.push(BodyPart::Suggestion(suggested));
+    }
+    if !text.is_empty() {
+        parts.push(BodyPart::Text(text.join("\n")));
+    }
+    parts
+}
+
+/// Length of the backtick fence if `line` opens a suggestion block.
+fn suggestion_fence(line: &str) -> Option<usize> {
+    let trimmed = line.trim();
+    let fence = trimmed.chars().take_while(|&c| c == '`').count();
+    (fence >= 3 && trimmed[fence..].trim() == INFO).then_some(fence)
+}
+
+/// Whether `line` closes a block opened with `fence` backticks.
+fn closes(line: &str, fence: usize) -> bool {
+    let trimmed = line.trim();
+    trimmed.len() >= fence && trimmed.chars().all(|c| c == '`')
+}
+
+/// Replacement lines of the last suggestion in `body`.
+#[must_use]
+pub fn last_suggestion(body: &str) -> Option<Vec<String>> {
+    body_parts(body)
+        .into_iter()
+        .rev()
+        .find_map(|part| match part {
+            BodyPart::Suggestion(lines) => Some(lines),
+            BodyPart::Text(_) => None,
+        })
+}
+
+/// A row of a comment body as drawn in a comment block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BodyRow {
+    Text(String),
+    /// Heading over a suggestion's mini-diff
+    SuggestionLabel,
+    /// A replaced line, by new-side line number
+    Removed(i64),
+    Added(String),
+}
+
+/// Rows of `body` at `width`: text is word-wrapped, and with `replaced`
+/// lines each suggestion becomes a heading, the replaced lines and the
+/// suggested ones. Diff rows aren't wrapped.
+#[must_use]
+pub fn body_rows(body: &str, width: usize, replaced: Option<RangeInclusive<i64>>) -> Vec<BodyRow> {
+    let Some(replaced) = replaced else {
+        return wrap_text(body, width)
+            .into_iter()
+            .map(BodyRow::Text)
+            .collect();
+    };
+    let mut rows = Vec::new();
+    for part in body_parts(body) {
+        match part {
+            BodyPart::Text(text) => {
+                rows.extend(wrap_text(&text, width).into_iter().map(BodyRow::Text));
+            }
+            BodyPart::Suggestion(lines) => {
+                rows.push(BodyRow::SuggestionLabel);
+                rows.extend(replaced.clone().map(BodyRow::Removed));
+                rows.extend(lines.into_iter().map(BodyRow::Added));
+            }
+        }
+    }
+    rows
+}
+
+/// A suggestion as a patch against one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuggestionPatch {
+    pub file_path: String,
+    /// Unified diff
+    pub patch: String,
+}
+
+impl SuggestionPatch {
+    /// Patch replacing `original` (lines from `start`) with `replacement`.
+    /// `line` gives the text of other new-side lines, for context.
+    #[must_use]
+    pub fn new(
+        file_path: &str,
+        start: i64,
+        original: &[String],
+        replacement: &[String],
+        line: impl Fn(i64) -> Option<String>,
+    ) -> Self {
+        #[allow(clippy::cast_possible_wrap)]
+        let end = start + original.len() as i64;
+        let mut before: Vec<String> = (1..start)
+            .rev()
+            .take(PATCH_CONTEXT)
+            .map_while(&line)
+            .collect();
+        before.reverse();
+        let after: Vec<String> = (end..).take(PATCH_CONTEXT).map_while(&line).collect();
+
+        #[allow(clippy::cast_possible_wrap)]
+        let first = start - before.len() as i64;
+        let context = before.len() + after.len();
+        let mut patch = format!(
+            "--- a/{file_path}\n+++ b/{file_path}\n@@ -{first},{} +{first},{} @@\n",
+            context + original.len(),
+            context + replacement.len()
+        );
+        let lines = before
+            .iter()
+            .map(|l| format!(" {l}"))
+            .chain(original.iter().map(|l| format!("-{l}")))
+            .chain(replacement.iter().map(|l| format!("+{l}")))
+            .chain(after.iter().map(|l| format!(" {l}")));
+        for line in lines {
+            patch.push_str(&line);
+            patch.push('\n');
+        }
+        Self {
+            file_path: file_path.to_string(),
+            patch,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_body_rows_turn_suggestions_into_a_mini_diff() {
+        let body = "Use a constant:\n````suggestion\nconst N: usize = 4;\n````\nThanks";
+        assert_eq!(
+            body_rows(body, 80, Some(10..=11)),
+            [
+                BodyRow::Text("Use a constant:".to_string()),
+                BodyRow::SuggestionLabel,
+                BodyRow::Removed(10),
+                BodyRow::Removed(11),
+                BodyRow::Added("const N: usize = 4;".to_string()),
+                BodyRow::Text("Thanks".to_string()),
+            ]
+        );
+        assert_eq!(body_rows(body, 80, None).len(), 5);
+        assert_eq!(
+            last_suggestion("```suggestion\n```"),
+            Some(Vec::new()),
+            "an empty suggestion deletes the lines"
+        );
+        assert_eq!(last_suggestion("```rust\nx\n```"), None);
+    }
+
+    #[test]
+    fn test_patch_keeps_context_around_replaced_lines() {
+        let file = ["a", "b", "c", "d", "e"];
+        let patch = SuggestionPatch::new(
+            "src/lib.rs",
+            2,
+            &strings(&["b", "c"]),
+            &strings(&["B"]),
+            |n| {
+                file.get(usize::try_from(n - 1).ok()?)
+                    .map(ToString::to_string)
+            },
+        );
+        assert_eq!(
+            patch.patch,
+            "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,5 +1,4 @@\n a\n-b\n-c\n+B\n d\n e\n"
+        );
+    }
+}
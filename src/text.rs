@@ -1,5 +1,10 @@
 //! Text utilities
 
+use std::borrow::Cow;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
 /// Wrap text to a maximum width, preserving line breaks.
 #[must_use]
 pub fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
@@ -89,3 +94,145 @@ pub fn wrap_text_preserve(text: &str, max_width: usize) -> Vec<String> {
 
     lines
 }
+
+/// Invisible unless a line breaks at it; never drawn in single-line headers.
+const SOFT_HYPHEN: char = '\u{ad}';
+
+fn strip_soft_hyphens(text: &str) -> Cow<'_, str> {
+    if text.contains(SOFT_HYPHEN) {
+        Cow::Owned(text.replace(SOFT_HYPHEN, ""))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Terminal columns taken by `text`, ignoring soft hyphens.
+#[must_use]
+pub fn display_width(text: &str) -> usize {
+    text.graphemes(true)
+        .filter(|g| *g != "\u{ad}")
+        .map(UnicodeWidthStr::width)
+        .sum()
+}
+
+/// A byte count for people: `512 B`, `12.3 KB`, `4.0 MB` (powers of 1024).
+#[must_use]
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Longest prefix of whole grapheme clusters that fits in `max_width` columns.
+fn head_within(text: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    for (idx, grapheme) in text.grapheme_indices(true) {
+        width += grapheme.width();
+        if width > max_width {
+            return &text[..idx];
+        }
+    }
+    text
+}
+
+/// Longest suffix of whole grapheme clusters that fits in `max_width` columns.
+fn tail_within(text: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    for (idx, grapheme) in text.grapheme_indices(true).rev() {
+        width += grapheme.width();
+        if width > max_width {
+            return &text[idx + grapheme.len()..];
+        }
+    }
+    text
+}
+
+/// Fit `text` into `max_width` columns, ending it with `…` if it's cut.
+/// Never splits a grapheme cluster; soft hyphens are dropped.
+#[must_use]
+pub fn truncate_end(text: &str, max_width: usize) -> String {
+    let text = strip_soft_hyphens(text);
+    if display_width(&text) <= max_width {
+        return text.into_owned();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+    format!("{}\u{2026}", head_within(&text, max_width - 1))
+}
+
+/// Fit `text` into `max_width` columns by replacing its middle with `…`,
+/// keeping slightly more of the end. Never splits a grapheme cluster; soft
+/// hyphens are dropped.
+#[must_use]
+pub fn truncate_middle(text: &str, max_width: usize) -> String {
+    let text = strip_soft_hyphens(text);
+    if display_width(&text) <= max_width {
+        return text.into_owned();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+    let keep = max_width - 1;
+    let head = head_within(&text, keep / 2);
+    let tail = tail_within(&text, keep - display_width(head));
+    format!("{head}\u{2026}{tail}")
+}
+
+/// Fit a path into `max_width` columns.
+///
+/// The file name stays whole when it fits, with the directory cut short
+/// before it (`src/vi…/mod.rs`); otherwise the path loses its middle, so the
+/// start and the extension both stay visible.
+#[must_use]
+pub fn truncate_path(path: &str, max_width: usize) -> String {
+    let path = strip_soft_hyphens(path);
+    if display_width(&path) <= max_width {
+        return path.into_owned();
+    }
+    if let Some(idx) = path.rfind('/') {
+        let filename = &path[idx + 1..];
+        let filename_width = display_width(filename);
+        if filename_width + 2 <= max_width {
+            let dir = head_within(&path[..idx], max_width - filename_width - 2);
+            return format!("{dir}\u{2026}/{filename}");
+        }
+    }
+    truncate_middle(&path, max_width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_path_keeps_filename_and_graphemes() {
+        assert_eq!(truncate_path("src/view/mod.rs", 20), "src/view/mod.rs");
+        assert_eq!(truncate_path("src/view/diff/mod.rs", 14), "src/vi…/mod.rs");
+        // Wide characters count two columns and are never split
+        assert_eq!(truncate_path("文档/数据库/池.rs", 12), "文档/…/池.rs");
+        // Too long even for the file name: elide the middle, keep the extension
+        assert_eq!(truncate_path("docs/final-draft.md", 12), "docs/…aft.md");
+        assert_eq!(truncate_path("hy\u{ad}phen/a.rs", 20), "hyphen/a.rs");
+    }
+
+    #[test]
+    fn test_truncate_middle_and_end() {
+        assert_eq!(truncate_middle("abcdefghij", 5), "ab…ij");
+        assert_eq!(truncate_middle("👩‍💻abcdef", 4), "…def");
+        assert_eq!(truncate_end("日本語テキスト", 7), "日本語…");
+        assert_eq!(truncate_end("abc", 0), "");
+        // Combining accents stay attached to their base letter
+        assert_eq!(truncate_end("re\u{301}sume\u{301}", 3), "re\u{301}…");
+        assert_eq!(display_width("e\u{301}\u{ad}文"), 3);
+    }
+}
@@ -36,6 +36,11 @@ pub struct DiffTheme {
     pub removed_bg: Rgba,
     /// Background for context lines
     pub context_bg: Rgba,
+    /// Background of the changed words in an added line paired with a
+    /// removed one (side-by-side)
+    pub added_emphasis_bg: Rgba,
+    /// Background of the changed words in a removed line
+    pub removed_emphasis_bg: Rgba,
 
     /// Line number text color
     pub line_number: Rgba,
@@ -58,6 +63,10 @@ pub struct Theme {
     pub border: Rgba,
     pub border_focused: Rgba,
     pub panel_bg: Rgba,
+    /// Title-bar background of the focused pane
+    pub chrome_focused: Rgba,
+    /// Title-bar background of the other panes
+    pub chrome_unfocused: Rgba,
 
     // Selection/highlighting
     pub selection_bg: Rgba,
@@ -132,6 +141,47 @@ impl DiffTheme {
     pub const fn style_line_number(&self, bg: Rgba) -> Style {
         Style::fg(self.line_number).with_bg(bg)
     }
+
+    /// Apply a seed theme's diff overrides (emphasis is derived later).
+    fn apply_overrides(&mut self, ov: &ThemeOverrides) -> anyhow::Result<()> {
+        apply_override(&mut self.added, ov.diff_added.as_ref())?;
+        apply_override(&mut self.removed, ov.diff_removed.as_ref())?;
+        apply_override(&mut self.context, ov.diff_context.as_ref())?;
+        apply_override(&mut self.hunk_header, ov.diff_hunk_header.as_ref())?;
+        apply_override(&mut self.highlight_added, ov.diff_highlight_added.as_ref())?;
+        apply_override(
+            &mut self.highlight_removed,
+            ov.diff_highlight_removed.as_ref(),
+        )?;
+        apply_override(&mut self.added_bg, ov.diff_added_bg.as_ref())?;
+        apply_override(&mut self.removed_bg, ov.diff_removed_bg.as_ref())?;
+        apply_override(&mut self.context_bg, ov.diff_context_bg.as_ref())?;
+        apply_override(&mut self.line_number, ov.diff_line_number.as_ref())?;
+        apply_override(
+            &mut self.added_line_number_bg,
+            ov.diff_added_line_number_bg.as_ref(),
+        )?;
+        apply_override(
+            &mut self.removed_line_number_bg,
+            ov.diff_removed_line_number_bg.as_ref(),
+        )?;
+        Ok(())
+    }
+
+    /// Set the emphasis backgrounds from the highlight and line colors,
+    /// unless given.
+    fn derive_emphasis(
+        &mut self,
+        added: Option<&String>,
+        removed: Option<&String>,
+    ) -> anyhow::Result<()> {
+        let emphasis_bg =
+            |highlight, line_bg| color_blend_over(color_with_alpha(highlight, 0.3), line_bg);
+        self.added_emphasis_bg = emphasis_bg(self.highlight_added, self.added_bg);
+        self.removed_emphasis_bg = emphasis_bg(self.highlight_removed, self.removed_bg);
+        apply_override(&mut self.added_emphasis_bg, added)?;
+        apply_override(&mut self.removed_emphasis_bg, removed)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -159,6 +209,8 @@ pub struct ThemeOverrides {
     pub selection_fg: Option<String>,
     pub border: Option<String>,
     pub border_focused: Option<String>,
+    pub chrome_focused: Option<String>,
+    pub chrome_unfocused: Option<String>,
     pub cursor: Option<String>,
     // Diff
     pub diff_added: Option<String>,
@@ -170,6 +222,8 @@ pub struct ThemeOverrides {
     pub diff_added_bg: Option<String>,
     pub diff_removed_bg: Option<String>,
     pub diff_context_bg: Option<String>,
+    pub diff_added_emphasis_bg: Option<String>,
+    pub diff_removed_emphasis_bg: Option<String>,
     pub diff_line_number: Option<String>,
     pub diff_added_line_number_bg: Option<String>,
     pub diff_removed_line_number_bg: Option<String>,
@@ -228,6 +282,8 @@ impl Theme {
             added_bg: color_blend_over(color_with_alpha(success, 0.08), bg),
             removed_bg: color_blend_over(color_with_alpha(error, 0.08), bg),
             context_bg: bg,
+            added_emphasis_bg: Rgba::TRANSPARENT,
+            removed_emphasis_bg: Rgba::TRANSPARENT,
             line_number: muted,
             added_line_number_bg: color_blend_over(color_with_alpha(success, 0.05), bg),
             removed_line_number_bg: color_blend_over(color_with_alpha(error, 0.05), bg),
@@ -249,27 +305,7 @@ impl Theme {
             apply_override(&mut border_focused, ov.border_focused.as_ref())?;
             apply_override(&mut cursor, ov.cursor.as_ref())?;
 
-            apply_override(&mut diff.added, ov.diff_added.as_ref())?;
-            apply_override(&mut diff.removed, ov.diff_removed.as_ref())?;
-            apply_override(&mut diff.context, ov.diff_context.as_ref())?;
-            apply_override(&mut diff.hunk_header, ov.diff_hunk_header.as_ref())?;
-            apply_override(&mut diff.highlight_added, ov.diff_highlight_added.as_ref())?;
-            apply_override(
-                &mut diff.highlight_removed,
-                ov.diff_highlight_removed.as_ref(),
-            )?;
-            apply_override(&mut diff.added_bg, ov.diff_added_bg.as_ref())?;
-            apply_override(&mut diff.removed_bg, ov.diff_removed_bg.as_ref())?;
-            apply_override(&mut diff.context_bg, ov.diff_context_bg.as_ref())?;
-            apply_override(&mut diff.line_number, ov.diff_line_number.as_ref())?;
-            apply_override(
-                &mut diff.added_line_number_bg,
-                ov.diff_added_line_number_bg.as_ref(),
-            )?;
-            apply_override(
-                &mut diff.removed_line_number_bg,
-                ov.diff_removed_line_number_bg.as_ref(),
-            )?;
+            diff.apply_overrides(ov)?;
 
             apply_override(&mut syntax.keyword, ov.syntax_keyword.as_ref())?;
             apply_override(&mut syntax.function, ov.syntax_function.as_ref())?;
@@ -284,6 +320,20 @@ impl Theme {
             apply_override(&mut syntax.attribute, ov.syntax_attribute.as_ref())?;
         }
 
+        // Chrome follows the (possibly overridden) panel and focus colors
+        let mut chrome_focused = focused_chrome(panel_bg, border_focused);
+        let mut chrome_unfocused = panel_bg;
+        if let Some(ov) = overrides {
+            apply_override(&mut chrome_focused, ov.chrome_focused.as_ref())?;
+            apply_override(&mut chrome_unfocused, ov.chrome_unfocused.as_ref())?;
+        }
+
+        // Word emphasis follows the (possibly overridden) highlight and line colors
+        diff.derive_emphasis(
+            overrides.and_then(|ov| ov.diff_added_emphasis_bg.as_ref()),
+            overrides.and_then(|ov| ov.diff_removed_emphasis_bg.as_ref()),
+        )?;
+
         Ok(Self {
             name,
             background: bg,
@@ -291,6 +341,8 @@ impl Theme {
             border,
             border_focused,
             panel_bg,
+            chrome_focused,
+            chrome_unfocused,
             selection_bg,
             selection_fg,
             cursor,
@@ -409,6 +461,10 @@ pub struct ThemeColors {
     pub border: String,
     pub border_focused: String,
     pub panel_bg: String,
+    /// Derived from `panel_bg` and `border_focused` when absent
+    pub chrome_focused: Option<String>,
+    /// `panel_bg` when absent
+    pub chrome_unfocused: Option<String>,
     pub selection_bg: String,
     pub selection_fg: String,
     pub cursor: String,
@@ -428,6 +484,10 @@ pub struct ThemeColors {
     pub diff_added_bg: String,
     pub diff_removed_bg: String,
     pub diff_context_bg: String,
+    /// Derived from `diff_highlight_added` and `diff_added_bg` when absent
+    pub diff_added_emphasis_bg: Option<String>,
+    /// Derived from `diff_highlight_removed` and `diff_removed_bg` when absent
+    pub diff_removed_emphasis_bg: Option<String>,
     pub diff_line_number: String,
     pub diff_added_line_number_bg: String,
     pub diff_removed_line_number_bg: String,
@@ -468,13 +528,41 @@ impl TryFrom<ThemeFile> for Theme {
         apply_override(&mut syntax.variable, c.syntax_variable.as_ref())?;
         apply_override(&mut syntax.constant, c.syntax_constant.as_ref())?;
         apply_override(&mut syntax.attribute, c.syntax_attribute.as_ref())?;
+        let border_focused = parse_color(&c.border_focused)?;
+        let panel_bg = parse_color(&c.panel_bg)?;
+        let mut chrome_focused = focused_chrome(panel_bg, border_focused);
+        let mut chrome_unfocused = panel_bg;
+        apply_override(&mut chrome_focused, c.chrome_focused.as_ref())?;
+        apply_override(&mut chrome_unfocused, c.chrome_unfocused.as_ref())?;
+        let mut diff = DiffTheme {
+            added: parse_color(&c.diff_added)?,
+            removed: parse_color(&c.diff_removed)?,
+            context: parse_color(&c.diff_context)?,
+            hunk_header: parse_color(&c.diff_hunk_header)?,
+            highlight_added: parse_color(&c.diff_highlight_added)?,
+            highlight_removed: parse_color(&c.diff_highlight_removed)?,
+            added_bg: parse_color(&c.diff_added_bg)?,
+            removed_bg: parse_color(&c.diff_removed_bg)?,
+            context_bg: parse_color(&c.diff_context_bg)?,
+            added_emphasis_bg: Rgba::TRANSPARENT,
+            removed_emphasis_bg: Rgba::TRANSPARENT,
+            line_number: parse_color(&c.diff_line_number)?,
+            added_line_number_bg: parse_color(&c.diff_added_line_number_bg)?,
+            removed_line_number_bg: parse_color(&c.diff_removed_line_number_bg)?,
+        };
+        diff.derive_emphasis(
+            c.diff_added_emphasis_bg.as_ref(),
+            c.diff_removed_emphasis_bg.as_ref(),
+        )?;
         Ok(Self {
             name: file.name,
             background: parse_color(&c.background)?,
             foreground: parse_color(&c.foreground)?,
             border: parse_color(&c.border)?,
-            border_focused: parse_color(&c.border_focused)?,
-            panel_bg: parse_color(&c.panel_bg)?,
+            border_focused,
+            panel_bg,
+            chrome_focused,
+            chrome_unfocused,
             selection_bg: parse_color(&c.selection_bg)?,
             selection_fg: parse_color(&c.selection_fg)?,
             cursor: parse_color(&c.cursor)?,
@@ -483,20 +571,7 @@ impl TryFrom<ThemeFile> for Theme {
             warning: parse_color(&c.warning)?,
             error: parse_color(&c.error)?,
             muted: parse_color(&c.muted)?,
-            diff: DiffTheme {
-                added: parse_color(&c.diff_added)?,
-                removed: parse_color(&c.diff_removed)?,
-                context: parse_color(&c.diff_context)?,
-                hunk_header: parse_color(&c.diff_hunk_header)?,
-                highlight_added: parse_color(&c.diff_highlight_added)?,
-                highlight_removed: parse_color(&c.diff_highlight_removed)?,
-                added_bg: parse_color(&c.diff_added_bg)?,
-                removed_bg: parse_color(&c.diff_removed_bg)?,
-                context_bg: parse_color(&c.diff_context_bg)?,
-                line_number: parse_color(&c.diff_line_number)?,
-                added_line_number_bg: parse_color(&c.diff_added_line_number_bg)?,
-                removed_line_number_bg: parse_color(&c.diff_removed_line_number_bg)?,
-            },
+            diff,
             syntax,
         })
     }
@@ -510,6 +585,11 @@ fn parse_color(hex: &str) -> anyhow::Result<Rgba> {
     color_from_hex(hex).ok_or_else(|| anyhow::anyhow!("Invalid hex color: {hex}"))
 }
 
+/// Panel background tinted toward the focus color.
+fn focused_chrome(panel_bg: Rgba, border_focused: Rgba) -> Rgba {
+    color_blend_over(color_with_alpha(border_focused, 0.15), panel_bg)
+}
+
 fn apply_override(target: &mut Rgba, source: Option<&String>) -> anyhow::Result<()> {
     if let Some(hex) = source {
         *target = parse_color(hex)?;
@@ -1,16 +1,34 @@
 //! State update logic (Elm Architecture)
 
-use crate::command::{command_id_to_message, get_commands};
-use crate::layout::visible_stream_rows;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::action_log::{LogStep, ReviewAction};
+use crate::command::{argument_message, command_id_to_message, get_commands, CommandId};
+use crate::compare::Comparison;
+use crate::config::StartupScreen;
+use crate::db::{thread_etag, ThreadSide, Verdict, FILE_THREAD_LINE, REVIEW_THREAD_PATH};
+use crate::diff::is_prose_path;
+use crate::i18n::{tr, tr_fmt, Text};
+use crate::inbox::Inbox;
+use crate::layout::{
+    block_margin, block_padding, command_output_rows, visible_stream_rows, EXPAND_CONTEXT_LINES,
+    FLASH_PREVIEW_WIDTH, WHEEL_SCROLL_ROWS,
+};
 use crate::message::Message;
 use crate::model::{
-    CommentRequest, DiffViewMode, EditorRequest, Focus, InlineEditor, Model, PaletteMode,
-    PendingCommentSubmission, ReviewFilter, Screen,
-};
-use crate::stream::{
-    active_file_index, compute_stream_layout, file_scroll_offset, StreamLayoutParams,
+    dir_key, quoted_snippet, BlameRequest, CommentRequest, ContextExpansion, DiffViewMode,
+    EditorRequest, Focus, InlineEditor, MenuAction, Model, PaletteMode, PatchRequest, ReviewFilter,
+    ReviewStatusChange, Screen, VerdictSubmission, DRAFT_AUTOSAVE,
 };
-use crate::{config, theme, Highlighter};
+use crate::review_command::CommandRun;
+use crate::review_query::ReviewQuery;
+use crate::search::{next_hit_row, prev_hit_row, DiffSearch};
+use crate::state::ReviewProgress;
+use crate::stream::{active_file_index, file_scroll_offset};
+use crate::suggestion::{suggestion_block, target_lines};
+use crate::view::hunk_thread_range;
+use crate::{config, layout, theme, Highlighter};
 
 fn update_list_nav(model: &mut Model, msg: &Message) {
     match msg {
@@ -125,6 +143,109 @@ fn update_cursor(model: &mut Model, msg: &Message) {
     update_active_file_from_scroll(model);
 }
 
+/// Dragging the sidebar divider: the width follows the mouse, within
+/// [`Model::sidebar_width`]'s bounds, and is saved on release.
+fn update_sidebar_resize(model: &mut Model, msg: &Message) {
+    match *msg {
+        Message::StartSidebarResize => model.sidebar_resizing = true,
+        Message::ResizeSidebar(width) => {
+            // Saved as drawn, not as far as the mouse went
+            model.config.sidebar_width = Some(width);
+            model.config.sidebar_width = Some(model.sidebar_width());
+            clamp_diff_scroll(model);
+        }
+        Message::EndSidebarResize => {
+            model.sidebar_resizing = false;
+            let _ = config::save_ui_config(&model.config);
+        }
+        Message::HoverDivider(hover) => model.divider_hover = hover,
+        _ => return,
+    }
+    model.needs_redraw = true;
+}
+
+/// Mouse in the diff pane: a click puts the cursor on the line under it
+/// without moving the view, and a drag selects lines from there in visual
+/// mode, scrolling when it passes an edge.
+fn update_diff_mouse(model: &mut Model, msg: &Message) {
+    match *msg {
+        Message::ClickDiffRow { row, side } => {
+            model.focus = Focus::DiffPane;
+            if let Some(side) = side {
+                model.sbs_side = side;
+            }
+            model.visual_mode = false;
+            model.diff_cursor = row;
+            snap_cursor_to_nearest_stop(model);
+            model.diff_drag_anchor = Some(model.diff_cursor);
+        }
+        Message::DragDiffRow(row) => {
+            let Some(anchor) = model.diff_drag_anchor else {
+                return;
+            };
+            model.diff_cursor = row.min(model.max_stream_row.get().saturating_sub(1));
+            snap_cursor_to_nearest_stop(model);
+            if model.diff_cursor != anchor {
+                model.visual_mode = true;
+                model.visual_anchor = anchor;
+            }
+            let visible = visible_stream_rows(model.height);
+            if model.diff_cursor < model.diff_scroll {
+                model.diff_scroll = model.diff_cursor;
+            } else if model.diff_cursor >= model.diff_scroll + visible {
+                model.diff_scroll = model.diff_cursor + 1 - visible.max(1);
+            }
+            clamp_diff_scroll(model);
+        }
+        _ => model.diff_drag_anchor = None,
+    }
+    // The file the cursor is in, not the one at the top, takes comments
+    let active = active_file_index(&model.stream_layout(), model.diff_cursor);
+    if active != model.file_index {
+        set_active_file(model, active);
+    }
+    sync_sidebar_from_active(model);
+    model.needs_redraw = true;
+}
+
+/// Move the cursor to the first line of the next/previous hunk, file or run
+/// of changes. Going back from inside one lands on its own first line, like
+/// vim's `{`.
+fn jump_to_section(model: &mut Model, msg: &Message) {
+    let targets = match msg {
+        Message::CursorNextHunk | Message::CursorPrevHunk => {
+            first_stops_after(&model.cursor_stops.borrow(), &model.hunk_rows.borrow())
+        }
+        Message::CursorNextFile | Message::CursorPrevFile => {
+            let offsets = model.stream_layout().file_offsets;
+            first_stops_after(&model.cursor_stops.borrow(), &offsets)
+        }
+        _ => model.change_rows.borrow().clone(),
+    };
+    let cursor = model.diff_cursor;
+    let target = match msg {
+        Message::CursorNextHunk | Message::CursorNextFile | Message::CursorNextChange => {
+            targets.iter().find(|&&row| row > cursor)
+        }
+        _ => targets.iter().rev().find(|&&row| row < cursor),
+    };
+    if let Some(&row) = target {
+        model.diff_cursor = row;
+        center_cursor_scroll(model);
+        update_active_file_from_scroll(model);
+    }
+}
+
+/// The first cursor stop past each of `starts` (header rows), deduplicated.
+fn first_stops_after(stops: &[usize], starts: &[usize]) -> Vec<usize> {
+    let mut firsts: Vec<usize> = starts
+        .iter()
+        .filter_map(|&start| stops.get(stops.partition_point(|&s| s <= start)).copied())
+        .collect();
+    firsts.dedup();
+    firsts
+}
+
 /// Center the viewport around the cursor position.
 /// When at the top or bottom of the stream, clamps scroll appropriately.
 fn center_cursor_scroll(model: &mut Model) {
@@ -205,6 +326,18 @@ fn update_scroll(model: &mut Model, msg: &Message) {
     update_active_file_from_scroll(model);
 }
 
+/// Move the diff by a wheel step, leaving the cursor where it is. Moving
+/// the cursor recenters on it.
+fn scroll_viewport(model: &mut Model, up: bool) {
+    model.diff_scroll = if up {
+        model.diff_scroll.saturating_sub(WHEEL_SCROLL_ROWS)
+    } else {
+        model.diff_scroll + WHEEL_SCROLL_ROWS
+    };
+    clamp_diff_scroll(model);
+    update_active_file_from_scroll(model);
+}
+
 fn update_thread_nav(model: &mut Model, msg: Message) {
     match msg {
         Message::NextThread => {
@@ -270,7 +403,8 @@ fn update_command_palette(model: &mut Model, msg: Message) {
     match msg {
         Message::ShowCommandPalette => {
             model.command_palette_mode = PaletteMode::Commands;
-            model.command_palette_commands = get_commands();
+            model.command_palette_commands =
+                filter_commands("", model.read_only, &model.palette_recent);
             model.command_palette_input.clear();
             model.command_palette_selection = 0;
             model.previous_focus = Some(model.focus);
@@ -288,11 +422,24 @@ fn update_command_palette(model: &mut Model, msg: Message) {
             model.focus = model.previous_focus.take().unwrap_or(Focus::DiffPane);
             model.needs_redraw = true;
         }
+        Message::PromptCommandArgument(id) => {
+            model.command_palette_mode = PaletteMode::Argument(id);
+            model.command_palette_input.clear();
+            model.command_palette_selection = 0;
+            model.previous_focus = Some(model.focus);
+            model.focus = Focus::CommandPalette;
+            model.needs_redraw = true;
+        }
+        Message::ShowCommentPicker => {
+            model.command_palette_mode = PaletteMode::Comments;
+            model.command_palette_input.clear();
+            model.command_palette_selection = 0;
+            model.previous_focus = Some(model.focus);
+            model.focus = Focus::CommandPalette;
+            model.needs_redraw = true;
+        }
         Message::CommandPaletteNext => {
-            let count = match model.command_palette_mode {
-                PaletteMode::Commands => model.command_palette_commands.len(),
-                PaletteMode::Themes => filter_theme_names(&model.command_palette_input).len(),
-            };
+            let count = palette_item_count(model);
             if count > 0 {
                 model.command_palette_selection = (model.command_palette_selection + 1) % count;
             }
@@ -300,10 +447,7 @@ fn update_command_palette(model: &mut Model, msg: Message) {
             model.needs_redraw = true;
         }
         Message::CommandPalettePrev => {
-            let count = match model.command_palette_mode {
-                PaletteMode::Commands => model.command_palette_commands.len(),
-                PaletteMode::Themes => filter_theme_names(&model.command_palette_input).len(),
-            };
+            let count = palette_item_count(model);
             if count > 0 {
                 model.command_palette_selection =
                     (model.command_palette_selection + count - 1) % count;
@@ -315,7 +459,11 @@ fn update_command_palette(model: &mut Model, msg: Message) {
             model.command_palette_input.push_str(&input);
             model.command_palette_selection = 0;
             if model.command_palette_mode == PaletteMode::Commands {
-                model.command_palette_commands = filter_commands(&model.command_palette_input);
+                model.command_palette_commands = filter_commands(
+                    &model.command_palette_input,
+                    model.read_only,
+                    &model.palette_recent,
+                );
             }
             preview_selected_theme(model);
             model.needs_redraw = true;
@@ -324,7 +472,11 @@ fn update_command_palette(model: &mut Model, msg: Message) {
             model.command_palette_input.pop();
             model.command_palette_selection = 0;
             if model.command_palette_mode == PaletteMode::Commands {
-                model.command_palette_commands = filter_commands(&model.command_palette_input);
+                model.command_palette_commands = filter_commands(
+                    &model.command_palette_input,
+                    model.read_only,
+                    &model.palette_recent,
+                );
             }
             preview_selected_theme(model);
             model.needs_redraw = true;
@@ -333,35 +485,115 @@ fn update_command_palette(model: &mut Model, msg: Message) {
             delete_last_word(&mut model.command_palette_input);
             model.command_palette_selection = 0;
             if model.command_palette_mode == PaletteMode::Commands {
-                model.command_palette_commands = filter_commands(&model.command_palette_input);
+                model.command_palette_commands = filter_commands(
+                    &model.command_palette_input,
+                    model.read_only,
+                    &model.palette_recent,
+                );
             }
             preview_selected_theme(model);
             model.needs_redraw = true;
         }
-        Message::CommandPaletteExecute => {
-            match model.command_palette_mode {
-                PaletteMode::Commands => {
-                    let commands = model.command_palette_commands.clone();
-                    if let Some(command) = commands.get(model.command_palette_selection) {
-                        update(model, Message::HideCommandPalette);
-                        let msg = command_id_to_message(command.id);
-                        update(model, msg);
-                    }
-                }
-                PaletteMode::Themes => {
-                    let theme_names = filter_theme_names(&model.command_palette_input);
-                    if let Some(name) = theme_names.get(model.command_palette_selection) {
-                        let name = name.to_string();
-                        // Clear saved theme so HideCommandPalette won't revert
-                        model.pre_palette_theme = None;
-                        update(model, Message::HideCommandPalette);
-                        update(model, Message::ApplyTheme(name));
-                    }
-                }
+        Message::CommandPaletteExecute => execute_palette_selection(model),
+        _ => {}
+    }
+}
+
+/// Run the selected palette entry for the current mode.
+fn execute_palette_selection(model: &mut Model) {
+    match model.command_palette_mode {
+        PaletteMode::Commands => {
+            let commands = model.command_palette_commands.clone();
+            if let Some(command) = commands.get(model.command_palette_selection) {
+                remember_command(model, command.id);
+                update(model, Message::HideCommandPalette);
+                let msg = command_id_to_message(command.id);
+                update(model, msg);
+            } else if let Some(id) = typed_comment_id(&model.command_palette_input) {
+                // A pasted permalink id jumps straight to the comment
+                update(model, Message::HideCommandPalette);
+                update(model, Message::JumpToComment(id));
+            }
+        }
+        PaletteMode::Comments => {
+            let input = &model.command_palette_input;
+            let id = model
+                .find_comments(input)
+                .get(model.command_palette_selection)
+                .map(|c| c.comment_id.clone())
+                .or_else(|| Some(input.trim().to_string()).filter(|s| !s.is_empty()));
+            if let Some(id) = id {
+                update(model, Message::HideCommandPalette);
+                update(model, Message::JumpToComment(id));
+            }
+        }
+        PaletteMode::Argument(id) => {
+            // Invalid input keeps the prompt open; its hint shows why
+            if let Some(msg) = argument_message(id, &model.command_palette_input) {
+                update(model, Message::HideCommandPalette);
+                update(model, msg);
+            }
+        }
+        PaletteMode::Themes => {
+            let theme_names = filter_theme_names(&model.command_palette_input);
+            if let Some(name) = theme_names.get(model.command_palette_selection) {
+                let name = name.to_string();
+                // Clear saved theme so HideCommandPalette won't revert
+                model.pre_palette_theme = None;
+                update(model, Message::HideCommandPalette);
+                update(model, Message::ApplyTheme(name));
+            }
+        }
+    }
+}
+
+/// Move `id` to the front of the palette's recent commands.
+fn remember_command(model: &mut Model, id: CommandId) {
+    const RECENT_COMMANDS: usize = 5;
+    model.palette_recent.retain(|&recent| recent != id);
+    model.palette_recent.insert(0, id);
+    model.palette_recent.truncate(RECENT_COMMANDS);
+}
+
+fn update_action_menu(model: &mut Model, msg: &Message) {
+    let count = model.action_menu.len();
+    match *msg {
+        Message::ShowActionMenu => {
+            model.action_menu = model.cursor_actions();
+            if model.action_menu.is_empty() {
+                model.flash_message = Some(tr(Text::MenuEmpty).to_string());
+            } else {
+                model.action_menu_selection = 0;
+                model.previous_focus = Some(model.focus);
+                model.focus = Focus::ActionMenu;
+            }
+        }
+        Message::HideActionMenu => close_action_menu(model),
+        Message::ActionMenuNext if count > 0 => {
+            model.action_menu_selection = (model.action_menu_selection + 1) % count;
+        }
+        Message::ActionMenuPrev if count > 0 => {
+            model.action_menu_selection = (model.action_menu_selection + count - 1) % count;
+        }
+        Message::ActionMenuExecute | Message::ActionMenuPick(_) => {
+            let index = match *msg {
+                Message::ActionMenuPick(index) => index,
+                _ => model.action_menu_selection,
+            };
+            let action = model.action_menu.get(index).copied();
+            close_action_menu(model);
+            if let Some(action) = action {
+                update(model, action.message());
             }
         }
         _ => {}
     }
+    model.needs_redraw = true;
+}
+
+fn close_action_menu(model: &mut Model) {
+    model.action_menu.clear();
+    model.focus = model.previous_focus.take().unwrap_or(Focus::DiffPane);
 }
 
 fn update_comment(model: &mut Model, msg: Message) {
@@ -439,20 +671,16 @@ fn update_comment(model: &mut Model, msg: Message) {
                 editor.clear_line();
             }
         }
+        Message::CommentInsertSuggestion => insert_suggestion(model),
         Message::SaveComment => {
-            if let Some(editor) = model.inline_editor.take() {
-                let body = editor.body();
-                if !body.is_empty() {
-                    model.pending_comment_submission = Some(PendingCommentSubmission {
-                        request: editor.request,
-                        body,
-                    });
-                }
-            }
+            // Kept until crit has it, in case sending fails or we crash
+            model.stash_draft();
+            save_inline_editor(model);
             model.visual_mode = false;
             model.focus = Focus::DiffPane;
         }
         Message::CancelComment => {
+            model.stash_draft();
             model.inline_editor = None;
             model.comment_input.clear();
             model.comment_target_line = None;
@@ -470,6 +698,64 @@ fn update_comment(model: &mut Model, msg: Message) {
     model.needs_redraw = true;
 }
 
+/// Queue what the inline editor holds: a verdict with its (optional)
+/// summary, an abandon with its (optional) reason, or a non-empty comment.
+fn save_inline_editor(model: &mut Model) {
+    let Some(editor) = model.inline_editor.take() else {
+        return;
+    };
+    let body = editor.body();
+    if let Some(verdict) = editor.verdict {
+        model.pending_verdict = Some(VerdictSubmission {
+            review_id: editor.request.review_id,
+            verdict,
+            summary: (!body.is_empty()).then_some(body),
+        });
+    } else if editor.abandon {
+        model.pending_review_status = Some(ReviewStatusChange::Abandon {
+            review_id: editor.request.review_id,
+            reason: (!body.is_empty()).then_some(body),
+        });
+    } else if !body.is_empty() {
+        model.queue_comment_submission(editor.request, body);
+    }
+}
+
+fn update_sidebar_scroll(model: &mut Model, msg: &Message) {
+    match msg {
+        Message::SidebarPageUp | Message::SidebarPageDown => {
+            let last = model.sidebar_items().len().saturating_sub(1);
+            let page = sidebar_visible_rows(model).max(1);
+            let index = if matches!(msg, Message::SidebarPageUp) {
+                model.sidebar_index.saturating_sub(page)
+            } else {
+                (model.sidebar_index + page).min(last)
+            };
+            if index != model.sidebar_index {
+                model.sidebar_index = index;
+                sync_file_index_from_sidebar(model);
+                ensure_sidebar_visible(model);
+            }
+        }
+
+        // The wheel moves only the viewport; the next key press brings the
+        // selection back into view
+        Message::SidebarScrollUp => {
+            model.sidebar_scroll = model.sidebar_scroll.saturating_sub(1);
+        }
+
+        Message::SidebarScrollDown => {
+            let max_scroll = model
+                .sidebar_items()
+                .len()
+                .saturating_sub(sidebar_visible_rows(model));
+            model.sidebar_scroll = (model.sidebar_scroll + 1).min(max_scroll);
+        }
+
+        _ => {}
+    }
+}
+
 fn update_file_sidebar(model: &mut Model, msg: &Message) {
     match msg {
         Message::NextFile => {
@@ -510,11 +796,7 @@ fn update_file_sidebar(model: &mut Model, msg: &Message) {
             let file_count = model.files_with_threads().len();
             if *idx < file_count {
                 model.focus = Focus::FileSidebar;
-                if let Some(pos) = model
-                    .sidebar_items()
-                    .iter()
-                    .position(|item| matches!(item, crate::model::SidebarItem::File { file_idx, .. } if *file_idx == *idx))
-                {
+                if let Some(pos) = model.sidebar_position_of_file(*idx) {
                     model.sidebar_index = pos;
                 }
                 jump_to_file(model, *idx);
@@ -527,6 +809,10 @@ fn update_file_sidebar(model: &mut Model, msg: &Message) {
             if let Some(item) = items.get(*idx) {
                 model.sidebar_index = *idx;
                 match item {
+                    crate::model::SidebarItem::Directory { path, .. } => {
+                        model.focus = Focus::FileSidebar;
+                        toggle_collapsed(model, dir_key(path));
+                    }
                     crate::model::SidebarItem::File { file_idx, .. } => {
                         model.focus = Focus::FileSidebar;
                         jump_to_file(model, *file_idx);
@@ -545,23 +831,13 @@ fn update_file_sidebar(model: &mut Model, msg: &Message) {
             let items = model.sidebar_items();
             if let Some(item) = items.get(model.sidebar_index) {
                 match item {
+                    crate::model::SidebarItem::Directory { path, .. } => {
+                        toggle_collapsed(model, dir_key(path));
+                    }
                     crate::model::SidebarItem::File {
-                        entry,
-                        file_idx,
-                        collapsed,
+                        entry, file_idx, ..
                     } => {
-                        // Toggle collapse state
-                        if *collapsed {
-                            model.collapsed_files.remove(&entry.path);
-                        } else {
-                            model.collapsed_files.insert(entry.path.clone());
-                        }
-                        // Clamp sidebar_index to new tree size
-                        let new_len = model.sidebar_items().len();
-                        if new_len > 0 && model.sidebar_index >= new_len {
-                            model.sidebar_index = new_len - 1;
-                        }
-                        ensure_sidebar_visible(model);
+                        toggle_collapsed(model, entry.path.clone());
                         // Also select this file
                         let target = *file_idx;
                         jump_to_file(model, target);
@@ -579,6 +855,95 @@ fn update_file_sidebar(model: &mut Model, msg: &Message) {
     }
 }
 
+/// Collapse or expand the sidebar node `key` in `collapsed_files`, keeping
+/// the selection within the tree.
+fn toggle_collapsed(model: &mut Model, key: String) {
+    if !model.collapsed_files.remove(&key) {
+        model.collapsed_files.insert(key);
+    }
+    let len = model.sidebar_items().len();
+    model.sidebar_index = model.sidebar_index.min(len.saturating_sub(1));
+    ensure_sidebar_visible(model);
+    model.needs_redraw = true;
+}
+
+/// `h` in the tree sidebar: collapse the selected directory, or a file's
+/// threads, else select its parent.
+fn sidebar_collapse(model: &mut Model) {
+    let items = model.sidebar_items();
+    let Some(item) = items.get(model.sidebar_index) else {
+        return;
+    };
+    let has_children = items
+        .get(model.sidebar_index + 1)
+        .is_some_and(|next| next.depth() > item.depth());
+    match item {
+        crate::model::SidebarItem::Directory {
+            path,
+            collapsed: false,
+            ..
+        } => return toggle_collapsed(model, dir_key(path)),
+        crate::model::SidebarItem::File {
+            entry,
+            collapsed: false,
+            ..
+        } if has_children => return toggle_collapsed(model, entry.path.clone()),
+        _ => {}
+    }
+    let depth = item.depth();
+    if let Some(parent) = items[..model.sidebar_index]
+        .iter()
+        .rposition(|item| item.depth() < depth)
+    {
+        model.sidebar_index = parent;
+        sync_file_index_from_sidebar(model);
+        ensure_sidebar_visible(model);
+        model.needs_redraw = true;
+    }
+}
+
+/// `l` in the tree sidebar: expand the selected directory or file, step
+/// into an open directory, else move to the diff pane.
+fn sidebar_expand(model: &mut Model) {
+    let items = model.sidebar_items();
+    match items.get(model.sidebar_index) {
+        Some(crate::model::SidebarItem::Directory {
+            path,
+            collapsed: true,
+            ..
+        }) => toggle_collapsed(model, dir_key(path)),
+        Some(crate::model::SidebarItem::Directory { .. }) => {
+            update_file_sidebar(model, &Message::NextFile);
+        }
+        Some(crate::model::SidebarItem::File {
+            entry,
+            collapsed: true,
+            ..
+        }) if model.threads.iter().any(|t| t.file_path == entry.path) => {
+            toggle_collapsed(model, entry.path.clone());
+        }
+        _ => update(model, Message::ToggleFocus),
+    }
+}
+
+/// Drop the open review and everything loaded for it.
+fn clear_loaded_review(model: &mut Model) {
+    record_review_progress(model);
+    model.current_review = None;
+    model.current_diff = None;
+    model.current_file_content = None;
+    model.highlighted_lines.clear();
+    model.file_cache.clear();
+    model.blame.clear();
+    model.review_load = None;
+    model.pending_files.clear();
+    model.threads.clear();
+    model.all_comments.clear();
+    model.thread_events.clear();
+    model.pending_thread_events.clear();
+    model.needs_redraw = true;
+}
+
 fn update_navigation(model: &mut Model, msg: &Message) {
     match msg {
         Message::SelectReview(id) => {
@@ -602,17 +967,22 @@ fn update_navigation(model: &mut Model, msg: &Message) {
             model.sidebar_index = 0;
             model.sidebar_scroll = 0;
             model.collapsed_files.clear();
+            model.word_diff_files.clear();
+            model.plain_files.clear();
+            model.folds.clear();
+            model.pending_expansion = None;
+            model.diff_view_overrides.clear();
+            model.viewed_files.clear();
+            model.action_log.clear();
+            model.recent_files.clear();
             model.diff_scroll = 0;
             model.diff_cursor = 0;
             model.expanded_thread = None;
-            model.current_review = None; // Clear to trigger reload
-            model.current_diff = None;
-            model.current_file_content = None;
-            model.highlighted_lines.clear();
-            model.file_cache.clear();
-            model.threads.clear();
-            model.all_comments.clear();
-            model.needs_redraw = true;
+            model.diff_search = None;
+            clear_loaded_review(model); // Clear to trigger reload
+                                        // It may change while open; preview it afresh afterwards
+            model.previews.remove(id);
+            remember_last_review(model, id);
             // Note: caller should load review details from DB
         }
 
@@ -621,153 +991,631 @@ fn update_navigation(model: &mut Model, msg: &Message) {
                 model.screen = Screen::ReviewList;
                 model.focus = Focus::ReviewList;
                 model.visual_mode = false;
-                model.current_review = None;
-                model.current_diff = None;
-                model.current_file_content = None;
-                model.highlighted_lines.clear();
-                model.file_cache.clear();
-                model.threads.clear();
-                model.all_comments.clear();
+                model.diff_search = None;
+                // Opened from the inbox: go back there and rescan this review,
+                // since the user probably just replied in it
+                if let Some(inbox) = model.inbox.as_mut() {
+                    if let Some(review) = &model.current_review {
+                        inbox.queue(&review.review_id);
+                    }
+                    model.screen = Screen::Inbox;
+                }
+                clear_loaded_review(model);
+            }
+            Screen::Inbox => {
+                model.screen = Screen::ReviewList;
+                model.inbox = None;
+                model.needs_redraw = true;
+            }
+            Screen::Compare => {
+                // A file's diff-of-diffs closes first, then the screen
+                match model.comparison.as_mut() {
+                    Some(comparison) if comparison.delta.is_some() => comparison.delta = None,
+                    _ => {
+                        model.screen = Screen::ReviewList;
+                        model.comparison = None;
+                    }
+                }
                 model.needs_redraw = true;
             }
             Screen::ReviewList => {
                 // Already at top level, could quit or no-op
             }
         },
-        _ => {}
-    }
-}
-
-fn update_view_filter(model: &mut Model, msg: &Message) {
-    match msg {
-        Message::CycleStatusFilter => {
-            model.filter = match model.filter {
-                ReviewFilter::All => ReviewFilter::Open,
-                ReviewFilter::Open => ReviewFilter::Closed,
-                ReviewFilter::Closed => ReviewFilter::All,
-            };
-            model.list_index = 0;
-            model.list_scroll = 0;
-            model.needs_redraw = true;
-        }
 
-        Message::ToggleDiffView => {
-            model.diff_view_mode = match model.diff_view_mode {
-                DiffViewMode::Unified => DiffViewMode::SideBySide,
-                DiffViewMode::SideBySide => DiffViewMode::Unified,
-            };
+        Message::ShowInbox => {
+            if model.screen == Screen::ReviewDetail {
+                update_navigation(model, &Message::Back);
+            }
+            model.inbox = Some(Inbox::new(&model.reviews, &model.statuses));
+            model.screen = Screen::Inbox;
+            model.focus = Focus::ReviewList;
             model.needs_redraw = true;
-            update_active_file_from_scroll(model);
         }
 
-        Message::ToggleSidebar => {
-            model.sidebar_visible = !model.sidebar_visible;
-            if !model.sidebar_visible && matches!(model.focus, Focus::FileSidebar) {
-                model.focus = Focus::DiffPane;
+        Message::RefreshInbox => {
+            if let Some(inbox) = model.inbox.as_mut() {
+                inbox.queue_open(&model.reviews, &model.statuses);
             }
             model.needs_redraw = true;
-            update_active_file_from_scroll(model);
         }
 
-        Message::ToggleDiffWrap => {
-            model.diff_wrap = !model.diff_wrap;
-            model.needs_redraw = true;
-            update_active_file_from_scroll(model);
-        }
+        Message::OpenInboxItem(index) => open_inbox_item(model, *index),
 
-        Message::OpenFileInEditor => {
-            let files = model.files_with_threads();
-            if let Some(file) = files.get(model.file_index) {
-                let line = model
-                    .expanded_thread
-                    .as_ref()
-                    .and_then(|thread_id| model.threads.iter().find(|t| t.thread_id == *thread_id))
-                    .and_then(|thread| {
-                        // Only use line number if thread is for the current file
-                        if thread.file_path == file.path && thread.selection_start > 0 {
-                            Some(thread.selection_start as u32)
-                        } else {
-                            None
-                        }
-                    });
-                model.pending_editor_request = Some(EditorRequest {
-                    file_path: file.path.clone(),
-                    line,
-                });
-            }
-        }
         _ => {}
     }
 }
 
-fn update_system_theme(model: &mut Model, msg: &Message) {
+/// Marking reviews in the list and opening their comparison.
+fn update_comparison(model: &mut Model, msg: &Message) {
     match msg {
-        Message::Resize { width, height } => {
-            model.resize(*width, *height);
+        Message::ToggleCompareMark(review_id) => {
+            if let Some(position) = model.compare_marks.iter().position(|id| id == review_id) {
+                model.compare_marks.remove(position);
+            } else {
+                model.compare_marks.push(review_id.clone());
+                if model.compare_marks.len() > 2 {
+                    model.compare_marks.remove(0);
+                }
+            }
             model.needs_redraw = true;
-            update_active_file_from_scroll(model);
-        }
-
-        Message::Quit => {
-            model.should_quit = true;
         }
 
-        Message::ShowThemePicker => {
-            model.pre_palette_theme = model.config.theme.clone();
-            model.command_palette_mode = PaletteMode::Themes;
-            model.command_palette_input.clear();
-            let theme_names = filter_theme_names(&model.command_palette_input);
-            model.command_palette_selection = theme_names
-                .iter()
-                .position(|&name| name == model.theme.name)
-                .unwrap_or(0);
-            model.previous_focus = Some(model.focus);
-            model.focus = Focus::CommandPalette;
+        Message::ShowComparison => {
+            if let [left, right] = model.compare_marks.as_slice() {
+                model.comparison = Some(Comparison::new(left, right));
+                model.screen = Screen::Compare;
+                model.focus = Focus::ReviewList;
+            } else {
+                model.flash_message = Some(tr(Text::CompareNeedsTwo).to_string());
+            }
             model.needs_redraw = true;
         }
 
-        Message::ApplyTheme(theme_name) => {
-            if let Some(loaded) = theme::load_built_in_theme(theme_name) {
-                model.theme = loaded.theme;
-                if let Some(syntax_theme) = loaded.syntax_theme {
-                    model.highlighter = Highlighter::with_theme(&syntax_theme);
-                } else if theme_name.to_lowercase().contains("light") {
-                    model.highlighter = Highlighter::with_theme("base16-ocean.light");
-                } else {
-                    model.highlighter = Highlighter::with_theme("base16-ocean.dark");
-                }
-                model.config.theme = Some(theme_name.clone());
-                let _ = config::save_ui_config(&model.config);
-                model.needs_redraw = true;
+        Message::OpenComparedFile => {
+            if let Some(comparison) = model.comparison.as_mut() {
+                comparison.open_selected();
             }
+            model.needs_redraw = true;
         }
         _ => {}
     }
 }
 
-#[allow(clippy::too_many_lines)]
-pub fn update(model: &mut Model, msg: Message) {
-    // Clear transient flash message on any user-initiated action.
-    if model.flash_message.is_some()
-        && !matches!(msg, Message::Tick | Message::Resize { .. } | Message::Noop)
+/// Record the opened review for `startup: last-review`. Only written when
+/// that startup screen is configured, so opening reviews doesn't otherwise
+/// rewrite the config file.
+fn remember_last_review(model: &mut Model, review_id: &str) {
+    if model.config.startup_screen() != StartupScreen::LastReview
+        || model.config.last_review.as_deref() == Some(review_id)
     {
-        model.flash_message = None;
-        model.needs_redraw = true;
+        return;
     }
+    model.config.last_review = Some(review_id.to_string());
+    let _ = config::save_ui_config(&model.config);
+}
 
-    match msg {
-        Message::ListUp
-        | Message::ListDown
-        | Message::ListPageUp
-        | Message::ListPageDown
-        | Message::ListTop
-        | Message::ListBottom => {
-            update_list_nav(model, &msg);
-        }
+/// Open an inbox thread: load its review, then scroll to and expand the thread.
+fn open_inbox_item(model: &mut Model, index: usize) {
+    let Some(inbox) = model.inbox.as_mut() else {
+        return;
+    };
+    let Some(item) = inbox.items.get(index).cloned() else {
+        return;
+    };
+    inbox.selection = index;
+    // SelectReview finds the review in the filtered list
+    if !model
+        .filtered_reviews()
+        .iter()
+        .any(|review| review.review_id == item.review_id)
+    {
+        model.filter = ReviewFilter::All;
+        model.search_input.clear();
+        model.search_active = false;
+    }
+    update_navigation(model, &Message::SelectReview(item.review_id));
+    model.pending_thread = Some(item.thread_id);
+}
+
+fn update_inbox_nav(model: &mut Model, msg: &Message) {
+    let visible = model.list_visible_height().max(1);
+    let Some(inbox) = model.inbox.as_mut() else {
+        return;
+    };
+    let last = inbox.items.len().saturating_sub(1);
+    inbox.selection = match msg {
+        Message::ListUp => inbox.selection.saturating_sub(1),
+        Message::ListDown => (inbox.selection + 1).min(last),
+        Message::ListPageUp => inbox.selection.saturating_sub(visible),
+        Message::ListPageDown => (inbox.selection + visible).min(last),
+        Message::ListTop => 0,
+        Message::ListBottom => last,
+        _ => inbox.selection,
+    };
+    inbox.scroll = inbox.first_visible(visible);
+    model.needs_redraw = true;
+}
+
+/// Move through the compared files, or scroll an open diff-of-diffs.
+fn update_compare_nav(model: &mut Model, msg: &Message) {
+    // Header block + status line (2) + help bar (2); one row per file
+    let chrome = layout::screen_header_height() + 4;
+    let page = (u32::from(model.height).saturating_sub(chrome) as usize).max(1);
+    let Some(comparison) = model.comparison.as_mut() else {
+        return;
+    };
+    if let Some(delta) = &comparison.delta {
+        let last = delta.len().saturating_sub(page);
+        comparison.delta_scroll = match msg {
+            Message::ListUp => comparison.delta_scroll.saturating_sub(1),
+            Message::ListDown => comparison.delta_scroll + 1,
+            Message::ListPageUp => comparison.delta_scroll.saturating_sub(page),
+            Message::ListPageDown => comparison.delta_scroll + page,
+            Message::ListTop => 0,
+            _ => last,
+        }
+        .min(last);
+    } else {
+        let last = comparison.files.len().saturating_sub(1);
+        comparison.selection = match msg {
+            Message::ListUp => comparison.selection.saturating_sub(1),
+            Message::ListDown => (comparison.selection + 1).min(last),
+            Message::ListPageUp => comparison.selection.saturating_sub(page),
+            Message::ListPageDown => (comparison.selection + page).min(last),
+            Message::ListTop => 0,
+            _ => last,
+        };
+        comparison.scroll = comparison.first_visible(page);
+    }
+    model.needs_redraw = true;
+}
+
+/// The review list's filter bar. Every edit re-parses the query, so the list
+/// narrows while typing, and queues the file scan a `path:` term needs.
+fn update_filter_bar(model: &mut Model, msg: Message) {
+    match msg {
+        Message::FilterBarActivate => model.filter_bar_active = true,
+        Message::FilterBarInput(text) => model.filter_bar_input.push_str(&text),
+        Message::FilterBarBackspace => {
+            model.filter_bar_input.pop();
+        }
+        Message::FilterBarDeleteWord => delete_last_word(&mut model.filter_bar_input),
+        Message::FilterBarSubmit => model.filter_bar_active = false,
+        Message::FilterBarClear => {
+            model.filter_bar_input.clear();
+            model.filter_bar_active = false;
+        }
+        _ => {}
+    }
+    let query = ReviewQuery::parse(&model.filter_bar_input);
+    if query != model.review_query {
+        model.review_query = query;
+        model.queue_file_scan();
+        model.list_index = 0;
+        model.list_scroll = 0;
+    }
+    model.needs_redraw = true;
+}
+
+/// Draw the current file in the other diff view mode (`ctrl+v`), leaving
+/// `diff_view_mode` for the rest. Its rows change, so this scrolls back to
+/// the file.
+fn toggle_file_diff_view(model: &mut Model) {
+    let Some(path) = model
+        .files_with_threads()
+        .get(model.file_index)
+        .map(|file| file.path.clone())
+    else {
+        return;
+    };
+    let mode = model.file_view_mode(&path).toggled();
+    model.flash_message = Some(tr_fmt(
+        Text::FileViewModeSet,
+        &[
+            &path,
+            &tr(match mode {
+                DiffViewMode::Unified => Text::ViewUnified,
+                DiffViewMode::SideBySide => Text::ViewSideBySide,
+            }),
+        ],
+    ));
+    if mode == model.diff_view_mode {
+        model.diff_view_overrides.remove(&path);
+    } else {
+        model.diff_view_overrides.insert(path, mode);
+    }
+    jump_to_file(model, model.file_index);
+}
+
+/// Fold the hunk at the cursor to its header line (`za`), or unfold the
+/// folded one under it. The cursor goes to the header, or the first line
+/// back.
+fn toggle_hunk_fold(model: &mut Model) {
+    let Some(path) = model
+        .files_with_threads()
+        .get(model.file_index)
+        .map(|file| file.path.clone())
+    else {
+        return;
+    };
+    if model.word_diff_files.contains(&path) {
+        model.flash_message = Some(tr(Text::FoldWordDiff).to_string());
+        return;
+    }
+    let Some((hunk, row)) = cursor_hunk(model) else {
+        model.flash_message = Some(tr(Text::NoHunkAtCursor).to_string());
+        return;
+    };
+    let folded = model.folds.toggle_hunk(&path, hunk);
+    model.diff_cursor = if folded { row } else { row + 1 };
+    if model.diff_cursor < model.diff_scroll {
+        center_cursor_scroll(model);
+    }
+    model.needs_redraw = true;
+}
+
+/// Fold the current file to its header (`zA`), or unfold it. The cursor
+/// goes to the header.
+fn toggle_file_fold(model: &mut Model) {
+    let Some(path) = model
+        .files_with_threads()
+        .get(model.file_index)
+        .map(|file| file.path.clone())
+    else {
+        return;
+    };
+    model.folds.toggle_file(&path);
+    // The header line sits below the header block's margin and padding
+    let start = file_scroll_offset(&model.stream_layout(), model.file_index);
+    model.diff_cursor = start + block_margin() + block_padding();
+    if model.diff_cursor < model.diff_scroll {
+        center_cursor_scroll(model);
+    }
+    model.needs_redraw = true;
+}
+
+/// Ask the main loop for more unchanged lines above (`up`) or below the
+/// hunk at the cursor, up to the next hunk.
+fn expand_context(model: &mut Model, up: bool) {
+    let files = model.files_with_threads();
+    let Some(path) = files.get(model.file_index).map(|file| file.path.clone()) else {
+        return;
+    };
+    let Some(entry) = model.file_cache.get(&path) else {
+        return;
+    };
+    if entry.normalized {
+        model.flash_message = Some(tr(Text::ExpandReformatted).to_string());
+        return;
+    }
+    let Some((hunk, header_row)) = cursor_hunk(model) else {
+        model.flash_message = Some(tr(Text::NoHunkAtCursor).to_string());
+        return;
+    };
+    let gap = entry
+        .diff
+        .as_ref()
+        .and_then(|diff| diff.context_gap(hunk, up, EXPAND_CONTEXT_LINES));
+    let Some(lines) = gap else {
+        model.flash_message = Some(tr(Text::NoMoreContext).to_string());
+        return;
+    };
+    model.pending_expansion = Some(ContextExpansion {
+        path,
+        file: model.file_index,
+        hunk,
+        up,
+        lines,
+        header_row,
+    });
+}
+
+/// Draw the current file with or without syntax highlighting, for big or
+/// minified files; nothing to toggle while `--no-syntax` is in effect.
+fn toggle_file_highlighting(model: &mut Model) {
+    let Some(path) = model
+        .files_with_threads()
+        .get(model.file_index)
+        .map(|file| file.path.clone())
+    else {
+        return;
+    };
+    model.needs_redraw = true;
+    if !model.syntax {
+        model.flash_message = Some(tr(Text::SyntaxDisabled).to_string());
+        return;
+    }
+    let text = if model.plain_files.remove(&path) {
+        Text::FileHighlightingOn
+    } else {
+        model.plain_files.insert(path.clone());
+        Text::FileHighlightingOff
+    };
+    model.flash_message = Some(tr_fmt(text, &[&path]));
+    model.highlight_file(&path);
+    model.sync_active_file_cache();
+}
+
+/// Switch the current file between its line and word diff (`W`); only prose
+/// files have one. Its rows change, so this scrolls back to the file.
+fn toggle_word_diff(model: &mut Model) {
+    let Some(path) = model
+        .files_with_threads()
+        .get(model.file_index)
+        .map(|file| file.path.clone())
+    else {
+        return;
+    };
+    if !is_prose_path(&path) {
+        model.flash_message = Some(tr(Text::WordDiffProseOnly).to_string());
+        model.needs_redraw = true;
+        return;
+    }
+    if !model.word_diff_files.remove(&path) {
+        model.word_diff_files.insert(path);
+    }
+    jump_to_file(model, model.file_index);
+}
+
+/// Review list filter, order and grouping.
+fn update_list_order(model: &mut Model, msg: &Message) {
+    match msg {
+        Message::CycleStatusFilter => {
+            model.filter = match model.filter {
+                ReviewFilter::All => ReviewFilter::Open,
+                ReviewFilter::Open => ReviewFilter::Closed,
+                ReviewFilter::Closed if model.codeowners.is_some() => ReviewFilter::Owned,
+                ReviewFilter::Closed | ReviewFilter::Owned => ReviewFilter::All,
+            };
+            model.list_index = 0;
+            model.list_scroll = 0;
+            model.needs_redraw = true;
+        }
+        Message::SetReviewSort(sort) => {
+            model.review_sort = *sort;
+            model.config.review_sort = Some(sort.name().to_string());
+            // A new order starts the usual way round; `None` wouldn't clear
+            // the saved setting
+            if model.review_sort_reversed() {
+                model.config.review_sort_reversed = Some(false);
+            }
+            let _ = config::save_ui_config(&model.config);
+            model.list_index = 0;
+            model.list_scroll = 0;
+            model.needs_redraw = true;
+        }
+        Message::ReverseReviewSort => {
+            model.config.review_sort_reversed = Some(!model.review_sort_reversed());
+            let _ = config::save_ui_config(&model.config);
+            model.list_index = 0;
+            model.list_scroll = 0;
+            model.needs_redraw = true;
+        }
+        Message::ToggleReviewGrouping => {
+            model.config.group_reviews_by_status = Some(!model.group_reviews_by_status());
+            let _ = config::save_ui_config(&model.config);
+            model.list_index = 0;
+            model.list_scroll = 0;
+            model.needs_redraw = true;
+        }
+        _ => {}
+    }
+}
+
+fn update_view_filter(model: &mut Model, msg: &Message) {
+    match msg {
+        Message::ToggleDiffView => {
+            model.diff_view_mode = model.diff_view_mode.toggled();
+            // Overrides the new default matches aren't overrides anymore
+            let mode = model.diff_view_mode;
+            model.diff_view_overrides.retain(|_, m| *m != mode);
+            model.needs_redraw = true;
+            update_active_file_from_scroll(model);
+        }
+
+        Message::ToggleSidebar => {
+            model.sidebar_visible = !model.sidebar_visible;
+            if !model.sidebar_visible && matches!(model.focus, Focus::FileSidebar) {
+                model.focus = Focus::DiffPane;
+            }
+            model.needs_redraw = true;
+            update_active_file_from_scroll(model);
+        }
+
+        Message::ToggleDiffWrap => {
+            model.diff_wrap = !model.diff_wrap;
+            model.needs_redraw = true;
+            update_active_file_from_scroll(model);
+        }
+
+        Message::ToggleFileDiffView => toggle_file_diff_view(model),
+
+        Message::ToggleWordDiff => toggle_word_diff(model),
+
+        Message::ToggleFileHighlighting => toggle_file_highlighting(model),
+
+        Message::ToggleHunkFold => toggle_hunk_fold(model),
+
+        Message::ToggleFileFold => toggle_file_fold(model),
+
+        Message::ExpandContextUp => expand_context(model, true),
+
+        Message::ExpandContextDown => expand_context(model, false),
+
+        Message::ToggleFileViewed => toggle_file_viewed(model),
+
+        Message::GoToLine(line) => go_to_line(model, *line),
+
+        Message::SetContextLines(lines) => {
+            model.config.context_lines = Some(*lines);
+            let _ = config::save_ui_config(&model.config);
+            model.needs_redraw = true;
+            update_active_file_from_scroll(model);
+        }
+
+        Message::ToggleCrMarkers => {
+            model.show_cr = !model.show_cr;
+            model.needs_redraw = true;
+        }
+
+        Message::CycleDensity => {
+            let density = layout::density().next();
+            layout::set_density(density);
+            model.config.density = Some(density.name().to_string());
+            let _ = config::save_ui_config(&model.config);
+            model.flash_message = Some(tr_fmt(Text::DensitySet, &[&density.name()]));
+            model.needs_redraw = true;
+            update_active_file_from_scroll(model);
+        }
+
+        Message::ToggleBlame => {
+            model.blame_gutter = !model.blame_gutter;
+            if model.blame_gutter && model.active_view_mode() == DiffViewMode::SideBySide {
+                model.flash_message = Some(tr(Text::BlameUnifiedOnly).to_string());
+            }
+            model.needs_redraw = true;
+            update_active_file_from_scroll(model);
+        }
+
+        Message::OpenFileInEditor => {
+            let files = model.files_with_threads();
+            if let Some(file) = files.get(model.file_index) {
+                let line = model
+                    .expanded_thread
+                    .as_ref()
+                    .and_then(|thread_id| model.threads.iter().find(|t| t.thread_id == *thread_id))
+                    .and_then(|thread| {
+                        // Only use line number if thread is for the current file
+                        // and refers to the new side (old-side lines aren't in
+                        // the working copy)
+                        if thread.file_path == file.path
+                            && thread.selection_start > 0
+                            && thread.side != Some(ThreadSide::Old)
+                        {
+                            Some(thread.selection_start as u32)
+                        } else {
+                            None
+                        }
+                    });
+                model.pending_editor_request = Some(EditorRequest {
+                    file_path: file.path.clone(),
+                    line,
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+fn update_system_theme(model: &mut Model, msg: &Message) {
+    match msg {
+        Message::Resize { width, height } => {
+            model.resize(*width, *height);
+            model.needs_redraw = true;
+            update_active_file_from_scroll(model);
+        }
+
+        Message::Quit => {
+            record_review_progress(model);
+            model.should_quit = true;
+        }
+
+        Message::ShowThemePicker => {
+            model.pre_palette_theme = model.config.theme.clone();
+            model.command_palette_mode = PaletteMode::Themes;
+            model.command_palette_input.clear();
+            let theme_names = filter_theme_names(&model.command_palette_input);
+            model.command_palette_selection = theme_names
+                .iter()
+                .position(|&name| name == model.theme.name)
+                .unwrap_or(0);
+            model.previous_focus = Some(model.focus);
+            model.focus = Focus::CommandPalette;
+            model.needs_redraw = true;
+        }
+
+        Message::ApplyTheme(theme_name) => {
+            if let Some(loaded) = theme::load_built_in_theme(theme_name) {
+                model.theme = loaded.theme;
+                if let Some(syntax_theme) = loaded.syntax_theme {
+                    model.highlighter = Arc::new(Highlighter::with_theme(&syntax_theme));
+                } else if theme_name.to_lowercase().contains("light") {
+                    model.highlighter = Arc::new(Highlighter::with_theme("base16-ocean.light"));
+                } else {
+                    model.highlighter = Arc::new(Highlighter::with_theme("base16-ocean.dark"));
+                }
+                model.config.theme = Some(theme_name.clone());
+                let _ = config::save_ui_config(&model.config);
+                model.needs_redraw = true;
+            }
+        }
+        _ => {}
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+pub fn update(model: &mut Model, msg: Message) {
+    // Clear transient flash message on any user-initiated action.
+    if model.flash_message.is_some()
+        && !matches!(
+            msg,
+            Message::Tick
+                | Message::ExternalRefresh
+                | Message::Resize { .. }
+                | Message::TerminalFocusGained
+                | Message::TerminalFocusLost
+                | Message::HoverDivider(_)
+                | Message::HighlightsReady(_)
+                | Message::Noop
+        )
+    {
+        model.flash_message = None;
+        model.needs_redraw = true;
+    }
+
+    if model.read_only && msg.is_mutating() {
+        model.flash_message = Some(tr(Text::ReadOnlyBlocked).to_string());
+        model.needs_redraw = true;
+        return;
+    }
+
+    match msg {
+        Message::ListUp
+        | Message::ListDown
+        | Message::ListPageUp
+        | Message::ListPageDown
+        | Message::ListTop
+        | Message::ListBottom => {
+            if model.screen == Screen::Inbox {
+                update_inbox_nav(model, &msg);
+            } else if model.screen == Screen::Compare {
+                update_compare_nav(model, &msg);
+            } else {
+                update_list_nav(model, &msg);
+            }
+        }
 
         Message::CursorUp | Message::CursorDown | Message::CursorTop | Message::CursorBottom => {
             update_cursor(model, &msg);
         }
+        Message::CursorNextHunk
+        | Message::CursorPrevHunk
+        | Message::CursorNextFile
+        | Message::CursorPrevFile
+        | Message::CursorNextChange
+        | Message::CursorPrevChange => {
+            jump_to_section(model, &msg);
+        }
+
+        Message::ClickDiffRow { .. } | Message::DragDiffRow(_) | Message::EndDiffDrag => {
+            update_diff_mouse(model, &msg);
+        }
+
+        Message::StartSidebarResize
+        | Message::ResizeSidebar(_)
+        | Message::EndSidebarResize
+        | Message::HoverDivider(_) => update_sidebar_resize(model, &msg),
 
         Message::VisualToggle => {
             if model.visual_mode {
@@ -778,6 +1626,50 @@ pub fn update(model: &mut Model, msg: Message) {
             }
             model.needs_redraw = true;
         }
+        Message::FocusSbsSide(side) => {
+            model.sbs_side = side;
+            model.needs_redraw = true;
+        }
+        Message::CopyLines => copy_lines(model),
+        Message::CopyLineReference => copy_line_reference(model),
+        Message::CopyFilePath => copy_file_path(model),
+        Message::CopyThreadLink => copy_thread_link(model),
+        Message::CopyToClipboard(text) => copy_to_clipboard(model, text),
+        Message::CopyCommentId => copy_comment_id(model),
+        Message::CopyCheckoutCommand => copy_checkout_command(model),
+        Message::ExportReview => {
+            model.pending_export = model.screen == Screen::ReviewDetail;
+        }
+        Message::RunReviewCommand
+        | Message::ShowCommandOutput
+        | Message::HideCommandOutput
+        | Message::CommandOutputUp
+        | Message::CommandOutputDown
+        | Message::CommandOutputTop
+        | Message::CommandOutputBottom => update_command_output(model, &msg),
+
+        Message::ShowInputLog
+        | Message::HideInputLog
+        | Message::InputLogUp
+        | Message::InputLogDown
+        | Message::InputLogTop
+        | Message::InputLogBottom => update_input_log(model, &msg),
+
+        Message::ToggleThreadsPanel
+        | Message::ThreadsPanelUp
+        | Message::ThreadsPanelDown
+        | Message::ThreadsPanelTop
+        | Message::ThreadsPanelBottom
+        | Message::ThreadsPanelSelect => update_threads_panel(model, &msg),
+        Message::ViewBlame => request_blame(model),
+        Message::ToggleMuteThread => toggle_mute_thread(model),
+        Message::ApplySuggestion => apply_suggestion(model),
+        Message::UndoSuggestion => undo_suggestion(model),
+        Message::JumpToComment(id) => jump_to_comment(model, &id),
+
+        Message::ViewportScrollUp | Message::ViewportScrollDown => {
+            scroll_viewport(model, matches!(msg, Message::ViewportScrollUp));
+        }
 
         Message::ScrollUp
         | Message::ScrollDown
@@ -806,10 +1698,21 @@ pub fn update(model: &mut Model, msg: Message) {
         | Message::CommandPaletteUpdateInput(_)
         | Message::CommandPaletteInputBackspace
         | Message::CommandPaletteDeleteWord
-        | Message::CommandPaletteExecute => {
+        | Message::CommandPaletteExecute
+        | Message::ShowCommentPicker
+        | Message::PromptCommandArgument(_) => {
             update_command_palette(model, msg);
         }
 
+        Message::ShowActionMenu
+        | Message::HideActionMenu
+        | Message::ActionMenuNext
+        | Message::ActionMenuPrev
+        | Message::ActionMenuExecute
+        | Message::ActionMenuPick(_) => {
+            update_action_menu(model, &msg);
+        }
+
         Message::StartComment => {
             handle_start_comment_inline(model);
         }
@@ -818,11 +1721,47 @@ pub fn update(model: &mut Model, msg: Message) {
             handle_start_comment_external(model);
         }
 
-        Message::EnterCommentMode
-        | Message::CommentInput(_)
-        | Message::CommentInputBackspace
-        | Message::CommentNewline
-        | Message::CommentCursorUp
+        Message::StartQuotedComment => {
+            handle_start_quoted_comment(model);
+        }
+
+        Message::StartHunkComment => {
+            handle_start_hunk_comment(model);
+        }
+
+        Message::StartFileComment => {
+            handle_start_file_comment(model);
+        }
+
+        Message::StartReviewComment => {
+            handle_start_review_comment(model);
+        }
+
+        Message::FinishReview => {
+            handle_finish_review(model);
+        }
+
+        Message::StartVerdict(verdict) => {
+            handle_start_verdict(model, verdict);
+        }
+
+        Message::StartAbandonReview => {
+            handle_start_abandon(model);
+        }
+
+        Message::RestoreReview => {
+            handle_restore_review(model);
+        }
+
+        Message::StartReply => {
+            handle_start_reply(model);
+        }
+
+        Message::EnterCommentMode
+        | Message::CommentInput(_)
+        | Message::CommentInputBackspace
+        | Message::CommentNewline
+        | Message::CommentCursorUp
         | Message::CommentCursorDown
         | Message::CommentCursorLeft
         | Message::CommentCursorRight
@@ -832,15 +1771,59 @@ pub fn update(model: &mut Model, msg: Message) {
         | Message::CommentWordRight
         | Message::CommentDeleteWord
         | Message::CommentClearLine
+        | Message::CommentInsertSuggestion
         | Message::SaveComment
         | Message::CancelComment => {
             update_comment(model, msg);
         }
+        Message::CancelCommentSubmission => model.cancel_comment_submission(),
 
-        Message::SelectReview(_) | Message::Back => {
+        Message::ReviewLoaded(data) => {
+            // Dropped if the user left the review while it loaded
+            if model.screen == Screen::ReviewDetail && model.current_review.is_none() {
+                model.install_review_data(*data);
+                restore_review_progress(model);
+            }
+        }
+
+        Message::ReviewLoadFailed(reason) => {
+            model.flash_message = Some(tr_fmt(Text::ReviewLoadFailed, &[&reason]));
+            update(model, Message::Back);
+        }
+
+        Message::HighlightsReady(highlights) => {
+            // Dropped if the file was reloaded or left the cache meanwhile
+            if let Some(entry) = model
+                .file_cache
+                .get_mut(&highlights.path)
+                .filter(|entry| entry.revision == highlights.revision)
+            {
+                highlights.apply(entry);
+                model.sync_active_file_cache();
+                model.needs_redraw = true;
+            }
+        }
+
+        Message::SelectReview(_)
+        | Message::Back
+        | Message::ShowInbox
+        | Message::RefreshInbox
+        | Message::OpenInboxItem(_) => {
             update_navigation(model, &msg);
         }
 
+        Message::ToggleCompareMark(_) | Message::ShowComparison | Message::OpenComparedFile => {
+            update_comparison(model, &msg);
+        }
+
+        Message::TogglePreview => {
+            model.preview_open = !model.preview_open;
+            model.needs_redraw = true;
+        }
+
+        Message::SidebarCollapse => sidebar_collapse(model),
+        Message::SidebarExpand => sidebar_expand(model),
+
         Message::NextFile
         | Message::PrevFile
         | Message::SidebarTop
@@ -850,158 +1833,1216 @@ pub fn update(model: &mut Model, msg: Message) {
         | Message::SidebarSelect => {
             update_file_sidebar(model, &msg);
         }
+        Message::JumpToRecentFile(n) => jump_to_recent_file(model, n),
+
+        Message::SidebarPageUp
+        | Message::SidebarPageDown
+        | Message::SidebarScrollUp
+        | Message::SidebarScrollDown => {
+            update_sidebar_scroll(model, &msg);
+        }
 
         Message::ToggleFocus => {
             model.focus = match model.focus {
                 Focus::ReviewList => Focus::ReviewList,
                 Focus::DiffPane => Focus::FileSidebar,
-                Focus::CommandPalette => model.previous_focus.take().unwrap_or(Focus::DiffPane),
+                Focus::CommandPalette
+                | Focus::ActionMenu
+                | Focus::CommandOutput
+                | Focus::InputLog
+                | Focus::ThreadsPanel => model.previous_focus.take().unwrap_or(Focus::DiffPane),
                 Focus::FileSidebar | Focus::ThreadExpanded | Focus::Commenting => Focus::DiffPane,
             };
         }
 
-        Message::ResolveThread(_id) | Message::ReopenThread(_id) => {
-            // TODO: Write to event log
+        Message::ResolveThread(id) => {
+            if model.pending_status_change.is_none() {
+                model.queue_status_change(&id, "resolved");
+            }
+        }
+        Message::ReopenThread(id) => {
+            if model.pending_status_change.is_none() {
+                model.queue_status_change(&id, "open");
+            }
+        }
+        Message::Undo => replay_action(model, false),
+        Message::Redo => replay_action(model, true),
+
+        Message::CycleStatusFilter
+        | Message::SetReviewSort(_)
+        | Message::ReverseReviewSort
+        | Message::ToggleReviewGrouping => {
+            update_list_order(model, &msg);
+        }
+
+        Message::ToggleDiffView
+        | Message::ToggleSidebar
+        | Message::ToggleDiffWrap
+        | Message::ToggleWordDiff
+        | Message::ToggleFileHighlighting
+        | Message::ToggleHunkFold
+        | Message::ToggleFileFold
+        | Message::ExpandContextUp
+        | Message::ExpandContextDown
+        | Message::ToggleFileDiffView
+        | Message::ToggleFileViewed
+        | Message::ToggleCrMarkers
+        | Message::ToggleBlame
+        | Message::CycleDensity
+        | Message::GoToLine(_)
+        | Message::SetContextLines(_)
+        | Message::OpenFileInEditor => {
+            update_view_filter(model, &msg);
+        }
+
+        Message::SearchActivate
+        | Message::SearchInput(_)
+        | Message::SearchBackspace
+        | Message::SearchDeleteWord
+        | Message::SearchClearLine
+        | Message::SearchClear
+        | Message::SearchSubmit
+        | Message::SearchNext
+        | Message::SearchPrev
+            if model.screen == Screen::ReviewDetail =>
+        {
+            update_diff_search(model, msg);
+        }
+
+        Message::SearchActivate => {
+            model.search_active = true;
+            model.needs_redraw = true;
+        }
+        Message::SearchInput(ref text) => {
+            model.search_input.push_str(text);
+            model.list_index = 0;
+            model.list_scroll = 0;
+            model.needs_redraw = true;
+        }
+        Message::SearchBackspace => {
+            model.search_input.pop();
+            model.list_index = 0;
+            model.list_scroll = 0;
+            model.needs_redraw = true;
+        }
+        Message::SearchDeleteWord => {
+            delete_last_word(&mut model.search_input);
+            model.list_index = 0;
+            model.list_scroll = 0;
+            model.needs_redraw = true;
+        }
+        Message::SearchClearLine => {
+            model.search_input.clear();
+            model.list_index = 0;
+            model.list_scroll = 0;
+            model.needs_redraw = true;
+        }
+        Message::SearchClear => {
+            model.search_input.clear();
+            model.search_active = false;
+            model.list_index = 0;
+            model.list_scroll = 0;
+            model.needs_redraw = true;
+        }
+
+        Message::FilterBarActivate
+        | Message::FilterBarInput(_)
+        | Message::FilterBarBackspace
+        | Message::FilterBarDeleteWord
+        | Message::FilterBarSubmit
+        | Message::FilterBarClear => update_filter_bar(model, msg),
+
+        Message::Resize { .. }
+        | Message::Quit
+        | Message::ShowThemePicker
+        | Message::ApplyTheme(_) => {
+            update_system_theme(model, &msg);
+        }
+
+        Message::Tick => {
+            autosave_draft(model);
+            schedule_refresh(model, false);
+        }
+        Message::ExternalRefresh => {
+            model.external_change = true;
+            schedule_refresh(model, false);
+        }
+        Message::TerminalFocusGained | Message::TerminalFocusLost => {
+            update_terminal_focus(model, &msg);
+        }
+        Message::SearchSubmit | Message::SearchNext | Message::SearchPrev | Message::Noop => {}
+    }
+}
+
+/// `/` search on a review. Typing moves the cursor to the first match from
+/// where the search started; Enter keeps the query for `n`/`N`, Esc drops it.
+fn update_diff_search(model: &mut Model, msg: Message) {
+    match msg {
+        Message::SearchActivate => {
+            model.diff_search = Some(DiffSearch::new(model.diff_cursor));
+        }
+        Message::SearchInput(text) => edit_diff_search(model, |query| query.push_str(&text)),
+        Message::SearchBackspace => edit_diff_search(model, |query| {
+            query.pop();
+        }),
+        Message::SearchDeleteWord => edit_diff_search(model, delete_last_word),
+        Message::SearchClearLine => edit_diff_search(model, String::clear),
+        Message::SearchClear => {
+            // Cancelling a search still being typed goes back to where it began
+            if let Some(search) = model.diff_search.take().filter(|s| s.editing) {
+                move_diff_cursor(model, search.origin);
+            }
+        }
+        Message::SearchSubmit => {
+            if let Some(search) = model.diff_search.as_mut() {
+                search.editing = false;
+            }
+            match model.diff_search_query() {
+                None => model.diff_search = None,
+                Some(query) if model.search_hits.borrow().is_empty() => {
+                    model.flash_message = Some(tr_fmt(Text::SearchNoMatches, &[&query]));
+                }
+                Some(_) => {}
+            }
+        }
+        Message::SearchNext | Message::SearchPrev => {
+            let hits = model.search_hits.borrow();
+            let row = if matches!(msg, Message::SearchNext) {
+                next_hit_row(&hits, model.diff_cursor)
+            } else {
+                prev_hit_row(&hits, model.diff_cursor)
+            };
+            drop(hits);
+            if let Some(row) = row {
+                move_diff_cursor(model, row);
+            } else if let Some(query) = model.diff_search_query() {
+                model.flash_message = Some(tr_fmt(Text::SearchNoMatches, &[&query]));
+            }
+        }
+        _ => {}
+    }
+    model.needs_redraw = true;
+}
+
+/// Edit the query being typed and move to its first match at or after the
+/// search's origin, wrapping around to the first.
+fn edit_diff_search(model: &mut Model, edit: impl FnOnce(&mut String)) {
+    let Some(search) = model.diff_search.as_mut() else {
+        return;
+    };
+    edit(&mut search.query);
+    let origin = search.origin;
+    let hits = model
+        .diff_search_query()
+        .map(|query| crate::view::compute_search_hits(model, query))
+        .unwrap_or_default();
+    let target = hits
+        .iter()
+        .find(|hit| hit.row >= origin)
+        .or_else(|| hits.first())
+        .map_or(origin, |hit| hit.row);
+    *model.search_hits.borrow_mut() = hits;
+    move_diff_cursor(model, target);
+}
+
+fn move_diff_cursor(model: &mut Model, row: usize) {
+    model.diff_cursor = row;
+    center_cursor_scroll(model);
+    update_active_file_from_scroll(model);
+}
+
+/// Build a `CommentRequest` from the current model state (visual selection or expanded thread).
+fn build_comment_request(model: &mut Model) -> Option<CommentRequest> {
+    let review = model.current_review.as_ref()?;
+    let review_id = review.review_id.clone();
+    let files = model.files_with_threads();
+    let file = files.get(model.file_index)?;
+    let file_path = file.path.clone();
+
+    if model.visual_mode {
+        if refuse_normalized_lines(model, &file_path) {
+            return None;
+        }
+        let sel_start = model.visual_anchor.min(model.diff_cursor);
+        let sel_end = model.visual_anchor.max(model.diff_cursor);
+
+        // The line map was recorded by the last frame and can be stale after
+        // a resize or view-mode toggle; recompute it from the current layout
+        // and warn if the selection now lands on different lines.
+        let side = model.cursor_side();
+        let fresh_map = crate::view::compute_line_map(model, side);
+        let (start_line, end_line) = selected_line_range(&fresh_map, sel_start, sel_end)?;
+        let shown = selected_line_range(&model.side_line_map(side).borrow(), sel_start, sel_end);
+        let notice = shown
+            .is_some_and(|shown| shown != (start_line, end_line))
+            .then(|| {
+                let target = end_line.map_or_else(
+                    || format!("{file_path}:{start_line}"),
+                    |end| format!("{file_path}:{start_line}-{end}"),
+                );
+                tr_fmt(Text::CommentTargetMoved, &[&target])
+            });
+        *model.side_line_map(side).borrow_mut() = fresh_map;
+
+        let excerpt = model.target_excerpt(&file_path, side, start_line, end_line);
+        Some(CommentRequest {
+            review_id,
+            file_path,
+            start_line,
+            end_line,
+            side,
+            thread_id: None,
+            existing_comments: Vec::new(),
+            thread_etag: None,
+            excerpt,
+            notice,
+        })
+    } else {
+        // Find the thread whose rendered position is closest to (and at or
+        // before) the cursor, so pressing 'a' targets the thread the user is
+        // actually looking at rather than the stale `expanded_thread`. Above
+        // the first file, that's one of the review-level threads.
+        let in_review_threads = model
+            .stream_layout()
+            .file_offsets
+            .first()
+            .is_some_and(|&start| model.diff_cursor < start);
+        let thread_id = {
+            let positions = model.thread_positions.borrow();
+            let mut best: Option<(usize, String)> = None;
+            let candidates = model.threads.iter().filter(|t| {
+                if in_review_threads {
+                    t.is_review_thread()
+                } else {
+                    t.file_path == file_path
+                }
+            });
+            for thread in candidates {
+                if let Some(&pos) = positions.get(&thread.thread_id) {
+                    if pos <= model.diff_cursor
+                        && best.as_ref().is_none_or(|(best_pos, _)| pos > *best_pos)
+                    {
+                        best = Some((pos, thread.thread_id.clone()));
+                    }
+                }
+            }
+            best.map(|(_, id)| id)
+        }?;
+        build_reply_request(model, thread_id)
+    }
+}
+
+/// Build a `CommentRequest` replying to `thread_id`, with the thread's
+/// comments for context and its etag as of now.
+fn build_reply_request(model: &Model, thread_id: String) -> Option<CommentRequest> {
+    let review_id = model.current_review.as_ref()?.review_id.clone();
+    let thread = model.threads.iter().find(|t| t.thread_id == thread_id)?;
+    let existing_comments = model
+        .all_comments
+        .get(&thread_id)
+        .cloned()
+        .unwrap_or_default();
+    let etag = thread_etag(&thread.status, &existing_comments);
+    let side = thread.side.unwrap_or(ThreadSide::New);
+    let excerpt = model.target_excerpt(
+        &thread.file_path,
+        side,
+        thread.selection_start,
+        thread.selection_end,
+    );
+
+    Some(CommentRequest {
+        review_id,
+        file_path: thread.file_path.clone(),
+        start_line: thread.selection_start,
+        end_line: thread.selection_end,
+        side,
+        thread_id: Some(thread_id),
+        existing_comments,
+        thread_etag: Some(etag),
+        excerpt,
+        notice: None,
+    })
+}
+
+/// The current file and the new- or old-side lines (on the focused side)
+/// under the visual selection or cursor row, in order.
+fn cursor_lines(model: &Model) -> Option<(String, Vec<i64>)> {
+    let (first, last) = if model.visual_mode {
+        (
+            model.visual_anchor.min(model.diff_cursor),
+            model.visual_anchor.max(model.diff_cursor),
+        )
+    } else {
+        (model.diff_cursor, model.diff_cursor)
+    };
+    let files = model.files_with_threads();
+    let file_path = files.get(model.file_index).map(|f| f.path.clone())?;
+
+    // Wrapped rows map to the same line; keep each line once, in order
+    let mut lines: Vec<i64> = Vec::new();
+    let line_map = model.side_line_map(model.cursor_side()).borrow();
+    for line in (first..=last).filter_map(|row| line_map.get(&row).copied()) {
+        if lines.last() != Some(&line) {
+            lines.push(line);
+        }
+    }
+    Some((file_path, lines))
+}
+
+/// `path:start`, or `path:start-end` for several lines.
+fn line_reference(file_path: &str, start: i64, end: i64) -> String {
+    if start == end {
+        format!("{file_path}:{start}")
+    } else {
+        format!("{file_path}:{start}-{end}")
+    }
+}
+
+/// Copy the visual selection (or the cursor row) from the focused side to
+/// the clipboard, leaving visual mode like vim's `y`.
+fn copy_lines(model: &mut Model) {
+    let side = model.cursor_side();
+    let Some((file_path, lines)) = cursor_lines(model) else {
+        return;
+    };
+    let text: Option<Vec<&str>> = model.file_cache.get(&file_path).and_then(|entry| {
+        lines
+            .iter()
+            .map(|&line| entry.side_line(side, line))
+            .collect()
+    });
+
+    model.flash_message = Some(match (text, lines.first(), lines.last()) {
+        (Some(text), Some(&start), Some(&end)) if !text.is_empty() => {
+            model.pending_clipboard = Some(text.join("\n"));
+            tr_fmt(Text::Copied, &[&line_reference(&file_path, start, end)])
+        }
+        _ => tr(Text::NothingToCopy).to_string(),
+    });
+    model.visual_mode = false;
+    model.needs_redraw = true;
+}
+
+/// Copy `path:line` for the cursor line or visual selection, leaving visual
+/// mode.
+fn copy_line_reference(model: &mut Model) {
+    let Some((file_path, lines)) = cursor_lines(model) else {
+        return;
+    };
+    model.visual_mode = false;
+    if let (Some(&start), Some(&end)) = (lines.first(), lines.last()) {
+        copy_to_clipboard(model, line_reference(&file_path, start, end));
+    } else {
+        model.flash_message = Some(tr(Text::NothingToCopy).to_string());
+        model.needs_redraw = true;
+    }
+}
+
+/// Copy the path of the file picked in the sidebar, or the current one.
+fn copy_file_path(model: &mut Model) {
+    let file_idx = targeted_file_index(model);
+    if let Some(path) = model
+        .files_with_threads()
+        .get(file_idx)
+        .map(|f| f.path.clone())
+    {
+        copy_to_clipboard(model, path);
+    }
+}
+
+/// Copy a link to the thread picked in the threads panel, else the one
+/// under the cursor or the expanded one.
+fn copy_thread_link(model: &mut Model) {
+    let thread_id = if model.focus == Focus::ThreadsPanel {
+        model
+            .panel_threads()
+            .get(model.threads_panel_selection)
+            .map(|t| t.thread_id.clone())
+    } else {
+        model
+            .thread_at_cursor()
+            .or_else(|| model.expanded_thread.clone())
+    };
+    if let Some(link) = thread_id.and_then(|id| model.thread_link(&id)) {
+        copy_to_clipboard(model, link);
+    } else {
+        model.flash_message = Some(tr(Text::NothingToCopy).to_string());
+        model.needs_redraw = true;
+    }
+}
+
+/// Queue `text` for the clipboard (main writes it as OSC 52) and flash its
+/// first line.
+fn copy_to_clipboard(model: &mut Model, text: String) {
+    let first_line = text.lines().next().unwrap_or_default();
+    let shown = crate::text::truncate_end(first_line, FLASH_PREVIEW_WIDTH);
+    model.flash_message = Some(tr_fmt(Text::Copied, &[&shown]));
+    model.pending_clipboard = Some(text);
+    model.needs_redraw = true;
+}
+
+/// Flag an auto-refresh poll once it's due (or right away with `force`), or
+/// a poll for a change the watcher saw. Held back while the terminal is
+/// unfocused, and while a comment is being written or sent so a reload
+/// can't race the submission.
+fn schedule_refresh(model: &mut Model, force: bool) {
+    let now = crate::clock::now();
+    let due = model.external_change
+        || model
+            .auto_refresh
+            .as_ref()
+            .is_some_and(|schedule| force || schedule.is_due(now));
+    if !due
+        || !model.terminal_focused
+        || model.pending_refresh
+        || model.focus == Focus::Commenting
+        || model.pending_comment_submission.is_some()
+    {
+        return;
+    }
+    model.pending_refresh = true;
+    model.external_change = false;
+    model.refresh_started = Some(now);
+    model.needs_redraw = true;
+}
+
+/// Keep the open editor's text as a draft every [`DRAFT_AUTOSAVE`], so a
+/// crash loses little of it.
+fn autosave_draft(model: &mut Model) {
+    if model.inline_editor.is_some()
+        && model
+            .draft_stashed_at
+            .is_none_or(|at| crate::clock::elapsed(at) >= DRAFT_AUTOSAVE)
+    {
+        model.stash_draft();
+    }
+}
+
+/// Track terminal focus. Coming back polls right away instead of waiting
+/// out the interval.
+fn update_terminal_focus(model: &mut Model, msg: &Message) {
+    let regained = matches!(msg, Message::TerminalFocusGained) && !model.terminal_focused;
+    model.terminal_focused = matches!(msg, Message::TerminalFocusGained);
+    if regained {
+        schedule_refresh(model, true);
+    }
+    model.needs_redraw = true;
+}
+
+fn copy_comment_id(model: &mut Model) {
+    model.flash_message = Some(if let Some(id) = model.comment_at_cursor() {
+        let flash = tr_fmt(Text::CopiedCommentId, &[&id]);
+        model.pending_clipboard = Some(id);
+        flash
+    } else {
+        tr(Text::NoCommentAtCursor).to_string()
+    });
+    model.needs_redraw = true;
+}
+
+fn copy_checkout_command(model: &mut Model) {
+    if let Some(command) = model.checkout_command() {
+        model.flash_message = Some(tr_fmt(Text::CopiedCheckoutCommand, &[&command]));
+        model.pending_clipboard = Some(command);
+        model.needs_redraw = true;
+    }
+}
+
+fn update_command_output(model: &mut Model, msg: &Message) {
+    let rows = command_output_rows(model.height);
+    match msg {
+        Message::RunReviewCommand => {
+            if model.screen != Screen::ReviewDetail {
+                return;
+            }
+            if model.config.review_command.is_none() {
+                model.flash_message = Some(tr(Text::NoReviewCommand).to_string());
+            } else if model.repo_path.is_none() {
+                model.flash_message = Some(tr(Text::ReviewCommandNeedsRepo).to_string());
+            } else {
+                model.pending_command_run = !model
+                    .current_command_run()
+                    .is_some_and(CommandRun::is_running);
+                show_command_output(model);
+            }
+        }
+        Message::ShowCommandOutput => {
+            if model.current_command_run().is_some() {
+                show_command_output(model);
+            } else {
+                update_command_output(model, &Message::RunReviewCommand);
+            }
+        }
+        Message::HideCommandOutput => {
+            model.focus = model.previous_focus.take().unwrap_or(Focus::DiffPane);
+        }
+        _ => {
+            if let Some(run) = model.command_run.as_mut() {
+                match msg {
+                    Message::CommandOutputUp => run.scroll_by(-1, rows),
+                    Message::CommandOutputDown => run.scroll_by(1, rows),
+                    Message::CommandOutputTop => run.scroll_by(isize::MIN, rows),
+                    _ => run.follow = true,
+                }
+            }
+        }
+    }
+    model.needs_redraw = true;
+}
+
+fn update_input_log(model: &mut Model, msg: &Message) {
+    let last_page = model
+        .input_profile
+        .log_lines()
+        .len()
+        .saturating_sub(command_output_rows(model.height));
+    let scroll = &mut model.input_log_scroll;
+    match msg {
+        Message::ShowInputLog if model.screen == Screen::ReviewDetail => {
+            *scroll = 0;
+            if model.focus != Focus::InputLog {
+                model.previous_focus = Some(model.focus);
+                model.focus = Focus::InputLog;
+            }
+        }
+        Message::HideInputLog => {
+            model.focus = model.previous_focus.take().unwrap_or(Focus::DiffPane);
+        }
+        Message::InputLogUp => *scroll = scroll.saturating_sub(1),
+        Message::InputLogDown => *scroll = (*scroll + 1).min(last_page),
+        Message::InputLogTop => *scroll = 0,
+        Message::InputLogBottom => *scroll = last_page,
+        _ => {}
+    }
+    model.needs_redraw = true;
+}
+
+fn update_threads_panel(model: &mut Model, msg: &Message) {
+    let last = model.panel_threads().len().saturating_sub(1);
+    match msg {
+        Message::ToggleThreadsPanel if model.focus == Focus::ThreadsPanel => {
+            model.focus = model.previous_focus.take().unwrap_or(Focus::DiffPane);
+        }
+        Message::ToggleThreadsPanel if model.screen == Screen::ReviewDetail => {
+            // Start on the current thread, else the first open one
+            let threads = model.panel_threads();
+            model.threads_panel_selection = threads
+                .iter()
+                .position(|t| model.expanded_thread.as_ref() == Some(&t.thread_id))
+                .or_else(|| threads.iter().position(|t| t.status == "open"))
+                .unwrap_or(0);
+            model.previous_focus = Some(model.focus);
+            model.focus = Focus::ThreadsPanel;
+        }
+        Message::ThreadsPanelUp => {
+            model.threads_panel_selection = model.threads_panel_selection.saturating_sub(1);
+        }
+        Message::ThreadsPanelDown => {
+            model.threads_panel_selection = (model.threads_panel_selection + 1).min(last);
+        }
+        Message::ThreadsPanelTop => model.threads_panel_selection = 0,
+        Message::ThreadsPanelBottom => model.threads_panel_selection = last,
+        Message::ThreadsPanelSelect => {
+            let Some(thread_id) = model
+                .panel_threads()
+                .get(model.threads_panel_selection)
+                .map(|t| t.thread_id.clone())
+            else {
+                return;
+            };
+            model.previous_focus = None;
+            model.focus = Focus::DiffPane;
+            model.visual_mode = false;
+            model.expanded_thread = Some(thread_id);
+            center_on_thread(model);
+            update_active_file_from_scroll(model);
+        }
+        _ => {}
+    }
+    model.needs_redraw = true;
+}
+
+fn show_command_output(model: &mut Model) {
+    if model.focus != Focus::CommandOutput {
+        model.previous_focus = Some(model.focus);
+        model.focus = Focus::CommandOutput;
+    }
+}
+
+/// Mute or unmute the thread selected in the sidebar, else the one under the
+/// cursor, else the one picked with `n`/`p`. Saved in `ui.json`.
+fn toggle_mute_thread(model: &mut Model) {
+    let sidebar_thread = || match model.sidebar_items().get(model.sidebar_index) {
+        Some(crate::model::SidebarItem::Thread { thread_id, .. }) => Some(thread_id.clone()),
+        _ => None,
+    };
+    let target = if model.focus == Focus::FileSidebar {
+        sidebar_thread()
+    } else {
+        model
+            .thread_at_cursor()
+            .or_else(|| model.expanded_thread.clone())
+    };
+    let Some(thread_id) = target else {
+        model.flash_message = Some(tr(Text::NoThreadAtCursor).to_string());
+        model.needs_redraw = true;
+        return;
+    };
+
+    let muted = &mut model.config.muted_threads;
+    let text = if muted.remove(&thread_id) {
+        Text::ThreadUnmuted
+    } else {
+        muted.insert(thread_id.clone());
+        if model.expanded_thread.as_ref() == Some(&thread_id) {
+            model.expanded_thread = None;
+            if model.focus == Focus::ThreadExpanded {
+                model.focus = Focus::DiffPane;
+            }
         }
+        Text::ThreadMuted
+    };
+    let _ = config::save_ui_config(&model.config);
+    model.flash_message = Some(tr_fmt(text, &[&thread_id]));
+    model.needs_redraw = true;
+}
+
+/// Queue a `git blame` lookup for the new-side line under the cursor.
+fn request_blame(model: &mut Model) {
+    let files = model.files_with_threads();
+    let Some(file_path) = files.get(model.file_index).map(|f| f.path.clone()) else {
+        return;
+    };
+    let Some(review) = model.current_review.as_ref() else {
+        return;
+    };
+    let line = if model.cursor_side() == ThreadSide::New {
+        model.line_map.borrow().get(&model.diff_cursor).copied()
+    } else {
+        None
+    };
+    let Some(line) = line else {
+        model.flash_message = Some(tr(Text::MenuEmpty).to_string());
+        model.needs_redraw = true;
+        return;
+    };
+    model.pending_blame_request = Some(BlameRequest {
+        file_path,
+        line,
+        commit: review.commit().to_string(),
+    });
+}
+
+/// Queue the suggestion under the cursor for main to apply to the working
+/// copy.
+fn apply_suggestion(model: &mut Model) {
+    let text = if model.repo_path.is_none() {
+        Text::SuggestionNeedsRepo
+    } else if let Some(patch) = model.cursor_suggestion() {
+        model.pending_patch = Some(PatchRequest { patch, undo: false });
+        return;
+    } else {
+        Text::NoSuggestionAtCursor
+    };
+    model.flash_message = Some(tr(text).to_string());
+    model.needs_redraw = true;
+}
+
+/// Queue the last applied suggestion to be taken back out.
+fn undo_suggestion(model: &mut Model) {
+    if let Some(patch) = model.applied_suggestion.clone() {
+        model.pending_patch = Some(PatchRequest { patch, undo: true });
+    } else {
+        model.flash_message = Some(tr(Text::NoSuggestionToUndo).to_string());
+        model.needs_redraw = true;
+    }
+}
+
+/// Insert a suggestion block holding the editor target's lines at the
+/// cursor, on lines of its own.
+fn insert_suggestion(model: &mut Model) {
+    let Some(editor) = model.inline_editor.as_mut() else {
+        return;
+    };
+    let request = &editor.request;
+    let replaced = target_lines(
+        &request.file_path,
+        request.start_line,
+        request.end_line,
+        Some(request.side),
+    );
+    let Some(replaced) = replaced.filter(|_| editor.is_comment()) else {
+        model.flash_message = Some(tr(Text::SuggestionUnavailable).to_string());
+        model.needs_redraw = true;
+        return;
+    };
+    // Lines that aren't loaded are left for the author to type
+    let lines: Vec<String> = model
+        .file_cache
+        .get(&request.file_path)
+        .map(|entry| {
+            replaced
+                .map_while(|line| entry.new_side_line(line).map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    if editor.cursor_col > 0 {
+        editor.newline();
+    }
+    editor.insert_str(&suggestion_block(&lines));
+}
+
+/// Scroll to comment `id` (case-insensitive), put the cursor on its author
+/// row and highlight it briefly.
+fn jump_to_comment(model: &mut Model, id: &str) {
+    let found = model.all_comments.iter().find_map(|(thread_id, comments)| {
+        comments
+            .iter()
+            .find(|c| c.comment_id.eq_ignore_ascii_case(id))
+            .map(|c| (thread_id.clone(), c.comment_id.clone()))
+    });
+    let Some((thread_id, comment_id)) = found else {
+        model.flash_message = Some(tr_fmt(Text::CommentNotFound, &[&id]));
+        model.needs_redraw = true;
+        return;
+    };
+
+    let row = model
+        .comment_positions
+        .borrow()
+        .get(&comment_id)
+        .map(|rows| rows.start);
+    if let Some(row) = row {
+        model.diff_cursor = row;
+        model.diff_scroll = row.saturating_sub(visible_stream_rows(model.height) / 2);
+    } else {
+        model.expanded_thread = Some(thread_id);
+        center_on_thread(model);
+    }
+    model.visual_mode = false;
+    model.focus = Focus::DiffPane;
+    model.comment_flash = Some((comment_id, crate::clock::now()));
+    update_active_file_from_scroll(model);
+    model.needs_redraw = true;
+}
+
+/// `(start, end)` lines covered by stream rows `first..=last`, with
+/// `end` omitted for a single line.
+fn selected_line_range(
+    line_map: &HashMap<usize, i64>,
+    first: usize,
+    last: usize,
+) -> Option<(i64, Option<i64>)> {
+    let lines = (first..=last).filter_map(|row| line_map.get(&row).copied());
+    let (min, max) = lines.fold(None, |acc: Option<(i64, i64)>, line| {
+        Some(acc.map_or((line, line), |(lo, hi)| (lo.min(line), hi.max(line))))
+    })?;
+    Some((min, (max != min).then_some(max)))
+}
+
+/// Open inline multi-line comment editor (a key).
+fn handle_start_comment_inline(model: &mut Model) {
+    if let Some(request) = build_comment_request(model) {
+        model.open_inline_editor(request);
+    }
+}
 
-        Message::CycleStatusFilter
-        | Message::ToggleDiffView
-        | Message::ToggleSidebar
-        | Message::ToggleDiffWrap
-        | Message::OpenFileInEditor => {
-            update_view_filter(model, &msg);
-        }
+/// Open the inline editor on the visual selection with the selected code
+/// quoted at the top (`>` key).
+fn handle_start_quoted_comment(model: &mut Model) {
+    if !model.visual_mode {
+        return;
+    }
+    let Some(request) = build_comment_request(model) else {
+        return;
+    };
+    let end = request.end_line.unwrap_or(request.start_line);
+    let lines: Vec<&str> = model
+        .file_cache
+        .get(&request.file_path)
+        .map(|entry| {
+            (request.start_line..=end)
+                .filter_map(|line| entry.side_line(request.side, line))
+                .collect()
+        })
+        .unwrap_or_default();
+    let body = format!("{}\n\n", quoted_snippet(&request, &lines));
+    model.inline_editor = Some(InlineEditor::with_body(request, &body));
+    model.focus = Focus::Commenting;
+    model.needs_redraw = true;
+}
 
-        Message::SearchActivate => {
-            model.search_active = true;
-            model.needs_redraw = true;
-        }
-        Message::SearchInput(ref text) => {
-            model.search_input.push_str(text);
-            model.list_index = 0;
-            model.list_scroll = 0;
-            model.needs_redraw = true;
-        }
-        Message::SearchBackspace => {
-            model.search_input.pop();
-            model.list_index = 0;
-            model.list_scroll = 0;
-            model.needs_redraw = true;
-        }
-        Message::SearchDeleteWord => {
-            delete_last_word(&mut model.search_input);
-            model.list_index = 0;
-            model.list_scroll = 0;
-            model.needs_redraw = true;
-        }
-        Message::SearchClearLine => {
-            model.search_input.clear();
-            model.list_index = 0;
-            model.list_scroll = 0;
-            model.needs_redraw = true;
-        }
-        Message::SearchClear => {
-            model.search_input.clear();
-            model.search_active = false;
-            model.list_index = 0;
-            model.list_scroll = 0;
-            model.needs_redraw = true;
-        }
+/// Whether `file_path` shows a normalized diff, whose line numbers aren't
+/// the file's; if so, flash that line comments are off.
+fn refuse_normalized_lines(model: &mut Model, file_path: &str) -> bool {
+    let normalized = model
+        .file_cache
+        .get(file_path)
+        .is_some_and(|entry| entry.normalized);
+    if normalized {
+        model.flash_message = Some(tr(Text::NormalizedNoLineComments).to_string());
+    }
+    normalized
+}
 
-        Message::Resize { .. }
-        | Message::Quit
-        | Message::ShowThemePicker
-        | Message::ApplyTheme(_) => {
-            update_system_theme(model, &msg);
+/// Open the inline editor on a new thread covering the hunk under the cursor
+/// (`c` key). It's drawn under the hunk header rather than after a line.
+fn handle_start_hunk_comment(model: &mut Model) {
+    if model.visual_mode {
+        return;
+    }
+    let files = model.files_with_threads();
+    if let Some(file) = files.get(model.file_index) {
+        let file_path = file.path.clone();
+        if refuse_normalized_lines(model, &file_path) {
+            return;
         }
-
-        Message::Tick | Message::Noop => {}
+    }
+    if let Some(request) = build_hunk_comment_request(model) {
+        model.open_inline_editor(request);
     }
 }
 
-/// Build a `CommentRequest` from the current model state (visual selection or expanded thread).
-fn build_comment_request(model: &mut Model) -> Option<CommentRequest> {
-    let review = model.current_review.as_ref()?;
-    let review_id = review.review_id.clone();
+/// The hunk whose header is the last one at or above the cursor in the
+/// current file: its index and its header's row.
+fn cursor_hunk(model: &Model) -> Option<(usize, usize)> {
+    let file_start = *model.stream_layout().file_offsets.get(model.file_index)?;
+    let rows = model.hunk_rows.borrow();
+    let headers: Vec<usize> = rows
+        .iter()
+        .copied()
+        .filter(|&row| row >= file_start && row <= model.diff_cursor)
+        .collect();
+    Some((headers.len().checked_sub(1)?, *headers.last()?))
+}
+
+/// Build a `CommentRequest` for a new thread on the hunk whose header is the
+/// last one at or above the cursor in the current file.
+fn build_hunk_comment_request(model: &Model) -> Option<CommentRequest> {
+    let review_id = model.current_review.as_ref()?.review_id.clone();
     let files = model.files_with_threads();
-    let file = files.get(model.file_index)?;
-    let file_path = file.path.clone();
+    let file_path = files.get(model.file_index)?.path.clone();
+    let (hunk, _) = cursor_hunk(model)?;
+    let diff = model.file_cache.get(&file_path)?.diff.as_ref()?;
+    let (side, start_line, end) = hunk_thread_range(diff.hunks.get(hunk)?)?;
+    let end_line = (end != start_line).then_some(end);
+    let excerpt = model.target_excerpt(&file_path, side, start_line, end_line);
+    Some(CommentRequest {
+        review_id,
+        file_path,
+        start_line,
+        end_line,
+        side,
+        thread_id: None,
+        existing_comments: Vec::new(),
+        thread_etag: None,
+        excerpt,
+        notice: None,
+    })
+}
 
-    if model.visual_mode {
-        let sel_start = model.visual_anchor.min(model.diff_cursor);
-        let sel_end = model.visual_anchor.max(model.diff_cursor);
+/// The file selected in the sidebar while it has focus, else the current
+/// file.
+fn targeted_file_index(model: &Model) -> usize {
+    match model.sidebar_items().get(model.sidebar_index) {
+        Some(
+            crate::model::SidebarItem::File { file_idx, .. }
+            | crate::model::SidebarItem::Thread { file_idx, .. },
+        ) if model.focus == Focus::FileSidebar => *file_idx,
+        _ => model.file_index,
+    }
+}
 
-        let line_map = model.line_map.borrow();
-        let mut min_line = i64::MAX;
-        let mut max_line = i64::MIN;
-        for row in sel_start..=sel_end {
-            if let Some(&new_line) = line_map.get(&row) {
-                min_line = min_line.min(new_line);
-                max_line = max_line.max(new_line);
-            }
-        }
-        drop(line_map);
+/// Queue a jump to new-side `line` of the active file; main resolves it
+/// like `--line` once the file's rows are in place.
+fn go_to_line(model: &mut Model, line: u32) {
+    let Some(file) = model.files_with_threads().get(model.file_index).cloned() else {
+        return;
+    };
+    model.pending_file = Some(file.path);
+    model.pending_line = Some(line);
+    model.focus = Focus::DiffPane;
+}
 
-        if min_line > max_line {
-            return None;
+/// Mark the targeted file (see [`targeted_file_index`]) viewed, or not
+/// (`x`), and log it for undo.
+fn toggle_file_viewed(model: &mut Model) {
+    let file_idx = targeted_file_index(model);
+    let Some(path) = model
+        .files_with_threads()
+        .get(file_idx)
+        .map(|file| file.path.clone())
+    else {
+        return;
+    };
+    let viewed = !model.viewed_files.contains(&path);
+    set_file_viewed(model, file_idx, viewed);
+    model
+        .action_log
+        .record(ReviewAction::Viewed { path, viewed });
+}
+
+/// Mark file `file_idx` viewed, or not, and save it with the review's
+/// progress.
+fn set_file_viewed(model: &mut Model, file_idx: usize, viewed: bool) {
+    let Some(file) = model.files_with_threads().get(file_idx).cloned() else {
+        return;
+    };
+    let under_cursor = active_file_index(&model.stream_layout(), model.diff_cursor) == file_idx;
+    model.viewed_files.remove(&file.path);
+    if viewed {
+        model.viewed_files.insert(file.path);
+        // Its lines are gone: leave the cursor on its header
+        if under_cursor {
+            let layout = model.stream_layout();
+            model.diff_cursor = layout.file_offsets.get(file_idx).copied().unwrap_or(0);
+            model.visual_mode = false;
+            jump_to_file(model, file_idx);
         }
+    }
+    record_review_progress(model);
+    model.needs_redraw = true;
+}
 
-        let end_line = if max_line == min_line {
-            None
+/// Take back the last logged action (`u`), or apply the last undone one
+/// again (Ctrl+r). A resolve or reopen goes through crit and moves in the
+/// log once written (see `Model::log_status_change`); a posted comment
+/// can't be taken back, so undo only says so and drops it.
+fn replay_action(model: &mut Model, redo: bool) {
+    let action = if redo {
+        model.action_log.last_undone()
+    } else {
+        model.action_log.last_done()
+    };
+    let Some(action) = action.cloned() else {
+        let text = if redo {
+            Text::NothingToRedo
         } else {
-            Some(max_line)
+            Text::NothingToUndo
         };
-
-        Some(CommentRequest {
-            review_id,
-            file_path,
-            start_line: min_line,
-            end_line,
-            thread_id: None,
-            existing_comments: Vec::new(),
-        })
+        model.flash_message = Some(tr(text).to_string());
+        return;
+    };
+    let (step, target) = if redo {
+        (LogStep::Redo, Some(action.clone()))
     } else {
-        // Find the thread whose rendered position is closest to (and at or
-        // before) the cursor, so pressing 'a' targets the thread the user is
-        // actually looking at rather than the stale `expanded_thread`.
-        let thread_id = {
-            let positions = model.thread_positions.borrow();
-            let mut best: Option<(usize, String)> = None;
-            for thread in model.threads.iter().filter(|t| t.file_path == file_path) {
-                if let Some(&pos) = positions.get(&thread.thread_id) {
-                    if pos <= model.diff_cursor
-                        && best.as_ref().is_none_or(|(best_pos, _)| pos > *best_pos)
-                    {
-                        best = Some((pos, thread.thread_id.clone()));
-                    }
-                }
+        (LogStep::Undo, action.inverse())
+    };
+    match target {
+        None => {
+            model.action_log.undo();
+            model.flash_message = Some(tr_fmt(Text::CantUndo, &[&action.label()]));
+            return;
+        }
+        Some(ReviewAction::ThreadStatus { thread_id, to, .. }) => {
+            if model.pending_status_change.is_some() {
+                return;
             }
-            best.map(|(_, id)| id)
-        }?;
-        let thread = model.threads.iter().find(|t| t.thread_id == thread_id)?;
-        let existing_comments = model
-            .all_comments
-            .get(&thread_id)
-            .cloned()
-            .unwrap_or_default();
+            model.queue_status_change(&thread_id, &to);
+            // Logged and flashed once written
+            if let Some(change) = &mut model.pending_status_change {
+                change.step = step;
+                return;
+            }
+        }
+        Some(ReviewAction::Viewed { path, viewed }) => {
+            if let Some(file_idx) = model
+                .files_with_threads()
+                .iter()
+                .position(|file| file.path == path)
+            {
+                set_file_viewed(model, file_idx, viewed);
+            }
+        }
+        Some(ReviewAction::Comment { .. }) => {}
+    }
+    let text = if redo { Text::Redone } else { Text::Undone };
+    model.flash_message = Some(tr_fmt(text, &[&action.label()]));
+    model.action_log.apply(step, action);
+    model.needs_redraw = true;
+}
 
-        Some(CommentRequest {
-            review_id,
-            file_path: thread.file_path.clone(),
-            start_line: thread.selection_start,
-            end_line: thread.selection_end,
-            thread_id: Some(thread_id),
-            existing_comments,
+/// Remember where the open review was left: the file and new-side line
+/// under the cursor, viewed files and collapsed sidebar groups. Saved
+/// when anything changed.
+fn record_review_progress(model: &mut Model) {
+    let Some(review_id) = model.current_review.as_ref().map(|r| r.review_id.clone()) else {
+        return;
+    };
+    let layout = model.stream_layout();
+    // Above the first file (description, review threads) there's no spot
+    // worth going back to
+    let in_files = layout
+        .file_offsets
+        .first()
+        .is_some_and(|&start| model.diff_cursor >= start);
+    let index = active_file_index(&layout, model.diff_cursor);
+    let file = in_files
+        .then(|| {
+            model
+                .files_with_threads()
+                .get(index)
+                .map(|f| f.path.clone())
         })
+        .flatten();
+    // On a thread or header row, the nearest line above it in the file
+    let file_start = layout.file_offsets.get(index).copied().unwrap_or(0);
+    let line = file.as_ref().and_then(|_| {
+        let line_map = model.line_map.borrow();
+        let line = (file_start..=model.diff_cursor)
+            .rev()
+            .find_map(|row| line_map.get(&row).copied())?;
+        u32::try_from(line).ok()
+    });
+    let progress = ReviewProgress {
+        file,
+        line,
+        viewed: model.viewed_files.iter().cloned().collect(),
+        collapsed: model.collapsed_files.iter().cloned().collect(),
+    };
+    if model.ui_state.reviews.get(&review_id) != Some(&progress) {
+        model.ui_state.reviews.insert(review_id, progress);
+        let _ = model.ui_state.save();
     }
 }
 
-/// Open inline multi-line comment editor (a key).
-fn handle_start_comment_inline(model: &mut Model) {
-    if let Some(request) = build_comment_request(model) {
-        model.inline_editor = Some(InlineEditor::new(request));
-        model.focus = Focus::Commenting;
+/// Put back what [`record_review_progress`] kept for the review just
+/// loaded. `--file` and `--thread` win over the saved position.
+fn restore_review_progress(model: &mut Model) {
+    let Some(review) = &model.current_review else {
+        return;
+    };
+    let Some(progress) = model.ui_state.reviews.get(&review.review_id).cloned() else {
+        return;
+    };
+    model.viewed_files = progress.viewed.into_iter().collect();
+    model.collapsed_files = progress.collapsed.into_iter().collect();
+    if model.pending_file.is_none() && model.pending_thread.is_none() {
+        model.pending_file = progress.file;
+        model.pending_line = progress.line;
+    }
+}
+
+/// Open the inline editor on a new file-level thread: for the file selected
+/// in the sidebar (`a` there), else the current file.
+fn handle_start_file_comment(model: &mut Model) {
+    let file_idx = targeted_file_index(model);
+    let Some(review) = model.current_review.as_ref() else {
+        return;
+    };
+    let Some(file) = model.files_with_threads().get(file_idx).cloned() else {
+        return;
+    };
+    let request = CommentRequest {
+        review_id: review.review_id.clone(),
+        file_path: file.path,
+        start_line: FILE_THREAD_LINE,
+        end_line: None,
+        side: ThreadSide::New,
+        thread_id: None,
+        existing_comments: Vec::new(),
+        thread_etag: None,
+        excerpt: Vec::new(),
+        notice: None,
+    };
+    model.open_inline_editor(request);
+}
+
+/// Open the inline editor on a new review-level thread, drawn after the
+/// description at the top of the stream.
+fn handle_start_review_comment(model: &mut Model) {
+    let Some(request) = review_thread_request(model) else {
+        return;
+    };
+    model.open_inline_editor(request);
+}
+
+/// A new review-level thread on the current review.
+fn review_thread_request(model: &Model) -> Option<CommentRequest> {
+    let review = model.current_review.as_ref()?;
+    Some(CommentRequest {
+        review_id: review.review_id.clone(),
+        file_path: REVIEW_THREAD_PATH.to_string(),
+        start_line: FILE_THREAD_LINE,
+        end_line: None,
+        side: ThreadSide::New,
+        thread_id: None,
+        existing_comments: Vec::new(),
+        thread_etag: None,
+        excerpt: Vec::new(),
+        notice: None,
+    })
+}
+
+/// Offer approve / request changes / abandon in the action menu ("Finish
+/// review"), or restore for an abandoned review.
+fn handle_finish_review(model: &mut Model) {
+    if model.current_review.is_none() {
+        return;
+    }
+    let abandoned = model.abandoned_review().is_some();
+    model.action_menu = if abandoned {
+        vec![MenuAction::RestoreReview]
+    } else {
+        vec![
+            MenuAction::Approve,
+            MenuAction::RequestChanges,
+            MenuAction::AbandonReview,
+        ]
+    };
+    // Start on the verdict left as a draft
+    model.action_menu_selection = usize::from(
+        !abandoned
+            && model
+                .draft_verdict()
+                .is_some_and(|draft| draft.verdict == Verdict::RequestChanges),
+    );
+    model.previous_focus = Some(model.focus);
+    model.focus = Focus::ActionMenu;
+    model.needs_redraw = true;
+}
+
+/// Open the inline editor on the optional summary for `verdict`. Saving
+/// queues the verdict even with an empty body.
+fn handle_start_verdict(model: &mut Model, verdict: Verdict) {
+    if let Some(request) = review_thread_request(model) {
+        model.open_verdict_editor(request, verdict);
+    }
+}
+
+/// Open the inline editor on the optional reason for abandoning the review.
+fn handle_start_abandon(model: &mut Model) {
+    if model.abandoned_review().is_some() {
+        model.flash_message = Some(tr(Text::AlreadyAbandoned).to_string());
         model.needs_redraw = true;
+    } else if let Some(request) = review_thread_request(model) {
+        model.open_abandon_editor(request);
+    }
+}
+
+/// Queue reopening the review, if it's abandoned.
+fn handle_restore_review(model: &mut Model) {
+    let Some(review) = model.current_review.as_ref() else {
+        return;
+    };
+    if review.status == "abandoned" {
+        model.pending_review_status = Some(ReviewStatusChange::Restore {
+            review_id: review.review_id.clone(),
+        });
+    } else {
+        model.flash_message = Some(tr(Text::NotAbandoned).to_string());
+    }
+    model.needs_redraw = true;
+}
+
+/// Open the inline editor on a reply to the expanded thread (`r` key).
+fn handle_start_reply(model: &mut Model) {
+    let Some(thread_id) = model.expanded_thread.clone() else {
+        return;
+    };
+    if let Some(request) = build_reply_request(model, thread_id) {
+        model.open_inline_editor(request);
     }
 }
 
@@ -1017,6 +3058,12 @@ fn sync_file_index_from_sidebar(model: &mut Model) {
     let items = model.sidebar_items();
     if let Some(item) = items.get(model.sidebar_index) {
         match item {
+            // Stay put while the current file is inside
+            crate::model::SidebarItem::Directory { files, .. } => {
+                if !files.contains(&model.file_index) {
+                    jump_to_file(model, files.start);
+                }
+            }
             crate::model::SidebarItem::File { file_idx, .. } => {
                 jump_to_file(model, *file_idx);
             }
@@ -1039,21 +3086,50 @@ fn sync_file_index_from_sidebar(model: &mut Model) {
 }
 
 fn jump_to_file(model: &mut Model, index: usize) {
-    model.file_index = index;
+    set_active_file(model, index);
     model.expanded_thread = None;
 
-    let layout = stream_layout(model);
+    let layout = model.stream_layout();
     model.diff_scroll = file_scroll_offset(&layout, index);
-    model.sync_active_file_cache();
     model.needs_redraw = true;
 }
 
+/// Jump to the `n`th (from 1) recent file other than the current one.
+fn jump_to_recent_file(model: &mut Model, n: usize) {
+    let target = n.checked_sub(1).and_then(|i| {
+        let path = *model.recent_other_files().get(i)?;
+        model
+            .files_with_threads()
+            .iter()
+            .position(|f| f.path == path)
+    });
+    if let Some(index) = target {
+        if let Some(pos) = model.sidebar_position_of_file(index) {
+            model.sidebar_index = pos;
+        }
+        jump_to_file(model, index);
+        ensure_sidebar_visible(model);
+    }
+}
+
+/// Make file `index` the current one. Both the file left and the one
+/// reached count as visited, for Alt+1….
+fn set_active_file(model: &mut Model, index: usize) {
+    let files = model.files_with_threads();
+    let paths =
+        [files.get(model.file_index), files.get(index)].map(|file| file.map(|f| f.path.clone()));
+    for path in paths.iter().flatten() {
+        model.visit_file(path);
+    }
+    model.file_index = index;
+    model.sync_active_file_cache();
+}
+
 fn update_active_file_from_scroll(model: &mut Model) {
-    let layout = stream_layout(model);
+    let layout = model.stream_layout();
     let active = active_file_index(&layout, model.diff_scroll);
     if active != model.file_index {
-        model.file_index = active;
-        model.sync_active_file_cache();
+        set_active_file(model, active);
     }
     sync_sidebar_from_active(model);
     model.needs_redraw = true;
@@ -1062,26 +3138,21 @@ fn update_active_file_from_scroll(model: &mut Model) {
 fn sync_sidebar_from_active(model: &mut Model) {
     let items = model.sidebar_items();
     let mut target = active_thread_from_scroll(model).and_then(|thread_id| {
-        items.iter().position(|item| match item {
-            crate::model::SidebarItem::Thread { thread_id: id, .. } => id == &thread_id,
-            crate::model::SidebarItem::File { .. } => false,
+        items.iter().position(|item| {
+            matches!(item, crate::model::SidebarItem::Thread { thread_id: id, .. } if id == &thread_id)
         })
     });
 
     if target.is_none() {
         if let Some(thread_id) = &model.expanded_thread {
-            target = items.iter().position(|item| match item {
-                crate::model::SidebarItem::Thread { thread_id: id, .. } => id == thread_id,
-                crate::model::SidebarItem::File { .. } => false,
+            target = items.iter().position(|item| {
+                matches!(item, crate::model::SidebarItem::Thread { thread_id: id, .. } if id == thread_id)
             });
         }
     }
 
     if target.is_none() {
-        target = items.iter().position(|item| match item {
-            crate::model::SidebarItem::File { file_idx, .. } => *file_idx == model.file_index,
-            crate::model::SidebarItem::Thread { .. } => false,
-        });
+        target = model.sidebar_position_of_file(model.file_index);
     }
 
     if let Some(index) = target {
@@ -1153,11 +3224,13 @@ fn ensure_sidebar_visible(model: &mut Model) {
     }
 }
 
-const fn sidebar_visible_rows(model: &Model) -> usize {
-    let mut start = 1usize;
-    if model.current_review.is_some() {
-        start = start.saturating_add(5);
-    }
+fn sidebar_visible_rows(model: &Model) -> usize {
+    // Before the first render, assume the shortest review header
+    let start = match model.sidebar_list_top.get() {
+        0 if model.current_review.is_some() => 6,
+        0 => 1,
+        top => top as usize,
+    };
     let bottom = model.height.saturating_sub(1) as usize;
     if start >= bottom {
         return 0;
@@ -1181,7 +3254,7 @@ fn center_on_thread(model: &mut Model) {
         drop(positions);
         // Thread not anchored in the diff (line outside hunk range).
         // Scroll to the end of the file's section as a fallback.
-        let layout = stream_layout(model);
+        let layout = model.stream_layout();
         let files = model.files_with_threads();
         if let Some(thread) = model.threads.iter().find(|t| t.thread_id == thread_id) {
             if let Some(file_index) = files.iter().position(|f| f.path == thread.file_path) {
@@ -1198,27 +3271,8 @@ fn center_on_thread(model: &mut Model) {
     }
 }
 
-fn stream_layout(model: &Model) -> crate::stream::StreamLayout {
-    let files = model.files_with_threads();
-    let width = diff_content_width(model);
-    let description = model
-        .current_review
-        .as_ref()
-        .and_then(|r| r.description.as_deref());
-    compute_stream_layout(&StreamLayoutParams {
-        files: &files,
-        file_cache: &model.file_cache,
-        threads: &model.threads,
-        all_comments: &model.all_comments,
-        view_mode: model.diff_view_mode,
-        wrap: model.diff_wrap,
-        content_width: width,
-        description,
-    })
-}
-
 fn clamp_diff_scroll(model: &mut Model) {
-    let layout = stream_layout(model);
+    let layout = model.stream_layout();
     let visible = visible_stream_rows(model.height);
     let max_scroll = layout.total_lines.saturating_sub(visible);
     if model.diff_scroll > max_scroll {
@@ -1226,26 +3280,24 @@ fn clamp_diff_scroll(model: &mut Model) {
     }
 }
 
-fn diff_content_width(model: &Model) -> u32 {
-    /// Must match `DIFF_MARGIN` in diff.rs.
-    const DIFF_MARGIN: u32 = 2;
-    let total_width = u32::from(model.width);
-    let pane_width = match model.layout_mode {
-        crate::model::LayoutMode::Full
-        | crate::model::LayoutMode::Compact
-        | crate::model::LayoutMode::Overlay => {
-            if model.sidebar_visible {
-                total_width.saturating_sub(u32::from(model.layout_mode.sidebar_width()))
-            } else {
-                total_width
-            }
-        }
-        crate::model::LayoutMode::Single => total_width,
-    };
-    pane_width.saturating_sub(DIFF_MARGIN * 2)
+/// If the theme picker is active, apply the currently highlighted theme as a preview.
+/// Number of selectable rows in the palette's current mode.
+fn palette_item_count(model: &Model) -> usize {
+    match model.command_palette_mode {
+        PaletteMode::Commands => model.command_palette_commands.len(),
+        PaletteMode::Themes => filter_theme_names(&model.command_palette_input).len(),
+        PaletteMode::Comments => model.find_comments(&model.command_palette_input).len(),
+        PaletteMode::Argument(_) => 0,
+    }
+}
+
+/// Palette input that looks like a comment permalink (`cm-…`).
+fn typed_comment_id(input: &str) -> Option<String> {
+    let input = input.trim();
+    (input.len() > 3 && input[..3].eq_ignore_ascii_case("cm-") && !input.contains(' '))
+        .then(|| input.to_string())
 }
 
-/// If the theme picker is active, apply the currently highlighted theme as a preview.
 fn preview_selected_theme(model: &mut Model) {
     if model.command_palette_mode != PaletteMode::Themes {
         return;
@@ -1255,7 +3307,7 @@ fn preview_selected_theme(model: &mut Model) {
         if let Some(loaded) = theme::load_built_in_theme(name) {
             model.theme = loaded.theme;
             if let Some(syntax_theme) = loaded.syntax_theme {
-                model.highlighter = Highlighter::with_theme(&syntax_theme);
+                model.highlighter = Arc::new(Highlighter::with_theme(&syntax_theme));
             }
         }
     }
@@ -1287,8 +3339,25 @@ fn delete_last_word(s: &mut String) {
     }
 }
 
-fn filter_commands(query: &str) -> Vec<crate::command::CommandSpec> {
-    let commands = get_commands();
+/// Palette commands matching `query`, recently run ones first under their
+/// own heading.
+fn filter_commands(
+    query: &str,
+    read_only: bool,
+    recent: &[CommandId],
+) -> Vec<crate::command::CommandSpec> {
+    let mut commands = get_commands(read_only);
+    for command in &mut commands {
+        if recent.contains(&command.id) {
+            command.category = tr(Text::CategoryRecent);
+        }
+    }
+    commands.sort_by_key(|command| {
+        recent
+            .iter()
+            .position(|&id| id == command.id)
+            .unwrap_or(usize::MAX)
+    });
     let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
     if terms.is_empty() {
         return commands;
@@ -1304,3 +3373,288 @@ fn filter_commands(query: &str) -> Vec<crate::command::CommandSpec> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::UiConfig;
+    use crate::db::{ReviewDetail, ReviewSummary, ThreadSummary};
+    use crate::diff::ParsedDiff;
+    use crate::model::{FileCacheEntry, ReviewSort, MIN_SIDEBAR_WIDTH};
+    use crate::render_backend::{Event, MouseButton, MouseEvent, MouseEventKind, OptimizedBuffer};
+    use crate::suggestion::SuggestionPatch;
+
+    /// Two changed lines far enough apart to be separate hunks
+    const TWO_HUNKS: &str =
+        "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n+B\n@@ -10,2 +10,2 @@\n c\n-d\n+D\n";
+
+    /// A review of `paths`, each changed by [`TWO_HUNKS`], drawn once so the
+    /// rows recorded while rendering are there.
+    fn review_model(paths: &[&str]) -> Model {
+        let mut model = Model::new(100, 60, UiConfig::default());
+        model.screen = Screen::ReviewDetail;
+        model.focus = Focus::DiffPane;
+        model.current_review = Some(ReviewDetail {
+            review_id: "cr-1".to_string(),
+            jj_change_id: String::new(),
+            initial_commit: String::new(),
+            final_commit: None,
+            title: "Two files".to_string(),
+            description: None,
+            author: "alice".to_string(),
+            created_at: String::new(),
+            status: "open".to_string(),
+            status_changed_at: None,
+            status_changed_by: None,
+            abandon_reason: None,
+            thread_count: 0,
+            open_thread_count: 0,
+            votes: Vec::new(),
+            rewritten_to: None,
+        });
+        for path in paths {
+            model.file_cache.insert(
+                (*path).to_string(),
+                FileCacheEntry {
+                    diff: Some(ParsedDiff::parse(TWO_HUNKS)),
+                    file_content: None,
+                    highlighted_lines: Vec::new(),
+                    file_highlighted_lines: Vec::new(),
+                    word_lines: None,
+                    normalized: false,
+                    revision: FileCacheEntry::next_revision(),
+                },
+            );
+        }
+        crate::view::view(&model, &mut OptimizedBuffer::new(100, 60));
+        model
+    }
+
+    /// Cursor rows after sending `msg` `times` times.
+    fn cursor_trail(model: &mut Model, msg: &Message, times: usize) -> Vec<usize> {
+        (0..times)
+            .map(|_| {
+                update(model, msg.clone());
+                model.diff_cursor
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_hunk_motions_cross_files_and_stop_at_the_ends() {
+        let mut model = review_model(&["a.rs", "b.rs"]);
+        let hunks = first_stops_after(&model.cursor_stops.borrow(), &model.hunk_rows.borrow());
+        let second_file = model.stream_layout().file_offsets[1];
+        assert_eq!(hunks.len(), 4);
+        assert!(hunks[1] < second_file && second_file < hunks[2]);
+
+        let forward = cursor_trail(&mut model, &Message::CursorNextHunk, 5);
+        assert_eq!(forward, [hunks[0], hunks[1], hunks[2], hunks[3], hunks[3]]);
+        let back = cursor_trail(&mut model, &Message::CursorPrevHunk, 4);
+        assert_eq!(back, [hunks[2], hunks[1], hunks[0], hunks[0]]);
+    }
+
+    #[test]
+    fn test_file_motions_and_going_back_from_inside() {
+        let mut model = review_model(&["a.rs", "b.rs"]);
+        let files = first_stops_after(
+            &model.cursor_stops.borrow(),
+            &model.stream_layout().file_offsets,
+        );
+        let hunks = first_stops_after(&model.cursor_stops.borrow(), &model.hunk_rows.borrow());
+        model.diff_cursor = files[0];
+
+        let forward = cursor_trail(&mut model, &Message::CursorNextFile, 2);
+        assert_eq!(forward, [files[1], files[1]]);
+        // From a later line of a hunk or file, back lands on its first line
+        model.diff_cursor = hunks[3] + 1;
+        assert_eq!(
+            cursor_trail(&mut model, &Message::CursorPrevHunk, 1),
+            [hunks[3]]
+        );
+        model.diff_cursor = hunks[3];
+        let back = cursor_trail(&mut model, &Message::CursorPrevFile, 3);
+        assert_eq!(back, [files[1], files[0], files[0]]);
+    }
+
+    #[test]
+    fn test_change_motions_skip_context() {
+        let mut model = review_model(&["a.rs", "b.rs"]);
+        let changes = model.change_rows.borrow().clone();
+        let stops = model.cursor_stops.borrow().clone();
+        assert_eq!(changes.len(), 4);
+        // Context lines between runs are stops the motion skips
+        assert!(stops.contains(&(changes[1] - 1)));
+        model.diff_cursor = stops[0];
+
+        let forward = cursor_trail(&mut model, &Message::CursorNextChange, 5);
+        assert_eq!(
+            forward,
+            [changes[0], changes[1], changes[2], changes[3], changes[3]]
+        );
+        // Back from the second line of a run lands on its first
+        model.diff_cursor = changes[3] + 1;
+        let back = cursor_trail(&mut model, &Message::CursorPrevChange, 5);
+        assert_eq!(
+            back,
+            [changes[3], changes[2], changes[1], changes[0], changes[0]]
+        );
+    }
+
+    #[test]
+    fn test_sidebar_resize_keeps_width_in_bounds() {
+        let mut model = Model::new(120, 40, UiConfig::default());
+        update(&mut model, Message::StartSidebarResize);
+        assert!(model.sidebar_resizing);
+        // Past either bound, what's saved is the width drawn
+        for (dragged, drawn) in [(2, MIN_SIDEBAR_WIDTH), (200, 60), (40, 40)] {
+            update(&mut model, Message::ResizeSidebar(dragged));
+            assert_eq!(model.sidebar_width(), drawn);
+            assert_eq!(model.config.sidebar_width, Some(drawn));
+        }
+    }
+
+    #[test]
+    fn test_clicking_column_titles_sorts_the_review_list() {
+        let mut model = Model::new(160, 40, UiConfig::default());
+        model.reviews = [("cr-1", 1), ("cr-2", 3), ("cr-3", 2)]
+            .map(|(id, open)| ReviewSummary {
+                review_id: id.to_string(),
+                title: id.to_string(),
+                author: "alice".to_string(),
+                status: "open".to_string(),
+                thread_count: open,
+                open_thread_count: open,
+                reviewers: Vec::new(),
+                created_at: None,
+                updated_at: None,
+                diff_stats: None,
+            })
+            .to_vec();
+        // Press and release on the click target whose message is `wanted`
+        let click = |model: &mut Model, wanted: fn(&Message) -> bool| {
+            crate::view::view(model, &mut OptimizedBuffer::new(160, 40));
+            let (x, y) = model
+                .list_click_targets
+                .borrow()
+                .iter()
+                .find(|target| wanted(&target.message))
+                .map(|target| (target.x.start, target.y))
+                .unwrap();
+            [MouseEventKind::Press, MouseEventKind::Release].map(|kind| {
+                let event = Event::Mouse(MouseEvent::new(x, y, MouseButton::Left, kind));
+                crate::input::map_event_to_message(model, &event)
+            })
+        };
+        let open_counts = |model: &Model| -> Vec<i64> {
+            model
+                .filtered_reviews()
+                .iter()
+                .map(|r| r.open_thread_count)
+                .collect()
+        };
+
+        let pressed = click(&mut model, |msg| {
+            matches!(msg, Message::SetReviewSort(ReviewSort::OpenThreads))
+        });
+        assert!(matches!(
+            pressed,
+            [
+                Message::SetReviewSort(ReviewSort::OpenThreads),
+                Message::Noop
+            ]
+        ));
+        // What SetReviewSort does, short of saving it
+        model.review_sort = ReviewSort::OpenThreads;
+        assert_eq!(open_counts(&model), [3, 2, 1]);
+        // The sorted column's title now reverses it, and no title sorts by
+        // threads again
+        let pressed = click(&mut model, |msg| matches!(msg, Message::ReverseReviewSort));
+        assert!(matches!(pressed[0], Message::ReverseReviewSort));
+        assert!(!model.list_click_targets.borrow().iter().any(|target| {
+            matches!(
+                target.message,
+                Message::SetReviewSort(ReviewSort::OpenThreads)
+            )
+        }));
+        model.config.review_sort_reversed = Some(true);
+        assert_eq!(open_counts(&model), [1, 2, 3]);
+        let pressed = click(&mut model, |msg| matches!(msg, Message::CycleStatusFilter));
+        assert!(matches!(pressed[0], Message::CycleStatusFilter));
+    }
+
+    #[test]
+    fn test_read_only_blocks_applying_and_undoing_suggestions() {
+        let mut model = Model::new(80, 24, UiConfig::default());
+        model.read_only = true;
+        model.repo_path = Some(".".to_string());
+        let lines = ["old".to_string()];
+        model.applied_suggestion = Some(SuggestionPatch::new("a.rs", 1, &lines, &lines, |_| None));
+        for msg in [Message::ApplySuggestion, Message::UndoSuggestion] {
+            update(&mut model, msg);
+            assert!(model.pending_patch.is_none());
+            assert_eq!(
+                model.flash_message.as_deref(),
+                Some(tr(Text::ReadOnlyBlocked))
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_only_blocks_every_mutating_message() {
+        let mut model = review_model(&["a.rs"]);
+        model.read_only = true;
+        model.threads = vec![ThreadSummary {
+            thread_id: "th-1".to_string(),
+            file_path: "a.rs".to_string(),
+            selection_start: 2,
+            selection_end: None,
+            side: None,
+            status: "open".to_string(),
+            comment_count: 1,
+        }];
+        model.diff_cursor = model.cursor_stops.borrow()[1];
+        let messages = [
+            Message::StartComment,
+            Message::StartCommentExternal,
+            Message::StartQuotedComment,
+            Message::StartHunkComment,
+            Message::StartFileComment,
+            Message::StartReviewComment,
+            Message::StartReply,
+            Message::FinishReview,
+            Message::StartVerdict(Verdict::Approve),
+            Message::StartAbandonReview,
+            Message::RestoreReview,
+            Message::EnterCommentMode,
+            Message::SaveComment,
+            Message::ResolveThread("th-1".to_string()),
+            Message::ReopenThread("th-1".to_string()),
+            Message::ApplySuggestion,
+            Message::UndoSuggestion,
+        ];
+        for msg in messages {
+            assert!(msg.is_mutating(), "{msg:?}");
+            model.flash_message = None;
+            update(&mut model, msg.clone());
+            assert_eq!(
+                model.flash_message.as_deref(),
+                Some(tr(Text::ReadOnlyBlocked)),
+                "{msg:?}"
+            );
+            assert_eq!(
+                (model.screen, model.focus),
+                (Screen::ReviewDetail, Focus::DiffPane)
+            );
+            assert!(model.inline_editor.is_none() && model.comment_input.is_empty());
+            assert!(model.pending_comment_request.is_none());
+            assert!(model.pending_editor_request.is_none());
+            assert!(model.pending_comment_submission.is_none());
+            assert!(model.pending_status_change.is_none());
+            assert!(model.pending_verdict.is_none());
+            assert!(model.pending_review_status.is_none());
+            assert!(model.pending_patch.is_none());
+            assert_eq!(model.threads[0].status, "open");
+        }
+    }
+}
@@ -2,8 +2,13 @@
 //!
 //! Supports both jj (Jujutsu) and git repositories.
 
-use std::path::Path;
-use std::process::Command;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context};
 
 use crate::diff::ParsedDiff;
 
@@ -27,9 +32,22 @@ pub fn detect_vcs(path: &Path) -> Option<VcsType> {
     None
 }
 
+/// Shell command that checks out a review's change for local testing:
+/// `jj new <change>` (a fresh working-copy commit on top of it) or
+/// `git switch --detach <commit>`.
+#[must_use]
+pub fn checkout_command(vcs: VcsType, change_id: &str, commit: &str) -> String {
+    match vcs {
+        VcsType::Jj if !change_id.is_empty() => format!("jj new {change_id}"),
+        VcsType::Jj => format!("jj new {commit}"),
+        VcsType::Git => format!("git switch --detach {commit}"),
+    }
+}
+
 /// Get the diff for a specific file between two commits.
 ///
-/// If `to_commit` is None, diffs against the working copy.
+/// If `to_commit` is None, diffs against the working copy. A binary file's
+/// [`ParsedDiff::binary`] gets the sizes the diff left out.
 #[must_use]
 pub fn get_file_diff(
     repo_path: &Path,
@@ -44,7 +62,44 @@ pub fn get_file_diff(
         VcsType::Git => get_git_diff(repo_path, file_path, from_commit, to_commit),
     };
 
-    output.map(|diff_text| ParsedDiff::parse(&diff_text))
+    let mut diff = ParsedDiff::parse(&output?);
+    if let Some(binary) = &mut diff.binary {
+        if binary.old_size.is_none() && !binary.added {
+            binary.old_size = file_size(repo_path, vcs, file_path, Some(from_commit));
+        }
+        if binary.new_size.is_none() && !binary.deleted {
+            binary.new_size = file_size(repo_path, vcs, file_path, to_commit);
+        }
+    }
+    Some(diff)
+}
+
+/// Size in bytes of `file_path` at `commit`, or in the working copy.
+fn file_size(repo_path: &Path, vcs: VcsType, file_path: &str, commit: Option<&str>) -> Option<u64> {
+    let Some(commit) = commit else {
+        return std::fs::metadata(repo_path.join(file_path))
+            .ok()
+            .map(|meta| meta.len());
+    };
+    let output = match vcs {
+        VcsType::Jj => Command::new("jj")
+            .current_dir(repo_path)
+            .args(["file", "show", "-r", commit, file_path])
+            .output()
+            .ok()?,
+        VcsType::Git => Command::new("git")
+            .current_dir(repo_path)
+            .args(["cat-file", "-s", &format!("{commit}:{file_path}")])
+            .output()
+            .ok()?,
+    };
+    if !output.status.success() {
+        return None;
+    }
+    match vcs {
+        VcsType::Jj => Some(output.stdout.len() as u64),
+        VcsType::Git => String::from_utf8_lossy(&output.stdout).trim().parse().ok(),
+    }
 }
 
 /// Get diff using jj
@@ -119,34 +174,61 @@ fn get_git_diff(
 
 /// Get file content at a specific commit.
 ///
-/// Returns the file content as a vector of lines.
-pub fn get_file_content(repo_path: &Path, file_path: &str, commit: &str) -> Option<Vec<String>> {
+/// Returns the file content as a vector of lines, or only the 1-based
+/// `lines` of it when given: output past the window is never read.
+#[must_use]
+pub fn get_file_content(
+    repo_path: &Path,
+    file_path: &str,
+    commit: &str,
+    lines: Option<RangeInclusive<usize>>,
+) -> Option<Vec<String>> {
     let vcs = detect_vcs(repo_path)?;
 
-    let output = match vcs {
+    let mut cmd = match vcs {
         VcsType::Jj => {
             // jj file show <file> -r <commit>
             let mut cmd = Command::new("jj");
-            cmd.current_dir(repo_path);
             cmd.arg("file").arg("show").arg(file_path);
             cmd.arg("-r").arg(commit);
-            cmd.output().ok()?
+            cmd
         }
         VcsType::Git => {
             // git show <commit>:<file>
             let mut cmd = Command::new("git");
-            cmd.current_dir(repo_path);
             cmd.arg("show").arg(format!("{commit}:{file_path}"));
-            cmd.output().ok()?
+            cmd
         }
     };
+    let mut child = cmd
+        .current_dir(repo_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let stdout = child.stdout.take()?;
+    let window = read_window(BufReader::new(stdout), &lines.unwrap_or(1..=usize::MAX));
+    // Stop a command still writing past the window
+    let _ = child.kill();
+    let status = child.wait().ok()?;
+    (status.success() || !window.is_empty()).then_some(window)
+}
 
-    if output.status.success() {
-        let content = String::from_utf8_lossy(&output.stdout);
-        Some(content.lines().map(String::from).collect())
-    } else {
-        None
+/// The 1-based `lines` of `reader`, reading no further than their end.
+fn read_window(mut reader: impl BufRead, lines: &RangeInclusive<usize>) -> Vec<String> {
+    let mut window = Vec::new();
+    let mut buf = Vec::new();
+    for number in 1..=*lines.end() {
+        buf.clear();
+        if !matches!(reader.read_until(b'\n', &mut buf), Ok(n) if n > 0) {
+            break;
+        }
+        if number >= *lines.start() {
+            let line = buf.strip_suffix(b"\n").unwrap_or(&buf);
+            window.push(String::from_utf8_lossy(line).into_owned());
+        }
     }
+    window
 }
 
 /// Get the full diff for all files between two commits.
@@ -196,10 +278,390 @@ pub fn get_full_diff(
     }
 }
 
+/// The commit jj change `change_id` points at now, or `None` if jj can't
+/// resolve it or it has diverged into several commits.
+#[must_use]
+pub fn jj_change_commit(repo_path: &Path, change_id: &str) -> Option<String> {
+    let output = Command::new("jj")
+        .current_dir(repo_path)
+        .args(["log", "--no-graph", "-r", change_id])
+        .args(["-T", r#"commit_id ++ "\n""#])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut commits = stdout.lines().filter(|line| !line.trim().is_empty());
+    let commit = commits.next()?.trim().to_string();
+    commits.next().is_none().then_some(commit)
+}
+
+/// `jj diff -r <change>` as `(path, diff)` pairs: the change against its
+/// parent at whatever commit it points to now.
+#[must_use]
+pub fn jj_change_diffs(repo_path: &Path, change_id: &str) -> Option<Vec<(String, String)>> {
+    let output = Command::new("jj")
+        .current_dir(repo_path)
+        .args(["diff", "--git", "-r", change_id])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| split_file_diffs(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Split a multi-file git-format diff at its `diff --git` headers, keyed by
+/// the new path.
+fn split_file_diffs(text: &str) -> Vec<(String, String)> {
+    let mut files: Vec<(String, String)> = Vec::new();
+    for line in text.split_inclusive('\n') {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            let header = header.trim_end();
+            let path = header.rsplit_once(" b/").map_or(header, |(_, path)| path);
+            files.push((path.to_string(), String::new()));
+        }
+        if let Some((_, diff)) = files.last_mut() {
+            diff.push_str(line);
+        }
+    }
+    files
+}
+
+/// Whether two commit ids name the same commit; either may be abbreviated.
+#[must_use]
+pub fn same_commit(a: &str, b: &str) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    !short.is_empty() && long.starts_with(short)
+}
+
+/// Apply a unified diff to the working copy at `repo_path` with
+/// `git apply`, or take it back out with `reverse`.
+///
+/// Paths are relative to `repo_path`; outside a git repo `git apply`
+/// patches the files directly, so jj working copies work too. Nothing is
+/// changed if any hunk fails.
+///
+/// # Errors
+///
+/// Returns an error if git can't be run or the patch doesn't apply.
+pub fn apply_patch(repo_path: &Path, patch: &str, reverse: bool) -> anyhow::Result<()> {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(repo_path).args(["apply", "--unidiff-zero"]);
+    if reverse {
+        cmd.arg("--reverse");
+    }
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run git apply")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(patch.as_bytes())
+            .context("Failed to write patch")?;
+    }
+    let output = child
+        .wait_with_output()
+        .context("Failed to run git apply")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // The first line names the hunk that failed
+        bail!("{}", stderr.lines().next().unwrap_or("git apply failed"));
+    }
+    Ok(())
+}
+
+/// Who last changed a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    /// Abbreviated commit hash
+    pub commit: String,
+    pub author: String,
+    /// Author date as `YYYY-MM-DD` (UTC)
+    pub date: String,
+    /// First line of the commit message
+    pub summary: String,
+}
+
+/// Blame one line of `file_path` as of `commit`.
+///
+/// Uses `git blame`, so it works in git and colocated jj repos; jj-only
+/// repos return `None`.
+#[must_use]
+pub fn blame_line(repo_path: &Path, file_path: &str, line: i64, commit: &str) -> Option<BlameLine> {
+    git_blame(repo_path, file_path, commit, Some(line))?
+        .into_iter()
+        .next()
+}
+
+/// Blame every line of `file_path` as of `commit`, in line order.
+///
+/// Like [`blame_line`], `None` outside git.
+#[must_use]
+pub fn blame_file(repo_path: &Path, file_path: &str, commit: &str) -> Option<Vec<BlameLine>> {
+    git_blame(repo_path, file_path, commit, None)
+}
+
+fn git_blame(
+    repo_path: &Path,
+    file_path: &str,
+    commit: &str,
+    line: Option<i64>,
+) -> Option<Vec<BlameLine>> {
+    if !repo_path.join(".git").exists() {
+        return None;
+    }
+    let mut cmd = Command::new("git");
+    cmd.current_dir(repo_path).arg("blame").arg("--porcelain");
+    if let Some(line) = line {
+        cmd.arg("-L").arg(format!("{line},{line}"));
+    }
+    let output = cmd.arg(commit).arg("--").arg(file_path).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_git_blame(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git blame --porcelain` output, one entry per blamed line.
+///
+/// A commit's author and summary follow only its first line, so they're
+/// remembered for the lines after it.
+fn parse_git_blame(text: &str) -> Vec<BlameLine> {
+    let mut commits: HashMap<&str, BlameLine> = HashMap::new();
+    let mut blamed = Vec::new();
+    let mut sha = None;
+    for line in text.lines() {
+        // The line's content ends its entry
+        if line.starts_with('\t') {
+            if let Some(entry) = sha.take().and_then(|sha| commits.get(sha)) {
+                blamed.push(entry.clone());
+            }
+            continue;
+        }
+        let Some(current) = sha else {
+            sha = line.split_whitespace().next();
+            if let Some(sha) = sha {
+                commits.entry(sha).or_insert_with(|| BlameLine {
+                    commit: sha.chars().take(8).collect(),
+                    author: String::new(),
+                    date: String::new(),
+                    summary: String::new(),
+                });
+            }
+            continue;
+        };
+        let Some(entry) = commits.get_mut(current) else {
+            continue;
+        };
+        if let Some(rest) = line.strip_prefix("author ") {
+            entry.author = rest.to_string();
+        } else if let Some(rest) = line.strip_prefix("author-time ") {
+            if let Ok(time) = rest.parse::<i64>() {
+                entry.date = utc_date(time);
+            }
+        } else if let Some(rest) = line.strip_prefix("summary ") {
+            entry.summary = rest.to_string();
+        }
+    }
+    blamed
+}
+
+/// `YYYY-MM-DD` for a Unix timestamp (days-to-civil, proleptic Gregorian).
+fn utc_date(secs: i64) -> String {
+    let days = secs.div_euclid(86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// A scratch checkout of one commit outside the repo, removed on drop: a
+/// detached `git worktree`, or a jj workspace in repos without `.git`.
+#[derive(Debug)]
+pub struct TempWorktree {
+    repo_path: PathBuf,
+    pub path: PathBuf,
+    /// jj workspace name; `None` for a git worktree
+    jj_workspace: Option<String>,
+}
+
+impl TempWorktree {
+    /// Check out `commit` of the repo at `repo_path` into a new directory
+    /// under the system temp dir.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `repo_path` isn't a repository or the checkout
+    /// fails.
+    pub fn create(repo_path: &Path, commit: &str) -> anyhow::Result<Self> {
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let name = format!("crit-ui-run-{}-{stamp}", std::process::id());
+        let path = std::env::temp_dir().join(&name);
+        let (mut cmd, jj_workspace) = if repo_path.join(".git").exists() {
+            let mut cmd = Command::new("git");
+            cmd.args(["worktree", "add", "--detach"])
+                .arg(&path)
+                .arg(commit);
+            (cmd, None)
+        } else if detect_vcs(repo_path) == Some(VcsType::Jj) {
+            let mut cmd = Command::new("jj");
+            cmd.args(["workspace", "add", "--name", &name, "--revision", commit])
+                .arg(&path);
+            (cmd, Some(name))
+        } else {
+            bail!("Not a repository: {}", repo_path.display());
+        };
+        let output = cmd
+            .current_dir(repo_path)
+            .output()
+            .context("Failed to create worktree")?;
+        if !output.status.success() {
+            bail!(
+                "Failed to check out {commit}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(Self {
+            repo_path: repo_path.to_path_buf(),
+            path,
+            jj_workspace,
+        })
+    }
+}
+
+impl Drop for TempWorktree {
+    fn drop(&mut self) {
+        let mut cmd = if let Some(name) = &self.jj_workspace {
+            let mut cmd = Command::new("jj");
+            cmd.args(["workspace", "forget", name]);
+            cmd
+        } else {
+            let mut cmd = Command::new("git");
+            cmd.args(["worktree", "remove", "--force"]).arg(&self.path);
+            cmd
+        };
+        let _ = cmd.current_dir(&self.repo_path).output();
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_git_blame() {
+        let out = "4f1c2b9a0d3e5f6a7b8c9d0e1f2a3b4c5d6e7f80 12 12 1\n\
+                   author Ada Lovelace\n\
+                   author-mail <ada@example.com>\n\
+                   author-time 1719835200\n\
+                   author-tz +0000\n\
+                   summary Fix pool sizing\n\
+                   filename src/db/pool.rs\n\
+                   \tlet size = 4;\n";
+        let ada = BlameLine {
+            commit: "4f1c2b9a".to_string(),
+            author: "Ada Lovelace".to_string(),
+            date: "2024-07-01".to_string(),
+            summary: "Fix pool sizing".to_string(),
+        };
+        assert_eq!(parse_git_blame(out), vec![ada.clone()]);
+
+        // Later lines of a commit only repeat its hash
+        let file = format!(
+            "{out}\
+             0d3e5f6a7b8c9d0e1f2a3b4c5d6e7f804f1c2b9a 3 13 1\n\
+             author Grace Hopper\n\
+             author-time 0\n\
+             summary Add pool\n\
+             filename src/db/pool.rs\n\
+             \tlet max = 8;\n\
+             4f1c2b9a0d3e5f6a7b8c9d0e1f2a3b4c5d6e7f80 13 14\n\
+             filename src/db/pool.rs\n\
+             \tlet min = 1;\n"
+        );
+        let blamed = parse_git_blame(&file);
+        assert_eq!(blamed.len(), 3);
+        assert_eq!(blamed[1].author, "Grace Hopper");
+        assert_eq!(blamed[1].date, "1970-01-01");
+        assert_eq!(blamed[2], ada);
+        assert_eq!(utc_date(0), "1970-01-01");
+        assert_eq!(utc_date(951_782_400), "2000-02-29");
+    }
+
+    #[test]
+    fn test_checkout_command() {
+        assert_eq!(
+            checkout_command(VcsType::Jj, "vkzqmlrq", "70c1cb1f"),
+            "jj new vkzqmlrq"
+        );
+        assert_eq!(
+            checkout_command(VcsType::Jj, "", "70c1cb1f"),
+            "jj new 70c1cb1f"
+        );
+        assert_eq!(
+            checkout_command(VcsType::Git, "vkzqmlrq", "70c1cb1f"),
+            "git switch --detach 70c1cb1f"
+        );
+    }
+
+    #[test]
+    fn test_binary_diff_gets_file_sizes() {
+        let repo = std::env::temp_dir().join(format!("crit-ui-binary-test-{}", std::process::id()));
+        std::fs::create_dir_all(&repo).unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .current_dir(&repo)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {args:?}");
+        };
+        git(&["init", "-q"]);
+        std::fs::write(repo.join("logo.png"), [0u8; 2048]).unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-qm", "first"]);
+        std::fs::write(repo.join("logo.png"), [1u8; 3000]).unwrap();
+
+        let diff = get_file_diff(&repo, "logo.png", "HEAD", None).unwrap();
+        let _ = std::fs::remove_dir_all(&repo);
+        let binary = diff.binary.unwrap();
+        assert_eq!((binary.old_size, binary.new_size), (Some(2048), Some(3000)));
+        assert!(diff.hunks.is_empty());
+    }
+
+    #[test]
+    fn test_split_file_diffs() {
+        let text = "diff --git a/src/a.rs b/src/a.rs\n\
+                    --- a/src/a.rs\n+++ b/src/a.rs\n@@ -1 +1 @@\n-a\n+b\n\
+                    diff --git a/old.rs b/new name.rs\n\
+                    rename from old.rs\nrename to new name.rs\n";
+        let files = split_file_diffs(text);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].0, "src/a.rs");
+        assert!(files[0].1.ends_with("+b\n"));
+        assert_eq!(files[1].0, "new name.rs");
+        assert!(files[1].1.starts_with("diff --git a/old.rs"));
+        assert!(split_file_diffs("").is_empty());
+
+        assert!(same_commit("70c1cb1f", "70c1cb1f2e9a"));
+        assert!(!same_commit("70c1cb1f", "9d0e1f2a"));
+        assert!(!same_commit("", "9d0e1f2a"));
+    }
+
     #[test]
     fn test_detect_vcs_none() {
         let temp = std::env::temp_dir();
@@ -207,4 +669,15 @@ mod tests {
         // This test just verifies the function doesn't panic
         let _ = detect_vcs(&temp);
     }
+
+    #[test]
+    fn test_read_window_stops_at_its_end() {
+        let text = "one\ntwo\r\nthree\nfour\n";
+        assert_eq!(read_window(text.as_bytes(), &(2..=3)), ["two\r", "three"]);
+        assert_eq!(read_window(text.as_bytes(), &(1..=usize::MAX)).len(), 4);
+        // A window past the end is empty, one running past it is cut short
+        assert!(read_window(text.as_bytes(), &(9..=12)).is_empty());
+        assert_eq!(read_window(text.as_bytes(), &(4..=12)), ["four"]);
+        assert_eq!(read_window(&b"no newline"[..], &(1..=1)), ["no newline"]);
+    }
 }
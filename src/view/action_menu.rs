@@ -0,0 +1,81 @@
+//! Context action menu (`.`) anchored at the diff cursor.
+//!
+//! A small borderless panel in the command palette's style: one row per
+//! action, label on the left and its key dim on the right. It opens below
+//! the cursor row, or above it when there isn't room.
+
+use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Style};
+
+use crate::model::{Focus, Model};
+use crate::text::display_width;
+use crate::view::components::{draw_text_truncated, Rect};
+
+/// Columns between the stream's left edge and the menu.
+const MENU_INDENT: u32 = 6;
+/// Padding left of the label and right of the key.
+const PAD: u32 = 2;
+/// Minimum space between a label and its key.
+const KEY_GAP: u32 = 4;
+
+pub(super) fn view(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
+    if model.focus != Focus::ActionMenu || model.action_menu.is_empty() {
+        return;
+    }
+    let theme = &model.theme;
+
+    let label_width = model
+        .action_menu
+        .iter()
+        .map(|action| display_width(action.label()) as u32)
+        .max()
+        .unwrap_or(0);
+    let width = (PAD + label_width + KEY_GAP + 1 + PAD).min(area.width.saturating_sub(MENU_INDENT));
+    // Blank row above and below the items
+    let height = (model.action_menu.len() as u32 + 2).min(area.height);
+    if width < PAD * 2 + 2 || height < 3 {
+        return;
+    }
+
+    let cursor_row = model.diff_cursor.saturating_sub(model.diff_scroll) as u32;
+    let below = area.y + cursor_row + 1;
+    let y = if below + height <= area.y + area.height {
+        below
+    } else {
+        (area.y + cursor_row).saturating_sub(height).max(area.y)
+    };
+    let x = area.x + MENU_INDENT;
+
+    buffer_fill_rect(buffer, x, y, width, height, theme.panel_bg);
+    for (index, action) in model
+        .action_menu
+        .iter()
+        .take(height as usize - 2)
+        .enumerate()
+    {
+        let row_y = y + 1 + index as u32;
+        let selected = index == model.action_menu_selection;
+        let (bg, fg) = if selected {
+            (theme.selection_bg, theme.selection_fg)
+        } else {
+            (theme.panel_bg, theme.foreground)
+        };
+        buffer_fill_rect(buffer, x, row_y, width, 1, bg);
+
+        let key_x = x + width - PAD - 1;
+        let label_width = key_x.saturating_sub(x + PAD + 1);
+        draw_text_truncated(
+            buffer,
+            x + PAD,
+            row_y,
+            action.label(),
+            label_width,
+            Style::fg(fg),
+        );
+        let key_style = if selected {
+            Style::fg(fg)
+        } else {
+            theme.style_muted()
+        };
+        buffer_draw_text(buffer, key_x, row_y, &action.key().to_string(), key_style);
+    }
+}
@@ -0,0 +1,93 @@
+//! `review_command` output overlay (`!`).
+//!
+//! A near-full-screen modal in the command palette's style: the command and
+//! commit bold on the left, the run's status badge and "esc" on the right,
+//! then the output, following new lines until the user scrolls up.
+
+use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Style};
+
+use crate::i18n::{tr, Text};
+use crate::layout::command_output_rows;
+use crate::model::{Focus, Model};
+use crate::review_command::RunStatus;
+use crate::text::display_width;
+use crate::view::components::{dim_rect, draw_text_truncated, run_status_badge, Rect};
+
+/// Columns between the screen edge and the modal.
+const H_MARGIN: u32 = 4;
+/// Rows between the screen edge and the modal.
+const V_MARGIN: u32 = 2;
+/// Padding inside the modal, left and right.
+const PAD: u32 = 2;
+
+pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
+    if model.focus != Focus::CommandOutput {
+        return;
+    }
+    let Some(run) = model.current_command_run() else {
+        return;
+    };
+    let theme = &model.theme;
+    let screen = Rect::from_size(model.width, model.height);
+    dim_rect(buffer, screen, 0.35);
+
+    let width = screen.width.saturating_sub(H_MARGIN * 2);
+    let height = screen.height.saturating_sub(V_MARGIN * 2);
+    if width < PAD * 2 + 10 || height < 5 {
+        return;
+    }
+    let (x, mut y) = (H_MARGIN, V_MARGIN);
+    buffer_fill_rect(buffer, x, y, width, height, theme.panel_bg);
+    let text_x = x + PAD;
+    let text_width = width - PAD * 2;
+    y += 1;
+
+    // Title row: command @ commit, then badge and "esc" on the right
+    let esc_label = "esc";
+    let (badge, badge_color) = run_status_badge(theme, run);
+    let badge = format!(" {badge} ");
+    let right_width = display_width(&badge) as u32 + 2 + esc_label.len() as u32;
+    let commit: String = run.commit.chars().take(12).collect();
+    draw_text_truncated(
+        buffer,
+        text_x,
+        y,
+        &format!("{} @ {commit}", run.command),
+        text_width.saturating_sub(right_width + 2),
+        theme.style_foreground().with_bold(),
+    );
+    let esc_x = text_x + text_width - esc_label.len() as u32;
+    buffer_draw_text(buffer, esc_x, y, esc_label, theme.style_muted());
+    let badge_x = esc_x.saturating_sub(2 + display_width(&badge) as u32);
+    buffer_draw_text(
+        buffer,
+        badge_x,
+        y,
+        &badge,
+        Style::fg(theme.panel_bg).with_bg(badge_color),
+    );
+    y += 2;
+
+    let rows = command_output_rows(model.height);
+    if run.output.is_empty() {
+        let (text, style) = match &run.status {
+            RunStatus::Running => return,
+            RunStatus::Error(message) => (message.as_str(), Style::fg(theme.error)),
+            _ => (tr(Text::RunNoOutput), theme.style_muted()),
+        };
+        draw_text_truncated(buffer, text_x, y, text, text_width, style);
+        return;
+    }
+    let first = run.first_visible(rows);
+    for line in run.output.iter().skip(first).take(rows) {
+        draw_text_truncated(
+            buffer,
+            text_x,
+            y,
+            line,
+            text_width,
+            theme.style_foreground(),
+        );
+        y += 1;
+    }
+}
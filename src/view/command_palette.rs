@@ -7,15 +7,19 @@
 //! - Search field with placeholder
 //! - Categorized, selectable list items with bullet indicator
 //!
-//! Supports two modes via `PaletteMode`:
-//! - Commands: shows categorized command list
+//! Supports four modes via `PaletteMode`:
+//! - Commands: shows categorized command list, recently run ones first
 //! - Themes: shows flat theme name list with current theme highlighted
+//! - Comments: shows the review's comments, filtered by id, author or body
+//! - Argument: prompts for the value of a command that takes one
 
 use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Style};
 
 use crate::{
-    command::CommandSpec,
-    model::{Focus, Model, PaletteMode},
+    command::{argument_message, CommandId, CommandSpec, MAX_CONTEXT_LINES},
+    glyphs::glyphs,
+    i18n::{tr, tr_fmt, Text},
+    model::{Focus, Model, PaletteMode, ReviewSort},
     theme,
     view::components::{dim_rect, draw_text_truncated, Rect},
 };
@@ -48,6 +52,8 @@ pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
     match model.command_palette_mode {
         PaletteMode::Commands => render_commands(model, buffer, screen),
         PaletteMode::Themes => render_themes(model, buffer, screen),
+        PaletteMode::Comments => render_comments(model, buffer, screen),
+        PaletteMode::Argument(id) => render_argument(model, buffer, screen, id),
     }
 }
 
@@ -88,7 +94,7 @@ fn render_commands(model: &Model, buffer: &mut OptimizedBuffer, screen: Rect) {
         buffer,
         text_x,
         y,
-        "Commands",
+        tr(Text::PaletteCommands),
         model.theme.style_foreground().with_bold(),
     );
     let esc_x = esc_right.saturating_sub(esc_label.len() as u32);
@@ -99,7 +105,14 @@ fn render_commands(model: &Model, buffer: &mut OptimizedBuffer, screen: Rect) {
     y += 1;
 
     // --- Search field ---
-    render_search_field(model, buffer, text_x, y, text_width);
+    render_search_field(
+        model,
+        buffer,
+        text_x,
+        y,
+        text_width,
+        tr(Text::PaletteSearch),
+    );
     y += 1;
 
     // --- 2 blank rows ---
@@ -134,10 +147,39 @@ fn render_commands(model: &Model, buffer: &mut OptimizedBuffer, screen: Rect) {
 }
 
 fn render_themes(model: &Model, buffer: &mut OptimizedBuffer, screen: Rect) {
+    let items: Vec<(String, bool)> = filtered_theme_names(&model.command_palette_input)
+        .into_iter()
+        .map(|name| (name.to_string(), name == model.theme.name))
+        .collect();
+    render_flat_list(model, buffer, screen, tr(Text::PaletteThemes), &items);
+}
+
+fn render_comments(model: &Model, buffer: &mut OptimizedBuffer, screen: Rect) {
+    let items: Vec<(String, bool)> = model
+        .find_comments(&model.command_palette_input)
+        .into_iter()
+        .map(|c| {
+            let first_line = c.body.lines().next().unwrap_or("");
+            (
+                format!("{}  @{}: {first_line}", c.comment_id, c.author),
+                false,
+            )
+        })
+        .collect();
+    render_flat_list(model, buffer, screen, tr(Text::PaletteComments), &items);
+}
+
+/// Render a modal with a flat, selectable list of `(label, is_current)` rows.
+fn render_flat_list(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    screen: Rect,
+    title: &str,
+    items: &[(String, bool)],
+) {
     let modal_width = 60u32.min(screen.width.saturating_sub(4));
 
-    let theme_names = filtered_theme_names(&model.command_palette_input);
-    let list_height = theme_names.len() as u32;
+    let list_height = items.len() as u32;
     // Vertical: 1 blank + title + 1 blank + search + 2 blank + rows + 2 blank
     let modal_height = (1 + 1 + 1 + 1 + 2 + list_height + 2).min(screen.height.saturating_sub(2));
     let modal_x = (screen.width.saturating_sub(modal_width)) / 2;
@@ -167,7 +209,7 @@ fn render_themes(model: &Model, buffer: &mut OptimizedBuffer, screen: Rect) {
         buffer,
         text_x,
         y,
-        "Themes",
+        title,
         model.theme.style_foreground().with_bold(),
     );
     let esc_x = esc_right.saturating_sub(esc_label.len() as u32);
@@ -178,45 +220,130 @@ fn render_themes(model: &Model, buffer: &mut OptimizedBuffer, screen: Rect) {
     y += 1;
 
     // --- Search field ---
-    render_search_field(model, buffer, text_x, y, text_width);
+    render_search_field(
+        model,
+        buffer,
+        text_x,
+        y,
+        text_width,
+        tr(Text::PaletteSearch),
+    );
     y += 1;
 
     // --- 2 blank rows ---
     y += 2;
 
-    // --- Theme list ---
+    // --- List ---
     let list_max = modal_y + modal_height - 2;
-    for (idx, name) in theme_names.iter().enumerate() {
+    for (idx, (label, is_current)) in items.iter().enumerate() {
         if y >= list_max {
             break;
         }
         let selected = idx == model.command_palette_selection;
-        let is_current = *name == model.theme.name;
-        render_theme_row(
+        render_list_row(
             buffer,
             &ModalLayout {
                 x: modal_x,
                 width: modal_width,
             },
             y,
-            name,
+            label,
             selected,
-            is_current,
+            *is_current,
             model,
         );
         y += 1;
     }
 }
 
+/// Value prompt for an argument command. The hint under the input turns the
+/// error color while the input isn't a valid value.
+fn render_argument(model: &Model, buffer: &mut OptimizedBuffer, screen: Rect, id: CommandId) {
+    let (title, hint) = match id {
+        CommandId::GoToLine => (
+            tr(Text::CmdGoToLine),
+            model
+                .files_with_threads()
+                .get(model.file_index)
+                .map_or_else(
+                    || tr(Text::CmdGoToLineDesc).to_string(),
+                    |file| tr_fmt(Text::PromptGoToLine, &[&file.path]),
+                ),
+        ),
+        CommandId::SortReviews => (
+            tr(Text::CmdSortReviews),
+            tr_fmt(
+                Text::PromptReviewSort,
+                &[
+                    &ReviewSort::ALL.map(ReviewSort::name).join("/"),
+                    &model.review_sort.name(),
+                ],
+            ),
+        ),
+        _ => (
+            tr(Text::CmdSetContextRadius),
+            tr_fmt(
+                Text::PromptContextRadius,
+                &[&MAX_CONTEXT_LINES, &model.context_lines()],
+            ),
+        ),
+    };
+    let input = &model.command_palette_input;
+    let invalid = !input.trim().is_empty() && argument_message(id, input).is_none();
+
+    let modal_width = 60u32.min(screen.width.saturating_sub(4));
+    // Vertical: 1 blank + title + 1 blank + input + 1 blank + hint + 1 blank
+    let modal_height = 7u32.min(screen.height.saturating_sub(2));
+    let modal_x = (screen.width.saturating_sub(modal_width)) / 2;
+    let modal_y = screen.height / 4;
+
+    buffer_fill_rect(
+        buffer,
+        modal_x,
+        modal_y,
+        modal_width,
+        modal_height,
+        model.theme.panel_bg,
+    );
+
+    let text_x = modal_x + TEXT_INDENT;
+    let text_width = modal_width.saturating_sub(TEXT_INDENT + OUTER_PAD);
+    let esc_label = "esc";
+    let esc_right = modal_x + modal_width - OUTER_PAD - TRAIL_PAD;
+
+    let mut y = modal_y + 1;
+    buffer_draw_text(
+        buffer,
+        text_x,
+        y,
+        title,
+        model.theme.style_foreground().with_bold(),
+    );
+    let esc_x = esc_right.saturating_sub(esc_label.len() as u32);
+    buffer_draw_text(buffer, esc_x, y, esc_label, model.theme.style_muted());
+    y += 2;
+
+    render_search_field(model, buffer, text_x, y, text_width, "");
+    y += 2;
+
+    let hint_style = if invalid {
+        Style::fg(model.theme.error)
+    } else {
+        model.theme.style_muted()
+    };
+    draw_text_truncated(buffer, text_x, y, &hint, text_width, hint_style);
+}
+
 fn render_search_field(
     model: &Model,
     buffer: &mut OptimizedBuffer,
     text_x: u32,
     y: u32,
     text_width: u32,
+    placeholder: &str,
 ) {
-    if model.command_palette_input.is_empty() {
-        buffer_draw_text(buffer, text_x, y, "Search", model.theme.style_muted());
+    if model.command_palette_input.is_empty() && !placeholder.is_empty() {
+        buffer_draw_text(buffer, text_x, y, placeholder, model.theme.style_muted());
     } else {
         let input_text = format!("{}\u{2588}", model.command_palette_input);
         draw_text_truncated(
@@ -253,7 +380,7 @@ fn render_item_row(
 
     // Bullet
     let bullet_x = highlight_x + INNER_PAD;
-    let bullet = if cmd.active { "●" } else { " " };
+    let bullet = if cmd.active { glyphs().bullet } else { " " };
     buffer_draw_text(buffer, bullet_x, y, bullet, Style::fg(fg));
 
     // Content area: name left, shortcut right
@@ -277,14 +404,14 @@ fn render_item_row(
     }
 }
 
-/// Render a single theme item row.
-/// Uses bullet (●) if this is the currently active theme.
 struct ModalLayout {
     x: u32,
     width: u32,
 }
 
-fn render_theme_row(
+/// Render a single flat-list row.
+/// Uses bullet (●) if this is the current item (e.g. the active theme).
+fn render_list_row(
     buffer: &mut OptimizedBuffer,
     layout: &ModalLayout,
     y: u32,
@@ -303,12 +430,12 @@ fn render_theme_row(
     };
     buffer_fill_rect(buffer, highlight_x, y, highlight_width, 1, bg);
 
-    // Bullet: show ● for current theme
+    // Bullet: show ● for the current item
     let bullet_x = highlight_x + INNER_PAD;
-    let bullet = if is_current { "●" } else { " " };
+    let bullet = if is_current { glyphs().bullet } else { " " };
     buffer_draw_text(buffer, bullet_x, y, bullet, Style::fg(fg));
 
-    // Theme name
+    // Label
     let name_x = bullet_x + BULLET_W + BULLET_GAP;
     let content_end = highlight_x + highlight_width - TRAIL_PAD;
     let content_width = content_end.saturating_sub(name_x);
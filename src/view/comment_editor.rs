@@ -2,12 +2,16 @@
 //!
 //! Renders a bottom-pinned modal centered on the diff pane:
 //! - Dimmed background
+//! - Target notice (if any) and a short excerpt of the code being commented on
 //! - Text area with existing comments context
 //! - Bottom bar with title (left) and hotkeys (right)
 
 use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Style};
 
-use crate::model::{Focus, InlineEditor, Model};
+use crate::db::{thread_location, Verdict, REVIEW_THREAD_PATH};
+use crate::i18n::{tr, tr_fmt, Text};
+use crate::model::{Focus, InlineEditor, MenuAction, Model};
+use crate::suggestion::target_lines;
 use crate::theme::Theme;
 use crate::view::components::{dim_rect, draw_help_bar_ext, draw_text_truncated, HotkeyHint, Rect};
 
@@ -31,7 +35,7 @@ pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
 
     // Compute diff pane region for centering
     let sidebar_w = if model.sidebar_visible {
-        u32::from(model.layout_mode.sidebar_width())
+        u32::from(model.sidebar_width())
     } else {
         0
     };
@@ -55,6 +59,9 @@ pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
 
     let mut y = panel.y + 1;
 
+    // --- Target warning and code excerpt ---
+    y = render_target(buffer, &model.theme, editor, content_x, content_width, y);
+
     // --- Existing comments context (dimmed) ---
     y = render_existing_comments(
         buffer,
@@ -82,10 +89,21 @@ pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
     // --- Bottom bar: title left + hotkeys right ---
     let title = build_title(editor);
     let help_area = Rect::new(panel.x, hotkey_row, panel.width, 1);
-    let hints = [
-        HotkeyHint::new("Submit", "ctrl+s"),
-        HotkeyHint::new("Cancel", "esc"),
-    ];
+    let request = &editor.request;
+    let takes_suggestion = editor.is_comment()
+        && target_lines(
+            &request.file_path,
+            request.start_line,
+            request.end_line,
+            Some(request.side),
+        )
+        .is_some();
+    let mut hints = Vec::new();
+    if takes_suggestion {
+        hints.push(HotkeyHint::new(tr(Text::HintSuggest), "ctrl+g"));
+    }
+    hints.push(HotkeyHint::new(tr(Text::HintSubmit), "ctrl+s"));
+    hints.push(HotkeyHint::new(tr(Text::HintCancel), "esc"));
     draw_help_bar_ext(
         buffer,
         help_area,
@@ -97,19 +115,26 @@ pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
 }
 
 fn build_title(editor: &InlineEditor) -> String {
-    let line_range = match editor.request.end_line {
-        Some(end) if end != editor.request.start_line => {
-            format!(
-                "{}:{}-{}",
-                editor.request.file_path, editor.request.start_line, end
-            )
-        }
-        _ => format!("{}:{}", editor.request.file_path, editor.request.start_line),
+    if let Some(verdict) = editor.verdict {
+        let label = match verdict {
+            Verdict::Approve => MenuAction::Approve.label(),
+            Verdict::RequestChanges => MenuAction::RequestChanges.label(),
+        };
+        return tr_fmt(Text::VerdictSummaryOn, &[&label]);
+    }
+    if editor.abandon {
+        return tr(Text::AbandonReasonTitle).to_string();
+    }
+    let request = &editor.request;
+    let line_range = if request.file_path == REVIEW_THREAD_PATH {
+        tr(Text::ReviewThreadLocation).to_string()
+    } else {
+        thread_location(&request.file_path, request.start_line, request.end_line)
     };
     if editor.request.thread_id.is_some() {
-        format!("Reply on {line_range}")
+        tr_fmt(Text::ReplyOn, &[&line_range])
     } else {
-        format!("Comment on {line_range}")
+        tr_fmt(Text::CommentOn, &[&line_range])
     }
 }
 
@@ -134,9 +159,10 @@ fn compute_panel(
     } else {
         0
     };
+    let target_rows = target_rows(editor);
     let text_area_height = 8u32;
-    // 1 top padding + context + text + 1 gap + 1 hotkey row + 1 bottom padding
-    let ideal_height = 1 + context_rows + text_area_height + 1 + 1 + 1;
+    // 1 top padding + target + context + text + 1 gap + 1 hotkey row + 1 bottom padding
+    let ideal_height = 1 + target_rows + context_rows + text_area_height + 1 + 1 + 1;
     let panel_height = ideal_height
         .clamp(MIN_HEIGHT, screen.height.saturating_sub(2))
         .min(screen.height);
@@ -146,6 +172,72 @@ fn compute_panel(
     Rect::new(panel_x, panel_y, panel_width, panel_height)
 }
 
+/// Rows used by `render_target`: notice, excerpt lines, the "more lines"
+/// row, blank separator.
+fn target_rows(editor: &InlineEditor) -> u32 {
+    let request = &editor.request;
+    let rows = u32::from(request.notice.is_some())
+        + request.excerpt.len() as u32
+        + u32::from(request.excerpt_hidden_lines() > 0);
+    if rows > 0 {
+        rows + 1
+    } else {
+        0
+    }
+}
+
+fn render_target(
+    buffer: &mut OptimizedBuffer,
+    theme: &Theme,
+    editor: &InlineEditor,
+    content_x: u32,
+    content_width: u32,
+    mut y: u32,
+) -> u32 {
+    if target_rows(editor) == 0 {
+        return y;
+    }
+    if let Some(notice) = &editor.request.notice {
+        let style = Style::fg(theme.warning).with_bg(theme.panel_bg);
+        draw_text_truncated(buffer, content_x, y, notice, content_width, style);
+        y += 1;
+    }
+    for (line_num, line) in (editor.request.start_line..).zip(&editor.request.excerpt) {
+        let gutter = format!("{line_num:>5} │ ");
+        let gutter_width = gutter.chars().count() as u32;
+        buffer_draw_text(
+            buffer,
+            content_x,
+            y,
+            &gutter,
+            theme.diff.style_line_number(theme.panel_bg),
+        );
+        draw_text_truncated(
+            buffer,
+            content_x + gutter_width,
+            y,
+            line,
+            content_width.saturating_sub(gutter_width),
+            theme.style_foreground().with_bg(theme.panel_bg),
+        );
+        y += 1;
+    }
+    let hidden = editor.request.excerpt_hidden_lines();
+    if hidden > 0 {
+        let more = tr_fmt(Text::ExcerptMoreLines, &[&hidden]);
+        draw_text_truncated(
+            buffer,
+            content_x + 8,
+            y,
+            &more,
+            content_width.saturating_sub(8),
+            theme.style_muted().with_bg(theme.panel_bg),
+        );
+        y += 1;
+    }
+    y + 1 // blank separator
+}
+
 fn render_existing_comments(
     buffer: &mut OptimizedBuffer,
     theme: &Theme,
@@ -0,0 +1,243 @@
+//! Comparison screen: the files two reviews touch, and a diff-of-diffs of
+//! one file.
+
+use crate::compare::{ComparedFile, Comparison, DeltaKind, Presence};
+use crate::i18n::{tr, tr_fmt, Text};
+use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Style};
+use crate::text::{display_width, truncate_path};
+
+use super::components::{
+    draw_block, draw_help_bar_ext, draw_text_truncated, BlockLine, HotkeyHint, Rect,
+};
+use crate::model::Model;
+
+/// Status line plus a blank line
+const STATUS_HEIGHT: u32 = 2;
+/// Width of each side's "+12 -3" column
+const COUNT_WIDTH: u32 = 12;
+
+pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
+    let theme = &model.theme;
+    let area = Rect::from_size(model.width, model.height);
+    let header_height = crate::layout::screen_header_height();
+    let Some(comparison) = &model.comparison else {
+        return;
+    };
+
+    let title = tr_fmt(
+        Text::CompareTitle,
+        &[&comparison.left_id, &comparison.right_id],
+    );
+    draw_block(
+        buffer,
+        Rect::new(area.x, area.y, area.width, header_height),
+        theme,
+        theme.panel_bg,
+        &[BlockLine::new(
+            &title,
+            Style::fg(theme.foreground).with_bold(),
+        )],
+    );
+
+    let status_y = area.y + header_height;
+    let list_y = status_y + STATUS_HEIGHT;
+    let list_height = area
+        .height
+        .saturating_sub(header_height + STATUS_HEIGHT + 2); // 2 for help bar
+    let list_area = Rect::new(area.x, list_y, area.width, list_height);
+
+    let status = status_text(comparison);
+    let status_style = if comparison.error.is_some() {
+        Style::fg(theme.error)
+    } else {
+        theme.style_muted()
+    };
+    draw_text_truncated(
+        buffer,
+        area.x + 5,
+        status_y,
+        &status,
+        area.width.saturating_sub(10),
+        status_style,
+    );
+
+    let hints: &[HotkeyHint] = if let Some(delta) = &comparison.delta {
+        draw_delta(model, buffer, list_area, comparison, delta);
+        &[
+            HotkeyHint::new(tr(Text::HintScroll), "j/k"),
+            HotkeyHint::new(tr(Text::HintBack), "Esc"),
+            HotkeyHint::new(tr(Text::HintQuit), "q"),
+        ]
+    } else {
+        let start = comparison
+            .first_visible((list_height as usize).max(1))
+            .min(comparison.files.len());
+        let end = (start + list_height as usize).min(comparison.files.len());
+        for (row, file) in comparison.files[start..end].iter().enumerate() {
+            let y = list_area.y + row as u32;
+            draw_file_row(
+                model,
+                buffer,
+                list_area,
+                y,
+                comparison,
+                file,
+                start + row == comparison.selection,
+            );
+        }
+        &[
+            HotkeyHint::new(tr(Text::HintNavigate), "j/k"),
+            HotkeyHint::new(tr(Text::HintOpen), "Enter"),
+            HotkeyHint::new(tr(Text::HintBack), "Esc"),
+            HotkeyHint::new(tr(Text::HintQuit), "q"),
+        ]
+    };
+    draw_help_bar_ext(buffer, area, theme, hints, theme.background, "");
+}
+
+fn status_text(comparison: &Comparison) -> String {
+    if let Some(error) = &comparison.error {
+        return error.clone();
+    }
+    if let Some(review_id) = comparison.next_to_load() {
+        return tr_fmt(Text::CompareLoading, &[&review_id]);
+    }
+    if comparison.delta.is_some() {
+        let path = comparison.selected().map_or("", |f| f.path.as_str());
+        return tr_fmt(
+            Text::CompareDeltaSummary,
+            &[&path, &comparison.left_id, &comparison.right_id],
+        );
+    }
+    let both = comparison
+        .files
+        .iter()
+        .filter(|f| f.presence == Presence::Both)
+        .count();
+    let same = comparison.files.iter().filter(|f| f.same).count();
+    tr_fmt(
+        Text::CompareSummary,
+        &[&comparison.files.len(), &both, &same],
+    )
+}
+
+/// `= path … +3 -1   +3 -1`: a marker (`=` same change, `~` differs, `<`/`>`
+/// only on the left/right review), the path, then each side's counts.
+fn draw_file_row(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    area: Rect,
+    y: u32,
+    comparison: &Comparison,
+    file: &ComparedFile,
+    selected: bool,
+) {
+    let theme = &model.theme;
+    let bg = if selected {
+        theme.selection_bg
+    } else {
+        theme.background
+    };
+    let fg = |color| Style::fg(if selected { theme.selection_fg } else { color }).with_bg(bg);
+
+    let margin: u32 = 2;
+    let item_x = area.x + margin;
+    let item_width = area.width.saturating_sub(margin * 2);
+    buffer_fill_rect(buffer, item_x, y, item_width, 1, bg);
+
+    let (marker, marker_color) = match file.presence {
+        Presence::Both if file.same => ("=", theme.success),
+        Presence::Both => ("~", theme.warning),
+        Presence::LeftOnly => ("<", theme.muted),
+        Presence::RightOnly => (">", theme.muted),
+    };
+    let left = item_x + 3;
+    buffer_draw_text(buffer, left, y, marker, fg(marker_color));
+
+    let right_edge = item_x + item_width.saturating_sub(2);
+    let right_x = right_edge.saturating_sub(COUNT_WIDTH);
+    let left_x = right_x.saturating_sub(COUNT_WIDTH);
+    let counts = |(added, removed): (usize, usize)| format!("+{added} -{removed}");
+    match file.presence {
+        Presence::Both => {
+            buffer_draw_text(buffer, left_x, y, &counts(file.left), fg(theme.muted));
+            buffer_draw_text(buffer, right_x, y, &counts(file.right), fg(theme.muted));
+        }
+        Presence::LeftOnly => {
+            let text = tr_fmt(Text::CompareOnly, &[&comparison.left_id]);
+            buffer_draw_text(buffer, left_x, y, &text, fg(theme.muted));
+        }
+        Presence::RightOnly => {
+            let text = tr_fmt(Text::CompareOnly, &[&comparison.right_id]);
+            buffer_draw_text(buffer, right_x, y, &text, fg(theme.muted));
+        }
+    }
+
+    let path_x = left + 2;
+    let path_room = left_x.saturating_sub(path_x + 1) as usize;
+    let path = truncate_path(&file.path, path_room);
+    buffer_draw_text(buffer, path_x, y, &path, fg(theme.foreground));
+    if file.same && display_width(&path) + 2 < path_room {
+        let note = tr(Text::CompareSame);
+        let note_x = path_x + display_width(&path) as u32 + 2;
+        draw_text_truncated(
+            buffer,
+            note_x,
+            y,
+            note,
+            left_x.saturating_sub(note_x + 1),
+            fg(theme.muted),
+        );
+    }
+}
+
+/// The diff-of-diffs, `<` for lines only the left review has and `>` for the
+/// right's, like diff(1).
+fn draw_delta(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    area: Rect,
+    comparison: &Comparison,
+    delta: &[crate::compare::DeltaLine],
+) {
+    let theme = &model.theme;
+    let x = area.x + 4;
+    let width = area.width.saturating_sub(8);
+    let mut y = area.y;
+    if delta.iter().all(|line| line.kind == DeltaKind::Both) {
+        buffer_draw_text(
+            buffer,
+            x,
+            y,
+            tr(Text::CompareNoDiff),
+            Style::fg(theme.success),
+        );
+        y += 2;
+    }
+    let rows = (area.y + area.height).saturating_sub(y) as usize;
+    for line in delta.iter().skip(comparison.delta_scroll).take(rows) {
+        let (prefix, style) = match line.kind {
+            DeltaKind::Both => ("  ", theme.style_muted()),
+            DeltaKind::Left => (
+                "< ",
+                Style::fg(theme.diff.removed).with_bg(theme.diff.removed_bg),
+            ),
+            DeltaKind::Right => (
+                "> ",
+                Style::fg(theme.diff.added).with_bg(theme.diff.added_bg),
+            ),
+        };
+        if line.kind != DeltaKind::Both {
+            buffer_fill_rect(buffer, x, y, width, 1, style.bg.unwrap_or(theme.background));
+        }
+        draw_text_truncated(
+            buffer,
+            x,
+            y,
+            &format!("{prefix}{}", line.text),
+            width,
+            style,
+        );
+        y += 1;
+    }
+}
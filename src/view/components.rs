@@ -1,12 +1,18 @@
 //! Reusable UI components
 
 use std::borrow::Cow;
+use std::ops::Range;
 
+use crate::annotations::Severity;
 use crate::render_backend::{
     buffer_dim_cell_rgb, buffer_draw_box, buffer_draw_text, buffer_fill_rect, BoxStyle,
     OptimizedBuffer, Rgba, Style,
 };
 
+use crate::i18n::{tr, tr_fmt, Text};
+use crate::refresh::spinner_at;
+use crate::review_command::{CommandRun, RunStatus};
+use crate::text::{display_width, truncate_end, wrap_text};
 use crate::theme::Theme;
 
 /// A rectangular area for layout
@@ -132,20 +138,47 @@ pub fn draw_text_truncated(
         return;
     }
 
-    let max_chars = max_width as usize;
-    let text = if text.chars().count() > max_chars {
-        if max_chars <= 1 {
-            take_chars(text, max_chars).to_string()
-        } else {
-            format!("{}\u{2026}", take_chars(text, max_chars - 1))
-        }
-    } else {
-        text.to_string()
-    };
-
+    let text = truncate_end(text, max_width as usize);
     buffer_draw_text(buffer, x, y, &text, style);
 }
 
+/// An empty pane's message: `title` centered a third of the way down
+/// `area`, with `hint` (what to do next) wrapped and muted below it.
+pub fn draw_empty_state(
+    buffer: &mut OptimizedBuffer,
+    area: Rect,
+    title: &str,
+    hint: &str,
+    theme: &Theme,
+) {
+    let width = area.width.saturating_sub(4) as usize;
+    if width == 0 || area.height == 0 {
+        return;
+    }
+    let centered = |text: &str| {
+        let text_width = display_width(text).min(width) as u32;
+        area.x + (area.width - text_width) / 2
+    };
+    let bottom = area.y + area.height;
+    let mut y = area.y + area.height / 3;
+    let title = truncate_end(title, width);
+    buffer_draw_text(
+        buffer,
+        centered(&title),
+        y,
+        &title,
+        theme.style_foreground().with_bold(),
+    );
+    y += 2;
+    for line in wrap_text(hint, width) {
+        if y >= bottom {
+            break;
+        }
+        buffer_draw_text(buffer, centered(&line), y, &line, theme.style_muted());
+        y += 1;
+    }
+}
+
 /// Draw a horizontal line
 #[allow(dead_code)]
 pub fn draw_hline(buffer: &mut OptimizedBuffer, x: u32, y: u32, width: u32, color: Rgba) {
@@ -160,6 +193,31 @@ pub fn draw_badge(buffer: &mut OptimizedBuffer, x: u32, y: u32, text: &str, fg:
     buffer_draw_text(buffer, x, y, &badge, Style::fg(fg).with_bg(bg));
 }
 
+/// Badge color for lint/CI annotations of `severity`.
+#[must_use]
+pub const fn severity_color(theme: &Theme, severity: Severity) -> Rgba {
+    match severity {
+        Severity::Error => theme.error,
+        Severity::Warning => theme.warning,
+        Severity::Notice => theme.muted,
+    }
+}
+
+/// Label and color of the `review_command` status badge.
+#[must_use]
+pub fn run_status_badge(theme: &Theme, run: &CommandRun) -> (String, Rgba) {
+    match &run.status {
+        RunStatus::Running => (
+            format!("{} {}", spinner_at(run.started), tr(Text::RunRunning)),
+            theme.muted,
+        ),
+        RunStatus::Succeeded => (tr(Text::RunPassed).to_string(), theme.success),
+        RunStatus::Failed(Some(code)) => (tr_fmt(Text::RunFailed, &[code]), theme.error),
+        RunStatus::Failed(None) => (tr(Text::RunKilled).to_string(), theme.error),
+        RunStatus::Error(_) => (tr(Text::RunError).to_string(), theme.error),
+    }
+}
+
 /// Format a thread count display
 #[must_use]
 pub fn format_thread_count(total: i64, open: i64) -> String {
@@ -172,40 +230,13 @@ pub fn format_thread_count(total: i64, open: i64) -> String {
     }
 }
 
-/// Truncate a path for display, keeping the filename visible
+/// `2025-06-30T12:00:00Z` → `2025-06-30 12:00`; other formats pass through.
 #[must_use]
-pub fn truncate_path(path: &str, max_width: usize) -> String {
-    if path.chars().count() <= max_width {
-        return path.to_string();
-    }
-
-    // Try to keep the filename
-    if let Some(idx) = path.rfind('/') {
-        let filename = &path[idx + 1..];
-        let filename_chars = filename.chars().count();
-        if filename_chars + 2 <= max_width {
-            // "\u{2026}/" + filename
-            let available = max_width - filename_chars - 2;
-            let prefix = take_chars(&path[..idx], available);
-            return format!("{prefix}\u{2026}/{filename}");
-        }
+pub fn short_timestamp(ts: &str) -> String {
+    match (ts.get(..10), ts.get(11..16)) {
+        (Some(date), Some(time)) if ts.as_bytes()[10] == b'T' => format!("{date} {time}"),
+        _ => ts.to_string(),
     }
-
-    // Just truncate from the end
-    let truncated = take_chars(path, max_width.saturating_sub(1));
-    format!("{truncated}…")
-}
-
-fn take_chars(text: &str, max_chars: usize) -> &str {
-    if max_chars == 0 {
-        return "";
-    }
-    for (count, (idx, _)) in text.char_indices().enumerate() {
-        if count == max_chars {
-            return &text[..idx];
-        }
-    }
-    text
 }
 
 /// A line of content within a block.
@@ -237,10 +268,10 @@ pub fn draw_block(
     lines: &[BlockLine<'_>],
 ) -> u32 {
     use crate::layout::{
-        BLOCK_LEFT_PAD, BLOCK_MARGIN, BLOCK_PADDING, BLOCK_RIGHT_PAD, BLOCK_SIDE_MARGIN,
+        block_margin, block_padding, BLOCK_LEFT_PAD, BLOCK_RIGHT_PAD, BLOCK_SIDE_MARGIN,
     };
 
-    let total_height = (BLOCK_MARGIN * 2 + BLOCK_PADDING * 2 + lines.len()) as u32;
+    let total_height = (block_margin() * 2 + block_padding() * 2 + lines.len()) as u32;
     if area.height < total_height {
         return 0;
     }
@@ -273,12 +304,12 @@ pub fn draw_block(
     };
 
     // Top margin
-    for _ in 0..BLOCK_MARGIN {
+    for _ in 0..block_margin() {
         draw_margin_line(buffer, y);
         y += 1;
     }
     // Top padding
-    for _ in 0..BLOCK_PADDING {
+    for _ in 0..block_padding() {
         draw_bar_line(buffer, y);
         y += 1;
     }
@@ -300,12 +331,12 @@ pub fn draw_block(
         y += 1;
     }
     // Bottom padding
-    for _ in 0..BLOCK_PADDING {
+    for _ in 0..block_padding() {
         draw_bar_line(buffer, y);
         y += 1;
     }
     // Bottom margin
-    for _ in 0..BLOCK_MARGIN {
+    for _ in 0..block_margin() {
         draw_margin_line(buffer, y);
         y += 1;
     }
@@ -322,6 +353,39 @@ pub fn dim_rect(buffer: &mut OptimizedBuffer, area: Rect, scale: f32) {
     }
 }
 
+/// Mark a pane's title bar as focused or not. Cells in `title` drawn on
+/// `panel_bg` take `chrome_focused` (when `tint` is on) or `chrome_unfocused`,
+/// and a `┃` bar at `bar_x` takes `border_focused` or `border`.
+pub fn draw_pane_chrome(
+    buffer: &mut OptimizedBuffer,
+    title: Rect,
+    bar_x: u32,
+    focused: bool,
+    tint: bool,
+    theme: &Theme,
+) {
+    let bg = if focused && tint {
+        theme.chrome_focused
+    } else {
+        theme.chrome_unfocused
+    };
+    let bar = if focused {
+        theme.border_focused
+    } else {
+        theme.border
+    };
+    for row in title.y..title.y + title.height {
+        for col in title.x..title.x + title.width {
+            if let Some(cell) = buffer.get_mut(col, row) {
+                if cell.bg == theme.panel_bg {
+                    cell.bg = bg;
+                }
+            }
+        }
+        buffer_draw_text(buffer, bar_x, row, "┃", Style::fg(bar).with_bg(bg));
+    }
+}
+
 /// A label + key hint for the help bar.
 pub struct HotkeyHint {
     pub label: Cow<'static, str>,
@@ -339,35 +403,16 @@ impl HotkeyHint {
 
     #[must_use]
     pub fn width(&self) -> usize {
-        self.label.len() + 1 + self.key.len()
+        self.label.chars().count() + 1 + self.key.len()
     }
 }
 
-/// Draw a right-aligned help bar of `[label key]` pairs within `area`.
+/// Draw a right-aligned help bar of `[label key]` pairs within `area`,
+/// with an optional left-aligned label.
 ///
 /// The bar is drawn on the second-to-last row of `area`. The last row
-/// is filled with `theme.background` as a bottom margin.
-pub fn draw_help_bar(
-    buffer: &mut OptimizedBuffer,
-    area: Rect,
-    theme: &Theme,
-    hints: &[HotkeyHint],
-) {
-    draw_help_bar_ext(buffer, area, theme, hints, theme.background, "");
-}
-
-/// Draw a help bar with a custom background color.
-pub fn draw_help_bar_with_bg(
-    buffer: &mut OptimizedBuffer,
-    area: Rect,
-    theme: &Theme,
-    hints: &[HotkeyHint],
-    bg: Rgba,
-) {
-    draw_help_bar_ext(buffer, area, theme, hints, bg, "");
-}
-
-/// Draw a help bar with custom bg and an optional left-aligned label.
+/// is filled with `bg` as a bottom margin. Returns the columns each hint
+/// was drawn across, for clicks.
 pub fn draw_help_bar_ext(
     buffer: &mut OptimizedBuffer,
     area: Rect,
@@ -375,7 +420,7 @@ pub fn draw_help_bar_ext(
     hints: &[HotkeyHint],
     bg: Rgba,
     left_label: &str,
-) {
+) -> Vec<Range<u32>> {
     let y = area.y + area.height.saturating_sub(2);
     let bottom_y = area.y + area.height.saturating_sub(1);
     buffer_fill_rect(buffer, area.x, bottom_y, area.width, 1, bg);
@@ -422,16 +467,20 @@ pub fn draw_help_bar_ext(
     let bright = theme.style_foreground();
 
     let mut x = x_start;
+    let mut spans = Vec::with_capacity(hints.len());
     for (i, hint) in hints.iter().enumerate() {
         if i > 0 {
             buffer_draw_text(buffer, x, y, separator, dim);
             x += sep_len as u32;
         }
+        let start = x;
         buffer_draw_text(buffer, x, y, &hint.label, dim);
-        x += hint.label.len() as u32;
+        x += hint.label.chars().count() as u32;
         buffer_draw_text(buffer, x, y, " ", dim);
         x += 1;
         buffer_draw_text(buffer, x, y, hint.key, bright);
         x += hint.key.len() as u32;
+        spans.push(start..x);
     }
+    spans
 }
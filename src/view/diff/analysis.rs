@@ -1,7 +1,10 @@
 //! Thread-to-diff mapping, change counting, and thread range analysis.
 
-use crate::db::ThreadSummary;
-use crate::diff::{DiffLineKind, ParsedDiff};
+use std::collections::HashMap;
+
+use crate::db::{ThreadSide, ThreadSummary};
+use crate::diff::{DiffHunk, DiffLineKind, ParsedDiff, TextEncoding};
+use crate::i18n::{tr, Text};
 
 use super::{ChangeCounts, ThreadAnchor};
 
@@ -20,57 +23,144 @@ pub fn diff_change_counts(diff: &ParsedDiff) -> ChangeCounts {
     ChangeCounts { added, removed }
 }
 
+/// Short lint labels shown in a file's header: a normalized diff, line
+/// endings, encoding, and a missing final newline.
+pub fn file_lint_badges(entry: &crate::model::FileCacheEntry) -> Vec<&'static str> {
+    let mut encoding = TextEncoding::default();
+    if let Some(diff) = &entry.diff {
+        encoding.merge(diff.encoding);
+    }
+    if let Some(content) = &entry.file_content {
+        encoding.merge(content.encoding);
+    }
+
+    let mut badges = Vec::new();
+    if entry.normalized {
+        badges.push(Text::BadgeNormalized);
+    }
+    badges.extend(encoding.encoding_label());
+    badges.extend(encoding.eol_label());
+    if entry
+        .diff
+        .as_ref()
+        .is_some_and(ParsedDiff::missing_final_newline)
+    {
+        badges.push(Text::BadgeNoEol);
+    }
+    badges.into_iter().map(tr).collect()
+}
+
 /// Map threads to display line indices within the diff
+///
+/// Threads that record a side anchor on that side's line numbers. Legacy
+/// threads without one anchor on new-file line numbers, and only fall back to
+/// the old side when their whole range lies in a single run of removed lines —
+/// a looser old-line fallback anchors threads on unrelated removed lines whose
+/// numbers coincidentally match.
+///
+/// A thread covering exactly a hunk's `@@` range is a hunk thread (see
+/// [`hunk_thread_range`]) and anchors on the hunk header instead.
 #[must_use]
 pub fn map_threads_to_diff(diff: &ParsedDiff, threads: &[&ThreadSummary]) -> Vec<ThreadAnchor> {
     let mut anchors = Vec::new();
 
-    // Build maps from line numbers to display line index
-    // Check both old and new line numbers since threads could reference either
-    let mut new_line_to_display: std::collections::HashMap<u32, usize> =
-        std::collections::HashMap::new();
+    // Build maps from old/new line numbers to display line index, and tag
+    // each removed line with the run of consecutive removals it belongs to.
+    let mut new_line_to_display: HashMap<u32, usize> = HashMap::new();
+    let mut old_line_to_display: HashMap<u32, usize> = HashMap::new();
+    let mut removed_run: HashMap<u32, usize> = HashMap::new();
+    let mut header_display: Vec<usize> = Vec::new();
+    // Hunk of each display line
+    let mut display_hunk: Vec<usize> = Vec::new();
+    let mut run_id = 0usize;
     let mut display_idx = 0;
 
-    for hunk in &diff.hunks {
+    for (hunk_idx, hunk) in diff.hunks.iter().enumerate() {
+        header_display.push(display_idx);
+        display_hunk.push(hunk_idx);
         display_idx += 1; // hunk header
+        run_id += 1;
         for line in &hunk.lines {
+            display_hunk.push(hunk_idx);
             if let Some(new_ln) = line.new_line {
                 new_line_to_display.insert(new_ln, display_idx);
             }
+            if let Some(old_ln) = line.old_line {
+                old_line_to_display.insert(old_ln, display_idx);
+            }
+            if line.kind == DiffLineKind::Removed {
+                if let Some(old_ln) = line.old_line {
+                    removed_run.insert(old_ln, run_id);
+                }
+            } else {
+                run_id += 1;
+            }
             display_idx += 1;
         }
     }
 
-    // Map each thread to its display position
-    // Only anchor on new-file line numbers — old-line fallback causes false
-    // anchoring when a thread's line number coincidentally matches a removed line
-    // in a different commit.
     for thread in threads {
-        let start_line = thread.selection_start as u32;
-        let display_line = new_line_to_display.get(&start_line);
-
-        if let Some(&display_line) = display_line {
-            let line_count = thread
-                .selection_end
-                .map_or(1, |end| (end - thread.selection_start + 1) as usize);
-
-            // Comment block goes after the last line of the range
-            let end_line = thread.selection_end.unwrap_or(thread.selection_start) as u32;
-            let comment_after_line = new_line_to_display
-                .get(&end_line)
-                .copied()
-                .unwrap_or(display_line);
-
-            anchors.push(ThreadAnchor {
-                thread_id: thread.thread_id.clone(),
-                display_line,
-                comment_after_line,
-                line_count,
-                status: thread.status.clone(),
-                comment_count: thread.comment_count,
-                is_expanded: true,
-            });
+        let (start, end) = thread_range(thread);
+        let (start_line, end_line) = (start as u32, end as u32);
+
+        let side = thread.side.unwrap_or(ThreadSide::New);
+        let hunk_anchor = |hunk: usize, side| ThreadAnchor {
+            thread_id: thread.thread_id.clone(),
+            display_line: header_display[hunk],
+            comment_after_line: header_display[hunk],
+            line_count: (end - start + 1) as usize,
+            side,
+            status: thread.status.clone(),
+            comment_count: thread.comment_count,
+            is_expanded: true,
+            hunk: Some(hunk),
+        };
+        let hunk = diff
+            .hunks
+            .iter()
+            .position(|hunk| hunk_thread_range(hunk) == Some((side, start, end)));
+        if let Some(hunk) = hunk {
+            anchors.push(hunk_anchor(hunk, side));
+            continue;
         }
+
+        let side = match thread.side {
+            Some(side) => side,
+            None if new_line_to_display.contains_key(&start_line) => ThreadSide::New,
+            None if in_single_removed_run(start_line, end_line, &removed_run) => ThreadSide::Old,
+            None => continue,
+        };
+        let line_to_display = match side {
+            ThreadSide::Old => &old_line_to_display,
+            ThreadSide::New => &new_line_to_display,
+        };
+        let Some(&display_line) = line_to_display.get(&start_line) else {
+            continue;
+        };
+        // A folded hunk's threads follow its summary line
+        let hunk = display_hunk[display_line];
+        if diff.hunks[hunk].folded {
+            anchors.push(hunk_anchor(hunk, side));
+            continue;
+        }
+
+        // Comment block goes after the last line of the range
+        let comment_after_line = line_to_display
+            .get(&end_line)
+            .copied()
+            .unwrap_or(display_line);
+
+        anchors.push(ThreadAnchor {
+            thread_id: thread.thread_id.clone(),
+            display_line,
+            comment_after_line,
+            line_count: (end - start + 1) as usize,
+            side,
+            status: thread.status.clone(),
+            comment_count: thread.comment_count,
+            is_expanded: true,
+            hunk: None,
+        });
     }
 
     // Sort by display line
@@ -78,17 +168,72 @@ pub fn map_threads_to_diff(diff: &ParsedDiff, threads: &[&ThreadSummary]) -> Vec
     anchors
 }
 
+/// The side and line range a hunk-level thread on `hunk` covers: the new
+/// side's `@@` range, or the old side's when the hunk only removes lines.
+#[must_use]
+pub fn hunk_thread_range(hunk: &DiffHunk) -> Option<(ThreadSide, i64, i64)> {
+    let (side, start, count) = if hunk.new_count > 0 {
+        (ThreadSide::New, hunk.new_start, hunk.new_count)
+    } else {
+        (ThreadSide::Old, hunk.old_start, hunk.old_count)
+    };
+    (count > 0).then(|| {
+        let start = i64::from(start);
+        (side, start, start + i64::from(count) - 1)
+    })
+}
+
+fn in_single_removed_run(start: u32, end: u32, removed_run: &HashMap<u32, usize>) -> bool {
+    removed_run
+        .get(&start)
+        .is_some_and(|run| (start..=end).all(|ln| removed_run.get(&ln) == Some(run)))
+}
+
+/// A thread's selection as an ordered `(start, end)` line pair.
+fn thread_range(thread: &ThreadSummary) -> (i64, i64) {
+    let end = thread.selection_end.unwrap_or(thread.selection_start);
+    (
+        thread.selection_start.min(end),
+        thread.selection_start.max(end),
+    )
+}
+
+/// Line ranges covered by threads in a diff, split by the side they anchor to.
+pub(super) struct ThreadRanges {
+    old: Vec<(i64, i64)>,
+    new: Vec<(i64, i64)>,
+}
+
+impl ThreadRanges {
+    /// Anchored threads use their resolved side; unanchored threads keep
+    /// new-side numbering. Hunk threads are left out, since a bar down the
+    /// whole hunk says nothing.
+    pub(super) fn new(threads: &[&ThreadSummary], anchors: &[ThreadAnchor]) -> Self {
+        let mut ranges = Self {
+            old: Vec::new(),
+            new: Vec::new(),
+        };
+        for thread in threads {
+            let anchor = anchors.iter().find(|a| a.thread_id == thread.thread_id);
+            if anchor.is_some_and(|a| a.hunk.is_some()) {
+                continue;
+            }
+            match anchor.map_or(ThreadSide::New, |a| a.side) {
+                ThreadSide::Old => ranges.old.push(thread_range(thread)),
+                ThreadSide::New => ranges.new.push(thread_range(thread)),
+            }
+        }
+        ranges
+    }
+
+    pub(super) fn contains(&self, old_line: Option<u32>, new_line: Option<u32>) -> bool {
+        line_in_thread_ranges(old_line.map(i64::from), &self.old)
+            || line_in_thread_ranges(new_line.map(i64::from), &self.new)
+    }
+}
+
 pub(super) fn build_thread_ranges(threads: &[&ThreadSummary]) -> Vec<(i64, i64)> {
-    threads
-        .iter()
-        .map(|thread| {
-            let end = thread.selection_end.unwrap_or(thread.selection_start);
-            (
-                thread.selection_start.min(end),
-                thread.selection_start.max(end),
-            )
-        })
-        .collect()
+    threads.iter().map(|thread| thread_range(thread)).collect()
 }
 
 pub(super) fn line_in_thread_ranges(line: Option<i64>, ranges: &[(i64, i64)]) -> bool {
@@ -99,3 +244,96 @@ pub(super) fn line_in_thread_ranges(line: Option<i64>, ranges: &[(i64, i64)]) ->
         .iter()
         .any(|(start, end)| line >= *start && line <= *end)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thread(id: &str, start: i64, end: Option<i64>, side: Option<ThreadSide>) -> ThreadSummary {
+        ThreadSummary {
+            thread_id: id.to_string(),
+            file_path: "a.rs".to_string(),
+            selection_start: start,
+            selection_end: end,
+            side,
+            status: "open".to_string(),
+            comment_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_threads_anchor_on_their_side() {
+        // old 1..=4 -> new 1..=3: lines 2-3 removed, one line added
+        let diff = ParsedDiff::parse(
+            "--- a/a.rs\n+++ b/a.rs\n@@ -1,4 +1,3 @@\n keep\n-gone one\n-gone two\n+added\n tail\n",
+        );
+        let threads = [
+            thread("old", 2, Some(3), Some(ThreadSide::Old)),
+            thread("new", 2, None, Some(ThreadSide::New)),
+            // Legacy: new line 3 exists, so it stays on the new side
+            thread("legacy-new", 3, None, None),
+            // Legacy: no new line 4, but old line 4 is context, not removed
+            thread("legacy-orphan", 4, None, None),
+        ];
+        let refs: Vec<&ThreadSummary> = threads.iter().collect();
+        let anchors = map_threads_to_diff(&diff, &refs);
+        let find = |id: &str| anchors.iter().find(|a| a.thread_id == id);
+
+        let old = find("old").unwrap();
+        assert_eq!(
+            (old.side, old.display_line, old.comment_after_line),
+            (ThreadSide::Old, 2, 3)
+        );
+        let new = find("new").unwrap();
+        assert_eq!((new.side, new.display_line), (ThreadSide::New, 4));
+        assert_eq!(find("legacy-new").unwrap().side, ThreadSide::New);
+        assert!(find("legacy-orphan").is_none());
+
+        let ranges = ThreadRanges::new(&refs, &anchors);
+        assert!(ranges.contains(Some(3), None));
+        assert!(!ranges.contains(Some(4), None));
+    }
+
+    #[test]
+    fn test_legacy_thread_falls_back_to_removed_run() {
+        let diff = ParsedDiff::parse(
+            "--- a/a.rs\n+++ b/a.rs\n@@ -1,6 +1,2 @@\n keep\n-a\n-b\n-c\n-d\n tail\n",
+        );
+        let inside = thread("inside", 3, Some(5), None);
+        // Old line 6 is context, so this range isn't a single removed run
+        let spans_context = thread("spans", 4, Some(6), None);
+        let anchors = map_threads_to_diff(&diff, &[&inside, &spans_context]);
+        assert_eq!(anchors.len(), 1);
+        assert_eq!(anchors[0].thread_id, "inside");
+        assert_eq!(anchors[0].side, ThreadSide::Old);
+    }
+
+    #[test]
+    fn test_hunk_thread_anchors_on_the_header() {
+        let diff = ParsedDiff::parse(
+            "--- a/a.rs\n+++ b/a.rs\n@@ -1,2 +1,2 @@\n keep\n-old\n+new\n@@ -9,2 +9,0 @@\n-x\n-y\n",
+        );
+        assert_eq!(
+            hunk_thread_range(&diff.hunks[1]),
+            Some((ThreadSide::Old, 9, 10))
+        );
+        let threads = [
+            thread("hunk", 1, Some(2), Some(ThreadSide::New)),
+            thread("removed-hunk", 9, Some(10), Some(ThreadSide::Old)),
+            thread("line", 2, None, Some(ThreadSide::New)),
+        ];
+        let refs: Vec<&ThreadSummary> = threads.iter().collect();
+        let anchors = map_threads_to_diff(&diff, &refs);
+        let find = |id: &str| anchors.iter().find(|a| a.thread_id == id).unwrap();
+
+        let hunk = find("hunk");
+        assert_eq!((hunk.hunk, hunk.comment_after_line), (Some(0), 0));
+        let removed = find("removed-hunk");
+        assert_eq!((removed.hunk, removed.comment_after_line), (Some(1), 4));
+        assert_eq!(find("line").hunk, None);
+
+        let ranges = ThreadRanges::new(&refs, &anchors);
+        assert!(ranges.contains(None, Some(2)));
+        assert!(!ranges.contains(None, Some(1)));
+    }
+}
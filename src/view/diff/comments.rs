@@ -1,15 +1,24 @@
 //! Comment block rendering (thread comment bubbles in the diff stream).
 
-use crate::render_backend::{buffer_draw_text, buffer_fill_rect, color_lerp, Style};
+use crate::render_backend::{
+    buffer_draw_text, buffer_fill_rect, color_lerp, OptimizedBuffer, Rgba, Style,
+};
 
-use crate::db::ThreadSummary;
-use crate::layout::BLOCK_PADDING;
-use crate::text::wrap_text;
-use crate::view::components::Rect;
+use crate::db::{thread_location, Comment, ThreadEvent, ThreadEventKind, ThreadSummary};
+use crate::glyphs::glyphs;
+use crate::i18n::{tr, tr_fmt, Text};
+use crate::layout::block_padding;
+use crate::model::FileCacheEntry;
+use crate::search::SearchKind;
+use crate::suggestion::{body_rows, replaced_lines, BodyRow};
+use crate::text::{display_width, truncate_path};
+use crate::theme::Theme;
+use crate::view::components::{short_timestamp, Rect};
 
 use super::helpers::{
     comment_block_area, comment_content_area, draw_plain_line_with_right, PlainLineContent,
 };
+use super::text_util::draw_search_matches;
 use super::StreamCursor;
 
 #[derive(Clone)]
@@ -17,6 +26,12 @@ pub(super) enum CommentLineKind {
     Header,
     Author,
     Body,
+    /// Status change (opened/resolved/reopened), one row each
+    Event,
+    /// Heading of a suggested change, then its removed and added lines
+    SuggestionLabel,
+    Removed,
+    Added,
 }
 
 #[derive(Clone)]
@@ -24,23 +39,62 @@ pub(super) struct CommentLine {
     pub left: String,
     pub right: Option<String>,
     pub kind: CommentLineKind,
+    /// Index of the comment this row belongs to (author and body rows)
+    pub comment: Option<usize>,
+}
+
+/// Status events recorded for a thread, or none if they haven't loaded.
+pub(super) fn events_for<'a>(
+    thread_events: &'a std::collections::HashMap<String, Vec<ThreadEvent>>,
+    thread_id: &str,
+) -> &'a [ThreadEvent] {
+    thread_events.get(thread_id).map_or(&[], Vec::as_slice)
+}
+
+fn event_line(event: &ThreadEvent) -> CommentLine {
+    let (marker, text) = match event.kind {
+        ThreadEventKind::Opened => (glyphs().opened, Text::ThreadEventOpened),
+        ThreadEventKind::Resolved => (glyphs().resolved, Text::ThreadEventResolved),
+        ThreadEventKind::Reopened => (glyphs().reopened, Text::ThreadEventReopened),
+    };
+    let mut left = format!("{marker} {}", tr_fmt(text, &[&event.actor]));
+    if let Some(reason) = event.reason.as_deref().filter(|r| !r.trim().is_empty()) {
+        left.push_str(": ");
+        left.push_str(reason.lines().next().unwrap_or_default());
+    }
+    CommentLine {
+        left,
+        right: Some(short_timestamp(&event.created_at)),
+        kind: CommentLineKind::Event,
+        comment: None,
+    }
 }
 
+/// Rows of a thread's block. A muted thread shows only its header and a
+/// one-line summary; `stream::comment_block_height` must agree. `entry`
+/// supplies the lines a suggestion replaces.
 fn build_comment_lines(
     thread: &ThreadSummary,
-    comments: &[crate::db::Comment],
+    comments: &[Comment],
+    events: &[ThreadEvent],
     content_width: usize,
+    muted: bool,
+    entry: Option<&FileCacheEntry>,
 ) -> Vec<CommentLine> {
     let mut content_lines: Vec<CommentLine> = Vec::new();
 
-    let line_range = thread.selection_end.map_or_else(
-        || format!("{}", thread.selection_start),
-        |end| format!("{}-{}", thread.selection_start, end),
-    );
-    let mut right_text = format!("{}:{}", thread.file_path, line_range);
-    let right_max = content_width.saturating_sub(thread.thread_id.len().saturating_add(1));
-    if right_max > 0 && right_text.len() > right_max {
-        right_text = crate::view::components::truncate_path(&right_text, right_max);
+    let mut right_text = if thread.is_review_thread() {
+        tr(Text::ReviewThreadLocation).to_string()
+    } else {
+        thread_location(
+            &thread.file_path,
+            thread.selection_start,
+            thread.selection_end,
+        )
+    };
+    let right_max = content_width.saturating_sub(display_width(&thread.thread_id) + 1);
+    if right_max > 0 && display_width(&right_text) > right_max {
+        right_text = truncate_path(&right_text, right_max);
     } else if right_max == 0 {
         right_text.clear();
     }
@@ -52,14 +106,38 @@ fn build_comment_lines(
             Some(right_text)
         },
         kind: CommentLineKind::Header,
+        comment: None,
     });
     content_lines.push(CommentLine {
         left: String::new(),
         right: None,
         kind: CommentLineKind::Body,
+        comment: None,
     });
+    if muted {
+        content_lines.push(CommentLine {
+            left: format!(
+                "{} {}",
+                glyphs().muted,
+                tr_fmt(Text::MutedThreadSummary, &[&comments.len()])
+            ),
+            right: None,
+            kind: CommentLineKind::Event,
+            comment: None,
+        });
+        return content_lines;
+    }
 
-    for comment in comments {
+    // Interleave status events by time. An event sharing a comment's
+    // timestamp follows it, except "opened", which starts the thread.
+    let mut events = events.iter().peekable();
+    for (index, comment) in comments.iter().enumerate() {
+        while let Some(event) = events.next_if(|e| {
+            e.created_at < comment.created_at
+                || (e.kind == ThreadEventKind::Opened && e.created_at == comment.created_at)
+        }) {
+            content_lines.push(event_line(event));
+        }
         let left = format!("@{}", comment.author);
         let right_max = content_width.saturating_sub(left.len().saturating_add(1));
         let right = if right_max > 0 {
@@ -75,24 +153,65 @@ fn build_comment_lines(
             left,
             right,
             kind: CommentLineKind::Author,
+            comment: Some(index),
         });
-        let wrapped = wrap_text(&comment.body, content_width);
-        for line in wrapped {
+        for row in body_rows(&comment.body, content_width, replaced_lines(thread)) {
+            let (left, kind) = match row {
+                BodyRow::Text(text) => (text, CommentLineKind::Body),
+                BodyRow::SuggestionLabel => (
+                    tr(Text::SuggestedChange).to_string(),
+                    CommentLineKind::SuggestionLabel,
+                ),
+                BodyRow::Removed(line) => (
+                    format!(
+                        "- {}",
+                        entry
+                            .and_then(|e| e.new_side_line(line))
+                            .unwrap_or_default()
+                    ),
+                    CommentLineKind::Removed,
+                ),
+                BodyRow::Added(text) => (format!("+ {text}"), CommentLineKind::Added),
+            };
             content_lines.push(CommentLine {
-                left: line,
+                left,
                 right: None,
-                kind: CommentLineKind::Body,
+                kind,
+                comment: Some(index),
             });
         }
     }
+    content_lines.extend(events.map(event_line));
 
     content_lines
 }
 
+/// Background of a row's text, and its left and right styles.
+const fn line_styles(kind: &CommentLineKind, line_bg: Rgba, theme: &Theme) -> (Rgba, Style, Style) {
+    let muted = theme.style_muted_on(line_bg);
+    match kind {
+        CommentLineKind::Header | CommentLineKind::Event => (line_bg, muted, muted),
+        CommentLineKind::Author | CommentLineKind::SuggestionLabel => {
+            (line_bg, theme.style_primary_on(line_bg), muted)
+        }
+        CommentLineKind::Body => (line_bg, theme.style_foreground_on(line_bg), muted),
+        CommentLineKind::Removed => {
+            let bg = theme.diff.removed_bg;
+            (bg, Style::fg(theme.diff.removed).with_bg(bg), muted)
+        }
+        CommentLineKind::Added => {
+            let bg = theme.diff.added_bg;
+            (bg, Style::fg(theme.diff.added).with_bg(bg), muted)
+        }
+    }
+}
+
 /// Compute the total row height of a comment block (for cursor range checks).
 pub(super) fn comment_block_rows(
+    cursor: &StreamCursor<'_>,
     thread: &ThreadSummary,
-    comments: &[crate::db::Comment],
+    comments: &[Comment],
+    events: &[ThreadEvent],
     area: Rect,
 ) -> usize {
     if comments.is_empty() {
@@ -100,17 +219,97 @@ pub(super) fn comment_block_rows(
     }
     let padded = comment_content_area(comment_block_area(area));
     let content_width = padded.width as usize;
-    let content_lines = build_comment_lines(thread, comments, content_width);
-    let content_start = BLOCK_PADDING;
+    let muted = cursor.muted_threads.contains(&thread.thread_id);
+    let entry = cursor.file_cache.get(&thread.file_path);
+    let content_lines = build_comment_lines(thread, comments, events, content_width, muted, entry);
+    let content_start = block_padding();
     let content_end = content_start + content_lines.len();
-    content_end.saturating_add(BLOCK_PADDING)
+    content_end.saturating_add(block_padding())
+}
+
+/// Record each comment's stream rows in `comment_positions`. Returns the
+/// flashed comment's rows within the block, if it's in this thread.
+fn record_comment_positions(
+    cursor: &StreamCursor<'_>,
+    comments: &[Comment],
+    content_lines: &[CommentLine],
+    content_start: usize,
+    block_start: usize,
+) -> Option<std::ops::RangeInclusive<usize>> {
+    let mut flash_rows = None;
+    let mut positions = cursor.comment_positions.borrow_mut();
+    for (index, comment) in comments.iter().enumerate() {
+        let mut rows = content_lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.comment == Some(index))
+            .map(|(i, _)| content_start + i);
+        let Some(first) = rows.next() else { continue };
+        let last = rows.next_back().unwrap_or(first);
+        if cursor.flash_comment == Some(comment.comment_id.as_str()) {
+            flash_rows = Some(first..=last);
+        }
+        positions.insert(
+            comment.comment_id.clone(),
+            block_start + first..block_start + last + 1,
+        );
+    }
+    flash_rows
+}
+
+/// Search matches in each content row, the first at stream row
+/// `first_row`. Body rows are searched as drawn, so a match split by word
+/// wrapping isn't found.
+fn comment_search_matches(
+    cursor: &StreamCursor<'_>,
+    content_lines: &[CommentLine],
+    first_row: usize,
+) -> Vec<Vec<std::ops::Range<usize>>> {
+    content_lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| match (&line.kind, line.comment) {
+            (CommentLineKind::Body, Some(_)) => {
+                cursor.search_matches(&line.left, first_row + i, 0, SearchKind::Comment)
+            }
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+/// Top (`▛▀…▀▜`) or bottom (`▙▄…▄▟`) border row of a comment block, from
+/// its `[left, fill, right]` glyphs. The outer corners keep the window bg.
+fn draw_block_edge(
+    buf: &mut OptimizedBuffer,
+    block: Rect,
+    y: u32,
+    (bg, style): (Rgba, Style),
+    [left, fill, right]: [&str; 3],
+) {
+    let right_x = block.x + block.width.saturating_sub(2);
+    buffer_fill_rect(buf, block.x + 1, y, block.width.saturating_sub(2), 1, bg);
+    buffer_draw_text(buf, block.x + 1, y, left, style);
+    for col in 2..block.width.saturating_sub(2) {
+        buffer_draw_text(buf, block.x + col, y, fill, style);
+    }
+    buffer_draw_text(buf, right_x, y, right, style);
+}
+
+/// The `▌▌` and `▐▐` bars at a comment block's edges.
+fn draw_block_sides(buf: &mut OptimizedBuffer, block: Rect, y: u32, style: Style) {
+    let right_x = block.x + block.width.saturating_sub(2);
+    buffer_draw_text(buf, block.x, y, "▌", style);
+    buffer_draw_text(buf, block.x + 1, y, "▌", style);
+    buffer_draw_text(buf, right_x, y, "▐", style);
+    buffer_draw_text(buf, right_x + 1, y, "▐", style);
 }
 
 pub(super) fn emit_comment_block(
     cursor: &mut StreamCursor<'_>,
     area: Rect,
     thread: &ThreadSummary,
-    comments: &[crate::db::Comment],
+    comments: &[Comment],
+    events: &[ThreadEvent],
     is_highlighted: bool,
     is_cursor: bool,
 ) {
@@ -122,16 +321,29 @@ pub(super) fn emit_comment_block(
     let block = comment_block_area(area);
     let padded = comment_content_area(block);
     let content_width = padded.width as usize;
-    let content_lines = build_comment_lines(thread, comments, content_width);
+    let muted = cursor.muted_threads.contains(&thread.thread_id);
+    let entry = cursor.file_cache.get(&thread.file_path);
+    let content_lines = build_comment_lines(thread, comments, events, content_width, muted, entry);
 
     let top_margin = 0usize;
     let bottom_margin = 0usize;
-    let content_start = top_margin + BLOCK_PADDING;
+    let content_start = top_margin + block_padding();
     let content_end = content_start + content_lines.len();
     let total_rows = content_end
-        .saturating_add(BLOCK_PADDING)
+        .saturating_add(block_padding())
         .saturating_add(bottom_margin);
 
+    let block_start = cursor.stream_row;
+    cursor.thread_rows.borrow_mut().insert(
+        thread.thread_id.clone(),
+        block_start..block_start + total_rows,
+    );
+    let flash_rows =
+        record_comment_positions(cursor, comments, &content_lines, content_start, block_start);
+
+    let search_matches =
+        comment_search_matches(cursor, &content_lines, block_start + content_start);
+
     for row in 0..total_rows {
         cursor.mark_cursor_stop();
         cursor.emit(|buf, y, theme| {
@@ -145,62 +357,34 @@ pub(super) fn emit_comment_block(
             } else {
                 base_bg
             };
-            let border_style = Style::fg(theme.background).with_bg(block_bg);
+            let flashing = flash_rows.as_ref().is_some_and(|rows| rows.contains(&row));
             let bar_style = Style::fg(theme.background).with_bg(block_bg);
-            let rc = block.x + block.width.saturating_sub(1);
-            let rc2 = block.x + block.width.saturating_sub(2);
             if row < top_margin {
                 buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
-            } else if row == top_margin {
-                // Top border:  ▛▀…▀▜  (outer corners match window bg)
+            } else if row == top_margin && row < content_start {
                 buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
-                buffer_fill_rect(
-                    buf,
-                    block.x + 1,
-                    y,
-                    block.width.saturating_sub(2),
-                    1,
-                    block_bg,
-                );
-                buffer_draw_text(buf, block.x + 1, y, "▛", border_style);
-                for col in 2..block.width.saturating_sub(2) {
-                    buffer_draw_text(buf, block.x + col, y, "▀", border_style);
-                }
-                buffer_draw_text(buf, rc2, y, "▜", border_style);
+                draw_block_edge(buf, block, y, (block_bg, bar_style), ["▛", "▀", "▜"]);
             } else if row < content_start {
                 buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
                 buffer_fill_rect(buf, block.x, y, block.width, 1, block_bg);
-                buffer_draw_text(buf, block.x, y, "▌", bar_style);
-                buffer_draw_text(buf, block.x + 1, y, "▌", bar_style);
-                buffer_draw_text(buf, rc2, y, "▐", bar_style);
-                buffer_draw_text(buf, rc, y, "▐", bar_style);
+                draw_block_sides(buf, block, y, bar_style);
             } else if row < content_end {
                 let line = &content_lines[row - content_start];
-                let (left_style, right_style) = match line.kind {
-                    CommentLineKind::Header => (
-                        theme.style_muted_on(block_bg),
-                        theme.style_muted_on(block_bg),
-                    ),
-                    CommentLineKind::Author => (
-                        theme.style_primary_on(block_bg),
-                        theme.style_muted_on(block_bg),
-                    ),
-                    CommentLineKind::Body => (
-                        theme.style_foreground_on(block_bg),
-                        theme.style_muted_on(block_bg),
-                    ),
+                let line_bg = if flashing {
+                    color_lerp(block_bg, theme.warning, 0.3)
+                } else {
+                    block_bg
                 };
+                let (text_bg, left_style, right_style) = line_styles(&line.kind, line_bg, theme);
                 buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
-                buffer_fill_rect(buf, block.x, y, block.width, 1, block_bg);
-                buffer_draw_text(buf, block.x, y, "▌", bar_style);
-                buffer_draw_text(buf, block.x + 1, y, "▌", bar_style);
-                buffer_draw_text(buf, rc2, y, "▐", bar_style);
-                buffer_draw_text(buf, rc, y, "▐", bar_style);
+                buffer_fill_rect(buf, block.x, y, block.width, 1, line_bg);
+                draw_block_sides(buf, block, y, bar_style);
+                buffer_fill_rect(buf, padded.x, y, padded.width, 1, text_bg);
                 draw_plain_line_with_right(
                     buf,
                     padded,
                     y,
-                    block_bg,
+                    text_bg,
                     &PlainLineContent {
                         left: &line.left,
                         right: line.right.as_deref(),
@@ -208,29 +392,22 @@ pub(super) fn emit_comment_block(
                         right_style,
                     },
                 );
-            } else if row < content_end + BLOCK_PADDING {
+                draw_search_matches(
+                    buf,
+                    padded.x,
+                    y,
+                    padded.width,
+                    0..line.left.chars().count(),
+                    &search_matches[row - content_start],
+                    theme,
+                );
+            } else if row < content_end + block_padding() {
                 buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
-                if row == content_end + BLOCK_PADDING - 1 {
-                    // Bottom border:  ▙▄…▄▟  (outer corners match window bg)
-                    buffer_fill_rect(
-                        buf,
-                        block.x + 1,
-                        y,
-                        block.width.saturating_sub(2),
-                        1,
-                        block_bg,
-                    );
-                    buffer_draw_text(buf, block.x + 1, y, "▙", border_style);
-                    for col in 2..block.width.saturating_sub(2) {
-                        buffer_draw_text(buf, block.x + col, y, "▄", border_style);
-                    }
-                    buffer_draw_text(buf, rc2, y, "▟", border_style);
+                if row == content_end + block_padding() - 1 {
+                    draw_block_edge(buf, block, y, (block_bg, bar_style), ["▙", "▄", "▟"]);
                 } else {
                     buffer_fill_rect(buf, block.x, y, block.width, 1, block_bg);
-                    buffer_draw_text(buf, block.x, y, "▌", bar_style);
-                    buffer_draw_text(buf, block.x + 1, y, "▌", bar_style);
-                    buffer_draw_text(buf, rc2, y, "▐", bar_style);
-                    buffer_draw_text(buf, rc, y, "▐", bar_style);
+                    draw_block_sides(buf, block, y, bar_style);
                 }
             } else {
                 buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
@@ -238,3 +415,64 @@ pub(super) fn emit_comment_block(
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: &str, at: &str) -> Comment {
+        Comment {
+            comment_id: id.to_string(),
+            author: "alice".to_string(),
+            body: "body".to_string(),
+            created_at: at.to_string(),
+        }
+    }
+
+    fn event(kind: ThreadEventKind, at: &str) -> ThreadEvent {
+        ThreadEvent {
+            kind,
+            actor: "bob".to_string(),
+            created_at: at.to_string(),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn events_interleave_with_comments_by_time() {
+        let thread = ThreadSummary {
+            thread_id: "th-1".to_string(),
+            file_path: "src/lib.rs".to_string(),
+            selection_start: 1,
+            selection_end: None,
+            side: None,
+            status: "open".to_string(),
+            comment_count: 2,
+        };
+        let comments = [
+            comment("cm-1", "2025-01-01T10:00:00Z"),
+            comment("cm-2", "2025-01-01T12:00:00Z"),
+        ];
+        let events = [
+            event(ThreadEventKind::Opened, "2025-01-01T10:00:00Z"),
+            event(ThreadEventKind::Resolved, "2025-01-01T11:00:00Z"),
+            event(ThreadEventKind::Reopened, "2025-01-01T12:00:00Z"),
+        ];
+        let rights: Vec<String> = build_comment_lines(&thread, &comments, &events, 80, false, None)
+            .iter()
+            .skip(2)
+            .filter(|l| !matches!(l.kind, CommentLineKind::Body))
+            .map(|l| l.right.clone().unwrap_or_default())
+            .collect();
+        assert_eq!(
+            rights,
+            [
+                "2025-01-01 10:00",
+                "cm-1",
+                "2025-01-01 11:00",
+                "cm-2",
+                "2025-01-01 12:00"
+            ]
+        );
+    }
+}
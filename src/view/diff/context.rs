@@ -3,19 +3,22 @@
 use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Style};
 
 use crate::db::ThreadSummary;
-use crate::layout::{CONTEXT_LINES, SBS_LINE_NUM_WIDTH};
+use crate::i18n::{tr_fmt, Text};
+use crate::layout::SBS_LINE_NUM_WIDTH;
+use crate::search::SearchKind;
 use crate::syntax::HighlightSpan;
 use crate::theme::Theme;
 use crate::view::components::Rect;
 
 use super::analysis::{build_thread_ranges, line_in_thread_ranges};
-use super::comments::{comment_block_rows, emit_comment_block};
+use super::comments::{comment_block_rows, emit_comment_block, events_for};
 use super::helpers::{
     cursor_bg, cursor_fg, draw_diff_base_line, orphaned_context_width, orphaned_context_x,
     selection_bg,
 };
 use super::text_util::{
-    draw_highlighted_text, draw_wrapped_line, wrap_content, HighlightContent, WrappedLine,
+    draw_highlighted_text, draw_search_matches, draw_wrapped_line, wrap_content, wrapped_row_start,
+    HighlightContent, WrappedLine,
 };
 use super::{DisplayItem, LineRange, LineRenderCtx, OrphanedContext, StreamCursor};
 
@@ -28,6 +31,7 @@ pub(super) fn calculate_context_ranges(
     threads: &[&ThreadSummary],
     total_lines: usize,
     exclude_ranges: &[(i64, i64)],
+    context_lines: i64,
 ) -> Vec<LineRange> {
     if threads.is_empty() {
         return Vec::new();
@@ -38,9 +42,9 @@ pub(super) fn calculate_context_ranges(
         .map(|t| {
             let thread_end = t.selection_end.unwrap_or(t.selection_start);
             LineRange {
-                start: (t.selection_start - CONTEXT_LINES).max(1),
+                start: (t.selection_start - context_lines).max(1),
                 #[allow(clippy::cast_possible_wrap)]
-                end: (thread_end + CONTEXT_LINES).min(total_lines as i64),
+                end: (thread_end + context_lines).min(total_lines as i64),
             }
         })
         .collect();
@@ -102,10 +106,12 @@ pub(super) fn build_context_items(
     threads: &[&ThreadSummary],
     exclude_ranges: &[(i64, i64)],
     start_line: i64,
+    context_lines: i64,
 ) -> Vec<DisplayItem> {
     #[allow(clippy::cast_possible_wrap)]
     let total_lines = start_line + lines.len() as i64 - 1;
-    let ranges = calculate_context_ranges(threads, total_lines as usize, exclude_ranges);
+    let ranges =
+        calculate_context_ranges(threads, total_lines as usize, exclude_ranges, context_lines);
     if ranges.is_empty() {
         return vec![DisplayItem::Separator(0)];
     }
@@ -174,11 +180,103 @@ pub(super) fn group_context_ranges_by_hunks(
 /// Shared mutable state for orphaned context rendering across sections.
 pub(super) struct OrphanedRenderState<'a> {
     pub all_comments: &'a std::collections::HashMap<String, Vec<crate::db::Comment>>,
+    pub thread_events: &'a std::collections::HashMap<String, Vec<crate::db::ThreadEvent>>,
     pub thread_positions: &'a std::cell::RefCell<std::collections::HashMap<String, usize>>,
     pub emitted_threads: &'a mut std::collections::HashSet<String>,
     pub last_line_num: &'a mut Option<i64>,
 }
 
+/// Record that `thread` was placed here and draw its comment block.
+fn emit_orphaned_thread(
+    cursor: &mut StreamCursor<'_>,
+    comment_area: Rect,
+    thread: &ThreadSummary,
+    state: &mut OrphanedRenderState<'_>,
+) {
+    state.emitted_threads.insert(thread.thread_id.clone());
+    state
+        .thread_positions
+        .borrow_mut()
+        .insert(thread.thread_id.clone(), cursor.stream_row);
+    if let Some(comments) = state.all_comments.get(&thread.thread_id) {
+        let events = events_for(state.thread_events, &thread.thread_id);
+        let rows = comment_block_rows(cursor, thread, comments, events, comment_area);
+        let is_cursor = cursor.is_cursor_at(rows);
+        let hl = is_cursor || cursor.is_selected_at(rows);
+        emit_comment_block(
+            cursor,
+            comment_area,
+            thread,
+            comments,
+            events,
+            hl,
+            is_cursor,
+        );
+    }
+}
+
+/// One context line, wrapped over several rows with wrap on.
+fn emit_context_line(
+    cursor: &mut StreamCursor<'_>,
+    area: Rect,
+    context: &OrphanedContext<'_>,
+    (item, line_num, line_content): (&DisplayItem, i64, &str),
+    wrap: bool,
+    show_thread_bar: bool,
+) {
+    if wrap {
+        let line_index = (line_num - context.start_line) as usize;
+        let highlight = context.highlights.get(line_index);
+        let line_num_width = SBS_LINE_NUM_WIDTH;
+        let cw = orphaned_context_width(area).saturating_sub(line_num_width) as usize;
+        let wrapped = wrap_content(highlight, line_content, cw);
+        let rows = wrapped.len().max(1);
+        let is_cursor = cursor.is_cursor_at(rows);
+        let is_selected = cursor.is_selected_at(rows);
+        let matches = cursor.search_matches(line_content, cursor.stream_row, cw, SearchKind::Line);
+        cursor.emit_rows(rows, |buf, y, theme, row| {
+            render_context_line_wrapped_row(
+                buf,
+                y,
+                line_num,
+                theme,
+                &LineRenderCtx {
+                    area,
+                    anchor: None,
+                    show_thread_bar,
+                    is_cursor,
+                    is_selected,
+                    show_cr: false,
+                    cursor_side: None,
+                    matches: &matches,
+                    left_matches: &[],
+                },
+                &wrapped,
+                row,
+            );
+        });
+    } else {
+        let is_cursor = cursor.is_cursor_at(1);
+        let is_selected = cursor.is_selected_at(1);
+        let matches = cursor.search_matches(line_content, cursor.stream_row, 0, SearchKind::Line);
+        cursor.emit(|buf, y, theme| {
+            render_context_item_block(
+                buf,
+                area,
+                y,
+                item,
+                theme,
+                show_thread_bar,
+                context.highlights,
+                is_cursor,
+                is_selected,
+                context.start_line,
+                &matches,
+            );
+        });
+    }
+}
+
 pub(super) fn emit_orphaned_context_section(
     cursor: &mut StreamCursor<'_>,
     area: Rect,
@@ -201,31 +299,14 @@ pub(super) fn emit_orphaned_context_section(
     let display_items = build_context_items_from_ranges(context.lines, ranges, context.start_line);
     for item in &display_items {
         if let DisplayItem::Line { line_num, .. } = item {
-            if let Some(prev) = state.last_line_num.as_ref() {
+            if let Some(prev) = *state.last_line_num {
                 for thread in &context.threads {
                     let end = thread.selection_end.unwrap_or(thread.selection_start);
                     if !state.emitted_threads.contains(thread.thread_id.as_str())
-                        && end > *prev
+                        && end > prev
                         && end < *line_num
                     {
-                        state.emitted_threads.insert(thread.thread_id.clone());
-                        state
-                            .thread_positions
-                            .borrow_mut()
-                            .insert(thread.thread_id.clone(), cursor.stream_row);
-                        if let Some(comments) = state.all_comments.get(&thread.thread_id) {
-                            let rows = comment_block_rows(thread, comments, comment_area);
-                            let is_cursor = cursor.is_cursor_at(rows);
-                            let hl = is_cursor || cursor.is_selected_at(rows);
-                            emit_comment_block(
-                                cursor,
-                                comment_area,
-                                thread,
-                                comments,
-                                hl,
-                                is_cursor,
-                            );
-                        }
+                        emit_orphaned_thread(cursor, comment_area, thread, state);
                     }
                 }
             }
@@ -251,6 +332,7 @@ pub(super) fn emit_orphaned_context_section(
                         false,
                         false,
                         context.start_line,
+                        &[],
                     );
                 });
             }
@@ -259,50 +341,14 @@ pub(super) fn emit_orphaned_context_section(
                 content: line_content,
             } => {
                 cursor.mark_cursor_stop();
-                if wrap {
-                    let line_index = (*line_num - context.start_line) as usize;
-                    let highlight = context.highlights.get(line_index);
-                    let line_num_width = SBS_LINE_NUM_WIDTH;
-                    let cw = orphaned_context_width(area).saturating_sub(line_num_width) as usize;
-                    let wrapped = wrap_content(highlight, line_content, cw);
-                    let rows = wrapped.len().max(1);
-                    let is_cursor = cursor.is_cursor_at(rows);
-                    let is_selected = cursor.is_selected_at(rows);
-                    cursor.emit_rows(rows, |buf, y, theme, row| {
-                        render_context_line_wrapped_row(
-                            buf,
-                            y,
-                            *line_num,
-                            theme,
-                            &LineRenderCtx {
-                                area,
-                                anchor: None,
-                                show_thread_bar,
-                                is_cursor,
-                                is_selected,
-                            },
-                            &wrapped,
-                            row,
-                        );
-                    });
-                } else {
-                    let is_cursor = cursor.is_cursor_at(1);
-                    let is_selected = cursor.is_selected_at(1);
-                    cursor.emit(|buf, y, theme| {
-                        render_context_item_block(
-                            buf,
-                            area,
-                            y,
-                            item,
-                            theme,
-                            show_thread_bar,
-                            context.highlights,
-                            is_cursor,
-                            is_selected,
-                            context.start_line,
-                        );
-                    });
-                }
+                emit_context_line(
+                    cursor,
+                    area,
+                    context,
+                    (item, *line_num, line_content),
+                    wrap,
+                    show_thread_bar,
+                );
 
                 let end_matches: Vec<&ThreadSummary> = context
                     .threads
@@ -315,17 +361,7 @@ pub(super) fn emit_orphaned_context_section(
                     })
                     .collect();
                 for thread in end_matches {
-                    state.emitted_threads.insert(thread.thread_id.clone());
-                    state
-                        .thread_positions
-                        .borrow_mut()
-                        .insert(thread.thread_id.clone(), cursor.stream_row);
-                    if let Some(comments) = state.all_comments.get(&thread.thread_id) {
-                        let rows = comment_block_rows(thread, comments, comment_area);
-                        let is_cursor = cursor.is_cursor_at(rows);
-                        let hl = is_cursor || cursor.is_selected_at(rows);
-                        emit_comment_block(cursor, comment_area, thread, comments, hl, is_cursor);
-                    }
+                    emit_orphaned_thread(cursor, comment_area, thread, state);
                 }
                 *state.last_line_num = Some(*line_num);
             }
@@ -338,6 +374,7 @@ pub(super) fn emit_remaining_orphaned_comments(
     comment_area: Rect,
     context: &OrphanedContext<'_>,
     all_comments: &std::collections::HashMap<String, Vec<crate::db::Comment>>,
+    thread_events: &std::collections::HashMap<String, Vec<crate::db::ThreadEvent>>,
     thread_positions: &std::cell::RefCell<std::collections::HashMap<String, usize>>,
     emitted_threads: &std::collections::HashSet<String>,
 ) {
@@ -352,10 +389,19 @@ pub(super) fn emit_remaining_orphaned_comments(
             .borrow_mut()
             .insert(thread.thread_id.clone(), cursor.stream_row);
         if let Some(comments) = all_comments.get(&thread.thread_id) {
-            let rows = comment_block_rows(thread, comments, comment_area);
+            let events = events_for(thread_events, &thread.thread_id);
+            let rows = comment_block_rows(cursor, thread, comments, events, comment_area);
             let is_cursor = cursor.is_cursor_at(rows);
             let hl = is_cursor || cursor.is_selected_at(rows);
-            emit_comment_block(cursor, comment_area, thread, comments, hl, is_cursor);
+            emit_comment_block(
+                cursor,
+                comment_area,
+                thread,
+                comments,
+                events,
+                hl,
+                is_cursor,
+            );
         }
     }
 }
@@ -372,18 +418,19 @@ pub(super) fn render_context_item_block(
     is_cursor: bool,
     is_selected: bool,
     start_line: i64,
+    matches: &[std::ops::Range<usize>],
 ) {
     let dt = &theme.diff;
     match item {
         DisplayItem::Separator(gap) => {
             draw_diff_base_line(buffer, area, y, dt.context_bg);
             let sep_text = if *gap > 0 {
-                format!("··· {gap} lines ···")
+                tr_fmt(Text::HiddenLines, &[gap])
             } else {
                 "···".to_string()
             };
             let sep_x = orphaned_context_x(area)
-                + orphaned_context_width(area).saturating_sub(sep_text.len() as u32) / 2;
+                + orphaned_context_width(area).saturating_sub(sep_text.chars().count() as u32) / 2;
             buffer_draw_text(
                 buffer,
                 sep_x,
@@ -424,6 +471,8 @@ pub(super) fn render_context_item_block(
                     bg,
                 },
             );
+            let row = 0..content.chars().count();
+            draw_search_matches(buffer, content_x, y, content_width, row, matches, theme);
         }
     }
 }
@@ -461,5 +510,15 @@ pub(super) fn render_context_line_wrapped_row(
     buffer_fill_rect(buffer, content_x, y, content_width, 1, bg);
     if let Some(line_content) = wrapped.get(row) {
         draw_wrapped_line(buffer, content_x, y, content_width, line_content, fg, bg);
+        let start = wrapped_row_start(wrapped, row);
+        draw_search_matches(
+            buffer,
+            content_x,
+            y,
+            content_width,
+            start..start + line_content.char_count(),
+            ctx.matches,
+            theme,
+        );
     }
 }
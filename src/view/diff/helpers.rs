@@ -4,23 +4,29 @@ use crate::render_backend::{
     buffer_draw_text, buffer_fill_rect, color_lerp, OptimizedBuffer, Rgba, Style,
 };
 
+use crate::glyphs::glyphs;
+use crate::i18n::{tr_fmt, Text};
 use crate::layout::{
-    BLOCK_LEFT_PAD, BLOCK_RIGHT_PAD, BLOCK_SIDE_MARGIN, COMMENT_H_MARGIN, COMMENT_H_PAD,
+    comment_block_span, comment_h_pad, BLOCK_LEFT_PAD, BLOCK_RIGHT_PAD, BLOCK_SIDE_MARGIN,
     DIFF_H_PAD, DIFF_MARGIN, ORPHANED_CONTEXT_LEFT_PAD,
 };
+use crate::text::{display_width, truncate_path};
 use crate::theme::Theme;
 use crate::view::components::Rect;
 
 use super::text_util::truncate_chars;
-use super::ChangeCounts;
+use super::{FileHeader, FilePosition, HunkSeparator};
+
+/// Narrowest path the pinned header keeps before dropping the file position
+const MIN_PINNED_PATH_WIDTH: usize = 12;
 
 // --- Block helpers (for file headers, pinned headers, comments) ---
 
-pub(super) const fn block_inner_x(area: Rect) -> u32 {
+pub const fn block_inner_x(area: Rect) -> u32 {
     area.x + BLOCK_SIDE_MARGIN + 1 + BLOCK_LEFT_PAD
 }
 
-pub(super) const fn block_inner_width(area: Rect) -> u32 {
+pub const fn block_inner_width(area: Rect) -> u32 {
     area.width
         .saturating_sub(BLOCK_SIDE_MARGIN * 2 + 1 + BLOCK_LEFT_PAD + BLOCK_RIGHT_PAD)
 }
@@ -109,6 +115,47 @@ pub(super) fn draw_diff_base_line(buffer: &mut OptimizedBuffer, area: Rect, y: u
     buffer_fill_rect(buffer, area.x, y, area.width, 1, bg);
 }
 
+/// A hunk header: a centered `···`, or what a folded hunk holds.
+pub(super) fn draw_hunk_separator(
+    buffer: &mut OptimizedBuffer,
+    area: Rect,
+    y: u32,
+    theme: &Theme,
+    separator: HunkSeparator,
+    is_cursor: bool,
+    is_selected: bool,
+) {
+    let dt = &theme.diff;
+    let bg = cursor_bg(
+        selection_bg(dt.context_bg, is_selected, theme),
+        is_cursor,
+        theme,
+    );
+    draw_diff_base_line(buffer, area, y, bg);
+    let sep = match separator.fold {
+        Some(fold) => {
+            let summary = tr_fmt(
+                Text::FoldedHunk,
+                &[&fold.lines, &fold.counts.added, &fold.counts.removed],
+            );
+            format!("··· {} {summary} ···", glyphs().collapsed)
+        }
+        None if separator.hidden > 0 => {
+            let hidden = tr_fmt(Text::ExpandableLines, &[&separator.hidden]);
+            format!("··· {} {hidden} ···", glyphs().expand)
+        }
+        None => "···".to_string(),
+    };
+    let width = display_width(&sep) as u32;
+    let sep_x = diff_content_x(area) + diff_content_width(area).saturating_sub(width) / 2;
+    let style = if is_cursor {
+        Style::fg(cursor_fg(theme.muted, true)).with_bg(bg)
+    } else {
+        theme.style_muted_on(bg)
+    };
+    buffer_draw_text(buffer, sep_x, y, &sep, style);
+}
+
 pub(super) const fn diff_margin_area(area: Rect) -> Rect {
     Rect::new(
         area.x + DIFF_MARGIN,
@@ -154,19 +201,20 @@ pub(super) fn draw_cursor_bar(
 }
 
 /// The comment block area inset by the horizontal margin (bar goes here).
-pub(super) const fn comment_block_area(area: Rect) -> Rect {
+pub(super) fn comment_block_area(area: Rect) -> Rect {
+    let (inset, width) = comment_block_span(area.width);
     Rect {
-        x: area.x + COMMENT_H_MARGIN,
-        width: area.width.saturating_sub(COMMENT_H_MARGIN * 2),
+        x: area.x + inset,
+        width,
         ..area
     }
 }
 
 /// Padded content area inside a comment block (after double bar + padding each side).
-pub(super) const fn comment_content_area(block: Rect) -> Rect {
+pub(super) fn comment_content_area(block: Rect) -> Rect {
     Rect {
-        x: block.x + 2 + COMMENT_H_PAD,
-        width: block.width.saturating_sub(4 + COMMENT_H_PAD * 2),
+        x: block.x + 2 + comment_h_pad(),
+        width: block.width.saturating_sub(4 + comment_h_pad() * 2),
         ..block
     }
 }
@@ -204,6 +252,33 @@ pub(super) fn draw_comment_block_text_line(
     buffer_draw_text(buffer, content_x, y, display_text, style.with_bg(bg));
 }
 
+/// Gutter marker for a line followed by `\ No newline at end of file`.
+pub(super) fn draw_no_newline_marker(
+    buffer: &mut OptimizedBuffer,
+    x: u32,
+    y: u32,
+    bg: Rgba,
+    theme: &Theme,
+) {
+    let marker = glyphs().no_newline;
+    buffer_draw_text(buffer, x, y, marker, Style::fg(theme.warning).with_bg(bg));
+}
+
+/// Draw a muted `^M` after `text_width` columns of content starting at `x`,
+/// if it fits within `max_width`. Keeps the existing cell background.
+pub(super) fn draw_cr_marker(
+    buffer: &mut OptimizedBuffer,
+    x: u32,
+    y: u32,
+    text_width: usize,
+    max_width: u32,
+    theme: &Theme,
+) {
+    if text_width + 2 <= max_width as usize {
+        buffer_draw_text(buffer, x + text_width as u32, y, "^M", theme.style_muted());
+    }
+}
+
 /// Content for a line with left-aligned and optional right-aligned text.
 pub(super) struct PlainLineContent<'a> {
     pub left: &'a str,
@@ -288,10 +363,18 @@ pub(super) fn draw_file_header_line(
     area: Rect,
     y: u32,
     theme: &Theme,
-    file_path: &str,
-    counts: Option<ChangeCounts>,
+    header: &FileHeader<'_>,
 ) {
-    let bg = theme.panel_bg;
+    let FileHeader {
+        path: file_path,
+        counts,
+        badges,
+        position,
+        viewed,
+        folded,
+        is_cursor,
+    } = *header;
+    let bg = cursor_bg(theme.panel_bg, is_cursor, theme);
     draw_block_base_line(buffer, area, y, bg, theme);
 
     let content_x = block_inner_x(area);
@@ -303,31 +386,72 @@ pub(super) fn draw_file_header_line(
         right_len += 3; // " / "
         right_len += format!("-{}", counts.removed).len();
     }
+    let counts_len = right_len;
+    for badge in badges {
+        right_len += badge.chars().count() + 3; // "[badge] "
+    }
 
+    let mark_len = if viewed || folded { 2 } else { 0 };
     let left_max = if right_len > 0 {
         content_width.saturating_sub(right_len + 1)
     } else {
         content_width
-    };
-    let left_text = if left_max == 0 {
-        ""
+    }
+    .saturating_sub(mark_len);
+    // Position and open threads follow the path when there's room for both
+    let status = position
+        .map(|position| file_position_segments(position, theme))
+        .filter(|segments| {
+            let status_len: usize = segments.iter().map(|(text, _)| display_width(text)).sum();
+            status_len + MIN_PINNED_PATH_WIDTH <= left_max
+        })
+        .unwrap_or_default();
+    let status_len: usize = status.iter().map(|(text, _)| display_width(text)).sum();
+    let left_text = truncate_path(file_path, left_max - status_len);
+
+    let path_style = if viewed {
+        buffer_draw_text(
+            buffer,
+            content_x,
+            y,
+            glyphs().viewed,
+            Style::fg(theme.success).with_bg(bg),
+        );
+        theme.style_muted_on(bg)
+    } else if folded {
+        buffer_draw_text(
+            buffer,
+            content_x,
+            y,
+            glyphs().collapsed,
+            theme.style_muted_on(bg),
+        );
+        theme.style_foreground_on(bg)
     } else {
-        truncate_chars(file_path, left_max)
+        theme.style_foreground_on(bg)
     };
+    let path_x = content_x + mark_len as u32;
+    buffer_draw_text(buffer, path_x, y, &left_text, path_style);
+    let mut x = path_x + display_width(&left_text) as u32;
+    for (text, color) in &status {
+        buffer_draw_text(buffer, x, y, text, Style::fg(*color).with_bg(bg));
+        x += display_width(text) as u32;
+    }
 
-    buffer_draw_text(
-        buffer,
-        content_x,
-        y,
-        left_text,
-        theme.style_foreground_on(bg),
-    );
+    if !badges.is_empty() && right_len <= content_width {
+        let mut x = content_x + block_inner_width(area) - right_len as u32;
+        for badge in badges {
+            let text = format!("[{badge}]");
+            buffer_draw_text(buffer, x, y, &text, Style::fg(theme.warning).with_bg(bg));
+            x += text.chars().count() as u32 + 1;
+        }
+    }
 
     if let Some(counts) = counts {
         let right_text = format!("+{} / -{}", counts.added, counts.removed);
         let right_width = right_text.len() as u32;
         if right_width > 0 && right_width as usize <= content_width {
-            let mut x = content_x + block_inner_width(area) - right_width;
+            let mut x = content_x + block_inner_width(area) - counts_len as u32;
             let add_text = format!("+{}", counts.added);
             buffer_draw_text(
                 buffer,
@@ -344,3 +468,35 @@ pub(super) fn draw_file_header_line(
         }
     }
 }
+
+/// `  file 3/9 · 4/9 viewed · 2 open`, split into colored segments.
+fn file_position_segments(position: FilePosition, theme: &Theme) -> Vec<(String, Rgba)> {
+    let mut segments = vec![(
+        format!(
+            "  {}",
+            tr_fmt(
+                Text::PinnedFilePosition,
+                &[&(position.index + 1), &position.total]
+            )
+        ),
+        theme.muted,
+    )];
+    if position.viewed_files > 0 {
+        segments.push((" \u{b7} ".to_string(), theme.muted));
+        segments.push((
+            tr_fmt(
+                Text::PinnedViewedFiles,
+                &[&position.viewed_files, &position.total],
+            ),
+            theme.success,
+        ));
+    }
+    if position.open_threads > 0 {
+        segments.push((" \u{b7} ".to_string(), theme.muted));
+        segments.push((
+            tr_fmt(Text::PinnedOpenThreads, &[&position.open_threads]),
+            theme.warning,
+        ));
+    }
+    segments
+}
@@ -8,32 +8,41 @@
 //! - `side_by_side`: SBS diff line rendering
 //! - `comments`: comment block rendering
 //! - `context`: orphaned context building + rendering
+//! - `word`: word diff rendering for prose files
+//! - `records`: skipping files outside the viewport
 
 mod analysis;
 mod comments;
 mod context;
 mod helpers;
+mod records;
 mod side_by_side;
 mod text_util;
 mod unified;
+mod word;
 
-use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer};
+use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Rgba};
 
 use super::components::Rect;
-use crate::db::ThreadSummary;
-use crate::diff::{DiffLine, DiffLineKind, ParsedDiff};
+use crate::db::{ReviewDetail, ReviewVote, ThreadSide, ThreadSummary, Verdict};
+use crate::diff::{intraline_changes, pair_lines, DiffLine, DiffLineKind, ParsedDiff};
+use crate::glyphs::glyphs;
+use crate::i18n::{tr, tr_fmt, Text};
 use crate::layout::{
-    block_height, BLOCK_MARGIN, BLOCK_PADDING, SBS_LINE_NUM_WIDTH, THREAD_COL_WIDTH,
-    UNIFIED_LINE_NUM_WIDTH,
+    block_height, block_margin, block_padding, MAX_PARSE_WARNING_ROWS, SBS_LINE_NUM_WIDTH,
+    THREAD_COL_WIDTH, UNIFIED_LINE_NUM_WIDTH,
 };
+use crate::search::{column_matches, find_matches, wrapped_row, SearchHit, SearchKind};
+use crate::stream::{abandon_reason, review_threads, split_file_threads, verdict_summary};
 use crate::syntax::HighlightSpan;
 use crate::theme::Theme;
 
 // Re-export public API
-pub use analysis::{diff_change_counts, map_threads_to_diff};
+pub use analysis::{diff_change_counts, file_lint_badges, hunk_thread_range, map_threads_to_diff};
+pub use helpers::{block_inner_width, block_inner_x};
 
-use analysis::{build_thread_ranges, line_in_thread_ranges};
-use comments::{comment_block_rows, emit_comment_block};
+use analysis::{build_thread_ranges, line_in_thread_ranges, ThreadRanges};
+use comments::{comment_block_rows, emit_comment_block, events_for};
 use context::{
     build_context_items, calculate_context_ranges, emit_orphaned_context_section,
     emit_remaining_orphaned_comments, group_context_ranges_by_hunks, render_context_item_block,
@@ -46,7 +55,10 @@ use helpers::{
 };
 use side_by_side::{render_side_by_side_line_block, render_side_by_side_line_wrapped_row};
 use text_util::wrap_content;
-use unified::{render_unified_diff_line_block, render_unified_diff_line_wrapped_row};
+use unified::{
+    blame_line_area, draw_blame_gutter, render_unified_diff_line_block,
+    render_unified_diff_line_wrapped_row,
+};
 
 /// Map from display-line index to the anchors at that position.
 type AnchorMap<'a> = std::collections::HashMap<usize, Vec<&'a ThreadAnchor>>;
@@ -63,9 +75,13 @@ pub struct ThreadAnchor {
     /// Display line after which the comment block should render (end of range)
     pub comment_after_line: usize,
     pub line_count: usize, // How many lines the thread spans
+    /// Side the thread resolved to (legacy threads are inferred)
+    pub side: ThreadSide,
     pub status: String,
     pub comment_count: i64,
     pub is_expanded: bool,
+    /// Index of the hunk for a hunk-level thread, drawn under its header
+    pub hunk: Option<usize>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -74,10 +90,91 @@ pub struct ChangeCounts {
     pub(super) removed: usize,
 }
 
+/// The pinned file's place in the review ("file 3/9") and its open threads.
+#[derive(Clone, Copy, Debug)]
+pub struct FilePosition {
+    /// 0-based
+    pub index: usize,
+    pub total: usize,
+    pub open_threads: usize,
+    /// Files marked viewed, of `total`
+    pub viewed_files: usize,
+    /// Whether this file is one
+    pub viewed: bool,
+}
+
+/// What a file header line shows.
+pub(super) struct FileHeader<'a> {
+    pub(super) path: &'a str,
+    pub(super) counts: Option<ChangeCounts>,
+    pub(super) badges: &'a [&'a str],
+    /// Pinned header only
+    pub(super) position: Option<FilePosition>,
+    /// Marked viewed: path muted behind a check
+    pub(super) viewed: bool,
+    /// Folded to its header: path behind a fold mark
+    pub(super) folded: bool,
+    /// Holds the diff cursor (a folded file's header)
+    pub(super) is_cursor: bool,
+}
+
 /// A line to display (either hunk header or diff line)
 enum DisplayLine {
-    HunkHeader,
+    HunkHeader(HunkSeparator),
     Diff(DiffLine),
+    /// A line of a folded hunk, not drawn; keeps display indices in step
+    Folded,
+}
+
+/// What a hunk's header line shows.
+#[derive(Debug, Clone, Copy, Default)]
+struct HunkSeparator {
+    /// New-side lines hidden above the hunk, which expanding it shows
+    hidden: u32,
+    /// Summarizes the hunk when it's folded
+    fold: Option<HunkFold>,
+}
+
+impl HunkSeparator {
+    fn of(hunks: &[crate::diff::DiffHunk], index: usize) -> Self {
+        Self {
+            hidden: crate::diff::hidden_lines_above(hunks, index),
+            fold: hunks.get(index).and_then(HunkFold::of),
+        }
+    }
+}
+
+/// What a folded hunk's header line says in place of its lines.
+#[derive(Debug, Clone, Copy)]
+struct HunkFold {
+    lines: usize,
+    counts: ChangeCounts,
+}
+
+impl HunkFold {
+    fn of(hunk: &crate::diff::DiffHunk) -> Option<Self> {
+        hunk.folded.then(|| {
+            let changed = |kind| hunk.lines.iter().filter(|l| l.kind == kind).count();
+            Self {
+                lines: hunk.lines.len(),
+                counts: ChangeCounts {
+                    added: changed(DiffLineKind::Added),
+                    removed: changed(DiffLineKind::Removed),
+                },
+            }
+        })
+    }
+
+    const fn is_change(self) -> bool {
+        self.counts.added + self.counts.removed > 0
+    }
+}
+
+impl DisplayLine {
+    /// Whether this is an added or removed line.
+    fn is_change(&self) -> bool {
+        matches!(self, Self::Diff(line) if line.kind != DiffLineKind::Context)
+    }
 }
 
 /// A paired line for side-by-side display
@@ -86,6 +183,17 @@ struct SideBySideLine {
     left: Option<SideLine>,
     right: Option<SideLine>,
     is_header: bool,
+    separator: HunkSeparator,
+}
+
+impl SideBySideLine {
+    /// Whether either side is an added or removed line.
+    fn is_change(&self) -> bool {
+        [&self.left, &self.right]
+            .into_iter()
+            .flatten()
+            .any(|side| side.kind != DiffLineKind::Context)
+    }
 }
 
 /// One side of a side-by-side line
@@ -95,6 +203,11 @@ struct SideLine {
     content: String,
     kind: DiffLineKind,
     display_index: usize,
+    no_newline: bool,
+    crlf: bool,
+    /// Changed chars against the line across from it, for a removed line
+    /// paired with an added one
+    emphasis: Vec<std::ops::Range<usize>>,
 }
 
 /// Shared rendering context for diff line render functions.
@@ -102,12 +215,23 @@ struct SideLine {
 /// Bundles the outer-scope parameters that are constant across all lines in a
 /// rendering pass, keeping the per-line closure params (`buffer`, `y`, `theme`)
 /// separate.
+#[allow(clippy::struct_excessive_bools)]
 struct LineRenderCtx<'a> {
     area: Rect,
     anchor: Option<&'a ThreadAnchor>,
     show_thread_bar: bool,
     is_cursor: bool,
     is_selected: bool,
+    /// Draw `^M` after lines that ended in CRLF
+    show_cr: bool,
+    /// Side-by-side column holding the cursor; the other column isn't
+    /// highlighted. `None` highlights the whole row.
+    cursor_side: Option<ThreadSide>,
+    /// Search matches on the line, as char ranges (the right column in
+    /// side-by-side)
+    matches: &'a [std::ops::Range<usize>],
+    /// Search matches on the left side-by-side column
+    left_matches: &'a [std::ops::Range<usize>],
 }
 
 /// Display item for file context view
@@ -134,8 +258,20 @@ struct StreamCursor<'a> {
     max_stream_row: &'a std::cell::Cell<usize>,
     selection: Option<(usize, usize)>,
     cursor_stops: &'a std::cell::RefCell<Vec<usize>>,
+    hunk_rows: &'a std::cell::RefCell<Vec<usize>>,
+    change_rows: &'a std::cell::RefCell<Vec<usize>>,
+    comment_positions: &'a std::cell::RefCell<CommentPositions>,
+    flash_comment: Option<&'a str>,
+    thread_rows: &'a std::cell::RefCell<CommentPositions>,
+    muted_threads: &'a std::collections::BTreeSet<String>,
+    /// Source of the lines suggestions replace
+    file_cache: &'a std::collections::HashMap<String, crate::model::FileCacheEntry>,
+    search: Option<&'a str>,
+    search_hits: &'a std::cell::RefCell<Vec<SearchHit>>,
 }
 
+type CommentPositions = std::collections::HashMap<String, std::ops::Range<usize>>;
+
 struct OrphanedContext<'a> {
     sections: Vec<Vec<LineRange>>,
     threads: Vec<&'a ThreadSummary>,
@@ -150,9 +286,17 @@ struct OrphanedContext<'a> {
 /// the entire stream.
 struct StreamRenderCtx<'a> {
     wrap: bool,
+    show_cr: bool,
     all_comments: &'a std::collections::HashMap<String, Vec<crate::db::Comment>>,
+    thread_events: &'a std::collections::HashMap<String, Vec<crate::db::ThreadEvent>>,
     thread_positions: &'a std::cell::RefCell<std::collections::HashMap<String, usize>>,
     line_map: &'a std::cell::RefCell<std::collections::HashMap<usize, i64>>,
+    old_line_map: &'a std::cell::RefCell<std::collections::HashMap<usize, i64>>,
+    sbs_side: ThreadSide,
+    word_diff_files: &'a std::collections::HashSet<String>,
+    folds: &'a crate::stream::Folds,
+    context_lines: i64,
+    blame: Option<&'a std::collections::HashMap<String, Vec<crate::vcs::BlameLine>>>,
 }
 
 /// Per-file rendering context for unified/SBS diff functions. Bundles the
@@ -164,9 +308,16 @@ struct DiffRenderCtx<'a> {
     threads: &'a [&'a ThreadSummary],
     file_highlights: &'a [Vec<HighlightSpan>],
     wrap: bool,
+    show_cr: bool,
     all_comments: &'a std::collections::HashMap<String, Vec<crate::db::Comment>>,
+    thread_events: &'a std::collections::HashMap<String, Vec<crate::db::ThreadEvent>>,
     thread_positions: &'a std::cell::RefCell<std::collections::HashMap<String, usize>>,
     line_map: &'a std::cell::RefCell<std::collections::HashMap<usize, i64>>,
+    old_line_map: &'a std::cell::RefCell<std::collections::HashMap<usize, i64>>,
+    sbs_side: ThreadSide,
+    /// The file's blame, indexed by new-side line, with the blame column on
+    /// (empty until it loads)
+    blame: Option<&'a [crate::vcs::BlameLine]>,
 }
 
 impl StreamCursor<'_> {
@@ -223,6 +374,39 @@ impl StreamCursor<'_> {
     fn mark_cursor_stop(&self) {
         self.cursor_stops.borrow_mut().push(self.stream_row);
     }
+
+    /// Record the current `stream_row` as the start of a hunk.
+    fn mark_hunk_start(&self) {
+        self.hunk_rows.borrow_mut().push(self.stream_row);
+    }
+
+    /// Record the current `stream_row` as the start of a run of changes.
+    fn mark_change_start(&self) {
+        self.change_rows.borrow_mut().push(self.stream_row);
+    }
+
+    /// Search matches in `text`, recording a hit on the stream row each one
+    /// starts on. `text` is drawn from `first_row`, `width` chars to a row
+    /// (0 when it isn't wrapped).
+    fn search_matches(
+        &self,
+        text: &str,
+        first_row: usize,
+        width: usize,
+        kind: SearchKind,
+    ) -> Vec<std::ops::Range<usize>> {
+        let Some(query) = self.search else {
+            return Vec::new();
+        };
+        let matches = find_matches(text, query);
+        self.search_hits
+            .borrow_mut()
+            .extend(matches.iter().map(|m| SearchHit {
+                row: first_row + wrapped_row(m.start, width),
+                kind,
+            }));
+        matches
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -233,13 +417,19 @@ fn build_side_by_side_lines(diff: &ParsedDiff) -> Vec<SideBySideLine> {
     let mut result = Vec::new();
     let mut display_index = 0;
 
-    for hunk in &diff.hunks {
+    for (index, hunk) in diff.hunks.iter().enumerate() {
+        let separator = HunkSeparator::of(&diff.hunks, index);
         result.push(SideBySideLine {
             left: None,
             right: None,
             is_header: true,
+            separator,
         });
         display_index += 1;
+        if separator.fold.is_some() {
+            display_index += hunk.lines.len();
+            continue;
+        }
 
         let mut i = 0;
         let lines = &hunk.lines;
@@ -255,14 +445,21 @@ fn build_side_by_side_lines(diff: &ParsedDiff) -> Vec<SideBySideLine> {
                             content: line.content.clone(),
                             kind: DiffLineKind::Context,
                             display_index: line_index,
+                            no_newline: line.no_newline,
+                            crlf: line.crlf,
+                            emphasis: Vec::new(),
                         }),
                         right: Some(SideLine {
                             line_num: line.new_line.unwrap_or(0),
                             content: line.content.clone(),
                             kind: DiffLineKind::Context,
                             display_index: line_index,
+                            no_newline: line.no_newline,
+                            crlf: line.crlf,
+                            emphasis: Vec::new(),
                         }),
                         is_header: false,
+                        separator: HunkSeparator::default(),
                     });
                     i += 1;
                     display_index += 1;
@@ -280,26 +477,7 @@ fn build_side_by_side_lines(diff: &ParsedDiff) -> Vec<SideBySideLine> {
                         i += 1;
                         display_index += 1;
                     }
-                    let max_len = removals.len().max(additions.len());
-                    for j in 0..max_len {
-                        let left = removals.get(j).map(|(l, idx)| SideLine {
-                            line_num: l.old_line.unwrap_or(0),
-                            content: l.content.clone(),
-                            kind: DiffLineKind::Removed,
-                            display_index: *idx,
-                        });
-                        let right = additions.get(j).map(|(l, idx)| SideLine {
-                            line_num: l.new_line.unwrap_or(0),
-                            content: l.content.clone(),
-                            kind: DiffLineKind::Added,
-                            display_index: *idx,
-                        });
-                        result.push(SideBySideLine {
-                            left,
-                            right,
-                            is_header: false,
-                        });
-                    }
+                    result.extend(pair_changes(&removals, &additions));
                 }
                 DiffLineKind::Added => {
                     let line_index = display_index;
@@ -310,8 +488,12 @@ fn build_side_by_side_lines(diff: &ParsedDiff) -> Vec<SideBySideLine> {
                             content: line.content.clone(),
                             kind: DiffLineKind::Added,
                             display_index: line_index,
+                            no_newline: line.no_newline,
+                            crlf: line.crlf,
+                            emphasis: Vec::new(),
                         }),
                         is_header: false,
+                        separator: HunkSeparator::default(),
                     });
                     i += 1;
                     display_index += 1;
@@ -323,6 +505,54 @@ fn build_side_by_side_lines(diff: &ParsedDiff) -> Vec<SideBySideLine> {
     result
 }
 
+/// Side-by-side rows for a run of removals followed by additions: each
+/// removal sits across from the addition `diff::pair_lines` pairs it with,
+/// with the words that differ between them emphasized.
+fn pair_changes(
+    removals: &[(&DiffLine, usize)],
+    additions: &[(&DiffLine, usize)],
+) -> Vec<SideBySideLine> {
+    let old: Vec<&str> = removals.iter().map(|(l, _)| l.content.as_str()).collect();
+    let new: Vec<&str> = additions.iter().map(|(l, _)| l.content.as_str()).collect();
+    pair_lines(&old, &new)
+        .into_iter()
+        .map(|(old_index, new_index)| {
+            let removal = old_index.map(|k| removals[k]);
+            let addition = new_index.map(|k| additions[k]);
+            let (old_emphasis, new_emphasis) = match (removal, addition) {
+                (Some((old, _)), Some((new, _))) => {
+                    intraline_changes(&old.content, &new.content).unwrap_or_default()
+                }
+                _ => Default::default(),
+            };
+            let left = removal.map(|(l, idx)| SideLine {
+                line_num: l.old_line.unwrap_or(0),
+                content: l.content.clone(),
+                kind: DiffLineKind::Removed,
+                display_index: idx,
+                no_newline: l.no_newline,
+                crlf: l.crlf,
+                emphasis: old_emphasis,
+            });
+            let right = addition.map(|(l, idx)| SideLine {
+                line_num: l.new_line.unwrap_or(0),
+                content: l.content.clone(),
+                kind: DiffLineKind::Added,
+                display_index: idx,
+                no_newline: l.no_newline,
+                crlf: l.crlf,
+                emphasis: new_emphasis,
+            });
+            SideBySideLine {
+                left,
+                right,
+                is_header: false,
+                separator: HunkSeparator::default(),
+            }
+        })
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // Public stream rendering
 // ---------------------------------------------------------------------------
@@ -333,6 +563,8 @@ pub fn render_pinned_header_block(
     file_path: &str,
     theme: &Theme,
     counts: Option<ChangeCounts>,
+    badges: &[&str],
+    position: Option<FilePosition>,
 ) -> usize {
     let content_lines = 1usize;
     let height = block_height(content_lines) as u32;
@@ -343,6 +575,13 @@ pub fn render_pinned_header_block(
     // Pinned header doesn't participate in cursor tracking
     let dummy_max = std::cell::Cell::new(0);
     let dummy_stops = std::cell::RefCell::new(Vec::new());
+    let dummy_hunks = std::cell::RefCell::new(Vec::new());
+    let dummy_changes = std::cell::RefCell::new(Vec::new());
+    let dummy_positions = std::cell::RefCell::new(CommentPositions::new());
+    let dummy_thread_rows = std::cell::RefCell::new(CommentPositions::new());
+    let dummy_search_hits = std::cell::RefCell::new(Vec::new());
+    let no_muted = std::collections::BTreeSet::new();
+    let no_files = std::collections::HashMap::new();
     let mut cursor = StreamCursor {
         buffer,
         area: Rect::new(area.x, area.y, area.width, height),
@@ -354,27 +593,45 @@ pub fn render_pinned_header_block(
         max_stream_row: &dummy_max,
         selection: None,
         cursor_stops: &dummy_stops,
+        hunk_rows: &dummy_hunks,
+        change_rows: &dummy_changes,
+        comment_positions: &dummy_positions,
+        flash_comment: None,
+        thread_rows: &dummy_thread_rows,
+        muted_threads: &no_muted,
+        file_cache: &no_files,
+        search: None,
+        search_hits: &dummy_search_hits,
     };
 
-    for _ in 0..BLOCK_MARGIN {
+    for _ in 0..block_margin() {
         cursor.emit(|buf, y, _| {
             buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
         });
     }
-    for _ in 0..BLOCK_PADDING {
+    for _ in 0..block_padding() {
         cursor.emit(|buf, y, theme| {
             draw_block_base_line(buf, area, y, theme.panel_bg, theme);
         });
     }
     cursor.emit(|buf, y, theme| {
-        draw_file_header_line(buf, area, y, theme, file_path, counts);
+        let header = FileHeader {
+            path: file_path,
+            counts,
+            badges,
+            position,
+            viewed: position.is_some_and(|position| position.viewed),
+            folded: false,
+            is_cursor: false,
+        };
+        draw_file_header_line(buf, area, y, theme, &header);
     });
-    for _ in 0..BLOCK_PADDING {
+    for _ in 0..block_padding() {
         cursor.emit(|buf, y, theme| {
             draw_block_base_line(buf, area, y, theme.panel_bg, theme);
         });
     }
-    for _ in 0..BLOCK_MARGIN {
+    for _ in 0..block_margin() {
         cursor.emit(|buf, y, _| {
             buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
         });
@@ -383,28 +640,74 @@ pub fn render_pinned_header_block(
     height as usize
 }
 
-/// Render a description block at the top of the stream.
-/// Uses the same half-block border style as `emit_comment_block`.
-fn render_description_block(
+/// Render a verdict and its summary as a block with a colored heading.
+fn render_verdict_block(
     cursor: &mut StreamCursor<'_>,
     area: Rect,
-    description: &str,
-    _theme: &Theme,
+    vote: &ReviewVote,
+    theme: &Theme,
+) {
+    let (glyph, text, color) = match vote.vote {
+        Verdict::Approve => ("✓", Text::VerdictApproved, theme.success),
+        Verdict::RequestChanges => ("✗", Text::VerdictRequestedChanges, theme.error),
+    };
+    let heading = format!("{glyph} {}", tr_fmt(text, &[&vote.reviewer]));
+    let summary = verdict_summary(vote).unwrap_or_default();
+    render_text_block(cursor, area, Some((&heading, color)), summary);
+}
+
+/// Render the banner of an abandoned review: who abandoned it, in the
+/// warning color, and why.
+fn render_abandoned_banner(
+    cursor: &mut StreamCursor<'_>,
+    area: Rect,
+    review: &ReviewDetail,
+    theme: &Theme,
+) {
+    let status = review.status_changed_by.as_deref().map_or_else(
+        || tr(Text::AbandonedBanner).to_string(),
+        |by| tr_fmt(Text::AbandonedBy, &[&by]),
+    );
+    let heading = format!("{} {status}", glyphs().warning);
+    let reason = abandon_reason(review).unwrap_or_default();
+    render_text_block(cursor, area, Some((&heading, theme.warning)), reason);
+}
+
+/// Lines of a text block: the heading, a blank line, then `text` wrapped to
+/// `width`.
+fn text_block_lines(heading: Option<&str>, text: &str, width: u32) -> Vec<String> {
+    let mut lines: Vec<String> = heading.into_iter().map(str::to_string).collect();
+    if !text.is_empty() {
+        if heading.is_some() {
+            lines.push(String::new());
+        }
+        lines.extend(crate::text::wrap_text(text, width as usize));
+    }
+    lines
+}
+
+/// Render a block of wrapped text, like the description at the top of the
+/// stream. A `heading` goes on the first line in its color, separated from
+/// the text by a blank line. Uses the same half-block border style as
+/// `emit_comment_block`.
+fn render_text_block(
+    cursor: &mut StreamCursor<'_>,
+    area: Rect,
+    heading: Option<(&str, Rgba)>,
+    text: &str,
 ) {
     use crate::render_backend::Style;
-    use crate::text::wrap_text;
 
     let block = comment_block_area(area);
     let padded = comment_content_area(block);
-    let content_width = padded.width as usize;
-    let content_lines = wrap_text(description, content_width);
+    let content_lines = text_block_lines(heading.map(|(heading, _)| heading), text, padded.width);
 
-    let top_margin = BLOCK_MARGIN;
-    let bottom_margin = BLOCK_MARGIN;
-    let content_start = top_margin + BLOCK_PADDING;
+    let top_margin = block_margin();
+    let bottom_margin = block_margin();
+    let content_start = top_margin + block_padding();
     let content_end = content_start + content_lines.len();
     let total_rows = content_end
-        .saturating_add(BLOCK_PADDING)
+        .saturating_add(block_padding())
         .saturating_add(bottom_margin);
 
     for row in 0..total_rows {
@@ -421,7 +724,7 @@ fn render_description_block(
             let rc2 = block.x + block.width.saturating_sub(2);
             if row < top_margin {
                 buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
-            } else if row == top_margin {
+            } else if row == top_margin && row < content_start {
                 // Top border:  ▛▀…▀▜
                 buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
                 buffer_fill_rect(
@@ -437,16 +740,8 @@ fn render_description_block(
                     buffer_draw_text(buf, block.x + col, y, "▀", border_style);
                 }
                 buffer_draw_text(buf, rc2, y, "▜", border_style);
-            } else if row < content_start {
-                // Padding rows: ▌▌ ... ▐▐
-                buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
-                buffer_fill_rect(buf, block.x, y, block.width, 1, block_bg);
-                buffer_draw_text(buf, block.x, y, "▌", bar_style);
-                buffer_draw_text(buf, block.x + 1, y, "▌", bar_style);
-                buffer_draw_text(buf, rc2, y, "▐", bar_style);
-                buffer_draw_text(buf, rc, y, "▐", bar_style);
             } else if row < content_end {
-                // Content rows: ▌▌ text ▐▐
+                // Padding and content rows: ▌▌ text ▐▐
                 buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
                 buffer_fill_rect(buf, block.x, y, block.width, 1, block_bg);
                 buffer_draw_text(buf, block.x, y, "▌", bar_style);
@@ -454,6 +749,12 @@ fn render_description_block(
                 buffer_draw_text(buf, rc2, y, "▐", bar_style);
                 buffer_draw_text(buf, rc, y, "▐", bar_style);
                 if let Some(text) = line_text {
+                    let left_style = match heading {
+                        Some((_, color)) if row == content_start => {
+                            Style::fg(color).with_bg(block_bg).with_bold()
+                        }
+                        _ => theme.style_foreground_on(block_bg),
+                    };
                     draw_plain_line_with_right(
                         buf,
                         padded,
@@ -462,14 +763,14 @@ fn render_description_block(
                         &PlainLineContent {
                             left: text,
                             right: None,
-                            left_style: theme.style_foreground_on(block_bg),
+                            left_style,
                             right_style: theme.style_muted_on(block_bg),
                         },
                     );
                 }
-            } else if row < content_end + BLOCK_PADDING {
+            } else if row < content_end + block_padding() {
                 buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
-                if row == content_end + BLOCK_PADDING - 1 {
+                if row == content_end + block_padding() - 1 {
                     // Bottom border:  ▙▄…▄▟
                     buffer_fill_rect(
                         buf,
@@ -502,30 +803,132 @@ fn render_description_block(
 pub struct DiffStreamParams<'a> {
     pub files: &'a [crate::model::FileEntry],
     pub file_cache: &'a std::collections::HashMap<String, crate::model::FileCacheEntry>,
+    /// Files not in `file_cache` yet, drawn as a loading placeholder
+    pub pending_files: &'a [crate::db::FileData],
     pub threads: &'a [ThreadSummary],
     pub all_comments: &'a std::collections::HashMap<String, Vec<crate::db::Comment>>,
+    pub thread_events: &'a std::collections::HashMap<String, Vec<crate::db::ThreadEvent>>,
     pub scroll: usize,
     pub diff_cursor: usize,
     pub theme: &'a Theme,
     pub view_mode: crate::model::DiffViewMode,
+    /// Files drawn in another mode than `view_mode`
+    pub view_mode_overrides: &'a std::collections::HashMap<String, crate::model::DiffViewMode>,
     pub wrap: bool,
+    pub show_cr: bool,
     pub thread_positions: &'a std::cell::RefCell<std::collections::HashMap<String, usize>>,
     pub max_stream_row: &'a std::cell::Cell<usize>,
     pub description: Option<&'a str>,
+    /// Verdicts, drawn as summary blocks above the description
+    pub votes: &'a [ReviewVote],
+    /// The review, when it's abandoned: a banner above the verdicts
+    pub abandoned: Option<&'a ReviewDetail>,
     pub selection: Option<(usize, usize)>,
     pub line_map: &'a std::cell::RefCell<std::collections::HashMap<usize, i64>>,
+    /// Stream row → old-side line, recorded in side-by-side mode
+    pub old_line_map: &'a std::cell::RefCell<std::collections::HashMap<usize, i64>>,
+    /// Focused side-by-side column
+    pub sbs_side: ThreadSide,
     pub cursor_stops: &'a std::cell::RefCell<Vec<usize>>,
+    /// Stream rows of hunk headers, recorded while rendering
+    pub hunk_rows: &'a std::cell::RefCell<Vec<usize>>,
+    /// Stream rows where runs of added/removed lines start, recorded while
+    /// rendering
+    pub change_rows: &'a std::cell::RefCell<Vec<usize>>,
+    /// Stream rows of each comment, recorded while rendering
+    pub comment_positions: &'a std::cell::RefCell<CommentPositions>,
+    /// Comment to highlight (just jumped to)
+    pub flash_comment: Option<&'a str>,
+    /// Stream rows of each thread's comment block, recorded while rendering
+    pub thread_rows: &'a std::cell::RefCell<CommentPositions>,
+    /// Threads drawn collapsed
+    pub muted_threads: &'a std::collections::BTreeSet<String>,
+    /// Prose files drawn as a word diff
+    pub word_diff_files: &'a std::collections::HashSet<String>,
+    /// Files marked viewed, drawn as their header only
+    pub viewed_files: &'a std::collections::HashSet<String>,
+    /// Folded hunks and files
+    pub folds: &'a crate::stream::Folds,
+    /// Lines of file content shown around threads outside the diff
+    pub context_lines: i64,
+    /// `/` search query to highlight
+    pub search: Option<&'a str>,
+    /// Blame by path, with the blame column on
+    pub blame: Option<&'a std::collections::HashMap<String, Vec<crate::vcs::BlameLine>>>,
+    /// Search matches, recorded while rendering
+    pub search_hits: &'a std::cell::RefCell<Vec<SearchHit>>,
+    /// Keeps each file's recordings so files off screen can be skipped;
+    /// `None` renders every file
+    pub stream_cache: Option<&'a std::cell::RefCell<crate::stream::StreamCache>>,
 }
 
+/// Banner above a file's hunks: what changed in a binary file, then
+/// recoverable parse problems. Row count must match
+/// `layout::diff_banner_rows`.
+fn render_diff_banner(cursor: &mut StreamCursor<'_>, area: Rect, diff: &ParsedDiff) {
+    use crate::render_backend::Style;
+
+    if let Some(binary) = &diff.binary {
+        let text = format!("{} {}", glyphs().binary, binary.summary());
+        cursor.emit(|buf, y, theme| {
+            draw_block_text_line(
+                buf,
+                area,
+                y,
+                theme.panel_bg,
+                &text,
+                Style::fg(theme.muted),
+                theme,
+            );
+        });
+    }
+    let warnings = &diff.warnings;
+    for warning in warnings.iter().take(MAX_PARSE_WARNING_ROWS) {
+        let text = format!("{} {warning}", glyphs().warning);
+        cursor.emit(|buf, y, theme| {
+            draw_block_text_line(
+                buf,
+                area,
+                y,
+                theme.panel_bg,
+                &text,
+                Style::fg(theme.warning),
+                theme,
+            );
+        });
+    }
+    if warnings.len() > MAX_PARSE_WARNING_ROWS {
+        let text = format!(
+            "{} … and {} more parse warnings",
+            glyphs().warning,
+            warnings.len() - MAX_PARSE_WARNING_ROWS
+        );
+        cursor.emit(|buf, y, theme| {
+            draw_block_text_line(
+                buf,
+                area,
+                y,
+                theme.panel_bg,
+                &text,
+                Style::fg(theme.warning),
+                theme,
+            );
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn render_file_with_diff(
     cursor: &mut StreamCursor<'_>,
     area: Rect,
+    path: &str,
     diff: &ParsedDiff,
     entry: &crate::model::FileCacheEntry,
     file_threads: &[&ThreadSummary],
     view_mode: crate::model::DiffViewMode,
     sctx: &StreamRenderCtx<'_>,
 ) {
+    let diff = &*sctx.folds.apply(path, diff);
     let anchors = map_threads_to_diff(diff, file_threads);
     let anchored_ids: std::collections::HashSet<&str> =
         anchors.iter().map(|a| a.thread_id.as_str()).collect();
@@ -541,8 +944,12 @@ fn render_file_with_diff(
             let hunk_ranges = crate::diff::hunk_exclusion_ranges(&diff.hunks);
             #[allow(clippy::cast_possible_wrap)]
             let total_lines = content.start_line + content.lines.len() as i64 - 1;
-            let ranges =
-                calculate_context_ranges(&orphaned_deref, total_lines as usize, &hunk_ranges);
+            let ranges = calculate_context_ranges(
+                &orphaned_deref,
+                total_lines as usize,
+                &hunk_ranges,
+                sctx.context_lines,
+            );
             let sections = group_context_ranges_by_hunks(ranges, &hunk_ranges);
             if sections.iter().any(|section| !section.is_empty()) {
                 orphaned_context = Some(OrphanedContext {
@@ -555,6 +962,8 @@ fn render_file_with_diff(
             }
         }
     }
+    render_diff_banner(cursor, area, diff);
+
     let line_area = diff_margin_area(area);
     let ctx = DiffRenderCtx {
         line_area,
@@ -562,11 +971,24 @@ fn render_file_with_diff(
         threads: file_threads,
         file_highlights: &entry.highlighted_lines,
         wrap: sctx.wrap,
+        show_cr: sctx.show_cr,
         all_comments: sctx.all_comments,
+        thread_events: sctx.thread_events,
         thread_positions: sctx.thread_positions,
         line_map: sctx.line_map,
+        old_line_map: sctx.old_line_map,
+        sbs_side: sctx.sbs_side,
+        blame: sctx
+            .blame
+            .map(|blame| blame.get(path).map_or(&[][..], Vec::as_slice)),
     };
 
+    // Combined (merge) diffs only have an annotated unified rendering
+    let view_mode = if diff.is_combined() {
+        crate::model::DiffViewMode::Unified
+    } else {
+        view_mode
+    };
     let emitted_threads = match view_mode {
         crate::model::DiffViewMode::Unified => render_file_diff_unified(
             cursor,
@@ -593,26 +1015,113 @@ fn render_file_with_diff(
             area,
             context,
             sctx.all_comments,
+            sctx.thread_events,
             sctx.thread_positions,
             &emitted_threads,
         );
     } else if !orphaned_threads.is_empty() {
-        let mut orphaned_sorted = orphaned_threads.clone();
+        let mut orphaned_sorted: Vec<&ThreadSummary> =
+            orphaned_threads.iter().map(|t| **t).collect();
         orphaned_sorted.sort_by_key(|t| t.selection_start);
-        for thread in &orphaned_sorted {
-            sctx.thread_positions
-                .borrow_mut()
-                .insert(thread.thread_id.clone(), cursor.stream_row);
-            if let Some(comments) = sctx.all_comments.get(&thread.thread_id) {
-                let rows = comment_block_rows(thread, comments, area);
-                let is_cursor = cursor.is_cursor_at(rows);
-                let hl = is_cursor || cursor.is_selected_at(rows);
-                emit_comment_block(cursor, area, thread, comments, hl, is_cursor);
-            }
+        emit_thread_blocks(cursor, area, &orphaned_sorted, sctx);
+    }
+}
+
+/// A prose file's word diff. Its threads follow the hunks, in line order,
+/// since word diff lines don't map one-to-one to the diff's.
+fn render_file_words(
+    cursor: &mut StreamCursor<'_>,
+    area: Rect,
+    diff: &ParsedDiff,
+    word_lines: &[Vec<crate::diff::WordLine>],
+    file_threads: &[&ThreadSummary],
+    sctx: &StreamRenderCtx<'_>,
+) {
+    render_diff_banner(cursor, area, diff);
+    let ctx = DiffRenderCtx {
+        line_area: diff_margin_area(area),
+        area,
+        threads: file_threads,
+        file_highlights: &[],
+        wrap: true,
+        show_cr: sctx.show_cr,
+        all_comments: sctx.all_comments,
+        thread_events: sctx.thread_events,
+        thread_positions: sctx.thread_positions,
+        line_map: sctx.line_map,
+        old_line_map: sctx.old_line_map,
+        sbs_side: sctx.sbs_side,
+        blame: None,
+    };
+    word::render_file_diff_words(cursor, word_lines, &ctx);
+
+    let mut sorted = file_threads.to_vec();
+    sorted.sort_by_key(|t| t.selection_start);
+    emit_thread_blocks(cursor, area, &sorted, sctx);
+}
+
+/// Comment blocks for threads with no line to follow (file-level, orphaned),
+/// one after another at the cursor.
+fn emit_thread_blocks(
+    cursor: &mut StreamCursor<'_>,
+    area: Rect,
+    threads: &[&ThreadSummary],
+    sctx: &StreamRenderCtx<'_>,
+) {
+    for thread in threads {
+        sctx.thread_positions
+            .borrow_mut()
+            .insert(thread.thread_id.clone(), cursor.stream_row);
+        if let Some(comments) = sctx.all_comments.get(&thread.thread_id) {
+            let events = events_for(sctx.thread_events, &thread.thread_id);
+            let rows = comment_block_rows(cursor, thread, comments, events, area);
+            let is_cursor = cursor.is_cursor_at(rows);
+            let hl = is_cursor || cursor.is_selected_at(rows);
+            emit_comment_block(cursor, area, thread, comments, events, hl, is_cursor);
         }
     }
 }
 
+/// A file-content line wrapped to the pane, one emitted row per wrapped row.
+fn emit_wrapped_content_line(
+    cursor: &mut StreamCursor<'_>,
+    line_area: Rect,
+    line_num: i64,
+    content: &str,
+    highlight: Option<&Vec<HighlightSpan>>,
+    show_thread_bar: bool,
+) {
+    let line_num_width = SBS_LINE_NUM_WIDTH;
+    let content_width = diff_content_width(line_area).saturating_sub(line_num_width) as usize;
+    let wrapped = wrap_content(highlight, content, content_width);
+    let rows = wrapped.len().max(1);
+    let is_cursor = cursor.is_cursor_at(rows);
+    let is_selected = cursor.is_selected_at(rows);
+    let matches =
+        cursor.search_matches(content, cursor.stream_row, content_width, SearchKind::Line);
+    cursor.emit_rows(rows, |buf, y, theme, row| {
+        render_context_line_wrapped_row(
+            buf,
+            y,
+            line_num,
+            theme,
+            &LineRenderCtx {
+                area: line_area,
+                anchor: None,
+                show_thread_bar,
+                is_cursor,
+                is_selected,
+                show_cr: false,
+                cursor_side: None,
+                matches: &matches,
+                left_matches: &[],
+            },
+            &wrapped,
+            row,
+        );
+    });
+}
+
 fn render_file_content_no_diff(
     cursor: &mut StreamCursor<'_>,
     area: Rect,
@@ -624,8 +1133,13 @@ fn render_file_content_no_diff(
     let line_area = diff_margin_area(area);
     let start_line = content.start_line;
     let thread_ranges = build_thread_ranges(file_threads);
-    let display_items =
-        build_context_items(content.lines.as_slice(), file_threads, &[], start_line);
+    let display_items = build_context_items(
+        content.lines.as_slice(),
+        file_threads,
+        &[],
+        start_line,
+        sctx.context_lines,
+    );
     for item in display_items {
         let show_thread_bar = match &item {
             DisplayItem::Line { line_num, .. } => {
@@ -647,41 +1161,26 @@ fn render_file_content_no_diff(
                         false,
                         false,
                         start_line,
+                        &[],
                     );
                 });
             }
             DisplayItem::Line { line_num, content } => {
                 cursor.mark_cursor_stop();
                 if sctx.wrap {
-                    let line_index = (*line_num - start_line) as usize;
-                    let highlight = file_highlights.get(line_index);
-                    let line_num_width = SBS_LINE_NUM_WIDTH;
-                    let content_width =
-                        diff_content_width(line_area).saturating_sub(line_num_width) as usize;
-                    let wrapped = wrap_content(highlight, content, content_width);
-                    let rows = wrapped.len().max(1);
-                    let is_cursor = cursor.is_cursor_at(rows);
-                    let is_selected = cursor.is_selected_at(rows);
-                    cursor.emit_rows(rows, |buf, y, theme, row| {
-                        render_context_line_wrapped_row(
-                            buf,
-                            y,
-                            *line_num,
-                            theme,
-                            &LineRenderCtx {
-                                area: line_area,
-                                anchor: None,
-                                show_thread_bar,
-                                is_cursor,
-                                is_selected,
-                            },
-                            &wrapped,
-                            row,
-                        );
-                    });
+                    emit_wrapped_content_line(
+                        cursor,
+                        line_area,
+                        *line_num,
+                        content,
+                        file_highlights.get((*line_num - start_line) as usize),
+                        show_thread_bar,
+                    );
                 } else {
                     let is_cursor = cursor.is_cursor_at(1);
                     let is_selected = cursor.is_selected_at(1);
+                    let matches =
+                        cursor.search_matches(content, cursor.stream_row, 0, SearchKind::Line);
                     cursor.emit(|buf, y, theme| {
                         render_context_item_block(
                             buf,
@@ -694,6 +1193,7 @@ fn render_file_content_no_diff(
                             is_cursor,
                             is_selected,
                             start_line,
+                            &matches,
                         );
                     });
                 }
@@ -710,10 +1210,11 @@ fn render_file_content_no_diff(
                     .entry(thread.thread_id.clone())
                     .or_insert(cursor.stream_row);
                 if let Some(comments) = sctx.all_comments.get(&thread.thread_id) {
-                    let rows = comment_block_rows(thread, comments, area);
+                    let events = events_for(sctx.thread_events, &thread.thread_id);
+                    let rows = comment_block_rows(cursor, thread, comments, events, area);
                     let is_cursor = cursor.is_cursor_at(rows);
                     let hl = is_cursor || cursor.is_selected_at(rows);
-                    emit_comment_block(cursor, area, thread, comments, hl, is_cursor);
+                    emit_comment_block(cursor, area, thread, comments, events, hl, is_cursor);
                 }
             }
         }
@@ -726,30 +1227,48 @@ fn render_file_header(
     file: &crate::model::FileEntry,
     file_cache: &std::collections::HashMap<String, crate::model::FileCacheEntry>,
     theme: &Theme,
+    viewed: bool,
+    folded: bool,
 ) {
-    for _ in 0..BLOCK_MARGIN {
+    for _ in 0..block_margin() {
         cursor.emit(|buf, y, _| {
             buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
         });
     }
-    for _ in 0..BLOCK_PADDING {
+    for _ in 0..block_padding() {
         cursor.emit(|buf, y, theme| {
             draw_block_base_line(buf, area, y, theme.panel_bg, theme);
         });
     }
-    let counts = file_cache
-        .get(&file.path)
+    let entry = file_cache.get(&file.path);
+    let counts = entry
         .and_then(|entry| entry.diff.as_ref())
         .map(diff_change_counts);
+    let badges = entry.map(file_lint_badges).unwrap_or_default();
+    cursor.search_matches(&file.path, cursor.stream_row, 0, SearchKind::File);
+    // A folded file's header stands in for its lines
+    if folded {
+        cursor.mark_cursor_stop();
+    }
+    let is_cursor = folded && cursor.is_cursor_at(1);
     cursor.emit(|buf, y, theme| {
-        draw_file_header_line(buf, area, y, theme, &file.path, counts);
+        let header = FileHeader {
+            path: &file.path,
+            counts,
+            badges: &badges,
+            position: None,
+            viewed,
+            folded,
+            is_cursor,
+        };
+        draw_file_header_line(buf, area, y, theme, &header);
     });
-    for _ in 0..BLOCK_PADDING {
+    for _ in 0..block_padding() {
         cursor.emit(|buf, y, theme| {
             draw_block_base_line(buf, area, y, theme.panel_bg, theme);
         });
     }
-    for _ in 0..BLOCK_MARGIN {
+    for _ in 0..block_margin() {
         cursor.emit(|buf, y, _| {
             buffer_fill_rect(buf, area.x, y, area.width, 1, theme.background);
         });
@@ -760,7 +1279,7 @@ struct UnifiedDisplayData<'a> {
     display_lines: Vec<DisplayLine>,
     anchor_map: AnchorMap<'a>,
     comment_map: AnchorMap<'a>,
-    thread_ranges: Vec<(i64, i64)>,
+    thread_ranges: ThreadRanges,
 }
 
 fn build_unified_display_data<'a>(
@@ -771,23 +1290,30 @@ fn build_unified_display_data<'a>(
     let mut anchor_map: AnchorMap<'_> = std::collections::HashMap::new();
     let mut comment_map: AnchorMap<'_> = std::collections::HashMap::new();
     for anchor in anchors {
-        anchor_map
-            .entry(anchor.display_line)
-            .or_default()
-            .push(anchor);
+        // Hunk threads have no line to mark; their position is the block
+        if anchor.hunk.is_none() {
+            anchor_map
+                .entry(anchor.display_line)
+                .or_default()
+                .push(anchor);
+        }
         comment_map
             .entry(anchor.comment_after_line)
             .or_default()
             .push(anchor);
     }
 
-    let thread_ranges = build_thread_ranges(threads);
+    let thread_ranges = ThreadRanges::new(threads, anchors);
 
     let mut display_lines: Vec<DisplayLine> = Vec::new();
-    for hunk in hunks {
-        display_lines.push(DisplayLine::HunkHeader);
+    for (index, hunk) in hunks.iter().enumerate() {
+        display_lines.push(DisplayLine::HunkHeader(HunkSeparator::of(hunks, index)));
         for line in &hunk.lines {
-            display_lines.push(DisplayLine::Diff(line.clone()));
+            display_lines.push(if hunk.folded {
+                DisplayLine::Folded
+            } else {
+                DisplayLine::Diff(line.clone())
+            });
         }
     }
 
@@ -822,10 +1348,11 @@ fn try_emit_line_comment(
             continue;
         };
         if let Some(comments) = ctx.all_comments.get(&comment_anchor.thread_id) {
-            let rows = comment_block_rows(thread, comments, ctx.area);
+            let events = events_for(ctx.thread_events, &comment_anchor.thread_id);
+            let rows = comment_block_rows(cursor, thread, comments, events, ctx.area);
             let is_cursor = cursor.is_cursor_at(rows);
             let hl = is_cursor || cursor.is_selected_at(rows);
-            emit_comment_block(cursor, ctx.area, thread, comments, hl, is_cursor);
+            emit_comment_block(cursor, ctx.area, thread, comments, events, hl, is_cursor);
         }
     }
 }
@@ -841,7 +1368,10 @@ fn render_unified_display_items(
 ) -> usize {
     let mut section_idx = 0usize;
     for (idx, display_line) in display_data.display_lines.iter().enumerate() {
-        if matches!(display_line, DisplayLine::HunkHeader) {
+        if matches!(display_line, DisplayLine::Folded) {
+            continue;
+        }
+        if matches!(display_line, DisplayLine::HunkHeader(_)) {
             if let Some(context) = orphaned_context {
                 if let Some(section) = context.sections.get(section_idx) {
                     emit_orphaned_context_section(
@@ -853,6 +1383,7 @@ fn render_unified_display_items(
                         ctx.wrap,
                         &mut OrphanedRenderState {
                             all_comments: ctx.all_comments,
+                            thread_events: ctx.thread_events,
                             thread_positions: ctx.thread_positions,
                             emitted_threads,
                             last_line_num,
@@ -863,10 +1394,10 @@ fn render_unified_display_items(
             section_idx = section_idx.saturating_add(1);
         }
         let show_thread_bar = match display_line {
-            DisplayLine::Diff(line) => {
-                line_in_thread_ranges(line.new_line.map(i64::from), &display_data.thread_ranges)
-            }
-            DisplayLine::HunkHeader => false,
+            DisplayLine::Diff(line) => display_data
+                .thread_ranges
+                .contains(line.old_line, line.new_line),
+            DisplayLine::HunkHeader(_) | DisplayLine::Folded => false,
         };
         let anchors_at_line = display_data.anchor_map.get(&idx);
         let anchor = anchors_at_line.and_then(|v: &Vec<&ThreadAnchor>| v.first().copied());
@@ -879,7 +1410,19 @@ fn render_unified_display_items(
             }
         }
         match display_line {
-            DisplayLine::HunkHeader => {
+            DisplayLine::Folded => {}
+            DisplayLine::HunkHeader(separator) => {
+                cursor.mark_hunk_start();
+                let fold = separator.fold;
+                // A folded hunk's summary is where the cursor stops in it
+                if let Some(fold) = fold {
+                    cursor.mark_cursor_stop();
+                    if fold.is_change() {
+                        cursor.mark_change_start();
+                    }
+                }
+                let is_cursor = fold.is_some() && cursor.is_cursor_at(1);
+                let is_selected = fold.is_some() && cursor.is_selected_at(1);
                 cursor.emit(|buf, y, theme| {
                     render_unified_diff_line_block(
                         buf,
@@ -890,8 +1433,12 @@ fn render_unified_display_items(
                             area: ctx.line_area,
                             anchor,
                             show_thread_bar,
-                            is_cursor: false,
-                            is_selected: false,
+                            is_cursor,
+                            is_selected,
+                            show_cr: false,
+                            cursor_side: None,
+                            matches: &[],
+                            left_matches: &[],
                         },
                         ctx.file_highlights.get(idx),
                     );
@@ -899,6 +1446,18 @@ fn render_unified_display_items(
             }
             DisplayLine::Diff(line) => {
                 cursor.mark_cursor_stop();
+                let blame = ctx.blame.map(|blame| {
+                    line.new_line
+                        .and_then(|n| (n as usize).checked_sub(1))
+                        .and_then(|i| blame.get(i))
+                });
+                let line_area = blame.map_or(ctx.line_area, |_| blame_line_area(ctx.line_area));
+                let follows_change = idx
+                    .checked_sub(1)
+                    .is_some_and(|prev| display_data.display_lines[prev].is_change());
+                if display_line.is_change() && !follows_change {
+                    cursor.mark_change_start();
+                }
                 // Record new-side line mapping for comment targeting
                 if let Some(nl) = line.new_line {
                     let base = cursor.stream_row;
@@ -906,7 +1465,7 @@ fn render_unified_display_items(
                     if ctx.wrap {
                         let thread_col_width = THREAD_COL_WIDTH;
                         let line_num_width = UNIFIED_LINE_NUM_WIDTH;
-                        let cw = diff_content_width(ctx.line_area)
+                        let cw = diff_content_width(line_area)
                             .saturating_sub(thread_col_width + line_num_width);
                         let max_c = cw.saturating_sub(2) as usize;
                         let row_count =
@@ -924,7 +1483,7 @@ fn render_unified_display_items(
                 if ctx.wrap {
                     let thread_col_width = THREAD_COL_WIDTH;
                     let line_num_width = UNIFIED_LINE_NUM_WIDTH;
-                    let content_width = diff_content_width(ctx.line_area)
+                    let content_width = diff_content_width(line_area)
                         .saturating_sub(thread_col_width + line_num_width);
                     let max_content = content_width.saturating_sub(2) as usize;
                     let wrapped =
@@ -932,6 +1491,12 @@ fn render_unified_display_items(
                     let rows = wrapped.len().max(1);
                     let is_cursor = cursor.is_cursor_at(rows);
                     let is_selected = cursor.is_selected_at(rows);
+                    let matches = cursor.search_matches(
+                        &line.content,
+                        cursor.stream_row,
+                        max_content,
+                        SearchKind::Line,
+                    );
                     cursor.emit_rows(rows, |buf, y, theme, row| {
                         render_unified_diff_line_wrapped_row(
                             buf,
@@ -939,19 +1504,41 @@ fn render_unified_display_items(
                             line,
                             theme,
                             &LineRenderCtx {
-                                area: ctx.line_area,
+                                area: line_area,
                                 anchor,
                                 show_thread_bar,
                                 is_cursor,
                                 is_selected,
+                                show_cr: ctx.show_cr,
+                                cursor_side: None,
+                                matches: &matches,
+                                left_matches: &[],
                             },
                             &wrapped,
                             row,
                         );
+                        if let Some(blame) = blame {
+                            let blame = blame.filter(|_| row == 0);
+                            draw_blame_gutter(
+                                buf,
+                                ctx.line_area,
+                                y,
+                                blame,
+                                is_cursor,
+                                is_selected,
+                                theme,
+                            );
+                        }
                     });
                 } else {
                     let is_cursor = cursor.is_cursor_at(1);
                     let is_selected = cursor.is_selected_at(1);
+                    let matches = cursor.search_matches(
+                        &line.content,
+                        cursor.stream_row,
+                        0,
+                        SearchKind::Line,
+                    );
                     cursor.emit(|buf, y, theme| {
                         render_unified_diff_line_block(
                             buf,
@@ -959,14 +1546,29 @@ fn render_unified_display_items(
                             display_line,
                             theme,
                             &LineRenderCtx {
-                                area: ctx.line_area,
+                                area: line_area,
                                 anchor,
                                 show_thread_bar,
                                 is_cursor,
                                 is_selected,
+                                show_cr: ctx.show_cr,
+                                cursor_side: None,
+                                matches: &matches,
+                                left_matches: &[],
                             },
                             ctx.file_highlights.get(idx),
                         );
+                        if let Some(blame) = blame {
+                            draw_blame_gutter(
+                                buf,
+                                ctx.line_area,
+                                y,
+                                blame,
+                                is_cursor,
+                                is_selected,
+                                theme,
+                            );
+                        }
                     });
                 }
             }
@@ -1009,6 +1611,7 @@ fn render_file_diff_unified(
                 ctx.wrap,
                 &mut OrphanedRenderState {
                     all_comments: ctx.all_comments,
+                    thread_events: ctx.thread_events,
                     thread_positions: ctx.thread_positions,
                     emitted_threads: &mut emitted_threads,
                     last_line_num: &mut last_line_num,
@@ -1028,15 +1631,30 @@ fn build_sbs_anchor_maps<'a>(
 ) -> (AnchorMap<'a>, AnchorMap<'a>) {
     let mut sbs_anchor_map: AnchorMap<'_> = std::collections::HashMap::new();
     let mut sbs_comment_map: AnchorMap<'_> = std::collections::HashMap::new();
+    let headers: Vec<usize> = sbs_lines
+        .iter()
+        .enumerate()
+        .filter_map(|(si, sl)| sl.is_header.then_some(si))
+        .collect();
     for anchor in anchors {
+        if let Some(hunk) = anchor.hunk {
+            if let Some(&si) = headers.get(hunk) {
+                sbs_comment_map.entry(si).or_default().push(anchor);
+            }
+            continue;
+        }
         if let Some(thread) = threads.iter().find(|t| t.thread_id == anchor.thread_id) {
             let start = thread.selection_start as u32;
             let end = thread.selection_end.unwrap_or(thread.selection_start) as u32;
             for (si, sl) in sbs_lines.iter().enumerate() {
-                if sl.right.as_ref().is_some_and(|l| l.line_num == start) {
+                let side_line = match anchor.side {
+                    ThreadSide::Old => sl.left.as_ref(),
+                    ThreadSide::New => sl.right.as_ref(),
+                };
+                if side_line.is_some_and(|l| l.line_num == start) {
                     sbs_anchor_map.entry(si).or_default().push(anchor);
                 }
-                if sl.right.as_ref().is_some_and(|l| l.line_num == end) {
+                if side_line.is_some_and(|l| l.line_num == end) {
                     sbs_comment_map.entry(si).or_default().push(anchor);
                 }
             }
@@ -1085,6 +1703,13 @@ fn render_sbs_line(
         let right_rows = right_wrapped.as_ref().map_or(1, Vec::len);
         let rows = left_rows.max(right_rows);
 
+        let (left_matches, right_matches) =
+            sbs_search_matches(cursor, sbs_line, ctx.cursor_side, left_width, right_width);
+        let ctx = &LineRenderCtx {
+            matches: &right_matches,
+            left_matches: &left_matches,
+            ..*ctx
+        };
         cursor.emit_rows(rows, |buf, y, theme, row| {
             render_side_by_side_line_wrapped_row(
                 buf,
@@ -1097,12 +1722,48 @@ fn render_sbs_line(
             );
         });
     } else {
+        let (left_matches, right_matches) =
+            sbs_search_matches(cursor, sbs_line, ctx.cursor_side, 0, 0);
+        let ctx = &LineRenderCtx {
+            matches: &right_matches,
+            left_matches: &left_matches,
+            ..*ctx
+        };
         cursor.emit(|buf, y, theme| {
             render_side_by_side_line_block(buf, y, sbs_line, theme, ctx, file_highlights);
         });
     }
 }
 
+/// Search matches in each column of a side-by-side line, wrapped to each
+/// column's own width. Only the focused column records hits.
+fn sbs_search_matches(
+    cursor: &StreamCursor<'_>,
+    sbs_line: &SideBySideLine,
+    side: Option<ThreadSide>,
+    left_width: usize,
+    right_width: usize,
+) -> (Vec<std::ops::Range<usize>>, Vec<std::ops::Range<usize>>) {
+    let Some(query) = cursor.search else {
+        return (Vec::new(), Vec::new());
+    };
+    let (left, right, hits) = column_matches(
+        query,
+        sbs_line
+            .left
+            .as_ref()
+            .map(|line| (line.content.as_str(), left_width)),
+        sbs_line
+            .right
+            .as_ref()
+            .map(|line| (line.content.as_str(), right_width)),
+        side.unwrap_or(ThreadSide::New),
+        cursor.stream_row,
+    );
+    cursor.search_hits.borrow_mut().extend(hits);
+    (left, right)
+}
+
 #[allow(clippy::too_many_lines)]
 fn render_file_diff_sbs(
     cursor: &mut StreamCursor<'_>,
@@ -1114,7 +1775,7 @@ fn render_file_diff_sbs(
     let mut emitted_threads: std::collections::HashSet<String> = std::collections::HashSet::new();
     let mut last_line_num: Option<i64> = None;
 
-    let thread_ranges = build_thread_ranges(ctx.threads);
+    let thread_ranges = ThreadRanges::new(ctx.threads, anchors);
     let (sbs_anchor_map, sbs_comment_map) = build_sbs_anchor_maps(anchors, ctx.threads, sbs_lines);
 
     let mut section_idx = 0usize;
@@ -1131,6 +1792,7 @@ fn render_file_diff_sbs(
                         ctx.wrap,
                         &mut OrphanedRenderState {
                             all_comments: ctx.all_comments,
+                            thread_events: ctx.thread_events,
                             thread_positions: ctx.thread_positions,
                             emitted_threads: &mut emitted_threads,
                             last_line_num: &mut last_line_num,
@@ -1143,9 +1805,9 @@ fn render_file_diff_sbs(
         let show_thread_bar = if sbs_line.is_header {
             false
         } else {
-            line_in_thread_ranges(
-                sbs_line.right.as_ref().map(|line| i64::from(line.line_num)),
-                &thread_ranges,
+            thread_ranges.contains(
+                sbs_line.left.as_ref().map(|line| line.line_num),
+                sbs_line.right.as_ref().map(|line| line.line_num),
             )
         };
         let anchors_at_line = sbs_anchor_map.get(&idx);
@@ -1182,22 +1844,43 @@ fn render_file_diff_sbs(
             1
         };
 
-        // Record new-side line mapping for comment targeting (right side = new)
+        // Record line mappings for comment targeting (left = old, right = new)
         if !sbs_line.is_header {
-            if let Some(right) = &sbs_line.right {
-                let nl = i64::from(right.line_num);
-                let base = cursor.stream_row;
-                let mut lm = ctx.line_map.borrow_mut();
-                for r in 0..sbs_rows {
-                    lm.insert(base + r, nl);
+            let base = cursor.stream_row;
+            let sides = [
+                (&sbs_line.left, ctx.old_line_map),
+                (&sbs_line.right, ctx.line_map),
+            ];
+            for (side, map) in sides {
+                if let Some(line) = side {
+                    let num = i64::from(line.line_num);
+                    let mut lm = map.borrow_mut();
+                    for r in 0..sbs_rows {
+                        lm.insert(base + r, num);
+                    }
                 }
             }
         }
-        if !sbs_line.is_header {
+        if sbs_line.is_header {
+            cursor.mark_hunk_start();
+            if let Some(fold) = sbs_line.separator.fold {
+                cursor.mark_cursor_stop();
+                if fold.is_change() {
+                    cursor.mark_change_start();
+                }
+            }
+        } else {
             cursor.mark_cursor_stop();
+            let follows_change = idx
+                .checked_sub(1)
+                .is_some_and(|prev| sbs_lines[prev].is_change());
+            if sbs_line.is_change() && !follows_change {
+                cursor.mark_change_start();
+            }
         }
-        let is_cursor = !sbs_line.is_header && cursor.is_cursor_at(sbs_rows);
-        let is_selected = !sbs_line.is_header && cursor.is_selected_at(sbs_rows);
+        let stop = !sbs_line.is_header || sbs_line.separator.fold.is_some();
+        let is_cursor = stop && cursor.is_cursor_at(sbs_rows);
+        let is_selected = stop && cursor.is_selected_at(sbs_rows);
         render_sbs_line(
             cursor,
             sbs_line,
@@ -1207,6 +1890,10 @@ fn render_file_diff_sbs(
                 show_thread_bar,
                 is_cursor,
                 is_selected,
+                show_cr: ctx.show_cr,
+                cursor_side: Some(ctx.sbs_side),
+                matches: &[],
+                left_matches: &[],
             },
             ctx.wrap,
             ctx.file_highlights,
@@ -1225,10 +1912,13 @@ fn render_file_diff_sbs(
                     .find(|t| t.thread_id == comment_anchor.thread_id)
                 {
                     if let Some(comments) = ctx.all_comments.get(&comment_anchor.thread_id) {
-                        let rows = comment_block_rows(thread, comments, ctx.area);
+                        let events = events_for(ctx.thread_events, &comment_anchor.thread_id);
+                        let rows = comment_block_rows(cursor, thread, comments, events, ctx.area);
                         let is_cursor = cursor.is_cursor_at(rows);
                         let hl = is_cursor || cursor.is_selected_at(rows);
-                        emit_comment_block(cursor, ctx.area, thread, comments, hl, is_cursor);
+                        emit_comment_block(
+                            cursor, ctx.area, thread, comments, events, hl, is_cursor,
+                        );
                     }
                 }
             }
@@ -1245,6 +1935,7 @@ fn render_file_diff_sbs(
                 ctx.wrap,
                 &mut OrphanedRenderState {
                     all_comments: ctx.all_comments,
+                    thread_events: ctx.thread_events,
                     thread_positions: ctx.thread_positions,
                     emitted_threads: &mut emitted_threads,
                     last_line_num: &mut last_line_num,
@@ -1255,11 +1946,125 @@ fn render_file_diff_sbs(
     emitted_threads
 }
 
-pub fn render_diff_stream(buffer: &mut OptimizedBuffer, area: Rect, params: &DiffStreamParams<'_>) {
+/// Forget what the previous pass recorded.
+fn clear_recorded_rows(params: &DiffStreamParams<'_>) {
     params.thread_positions.borrow_mut().clear();
     params.line_map.borrow_mut().clear();
+    params.old_line_map.borrow_mut().clear();
     params.cursor_stops.borrow_mut().clear();
+    params.hunk_rows.borrow_mut().clear();
+    params.change_rows.borrow_mut().clear();
+    params.comment_positions.borrow_mut().clear();
+    params.thread_rows.borrow_mut().clear();
+    params.search_hits.borrow_mut().clear();
     params.max_stream_row.set(0);
+}
+
+/// One muted row standing in for a file's content.
+fn emit_muted_line(cursor: &mut StreamCursor<'_>, area: Rect, text: &str) {
+    cursor.emit(|buf, y, theme| {
+        draw_block_text_line(
+            buf,
+            area,
+            y,
+            theme.panel_bg,
+            text,
+            theme.style_muted(),
+            theme,
+        );
+    });
+}
+
+/// A file's lines below its header and file-level threads: word diff,
+/// diff or plain content, whichever the entry has.
+fn render_file_body(
+    cursor: &mut StreamCursor<'_>,
+    area: Rect,
+    path: &str,
+    entry: &crate::model::FileCacheEntry,
+    file_threads: &[&ThreadSummary],
+    view_mode: crate::model::DiffViewMode,
+    sctx: &StreamRenderCtx<'_>,
+) {
+    let word_lines = entry
+        .word_lines
+        .as_ref()
+        .filter(|_| sctx.word_diff_files.contains(path));
+    if let (Some(diff), Some(word_lines)) = (&entry.diff, word_lines) {
+        render_file_words(cursor, area, diff, word_lines, file_threads, sctx);
+    } else if let Some(diff) = &entry.diff {
+        render_file_with_diff(
+            cursor,
+            area,
+            path,
+            diff,
+            entry,
+            file_threads,
+            view_mode,
+            sctx,
+        );
+    } else if let Some(content) = &entry.file_content {
+        render_file_content_no_diff(
+            cursor,
+            area,
+            content,
+            file_threads,
+            &entry.highlighted_lines,
+            sctx,
+        );
+    } else {
+        emit_muted_line(cursor, area, tr(Text::NoContent));
+    }
+}
+
+/// A file's header and, unless it's marked viewed or folded, its
+/// file-level threads and body.
+fn render_file(
+    cursor: &mut StreamCursor<'_>,
+    area: Rect,
+    file: &crate::model::FileEntry,
+    params: &DiffStreamParams<'_>,
+    sctx: &StreamRenderCtx<'_>,
+) {
+    let viewed = params.viewed_files.contains(&file.path);
+    let folded = params.folds.file(&file.path);
+    render_file_header(
+        cursor,
+        area,
+        file,
+        params.file_cache,
+        params.theme,
+        viewed,
+        folded,
+    );
+    if viewed || folded {
+        return;
+    }
+
+    let (file_level, file_threads) = split_file_threads(params.threads, &file.path);
+    emit_thread_blocks(cursor, area, &file_level, sctx);
+
+    if let Some(entry) = params.file_cache.get(&file.path) {
+        render_file_body(
+            cursor,
+            area,
+            &file.path,
+            entry,
+            &file_threads,
+            params
+                .view_mode_overrides
+                .get(&file.path)
+                .copied()
+                .unwrap_or(params.view_mode),
+            sctx,
+        );
+    } else if params.pending_files.iter().any(|f| f.path == file.path) {
+        emit_muted_line(cursor, area, tr(Text::LoadingFile));
+    }
+}
+
+pub fn render_diff_stream(buffer: &mut OptimizedBuffer, area: Rect, params: &DiffStreamParams<'_>) {
+    clear_recorded_rows(params);
     let mut cursor = StreamCursor {
         buffer,
         area,
@@ -1271,70 +2076,77 @@ pub fn render_diff_stream(buffer: &mut OptimizedBuffer, area: Rect, params: &Dif
         max_stream_row: params.max_stream_row,
         selection: params.selection,
         cursor_stops: params.cursor_stops,
+        hunk_rows: params.hunk_rows,
+        change_rows: params.change_rows,
+        comment_positions: params.comment_positions,
+        flash_comment: params.flash_comment,
+        thread_rows: params.thread_rows,
+        muted_threads: params.muted_threads,
+        file_cache: params.file_cache,
+        search: params.search,
+        search_hits: params.search_hits,
     };
 
-    // Render description block if present
-    if let Some(desc) = params.description {
-        if !desc.trim().is_empty() {
-            render_description_block(&mut cursor, area, desc, params.theme);
-        }
-    }
-
     let files = params.files;
-    let file_cache = params.file_cache;
     let threads = params.threads;
     let theme = params.theme;
-    let view_mode = params.view_mode;
     let sctx = StreamRenderCtx {
         wrap: params.wrap,
+        show_cr: params.show_cr,
         all_comments: params.all_comments,
+        thread_events: params.thread_events,
         thread_positions: params.thread_positions,
         line_map: params.line_map,
+        old_line_map: params.old_line_map,
+        sbs_side: params.sbs_side,
+        word_diff_files: params.word_diff_files,
+        folds: params.folds,
+        context_lines: params.context_lines,
+        blame: params.blame,
     };
 
-    for file in files {
-        render_file_header(&mut cursor, area, file, file_cache, theme);
+    if let Some(review) = params.abandoned {
+        render_abandoned_banner(&mut cursor, area, review, theme);
+    }
+    for vote in params.votes {
+        render_verdict_block(&mut cursor, area, vote, theme);
+    }
+    // Render description block if present
+    if let Some(desc) = params.description {
+        if !desc.trim().is_empty() {
+            render_text_block(&mut cursor, area, None, desc);
+        }
+    }
+    emit_thread_blocks(&mut cursor, area, &review_threads(threads), &sctx);
 
-        let file_threads: Vec<&ThreadSummary> = threads
+    for file in files {
+        let Some(cache) = params.stream_cache else {
+            render_file(&mut cursor, area, file, params, &sctx);
+            continue;
+        };
+        let key = records::render_key(params, area.width, &file.path);
+        if let Some(record) = cache.borrow().record(&file.path, key) {
+            if records::off_screen(&cursor, record.rows) {
+                records::replay_record(&mut cursor, &sctx, record);
+                continue;
+            }
+        }
+        let start = cursor.stream_row;
+        let marks = records::RecordMarks::of(&cursor);
+        render_file(&mut cursor, area, file, params, &sctx);
+        let file_threads: Vec<_> = threads
             .iter()
             .filter(|t| t.file_path == file.path)
             .collect();
-
-        if let Some(entry) = file_cache.get(&file.path) {
-            if let Some(diff) = &entry.diff {
-                render_file_with_diff(
-                    &mut cursor,
-                    area,
-                    diff,
-                    entry,
-                    &file_threads,
-                    view_mode,
-                    &sctx,
-                );
-            } else if let Some(content) = &entry.file_content {
-                render_file_content_no_diff(
-                    &mut cursor,
-                    area,
-                    content,
-                    &file_threads,
-                    &entry.highlighted_lines,
-                    &sctx,
-                );
-            } else {
-                cursor.emit(|buf, y, theme| {
-                    draw_block_text_line(
-                        buf,
-                        area,
-                        y,
-                        theme.panel_bg,
-                        "No content available",
-                        theme.style_muted(),
-                        theme,
-                    );
-                });
-            }
-        }
+        let record = records::take_record(&cursor, &sctx, start, &marks, &file_threads);
+        cache.borrow_mut().store_record(&file.path, key, record);
     }
+    if let Some(cache) = params.stream_cache {
+        cache.borrow_mut().retain_records(files);
+    }
+
+    // n/N step through hits in row order, whatever order they were recorded
+    params.search_hits.borrow_mut().sort_by_key(|hit| hit.row);
 
     if cursor.remaining_rows() > 0 {
         let remaining_start = area.y + cursor.screen_row as u32;
@@ -0,0 +1,283 @@
+//! Skipping files outside the viewport.
+//!
+//! Rendering records cursor stops, hunk and change rows, line maps, thread
+//! and comment rows and search hits for the whole stream, not just what's
+//! on screen, so every file used to be walked each frame. A rendered file
+//! keeps what it recorded in the `StreamCache`, relative to its first row;
+//! while it stays off screen under the same key it's replayed at its
+//! current offset instead.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::db::ThreadSummary;
+use crate::stream::{file_key, FileRecord, StreamLayoutParams};
+
+use super::{DiffStreamParams, StreamCursor, StreamRenderCtx};
+
+/// Key of a file's render record: its layout key plus what changes what
+/// it records or draws without changing its rows.
+pub(super) fn render_key(params: &DiffStreamParams<'_>, width: u32, path: &str) -> u64 {
+    let layout_key = file_key(
+        &StreamLayoutParams {
+            files: params.files,
+            file_cache: params.file_cache,
+            threads: params.threads,
+            all_comments: params.all_comments,
+            thread_events: params.thread_events,
+            view_mode: params.view_mode,
+            view_mode_overrides: params.view_mode_overrides,
+            wrap: params.wrap,
+            content_width: width,
+            description: None,
+            votes: &[],
+            abandoned: None,
+            muted_threads: params.muted_threads,
+            word_diff_files: params.word_diff_files,
+            viewed_files: params.viewed_files,
+            folds: params.folds,
+            context_lines: params.context_lines,
+            blame_gutter: params.blame.is_some(),
+        },
+        path,
+    );
+    let mut hasher = DefaultHasher::new();
+    layout_key.hash(&mut hasher);
+    params.search.hash(&mut hasher);
+    params.show_cr.hash(&mut hasher);
+    params.sbs_side.hash(&mut hasher);
+    params
+        .pending_files
+        .iter()
+        .any(|f| f.path == path)
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether `rows` rows from the cursor's position would all be off screen.
+pub(super) const fn off_screen(cursor: &StreamCursor<'_>, rows: usize) -> bool {
+    let start = cursor.stream_row;
+    start + rows <= cursor.scroll || start >= cursor.scroll + cursor.area.height as usize
+}
+
+/// Lengths of the append-only recordings when a file starts.
+pub(super) struct RecordMarks {
+    cursor_stops: usize,
+    hunk_rows: usize,
+    change_rows: usize,
+    search_hits: usize,
+}
+
+impl RecordMarks {
+    pub(super) fn of(cursor: &StreamCursor<'_>) -> Self {
+        Self {
+            cursor_stops: cursor.cursor_stops.borrow().len(),
+            hunk_rows: cursor.hunk_rows.borrow().len(),
+            change_rows: cursor.change_rows.borrow().len(),
+            search_hits: cursor.search_hits.borrow().len(),
+        }
+    }
+}
+
+/// What the file that started at `start` recorded, given its `threads`.
+pub(super) fn take_record(
+    cursor: &StreamCursor<'_>,
+    sctx: &StreamRenderCtx<'_>,
+    start: usize,
+    marks: &RecordMarks,
+    threads: &[&ThreadSummary],
+) -> FileRecord {
+    let end = cursor.stream_row;
+    let relative = |rows: &[usize]| rows.iter().map(|row| row - start).collect();
+    let line_map = |map: &std::cell::RefCell<std::collections::HashMap<usize, i64>>| {
+        let map = map.borrow();
+        (start..end)
+            .filter_map(|row| map.get(&row).map(|&line| (row - start, line)))
+            .collect()
+    };
+    let thread_positions = sctx.thread_positions.borrow();
+    let thread_rows = cursor.thread_rows.borrow();
+    let comment_positions = cursor.comment_positions.borrow();
+    let mut record = FileRecord {
+        rows: end - start,
+        cursor_stops: relative(&cursor.cursor_stops.borrow()[marks.cursor_stops..]),
+        hunk_rows: relative(&cursor.hunk_rows.borrow()[marks.hunk_rows..]),
+        change_rows: relative(&cursor.change_rows.borrow()[marks.change_rows..]),
+        search_hits: cursor.search_hits.borrow()[marks.search_hits..]
+            .iter()
+            .map(|hit| crate::search::SearchHit {
+                row: hit.row - start,
+                ..*hit
+            })
+            .collect(),
+        line_map: line_map(sctx.line_map),
+        old_line_map: line_map(sctx.old_line_map),
+        ..FileRecord::default()
+    };
+    let in_file = |row: usize| (start..end).contains(&row);
+    for thread in threads {
+        let id = &thread.thread_id;
+        if let Some(&row) = thread_positions.get(id).filter(|&&row| in_file(row)) {
+            record.thread_positions.push((id.clone(), row - start));
+        }
+        if let Some(rows) = thread_rows.get(id).filter(|rows| in_file(rows.start)) {
+            record
+                .thread_rows
+                .push((id.clone(), rows.start - start..rows.end - start));
+        }
+        for comment in sctx.all_comments.get(id).into_iter().flatten() {
+            let id = &comment.comment_id;
+            if let Some(rows) = comment_positions.get(id).filter(|rows| in_file(rows.start)) {
+                record
+                    .comment_positions
+                    .push((id.clone(), rows.start - start..rows.end - start));
+            }
+        }
+    }
+    record
+}
+
+/// Record `record` at the cursor's position and move past it without
+/// drawing.
+pub(super) fn replay_record(
+    cursor: &mut StreamCursor<'_>,
+    sctx: &StreamRenderCtx<'_>,
+    record: &FileRecord,
+) {
+    let start = cursor.stream_row;
+    cursor
+        .cursor_stops
+        .borrow_mut()
+        .extend(shifted(&record.cursor_stops, start));
+    cursor
+        .hunk_rows
+        .borrow_mut()
+        .extend(shifted(&record.hunk_rows, start));
+    cursor
+        .change_rows
+        .borrow_mut()
+        .extend(shifted(&record.change_rows, start));
+    cursor
+        .search_hits
+        .borrow_mut()
+        .extend(
+            record
+                .search_hits
+                .iter()
+                .map(|hit| crate::search::SearchHit {
+                    row: hit.row + start,
+                    ..*hit
+                }),
+        );
+    sctx.line_map.borrow_mut().extend(
+        record
+            .line_map
+            .iter()
+            .map(|&(row, line)| (row + start, line)),
+    );
+    sctx.old_line_map.borrow_mut().extend(
+        record
+            .old_line_map
+            .iter()
+            .map(|&(row, line)| (row + start, line)),
+    );
+    sctx.thread_positions.borrow_mut().extend(
+        record
+            .thread_positions
+            .iter()
+            .map(|(id, row)| (id.clone(), row + start)),
+    );
+    cursor.thread_rows.borrow_mut().extend(
+        record
+            .thread_rows
+            .iter()
+            .map(|(id, rows)| (id.clone(), rows.start + start..rows.end + start)),
+    );
+    cursor.comment_positions.borrow_mut().extend(
+        record
+            .comment_positions
+            .iter()
+            .map(|(id, rows)| (id.clone(), rows.start + start..rows.end + start)),
+    );
+    cursor.stream_row += record.rows;
+    cursor.max_stream_row.set(cursor.stream_row);
+}
+
+fn shifted(rows: &[usize], by: usize) -> impl Iterator<Item = usize> + '_ {
+    rows.iter().map(move |row| row + by)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::UiConfig;
+    use crate::db::CritClient;
+    use crate::demo_client::{DemoClient, DemoSize, DEFAULT_DEMO_SEED};
+    use crate::diff::ParsedDiff;
+    use crate::model::{DiffViewMode, FileCacheEntry, FileContent, Focus, Model, Screen};
+    use crate::render_backend::OptimizedBuffer;
+
+    /// Everything the stream recorded, in a comparable form.
+    fn recorded(model: &Model) -> String {
+        let mut line_map: Vec<_> = model.line_map.borrow().clone().into_iter().collect();
+        line_map.sort_unstable();
+        let mut threads: Vec<_> = model.thread_rows.borrow().clone().into_iter().collect();
+        threads.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        let mut comments: Vec<_> = model
+            .comment_positions
+            .borrow()
+            .clone()
+            .into_iter()
+            .collect();
+        comments.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        format!(
+            "{:?} {:?} {:?} {line_map:?} {threads:?} {comments:?} {}",
+            model.cursor_stops.borrow(),
+            model.hunk_rows.borrow(),
+            model.change_rows.borrow(),
+            model.max_stream_row.get()
+        )
+    }
+
+    #[test]
+    fn test_skipped_files_record_what_rendering_them_would() {
+        let client = DemoClient::new(DemoSize::Small, DEFAULT_DEMO_SEED);
+        let review = &client.list_reviews(None).unwrap()[0];
+        let data = client.load_review_data(&review.review_id).unwrap().unwrap();
+        let mut model = Model::new(120, 30, UiConfig::default());
+        model.screen = Screen::ReviewDetail;
+        model.focus = Focus::DiffPane;
+        for file in &data.files {
+            let entry = FileCacheEntry {
+                diff: file.diff.as_deref().map(ParsedDiff::parse),
+                file_content: file
+                    .content
+                    .as_ref()
+                    .map(|c| FileContent::new(c.lines.clone(), c.start_line)),
+                highlighted_lines: Vec::new(),
+                file_highlighted_lines: Vec::new(),
+                word_lines: None,
+                normalized: false,
+                revision: FileCacheEntry::next_revision(),
+            };
+            model.file_cache.insert(file.path.clone(), entry);
+        }
+        model.install_review_data(data);
+        model.pending_files.clear();
+
+        let mut buffer = OptimizedBuffer::new(120, 30);
+        for mode in [DiffViewMode::Unified, DiffViewMode::SideBySide] {
+            model.diff_view_mode = mode;
+            model.diff_scroll = 0;
+            crate::view::view(&model, &mut buffer);
+            let total = model.max_stream_row.get();
+            for scroll in (0..total).step_by(23) {
+                model.diff_scroll = scroll;
+                crate::view::view(&model, &mut buffer);
+                let skipping = recorded(&model);
+                *model.stream_cache.borrow_mut() = crate::stream::StreamCache::default();
+                crate::view::view(&model, &mut buffer);
+                assert_eq!(skipping, recorded(&model), "{mode:?} at row {scroll}");
+            }
+        }
+    }
+}
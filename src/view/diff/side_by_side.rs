@@ -2,15 +2,20 @@
 
 use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Rgba, Style};
 
+use crate::db::ThreadSide;
 use crate::diff::DiffLineKind;
 use crate::layout::SBS_LINE_NUM_WIDTH;
 use crate::syntax::HighlightSpan;
-use crate::theme::Theme;
+use crate::theme::{DiffTheme, Theme};
 
 use super::helpers::{
-    cursor_bg, cursor_fg, diff_content_width, diff_content_x, draw_diff_base_line, selection_bg,
+    cursor_bg, cursor_fg, diff_content_width, diff_content_x, draw_cr_marker, draw_diff_base_line,
+    draw_hunk_separator, draw_no_newline_marker, selection_bg,
+};
+use super::text_util::{
+    draw_emphasis, draw_highlighted_text, draw_search_matches, draw_wrapped_line,
+    wrapped_row_start, HighlightContent, WrappedLine,
 };
-use super::text_util::{draw_highlighted_text, draw_wrapped_line, HighlightContent, WrappedLine};
 use super::{LineRenderCtx, SideBySideLine, SideLine};
 
 /// Layout coordinates for one side of a side-by-side diff panel.
@@ -18,8 +23,56 @@ struct SidePanelLayout<'a> {
     ln_x: u32,
     content_x: u32,
     content_width: u32,
-    dt: &'a crate::theme::DiffTheme,
+    dt: &'a DiffTheme,
     line_number_color: Rgba,
+    /// Search matches on this column's line
+    matches: &'a [std::ops::Range<usize>],
+}
+
+/// Cursor and selection flags for one column. Only the focused column
+/// carries the highlight so it's clear which side `y` and comments use.
+fn side_highlight(ctx: &LineRenderCtx<'_>, side: ThreadSide) -> (bool, bool) {
+    let focused = ctx.cursor_side.is_none_or(|focus| focus == side);
+    (ctx.is_cursor && focused, ctx.is_selected && focused)
+}
+
+/// Changed-word emphasis, then search matches, over the drawn row holding
+/// `line`'s chars `row_chars`. The cursor and selection backgrounds win
+/// over emphasis.
+fn draw_overlays(
+    buffer: &mut OptimizedBuffer,
+    y: u32,
+    line: &SideLine,
+    layout: &SidePanelLayout<'_>,
+    row_chars: std::ops::Range<usize>,
+    (is_cursor, is_selected): (bool, bool),
+    theme: &Theme,
+) {
+    let emphasis = match line.kind {
+        DiffLineKind::Added => Some(layout.dt.added_emphasis_bg),
+        DiffLineKind::Removed => Some(layout.dt.removed_emphasis_bg),
+        DiffLineKind::Context => None,
+    };
+    if let Some(bg) = emphasis.filter(|_| !is_cursor && !is_selected) {
+        draw_emphasis(
+            buffer,
+            layout.content_x,
+            y,
+            layout.content_width,
+            row_chars.clone(),
+            &line.emphasis,
+            bg,
+        );
+    }
+    draw_search_matches(
+        buffer,
+        layout.content_x,
+        y,
+        layout.content_width,
+        row_chars,
+        layout.matches,
+        theme,
+    );
 }
 
 pub(super) fn render_side_by_side_line_block(
@@ -34,11 +87,15 @@ pub(super) fn render_side_by_side_line_block(
     let is_cursor = ctx.is_cursor;
     let is_sel = ctx.is_selected;
     if sbs_line.is_header {
-        draw_diff_base_line(buffer, ctx.area, y, dt.context_bg);
-        let sep = "···";
-        let sep_x = diff_content_x(ctx.area)
-            + diff_content_width(ctx.area).saturating_sub(sep.len() as u32) / 2;
-        buffer_draw_text(buffer, sep_x, y, sep, theme.style_muted_on(dt.context_bg));
+        draw_hunk_separator(
+            buffer,
+            ctx.area,
+            y,
+            theme,
+            sbs_line.separator,
+            is_cursor,
+            is_sel,
+        );
         return;
     }
 
@@ -53,6 +110,8 @@ pub(super) fn render_side_by_side_line_block(
     let half_width = available / 2;
     let left_content_width = half_width.saturating_sub(line_num_width);
     let right_content_width = half_width.saturating_sub(line_num_width);
+    let (left_cursor, left_sel) = side_highlight(ctx, ThreadSide::Old);
+    let (right_cursor, right_sel) = side_highlight(ctx, ThreadSide::New);
 
     let left_ln_x = content_x;
     let left_content_x = left_ln_x + line_num_width;
@@ -79,10 +138,11 @@ pub(super) fn render_side_by_side_line_block(
             content_width: left_content_width,
             dt,
             line_number_color: dt.line_number,
+            matches: ctx.left_matches,
         },
         left_highlights,
-        is_cursor,
-        is_sel,
+        left_cursor,
+        left_sel,
         theme,
     );
 
@@ -98,12 +158,29 @@ pub(super) fn render_side_by_side_line_block(
             content_width: right_content_width,
             dt,
             line_number_color: theme.muted,
+            matches: ctx.matches,
         },
         right_highlights,
-        is_cursor,
-        is_sel,
+        right_cursor,
+        right_sel,
         theme,
     );
+
+    if ctx.show_cr {
+        let sides = [
+            (sbs_line.left.as_ref(), left_content_x, left_content_width),
+            (
+                sbs_line.right.as_ref(),
+                right_content_x,
+                right_content_width,
+            ),
+        ];
+        for (side, x, width) in sides {
+            if let Some(line) = side.filter(|l| l.crlf) {
+                draw_cr_marker(buffer, x, y, line.content.chars().count(), width, theme);
+            }
+        }
+    }
 }
 
 pub(super) fn render_side_by_side_line_wrapped_row(
@@ -129,6 +206,8 @@ pub(super) fn render_side_by_side_line_wrapped_row(
     let half_width = available / 2;
     let left_content_width = half_width.saturating_sub(line_num_width);
     let right_content_width = half_width.saturating_sub(line_num_width);
+    let (left_cursor, left_sel) = side_highlight(ctx, ThreadSide::Old);
+    let (right_cursor, right_sel) = side_highlight(ctx, ThreadSide::New);
 
     let left_ln_x = content_x;
     let left_content_x = left_ln_x + line_num_width;
@@ -146,11 +225,12 @@ pub(super) fn render_side_by_side_line_wrapped_row(
             content_width: left_content_width,
             dt,
             line_number_color: dt.line_number,
+            matches: ctx.left_matches,
         },
         wrapped_sides.0,
         row,
-        is_cursor,
-        is_sel,
+        left_cursor,
+        left_sel,
         theme,
     );
 
@@ -166,13 +246,37 @@ pub(super) fn render_side_by_side_line_wrapped_row(
             content_width: right_content_width,
             dt,
             line_number_color: theme.muted,
+            matches: ctx.matches,
         },
         wrapped_sides.1,
         row,
-        is_cursor,
-        is_sel,
+        right_cursor,
+        right_sel,
         theme,
     );
+
+    if ctx.show_cr {
+        let sides = [
+            (
+                sbs_line.left.as_ref(),
+                wrapped_sides.0,
+                left_content_x,
+                left_content_width,
+            ),
+            (
+                sbs_line.right.as_ref(),
+                wrapped_sides.1,
+                right_content_x,
+                right_content_width,
+            ),
+        ];
+        for (side, wrapped, x, width) in sides {
+            let last_row = wrapped.and_then(|w| w.last().filter(|_| row + 1 == w.len()));
+            if let (Some(_), Some(last)) = (side.filter(|l| l.crlf), last_row) {
+                draw_cr_marker(buffer, x, y, last.char_count(), width, theme);
+            }
+        }
+    }
 }
 
 fn render_side_line_wrapped_row(
@@ -240,6 +344,9 @@ fn render_side_line_wrapped_row(
                 &ln_str,
                 Style::fg(ln_fg).with_bg(line_num_bg),
             );
+            if line.no_newline {
+                draw_no_newline_marker(buffer, layout.ln_x + 5, y, line_num_bg, theme);
+            }
         }
 
         buffer_fill_rect(buffer, layout.content_x, y, layout.content_width, 1, bg);
@@ -254,6 +361,16 @@ fn render_side_line_wrapped_row(
                     fg,
                     bg,
                 );
+                let start = wrapped_row_start(lines, row);
+                draw_overlays(
+                    buffer,
+                    y,
+                    line,
+                    layout,
+                    start..start + line_content.char_count(),
+                    (is_cursor, is_selected),
+                    theme,
+                );
             }
         }
     } else {
@@ -337,6 +454,9 @@ fn render_side_line(
             &ln_str,
             Style::fg(ln_fg).with_bg(line_num_bg),
         );
+        if line.no_newline {
+            draw_no_newline_marker(buffer, layout.ln_x + 5, y, line_num_bg, theme);
+        }
 
         buffer_fill_rect(buffer, layout.content_x, y, layout.content_width, 1, bg);
         draw_highlighted_text(
@@ -351,6 +471,15 @@ fn render_side_line(
                 bg,
             },
         );
+        draw_overlays(
+            buffer,
+            y,
+            line,
+            layout,
+            0..line.content.chars().count(),
+            (is_cursor, is_selected),
+            theme,
+        );
     } else {
         let empty_bg = cursor_bg(
             selection_bg(layout.dt.context_bg, is_selected, theme),
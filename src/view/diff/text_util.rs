@@ -1,9 +1,15 @@
 //! Text wrapping, truncation, and highlighted-text drawing utilities.
 
-use crate::render_backend::{buffer_draw_text, OptimizedBuffer, Rgba, Style};
+use std::ops::Range;
 
+use crate::render_backend::{
+    buffer_draw_text, buffer_recolor_cell, buffer_set_cell_bg, OptimizedBuffer, Rgba, Style,
+};
+
+use crate::search::clip_to_row;
 use crate::syntax::HighlightSpan;
 use crate::text::wrap_text_preserve;
+use crate::theme::Theme;
 
 // --- Character-level helpers ---
 
@@ -31,6 +37,16 @@ pub(super) enum WrappedLine {
     Text(String),
 }
 
+impl WrappedLine {
+    /// Display width in characters.
+    pub(super) fn char_count(&self) -> usize {
+        match self {
+            Self::Spans(spans) => spans.iter().map(|s| s.text.chars().count()).sum(),
+            Self::Text(text) => text.chars().count(),
+        }
+    }
+}
+
 pub(super) fn wrap_highlight_spans(
     spans: &[HighlightSpan],
     max_width: usize,
@@ -103,8 +119,52 @@ pub(super) fn wrap_content(
         .collect()
 }
 
+/// Char offset of wrapped `row` within its logical line.
+pub(super) fn wrapped_row_start(wrapped: &[WrappedLine], row: usize) -> usize {
+    wrapped.iter().take(row).map(WrappedLine::char_count).sum()
+}
+
 // --- Drawing ---
 
+/// Restyle the cells of search matches on one drawn row. `matches` are char
+/// ranges on the whole logical line; the row drawn at `x` holds the line's
+/// chars `row`, one cell each.
+pub(super) fn draw_search_matches(
+    buffer: &mut OptimizedBuffer,
+    x: u32,
+    y: u32,
+    max_width: u32,
+    row: Range<usize>,
+    matches: &[Range<usize>],
+    theme: &Theme,
+) {
+    let row = row.start..row.end.min(row.start + max_width as usize);
+    for range in clip_to_row(matches, row) {
+        for col in range {
+            buffer_recolor_cell(buffer, x + col as u32, y, theme.background, theme.warning);
+        }
+    }
+}
+
+/// Put `bg` behind the changed words (`emphasis`, char ranges on the whole
+/// logical line) on one drawn row, like [`draw_search_matches`].
+pub(super) fn draw_emphasis(
+    buffer: &mut OptimizedBuffer,
+    x: u32,
+    y: u32,
+    max_width: u32,
+    row: Range<usize>,
+    emphasis: &[Range<usize>],
+    bg: Rgba,
+) {
+    let row = row.start..row.end.min(row.start + max_width as usize);
+    for range in clip_to_row(emphasis, row) {
+        for col in range {
+            buffer_set_cell_bg(buffer, x + col as u32, y, bg);
+        }
+    }
+}
+
 pub(super) fn draw_wrapped_line(
     buffer: &mut OptimizedBuffer,
     x: u32,
@@ -1,16 +1,23 @@
 //! Unified diff rendering (single-pane with +/- lines).
 
-use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Style};
+use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Rgba, Style};
 
 use crate::diff::{DiffLine, DiffLineKind};
-use crate::layout::UNIFIED_LINE_NUM_WIDTH;
+use crate::layout::{BLAME_GUTTER_WIDTH, DIFF_H_PAD, UNIFIED_LINE_NUM_WIDTH};
 use crate::syntax::HighlightSpan;
+use crate::text::truncate_end;
 use crate::theme::Theme;
+use crate::vcs::BlameLine;
+use crate::view::components::Rect;
 
 use super::helpers::{
-    cursor_bg, cursor_fg, diff_content_width, diff_content_x, draw_diff_base_line, selection_bg,
+    cursor_bg, cursor_fg, diff_content_width, diff_content_x, draw_cr_marker, draw_diff_base_line,
+    draw_hunk_separator, draw_no_newline_marker, selection_bg,
+};
+use super::text_util::{
+    draw_highlighted_text, draw_search_matches, draw_wrapped_line, wrapped_row_start,
+    HighlightContent, WrappedLine,
 };
-use super::text_util::{draw_highlighted_text, draw_wrapped_line, HighlightContent, WrappedLine};
 use super::{DisplayLine, LineRenderCtx};
 
 pub(super) fn render_unified_diff_line_block(
@@ -23,13 +30,18 @@ pub(super) fn render_unified_diff_line_block(
 ) {
     let dt = &theme.diff;
     match display_line {
-        DisplayLine::HunkHeader => {
-            draw_diff_base_line(buffer, ctx.area, y, dt.context_bg);
-            let sep = "···";
-            let sep_x = diff_content_x(ctx.area)
-                + diff_content_width(ctx.area).saturating_sub(sep.len() as u32) / 2;
-            buffer_draw_text(buffer, sep_x, y, sep, theme.style_muted_on(dt.context_bg));
+        DisplayLine::HunkHeader(separator) => {
+            draw_hunk_separator(
+                buffer,
+                ctx.area,
+                y,
+                theme,
+                *separator,
+                ctx.is_cursor,
+                ctx.is_selected,
+            );
         }
+        DisplayLine::Folded => {}
         DisplayLine::Diff(line) => {
             let base_bg = cursor_bg(
                 selection_bg(dt.context_bg, ctx.is_selected, theme),
@@ -52,12 +64,30 @@ pub(super) fn render_unified_diff_line_block(
                     content_width,
                 },
                 line,
-                dt,
                 highlights,
                 ctx.is_cursor,
                 ctx.is_selected,
                 theme,
             );
+            draw_search_matches(
+                buffer,
+                content_start + 1,
+                y,
+                content_width.saturating_sub(2),
+                0..line.content.chars().count(),
+                ctx.matches,
+                theme,
+            );
+            if ctx.show_cr && line.crlf {
+                draw_cr_marker(
+                    buffer,
+                    content_start + 1,
+                    y,
+                    line.content.chars().count(),
+                    content_width.saturating_sub(2),
+                    theme,
+                );
+            }
         }
     }
 }
@@ -74,37 +104,8 @@ pub(super) fn render_unified_diff_line_wrapped_row(
     let dt = &theme.diff;
     let is_cursor = ctx.is_cursor;
     let is_sel = ctx.is_selected;
-    let (bg, line_num_bg, default_fg, sign, sign_color) = match line.kind {
-        DiffLineKind::Added => (
-            cursor_bg(selection_bg(dt.added_bg, is_sel, theme), is_cursor, theme),
-            cursor_bg(
-                selection_bg(dt.added_line_number_bg, is_sel, theme),
-                is_cursor,
-                theme,
-            ),
-            cursor_fg(dt.added, is_cursor),
-            "+",
-            cursor_fg(dt.highlight_added, is_cursor),
-        ),
-        DiffLineKind::Removed => (
-            cursor_bg(selection_bg(dt.removed_bg, is_sel, theme), is_cursor, theme),
-            cursor_bg(
-                selection_bg(dt.removed_line_number_bg, is_sel, theme),
-                is_cursor,
-                theme,
-            ),
-            cursor_fg(dt.removed, is_cursor),
-            "-",
-            cursor_fg(dt.highlight_removed, is_cursor),
-        ),
-        DiffLineKind::Context => (
-            cursor_bg(selection_bg(dt.context_bg, is_sel, theme), is_cursor, theme),
-            cursor_bg(selection_bg(dt.context_bg, is_sel, theme), is_cursor, theme),
-            cursor_fg(dt.context, is_cursor),
-            " ",
-            cursor_fg(dt.context, is_cursor),
-        ),
-    };
+    let (bg, line_num_bg, default_fg, sign, sign_color) =
+        line_colors(line, is_cursor, is_sel, theme);
 
     let base_bg = cursor_bg(selection_bg(dt.context_bg, is_sel, theme), is_cursor, theme);
     draw_diff_base_line(buffer, ctx.area, y, base_bg);
@@ -115,42 +116,7 @@ pub(super) fn render_unified_diff_line_wrapped_row(
     let line_num_x = content_x;
     buffer_fill_rect(buffer, line_num_x, y, line_num_width, 1, line_num_bg);
     if row == 0 {
-        let old_ln = line
-            .old_line
-            .map_or_else(|| "     ".to_string(), |n| format!("{n:>5}"));
-        let new_ln = line
-            .new_line
-            .map_or_else(|| "     ".to_string(), |n| format!("{n:>5}"));
-
-        let ln_fg = cursor_fg(dt.line_number, is_cursor);
-        buffer_draw_text(
-            buffer,
-            line_num_x,
-            y,
-            &old_ln,
-            Style::fg(ln_fg).with_bg(line_num_bg),
-        );
-        buffer_draw_text(
-            buffer,
-            line_num_x + 5,
-            y,
-            " ",
-            Style::fg(ln_fg).with_bg(line_num_bg),
-        );
-        buffer_draw_text(
-            buffer,
-            line_num_x + 6,
-            y,
-            &new_ln,
-            Style::fg(ln_fg).with_bg(line_num_bg),
-        );
-        buffer_draw_text(
-            buffer,
-            line_num_x + 11,
-            y,
-            " ",
-            Style::fg(ln_fg).with_bg(line_num_bg),
-        );
+        draw_line_numbers(buffer, line_num_x, y, line, line_num_bg, is_cursor, theme);
     }
 
     let content_start = line_num_x + line_num_width;
@@ -177,115 +143,163 @@ pub(super) fn render_unified_diff_line_wrapped_row(
             default_fg,
             bg,
         );
+        let start = wrapped_row_start(wrapped, row);
+        draw_search_matches(
+            buffer,
+            content_start + 1,
+            y,
+            max_content,
+            start..start + line_content.char_count(),
+            ctx.matches,
+            theme,
+        );
+        if ctx.show_cr && line.crlf && row + 1 == wrapped.len() {
+            draw_cr_marker(
+                buffer,
+                content_start + 1,
+                y,
+                line_content.char_count(),
+                max_content,
+                theme,
+            );
+        }
     }
 }
 
-/// Layout coordinates for a unified diff line.
-pub(super) struct UnifiedLineLayout {
-    x: u32,
-    content_x: u32,
-    content_width: u32,
+/// What's left of a unified line's area right of the blame column.
+pub(super) const fn blame_line_area(area: Rect) -> Rect {
+    Rect::new(
+        area.x + BLAME_GUTTER_WIDTH,
+        area.y,
+        area.width.saturating_sub(BLAME_GUTTER_WIDTH),
+        area.height,
+    )
 }
 
-/// Render a single unified diff line (line numbers + sign + content)
-pub(super) fn render_diff_line(
+/// The blame column at the left of `area`: the commit and author that last
+/// changed the line, muted. Blank for removed lines and wrapped rows.
+pub(super) fn draw_blame_gutter(
     buffer: &mut OptimizedBuffer,
+    area: Rect,
     y: u32,
-    layout: &UnifiedLineLayout,
-    line: &DiffLine,
-    dt: &crate::theme::DiffTheme,
-    highlights: Option<&Vec<HighlightSpan>>,
+    blame: Option<&BlameLine>,
     is_cursor: bool,
     is_selected: bool,
     theme: &Theme,
 ) {
-    let (bg, line_num_bg, default_fg, sign, sign_color) = match line.kind {
+    let bg = cursor_bg(
+        selection_bg(theme.diff.context_bg, is_selected, theme),
+        is_cursor,
+        theme,
+    );
+    buffer_fill_rect(buffer, area.x, y, BLAME_GUTTER_WIDTH, 1, bg);
+    let Some(blame) = blame else {
+        return;
+    };
+    let width = BLAME_GUTTER_WIDTH.saturating_sub(DIFF_H_PAD + 1) as usize;
+    let text = truncate_end(&format!("{} {}", blame.commit, blame.author), width);
+    buffer_draw_text(
+        buffer,
+        area.x + DIFF_H_PAD,
+        y,
+        &text,
+        theme.style_muted_on(bg),
+    );
+}
+
+/// Backgrounds, text color, sign and sign color for a unified line.
+fn line_colors(
+    line: &DiffLine,
+    is_cursor: bool,
+    is_selected: bool,
+    theme: &Theme,
+) -> (Rgba, Rgba, Rgba, &'static str, Rgba) {
+    let dt = &theme.diff;
+    let bg = |color| cursor_bg(selection_bg(color, is_selected, theme), is_cursor, theme);
+    match line.kind {
         DiffLineKind::Added => (
-            cursor_bg(
-                selection_bg(dt.added_bg, is_selected, theme),
-                is_cursor,
-                theme,
-            ),
-            cursor_bg(
-                selection_bg(dt.added_line_number_bg, is_selected, theme),
-                is_cursor,
-                theme,
-            ),
+            bg(dt.added_bg),
+            bg(dt.added_line_number_bg),
             cursor_fg(dt.added, is_cursor),
             "+",
             cursor_fg(dt.highlight_added, is_cursor),
         ),
         DiffLineKind::Removed => (
-            cursor_bg(
-                selection_bg(dt.removed_bg, is_selected, theme),
-                is_cursor,
-                theme,
-            ),
-            cursor_bg(
-                selection_bg(dt.removed_line_number_bg, is_selected, theme),
-                is_cursor,
-                theme,
-            ),
+            bg(dt.removed_bg),
+            bg(dt.removed_line_number_bg),
             cursor_fg(dt.removed, is_cursor),
             "-",
             cursor_fg(dt.highlight_removed, is_cursor),
         ),
         DiffLineKind::Context => (
-            cursor_bg(
-                selection_bg(dt.context_bg, is_selected, theme),
-                is_cursor,
-                theme,
-            ),
-            cursor_bg(
-                selection_bg(dt.context_bg, is_selected, theme),
-                is_cursor,
-                theme,
-            ),
+            bg(dt.context_bg),
+            bg(dt.context_bg),
             cursor_fg(dt.context, is_cursor),
             " ",
             cursor_fg(dt.context, is_cursor),
         ),
-    };
-
-    let ln_fg = cursor_fg(dt.line_number, is_cursor);
-    buffer_fill_rect(buffer, layout.x, y, 12, 1, line_num_bg);
-    buffer_fill_rect(buffer, layout.content_x, y, layout.content_width, 1, bg);
+    }
+}
 
-    let old_ln = line
-        .old_line
-        .map_or_else(|| "     ".to_string(), |n| format!("{n:>5}"));
+/// Old and new line numbers at `x`, with the missing-newline marker after
+/// them.
+fn draw_line_numbers(
+    buffer: &mut OptimizedBuffer,
+    x: u32,
+    y: u32,
+    line: &DiffLine,
+    bg: Rgba,
+    is_cursor: bool,
+    theme: &Theme,
+) {
+    let style = Style::fg(cursor_fg(theme.diff.line_number, is_cursor)).with_bg(bg);
     let new_ln = line
         .new_line
         .map_or_else(|| "     ".to_string(), |n| format!("{n:>5}"));
+    buffer_draw_text(buffer, x, y, &old_gutter_text(line), style);
+    buffer_draw_text(buffer, x + 5, y, " ", style);
+    buffer_draw_text(buffer, x + 6, y, &new_ln, style);
+    buffer_draw_text(buffer, x + 11, y, " ", style);
+    if line.no_newline {
+        draw_no_newline_marker(buffer, x + 11, y, bg, theme);
+    }
+}
 
-    buffer_draw_text(
-        buffer,
-        layout.x,
-        y,
-        &old_ln,
-        Style::fg(ln_fg).with_bg(line_num_bg),
-    );
-    buffer_draw_text(
-        buffer,
-        layout.x + 5,
-        y,
-        " ",
-        Style::fg(ln_fg).with_bg(line_num_bg),
-    );
-    buffer_draw_text(
-        buffer,
-        layout.x + 6,
-        y,
-        &new_ln,
-        Style::fg(ln_fg).with_bg(line_num_bg),
-    );
-    buffer_draw_text(
-        buffer,
-        layout.x + 11,
-        y,
-        " ",
-        Style::fg(ln_fg).with_bg(line_num_bg),
-    );
+/// Old-side gutter text. Combined (merge) diffs show the per-parent origin
+/// markers here instead of a single old line number.
+fn old_gutter_text(line: &DiffLine) -> String {
+    if let Some(origins) = &line.origins {
+        return format!("{origins:>5}");
+    }
+    line.old_line
+        .map_or_else(|| "     ".to_string(), |n| format!("{n:>5}"))
+}
+
+/// Layout coordinates for a unified diff line.
+pub(super) struct UnifiedLineLayout {
+    x: u32,
+    content_x: u32,
+    content_width: u32,
+}
+
+/// Render a single unified diff line (line numbers + sign + content)
+pub(super) fn render_diff_line(
+    buffer: &mut OptimizedBuffer,
+    y: u32,
+    layout: &UnifiedLineLayout,
+    line: &DiffLine,
+    highlights: Option<&Vec<HighlightSpan>>,
+    is_cursor: bool,
+    is_selected: bool,
+    theme: &Theme,
+) {
+    let (bg, line_num_bg, default_fg, sign, sign_color) =
+        line_colors(line, is_cursor, is_selected, theme);
+
+    buffer_fill_rect(buffer, layout.x, y, 12, 1, line_num_bg);
+    buffer_fill_rect(buffer, layout.content_x, y, layout.content_width, 1, bg);
+
+    draw_line_numbers(buffer, layout.x, y, line, line_num_bg, is_cursor, theme);
 
     buffer_draw_text(
         buffer,
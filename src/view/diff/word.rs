@@ -0,0 +1,160 @@
+//! Word diff rendering for prose files (`W`).
+//!
+//! Each display line is wrapped at spaces; removed and added words are
+//! drawn on the diff's emphasis backgrounds within the line. The gutter
+//! matches the unified view's.
+
+use crate::render_backend::{buffer_draw_text, OptimizedBuffer, Style};
+
+use crate::diff::{wrap_spans, WordKind, WordLine, WordSpan};
+use crate::layout::{THREAD_COL_WIDTH, UNIFIED_LINE_NUM_WIDTH};
+use crate::search::SearchKind;
+use crate::theme::Theme;
+
+use super::helpers::{
+    cursor_bg, cursor_fg, diff_content_width, diff_content_x, draw_diff_base_line, selection_bg,
+};
+use super::text_util::draw_search_matches;
+use super::{DiffRenderCtx, StreamCursor};
+
+/// Chars per row of a word diff line in `ctx`'s area; the same width the
+/// unified view wraps at.
+const fn word_wrap_width(ctx: &DiffRenderCtx<'_>) -> usize {
+    diff_content_width(ctx.line_area)
+        .saturating_sub(THREAD_COL_WIDTH + UNIFIED_LINE_NUM_WIDTH)
+        .saturating_sub(2) as usize
+}
+
+/// Render the word diff of each hunk, after a `···` separator like the
+/// unified view's.
+pub(super) fn render_file_diff_words(
+    cursor: &mut StreamCursor<'_>,
+    hunks: &[Vec<WordLine>],
+    ctx: &DiffRenderCtx<'_>,
+) {
+    let width = word_wrap_width(ctx);
+    for lines in hunks {
+        cursor.mark_hunk_start();
+        cursor.emit(|buf, y, theme| {
+            let bg = theme.diff.context_bg;
+            draw_diff_base_line(buf, ctx.line_area, y, bg);
+            let sep = "···";
+            let sep_x = diff_content_x(ctx.line_area)
+                + diff_content_width(ctx.line_area).saturating_sub(sep.len() as u32) / 2;
+            buffer_draw_text(buf, sep_x, y, sep, theme.style_muted_on(bg));
+        });
+
+        let mut follows_change = false;
+        for line in lines {
+            let rows = wrap_spans(&line.spans, width);
+            cursor.mark_cursor_stop();
+            if line.is_change() && !follows_change {
+                cursor.mark_change_start();
+            }
+            follows_change = line.is_change();
+            if let Some(new_line) = line.new_line {
+                let mut line_map = ctx.line_map.borrow_mut();
+                for row in 0..rows.len() {
+                    line_map.insert(cursor.stream_row + row, i64::from(new_line));
+                }
+            }
+
+            let is_cursor = cursor.is_cursor_at(rows.len());
+            let is_selected = cursor.is_selected_at(rows.len());
+            let matches: Vec<_> = rows
+                .iter()
+                .enumerate()
+                .map(|(index, row)| {
+                    let text: String = row.iter().map(|span| span.text.as_str()).collect();
+                    cursor.search_matches(&text, cursor.stream_row + index, 0, SearchKind::Line)
+                })
+                .collect();
+            cursor.emit_rows(rows.len(), |buf, y, theme, row| {
+                draw_word_row(
+                    buf,
+                    y,
+                    ctx,
+                    theme,
+                    &WordRow {
+                        line,
+                        spans: &rows[row],
+                        first: row == 0,
+                        is_cursor,
+                        is_selected,
+                        matches: &matches[row],
+                    },
+                );
+            });
+        }
+    }
+}
+
+/// One wrapped row of a word diff line.
+struct WordRow<'a> {
+    line: &'a WordLine,
+    spans: &'a [WordSpan],
+    /// The line's first row, which carries the gutter and sign
+    first: bool,
+    is_cursor: bool,
+    is_selected: bool,
+    matches: &'a [std::ops::Range<usize>],
+}
+
+fn draw_word_row(
+    buffer: &mut OptimizedBuffer,
+    y: u32,
+    ctx: &DiffRenderCtx<'_>,
+    theme: &Theme,
+    row: &WordRow<'_>,
+) {
+    let dt = &theme.diff;
+    let bg = cursor_bg(
+        selection_bg(dt.context_bg, row.is_selected, theme),
+        row.is_cursor,
+        theme,
+    );
+    draw_diff_base_line(buffer, ctx.line_area, y, bg);
+
+    let x = diff_content_x(ctx.line_area);
+    let content_x = x + UNIFIED_LINE_NUM_WIDTH;
+    if row.first {
+        let number = |n: Option<u32>| n.map_or_else(|| "     ".to_string(), |n| format!("{n:>5}"));
+        let gutter = format!(
+            "{} {} ",
+            number(row.line.old_line),
+            number(row.line.new_line)
+        );
+        let ln_style = Style::fg(cursor_fg(dt.line_number, row.is_cursor)).with_bg(bg);
+        buffer_draw_text(buffer, x, y, &gutter, ln_style);
+        if row.line.is_change() {
+            buffer_draw_text(buffer, content_x, y, "~", ln_style);
+        }
+    }
+
+    let mut col = content_x + 1;
+    let mut chars = 0;
+    for span in row.spans {
+        let style = match span.kind {
+            WordKind::Same => Style::fg(cursor_fg(dt.context, row.is_cursor)).with_bg(bg),
+            WordKind::Removed => {
+                Style::fg(cursor_fg(dt.removed, row.is_cursor)).with_bg(dt.removed_emphasis_bg)
+            }
+            WordKind::Added => {
+                Style::fg(cursor_fg(dt.added, row.is_cursor)).with_bg(dt.added_emphasis_bg)
+            }
+        };
+        buffer_draw_text(buffer, col, y, &span.text, style);
+        let len = span.text.chars().count();
+        col += len as u32;
+        chars += len;
+    }
+    draw_search_matches(
+        buffer,
+        content_x + 1,
+        y,
+        word_wrap_width(ctx) as u32,
+        0..chars,
+        row.matches,
+        theme,
+    );
+}
@@ -0,0 +1,179 @@
+//! Inbox screen: threads waiting on the user's reply, newest first.
+
+use crate::db::thread_location;
+use crate::i18n::{tr, tr_fmt, Text};
+use crate::inbox::{Inbox, InboxItem};
+use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Style};
+use crate::text::{display_width, truncate_path};
+
+use super::components::{
+    draw_block, draw_empty_state, draw_help_bar_ext, draw_text_truncated, short_timestamp,
+    BlockLine, HotkeyHint, Rect,
+};
+use crate::model::Model;
+
+/// Progress/summary line plus a blank line (where the list has its search bar)
+const STATUS_HEIGHT: u32 = 2;
+/// Lines per item
+const ITEM_HEIGHT: u32 = 2;
+
+pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
+    let theme = &model.theme;
+    let area = Rect::from_size(model.width, model.height);
+    let header_height = crate::layout::screen_header_height();
+    let Some(inbox) = &model.inbox else {
+        return;
+    };
+
+    let title = tr_fmt(Text::InboxTitle, &[&model.current_user]);
+    draw_block(
+        buffer,
+        Rect::new(area.x, area.y, area.width, header_height),
+        theme,
+        theme.panel_bg,
+        &[BlockLine::new(
+            &title,
+            Style::fg(theme.foreground).with_bold(),
+        )],
+    );
+
+    if let Some(frame) = model.refresh_spinner() {
+        use crate::layout::{block_margin, block_padding, BLOCK_RIGHT_PAD, BLOCK_SIDE_MARGIN};
+        let x = (area.x + area.width).saturating_sub(BLOCK_SIDE_MARGIN + BLOCK_RIGHT_PAD + 1);
+        let y = area.y + (block_margin() + block_padding()) as u32;
+        buffer_draw_text(buffer, x, y, frame, theme.style_muted_on(theme.panel_bg));
+    }
+
+    let status_y = area.y + header_height;
+    buffer_draw_text(
+        buffer,
+        area.x + 5,
+        status_y,
+        &status_text(inbox),
+        theme.style_muted(),
+    );
+
+    let list_y = status_y + STATUS_HEIGHT;
+    let list_height = area
+        .height
+        .saturating_sub(header_height + STATUS_HEIGHT + 2); // 2 for help bar
+    let list_area = Rect::new(area.x, list_y, area.width, list_height);
+
+    if inbox.items.is_empty() && !inbox.is_loading() {
+        draw_empty_state(
+            buffer,
+            list_area,
+            tr(Text::InboxEmpty),
+            tr(Text::InboxEmptyHint),
+            theme,
+        );
+    }
+
+    let visible = (list_height / ITEM_HEIGHT) as usize;
+    let start = inbox.first_visible(visible.max(1)).min(inbox.items.len());
+    let end = (start + visible).min(inbox.items.len());
+    for (row, item) in inbox.items[start..end].iter().enumerate() {
+        let y = list_area.y + row as u32 * ITEM_HEIGHT;
+        draw_item(
+            model,
+            buffer,
+            list_area,
+            y,
+            item,
+            start + row == inbox.selection,
+        );
+    }
+
+    let hints = &[
+        HotkeyHint::new(tr(Text::HintCommands), "ctrl+p"),
+        HotkeyHint::new(tr(Text::HintOpen), "Enter"),
+        HotkeyHint::new(tr(Text::HintRefresh), "r"),
+        HotkeyHint::new(tr(Text::HintBack), "Esc"),
+        HotkeyHint::new(tr(Text::HintQuit), "q"),
+    ];
+    draw_help_bar_ext(buffer, area, theme, hints, theme.background, "");
+}
+
+fn status_text(inbox: &Inbox) -> String {
+    let mut text = if inbox.is_loading() {
+        tr_fmt(Text::InboxScanning, &[&inbox.scanned(), &inbox.total])
+    } else {
+        tr_fmt(Text::InboxSummary, &[&inbox.items.len()])
+    };
+    if inbox.failed > 0 {
+        text = format!(
+            "{text} \u{b7} {}",
+            tr_fmt(Text::InboxFailed, &[&inbox.failed])
+        );
+    }
+    text
+}
+
+/// Two rows, laid out like a review list item:
+/// `cr-id  title … path:line` / `@author  first line of comment … time`.
+fn draw_item(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    area: Rect,
+    y: u32,
+    item: &InboxItem,
+    selected: bool,
+) {
+    let theme = &model.theme;
+    let bg = if selected {
+        theme.selection_bg
+    } else {
+        theme.background
+    };
+    let fg = |color| Style::fg(if selected { theme.selection_fg } else { color }).with_bg(bg);
+
+    let margin: u32 = 2;
+    let item_x = area.x + margin;
+    let item_width = area.width.saturating_sub(margin * 2);
+    buffer_fill_rect(buffer, item_x, y, item_width, ITEM_HEIGHT, bg);
+
+    let left = item_x + 3;
+    let right_edge = item_x + item_width.saturating_sub(2);
+
+    // Line 1: review id, title, location
+    buffer_draw_text(
+        buffer,
+        left,
+        y,
+        &item.review_id,
+        Style::fg(theme.primary).with_bg(bg),
+    );
+    let title_x = left + display_width(&item.review_id) as u32 + 2;
+    let location_room = (right_edge.saturating_sub(title_x) / 2) as usize;
+    let location = truncate_path(
+        &thread_location(&item.file_path, item.line, None),
+        location_room,
+    );
+    let location_x = right_edge.saturating_sub(display_width(&location) as u32);
+    buffer_draw_text(buffer, location_x, y, &location, fg(theme.muted));
+    draw_text_truncated(
+        buffer,
+        title_x,
+        y,
+        &item.review_title,
+        location_x.saturating_sub(title_x + 1),
+        fg(theme.foreground),
+    );
+
+    // Line 2: who spoke last, what they said, when
+    let y2 = y + 1;
+    let author = format!("@{}", item.last_author);
+    buffer_draw_text(buffer, left, y2, &author, fg(theme.warning));
+    let when = short_timestamp(&item.last_at);
+    let when_x = right_edge.saturating_sub(display_width(&when) as u32);
+    buffer_draw_text(buffer, when_x, y2, &when, fg(theme.muted));
+    let body_x = left + display_width(&author) as u32 + 2;
+    draw_text_truncated(
+        buffer,
+        body_x,
+        y2,
+        &item.last_body,
+        when_x.saturating_sub(body_x + 1),
+        fg(theme.foreground),
+    );
+}
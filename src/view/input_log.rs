@@ -0,0 +1,71 @@
+//! Input latency log overlay (command palette).
+//!
+//! The command output modal's frame: a bold title and "esc" on the right,
+//! then `InputProfile::log_lines`, the per-message table above the newest
+//! spans.
+
+use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer};
+
+use crate::i18n::{tr, Text};
+use crate::layout::command_output_rows;
+use crate::model::{Focus, Model};
+use crate::view::components::{dim_rect, draw_text_truncated, Rect};
+
+/// Columns between the screen edge and the modal.
+const H_MARGIN: u32 = 4;
+/// Rows between the screen edge and the modal.
+const V_MARGIN: u32 = 2;
+/// Padding inside the modal, left and right.
+const PAD: u32 = 2;
+
+pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
+    if model.focus != Focus::InputLog {
+        return;
+    }
+    let theme = &model.theme;
+    let screen = Rect::from_size(model.width, model.height);
+    dim_rect(buffer, screen, 0.35);
+
+    let width = screen.width.saturating_sub(H_MARGIN * 2);
+    let height = screen.height.saturating_sub(V_MARGIN * 2);
+    if width < PAD * 2 + 10 || height < 5 {
+        return;
+    }
+    let (x, mut y) = (H_MARGIN, V_MARGIN);
+    buffer_fill_rect(buffer, x, y, width, height, theme.panel_bg);
+    let text_x = x + PAD;
+    let text_width = width - PAD * 2;
+    y += 1;
+
+    let esc_label = "esc";
+    draw_text_truncated(
+        buffer,
+        text_x,
+        y,
+        tr(Text::InputLogTitle),
+        text_width.saturating_sub(esc_label.len() as u32 + 2),
+        theme.style_foreground().with_bold(),
+    );
+    let esc_x = text_x + text_width - esc_label.len() as u32;
+    buffer_draw_text(buffer, esc_x, y, esc_label, theme.style_muted());
+    y += 2;
+
+    let rows = command_output_rows(model.height);
+    for line in model
+        .input_profile
+        .log_lines()
+        .iter()
+        .skip(model.input_log_scroll)
+        .take(rows)
+    {
+        draw_text_truncated(
+            buffer,
+            text_x,
+            y,
+            line,
+            text_width,
+            theme.style_foreground(),
+        );
+        y += 1;
+    }
+}
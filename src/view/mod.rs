@@ -1,13 +1,20 @@
 //! View rendering
 
+mod action_menu;
+mod command_output;
 mod command_palette;
 mod comment_editor;
+mod compare;
 mod components;
 mod diff;
+mod inbox;
+mod input_log;
 mod review_detail;
 mod review_list;
+mod threads_panel;
 
-pub use diff::map_threads_to_diff;
+pub use diff::{hunk_thread_range, map_threads_to_diff};
+pub use review_detail::{compute_line_map, compute_search_hits, compute_stream_regions};
 
 use crate::render_backend::{buffer_clear, OptimizedBuffer};
 
@@ -23,8 +30,18 @@ pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
     match model.screen {
         Screen::ReviewList => review_list::view(model, buffer),
         Screen::ReviewDetail => review_detail::view(model, buffer),
+        Screen::Inbox => inbox::view(model, buffer),
+        Screen::Compare => compare::view(model, buffer),
     }
 
     comment_editor::view(model, buffer);
+    command_output::view(model, buffer);
+    input_log::view(model, buffer);
+    threads_panel::view(model, buffer);
     command_palette::view(model, buffer);
+
+    if model.dim_unfocused && !model.terminal_focused {
+        let screen = Rect::from_size(model.width, model.height);
+        components::dim_rect(buffer, screen, 0.6);
+    }
 }
@@ -1,24 +1,41 @@
 //! Review detail screen rendering
 
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
 use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Rgba, Style};
 
+use super::action_menu;
 use super::components::{
-    dim_rect, draw_help_bar, draw_help_bar_with_bg, draw_text_truncated, truncate_path, HotkeyHint,
-    Rect,
+    dim_rect, draw_empty_state, draw_help_bar_ext, draw_pane_chrome, draw_text_truncated,
+    run_status_badge, severity_color, HotkeyHint, Rect,
 };
 use super::diff::{
-    diff_change_counts, render_diff_stream, render_pinned_header_block, DiffStreamParams,
+    block_inner_width, block_inner_x, diff_change_counts, file_lint_badges, render_diff_stream,
+    render_pinned_header_block, DiffStreamParams, FilePosition,
 };
-use crate::layout::{BLOCK_MARGIN, BLOCK_PADDING, DIFF_MARGIN};
-use crate::model::{Focus, LayoutMode, Model, SidebarItem};
+use crate::db::{thread_location, ReviewDetail, ThreadSide, Verdict, REVIEW_THREAD_PATH};
+use crate::glyphs::glyphs;
+use crate::i18n::{tr, tr_fmt, Text};
+use crate::layout::{block_margin, block_padding, BLOCK_SIDE_MARGIN, DIFF_MARGIN};
+use crate::model::{DiffViewMode, FileEntry, Focus, LayoutMode, MenuAction, Model, SidebarItem};
 use crate::render_backend::color_lerp;
-use crate::stream::{block_height, description_block_height};
+use crate::search::{find_matches, hit_position, SearchHit, SearchKind};
+use crate::stream::{
+    abandoned_banner_height, block_height, description_block_height, review_threads_height,
+    verdict_blocks_height, RenderedRows, StreamLayout,
+};
+use crate::text::{display_width, truncate_end, truncate_middle, truncate_path};
+use crate::theme::Theme;
 
 struct SidebarPadding {
     left: u32,
     right: u32,
 }
 
+/// Columns each level of the tree sidebar is indented by
+const TREE_INDENT: u32 = 2;
+
 /// Render the review detail screen
 pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
     let area = Rect::from_size(model.width, model.height);
@@ -35,11 +52,12 @@ pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
     match model.layout_mode {
         LayoutMode::Full | LayoutMode::Compact | LayoutMode::Overlay => {
             if model.sidebar_visible {
-                let sidebar_width = u32::from(model.layout_mode.sidebar_width());
+                let sidebar_width = u32::from(model.sidebar_width());
                 let (sidebar_area, diff_area) = inner.split_left(sidebar_width);
 
                 draw_file_sidebar(model, buffer, sidebar_area);
                 draw_diff_pane(model, buffer, diff_area);
+                draw_divider_grip(model, buffer, sidebar_area);
             } else {
                 draw_diff_pane(model, buffer, inner);
             }
@@ -58,6 +76,31 @@ pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
     render_help_bar(model, buffer, area);
 }
 
+/// Handle on the sidebar's last column while the mouse is over the divider
+/// or dragging it.
+fn draw_divider_grip(model: &Model, buffer: &mut OptimizedBuffer, sidebar: Rect) {
+    const GRIP_ROWS: u32 = 3;
+    if !(model.divider_hover || model.sidebar_resizing) || sidebar.width == 0 {
+        return;
+    }
+    let color = if model.sidebar_resizing {
+        model.theme.primary
+    } else {
+        model.theme.muted
+    };
+    let x = sidebar.x + sidebar.width - 1;
+    let top = sidebar.y + sidebar.height.saturating_sub(GRIP_ROWS) / 2;
+    for y in top..(top + GRIP_ROWS).min(sidebar.y + sidebar.height) {
+        buffer_draw_text(
+            buffer,
+            x,
+            y,
+            glyphs().grip,
+            Style::fg(color).with_bg(model.theme.panel_bg),
+        );
+    }
+}
+
 fn draw_loading_splash(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
     let theme = &model.theme;
     buffer_fill_rect(
@@ -69,8 +112,8 @@ fn draw_loading_splash(model: &Model, buffer: &mut OptimizedBuffer, area: Rect)
         theme.background,
     );
 
-    let title = "Loading review...";
-    let title_width = title.len() as u32;
+    let title = tr(Text::LoadingReview);
+    let title_width = title.chars().count() as u32;
     let x = area
         .x
         .saturating_add(area.width.saturating_sub(title_width) / 2);
@@ -78,6 +121,79 @@ fn draw_loading_splash(model: &Model, buffer: &mut OptimizedBuffer, area: Rect)
     buffer_draw_text(buffer, x, y, title, Style::fg(theme.foreground).with_bold());
 }
 
+/// Render a directory of the tree sidebar: its name and the open threads
+/// under it.
+fn draw_sidebar_directory_item(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    item: &SidebarItem,
+    item_idx: usize,
+    y: u32,
+    inner: Rect,
+    pad: &SidebarPadding,
+) {
+    let SidebarItem::Directory {
+        path,
+        depth,
+        open_threads,
+        resolved_threads,
+        collapsed,
+        ..
+    } = item
+    else {
+        return;
+    };
+    let theme = &model.theme;
+    let selected = item_idx == model.sidebar_index;
+    let row_bg = if selected && model.focus == Focus::FileSidebar {
+        theme.selection_bg
+    } else if selected {
+        color_lerp(theme.panel_bg, theme.selection_bg, 0.5)
+    } else {
+        theme.panel_bg
+    };
+    if selected {
+        buffer_fill_rect(buffer, inner.x, y, inner.width, 1, row_bg);
+    }
+
+    let prefix_x = inner.x + pad.left + TREE_INDENT * *depth as u32;
+    let indicator = if *collapsed {
+        glyphs().collapsed
+    } else {
+        glyphs().expanded
+    };
+    let style = theme.style_muted_on(row_bg);
+    buffer_draw_text(buffer, prefix_x, y, indicator, style);
+
+    let (count, count_color) = if *open_threads > 0 {
+        (open_threads.to_string(), theme.warning)
+    } else if *resolved_threads > 0 {
+        (glyphs().resolved.to_string(), theme.success)
+    } else {
+        (String::new(), theme.muted)
+    };
+    let count_len = display_width(&count) as u32;
+    let count_x = (inner.x + inner.width).saturating_sub(pad.right + count_len);
+    buffer_draw_text(
+        buffer,
+        count_x,
+        y,
+        &count,
+        Style::fg(count_color).with_bg(row_bg),
+    );
+
+    let name = format!("{}/", path.rsplit('/').next().unwrap_or(path));
+    let name_x = prefix_x + 2;
+    draw_text_truncated(
+        buffer,
+        name_x,
+        y,
+        &name,
+        count_x.saturating_sub(name_x + 1),
+        style,
+    );
+}
+
 /// Render a file item in the sidebar
 fn draw_sidebar_file_item(
     model: &Model,
@@ -92,6 +208,8 @@ fn draw_sidebar_file_item(
         entry,
         file_idx,
         collapsed,
+        viewed,
+        depth,
     } = item
     {
         let theme = &model.theme;
@@ -110,21 +228,28 @@ fn draw_sidebar_file_item(
             buffer_fill_rect(buffer, inner.x, y, inner.width, 1, row_bg);
         }
 
-        let collapse_indicator = if *collapsed { "▸ " } else { "▾ " };
-        let (prefix, style) = if *file_idx == model.file_index {
-            (collapse_indicator, theme.style_primary().with_bg(row_bg))
+        let prefix = if *collapsed {
+            glyphs().collapsed
+        } else {
+            glyphs().expanded
+        };
+        let style = if *file_idx == model.file_index {
+            theme.style_primary().with_bg(row_bg)
+        } else if *viewed {
+            theme.style_muted_on(row_bg)
         } else {
-            (collapse_indicator, theme.style_foreground_on(row_bg))
+            theme.style_foreground_on(row_bg)
         };
 
-        let prefix_x = inner.x + pad.left;
+        let indent = TREE_INDENT * *depth as u32;
+        let prefix_x = inner.x + pad.left + indent;
         buffer_draw_text(buffer, prefix_x, y, prefix, style);
 
         // Thread count indicator
         let thread_indicator = if entry.open_threads > 0 {
             format!("{}", entry.open_threads)
         } else if entry.resolved_threads > 0 {
-            "✓".to_string()
+            glyphs().resolved.to_string()
         } else {
             " ".to_string()
         };
@@ -135,13 +260,28 @@ fn draw_sidebar_file_item(
             theme.success
         };
 
+        // Annotation badge left of the thread indicator: most severe level's
+        // count, drawn reversed so it doesn't read as a thread count
+        let annotation_badge = model
+            .file_annotations(&entry.path)
+            .worst()
+            .map(|(severity, count)| (format!("{count}"), severity_color(theme, severity)));
+        let badge_len = annotation_badge
+            .as_ref()
+            .map_or(0, |(text, _)| text.len() as u32 + 1);
+        // Viewed mark left of the badge
+        let viewed_len: u32 = if *viewed { 2 } else { 0 };
+
         let indicator_len = thread_indicator.chars().count() as u32;
         let prefix_width: u32 = 2;
-        let filename_width = inner
-            .width
-            .saturating_sub(prefix_width + indicator_len + pad.left + pad.right);
+        let filename_width = inner.width.saturating_sub(
+            indent + prefix_width + indicator_len + badge_len + viewed_len + pad.left + pad.right,
+        );
 
-        let filename = truncate_path(&entry.path, filename_width as usize);
+        // The tree shows directories as their own rows
+        let tree_name = entry.path.rsplit_once('/').filter(|_| model.sidebar_tree());
+        let path = tree_name.map_or(entry.path.as_str(), |(_, name)| name);
+        let filename = truncate_path(path, filename_width as usize);
         draw_text_truncated(
             buffer,
             prefix_x + prefix_width,
@@ -162,6 +302,20 @@ fn draw_sidebar_file_item(
             &thread_indicator,
             Style::fg(indicator_color).with_bg(row_bg),
         );
+        if let Some((text, color)) = annotation_badge {
+            let badge_x = indicator_x.saturating_sub(badge_len);
+            buffer_draw_text(buffer, badge_x, y, &text, Style::fg(row_bg).with_bg(color));
+        }
+        if *viewed {
+            let viewed_x = indicator_x.saturating_sub(badge_len + viewed_len);
+            buffer_draw_text(
+                buffer,
+                viewed_x,
+                y,
+                glyphs().viewed,
+                Style::fg(theme.success).with_bg(row_bg),
+            );
+        }
     }
 }
 
@@ -179,6 +333,9 @@ fn draw_sidebar_thread_item(
         thread_id,
         status,
         comment_count,
+        muted,
+        file_level,
+        depth,
         ..
     } = item
     {
@@ -198,13 +355,17 @@ fn draw_sidebar_thread_item(
             buffer_fill_rect(buffer, inner.x, y, inner.width, 1, row_bg);
         }
 
-        let indent: u32 = 4;
+        let indent = 2 + TREE_INDENT * *depth as u32;
         let thread_x = inner.x + pad.left + indent;
 
         // Right-aligned comment count indicator
-        let count_text = format!("{comment_count}");
+        let count_text = if *muted {
+            glyphs().muted.to_string()
+        } else {
+            format!("{comment_count}")
+        };
         let count_len = count_text.chars().count() as u32;
-        let count_color = if status == "open" {
+        let count_color = if status == "open" && !*muted {
             theme.warning
         } else {
             theme.muted
@@ -222,7 +383,12 @@ fn draw_sidebar_thread_item(
         } else {
             theme.style_muted_on(row_bg)
         };
-        draw_text_truncated(buffer, thread_x, y, thread_id, id_width, text_style);
+        let label = if *file_level {
+            format!("{} {thread_id}", glyphs().file_thread)
+        } else {
+            thread_id.clone()
+        };
+        draw_text_truncated(buffer, thread_x, y, &label, id_width, text_style);
 
         buffer_draw_text(
             buffer,
@@ -234,6 +400,107 @@ fn draw_sidebar_thread_item(
     }
 }
 
+/// The review block at the top of the file sidebar: id and status, title,
+/// ref and commit rows, draft verdict. `area` starts at the id row and ends
+/// at the sidebar's last row; returns the row below the block.
+fn draw_sidebar_review_header(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    review: &ReviewDetail,
+    area: Rect,
+) -> u32 {
+    let theme = &model.theme;
+    let (text_x, mut y, text_width) = (area.x, area.y, area.width);
+    let bottom = area.y + area.height;
+    // Header: "cr-xxxx · status"
+    let id_len = review.review_id.len() as u32;
+    draw_text_truncated(
+        buffer,
+        text_x,
+        y,
+        &review.review_id,
+        text_width,
+        Style::fg(theme.foreground).with_bold(),
+    );
+    let sep_x = text_x + id_len;
+    if sep_x + 3 < text_x + text_width {
+        buffer_draw_text(buffer, sep_x, y, " \u{b7} ", theme.style_muted());
+        let status_x = sep_x + 3;
+        draw_text_truncated(
+            buffer,
+            status_x,
+            y,
+            model.statuses.label(&review.status),
+            text_width.saturating_sub(id_len + 3),
+            Style::fg(model.statuses.color(&review.status, theme)),
+        );
+    }
+    if let Some(frame) = model.refresh_spinner() {
+        let x = (text_x + text_width).saturating_sub(1);
+        buffer_draw_text(buffer, x, y, frame, theme.style_muted());
+    }
+    y += 1;
+
+    // Title (word-wrapped, bright, non-bold)
+    if !review.title.is_empty() {
+        y += 1;
+        for line in word_wrap_lines(&review.title, text_width as usize) {
+            if y >= bottom {
+                break;
+            }
+            draw_text_truncated(
+                buffer,
+                text_x,
+                y,
+                &line,
+                text_width,
+                theme.style_foreground(),
+            );
+            y += 1;
+        }
+    }
+    y += 1;
+
+    // Ref and commit ID on separate rows.
+    let ref_display = format_ref_for_display(&review.jj_change_id, text_width as usize);
+    draw_text_truncated(
+        buffer,
+        text_x,
+        y,
+        &ref_display,
+        text_width,
+        theme.style_muted(),
+    );
+    y += 1;
+    // `review_command` status, right-aligned on the commit row
+    let mut commit_width = text_width;
+    if let Some(run) = model.current_command_run() {
+        let (label, color) = run_status_badge(theme, run);
+        let badge = format!(" {label} ");
+        let badge_width = display_width(&badge) as u32;
+        if badge_width < text_width {
+            let x = text_x + text_width - badge_width;
+            buffer_draw_text(
+                buffer,
+                x,
+                y,
+                &badge,
+                Style::fg(theme.panel_bg).with_bg(color),
+            );
+            commit_width = text_width - badge_width - 1;
+        }
+    }
+    let (commit, commit_style) = commit_label(review, theme);
+    draw_text_truncated(buffer, text_x, y, &commit, commit_width, commit_style);
+    y += 1;
+    if let Some(draft) = model.draft_verdict() {
+        draw_draft_verdict_chip(buffer, text_x, y, text_width, draft.verdict, theme);
+        y += 1;
+    }
+    y += 1;
+    y
+}
+
 fn draw_file_sidebar(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
     let theme = &model.theme;
     let inner = area;
@@ -255,82 +522,28 @@ fn draw_file_sidebar(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
 
     // Draw review header info
     if let Some(review) = &model.current_review {
-        // Header: "cr-xxxx · status"
-        let id_len = review.review_id.len() as u32;
-        draw_text_truncated(
+        y = draw_sidebar_review_header(
+            model,
             buffer,
-            text_x,
-            y,
-            &review.review_id,
-            text_width,
-            Style::fg(theme.foreground).with_bold(),
+            review,
+            Rect::new(text_x, y, text_width, bottom.saturating_sub(y)),
         );
-        let sep_x = text_x + id_len;
-        if sep_x + 3 < text_x + text_width {
-            buffer_draw_text(buffer, sep_x, y, " \u{b7} ", theme.style_muted());
-            let status_x = sep_x + 3;
-            let status_color = match review.status.as_str() {
-                "open" | "merged" => theme.success,
-                "abandoned" => theme.muted,
-                "approved" => theme.warning,
-                _ => theme.foreground,
-            };
-            draw_text_truncated(
-                buffer,
-                status_x,
-                y,
-                &review.status,
-                text_width.saturating_sub(id_len + 3),
-                Style::fg(status_color),
-            );
-        }
-        y += 1;
 
-        // Title (word-wrapped, bright, non-bold)
-        if !review.title.is_empty() {
-            y += 1;
-            for line in word_wrap_lines(&review.title, text_width as usize) {
-                if y >= bottom {
-                    break;
-                }
-                draw_text_truncated(
-                    buffer,
-                    text_x,
-                    y,
-                    &line,
-                    text_width,
-                    theme.style_foreground(),
-                );
-                y += 1;
-            }
-        }
-        y += 1;
-
-        // Ref and commit ID on separate rows.
-        let ref_display = format_ref_for_display(&review.jj_change_id, text_width as usize);
-        draw_text_truncated(
+        // The id row and its padding are the sidebar's title bar
+        draw_pane_chrome(
             buffer,
-            text_x,
-            y,
-            &ref_display,
-            text_width,
-            theme.style_muted(),
-        );
-        y += 1;
-        draw_text_truncated(
-            buffer,
-            text_x,
-            y,
-            &review.initial_commit,
-            text_width,
-            theme.style_muted(),
+            Rect::new(inner.x, inner.y, inner.width, inner.height.min(3)),
+            inner.x,
+            model.sidebar_has_focus(),
+            model.focus_tint,
+            theme,
         );
-        y += 2;
     }
 
+    model.sidebar_list_top.set(y);
     if items.is_empty() {
         if y < bottom {
-            buffer_draw_text(buffer, text_x, y, "No files", theme.style_muted());
+            buffer_draw_text(buffer, text_x, y, tr(Text::NoFiles), theme.style_muted());
         }
         return;
     }
@@ -342,6 +555,9 @@ fn draw_file_sidebar(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
         }
 
         match item {
+            SidebarItem::Directory { .. } => {
+                draw_sidebar_directory_item(model, buffer, item, item_idx, y, inner, &pad);
+            }
             SidebarItem::File { .. } => {
                 draw_sidebar_file_item(model, buffer, item, item_idx, y, inner, &pad);
             }
@@ -354,6 +570,30 @@ fn draw_file_sidebar(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
     }
 }
 
+/// Chip for a verdict chosen but not sent yet: "draft verdict: approve".
+fn draw_draft_verdict_chip(
+    buffer: &mut OptimizedBuffer,
+    x: u32,
+    y: u32,
+    width: u32,
+    verdict: Verdict,
+    theme: &Theme,
+) {
+    let (action, color) = match verdict {
+        Verdict::Approve => (MenuAction::Approve, theme.success),
+        Verdict::RequestChanges => (MenuAction::RequestChanges, theme.error),
+    };
+    let label = tr_fmt(Text::DraftVerdictChip, &[&action.label().to_lowercase()]);
+    let chip = truncate_end(&format!(" {label} "), width as usize);
+    buffer_draw_text(
+        buffer,
+        x,
+        y,
+        &chip,
+        Style::fg(theme.panel_bg).with_bg(color),
+    );
+}
+
 /// Simple word-wrap: split text into lines that fit within `max_width` characters.
 fn word_wrap_lines(text: &str, max_width: usize) -> Vec<String> {
     if max_width == 0 {
@@ -383,6 +623,24 @@ fn word_wrap_lines(text: &str, max_width: usize) -> Vec<String> {
     lines
 }
 
+/// The sidebar's commit row: the initial commit or, for a jj change
+/// rewritten since crit recorded the review, the recorded commit and where
+/// it points now in the warning color.
+fn commit_label(review: &ReviewDetail, theme: &Theme) -> (String, Style) {
+    let Some(to) = &review.rewritten_to else {
+        return (review.initial_commit.clone(), theme.style_muted());
+    };
+    let from = review
+        .final_commit
+        .as_deref()
+        .unwrap_or(&review.initial_commit);
+    let (from, to) = (from.get(..8).unwrap_or(from), to.get(..8).unwrap_or(to));
+    (
+        format!("{from} {} {to}", glyphs().crumb),
+        Style::fg(theme.warning),
+    )
+}
+
 fn format_ref_for_display(raw: &str, max_width: usize) -> String {
     if max_width == 0 {
         return String::new();
@@ -393,110 +651,185 @@ fn format_ref_for_display(raw: &str, max_width: usize) -> String {
     } else if let Some(rest) = raw.strip_prefix("refs/remotes/") {
         rest
     } else if let Some(rest) = raw.strip_prefix("refs/tags/") {
-        return format_with_prefix("⎇ ", &format!("tag:{rest}"), max_width);
+        return format_with_prefix(&branch_prefix(), &format!("tag:{rest}"), max_width);
     } else if let Some(rest) = raw.strip_prefix("refs/") {
         rest
     } else {
         raw
     };
 
-    format_with_prefix("⎇ ", branch, max_width)
+    format_with_prefix(&branch_prefix(), branch, max_width)
+}
+
+fn branch_prefix() -> String {
+    format!("{} ", glyphs().branch)
 }
 
 fn format_with_prefix(prefix: &str, body: &str, max_width: usize) -> String {
-    let prefix_chars = prefix.chars().count();
-    if max_width <= prefix_chars {
-        return take_chars(prefix, max_width).to_string();
+    let prefix_width = display_width(prefix);
+    if max_width <= prefix_width {
+        return truncate_end(prefix, max_width);
     }
 
-    let body_width = max_width - prefix_chars;
+    let body_width = max_width - prefix_width;
     let truncated = truncate_middle(body, body_width);
     format!("{prefix}{truncated}")
 }
 
-fn truncate_middle(text: &str, max_chars: usize) -> String {
-    let count = text.chars().count();
-    if count <= max_chars {
-        return text.to_string();
-    }
-
-    if max_chars == 0 {
-        return String::new();
-    }
-    if max_chars == 1 {
-        return "…".to_string();
+/// Rebuild the stream-row → line map for `side` from the current layout.
+///
+/// `Model::line_map` (and `old_line_map`) is recorded while drawing, so after a resize or a
+/// view-mode toggle it describes the previous frame until the next render.
+/// This replays the stream layout off-screen (nothing is drawn) without
+/// touching the model's render state.
+#[must_use]
+pub fn compute_line_map(model: &Model, side: ThreadSide) -> HashMap<usize, i64> {
+    let replay = replay_stream(model, None);
+    match side {
+        ThreadSide::Old => replay.old_line_map,
+        ThreadSide::New => replay.line_map,
     }
+}
 
-    let keep = max_chars - 1;
-    let head = keep / 2;
-    let tail = keep - head;
-    let start = take_chars(text, head);
-    let end = take_last_chars(text, tail);
-    format!("{start}…{end}")
+/// Matches of `query` in the current layout.
+///
+/// Replayed off-screen like [`compute_line_map`]: `Model::search_hits` is
+/// only recorded on the next frame, too late to move the cursor for the
+/// keypress that changed the query.
+#[must_use]
+pub fn compute_search_hits(model: &Model, query: &str) -> Vec<SearchHit> {
+    replay_stream(model, Some(query)).search_hits
 }
 
-fn take_chars(text: &str, max_chars: usize) -> &str {
-    if max_chars == 0 {
-        return "";
-    }
-    for (count, (idx, _)) in text.char_indices().enumerate() {
-        if count == max_chars {
-            return &text[..idx];
-        }
-    }
-    text
+/// The open review's [`StreamLayout`] with the rows of its lines and
+/// threads, for "what row is file F line N at?" without a frame drawn.
+///
+/// Replayed off-screen like [`compute_line_map`].
+#[must_use]
+pub fn compute_stream_regions(model: &Model) -> StreamLayout {
+    let replay = replay_stream(model, None);
+    model.stream_layout().with_rendered_rows(RenderedRows {
+        line_map: replay.line_map,
+        old_line_map: replay.old_line_map,
+        thread_positions: replay.thread_positions,
+    })
 }
 
-fn take_last_chars(text: &str, max_chars: usize) -> String {
-    if max_chars == 0 {
-        return String::new();
-    }
-    let total = text.chars().count();
-    if total <= max_chars {
-        return text.to_string();
-    }
-    text.chars().skip(total - max_chars).collect()
+/// What an off-screen replay of the stream recorded.
+struct StreamReplay {
+    line_map: HashMap<usize, i64>,
+    old_line_map: HashMap<usize, i64>,
+    thread_positions: HashMap<String, usize>,
+    search_hits: Vec<SearchHit>,
 }
 
-fn draw_diff_pane(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
-    let theme = &model.theme;
-    let inner = area;
+fn replay_stream(model: &Model, search: Option<&str>) -> StreamReplay {
+    let pane_width = match model.layout_mode {
+        LayoutMode::Full | LayoutMode::Compact | LayoutMode::Overlay if model.sidebar_visible => {
+            u32::from(model.width).saturating_sub(u32::from(model.sidebar_width()))
+        }
+        _ => u32::from(model.width),
+    };
+    let files = model.files_with_threads();
+    let description = model
+        .current_review
+        .as_ref()
+        .and_then(|r| r.description.as_deref());
 
-    let content_area = Rect::new(
-        inner.x,
-        inner.y,
-        inner.width,
-        inner.height.saturating_sub(3),
+    let line_map = RefCell::new(HashMap::new());
+    let old_line_map = RefCell::new(HashMap::new());
+    let thread_positions = RefCell::new(HashMap::new());
+    let search_hits = RefCell::new(Vec::new());
+    let mut scratch = OptimizedBuffer::new(1, 1);
+    render_diff_stream(
+        &mut scratch,
+        Rect::new(0, 0, pane_width, 0),
+        &DiffStreamParams {
+            files: &files,
+            file_cache: &model.file_cache,
+            pending_files: &model.pending_files,
+            threads: &model.threads,
+            all_comments: &model.all_comments,
+            thread_events: &model.thread_events,
+            scroll: 0,
+            diff_cursor: model.diff_cursor,
+            theme: &model.theme,
+            view_mode: model.diff_view_mode,
+            view_mode_overrides: &model.diff_view_overrides,
+            wrap: model.diff_wrap,
+            show_cr: model.show_cr,
+            thread_positions: &thread_positions,
+            max_stream_row: &Cell::new(0),
+            description,
+            votes: model.review_votes(),
+            abandoned: model.abandoned_review(),
+            selection: None,
+            line_map: &line_map,
+            old_line_map: &old_line_map,
+            sbs_side: model.sbs_side,
+            cursor_stops: &RefCell::new(Vec::new()),
+            hunk_rows: &RefCell::new(Vec::new()),
+            change_rows: &RefCell::new(Vec::new()),
+            comment_positions: &RefCell::new(HashMap::new()),
+            flash_comment: None,
+            thread_rows: &RefCell::new(HashMap::new()),
+            muted_threads: &model.config.muted_threads,
+            word_diff_files: &model.word_diff_files,
+            viewed_files: &model.viewed_files,
+            folds: &model.folds,
+            context_lines: model.context_lines(),
+            search,
+            blame: model.blame_gutter.then_some(&model.blame),
+            search_hits: &search_hits,
+            stream_cache: None,
+        },
     );
-
-    let files = model.files_with_threads();
-    if files.is_empty() {
-        buffer_draw_text(
-            buffer,
-            inner.x + 2,
-            inner.y + 1,
-            "No content available",
-            theme.style_muted(),
-        );
-        return;
+    StreamReplay {
+        line_map: line_map.into_inner(),
+        old_line_map: old_line_map.into_inner(),
+        thread_positions: thread_positions.into_inner(),
+        search_hits: search_hits.into_inner(),
     }
+}
 
-    let file_title = files
-        .get(model.file_index)
-        .map_or("No file selected", |f| f.path.as_str());
-
-    let counts = files
-        .get(model.file_index)
-        .and_then(|file| model.file_cache.get(&file.path))
+/// Pinned header for the current file: path, position in the review, open
+/// threads, lint badges and change counts.
+fn draw_pinned_file_header(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    area: Rect,
+    files: &[FileEntry],
+) {
+    let file = files.get(model.file_index);
+    let file_title = file.map_or_else(|| tr(Text::NoFileSelected), |f| f.path.as_str());
+    let entry = file.and_then(|file| model.file_cache.get(&file.path));
+    let counts = entry
         .and_then(|entry| entry.diff.as_ref())
         .map(diff_change_counts);
+    let badges = entry.map(file_lint_badges).unwrap_or_default();
+    let position = file.map(|file| FilePosition {
+        index: model.file_index,
+        total: files.len(),
+        open_threads: file.open_threads,
+        viewed_files: files
+            .iter()
+            .filter(|f| model.viewed_files.contains(&f.path))
+            .count(),
+        viewed: model.viewed_files.contains(&file.path),
+    });
+    render_pinned_header_block(
+        buffer,
+        area,
+        file_title,
+        &model.theme,
+        counts,
+        &badges,
+        position,
+    );
+}
 
-    let description = model
-        .current_review
-        .as_ref()
-        .and_then(|r| r.description.as_deref());
-
-    // Pinned header at the top
+/// The pinned header at the top of `content_area` and the stream below it.
+fn split_pinned_header(content_area: Rect) -> (Rect, Rect) {
     let pinned_height = block_height(1) as u32;
     let pinned_area = Rect::new(
         content_area.x,
@@ -504,14 +837,38 @@ fn draw_diff_pane(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
         content_area.width,
         pinned_height.min(content_area.height),
     );
-
-    // Stream area starts below the pinned header
     let stream_area = Rect::new(
         content_area.x,
         content_area.y + pinned_height,
         content_area.width,
         content_area.height.saturating_sub(pinned_height),
     );
+    (pinned_area, stream_area)
+}
+
+fn draw_diff_pane(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
+    let theme = &model.theme;
+
+    let content_area = Rect::new(area.x, area.y, area.width, area.height.saturating_sub(3));
+
+    let files = model.files_with_threads();
+    if files.is_empty() {
+        draw_empty_state(
+            buffer,
+            content_area,
+            tr(Text::ReviewEmpty),
+            tr(Text::ReviewEmptyHint),
+            theme,
+        );
+        return;
+    }
+
+    let description = model
+        .current_review
+        .as_ref()
+        .and_then(|r| r.description.as_deref());
+
+    let (pinned_area, stream_area) = split_pinned_header(content_area);
 
     buffer_fill_rect(
         buffer,
@@ -523,13 +880,10 @@ fn draw_diff_pane(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
     );
 
     // Compute visual selection range
-    let selection = if model.visual_mode {
-        let a = model.visual_anchor;
-        let b = model.diff_cursor;
-        Some((a.min(b), a.max(b)))
-    } else {
-        None
-    };
+    let selection = model.visual_mode.then(|| {
+        let (a, b) = (model.visual_anchor, model.diff_cursor);
+        (a.min(b), a.max(b))
+    });
 
     // Render stream content (description block + files) below pinned header
     render_diff_stream(
@@ -538,55 +892,331 @@ fn draw_diff_pane(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
         &DiffStreamParams {
             files: &files,
             file_cache: &model.file_cache,
+            pending_files: &model.pending_files,
             threads: &model.threads,
             all_comments: &model.all_comments,
+            thread_events: &model.thread_events,
             scroll: model.diff_scroll,
-            diff_cursor: model.diff_cursor,
+            diff_cursor: highlighted_cursor(model),
             theme,
             view_mode: model.diff_view_mode,
+            view_mode_overrides: &model.diff_view_overrides,
             wrap: model.diff_wrap,
+            show_cr: model.show_cr,
             thread_positions: &model.thread_positions,
             max_stream_row: &model.max_stream_row,
             description,
+            votes: model.review_votes(),
+            abandoned: model.abandoned_review(),
             selection,
             line_map: &model.line_map,
+            old_line_map: &model.old_line_map,
+            sbs_side: model.sbs_side,
             cursor_stops: &model.cursor_stops,
+            hunk_rows: &model.hunk_rows,
+            change_rows: &model.change_rows,
+            comment_positions: &model.comment_positions,
+            flash_comment: model.flashed_comment(),
+            thread_rows: &model.thread_rows,
+            muted_threads: &model.config.muted_threads,
+            word_diff_files: &model.word_diff_files,
+            viewed_files: &model.viewed_files,
+            folds: &model.folds,
+            context_lines: model.context_lines(),
+            search: model.diff_search_query(),
+            blame: model.blame_gutter.then_some(&model.blame),
+            search_hits: &model.search_hits,
+            stream_cache: Some(&model.stream_cache),
         },
     );
 
+    draw_gutter_cursor(model, buffer, stream_area);
+
     // Render pinned header:
     // - When at top (description visible): show review title
     // - When file header reaches pinned position: show current file header
-    // The file header text is at: desc_lines + BLOCK_MARGIN + BLOCK_PADDING
-    // (accounting for the file block's margin and padding before the header text)
     let layout_width = stream_area.width.saturating_sub(DIFF_MARGIN * 2);
-    let desc_lines = description_block_height(description, layout_width);
-    let file_header_offset = desc_lines + BLOCK_MARGIN + BLOCK_PADDING;
-    if model.diff_scroll >= file_header_offset {
-        // Scrolled past description - show file header
-        render_pinned_header_block(buffer, pinned_area, file_title, theme, counts);
+    if model.diff_scroll >= first_file_header_row(model, description, layout_width) {
+        // Scrolled past description and review threads - show file header
+        draw_pinned_file_header(model, buffer, pinned_area, &files);
     } else if let Some(review) = &model.current_review {
         // At top - show review title
-        render_pinned_header_block(buffer, pinned_area, &review.title, theme, None);
+        render_pinned_header_block(buffer, pinned_area, &review.title, theme, None, &[], None);
     }
+    draw_pinned_header_chrome(model, buffer, pinned_area);
+    draw_breadcrumb(model, buffer, pinned_area, &files);
 
     // Bottom margin between content and footer
-    if inner.height >= 3 {
-        let margin_y = inner.y + inner.height - 3;
-        buffer_fill_rect(buffer, inner.x, margin_y, inner.width, 1, theme.background);
+    if area.height >= 3 {
+        let margin_y = area.y + area.height - 3;
+        buffer_fill_rect(buffer, area.x, margin_y, area.width, 1, theme.background);
+        let bar = Rect::new(area.x, margin_y, area.width, 1);
+        if model.pending_comment_submission.is_some() {
+            draw_pending_comment_bar(model, buffer, bar);
+        } else {
+            draw_diff_search_bar(model, buffer, bar);
+        }
     }
 
     if model.focus == Focus::FileSidebar {
-        dim_rect(buffer, inner, 0.7);
+        dim_rect(buffer, area, 0.7);
+    }
+
+    action_menu::view(model, buffer, stream_area);
+}
+
+/// Stream row to highlight as the cursor; none in lean mode, which marks
+/// it in the gutter instead.
+const fn highlighted_cursor(model: &Model) -> usize {
+    if model.lean_cursor {
+        usize::MAX
+    } else {
+        model.diff_cursor
     }
 }
 
+/// In lean mode, the diff cursor as a marker in the stream's left margin,
+/// so moving it only repaints two cells.
+fn draw_gutter_cursor(model: &Model, buffer: &mut OptimizedBuffer, stream_area: Rect) {
+    if !model.lean_cursor {
+        return;
+    }
+    let Some(row) = model.diff_cursor.checked_sub(model.diff_scroll) else {
+        return;
+    };
+    if row < stream_area.height as usize {
+        buffer_draw_text(
+            buffer,
+            stream_area.x,
+            stream_area.y + row as u32,
+            glyphs().cursor,
+            Style::fg(model.theme.border_focused),
+        );
+    }
+}
+
+/// Focus bar and tint on the pinned header's padded block.
+fn draw_pinned_header_chrome(model: &Model, buffer: &mut OptimizedBuffer, pinned_area: Rect) {
+    let height = block_height(1) as u32;
+    if pinned_area.height < height {
+        return;
+    }
+    let title = Rect::new(
+        pinned_area.x + BLOCK_SIDE_MARGIN,
+        pinned_area.y + block_margin() as u32,
+        pinned_area.width.saturating_sub(BLOCK_SIDE_MARGIN * 2),
+        height - block_margin() as u32 * 2,
+    );
+    draw_pane_chrome(
+        buffer,
+        title,
+        title.x,
+        !model.sidebar_has_focus(),
+        model.focus_tint,
+        &model.theme,
+    );
+}
+
+/// Breadcrumb in the pinned header's top margin: review › file › thread at
+/// the cursor on the left, the recent files Alt+1… jump to on the right.
+fn draw_breadcrumb(model: &Model, buffer: &mut OptimizedBuffer, area: Rect, files: &[FileEntry]) {
+    let Some(review) = &model.current_review else {
+        return;
+    };
+    if area.height == 0 {
+        return;
+    }
+    let theme = &model.theme;
+    let (dim, bright) = (theme.style_muted(), theme.style_foreground());
+    let x = block_inner_x(area);
+    let right = x + block_inner_width(area);
+
+    let file = files.get(model.file_index);
+    let mut segments = vec![review.review_id.clone()];
+    segments.extend(file.map(|f| f.path.clone()));
+    let thread = model
+        .thread_at_cursor()
+        .or_else(|| model.expanded_thread.clone())
+        .and_then(|id| model.threads.iter().find(|t| t.thread_id == id))
+        .filter(|t| file.is_some_and(|f| f.path == t.file_path));
+    segments
+        .extend(thread.map(|t| thread_location(&t.thread_id, t.selection_start, t.selection_end)));
+    let last = segments.pop().unwrap_or_default();
+    let head: String = segments
+        .iter()
+        .map(|segment| [segment, " ", glyphs().crumb, " "].concat())
+        .collect();
+
+    // Recent files right-aligned, dropped from the oldest until they fit
+    // beside the breadcrumb
+    let crumb_width = (display_width(&head) + display_width(&last)) as u32;
+    let mut recent: Vec<(String, String)> = model
+        .recent_other_files()
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let name = path.rsplit('/').next().unwrap_or(path).to_string();
+            (name, format!("alt+{}", i + 1))
+        })
+        .collect();
+    let recent_width = |recent: &[(String, String)]| {
+        recent
+            .iter()
+            .map(|(name, key)| (display_width(name) + key.len() + 3) as u32)
+            .sum::<u32>()
+    };
+    while !recent.is_empty() && x + crumb_width + 2 + recent_width(&recent) > right {
+        recent.pop();
+    }
+    let mut rx = right.saturating_sub(recent_width(&recent).saturating_sub(2));
+    let limit = if recent.is_empty() { right } else { rx - 2 };
+    for (name, key) in &recent {
+        buffer_draw_text(buffer, rx, area.y, name, dim);
+        rx += display_width(name) as u32 + 1;
+        buffer_draw_text(buffer, rx, area.y, key, bright);
+        rx += key.len() as u32 + 2;
+    }
+
+    let head_width = (display_width(&head) as u32).min(limit.saturating_sub(x));
+    draw_text_truncated(buffer, x, area.y, &head, head_width, dim);
+    let last_x = x + head_width;
+    draw_text_truncated(
+        buffer,
+        last_x,
+        area.y,
+        &last,
+        limit.saturating_sub(last_x),
+        bright,
+    );
+}
+
+/// Stream row of the first file header's text: after the verdicts, the
+/// description block and review-level threads, plus the file block's margin
+/// and padding.
+fn first_file_header_row(model: &Model, description: Option<&str>, layout_width: u32) -> usize {
+    abandoned_banner_height(model.abandoned_review(), layout_width)
+        + verdict_blocks_height(model.review_votes(), layout_width)
+        + description_block_height(description, layout_width)
+        + review_threads_height(
+            &model.threads,
+            &model.all_comments,
+            &model.thread_events,
+            &model.config.muted_threads,
+            layout_width,
+        )
+        + block_margin()
+        + block_padding()
+}
+
+/// `/` prompt between the stream and the footer, with the match at the
+/// cursor out of the total and where the matches are.
+fn draw_diff_search_bar(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
+    let Some(search) = &model.diff_search else {
+        return;
+    };
+    let theme = &model.theme;
+    let x = area.x + 2;
+    let width = area.width.saturating_sub(4);
+
+    let status = search.active_query().map_or_else(String::new, |query| {
+        let hits = model.search_hits.borrow();
+        let count = |kind| hits.iter().filter(|hit| hit.kind == kind).count();
+        let title = model
+            .current_review
+            .as_ref()
+            .map_or(0, |review| find_matches(&review.title, query).len());
+        let breakdown = tr_fmt(
+            Text::SearchBreakdown,
+            &[
+                &title,
+                &count(SearchKind::File),
+                &count(SearchKind::Comment),
+                &count(SearchKind::Line),
+            ],
+        );
+        let position = hit_position(&hits, model.diff_cursor);
+        format!("{position}/{}  {breakdown}", hits.len())
+    });
+    let status_width = (display_width(&status) as u32).min(width);
+    buffer_draw_text(
+        buffer,
+        x + width - status_width,
+        area.y,
+        &status,
+        theme.style_muted(),
+    );
+
+    let (prompt, style) = if search.editing {
+        (
+            format!("/{}\u{2588}", search.query),
+            theme.style_foreground(),
+        )
+    } else {
+        (format!("/{}", search.query), theme.style_muted())
+    };
+    let prompt_width = width.saturating_sub(status_width + 2);
+    draw_text_truncated(buffer, x, area.y, &prompt, prompt_width, style);
+}
+
+/// A saved comment waiting out its undo window, in the search bar's row:
+/// where it goes, new thread or reply, its first line and the countdown.
+fn draw_pending_comment_bar(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
+    let Some(submission) = &model.pending_comment_submission else {
+        return;
+    };
+    let theme = &model.theme;
+    let request = &submission.request;
+    let x = area.x + 2;
+    let width = area.width.saturating_sub(4);
+
+    let remaining = submission
+        .send_at
+        .saturating_duration_since(crate::clock::now());
+    let countdown = tr_fmt(
+        Text::PendingCommentCountdown,
+        &[&remaining.as_millis().div_ceil(1000).max(1)],
+    );
+    let countdown_width = (display_width(&countdown) as u32).min(width);
+    buffer_draw_text(
+        buffer,
+        x + width - countdown_width,
+        area.y,
+        &countdown,
+        Style::fg(theme.warning).with_bg(theme.background),
+    );
+
+    let target = request.thread_id.as_ref().map_or_else(
+        || tr(Text::PendingCommentNewThread).to_string(),
+        |thread_id| tr_fmt(Text::PendingCommentReply, &[thread_id]),
+    );
+    let location = if request.file_path == REVIEW_THREAD_PATH {
+        tr(Text::PreviewReviewThread).to_string()
+    } else {
+        thread_location(&request.file_path, request.start_line, request.end_line)
+    };
+    let first_line = submission
+        .body
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default()
+        .trim();
+    let summary = format!("{target} · {location} · \"{first_line}\"");
+    draw_text_truncated(
+        buffer,
+        x,
+        area.y,
+        &summary,
+        width.saturating_sub(countdown_width + 2),
+        theme.style_foreground(),
+    );
+}
+
 /// A hotkey hint: label in dim, key in bright
 fn render_help_bar(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
     let mut footer_x = area.x;
     let mut footer_width = area.width;
     if model.sidebar_visible {
-        let sidebar_width = u32::from(model.layout_mode.sidebar_width());
+        let sidebar_width = u32::from(model.sidebar_width());
         if sidebar_width < area.width
             && matches!(
                 model.layout_mode,
@@ -602,72 +1232,170 @@ fn render_help_bar(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
         return;
     }
 
-    let mut all_hints: Vec<HotkeyHint> = vec![HotkeyHint::new("Commands", "ctrl+p")];
+    let all_hints = footer_hints(model);
+    let footer = Rect::new(footer_x, area.y, footer_width, area.height);
+    let mode_label = if model.read_only {
+        tr(Text::ReadOnlyLabel)
+    } else {
+        ""
+    };
+    if let Some(flash) = &model.flash_message {
+        // Render flash message in error color instead of normal hints.
+        let bg = model.theme.background;
+        let y = footer.y + footer.height.saturating_sub(2);
+        buffer_fill_rect(buffer, footer.x, y, footer.width, 2, bg);
+        let style = Style::fg(model.theme.error).with_bg(bg);
+        draw_text_truncated(
+            buffer,
+            footer.x + 2,
+            y,
+            flash,
+            footer.width.saturating_sub(4),
+            style,
+        );
+    } else {
+        let bg = if model.focus == Focus::FileSidebar {
+            let scale = 0.7;
+            let bg = &model.theme.background;
+            Rgba::new(bg.r * scale, bg.g * scale, bg.b * scale, bg.a)
+        } else {
+            model.theme.background
+        };
+        draw_help_bar_ext(buffer, footer, &model.theme, &all_hints, bg, mode_label);
+    }
+}
+
+/// File sidebar hints; in tree mode `h`/`l` fold and `Esc` goes back.
+fn sidebar_hints(model: &Model) -> Vec<HotkeyHint> {
+    let mut hints = vec![
+        HotkeyHint::new(tr(Text::HintNavigate), "j/k"),
+        HotkeyHint::new(tr(Text::HintOpen), "Enter"),
+        HotkeyHint::new(tr(Text::HintSidebar), "s"),
+    ];
+    if model.sidebar_tree() {
+        hints.extend([
+            HotkeyHint::new(tr(Text::HintFold), "h/l"),
+            HotkeyHint::new(tr(Text::HintBack), "Esc"),
+        ]);
+    } else {
+        hints.push(HotkeyHint::new(tr(Text::HintBack), "h"));
+    }
+    hints.push(HotkeyHint::new(tr(Text::HintQuit), "q"));
+    hints
+}
+
+/// Hotkey hints for the current focus. Mutating actions are left out in
+/// read-only mode.
+fn footer_hints(model: &Model) -> Vec<HotkeyHint> {
+    let mut all_hints: Vec<HotkeyHint> = vec![HotkeyHint::new(tr(Text::HintCommands), "ctrl+p")];
 
     match model.focus {
-        Focus::FileSidebar => {
+        Focus::FileSidebar => all_hints.extend(sidebar_hints(model)),
+        Focus::DiffPane if model.visual_mode => {
             all_hints.extend([
-                HotkeyHint::new("Navigate", "j/k"),
-                HotkeyHint::new("Open", "Enter"),
-                HotkeyHint::new("Sidebar", "s"),
-                HotkeyHint::new("Back", "h"),
-                HotkeyHint::new("Quit", "q"),
+                HotkeyHint::new(tr(Text::HintSelect), "j/k"),
+                HotkeyHint::new(tr(Text::HintCopy), "y"),
             ]);
+            if !model.read_only {
+                all_hints.extend([
+                    HotkeyHint::new(tr(Text::HintComment), "a"),
+                    HotkeyHint::new(tr_fmt(Text::HintCommentWith, &[&model.editor_name]), "A"),
+                    HotkeyHint::new(tr(Text::HintQuote), ">"),
+                ]);
+            }
+            all_hints.push(HotkeyHint::new(tr(Text::HintExit), "V/Esc"));
         }
-        Focus::DiffPane if model.visual_mode => {
+        Focus::DiffPane if model.diff_search.as_ref().is_some_and(|s| s.editing) => {
             all_hints.extend([
-                HotkeyHint::new("Select", "j/k"),
-                HotkeyHint::new("Comment", "a"),
-                HotkeyHint::new(format!("Comment with {}", model.editor_name), "A"),
-                HotkeyHint::new("Exit", "V/Esc"),
+                HotkeyHint::new(tr(Text::HintSearch), "Enter"),
+                HotkeyHint::new(tr(Text::HintCancel), "Esc"),
             ]);
         }
         Focus::DiffPane => {
-            let on_diff_line = model.line_map.borrow().contains_key(&model.diff_cursor);
+            let on_diff_line = model
+                .side_line_map(model.cursor_side())
+                .borrow()
+                .contains_key(&model.diff_cursor);
+            if on_diff_line && !model.read_only {
+                all_hints.push(HotkeyHint::new(tr(Text::HintSelect), "V"));
+            }
             if on_diff_line {
-                all_hints.push(HotkeyHint::new("Select", "V"));
+                all_hints.push(HotkeyHint::new(tr(Text::HintCopy), "y"));
+            }
+            all_hints.push(HotkeyHint::new(tr(Text::HintActions), "."));
+            if model.view_mode_at_row(model.diff_cursor) == DiffViewMode::SideBySide {
+                all_hints.push(HotkeyHint::new(tr(Text::HintSide), "←/→"));
+            }
+            if model.diff_search.is_some() {
+                all_hints.push(HotkeyHint::new(tr(Text::HintMatch), "n/N"));
             }
             all_hints.extend([
-                HotkeyHint::new("View", "v"),
-                HotkeyHint::new("Wrap", "w"),
-                HotkeyHint::new("Open File", "o"),
-                HotkeyHint::new("Sidebar", "s"),
-                HotkeyHint::new("Back", "Esc"),
-                HotkeyHint::new("Quit", "q"),
+                HotkeyHint::new(tr(Text::HintHunk), "{/}"),
+                HotkeyHint::new(tr(Text::HintFile), "(/)"),
+                HotkeyHint::new(tr(Text::HintChange), "+/-"),
+                HotkeyHint::new(tr(Text::HintSearch), "/"),
+                HotkeyHint::new(tr(Text::HintView), "v"),
+                HotkeyHint::new(tr(Text::HintWrap), "w"),
+                HotkeyHint::new(tr(Text::HintOpenFile), "o"),
+                HotkeyHint::new(tr(Text::HintSidebar), "s"),
             ]);
+            if model.diff_search.is_some() {
+                all_hints.push(HotkeyHint::new(tr(Text::HintClear), "Esc"));
+            } else {
+                all_hints.push(HotkeyHint::new(tr(Text::HintBack), "Esc"));
+            }
+            all_hints.push(HotkeyHint::new(tr(Text::HintQuit), "q"));
         }
-        Focus::ThreadExpanded => {
+        Focus::CommandOutput => {
+            all_hints.extend([
+                HotkeyHint::new(tr(Text::HintScroll), "j/k"),
+                HotkeyHint::new(tr(Text::HintRerun), "r"),
+                HotkeyHint::new(tr(Text::HintClose), "Esc"),
+            ]);
+        }
+        Focus::InputLog => {
             all_hints.extend([
-                HotkeyHint::new("Resolve", "r"),
-                HotkeyHint::new("Collapse", "Esc"),
+                HotkeyHint::new(tr(Text::HintScroll), "j/k"),
+                HotkeyHint::new(tr(Text::HintClose), "Esc"),
             ]);
         }
+        Focus::ActionMenu => {
+            all_hints.extend([
+                HotkeyHint::new(tr(Text::HintNavigate), "j/k"),
+                HotkeyHint::new(tr(Text::HintOpen), "Enter"),
+                HotkeyHint::new(tr(Text::HintCancel), "Esc"),
+            ]);
+        }
+        Focus::ThreadExpanded => {
+            if !model.read_only {
+                all_hints.extend([
+                    HotkeyHint::new(tr(Text::HintReply), "r"),
+                    HotkeyHint::new(tr(resolve_hint(model)), "R"),
+                ]);
+            }
+            all_hints.push(HotkeyHint::new(tr(Text::HintCollapse), "Esc"));
+        }
         _ => {
-            all_hints.extend([HotkeyHint::new("Back", "Esc"), HotkeyHint::new("Quit", "q")]);
+            all_hints.extend([
+                HotkeyHint::new(tr(Text::HintBack), "Esc"),
+                HotkeyHint::new(tr(Text::HintQuit), "q"),
+            ]);
         }
     }
 
-    let footer = Rect::new(footer_x, area.y, footer_width, area.height);
-    if let Some(flash) = &model.flash_message {
-        // Render flash message in error color instead of normal hints.
-        let bg = model.theme.background;
-        let y = footer.y + footer.height.saturating_sub(2);
-        buffer_fill_rect(buffer, footer.x, y, footer.width, 2, bg);
-        let style = Style::fg(model.theme.error).with_bg(bg);
-        draw_text_truncated(
-            buffer,
-            footer.x + 2,
-            y,
-            flash,
-            footer.width.saturating_sub(4),
-            style,
-        );
-    } else if model.focus == Focus::FileSidebar {
-        let scale = 0.7;
-        let bg = &model.theme.background;
-        let dimmed_bg = Rgba::new(bg.r * scale, bg.g * scale, bg.b * scale, bg.a);
-        draw_help_bar_with_bg(buffer, footer, &model.theme, &all_hints, dimmed_bg);
+    all_hints
+}
+
+/// `R` resolves an open thread and reopens a resolved one.
+fn resolve_hint(model: &Model) -> Text {
+    let resolved = model
+        .expanded_thread
+        .as_ref()
+        .and_then(|id| model.threads.iter().find(|t| t.thread_id == *id))
+        .is_some_and(|t| t.status != "open");
+    if resolved {
+        Text::HintReopen
     } else {
-        draw_help_bar(buffer, footer, &model.theme, &all_hints);
+        Text::HintResolve
     }
 }
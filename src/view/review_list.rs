@@ -1,23 +1,42 @@
 //! Review list screen rendering
 
-use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Style};
+use crate::annotations::Severity;
+use crate::db::ReviewSummary;
+use crate::i18n::{tr, tr_fmt, Text};
+use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Rgba, Style};
 
 use super::components::{
-    draw_block, draw_help_bar_ext, draw_text_truncated, BlockLine, HotkeyHint, Rect,
+    draw_block, draw_empty_state, draw_help_bar_ext, draw_text_truncated, severity_color,
+    short_timestamp, BlockLine, HotkeyHint, Rect,
 };
-use crate::model::{Model, ReviewFilter};
+use crate::message::Message;
+use crate::model::{ClickTarget, Model, ReviewFilter, ReviewSort};
+use crate::preview::ReviewPreview;
+use crate::text::{display_width, truncate_middle, truncate_path, wrap_text};
 
-/// Height of the header block (margin + padding + 1 content line + padding + margin)
-const HEADER_HEIGHT: u32 = 5;
 /// Height of the search bar area (prompt line + blank line below)
 const SEARCH_HEIGHT: u32 = 2;
 /// Lines per review item
 const ITEM_HEIGHT: u32 = 2;
+/// Description lines shown in the preview pane
+const PREVIEW_DESCRIPTION_LINES: usize = 6;
+/// Files listed in the preview pane before the rest are counted
+const PREVIEW_FILES: usize = 10;
+/// Title room kept on line 1 before metadata columns are dropped
+const MIN_TITLE_WIDTH: u32 = 24;
+/// Gap between metadata columns
+const COLUMN_GAP: u32 = 2;
+/// Left and right margins of a review item, and its text padding
+const ITEM_MARGIN: u32 = 2;
+const ITEM_LEFT_PAD: u32 = 3;
+const ITEM_RIGHT_PAD: u32 = 2;
 
 /// Render the review list screen
 pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
     let theme = &model.theme;
     let area = Rect::from_size(model.width, model.height);
+    let header_height = crate::layout::screen_header_height();
+    model.list_click_targets.borrow_mut().clear();
 
     // Fill background
     buffer_fill_rect(
@@ -31,18 +50,18 @@ pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
 
     // Header block
     let header_text = model.repo_path.as_ref().map_or_else(
-        || "Reviews".to_string(),
+        || tr(Text::Reviews).to_string(),
         |path| {
             let display_path = std::env::var("HOME")
                 .ok()
                 .and_then(|home| path.strip_prefix(&home).map(|rest| format!("~{rest}")))
                 .unwrap_or_else(|| path.clone());
-            format!("Reviews for {display_path}")
+            tr_fmt(Text::ReviewsFor, &[&display_path])
         },
     );
     draw_block(
         buffer,
-        Rect::new(area.x, area.y, area.width, HEADER_HEIGHT),
+        Rect::new(area.x, area.y, area.width, header_height),
         theme,
         theme.panel_bg,
         &[BlockLine::new(
@@ -50,32 +69,68 @@ pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
             Style::fg(theme.foreground).with_bold(),
         )],
     );
+    {
+        use crate::layout::{block_margin, block_padding, BLOCK_RIGHT_PAD, BLOCK_SIDE_MARGIN};
+        let right = (area.x + area.width).saturating_sub(BLOCK_SIDE_MARGIN + BLOCK_RIGHT_PAD);
+        let y = area.y + (block_margin() + block_padding()) as u32;
+        if let Some(frame) = model.refresh_spinner() {
+            buffer_draw_text(
+                buffer,
+                right - 1,
+                y,
+                frame,
+                theme.style_muted_on(theme.panel_bg),
+            );
+        }
+        draw_active_filters(model, buffer, &header_text, right.saturating_sub(3), y);
+    }
 
-    // Search bar
-    let search_y = area.y + HEADER_HEIGHT;
-    draw_search_bar(model, buffer, area.x, search_y, area.width);
+    // Search bar (or the filter bar while it's open)
+    let search_y = area.y + header_height;
+    if model.filter_bar_active {
+        draw_filter_bar(model, buffer, area.x, search_y, area.width);
+    } else {
+        draw_search_bar(model, buffer, area.x, search_y, area.width);
+    }
 
     // List area
     let list_y = search_y + SEARCH_HEIGHT;
     let list_height = area
         .height
-        .saturating_sub(HEADER_HEIGHT + SEARCH_HEIGHT + 2); // 2 for help bar
-    let list_area = Rect::new(area.x, list_y, area.width, list_height);
+        .saturating_sub(header_height + SEARCH_HEIGHT + 2); // 2 for help bar
+    let list_width = model
+        .preview_x()
+        .map_or(area.width, |x| u32::from(x).saturating_sub(area.x));
+    let list_area = Rect::new(area.x, list_y, list_width, list_height);
 
     let reviews = model.filtered_reviews();
-
-    if reviews.is_empty() {
-        buffer_draw_text(
+    if model.preview_x().is_some() {
+        let preview_area = Rect::new(
+            area.x + list_width,
+            list_y,
+            area.width.saturating_sub(list_width + 2),
+            list_height,
+        );
+        draw_preview(
+            model,
             buffer,
-            list_area.x + 4,
-            list_area.y,
-            "No reviews found",
-            theme.style_muted(),
+            preview_area,
+            reviews.get(model.list_index).copied(),
         );
+    }
+
+    if reviews.is_empty() {
+        let (title, hint) = empty_list_message(model);
+        draw_empty_state(buffer, list_area, &title, tr(hint), theme);
         render_help_bar(model, buffer, area);
         return;
     }
 
+    let columns = column_layout(model, &reviews, list_area);
+    if !model.filter_bar_active {
+        draw_column_titles(model, buffer, list_area, search_y + 1, &columns);
+    }
+
     let visible_items = (list_height / ITEM_HEIGHT) as usize;
     let start = model.list_scroll.min(reviews.len());
     let end = (start + visible_items).min(reviews.len());
@@ -83,12 +138,45 @@ pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
     for (row, review) in reviews[start..end].iter().enumerate() {
         let idx = start + row;
         let y = list_area.y + (row as u32) * ITEM_HEIGHT;
-        draw_review_item(model, buffer, list_area, y, review, idx == model.list_index);
+        let selected = idx == model.list_index;
+        draw_review_item(model, buffer, list_area, y, review, &columns, selected);
     }
 
     render_help_bar(model, buffer, area);
 }
 
+/// Why the list is empty and the hint for what to do: no reviews at all,
+/// then the search, the filter bar and the status filter in turn.
+fn empty_list_message(model: &Model) -> (String, Text) {
+    if model.reviews.is_empty() {
+        return (tr(Text::NoReviews).to_string(), Text::EmptyNoReviewsHint);
+    }
+    if !model.search_input.is_empty() {
+        let hint = if model.search_active {
+            Text::EmptySearchActiveHint
+        } else {
+            Text::EmptySearchHint
+        };
+        return (
+            tr_fmt(Text::EmptySearchNoMatch, &[&model.search_input]),
+            hint,
+        );
+    }
+    if !model.review_query.is_empty() {
+        return (
+            tr(Text::EmptyFilterNoMatch).to_string(),
+            Text::EmptyFilterHint,
+        );
+    }
+    let title = match model.filter {
+        ReviewFilter::Open => Text::EmptyNoOpenReviews,
+        ReviewFilter::Closed => Text::EmptyNoClosedReviews,
+        ReviewFilter::Owned => Text::EmptyNoOwnedReviews,
+        ReviewFilter::All => Text::NoReviews,
+    };
+    (tr(title).to_string(), Text::EmptyStatusFilterHint)
+}
+
 fn draw_search_bar(model: &Model, buffer: &mut OptimizedBuffer, x: u32, y: u32, width: u32) {
     let theme = &model.theme;
     buffer_fill_rect(buffer, x, y, width, SEARCH_HEIGHT, theme.background);
@@ -99,8 +187,91 @@ fn draw_search_bar(model: &Model, buffer: &mut OptimizedBuffer, x: u32, y: u32,
         let visible = tail_chars(&model.search_input, max_chars);
         let prompt = format!("/ {visible}\u{2588}");
         buffer_draw_text(buffer, text_x, y, &prompt, theme.style_foreground());
+    } else if !model.search_input.is_empty() {
+        // Query still filtering the list (e.g. from a saved startup filter)
+        let max_chars = width.saturating_sub(7) as usize;
+        let prompt = format!("/ {}", tail_chars(&model.search_input, max_chars));
+        buffer_draw_text(buffer, text_x, y, &prompt, theme.style_muted());
     } else {
-        buffer_draw_text(buffer, text_x, y, "Press / to search", theme.style_muted());
+        buffer_draw_text(
+            buffer,
+            text_x,
+            y,
+            tr(Text::PressSlashToSearch),
+            theme.style_muted(),
+        );
+    }
+}
+
+/// `filter: author:bob path:src/**`, right-aligned to end before `right` in
+/// the header, when the filter bar has terms. Dropped if it would run into
+/// the header text.
+fn draw_active_filters(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    header_text: &str,
+    right: u32,
+    y: u32,
+) {
+    use crate::layout::{BLOCK_LEFT_PAD, BLOCK_SIDE_MARGIN};
+    if model.review_query.is_empty() {
+        return;
+    }
+    let text = tr_fmt(Text::FiltersActive, &[&model.filter_bar_input.trim()]);
+    let header_end = BLOCK_SIDE_MARGIN + BLOCK_LEFT_PAD + display_width(header_text) as u32;
+    let room = right.saturating_sub(header_end + 2) as usize;
+    let text = truncate_middle(&text, room);
+    if text.is_empty() {
+        return;
+    }
+    let x = right.saturating_sub(display_width(&text) as u32);
+    let theme = &model.theme;
+    buffer_draw_text(
+        buffer,
+        x,
+        y,
+        &text,
+        Style::fg(theme.primary).with_bg(theme.panel_bg),
+    );
+}
+
+/// The filter bar being edited, with the syntax while it's empty and words
+/// it doesn't understand on the right.
+fn draw_filter_bar(model: &Model, buffer: &mut OptimizedBuffer, x: u32, y: u32, width: u32) {
+    let theme = &model.theme;
+    buffer_fill_rect(buffer, x, y, width, SEARCH_HEIGHT, theme.background);
+
+    let text_x = x + 5;
+    let invalid = if model.review_query.invalid.is_empty() {
+        String::new()
+    } else {
+        tr_fmt(
+            Text::FilterBarUnknown,
+            &[&model.review_query.invalid.join(" ")],
+        )
+    };
+    let invalid_width = display_width(&invalid) as u32;
+    let prompt_room = width.saturating_sub(10 + invalid_width);
+    let label = tr_fmt(Text::FiltersActive, &[&""]);
+    let max_chars = prompt_room.saturating_sub(display_width(&label) as u32 + 1) as usize;
+    let prompt = format!(
+        "{label}{}\u{2588}",
+        tail_chars(&model.filter_bar_input, max_chars)
+    );
+    buffer_draw_text(buffer, text_x, y, &prompt, theme.style_foreground());
+    if model.filter_bar_input.is_empty() {
+        draw_text_truncated(
+            buffer,
+            text_x + display_width(&prompt) as u32 + 1,
+            y,
+            tr(Text::FilterBarHelp),
+            prompt_room.saturating_sub(display_width(&prompt) as u32 + 1),
+            theme.style_muted(),
+        );
+    }
+    if !invalid.is_empty() {
+        let invalid_x = (x + width).saturating_sub(5 + invalid_width);
+        buffer_draw_text(buffer, invalid_x, y, &invalid, Style::fg(theme.error));
     }
 }
 
@@ -124,7 +295,8 @@ fn draw_review_item(
     buffer: &mut OptimizedBuffer,
     area: Rect,
     y: u32,
-    review: &crate::db::ReviewSummary,
+    review: &ReviewSummary,
+    columns: &[PlacedColumn],
     selected: bool,
 ) {
     let theme = &model.theme;
@@ -135,17 +307,16 @@ fn draw_review_item(
     };
 
     // Fill both lines with 2-space margin on each side
-    let margin: u32 = 2;
-    let item_x = area.x + margin;
-    let item_width = area.width.saturating_sub(margin * 2);
+    let item_x = area.x + ITEM_MARGIN;
+    let item_width = area.width.saturating_sub(ITEM_MARGIN * 2);
     buffer_fill_rect(buffer, item_x, y, item_width, ITEM_HEIGHT, bg);
 
-    let left_pad: u32 = 3;
-    let right_pad: u32 = 2;
-    let mut x = item_x + left_pad;
-    let right_edge = item_x + item_width.saturating_sub(right_pad);
+    let mut x = item_x + ITEM_LEFT_PAD;
+    let right_edge = item_right_edge(area);
+
+    draw_compare_mark(model, buffer, item_x + 1, y, &review.review_id, selected);
 
-    // === Line 1: id  title ...    N th ===
+    // === Line 1: id  title ...    files  +/-  created  updated  N th ===
 
     // Review ID
     let id_style = Style::fg(theme.primary).with_bg(bg);
@@ -153,27 +324,14 @@ fn draw_review_item(
     buffer_draw_text(buffer, x, y, &review.review_id, id_style);
     x += id_len + 2;
 
-    // Thread count (right-aligned): "N th"
-    let thread_text = format_thread_label(review.thread_count, review.open_thread_count);
-    let thread_len = thread_text.len() as u32;
-    let thread_x = right_edge.saturating_sub(thread_len);
-    let thread_color = if selected {
-        theme.selection_fg
-    } else if review.open_thread_count > 0 {
-        theme.warning
-    } else {
-        theme.muted
-    };
-    buffer_draw_text(
-        buffer,
-        thread_x,
-        y,
-        &thread_text,
-        Style::fg(thread_color).with_bg(bg),
-    );
+    // Metadata columns (right-aligned)
+    for column in columns {
+        draw_cell(model, buffer, y, review, column, bg, selected);
+    }
+    let columns_x = columns.first().map_or(right_edge, |c| c.x);
 
-    // Title (fills space between ID and thread count)
-    let title_width = thread_x.saturating_sub(x + 1);
+    // Title (fills space between ID and the columns)
+    let title_width = columns_x.saturating_sub(x + 1);
     let title_style = if selected {
         Style::fg(theme.selection_fg).with_bg(bg)
     } else {
@@ -183,22 +341,17 @@ fn draw_review_item(
 
     // === Line 2: [status]  @author ===
     let y2 = y + 1;
-    let mut x2 = item_x + left_pad;
+    let mut x2 = item_x + ITEM_LEFT_PAD;
 
     // Status badge
-    let badge = format!("[{}]", review.status);
+    let badge = format!("[{}]", model.statuses.label(&review.status));
     let badge_color = if selected {
         theme.selection_fg
     } else {
-        match review.status.as_str() {
-            "open" | "merged" => theme.success,
-            "abandoned" => theme.muted,
-            "approved" => theme.warning,
-            _ => theme.foreground,
-        }
+        model.statuses.color(&review.status, theme)
     };
     buffer_draw_text(buffer, x2, y2, &badge, Style::fg(badge_color).with_bg(bg));
-    x2 += badge.len() as u32 + 2;
+    x2 += display_width(&badge) as u32 + 2;
 
     // Author -> Reviewers
     let people = if review.reviewers.is_empty() {
@@ -212,7 +365,27 @@ fn draw_review_item(
     } else {
         theme.muted
     };
-    let people_width = right_edge.saturating_sub(x2);
+    // Badges right-aligned under the thread count
+    let badges = row_badges(model, &review.review_id);
+    let mut right = right_edge;
+    for (badge, color) in badges.iter().rev() {
+        let badge_x = right.saturating_sub(display_width(badge) as u32);
+        let badge_color = if selected { theme.selection_fg } else { *color };
+        buffer_draw_text(
+            buffer,
+            badge_x,
+            y2,
+            badge,
+            Style::fg(badge_color).with_bg(bg),
+        );
+        right = badge_x.saturating_sub(2);
+    }
+    let people_right = if badges.is_empty() {
+        right_edge
+    } else {
+        right + 1
+    };
+    let people_width = people_right.saturating_sub(x2);
     draw_text_truncated(
         buffer,
         x2,
@@ -223,6 +396,437 @@ fn draw_review_item(
     );
 }
 
+/// A metadata column on line 1 of each review, left to right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Files,
+    Lines,
+    Created,
+    Updated,
+    Threads,
+}
+
+impl Column {
+    const ALL: [Self; 5] = [
+        Self::Files,
+        Self::Lines,
+        Self::Created,
+        Self::Updated,
+        Self::Threads,
+    ];
+    /// Dropped in this order when the list is too narrow for all of them
+    const DROP_ORDER: [Self; 4] = [Self::Created, Self::Lines, Self::Files, Self::Updated];
+
+    const fn title(self) -> Text {
+        match self {
+            Self::Files => Text::ColumnFiles,
+            Self::Lines => Text::ColumnLines,
+            Self::Created => Text::ColumnCreated,
+            Self::Updated => Text::ColumnUpdated,
+            Self::Threads => Text::ColumnThreads,
+        }
+    }
+
+    /// Order that clicking the column's title picks.
+    const fn sort(self) -> ReviewSort {
+        match self {
+            Self::Files => ReviewSort::Files,
+            Self::Lines => ReviewSort::Lines,
+            Self::Created => ReviewSort::Created,
+            Self::Updated => ReviewSort::Updated,
+            Self::Threads => ReviewSort::OpenThreads,
+        }
+    }
+}
+
+/// A column where it's drawn: `x` to `x + width`, text right-aligned.
+struct PlacedColumn {
+    column: Column,
+    x: u32,
+    width: u32,
+}
+
+const fn item_right_edge(area: Rect) -> u32 {
+    area.x + ITEM_MARGIN + area.width.saturating_sub(ITEM_MARGIN * 2 + ITEM_RIGHT_PAD)
+}
+
+/// Columns sized to their widest cell among `reviews` and placed from the
+/// right edge, dropping the least useful while titles would get less than
+/// [`MIN_TITLE_WIDTH`].
+fn column_layout(model: &Model, reviews: &[&ReviewSummary], area: Rect) -> Vec<PlacedColumn> {
+    let mut widths: Vec<(Column, u32)> = Column::ALL
+        .iter()
+        .map(|&column| {
+            // Room for the sort arrow either way, so titles don't shift
+            let title = display_width(tr(column.title())) as u32 + 2;
+            let cells = reviews
+                .iter()
+                .map(|review| display_width(&cell_text(model, review, column)) as u32);
+            (column, cells.fold(title, u32::max))
+        })
+        .collect();
+    let id_width = reviews
+        .iter()
+        .map(|review| display_width(&review.review_id) as u32)
+        .max()
+        .unwrap_or(0);
+    let right_edge = item_right_edge(area);
+    let title_x = area.x + ITEM_MARGIN + ITEM_LEFT_PAD + id_width + 2;
+    let columns_width = |widths: &[(Column, u32)]| {
+        widths
+            .iter()
+            .map(|(_, width)| width + COLUMN_GAP)
+            .sum::<u32>()
+    };
+    for drop in Column::DROP_ORDER {
+        if right_edge.saturating_sub(title_x + columns_width(&widths)) >= MIN_TITLE_WIDTH {
+            break;
+        }
+        widths.retain(|(column, _)| *column != drop);
+    }
+
+    let mut right = right_edge;
+    let mut placed: Vec<PlacedColumn> = widths
+        .iter()
+        .rev()
+        .map(|&(column, width)| {
+            let x = right.saturating_sub(width);
+            right = x.saturating_sub(COLUMN_GAP);
+            PlacedColumn { column, x, width }
+        })
+        .collect();
+    placed.reverse();
+    placed
+}
+
+/// `review`'s value in `column`; empty until the scan has filled in what
+/// crit didn't list.
+fn cell_text(model: &Model, review: &ReviewSummary, column: Column) -> String {
+    match column {
+        Column::Files => model
+            .review_stats(review)
+            .map_or_else(String::new, |stats| stats.files.to_string()),
+        Column::Lines => model
+            .review_stats(review)
+            .map_or_else(String::new, |stats| {
+                format!("+{} -{}", stats.added, stats.removed)
+            }),
+        Column::Created => review
+            .created_at
+            .as_deref()
+            .map_or_else(String::new, list_timestamp),
+        Column::Updated => model
+            .review_updated_at(review)
+            .map_or_else(String::new, list_timestamp),
+        Column::Threads => format_thread_label(review.thread_count, review.open_thread_count),
+    }
+}
+
+/// `MM-DD HH:MM` for an RFC 3339 timestamp; anything else as is.
+fn list_timestamp(ts: &str) -> String {
+    let short = short_timestamp(ts);
+    match short.get(5..) {
+        Some(rest) if short.len() == 16 && short.as_bytes()[10] == b' ' => rest.to_string(),
+        _ => short,
+    }
+}
+
+fn draw_cell(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    y: u32,
+    review: &ReviewSummary,
+    placed: &PlacedColumn,
+    bg: Rgba,
+    selected: bool,
+) {
+    let theme = &model.theme;
+    let text = cell_text(model, review, placed.column);
+    let right = placed.x + placed.width;
+    let x = right.saturating_sub(display_width(&text) as u32);
+    let color = if selected {
+        theme.selection_fg
+    } else {
+        match placed.column {
+            Column::Threads if review.open_thread_count > 0 => theme.warning,
+            Column::Lines => theme.diff.added,
+            _ => theme.muted,
+        }
+    };
+    buffer_draw_text(buffer, x, y, &text, Style::fg(color).with_bg(bg));
+    // Removed lines in their own color
+    if let (Column::Lines, false, Some(minus)) = (placed.column, selected, text.find(" -")) {
+        buffer_draw_text(
+            buffer,
+            x + display_width(&text[..minus]) as u32,
+            y,
+            &text[minus..],
+            Style::fg(theme.diff.removed).with_bg(bg),
+        );
+    }
+}
+
+/// `sorted by …` (and `grouped by status`) on the left and the column titles
+/// over their columns, the sorted one marked with an arrow.
+fn draw_column_titles(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    area: Rect,
+    y: u32,
+    columns: &[PlacedColumn],
+) {
+    let theme = &model.theme;
+    let sort = match model.review_sort {
+        ReviewSort::Status => Text::SortStatus,
+        ReviewSort::Updated => Text::SortUpdated,
+        ReviewSort::Created => Text::SortCreated,
+        ReviewSort::Author => Text::SortAuthor,
+        ReviewSort::OpenThreads => Text::SortOpenThreads,
+        ReviewSort::Files => Text::SortFiles,
+        ReviewSort::Lines => Text::SortLines,
+    };
+    let mut label = tr_fmt(Text::SortedBy, &[&tr(sort)]);
+    if model.group_reviews_by_status() {
+        label = format!("{label} \u{b7} {}", tr(Text::GroupedByStatus));
+    }
+    let x = area.x + ITEM_MARGIN + ITEM_LEFT_PAD;
+    let room = columns
+        .first()
+        .map_or_else(|| item_right_edge(area), |c| c.x)
+        .saturating_sub(x + 1);
+    draw_text_truncated(buffer, x, y, &label, room, theme.style_muted());
+    let arrow = if model.review_sort_reversed() {
+        '\u{2191}'
+    } else {
+        '\u{2193}'
+    };
+    let mut targets = model.list_click_targets.borrow_mut();
+    for placed in columns {
+        let sorted = placed.column.sort() == model.review_sort;
+        // Clicking the sorted column again reverses it
+        targets.push(ClickTarget {
+            y,
+            x: placed.x..placed.x + placed.width,
+            message: if sorted {
+                Message::ReverseReviewSort
+            } else {
+                Message::SetReviewSort(placed.column.sort())
+            },
+        });
+        let title = if sorted {
+            format!("{} {arrow}", tr(placed.column.title()))
+        } else {
+            tr(placed.column.title()).to_string()
+        };
+        let style = if sorted {
+            Style::fg(theme.primary).with_bold()
+        } else {
+            theme.style_muted()
+        };
+        let title_x = (placed.x + placed.width).saturating_sub(display_width(&title) as u32);
+        buffer_draw_text(buffer, title_x, y, &title, style);
+    }
+}
+
+/// A line of the preview pane, with an optional right-aligned note.
+struct PreviewRow {
+    text: String,
+    style: Style,
+    note: Option<(String, Style)>,
+}
+
+impl PreviewRow {
+    fn new(text: impl Into<String>, style: Style) -> Self {
+        Self {
+            text: text.into(),
+            style,
+            note: None,
+        }
+    }
+
+    fn with_note(mut self, note: String, style: Style) -> Self {
+        self.note = Some((note, style));
+        self
+    }
+}
+
+/// The highlighted review's description, changed files and open threads,
+/// cut off at the bottom of `area`.
+fn draw_preview(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    area: Rect,
+    review: Option<&ReviewSummary>,
+) {
+    let theme = &model.theme;
+    let bg = theme.panel_bg;
+    buffer_fill_rect(buffer, area.x, area.y, area.width, area.height, bg);
+    let Some(review) = review else {
+        return;
+    };
+    let x = area.x + 2;
+    let width = area.width.saturating_sub(4);
+    let rows = preview_rows(model, review, width as usize);
+    for (row, line) in rows
+        .iter()
+        .take(area.height.saturating_sub(2) as usize)
+        .enumerate()
+    {
+        let y = area.y + 1 + row as u32;
+        let text_width = line.note.as_ref().map_or(width, |(note, style)| {
+            let note_width = display_width(note) as u32;
+            buffer_draw_text(
+                buffer,
+                (x + width).saturating_sub(note_width),
+                y,
+                note,
+                style.with_bg(bg),
+            );
+            width.saturating_sub(note_width + 2)
+        });
+        draw_text_truncated(buffer, x, y, &line.text, text_width, line.style.with_bg(bg));
+    }
+}
+
+fn preview_rows(model: &Model, review: &ReviewSummary, width: usize) -> Vec<PreviewRow> {
+    let theme = &model.theme;
+    let muted = Style::fg(theme.muted);
+    let heading = Style::fg(theme.primary).with_bold();
+    let mut rows = vec![
+        PreviewRow::new(&review.title, Style::fg(theme.foreground).with_bold()),
+        PreviewRow::new("", muted),
+    ];
+    let Some(preview) = model.previews.get(&review.review_id) else {
+        rows.push(PreviewRow::new(tr(Text::LoadingFile), muted));
+        return rows;
+    };
+    if let Some(error) = &preview.error {
+        rows.push(PreviewRow::new(error, Style::fg(theme.error)));
+        return rows;
+    }
+    preview_description(
+        preview,
+        width,
+        &mut rows,
+        Style::fg(theme.foreground),
+        muted,
+    );
+
+    rows.push(PreviewRow::new("", muted));
+    rows.push(PreviewRow::new(
+        tr_fmt(Text::PreviewFiles, &[&preview.files.len()]),
+        heading,
+    ));
+    for file in preview.files.iter().take(PREVIEW_FILES) {
+        let counts = format!("+{} -{}", file.added, file.removed);
+        let room = width.saturating_sub(display_width(&counts) + 2);
+        rows.push(
+            PreviewRow::new(truncate_path(&file.path, room), Style::fg(theme.foreground))
+                .with_note(counts, muted),
+        );
+    }
+    if preview.files.len() > PREVIEW_FILES {
+        let more = preview.files.len() - PREVIEW_FILES;
+        rows.push(PreviewRow::new(
+            tr_fmt(Text::PreviewMoreFiles, &[&more]),
+            muted,
+        ));
+    }
+
+    rows.push(PreviewRow::new("", muted));
+    rows.push(PreviewRow::new(
+        tr_fmt(Text::PreviewOpenThreads, &[&preview.open_threads.len()]),
+        heading,
+    ));
+    if preview.open_threads.is_empty() {
+        rows.push(PreviewRow::new(tr(Text::PreviewNoOpenThreads), muted));
+    }
+    for thread in &preview.open_threads {
+        let location = if thread.location.is_empty() {
+            tr(Text::PreviewReviewThread).to_string()
+        } else {
+            let room = width.saturating_sub(display_width(&thread.author) + 3);
+            truncate_path(&thread.location, room)
+        };
+        rows.push(
+            PreviewRow::new(location, Style::fg(theme.warning))
+                .with_note(format!("@{}", thread.author), muted),
+        );
+        rows.push(PreviewRow::new(format!("  {}", thread.summary), muted));
+    }
+    rows
+}
+
+/// The description wrapped to `width`, cut after a few lines.
+fn preview_description(
+    preview: &ReviewPreview,
+    width: usize,
+    rows: &mut Vec<PreviewRow>,
+    style: Style,
+    muted: Style,
+) {
+    let Some(description) = &preview.description else {
+        rows.push(PreviewRow::new(tr(Text::PreviewNoDescription), muted));
+        return;
+    };
+    let mut lines = wrap_text(description, width.max(1));
+    if lines.len() > PREVIEW_DESCRIPTION_LINES {
+        lines.truncate(PREVIEW_DESCRIPTION_LINES);
+        if let Some(last) = lines.last_mut() {
+            last.push('…');
+        }
+    }
+    rows.extend(lines.into_iter().map(|line| PreviewRow::new(line, style)));
+}
+
+/// 1 or 2 when the review is marked for comparison: the side it will be
+/// compared on.
+fn draw_compare_mark(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    x: u32,
+    y: u32,
+    review_id: &str,
+    selected: bool,
+) {
+    let theme = &model.theme;
+    let Some(position) = model.compare_marks.iter().position(|id| id == review_id) else {
+        return;
+    };
+    let (fg, bg) = if selected {
+        (theme.selection_fg, theme.selection_bg)
+    } else {
+        (theme.warning, theme.background)
+    };
+    buffer_draw_text(
+        buffer,
+        x,
+        y,
+        &(position + 1).to_string(),
+        Style::fg(fg).with_bg(bg).with_bold(),
+    );
+}
+
+/// Line 2 badges, left to right: files the user owns, then the most severe
+/// level of lint/CI annotations.
+fn row_badges(model: &Model, review_id: &str) -> Vec<(String, Rgba)> {
+    let theme = &model.theme;
+    let mut badges = Vec::new();
+    let owned = model.owned_file_count(review_id);
+    if owned > 0 {
+        badges.push((tr_fmt(Text::OwnedFiles, &[&owned]), theme.primary));
+    }
+    if let Some((severity, count)) = model.review_annotations(review_id).worst() {
+        let text = match severity {
+            Severity::Error => Text::AnnotationErrors,
+            Severity::Warning => Text::AnnotationWarnings,
+            Severity::Notice => Text::AnnotationNotices,
+        };
+        badges.push((tr_fmt(text, &[&count]), severity_color(theme, severity)));
+    }
+    badges
+}
+
 fn format_thread_label(total: i64, open: i64) -> String {
     if total == 0 {
         return String::new();
@@ -236,45 +840,66 @@ fn format_thread_label(total: i64, open: i64) -> String {
 
 fn render_help_bar(model: &Model, buffer: &mut OptimizedBuffer, area: Rect) {
     let version = concat!("crit-ui v", env!("CARGO_PKG_VERSION"));
+    let label = if model.read_only {
+        format!("{version} \u{b7} {}", tr(Text::ReadOnlyLabel))
+    } else {
+        version.to_string()
+    };
     let filter_hint = HotkeyHint::new(
         match model.filter {
-            ReviewFilter::All => "Status (All)",
-            ReviewFilter::Open => "Status (Open)",
-            ReviewFilter::Closed => "Status (Closed)",
+            ReviewFilter::All => tr(Text::StatusAll),
+            ReviewFilter::Open => tr(Text::StatusOpen),
+            ReviewFilter::Closed => tr(Text::StatusClosed),
+            ReviewFilter::Owned => tr(Text::StatusOwned),
         },
         "s",
     );
 
-    if model.search_active {
-        let hints = &[
-            HotkeyHint::new("Commands", "ctrl+p"),
-            HotkeyHint::new("Select", "Enter"),
+    let hints = if model.filter_bar_active {
+        vec![
+            HotkeyHint::new(tr(Text::HintSubmit), "Enter"),
+            HotkeyHint::new(tr(Text::HintClear), "Esc"),
+            HotkeyHint::new(tr(Text::HintQuit), "ctrl+c"),
+        ]
+    } else if model.search_active {
+        vec![
+            HotkeyHint::new(tr(Text::HintCommands), "ctrl+p"),
+            HotkeyHint::new(tr(Text::HintSelect), "Enter"),
             filter_hint,
-            HotkeyHint::new("Clear", "Esc"),
-            HotkeyHint::new("Quit", "ctrl+c"),
-        ];
-        draw_help_bar_ext(
-            buffer,
-            area,
-            &model.theme,
-            hints,
-            model.theme.background,
-            version,
-        );
+            HotkeyHint::new(tr(Text::HintClear), "Esc"),
+            HotkeyHint::new(tr(Text::HintQuit), "ctrl+c"),
+        ]
     } else {
-        let hints = &[
-            HotkeyHint::new("Commands", "ctrl+p"),
-            HotkeyHint::new("Select", "Enter"),
+        vec![
+            HotkeyHint::new(tr(Text::HintCommands), "ctrl+p"),
+            HotkeyHint::new(tr(Text::HintSelect), "Enter"),
             filter_hint,
-            HotkeyHint::new("Quit", "q"),
-        ];
-        draw_help_bar_ext(
-            buffer,
-            area,
-            &model.theme,
-            hints,
-            model.theme.background,
-            version,
-        );
+            HotkeyHint::new(tr(Text::HintSort), "o"),
+            HotkeyHint::new(tr(Text::HintFilterBar), "f"),
+            HotkeyHint::new(tr(Text::HintInbox), "i"),
+            HotkeyHint::new(tr(Text::HintPreview), "p"),
+            if model.compare_marks.len() == 2 {
+                HotkeyHint::new(tr(Text::HintCompare), "c")
+            } else {
+                HotkeyHint::new(tr(Text::HintMark), "space")
+            },
+            HotkeyHint::new(tr(Text::HintQuit), "q"),
+        ]
+    };
+    let spans = draw_help_bar_ext(
+        buffer,
+        area,
+        &model.theme,
+        &hints,
+        model.theme.background,
+        &label,
+    );
+    // The status filter's hint cycles it when clicked
+    if let Some(x) = spans.get(2).filter(|_| !model.filter_bar_active) {
+        model.list_click_targets.borrow_mut().push(ClickTarget {
+            y: area.y + area.height.saturating_sub(2),
+            x: x.clone(),
+            message: Message::CycleStatusFilter,
+        });
     }
 }
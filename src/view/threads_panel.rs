@@ -0,0 +1,219 @@
+//! Review-wide thread list (`t`).
+//!
+//! A modal in the command palette's style: "Threads" bold on the left, the
+//! open count and "esc" on the right, then every thread grouped under its
+//! file (review-level threads first) with a status icon, its lines, the first
+//! comment's opening line and the comment count. The list scrolls to keep the
+//! selection near the middle.
+
+use crate::render_backend::{buffer_draw_text, buffer_fill_rect, OptimizedBuffer, Style};
+
+use crate::db::ThreadSummary;
+use crate::glyphs::glyphs;
+use crate::i18n::{tr, tr_fmt, Text};
+use crate::model::{Focus, Model};
+use crate::text::display_width;
+use crate::view::components::{dim_rect, draw_text_truncated, Rect};
+
+/// Widest the modal gets.
+const MAX_WIDTH: u32 = 90;
+/// Rows between the screen edge and the modal.
+const V_MARGIN: u32 = 2;
+/// Padding inside the modal, left and right.
+const PAD: u32 = 2;
+/// Thread rows are indented under their file.
+const THREAD_INDENT: u32 = 2;
+
+/// Row types for the thread list.
+enum Row<'a> {
+    /// File path, or the review-level heading
+    Heading(&'a str),
+    /// Thread and its index in `Model::panel_threads`
+    Thread(&'a ThreadSummary, usize),
+}
+
+pub fn view(model: &Model, buffer: &mut OptimizedBuffer) {
+    if model.focus != Focus::ThreadsPanel {
+        return;
+    }
+    let theme = &model.theme;
+    let screen = Rect::from_size(model.width, model.height);
+    dim_rect(buffer, screen, 0.35);
+
+    let threads = model.panel_threads();
+    let rows = build_rows(&threads);
+    let width = MAX_WIDTH.min(screen.width.saturating_sub(4));
+    // Vertical: 1 blank + title + 1 blank + rows + 1 blank; an empty list
+    // has its message and hint instead of rows
+    let height = (rows.len().max(2) as u32 + 4).min(screen.height.saturating_sub(V_MARGIN * 2));
+    if width < PAD * 2 + 10 || height < 5 {
+        return;
+    }
+    let x = (screen.width - width) / 2;
+    let mut y = V_MARGIN;
+    buffer_fill_rect(buffer, x, y, width, height, theme.panel_bg);
+    let text_x = x + PAD;
+    let text_width = width - PAD * 2;
+    y += 1;
+
+    // Title row: "Threads", then the open count and "esc" on the right
+    let esc_label = "esc";
+    let open = threads.iter().filter(|t| t.status == "open").count();
+    let count = tr_fmt(Text::ThreadsPanelCount, &[&open, &threads.len()]);
+    let count_width = display_width(&count) as u32;
+    buffer_draw_text(
+        buffer,
+        text_x,
+        y,
+        tr(Text::ThreadsPanelTitle),
+        theme.style_foreground().with_bold(),
+    );
+    let esc_x = text_x + text_width - esc_label.len() as u32;
+    buffer_draw_text(buffer, esc_x, y, esc_label, theme.style_muted());
+    buffer_draw_text(
+        buffer,
+        esc_x.saturating_sub(count_width + 2),
+        y,
+        &count,
+        theme.style_muted(),
+    );
+    y += 2;
+
+    if rows.is_empty() {
+        draw_text_truncated(
+            buffer,
+            text_x,
+            y,
+            tr(Text::ThreadsPanelEmpty),
+            text_width,
+            theme.style_foreground(),
+        );
+        draw_text_truncated(
+            buffer,
+            text_x,
+            y + 1,
+            tr(Text::ThreadsPanelEmptyHint),
+            text_width,
+            theme.style_muted(),
+        );
+        return;
+    }
+
+    let visible = (height - 4) as usize;
+    let selected_row = rows
+        .iter()
+        .position(
+            |row| matches!(row, Row::Thread(_, index) if *index == model.threads_panel_selection),
+        )
+        .unwrap_or(0);
+    let first = selected_row
+        .saturating_sub(visible / 2)
+        .min(rows.len().saturating_sub(visible));
+    for row in rows.iter().skip(first).take(visible) {
+        match row {
+            Row::Heading(name) => {
+                draw_text_truncated(
+                    buffer,
+                    text_x,
+                    y,
+                    name,
+                    text_width,
+                    theme.style_primary().with_bold(),
+                );
+            }
+            Row::Thread(thread, index) => {
+                let selected = *index == model.threads_panel_selection;
+                draw_thread_row(model, buffer, x, y, width, thread, selected);
+            }
+        }
+        y += 1;
+    }
+}
+
+/// Headings and thread rows, a heading wherever the file changes.
+fn build_rows<'a>(threads: &[&'a ThreadSummary]) -> Vec<Row<'a>> {
+    let mut rows = Vec::new();
+    let mut current_file: Option<&str> = None;
+    for (index, thread) in threads.iter().enumerate() {
+        if current_file != Some(thread.file_path.as_str()) {
+            rows.push(Row::Heading(if thread.is_review_thread() {
+                tr(Text::ThreadsPanelReview)
+            } else {
+                &thread.file_path
+            }));
+            current_file = Some(&thread.file_path);
+        }
+        rows.push(Row::Thread(thread, index));
+    }
+    rows
+}
+
+/// One thread: status icon, lines, first comment line, comment count.
+fn draw_thread_row(
+    model: &Model,
+    buffer: &mut OptimizedBuffer,
+    x: u32,
+    y: u32,
+    width: u32,
+    thread: &ThreadSummary,
+    selected: bool,
+) {
+    let theme = &model.theme;
+    let bg = if selected {
+        theme.selection_bg
+    } else {
+        theme.panel_bg
+    };
+    buffer_fill_rect(buffer, x + 1, y, width - 2, 1, bg);
+
+    let open = thread.status == "open";
+    let muted = model.is_muted(&thread.thread_id);
+    let (icon, icon_color) = if muted {
+        (glyphs().muted, theme.muted)
+    } else if open {
+        (glyphs().opened, theme.warning)
+    } else {
+        (glyphs().resolved, theme.success)
+    };
+    let mut text_x = x + PAD + THREAD_INDENT;
+    buffer_draw_text(buffer, text_x, y, icon, Style::fg(icon_color).with_bg(bg));
+    text_x += 2;
+
+    let count = thread.comment_count.to_string();
+    let count_x = x + width - PAD - count.len() as u32;
+    let location = if thread.is_review_thread() {
+        String::new()
+    } else if thread.is_file_thread() {
+        format!("{} ", glyphs().file_thread)
+    } else {
+        match thread.selection_end {
+            Some(end) if end != thread.selection_start => {
+                format!("L{}-{end}  ", thread.selection_start)
+            }
+            _ => format!("L{}  ", thread.selection_start),
+        }
+    };
+    let first_line = model
+        .all_comments
+        .get(&thread.thread_id)
+        .and_then(|comments| comments.first())
+        .and_then(|comment| comment.body.lines().next())
+        .unwrap_or(&thread.thread_id);
+    let text_style = if selected {
+        Style::fg(theme.selection_fg).with_bg(bg)
+    } else if open && !muted {
+        theme.style_foreground_on(bg)
+    } else {
+        theme.style_muted_on(bg)
+    };
+    draw_text_truncated(
+        buffer,
+        text_x,
+        y,
+        &format!("{location}{first_line}"),
+        count_x.saturating_sub(text_x + 2),
+        text_style,
+    );
+    // After the text, which can run wide on double-width characters
+    buffer_draw_text(buffer, count_x, y, &count, theme.style_muted_on(bg));
+}
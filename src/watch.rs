@@ -0,0 +1,132 @@
+//! Polling watcher for review files in the working directory, and for
+//! crit's database and the VCS state behind [`Message::ExternalRefresh`].
+//!
+//! Stats files on a fixed interval instead of using OS notifications: the
+//! set of watched files is small, the main loop already wakes every 100ms,
+//! and it needs no extra dependency or background thread.
+//!
+//! [`Message::ExternalRefresh`]: crate::message::Message::ExternalRefresh
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Minimum time between two stat passes.
+pub const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Paths (relative to the repo) that change when another client writes to
+/// crit or the working copy moves on.
+///
+/// crit's database, jj's operation heads (a new op per working-copy
+/// snapshot, which rewrites a jj review's commit) and git's `HEAD`.
+/// Directories are stamped by their own mtime.
+pub const EXTERNAL_STATE: &[&str] = &[
+    ".crit/index.db",
+    ".crit/index.db-wal",
+    ".jj/repo/op_heads/heads",
+    ".git/HEAD",
+];
+
+/// What a file looked like on disk when last checked.
+type Stamp = (Option<SystemTime>, u64);
+
+pub struct FileWatcher {
+    root: PathBuf,
+    interval: Duration,
+    last_poll: Option<Instant>,
+    stamps: HashMap<String, Stamp>,
+}
+
+impl FileWatcher {
+    #[must_use]
+    pub fn new(root: &Path, interval: Duration) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            interval,
+            last_poll: None,
+            stamps: HashMap::new(),
+        }
+    }
+
+    /// Repo-relative paths among `paths` that changed since the previous
+    /// poll. A path's first sighting only records its stamp. Returns nothing
+    /// when called again within the interval.
+    pub fn poll<'a>(&mut self, paths: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        if self
+            .last_poll
+            .is_some_and(|at| crate::clock::elapsed(at) < self.interval)
+        {
+            return Vec::new();
+        }
+        self.last_poll = Some(crate::clock::now());
+
+        let mut changed = Vec::new();
+        for path in paths {
+            let Ok(meta) = fs::metadata(self.root.join(path)) else {
+                continue;
+            };
+            let stamp = (meta.modified().ok(), meta.len());
+            match self.stamps.insert(path.to_string(), stamp) {
+                Some(previous) if previous != stamp => changed.push(path.to_string()),
+                _ => {}
+            }
+        }
+        changed
+    }
+
+    /// Record how `paths` look now without reporting changes, e.g. after
+    /// our own crit call may have touched them.
+    pub fn settle<'a>(&mut self, paths: impl IntoIterator<Item = &'a str>) {
+        self.last_poll = None;
+        self.poll(paths);
+    }
+
+    /// Read a watched file as lines (invalid UTF-8 is replaced).
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the file can't be read.
+    pub fn read_lines(&self, path: &str) -> io::Result<Vec<String>> {
+        let bytes = fs::read(self.root.join(path))?;
+        let text = String::from_utf8_lossy(&bytes);
+        let text = text.strip_suffix('\n').unwrap_or(&text);
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        // Keep `\r` so FileContent can detect CRLF files
+        Ok(text.split('\n').map(str::to_string).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_reports_changes_after_first_sighting() {
+        let root = std::env::temp_dir().join(format!("crit-ui-watch-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let file = root.join("a.rs");
+        fs::write(&file, "fn a() {}\n").unwrap();
+
+        let mut watcher = FileWatcher::new(&root, Duration::ZERO);
+        assert!(watcher.poll(["a.rs", "missing.rs"]).is_empty());
+        assert!(watcher.poll(["a.rs"]).is_empty());
+
+        fs::write(&file, "fn a() {}\r\nfn b() {}\n").unwrap();
+        assert_eq!(watcher.poll(["a.rs"]), ["a.rs"]);
+        assert_eq!(
+            watcher.read_lines("a.rs").unwrap(),
+            ["fn a() {}\r", "fn b() {}"]
+        );
+
+        watcher.settle(["a.rs"]);
+        fs::write(&file, "fn c() {}\n").unwrap();
+        watcher.settle(["a.rs"]);
+        assert!(watcher.poll(["a.rs"]).is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}